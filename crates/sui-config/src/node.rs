@@ -710,12 +710,43 @@ pub struct OverloadThresholdConfig {
     pub max_txn_age_in_queue: Duration,
     // TODO: Move other thresholds here as well, including `MAX_TM_QUEUE_LENGTH`
     // and `MAX_PER_OBJECT_QUEUE_LENGTH`.
+    /// Number of transactions pending execution at which the consensus handler waits for
+    /// execution to catch up before processing the next commit.
+    #[serde(default = "default_consensus_backpressure_max_pending_execution")]
+    pub consensus_backpressure_max_pending_execution: usize,
+    /// The longest the consensus handler waits for transactions pending execution to drop below
+    /// `consensus_backpressure_max_pending_execution`. Pending transactions can depend on
+    /// transactions that are not sequenced yet, so the handler proceeds after this wait.
+    #[serde(default = "default_consensus_backpressure_max_wait")]
+    pub consensus_backpressure_max_wait: Duration,
+    /// Number of transactions ready for execution at which the consensus handler waits for
+    /// execution to catch up however long it takes. Ready transactions have all their inputs
+    /// available, so they drain without further consensus output.
+    #[serde(default = "default_consensus_backpressure_max_ready_execution")]
+    pub consensus_backpressure_max_ready_execution: usize,
+}
+
+fn default_consensus_backpressure_max_pending_execution() -> usize {
+    50_000
+}
+
+fn default_consensus_backpressure_max_wait() -> Duration {
+    Duration::from_secs(5)
+}
+
+fn default_consensus_backpressure_max_ready_execution() -> usize {
+    100_000
 }
 
 impl Default for OverloadThresholdConfig {
     fn default() -> Self {
         Self {
             max_txn_age_in_queue: Duration::from_secs(1), // 1 second
+            consensus_backpressure_max_pending_execution:
+                default_consensus_backpressure_max_pending_execution(),
+            consensus_backpressure_max_wait: default_consensus_backpressure_max_wait(),
+            consensus_backpressure_max_ready_execution:
+                default_consensus_backpressure_max_ready_execution(),
         }
     }
 }
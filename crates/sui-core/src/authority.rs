@@ -22,8 +22,8 @@ use parking_lot::Mutex;
 use prometheus::{
     register_histogram_vec_with_registry, register_histogram_with_registry,
     register_int_counter_vec_with_registry, register_int_counter_with_registry,
-    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, Histogram, IntCounter,
-    IntCounterVec, IntGauge, IntGaugeVec, Registry,
+    register_int_gauge_vec_with_registry, register_int_gauge_with_registry, Histogram,
+    HistogramVec, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Registry,
 };
 use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
@@ -129,6 +129,7 @@ use crate::authority::authority_store::{ExecutionLockReadGuard, InputKey, Object
 use crate::authority::authority_store_pruner::AuthorityStorePruner;
 use crate::authority::epoch_start_configuration::EpochStartConfigTrait;
 use crate::authority::epoch_start_configuration::EpochStartConfiguration;
+use crate::authority::write_amplification::WriteStore;
 use crate::checkpoints::checkpoint_executor::CheckpointExecutor;
 use crate::checkpoints::CheckpointStore;
 use crate::consensus_adapter::ConsensusAdapter;
@@ -172,6 +173,7 @@ pub mod authority_store_tables;
 pub mod authority_store_types;
 pub mod epoch_start_configuration;
 pub mod test_authority_builder;
+pub mod write_amplification;
 
 pub(crate) mod authority_notify_read;
 pub(crate) mod authority_store;
@@ -239,6 +241,17 @@ pub struct AuthorityMetrics {
     pub consensus_committed_subdags: IntCounterVec,
     pub consensus_committed_certificates: IntGaugeVec,
     pub consensus_committed_user_transactions: IntGaugeVec,
+    pub consensus_malformed_transactions: IntCounterVec,
+    pub consensus_commit_transactions: HistogramVec,
+    pub consensus_commit_bytes: HistogramVec,
+    pub consensus_commit_authority_transactions: IntCounterVec,
+    pub consensus_leader_round_gap: HistogramVec,
+    pub consensus_dropped_over_quota_transactions: IntCounterVec,
+    pub consensus_invalid_epoch_boundary_markers: IntCounterVec,
+    pub consensus_handler_pending_execution: IntGauge,
+    pub consensus_handler_backpressure_waits: IntCounter,
+    pub consensus_handler_backpressure_timeouts: IntCounter,
+    pub consensus_handler_backpressure_latency: Histogram,
     pub consensus_calculated_throughput: IntGauge,
     pub consensus_calculated_throughput_profile: IntGauge,
 
@@ -260,6 +273,8 @@ const POSITIVE_INT_BUCKETS: &[f64] = &[
     1., 2., 5., 10., 20., 50., 100., 200., 500., 1000., 2000., 5000., 10000., 20000., 50000.,
 ];
 
+const BYTES_BUCKETS: &[f64] = &[1e3, 1e4, 5e4, 1e5, 5e5, 1e6, 5e6, 1e7, 5e7, 1e8];
+
 const LATENCY_SEC_BUCKETS: &[f64] = &[
     0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1., 2., 3., 4., 5., 6., 7., 8., 9., 10., 20.,
     30., 60., 90.,
@@ -568,6 +583,72 @@ impl AuthorityMetrics {
                 &["authority"],
                 registry,
             ).unwrap(),
+            consensus_malformed_transactions: register_int_counter_vec_with_registry!(
+                "consensus_malformed_transactions",
+                "Number of committed transactions that failed to deserialize, sliced by author of the including certificate or block",
+                &["authority"],
+                registry,
+            ).unwrap(),
+            consensus_commit_transactions: register_histogram_vec_with_registry!(
+                "consensus_commit_transactions",
+                "Number of transactions in each consensus commit, sliced by consensus protocol",
+                &["consensus"],
+                POSITIVE_INT_BUCKETS.to_vec(),
+                registry,
+            ).unwrap(),
+            consensus_commit_bytes: register_histogram_vec_with_registry!(
+                "consensus_commit_bytes",
+                "Number of bytes of transactions in each consensus commit, sliced by consensus protocol",
+                &["consensus"],
+                BYTES_BUCKETS.to_vec(),
+                registry,
+            ).unwrap(),
+            consensus_commit_authority_transactions: register_int_counter_vec_with_registry!(
+                "consensus_commit_authority_transactions",
+                "Number of committed transactions, sliced by consensus protocol and author of the including certificate or block",
+                &["consensus", "authority"],
+                registry,
+            ).unwrap(),
+            consensus_leader_round_gap: register_histogram_vec_with_registry!(
+                "consensus_leader_round_gap",
+                "Number of rounds between the leaders of consecutive consensus commits, sliced by consensus protocol",
+                &["consensus"],
+                POSITIVE_INT_BUCKETS.to_vec(),
+                registry,
+            ).unwrap(),
+            consensus_dropped_over_quota_transactions: register_int_counter_vec_with_registry!(
+                "consensus_dropped_over_quota_transactions",
+                "Number of committed user transactions dropped because their submitter was over its submission quota, sliced by submitter",
+                &["authority"],
+                registry,
+            ).unwrap(),
+            consensus_invalid_epoch_boundary_markers: register_int_counter_vec_with_registry!(
+                "consensus_invalid_epoch_boundary_markers",
+                "Number of committed EndOfPublish messages dropped because they were not included by their own authority, sliced by author of the including certificate or block",
+                &["authority"],
+                registry,
+            ).unwrap(),
+            consensus_handler_pending_execution: register_int_gauge_with_registry!(
+                "consensus_handler_pending_execution",
+                "Number of transactions pending execution, as last observed by the consensus handler",
+                registry,
+            ).unwrap(),
+            consensus_handler_backpressure_waits: register_int_counter_with_registry!(
+                "consensus_handler_backpressure_waits",
+                "Number of consensus commits whose processing waited for execution to catch up",
+                registry,
+            ).unwrap(),
+            consensus_handler_backpressure_timeouts: register_int_counter_with_registry!(
+                "consensus_handler_backpressure_timeouts",
+                "Number of consensus commits processed after waiting the maximum time for execution to catch up",
+                registry,
+            ).unwrap(),
+            consensus_handler_backpressure_latency: register_histogram_with_registry!(
+                "consensus_handler_backpressure_latency",
+                "Time the consensus handler waited for execution to catch up before processing a commit",
+                LATENCY_SEC_BUCKETS.to_vec(),
+                registry,
+            ).unwrap(),
             limits_metrics: Arc::new(LimitsMetrics::new(registry)),
             bytecode_verifier_metrics: Arc::new(BytecodeVerifierMetrics::new(registry)),
             authenticator_state_update_failed: register_int_counter_with_registry!(
@@ -685,6 +766,10 @@ impl AuthorityState {
         self.overload_threshold_config.max_txn_age_in_queue
     }
 
+    pub fn overload_threshold_config(&self) -> &OverloadThresholdConfig {
+        &self.overload_threshold_config
+    }
+
     pub fn get_epoch_state_commitments(
         &self,
         epoch: EpochId,
@@ -1385,6 +1470,15 @@ impl AuthorityState {
                 tx_digest,
             );
 
+        if cfg!(debug_assertions) {
+            if let Err(violations) = inner_temp_store.check_lamport_version() {
+                panic!(
+                    "Transaction {tx_digest:?} assigned versions inconsistent with its lamport version {}: {violations:?}",
+                    inner_temp_store.lamport_version
+                );
+            }
+        }
+
         Ok((inner_temp_store, effects, execution_error_opt.err()))
     }
 
@@ -1703,7 +1797,7 @@ impl AuthorityState {
             .process_object_index(effects, written, module_resolver)
             .tap_err(|e| warn!(tx_digest=?digest, "Failed to process object index, index_tx is skipped: {e}"))?;
 
-        indexes
+        let (sequence, bytes_written) = indexes
             .index_tx(
                 cert.data().intent_message().value.sender(),
                 cert.data()
@@ -1731,7 +1825,13 @@ impl AuthorityState {
                 tx_coins,
                 loaded_child_objects,
             )
-            .await
+            .await?;
+        self.database.write_amplification_profiler().record(
+            digest,
+            WriteStore::Indices,
+            bytes_written as u64,
+        );
+        Ok(sequence)
     }
 
     fn process_object_index(
@@ -3604,15 +3704,8 @@ impl AuthorityState {
             return None;
         }
         let written_coin_objects = inner_temporary_store
-            .written
-            .iter()
-            .filter_map(|(k, v)| {
-                if v.is_coin() {
-                    Some((*k, v.clone()))
-                } else {
-                    None
-                }
-            })
+            .written_coins()
+            .map(|(k, v)| (*k, v.clone()))
             .collect();
         let input_coin_objects = inner_temporary_store
             .input_objects
@@ -95,6 +95,7 @@ const LAST_CONSENSUS_STATS_ADDR: u64 = 0;
 const RECONFIG_STATE_INDEX: u64 = 0;
 const FINAL_EPOCH_CHECKPOINT_INDEX: u64 = 0;
 const OVERRIDE_PROTOCOL_UPGRADE_BUFFER_STAKE_INDEX: u64 = 0;
+const CONSENSUS_DEDUP_WINDOW_INDEX: u64 = 0;
 pub const EPOCH_DB_PREFIX: &str = "epoch_";
 
 // CertLockGuard and CertTxGuard are functionally identical right now, but we retain a distinction
@@ -119,6 +120,10 @@ pub enum ConsensusCertificateResult {
     Defered(DeferralKey),
     /// Everything else, e.g. AuthorityCapabilities, CheckpointSignatures, etc.
     ConsensusMessage,
+    /// The executable transactions of a soft bundle, in the order they were bundled.
+    SoftBundle(Vec<VerifiedExecutableTransaction>),
+    /// A soft bundle that was rejected as a whole, none of whose transactions will be executed.
+    SoftBundleRejected,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
@@ -138,21 +143,50 @@ pub trait ConsensusStatsAPI {
 
     fn get_user_transactions(&self, authority: usize) -> u64;
     fn inc_user_transactions(&mut self, authority: usize) -> u64;
+
+    /// The timestamp of the last commit processed, which the timestamps of later commits are
+    /// never allowed to precede.
+    fn get_last_commit_timestamp_ms(&self) -> u64;
+    fn set_last_commit_timestamp_ms(&mut self, timestamp_ms: u64);
+
+    /// The digest of the last commit processed, which the next commit is linked to.
+    fn get_last_commit_digest(&self) -> Option<[u8; 32]>;
+    fn set_last_commit_digest(&mut self, digest: [u8; 32]);
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[enum_dispatch(ConsensusStatsAPI)]
 pub enum ConsensusStats {
     V1(ConsensusStatsV1),
+    V2(ConsensusStatsV2),
 }
 
 impl ConsensusStats {
     pub fn new(size: usize) -> Self {
-        Self::V1(ConsensusStatsV1 {
+        Self::V2(ConsensusStatsV2 {
             narwhal_certificates: vec![0; size],
             user_transactions: vec![0; size],
+            last_commit_timestamp_ms: 0,
+            last_commit_digest: None,
         })
     }
+
+    /// Converts stats stored by an older version to the latest version. Stats stored as V1 did not
+    /// track the last commit timestamp or digest, so they start at 0 and none.
+    pub fn upgrade(self) -> Self {
+        match self {
+            Self::V1(ConsensusStatsV1 {
+                narwhal_certificates,
+                user_transactions,
+            }) => Self::V2(ConsensusStatsV2 {
+                narwhal_certificates,
+                user_transactions,
+                last_commit_timestamp_ms: 0,
+                last_commit_digest: None,
+            }),
+            stats @ Self::V2(_) => stats,
+        }
+    }
 }
 
 impl Default for ConsensusStats {
@@ -189,6 +223,71 @@ impl ConsensusStatsAPI for ConsensusStatsV1 {
         self.user_transactions[authority] += 1;
         self.user_transactions[authority]
     }
+
+    fn get_last_commit_timestamp_ms(&self) -> u64 {
+        0
+    }
+
+    fn set_last_commit_timestamp_ms(&mut self, _timestamp_ms: u64) {
+        // Not tracked by V1 stats, which are upgraded to V2 when they are recovered, see
+        // `ConsensusStats::upgrade`.
+    }
+
+    fn get_last_commit_digest(&self) -> Option<[u8; 32]> {
+        None
+    }
+
+    fn set_last_commit_digest(&mut self, _digest: [u8; 32]) {
+        // Not tracked by V1 stats, see `set_last_commit_timestamp_ms`.
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ConsensusStatsV2 {
+    pub narwhal_certificates: Vec<u64>,
+    pub user_transactions: Vec<u64>,
+    pub last_commit_timestamp_ms: u64,
+    pub last_commit_digest: Option<[u8; 32]>,
+}
+
+impl ConsensusStatsAPI for ConsensusStatsV2 {
+    fn is_initialized(&self) -> bool {
+        !self.narwhal_certificates.is_empty()
+    }
+
+    fn get_narwhal_certificates(&self, authority: usize) -> u64 {
+        self.narwhal_certificates[authority]
+    }
+
+    fn inc_narwhal_certificates(&mut self, authority: usize) -> u64 {
+        self.narwhal_certificates[authority] += 1;
+        self.narwhal_certificates[authority]
+    }
+
+    fn get_user_transactions(&self, authority: usize) -> u64 {
+        self.user_transactions[authority]
+    }
+
+    fn inc_user_transactions(&mut self, authority: usize) -> u64 {
+        self.user_transactions[authority] += 1;
+        self.user_transactions[authority]
+    }
+
+    fn get_last_commit_timestamp_ms(&self) -> u64 {
+        self.last_commit_timestamp_ms
+    }
+
+    fn set_last_commit_timestamp_ms(&mut self, timestamp_ms: u64) {
+        self.last_commit_timestamp_ms = timestamp_ms;
+    }
+
+    fn get_last_commit_digest(&self) -> Option<[u8; 32]> {
+        self.last_commit_digest
+    }
+
+    fn set_last_commit_digest(&mut self, digest: [u8; 32]) {
+        self.last_commit_digest = Some(digest);
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
@@ -270,6 +369,10 @@ pub struct AuthorityPerEpochStore {
 
     /// aggregator for JWK votes
     jwk_aggregator: Mutex<JwkAggregator>,
+
+    /// Transactions that tests want deferred, with the consensus round to defer them to.
+    #[cfg(test)]
+    deferrals_for_testing: Mutex<HashMap<TransactionDigest, Round>>,
 }
 
 /// AuthorityEpochTables contains tables that contain data that is only valid within an epoch.
@@ -418,6 +521,16 @@ pub struct AuthorityEpochTables {
 
     /// Transactions that are being deferred until some future time
     deferred_transactions: DBMap<DeferralKey, Vec<VerifiedSequencedConsensusTransaction>>,
+
+    /// Number of consecutive commits the consensus handler deduplicates transactions over. It is
+    /// recorded by the first consensus handler started in the epoch, so the window stays fixed
+    /// for the rest of the epoch even if the default changes across a restart.
+    consensus_dedup_window: DBMap<u64, u64>,
+
+    /// Bytes of user transactions accepted from each authority (by authority index) in each
+    /// consensus commit (by sub-dag index). Only the commits within the authority submission
+    /// quota window are kept.
+    authority_submission_bytes: DBMap<u64, Vec<u64>>,
 }
 
 // DeferralKey requires both the round to which the tx should be deferred (so that we can
@@ -647,12 +760,11 @@ impl AuthorityPerEpochStore {
         let pending_consensus_transactions = tables.get_all_pending_consensus_transactions();
         let pending_consensus_certificates: HashSet<_> = pending_consensus_transactions
             .iter()
-            .filter_map(|transaction| {
-                if let ConsensusTransactionKind::UserTransaction(certificate) = &transaction.kind {
-                    Some(*certificate.digest())
-                } else {
-                    None
-                }
+            .flat_map(|transaction| {
+                transaction
+                    .user_certificates()
+                    .iter()
+                    .map(|certificate| *certificate.digest())
             })
             .collect();
         assert_eq!(
@@ -756,6 +868,8 @@ impl AuthorityPerEpochStore {
             execution_component,
             chain_identifier,
             jwk_aggregator,
+            #[cfg(test)]
+            deferrals_for_testing: Default::default(),
         });
         s.update_buffer_stake_metric();
         s
@@ -1314,8 +1428,24 @@ impl AuthorityPerEpochStore {
         Ok(txns)
     }
 
+    /// Defers `digest` to `future_round` the next time it is sequenced.
+    #[cfg(test)]
+    pub(crate) fn defer_transaction_for_testing(
+        &self,
+        digest: TransactionDigest,
+        future_round: Round,
+    ) {
+        self.deferrals_for_testing
+            .lock()
+            .insert(digest, future_round);
+    }
+
     // Placeholder implementation
     fn should_defer(&self, _cert: &VerifiedExecutableTransaction) -> Option<DeferralKey> {
+        #[cfg(test)]
+        if let Some(future_round) = self.deferrals_for_testing.lock().remove(_cert.digest()) {
+            return Some(DeferralKey::new_for_consensus_round(future_round, 0));
+        }
         // placeholder constructions to silence lints
         let _ = DeferralKey::new_for_randomness_round(0, 0);
         let _ = DeferralKey::new_for_consensus_round(0, 0);
@@ -1353,7 +1483,8 @@ impl AuthorityPerEpochStore {
         self.tables
             .pending_consensus_transactions
             .insert(&transaction.key(), transaction)?;
-        if let ConsensusTransactionKind::UserTransaction(cert) = &transaction.kind {
+        let certificates = transaction.user_certificates();
+        if !certificates.is_empty() {
             let state = lock.expect("Must pass reconfiguration lock when storing certificate");
             // Caller is responsible for performing graceful check
             assert!(
@@ -1362,15 +1493,24 @@ impl AuthorityPerEpochStore {
             );
             self.pending_consensus_certificates
                 .lock()
-                .insert(*cert.digest());
+                .extend(certificates.iter().map(|cert| *cert.digest()));
         }
         Ok(())
     }
 
     pub fn remove_pending_consensus_transaction(&self, key: &ConsensusTransactionKey) -> SuiResult {
         self.tables.pending_consensus_transactions.remove(key)?;
-        if let ConsensusTransactionKey::Certificate(cert) = key {
-            self.pending_consensus_certificates.lock().remove(cert);
+        match key {
+            ConsensusTransactionKey::Certificate(cert) => {
+                self.pending_consensus_certificates.lock().remove(cert);
+            }
+            ConsensusTransactionKey::SoftBundle(certs) => {
+                let mut pending_consensus_certificates = self.pending_consensus_certificates.lock();
+                for cert in certs {
+                    pending_consensus_certificates.remove(cert);
+                }
+            }
+            _ => {}
         }
         Ok(())
     }
@@ -1524,6 +1664,39 @@ impl AuthorityPerEpochStore {
             .set(self.get_effective_buffer_stake_bps() as i64);
     }
 
+    /// Returns the consensus dedup window recorded for this epoch, recording `default_commits` if
+    /// none has been recorded yet.
+    pub fn get_or_init_consensus_dedup_window(&self, default_commits: u64) -> SuiResult<u64> {
+        if let Some(window) = self
+            .tables
+            .consensus_dedup_window
+            .get(&CONSENSUS_DEDUP_WINDOW_INDEX)?
+        {
+            return Ok(window);
+        }
+        info!(
+            epoch = ?self.epoch(),
+            "recording consensus dedup window of {} commits", default_commits
+        );
+        self.tables
+            .consensus_dedup_window
+            .insert(&CONSENSUS_DEDUP_WINDOW_INDEX, &default_commits)?;
+        Ok(default_commits)
+    }
+
+    /// Returns the bytes of user transactions accepted from each authority in every recorded
+    /// commit with a sub-dag index of at least `from_sub_dag_index`, in commit order.
+    pub fn get_authority_submission_bytes(
+        &self,
+        from_sub_dag_index: u64,
+    ) -> Result<Vec<(u64, Vec<u64>)>, TypedStoreError> {
+        Ok(self
+            .tables
+            .authority_submission_bytes
+            .range_iter(from_sub_dag_index..)
+            .collect())
+    }
+
     pub fn get_effective_buffer_stake_bps(&self) -> u64 {
         self.tables
             .override_protocol_upgrade_buffer_stake
@@ -1773,6 +1946,32 @@ impl AuthorityPerEpochStore {
         Ok(())
     }
 
+    /// Record the bytes of user transactions accepted from each authority in a consensus commit,
+    /// and prune the commits that fell out of the authority submission quota window.
+    fn record_authority_submission_bytes(
+        &self,
+        batch: &mut DBBatch,
+        sub_dag_index: u64,
+        submission_bytes: Vec<u64>,
+    ) -> SuiResult {
+        batch.insert_batch(
+            &self.tables.authority_submission_bytes,
+            [(sub_dag_index, submission_bytes)],
+        )?;
+        if let Some(window) = self
+            .protocol_config
+            .authority_submission_quota_window_commits_as_option()
+        {
+            let window_start = (sub_dag_index + 1).saturating_sub(window);
+            batch.schedule_delete_range(
+                &self.tables.authority_submission_bytes,
+                &0,
+                &window_start,
+            )?;
+        }
+        Ok(())
+    }
+
     pub fn test_insert_user_signature(
         &self,
         digest: TransactionDigest,
@@ -1916,7 +2115,9 @@ impl AuthorityPerEpochStore {
         // Signatures are verified as part of narwhal payload verification in SuiTxValidator
         match &transaction.transaction {
             SequencedConsensusTransactionKind::External(ConsensusTransaction {
-                kind: ConsensusTransactionKind::UserTransaction(_certificate),
+                kind:
+                    ConsensusTransactionKind::UserTransaction(_)
+                    | ConsensusTransactionKind::SoftBundle(_),
                 ..
             }) => {}
             SequencedConsensusTransactionKind::External(ConsensusTransaction {
@@ -1928,18 +2129,12 @@ impl AuthorityPerEpochStore {
                     return None;
                 }
             }
+            // EndOfPublish messages not sent by the author of their certificate or block were
+            // already dropped by `EpochBoundaryMarkers` when the commit was sequenced.
             SequencedConsensusTransactionKind::External(ConsensusTransaction {
-                kind: ConsensusTransactionKind::EndOfPublish(authority),
+                kind: ConsensusTransactionKind::EndOfPublish(_),
                 ..
-            }) => {
-                if &transaction.sender_authority() != authority {
-                    warn!(
-                        "EndOfPublish authority {} does not match narwhal certificate source {}",
-                        authority, transaction.certificate_author_index
-                    );
-                    return None;
-                }
-            }
+            }) => {}
             SequencedConsensusTransactionKind::External(ConsensusTransaction {
                 kind: ConsensusTransactionKind::CapabilityNotification(capabilities),
                 ..
@@ -1998,6 +2193,7 @@ impl AuthorityPerEpochStore {
         object_store: impl ObjectStore,
         commit_round: Round,
         commit_timestamp: TimestampMs,
+        authority_submission_bytes: Option<Vec<u64>>,
         skipped_consensus_txns: &IntCounter,
     ) -> SuiResult<Vec<VerifiedExecutableTransaction>> {
         let verified_transactions: Vec<_> = transactions
@@ -2008,7 +2204,7 @@ impl AuthorityPerEpochStore {
             .collect();
         let roots: BTreeSet<_> = verified_transactions
             .iter()
-            .filter_map(|transaction| transaction.0.transaction.executable_transaction_digest())
+            .flat_map(|transaction| transaction.0.transaction.executable_transaction_digests())
             .collect();
         let (end_of_publish_transactions, mut sequenced_transactions): (Vec<_>, Vec<_>) =
             verified_transactions
@@ -2047,6 +2243,13 @@ impl AuthorityPerEpochStore {
             )
             .await?;
         self.record_consensus_commit_stats(&mut batch, consensus_stats)?;
+        if let Some(submission_bytes) = authority_submission_bytes {
+            self.record_authority_submission_bytes(
+                &mut batch,
+                consensus_stats.index.sub_dag_index,
+                submission_bytes,
+            )?;
+        }
 
         // The last block in this function notifies about new checkpoint if needed
         // It's important that we use as_ref() here to make sure we are not dropping the lock.
@@ -2125,6 +2328,7 @@ impl AuthorityPerEpochStore {
             object_store,
             self.get_highest_pending_checkpoint_height() + 1,
             0,
+            None,
             skipped_consensus_txns,
         )
         .await
@@ -2170,7 +2374,7 @@ impl AuthorityPerEpochStore {
             let unique_shared_input_objects = {
                 let mut shared_input_objects: Vec<_> = transactions
                     .iter()
-                    .filter_map(|tx| tx.0.as_shared_object_txn())
+                    .flat_map(|tx| tx.0.as_shared_object_txns())
                     .flat_map(|tx| {
                         tx.transaction_data()
                             .shared_input_objects()
@@ -2194,19 +2398,35 @@ impl AuthorityPerEpochStore {
         let mut deferred_txns: BTreeMap<DeferralKey, Vec<VerifiedSequencedConsensusTransaction>> =
             BTreeMap::new();
 
+        // Transactions sequenced in this commit, which are not yet recorded as processed. A
+        // transaction sequenced both on its own and in a soft bundle is only executed the first
+        // time, and the bundle is rejected if it comes second.
+        let mut sequenced_digests = HashSet::new();
+
         for tx in transactions {
             let key = tx.0.transaction.key();
             self.record_consensus_message_processed(batch, key.clone())?;
-            match self
-                .process_consensus_transaction(
+            let already_sequenced =
+                tx.0.transaction
+                    .executable_transaction_digests()
+                    .iter()
+                    .any(|digest| sequenced_digests.contains(digest));
+            let result = if !already_sequenced {
+                self.process_consensus_transaction(
                     batch,
                     &mut shared_input_next_versions,
                     tx,
                     checkpoint_service,
                 )
                 .await?
-            {
+            } else if tx.0.is_soft_bundle() {
+                ConsensusCertificateResult::SoftBundleRejected
+            } else {
+                ConsensusCertificateResult::Ignored
+            };
+            match result {
                 ConsensusCertificateResult::SuiTransaction(cert) => {
+                    sequenced_digests.insert(*cert.digest());
                     notifications.push(key);
                     verified_certificates.push(cert);
                 }
@@ -2218,7 +2438,22 @@ impl AuthorityPerEpochStore {
                         .or_default()
                         .push(tx.clone());
                 }
-                ConsensusCertificateResult::ConsensusMessage => notifications.push(key),
+                ConsensusCertificateResult::SoftBundle(certs) => {
+                    // Each transaction of the bundle is now sequenced, so it must not be
+                    // sequenced again on its own.
+                    for cert in &certs {
+                        sequenced_digests.insert(*cert.digest());
+                        let cert_key = SequencedConsensusTransactionKey::External(
+                            ConsensusTransactionKey::Certificate(*cert.digest()),
+                        );
+                        self.record_consensus_message_processed(batch, cert_key.clone())?;
+                        notifications.push(cert_key);
+                    }
+                    notifications.push(key);
+                    verified_certificates.extend(certs);
+                }
+                ConsensusCertificateResult::ConsensusMessage
+                | ConsensusCertificateResult::SoftBundleRejected => notifications.push(key),
                 ConsensusCertificateResult::Ignored => (),
             }
         }
@@ -2397,6 +2632,96 @@ impl AuthorityPerEpochStore {
 
                 Ok(ConsensusCertificateResult::SuiTransaction(certificate))
             }
+            SequencedConsensusTransactionKind::External(ConsensusTransaction {
+                kind: ConsensusTransactionKind::SoftBundle(certificates),
+                ..
+            }) => {
+                if !self.protocol_config().soft_bundle() {
+                    warn!(
+                        ?tracking_id,
+                        "Soft bundle sequenced while soft bundles are disabled"
+                    );
+                    return Ok(ConsensusCertificateResult::SoftBundleRejected);
+                }
+                // The bundle is rejected as a whole if any of its transactions could not be
+                // accepted on its own, or was already sequenced without the bundle.
+                if certificates
+                    .iter()
+                    .any(|certificate| certificate.epoch() != self.epoch())
+                {
+                    debug!(
+                        ?tracking_id,
+                        "Soft bundle epoch doesn't match the current epoch"
+                    );
+                    return Ok(ConsensusCertificateResult::SoftBundleRejected);
+                }
+                if self.has_sent_end_of_publish(certificate_author)? {
+                    warn!("[Byzantine authority] Authority {:?} sent a new, previously unseen soft bundle after it sent EndOfPublish message to consensus", certificate_author.concise());
+                    return Ok(ConsensusCertificateResult::SoftBundleRejected);
+                }
+                if !self
+                    .get_reconfig_state_read_lock_guard()
+                    .should_accept_consensus_certs()
+                {
+                    debug!(?tracking_id, "Ignoring soft bundle because of end of epoch");
+                    return Ok(ConsensusCertificateResult::SoftBundleRejected);
+                }
+                for certificate in certificates {
+                    if self.is_tx_cert_consensus_message_processed(certificate)? {
+                        debug!(
+                            ?tracking_id,
+                            tx_digest = ?certificate.digest(),
+                            "Rejecting soft bundle with a transaction that was already sequenced",
+                        );
+                        return Ok(ConsensusCertificateResult::SoftBundleRejected);
+                    }
+                }
+
+                // Safe because signatures are verified when VerifiedSequencedConsensusTransaction
+                // is constructed.
+                let certificates: Vec<_> = certificates
+                    .iter()
+                    .map(|certificate| {
+                        VerifiedExecutableTransaction::new_from_certificate(
+                            VerifiedCertificate::new_unchecked(certificate.clone()),
+                        )
+                    })
+                    .collect();
+
+                debug!(
+                    ?tracking_id,
+                    tx_digests = ?certificates.iter().map(|c| *c.digest()).collect::<Vec<_>>(),
+                    "handle_consensus_transaction SoftBundle",
+                );
+
+                // The bundle is deferred as a whole, so that it stays adjacent.
+                if let Some(deferral_key) = certificates
+                    .iter()
+                    .find_map(|certificate| self.should_defer(certificate))
+                {
+                    debug!(
+                        ?tracking_id,
+                        "Deferring soft bundle until {:?}", deferral_key
+                    );
+                    return Ok(ConsensusCertificateResult::Defered(deferral_key));
+                }
+
+                for certificate in &certificates {
+                    if certificate.contains_shared_object() {
+                        self.record_shared_object_cert_from_consensus(
+                            batch,
+                            shared_input_next_versions,
+                            certificate,
+                        )
+                        .await?;
+                    } else {
+                        self.record_owned_object_cert_from_consensus(batch, certificate)
+                            .await?;
+                    }
+                }
+
+                Ok(ConsensusCertificateResult::SoftBundle(certificates))
+            }
             SequencedConsensusTransactionKind::External(ConsensusTransaction {
                 kind: ConsensusTransactionKind::CheckpointSignature(info),
                 ..
@@ -36,6 +36,9 @@ use crate::authority::authority_store_types::{
     get_store_object_pair, ObjectContentDigest, StoreObject, StoreObjectPair, StoreObjectWrapper,
 };
 use crate::authority::epoch_start_configuration::{EpochFlag, EpochStartConfiguration};
+use crate::authority::write_amplification::{
+    BatchWriteTracker, WriteAmplificationProfiler, WriteStore,
+};
 
 use super::authority_store_tables::LiveObject;
 use super::{authority_store_tables::AuthorityPerpetualTables, *};
@@ -135,6 +138,8 @@ pub struct AuthorityStore {
     metrics: AuthorityStoreMetrics,
 
     package_cache: Arc<PackageObjectCache>,
+
+    write_amplification_profiler: Arc<WriteAmplificationProfiler>,
 }
 
 pub type ExecutionLockReadGuard<'a> = RwLockReadGuard<'a, EpochId>;
@@ -247,6 +252,7 @@ impl AuthorityStore {
             enable_epoch_sui_conservation_check,
             metrics: AuthorityStoreMetrics::new(registry),
             package_cache: PackageObjectCache::new(),
+            write_amplification_profiler: Arc::new(WriteAmplificationProfiler::new()),
         });
         // Only initialize an empty database.
         if store
@@ -954,6 +960,11 @@ impl AuthorityStore {
         Ok(())
     }
 
+    /// Measures the bytes written by transaction commits, when enabled.
+    pub fn write_amplification_profiler(&self) -> &Arc<WriteAmplificationProfiler> {
+        &self.write_amplification_profiler
+    }
+
     pub fn get_epoch_start_configuration(&self) -> SuiResult<Option<EpochStartConfiguration>> {
         Ok(self.perpetual_tables.epoch_start_configuration.get(&())?)
     }
@@ -975,6 +986,7 @@ impl AuthorityStore {
             .await;
         // Extract the new state from the execution
         let mut write_batch = self.perpetual_tables.transactions.batch();
+        let mut write_tracker = BatchWriteTracker::new(&write_batch);
 
         // Store the certificate indexed by transaction digest
         let transaction_digest = transaction.digest();
@@ -982,11 +994,13 @@ impl AuthorityStore {
             &self.perpetual_tables.transactions,
             iter::once((transaction_digest, transaction.serializable_ref())),
         )?;
+        write_tracker.record(&write_batch, WriteStore::Transactions);
 
         // Add batched writes for objects and locks.
         let effects_digest = effects.digest();
         self.update_objects_and_locks(
             &mut write_batch,
+            &mut write_tracker,
             inner_temporary_store,
             effects,
             transaction,
@@ -1004,6 +1018,7 @@ impl AuthorityStore {
                 &self.perpetual_tables.executed_effects,
                 [(transaction_digest, effects_digest)],
             )?;
+        write_tracker.record(&write_batch, WriteStore::Effects);
 
         // test crashing before writing the batch
         fail_point_async!("crash");
@@ -1011,6 +1026,16 @@ impl AuthorityStore {
         // Commit.
         write_batch.write()?;
 
+        self.write_amplification_profiler.record_transaction(
+            transaction_digest,
+            transaction
+                .transaction_data()
+                .move_calls()
+                .into_iter()
+                .map(|(package, module, _)| (*package, module.to_string())),
+            &write_tracker.finish(),
+        );
+
         if transaction.transaction_data().is_end_of_epoch_tx() {
             // At the end of epoch, since system packages may have been upgraded, force
             // reload them in the cache.
@@ -1063,6 +1088,7 @@ impl AuthorityStore {
     async fn update_objects_and_locks(
         &self,
         write_batch: &mut DBBatch,
+        write_tracker: &mut BatchWriteTracker,
         inner_temporary_store: InnerTemporaryStore,
         effects: &TransactionEffects,
         transaction: &VerifiedTransaction,
@@ -1211,6 +1237,8 @@ impl AuthorityStore {
             )?;
         }
 
+        write_tracker.record(write_batch, WriteStore::Objects);
+
         let event_digest = events.digest();
         let events = events
             .data
@@ -1219,6 +1247,7 @@ impl AuthorityStore {
             .map(|(i, e)| ((event_digest, i), e));
 
         write_batch.insert_batch(&self.perpetual_tables.events, events)?;
+        write_tracker.record(write_batch, WriteStore::Events);
 
         let new_locks_to_init: Vec<_> = written
             .values()
@@ -1248,7 +1277,9 @@ impl AuthorityStore {
         // Make sure to delete the locks for any received objects.
         // Any objects that occur as a `Receiving` argument but have not been received will not
         // have their locks touched.
-        self.delete_locks(write_batch, &received_objects)
+        self.delete_locks(write_batch, &received_objects)?;
+        write_tracker.record(write_batch, WriteStore::Objects);
+        Ok(())
     }
 
     /// Acquires a lock for a transaction on the given objects if they have all been initialized previously
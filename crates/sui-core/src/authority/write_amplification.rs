@@ -0,0 +1,400 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Measures how many bytes committing a transaction writes to each of the stores backing the
+//! authority, and aggregates these measurements per checkpoint and per module of the Move
+//! functions the transactions called. Profiling is disabled by default, in which case nothing
+//! is recorded.
+
+use std::collections::{HashMap, VecDeque};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use lru::LruCache;
+use parking_lot::Mutex;
+use sui_types::base_types::ObjectID;
+use sui_types::digests::TransactionDigest;
+use sui_types::messages_checkpoint::CheckpointSequenceNumber;
+use typed_store::rocks::DBBatch;
+
+/// Number of most recent checkpoints whose writes are kept in the report.
+const MAX_CHECKPOINTS_TRACKED: usize = 1000;
+
+/// Number of transactions whose writes are kept until their checkpoint is recorded. Writes are
+/// recorded for transactions that never make it into a checkpoint (e.g. indexing of transactions
+/// that are re-executed), so the least recently written ones are dropped past this limit.
+const MAX_PENDING_TRANSACTIONS: usize = 100_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WriteStore {
+    Transactions,
+    Effects,
+    /// Objects, indirect objects, owned object locks, and object markers
+    Objects,
+    Events,
+    /// Secondary indices, only maintained by fullnodes
+    Indices,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WriteBytes {
+    pub transactions: u64,
+    pub effects: u64,
+    pub objects: u64,
+    pub events: u64,
+    pub indices: u64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AggregatedWrites {
+    pub transaction_count: u64,
+    pub bytes: WriteBytes,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ModuleWrites {
+    pub package: ObjectID,
+    pub module: String,
+    pub writes: AggregatedWrites,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WriteAmplificationReport {
+    /// Writes of all transactions included in a checkpoint since profiling was enabled
+    pub total: AggregatedWrites,
+    /// Writes of transactions calling a function of each module, sorted by decreasing bytes
+    /// written. A transaction calling functions from several modules counts towards each of them.
+    pub by_module: Vec<ModuleWrites>,
+    /// Writes of the transactions in each of the most recent checkpoints, in ascending order
+    pub by_checkpoint: Vec<(CheckpointSequenceNumber, AggregatedWrites)>,
+    /// Number of transactions whose writes were dropped before their checkpoint was recorded,
+    /// because too many transactions were pending
+    pub dropped_transactions: u64,
+}
+
+/// Writes of a transaction which is not yet part of a checkpoint.
+#[derive(Default)]
+struct PendingTransaction {
+    modules: Vec<(ObjectID, String)>,
+    bytes: WriteBytes,
+}
+
+struct Inner {
+    pending: LruCache<TransactionDigest, PendingTransaction>,
+    dropped_transactions: u64,
+    total: AggregatedWrites,
+    by_module: HashMap<(ObjectID, String), AggregatedWrites>,
+    by_checkpoint: VecDeque<(CheckpointSequenceNumber, AggregatedWrites)>,
+}
+
+#[derive(Default)]
+pub struct WriteAmplificationProfiler {
+    enabled: AtomicBool,
+    inner: Mutex<Inner>,
+}
+
+/// Attributes the growth of a write batch to the store written since the previous measurement.
+pub(crate) struct BatchWriteTracker {
+    last_size: usize,
+    bytes: WriteBytes,
+}
+
+impl WriteBytes {
+    pub fn total(&self) -> u64 {
+        self.transactions + self.effects + self.objects + self.events + self.indices
+    }
+
+    pub fn add(&mut self, store: WriteStore, bytes: u64) {
+        let counter = match store {
+            WriteStore::Transactions => &mut self.transactions,
+            WriteStore::Effects => &mut self.effects,
+            WriteStore::Objects => &mut self.objects,
+            WriteStore::Events => &mut self.events,
+            WriteStore::Indices => &mut self.indices,
+        };
+        *counter += bytes;
+    }
+
+    fn merge(&mut self, other: &WriteBytes) {
+        self.transactions += other.transactions;
+        self.effects += other.effects;
+        self.objects += other.objects;
+        self.events += other.events;
+        self.indices += other.indices;
+    }
+}
+
+impl AggregatedWrites {
+    fn add_transaction(&mut self, bytes: &WriteBytes) {
+        self.transaction_count += 1;
+        self.bytes.merge(bytes);
+    }
+}
+
+impl Inner {
+    fn with_capacity(max_pending: usize) -> Self {
+        Self {
+            pending: LruCache::new(NonZeroUsize::new(max_pending).unwrap()),
+            dropped_transactions: 0,
+            total: AggregatedWrites::default(),
+            by_module: HashMap::new(),
+            by_checkpoint: VecDeque::new(),
+        }
+    }
+
+    /// The writes of the pending transaction `digest`, dropping those of the least recently
+    /// written transaction if too many are pending.
+    fn pending(&mut self, digest: &TransactionDigest) -> &mut PendingTransaction {
+        if !self.pending.contains(digest) {
+            let evicted = self.pending.push(*digest, PendingTransaction::default());
+            if evicted.is_some() {
+                self.dropped_transactions += 1;
+            }
+        }
+        self.pending.get_mut(digest).unwrap()
+    }
+}
+
+impl Default for Inner {
+    fn default() -> Self {
+        Self::with_capacity(MAX_PENDING_TRANSACTIONS)
+    }
+}
+
+impl WriteAmplificationProfiler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Starts recording writes, discarding any previously collected data.
+    pub fn enable(&self) {
+        *self.inner.lock() = Inner::default();
+        self.enabled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn disable(&self) {
+        self.enabled.store(false, Ordering::Relaxed);
+        self.inner.lock().pending.clear();
+    }
+
+    /// Records the bytes written for a committed transaction, along with the modules of the
+    /// functions it called.
+    pub fn record_transaction(
+        &self,
+        digest: &TransactionDigest,
+        modules: impl IntoIterator<Item = (ObjectID, String)>,
+        bytes: &WriteBytes,
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut inner = self.inner.lock();
+        let pending = inner.pending(digest);
+        pending.modules.extend(modules);
+        pending.bytes.merge(bytes);
+    }
+
+    /// Records bytes written to `store` on behalf of a transaction, for writes happening outside
+    /// of the transaction's commit (e.g. indexing).
+    pub fn record(&self, digest: &TransactionDigest, store: WriteStore, bytes: u64) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut inner = self.inner.lock();
+        inner.pending(digest).bytes.add(store, bytes);
+    }
+
+    /// Aggregates the writes of the transactions included in a checkpoint.
+    pub fn record_checkpoint(
+        &self,
+        sequence_number: CheckpointSequenceNumber,
+        tx_digests: &[TransactionDigest],
+    ) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut inner = self.inner.lock();
+        let mut checkpoint = AggregatedWrites::default();
+        for digest in tx_digests {
+            let Some(PendingTransaction { mut modules, bytes }) = inner.pending.pop(digest) else {
+                continue;
+            };
+            checkpoint.add_transaction(&bytes);
+            inner.total.add_transaction(&bytes);
+            modules.sort();
+            modules.dedup();
+            for module in modules {
+                inner
+                    .by_module
+                    .entry(module)
+                    .or_default()
+                    .add_transaction(&bytes);
+            }
+        }
+        if inner.by_checkpoint.len() == MAX_CHECKPOINTS_TRACKED {
+            inner.by_checkpoint.pop_front();
+        }
+        inner.by_checkpoint.push_back((sequence_number, checkpoint));
+    }
+
+    pub fn report(&self) -> WriteAmplificationReport {
+        let inner = self.inner.lock();
+        let mut by_module: Vec<_> = inner
+            .by_module
+            .iter()
+            .map(|((package, module), writes)| ModuleWrites {
+                package: *package,
+                module: module.clone(),
+                writes: writes.clone(),
+            })
+            .collect();
+        by_module.sort_by(|a, b| {
+            b.writes
+                .bytes
+                .total()
+                .cmp(&a.writes.bytes.total())
+                .then_with(|| (a.package, &a.module).cmp(&(b.package, &b.module)))
+        });
+        WriteAmplificationReport {
+            total: inner.total.clone(),
+            by_module,
+            by_checkpoint: inner.by_checkpoint.iter().cloned().collect(),
+            dropped_transactions: inner.dropped_transactions,
+        }
+    }
+}
+
+impl BatchWriteTracker {
+    pub fn new(batch: &DBBatch) -> Self {
+        Self {
+            last_size: batch.size_in_bytes(),
+            bytes: WriteBytes::default(),
+        }
+    }
+
+    /// Attributes everything added to `batch` since the last call to `store`.
+    pub fn record(&mut self, batch: &DBBatch, store: WriteStore) {
+        let size = batch.size_in_bytes();
+        self.bytes
+            .add(store, size.saturating_sub(self.last_size) as u64);
+        self.last_size = size;
+    }
+
+    pub fn finish(self) -> WriteBytes {
+        self.bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bytes(objects: u64, indices: u64) -> WriteBytes {
+        WriteBytes {
+            objects,
+            indices,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_disabled_profiler_records_nothing() {
+        let profiler = WriteAmplificationProfiler::new();
+        let digest = TransactionDigest::random();
+        profiler.record_transaction(&digest, [], &bytes(10, 0));
+        profiler.record_checkpoint(0, &[digest]);
+        assert_eq!(profiler.report(), WriteAmplificationReport::default());
+    }
+
+    #[test]
+    fn test_aggregate_by_checkpoint_and_module() {
+        let profiler = WriteAmplificationProfiler::new();
+        profiler.enable();
+
+        let package = ObjectID::random();
+        let (tx1, tx2, tx3) = (
+            TransactionDigest::random(),
+            TransactionDigest::random(),
+            TransactionDigest::random(),
+        );
+        profiler.record(&tx1, WriteStore::Indices, 5);
+        profiler.record_transaction(
+            &tx1,
+            [(package, "a".to_owned()), (package, "a".to_owned())],
+            &bytes(10, 0),
+        );
+        profiler.record_transaction(
+            &tx2,
+            [(package, "a".to_owned()), (package, "b".to_owned())],
+            &bytes(100, 0),
+        );
+        profiler.record_transaction(&tx3, [], &bytes(1, 0));
+
+        profiler.record_checkpoint(0, &[tx1, tx2]);
+        profiler.record_checkpoint(1, &[tx3]);
+
+        let report = profiler.report();
+        assert_eq!(report.total.transaction_count, 3);
+        assert_eq!(report.total.bytes, bytes(111, 5));
+        assert_eq!(
+            report.by_checkpoint,
+            vec![
+                (
+                    0,
+                    AggregatedWrites {
+                        transaction_count: 2,
+                        bytes: bytes(110, 5)
+                    }
+                ),
+                (
+                    1,
+                    AggregatedWrites {
+                        transaction_count: 1,
+                        bytes: bytes(1, 0)
+                    }
+                ),
+            ]
+        );
+        let modules: Vec<_> = report
+            .by_module
+            .iter()
+            .map(|m| {
+                (
+                    m.module.as_str(),
+                    m.writes.transaction_count,
+                    m.writes.bytes.total(),
+                )
+            })
+            .collect();
+        assert_eq!(modules, vec![("a", 2, 115), ("b", 1, 100)]);
+    }
+
+    #[test]
+    fn test_pending_transactions_are_bounded() {
+        let profiler = WriteAmplificationProfiler::new();
+        profiler.enable();
+        *profiler.inner.lock() = Inner::with_capacity(2);
+
+        let (tx1, tx2, tx3) = (
+            TransactionDigest::random(),
+            TransactionDigest::random(),
+            TransactionDigest::random(),
+        );
+        profiler.record_transaction(&tx1, [], &bytes(1, 0));
+        profiler.record_transaction(&tx2, [], &bytes(10, 0));
+        // Writing to tx1 again makes tx2 the least recently written.
+        profiler.record(&tx1, WriteStore::Indices, 5);
+        profiler.record_transaction(&tx3, [], &bytes(100, 0));
+
+        profiler.record_checkpoint(0, &[tx1, tx2, tx3]);
+
+        let report = profiler.report();
+        assert_eq!(report.dropped_transactions, 1);
+        assert_eq!(report.total.transaction_count, 2);
+        assert_eq!(report.total.bytes, bytes(101, 5));
+    }
+}
@@ -4,12 +4,14 @@
 
 use anyhow::Result;
 use async_trait::async_trait;
+use futures::future::try_join_all;
 use mysten_metrics::histogram::Histogram as MystenHistogram;
 use mysten_metrics::spawn_monitored_task;
 use prometheus::{
     register_int_counter_vec_with_registry, register_int_counter_with_registry, IntCounter,
     IntCounterVec, Registry,
 };
+use std::collections::HashSet;
 use std::{io, sync::Arc};
 use sui_network::{
     api::{Validator, ValidatorServer},
@@ -19,9 +21,10 @@ use sui_types::effects::TransactionEffectsAPI;
 use sui_types::effects::TransactionEvents;
 use sui_types::messages_consensus::ConsensusTransaction;
 use sui_types::messages_grpc::{
-    HandleCertificateResponse, HandleCertificateResponseV2, HandleTransactionResponse,
-    ObjectInfoRequest, ObjectInfoResponse, SubmitCertificateResponse, SystemStateRequest,
-    TransactionInfoRequest, TransactionInfoResponse,
+    HandleCertificateResponse, HandleCertificateResponseV2, HandleSoftBundleCertificatesRequest,
+    HandleSoftBundleCertificatesResponse, HandleTransactionResponse, ObjectInfoRequest,
+    ObjectInfoResponse, SubmitCertificateResponse, SystemStateRequest, TransactionInfoRequest,
+    TransactionInfoResponse,
 };
 use sui_types::multiaddr::Multiaddr;
 use sui_types::sui_system_state::SuiSystemState;
@@ -35,6 +38,7 @@ use tokio::task::JoinHandle;
 use tracing::{error_span, info, Instrument};
 
 use crate::consensus_adapter::{ConnectionMonitorStatusForTests, LazyNarwhalClient};
+use crate::consensus_handler::SequencedConsensusTransactionKey;
 use crate::{
     authority::AuthorityState,
     consensus_adapter::{ConsensusAdapter, ConsensusAdapterMetrics},
@@ -496,6 +500,146 @@ impl ValidatorService {
             fastpath_input_objects: vec![], // unused field
         }))
     }
+
+    async fn handle_soft_bundle_certificates(
+        self,
+        request: tonic::Request<HandleSoftBundleCertificatesRequest>,
+    ) -> Result<HandleSoftBundleCertificatesResponse, tonic::Status> {
+        let Self {
+            state,
+            consensus_adapter,
+            metrics,
+        } = self;
+
+        let epoch_store = state.load_epoch_store_one_call_per_task();
+        let certificates = request.into_inner().certificates;
+
+        fp_ensure!(
+            epoch_store.protocol_config().soft_bundle(),
+            SuiError::UnsupportedFeatureError {
+                error: "soft bundle is not enabled on this network".to_string(),
+            }
+            .into()
+        );
+        fp_ensure!(
+            !state.is_fullnode(&epoch_store),
+            SuiError::FullNodeCantHandleCertificate.into()
+        );
+
+        // 1) Validate the bundle. Every certificate in a bundle has to go through consensus, so
+        // bundles are limited to certificates with shared objects that have not been sequenced.
+        let max_soft_bundle_size = epoch_store.protocol_config().max_soft_bundle_size();
+        fp_ensure!(
+            !certificates.is_empty() && certificates.len() as u64 <= max_soft_bundle_size,
+            SuiError::InvalidSoftBundle {
+                error: format!(
+                    "number of certificates must be between 1 and {max_soft_bundle_size}, got {}",
+                    certificates.len()
+                ),
+            }
+            .into()
+        );
+        let mut digests = HashSet::new();
+        for certificate in &certificates {
+            let tx_digest = *certificate.digest();
+            fp_ensure!(
+                digests.insert(tx_digest),
+                SuiError::InvalidSoftBundle {
+                    error: format!("duplicate certificate {tx_digest:?}"),
+                }
+                .into()
+            );
+            // CRITICAL! Validators should never sign an external system transaction.
+            fp_ensure!(
+                !certificate.is_system_tx(),
+                SuiError::InvalidSystemTransaction.into()
+            );
+            fp_ensure!(
+                certificate.contains_shared_object(),
+                SuiError::InvalidSoftBundle {
+                    error: format!("certificate {tx_digest:?} does not use shared objects"),
+                }
+                .into()
+            );
+            fp_ensure!(
+                !state.is_tx_already_executed(&tx_digest)?
+                    && !epoch_store.is_tx_cert_consensus_message_processed(certificate)?,
+                SuiError::InvalidSoftBundle {
+                    error: format!("certificate {tx_digest:?} has already been sequenced"),
+                }
+                .into()
+            );
+
+            // Check system overload
+            let overload_check_res =
+                state.check_system_overload(&consensus_adapter, certificate.data());
+            if let Err(error) = overload_check_res {
+                metrics
+                    .num_rejected_cert_during_overload
+                    .with_label_values(&[error.as_ref()])
+                    .inc();
+                return Err(error.into());
+            }
+        }
+
+        // 2) Verify the certificates and submit them to consensus as a single transaction, within
+        // the reconfiguration lock.
+        let certificates = {
+            let _timer = metrics.cert_verification_latency.start_timer();
+            try_join_all(
+                certificates
+                    .into_iter()
+                    .map(|certificate| epoch_store.signature_verifier.verify_cert(certificate)),
+            )
+            .await?
+        };
+        let transaction = ConsensusTransaction::new_soft_bundle_message(
+            &state.name,
+            certificates
+                .iter()
+                .map(|certificate| certificate.clone().into())
+                .collect(),
+        );
+        let bundle_key = SequencedConsensusTransactionKey::External(transaction.key());
+        {
+            let reconfiguration_lock = epoch_store.get_reconfig_state_read_lock_guard();
+            if !reconfiguration_lock.should_accept_user_certs() {
+                metrics.num_rejected_cert_in_epoch_boundary.inc();
+                return Err(SuiError::ValidatorHaltedAtEpochEnd.into());
+            }
+            let _metrics_guard = metrics.consensus_latency.start_timer();
+            consensus_adapter.submit(transaction, Some(&reconfiguration_lock), &epoch_store)?;
+        }
+
+        // 3) The bundle is processed whether it is sequenced or rejected. It was sequenced only if
+        // all of its certificates were.
+        epoch_store
+            .consensus_message_processed_notify(bundle_key)
+            .await?;
+        for certificate in &certificates {
+            fp_ensure!(
+                epoch_store.is_tx_cert_consensus_message_processed(certificate)?,
+                SuiError::SoftBundleRejected.into()
+            );
+        }
+
+        // 4) Wait for the execution results of every certificate in the bundle.
+        let mut responses = Vec::with_capacity(certificates.len());
+        for certificate in &certificates {
+            let effects = state.execute_certificate(certificate, &epoch_store).await?;
+            let events = if let Some(event_digest) = effects.events_digest() {
+                state.get_transaction_events(event_digest)?
+            } else {
+                TransactionEvents::default()
+            };
+            responses.push(HandleCertificateResponseV2 {
+                signed_effects: effects.into_inner(),
+                events,
+                fastpath_input_objects: vec![], // unused field
+            });
+        }
+        Ok(HandleSoftBundleCertificatesResponse { responses })
+    }
 }
 
 #[async_trait]
@@ -557,6 +701,32 @@ impl Validator for ValidatorService {
         })
     }
 
+    async fn handle_soft_bundle_certificates(
+        &self,
+        request: tonic::Request<HandleSoftBundleCertificatesRequest>,
+    ) -> Result<tonic::Response<HandleSoftBundleCertificatesResponse>, tonic::Status> {
+        let validator_service = self.clone();
+        // Spawns a task which handles the bundle. The task will unconditionally continue
+        // processing in the event that the client connection is dropped.
+        spawn_monitored_task!(async move {
+            let span = error_span!(
+                "handle_soft_bundle_certificates",
+                tx_digests = ?request
+                    .get_ref()
+                    .certificates
+                    .iter()
+                    .map(|certificate| *certificate.digest())
+                    .collect::<Vec<_>>()
+            );
+            Self::handle_soft_bundle_certificates(validator_service, request)
+                .instrument(span)
+                .await
+        })
+        .await
+        .unwrap()
+        .map(tonic::Response::new)
+    }
+
     async fn handle_certificate(
         &self,
         request: tonic::Request<CertifiedTransaction>,
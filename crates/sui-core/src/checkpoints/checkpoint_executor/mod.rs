@@ -1000,5 +1000,8 @@ fn finalize_checkpoint(
     )?;
 
     accumulator.accumulate_checkpoint(effects, checkpoint_sequence, epoch_store)?;
+    authority_store
+        .write_amplification_profiler()
+        .record_checkpoint(checkpoint_sequence, tx_digests);
     Ok(())
 }
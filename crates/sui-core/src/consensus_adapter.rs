@@ -53,7 +53,6 @@ use sui_simulator::narwhal_network::connectivity::ConnectionStatus;
 use sui_types::base_types::AuthorityName;
 use sui_types::fp_ensure;
 use sui_types::messages_consensus::ConsensusTransaction;
-use sui_types::messages_consensus::ConsensusTransactionKind;
 use tokio::time::Duration;
 use tracing::{debug, info, warn};
 
@@ -418,13 +417,14 @@ impl ConsensusAdapter {
         committee: &Committee,
         transaction: &ConsensusTransaction,
     ) -> (impl Future<Output = ()>, usize, usize, usize) {
-        let (duration, position, positions_moved, preceding_disconnected) = match &transaction.kind
-        {
-            ConsensusTransactionKind::UserTransaction(certificate) => {
-                self.await_submit_delay_user_transaction(committee, certificate.digest())
-            }
-            _ => (Duration::ZERO, 0, 0, 0),
-        };
+        // Soft bundles are positioned by their first certificate.
+        let (duration, position, positions_moved, preceding_disconnected) =
+            match transaction.user_certificates().first() {
+                Some(certificate) => {
+                    self.await_submit_delay_user_transaction(committee, certificate.digest())
+                }
+                None => (Duration::ZERO, 0, 0, 0),
+            };
         (
             tokio::time::sleep(duration),
             position,
@@ -685,7 +685,7 @@ impl ConsensusAdapter {
         transaction: ConsensusTransaction,
         epoch_store: &Arc<AuthorityPerEpochStore>,
     ) {
-        if matches!(transaction.kind, ConsensusTransactionKind::EndOfPublish(..)) {
+        if transaction.is_end_of_publish() {
             info!(epoch=?epoch_store.epoch(), "Submitting EndOfPublish message to Narwhal");
             epoch_store.record_epoch_pending_certs_process_time_metric();
         }
@@ -721,11 +721,7 @@ impl ConsensusAdapter {
 
         let transaction_key = transaction.key();
         // Log warnings for capability or end of publish transactions that fail to get sequenced
-        let _monitor = if matches!(
-            transaction.kind,
-            ConsensusTransactionKind::EndOfPublish(_)
-                | ConsensusTransactionKind::CapabilityNotification(_)
-        ) {
+        let _monitor = if transaction.kind.is_epoch_critical() {
             let transaction_key = transaction_key.clone();
             Some(CancelOnDrop(spawn_monitored_task!(async {
                 let mut i = 0u64;
@@ -816,8 +812,8 @@ impl ConsensusAdapter {
         epoch_store
             .remove_pending_consensus_transaction(&transaction.key())
             .expect("Storage error when removing consensus transaction");
-        let send_end_of_publish = if let ConsensusTransactionKind::UserTransaction(_cert) =
-            &transaction.kind
+        let send_end_of_publish = if transaction.is_user_certificate()
+            || transaction.is_soft_bundle()
         {
             let reconfig_guard = epoch_store.get_reconfig_state_read_lock_guard();
             // If we are in RejectUserCerts state and we just drained the list we need to
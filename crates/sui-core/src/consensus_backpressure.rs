@@ -0,0 +1,194 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Backpressure from execution to the consensus handler. When execution falls behind, the handler
+//! waits before processing the next commit instead of piling up transactions in memory.
+
+use crate::authority::AuthorityMetrics;
+use crate::transaction_manager::TransactionManager;
+use std::sync::Arc;
+use std::time::Duration;
+use sui_config::node::OverloadThresholdConfig;
+use tokio::time::Instant;
+use tracing::warn;
+
+/// How often the pending execution is checked while the consensus handler is waiting.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Reports how far execution lags behind the consensus handler.
+pub trait ExecutionBackpressure: Send + Sync {
+    /// Number of transactions scheduled for execution that have not been executed yet.
+    fn pending_execution(&self) -> usize;
+
+    /// Number of the pending transactions that have all their inputs available, and so will be
+    /// executed without waiting for more consensus output.
+    fn ready_execution(&self) -> usize;
+}
+
+impl ExecutionBackpressure for TransactionManager {
+    fn pending_execution(&self) -> usize {
+        self.inflight_queue_len()
+    }
+
+    fn ready_execution(&self) -> usize {
+        self.executing_queue_len()
+    }
+}
+
+/// Throttles the consensus handler while execution lags behind.
+pub struct ConsensusBackpressure {
+    execution: Arc<dyn ExecutionBackpressure>,
+    max_pending_execution: usize,
+    max_ready_execution: usize,
+    poll_interval: Duration,
+    max_wait: Duration,
+    metrics: Arc<AuthorityMetrics>,
+}
+
+impl ConsensusBackpressure {
+    pub fn new(
+        execution: Arc<dyn ExecutionBackpressure>,
+        config: &OverloadThresholdConfig,
+        metrics: Arc<AuthorityMetrics>,
+    ) -> Self {
+        Self {
+            execution,
+            max_pending_execution: config.consensus_backpressure_max_pending_execution,
+            max_ready_execution: config.consensus_backpressure_max_ready_execution,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+            max_wait: config.consensus_backpressure_max_wait,
+            metrics,
+        }
+    }
+
+    /// Waits until the number of transactions pending execution drops below the limit, or until
+    /// the maximum wait has elapsed. While the number of transactions ready for execution is at
+    /// its limit, waits past the maximum wait, as these drain without more consensus output.
+    /// Returns true if the handler was throttled.
+    pub async fn wait_for_execution(&self) -> bool {
+        let (mut pending, mut ready) = self.observe_execution();
+        if pending < self.max_pending_execution && ready < self.max_ready_execution {
+            return false;
+        }
+
+        let _timer = self
+            .metrics
+            .consensus_handler_backpressure_latency
+            .start_timer();
+        self.metrics.consensus_handler_backpressure_waits.inc();
+        let deadline = Instant::now() + self.max_wait;
+        while pending >= self.max_pending_execution || ready >= self.max_ready_execution {
+            if ready < self.max_ready_execution && Instant::now() >= deadline {
+                warn!(
+                    "Execution still has {} pending transactions after waiting {:?}, continuing to process consensus output",
+                    pending, self.max_wait
+                );
+                self.metrics.consensus_handler_backpressure_timeouts.inc();
+                break;
+            }
+            tokio::time::sleep(self.poll_interval).await;
+            (pending, ready) = self.observe_execution();
+        }
+        true
+    }
+
+    fn observe_execution(&self) -> (usize, usize) {
+        let pending = self.execution.pending_execution();
+        self.metrics
+            .consensus_handler_pending_execution
+            .set(pending as i64);
+        (pending, self.execution.ready_execution())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use prometheus::Registry;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Transactions pending execution, and how many of them are ready for execution
+    struct TestExecution(AtomicUsize, AtomicUsize);
+
+    impl ExecutionBackpressure for TestExecution {
+        fn pending_execution(&self) -> usize {
+            self.0.load(Ordering::Relaxed)
+        }
+
+        fn ready_execution(&self) -> usize {
+            self.1.load(Ordering::Relaxed)
+        }
+    }
+
+    fn test_config() -> OverloadThresholdConfig {
+        OverloadThresholdConfig {
+            consensus_backpressure_max_pending_execution: 10,
+            consensus_backpressure_max_wait: Duration::from_secs(1),
+            consensus_backpressure_max_ready_execution: 20,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_consensus_backpressure() {
+        let metrics = Arc::new(AuthorityMetrics::new(&Registry::new()));
+        let execution = Arc::new(TestExecution(AtomicUsize::new(5), AtomicUsize::new(0)));
+        let backpressure =
+            ConsensusBackpressure::new(execution.clone(), &test_config(), metrics.clone());
+
+        // Execution keeps up.
+        assert!(!backpressure.wait_for_execution().await);
+        assert_eq!(metrics.consensus_handler_pending_execution.get(), 5);
+
+        // Execution catches up while the handler waits.
+        execution.0.store(10, Ordering::Relaxed);
+        let drain = {
+            let execution = execution.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(200)).await;
+                execution.0.store(3, Ordering::Relaxed);
+            })
+        };
+        assert!(backpressure.wait_for_execution().await);
+        drain.await.unwrap();
+        assert_eq!(metrics.consensus_handler_pending_execution.get(), 3);
+        assert_eq!(metrics.consensus_handler_backpressure_waits.get(), 1);
+        assert_eq!(metrics.consensus_handler_backpressure_timeouts.get(), 0);
+
+        // Execution does not catch up, and the handler stops waiting.
+        execution.0.store(20, Ordering::Relaxed);
+        assert!(backpressure.wait_for_execution().await);
+        assert_eq!(metrics.consensus_handler_backpressure_waits.get(), 2);
+        assert_eq!(metrics.consensus_handler_backpressure_timeouts.get(), 1);
+    }
+    #[tokio::test(start_paused = true)]
+    async fn test_consensus_backpressure_hard_limit() {
+        let metrics = Arc::new(AuthorityMetrics::new(&Registry::new()));
+        let execution = Arc::new(TestExecution(AtomicUsize::new(30), AtomicUsize::new(20)));
+        let backpressure =
+            ConsensusBackpressure::new(execution.clone(), &test_config(), metrics.clone());
+
+        // Execution only catches up well past the maximum wait, and the handler waits for it.
+        let drain = {
+            let execution = execution.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                execution.0.store(3, Ordering::Relaxed);
+                execution.1.store(3, Ordering::Relaxed);
+            })
+        };
+        let start = Instant::now();
+        assert!(backpressure.wait_for_execution().await);
+        assert!(start.elapsed() >= Duration::from_secs(10));
+        drain.await.unwrap();
+        assert_eq!(metrics.consensus_handler_backpressure_timeouts.get(), 0);
+
+        // Once under the hard limit, the maximum wait applies again.
+        execution.0.store(30, Ordering::Relaxed);
+        execution.1.store(10, Ordering::Relaxed);
+        let start = Instant::now();
+        assert!(backpressure.wait_for_execution().await);
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert_eq!(metrics.consensus_handler_backpressure_timeouts.get(), 1);
+    }
+}
@@ -7,37 +7,43 @@ use crate::authority::authority_per_epoch_store::{
 use crate::authority::epoch_start_configuration::EpochStartConfigTrait;
 use crate::authority::{AuthorityMetrics, AuthorityState, AuthorityStore};
 use crate::checkpoints::{CheckpointService, CheckpointServiceNotify};
+use crate::consensus_backpressure::ConsensusBackpressure;
 use crate::consensus_throughput_calculator::ConsensusThroughputCalculator;
 use crate::consensus_types::committee_api::CommitteeAPI;
-use crate::consensus_types::consensus_output_api::ConsensusOutputAPI;
+use crate::consensus_types::consensus_output_api::{
+    ConsensusCommitDigest, ConsensusOutputAPI, EpochBoundaryMarkers, MysticetiReputationScorer,
+    NarwhalConsensusOutput, MYSTICETI_SCHEDULE_CHANGE_SUB_DAGS,
+};
 use crate::consensus_types::AuthorityIndex;
 use crate::scoring_decision::update_low_scoring_authorities;
 use crate::transaction_manager::TransactionManager;
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
-use lru::LruCache;
 use mysten_metrics::{monitored_scope, spawn_monitored_task};
 use narwhal_config::Committee;
 use narwhal_executor::{ExecutionIndices, ExecutionState};
 use narwhal_types::ConsensusOutput;
 use serde::{Deserialize, Serialize};
 use std::collections::hash_map::DefaultHasher;
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, VecDeque};
 use std::hash::{Hash, Hasher};
-use std::num::NonZeroUsize;
 use std::sync::Arc;
+use sui_config::node::OverloadThresholdConfig;
 use sui_types::authenticator_state::ActiveJwk;
 use sui_types::base_types::{AuthorityName, EpochId, TransactionDigest};
 use sui_types::executable_transaction::{
     TrustedExecutableTransaction, VerifiedExecutableTransaction,
 };
-use sui_types::messages_consensus::{
-    ConsensusTransaction, ConsensusTransactionKey, ConsensusTransactionKind,
-};
+use sui_types::messages_consensus::{ConsensusTransaction, ConsensusTransactionKey};
 use sui_types::storage::ObjectStore;
 use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
 use sui_types::transaction::{SenderSignedData, VerifiedTransaction};
-use tracing::{debug, error, info, instrument, trace_span};
+use tracing::{debug, info, instrument, trace_span};
+
+pub use crate::consensus_types::consensus_output_api::{
+    CommitTimestampPolicy, ConsensusCommitTimestamps, MalformedTransactionPolicy,
+    ScriptedCommitTimestamps, SkewedCommitTimestamps,
+};
 
 pub struct ConsensusHandlerInitializer {
     state: Arc<AuthorityState>,
@@ -94,6 +100,7 @@ impl ConsensusHandlerInitializer {
             committee,
             self.state.metrics.clone(),
             self.throughput_calculator.clone(),
+            self.state.overload_threshold_config(),
         )
     }
 }
@@ -102,10 +109,8 @@ pub struct ConsensusHandler<T, C> {
     /// A store created for each epoch. ConsensusHandler is recreated each epoch, with the
     /// corresponding store. This store is also used to get the current epoch ID.
     epoch_store: Arc<AuthorityPerEpochStore>,
-    /// Holds the indices, hash and stats after the last consensus commit
-    /// It is used for avoiding replaying already processed transactions,
-    /// checking chain consistency, and accumulating per-epoch consensus output stats.
-    last_consensus_stats: ExecutionIndicesWithStats,
+    /// Decides which transactions of each commit are sequenced
+    sequencer: ConsensusCommitSequencer,
     checkpoint_service: Arc<C>,
     /// parent_sync_store is needed when determining the next version to assign for shared objects.
     object_store: T,
@@ -116,14 +121,16 @@ pub struct ConsensusHandler<T, C> {
     // TODO: ConsensusHandler doesn't really share metrics with AuthorityState. We could define
     // a new metrics type here if we want to.
     metrics: Arc<AuthorityMetrics>,
-    /// Lru cache to quickly discard transactions processed by consensus
-    processed_cache: LruCache<SequencedConsensusTransactionKey, ()>,
     transaction_scheduler: AsyncTransactionScheduler,
+    /// Throttles processing of commits while execution lags behind
+    backpressure: ConsensusBackpressure,
     /// Using the throughput calculator to record the current consensus throughput
     throughput_calculator: Arc<ConsensusThroughputCalculator>,
 }
 
-const PROCESSED_CACHE_CAP: usize = 1024 * 1024;
+/// Number of commits the consensus handler deduplicates transactions over, unless a different
+/// window has already been recorded for the epoch.
+pub const DEFAULT_CONSENSUS_DEDUP_WINDOW_COMMITS: u64 = 50;
 
 impl<T, C> ConsensusHandler<T, C> {
     pub fn new(
@@ -135,6 +142,7 @@ impl<T, C> ConsensusHandler<T, C> {
         committee: Committee,
         metrics: Arc<AuthorityMetrics>,
         throughput_calculator: Arc<ConsensusThroughputCalculator>,
+        overload_threshold_config: &OverloadThresholdConfig,
     ) -> Self {
         // Recover last_consensus_stats so it is consistent across validators.
         let mut last_consensus_stats = epoch_store
@@ -144,31 +152,72 @@ impl<T, C> ConsensusHandler<T, C> {
         if !last_consensus_stats.stats.is_initialized() {
             last_consensus_stats.stats = ConsensusStats::new(committee.size());
         }
+        last_consensus_stats.stats = last_consensus_stats.stats.upgrade();
+        let dedup_window = epoch_store
+            .get_or_init_consensus_dedup_window(DEFAULT_CONSENSUS_DEDUP_WINDOW_COMMITS)
+            .expect("Should be able to read consensus dedup window");
+        let protocol_config = epoch_store.protocol_config();
+        let submission_quota = protocol_config
+            .authority_submission_quota_window_commits_as_option()
+            .zip(protocol_config.max_authority_submission_bytes_per_window_as_option())
+            .map(|(window, max_bytes)| {
+                let window_start =
+                    (last_consensus_stats.index.sub_dag_index + 1).saturating_sub(window);
+                let commits = epoch_store
+                    .get_authority_submission_bytes(window_start)
+                    .expect("Should be able to read authority submission bytes");
+                AuthoritySubmissionQuota::new(window, max_bytes, committee.size(), commits)
+            });
+        let mut sequencer = ConsensusCommitSequencer::new(
+            epoch_store.epoch(),
+            epoch_store.epoch_start_config().epoch_start_timestamp_ms(),
+            last_consensus_stats,
+            ConsensusDedupWindow::new(dedup_window),
+            submission_quota,
+        );
+        sequencer.monotonic_commit_timestamps = protocol_config.monotonic_commit_timestamps();
+        let backpressure = ConsensusBackpressure::new(
+            transaction_manager.clone(),
+            overload_threshold_config,
+            metrics.clone(),
+        );
         let transaction_scheduler =
             AsyncTransactionScheduler::start(transaction_manager, epoch_store.clone());
         Self {
             epoch_store,
-            last_consensus_stats,
+            sequencer,
             checkpoint_service,
             object_store,
             low_scoring_authorities,
             committee,
             metrics,
-            processed_cache: LruCache::new(NonZeroUsize::new(PROCESSED_CACHE_CAP).unwrap()),
             transaction_scheduler,
+            backpressure,
             throughput_calculator,
         }
     }
 
-    /// Updates the execution indexes based on the provided input. Some is returned when the indexes
-    /// are updated which means that the transaction has been seen for first time. None is returned
-    /// otherwise.
-    fn update_index_and_hash(&mut self, index: ExecutionIndices, v: &[u8]) -> bool {
-        update_index_and_hash(&mut self.last_consensus_stats, index, v)
+    /// Replaces the policy deciding commit timestamps. Only meant for tests and local networks, as
+    /// validators must agree on the timestamps of commits.
+    pub fn set_commit_timestamp_policy(&mut self, policy: Arc<dyn CommitTimestampPolicy>) {
+        self.sequencer.commit_timestamp_policy = policy;
+    }
+
+    /// Replaces the policy deciding what happens to committed transactions that fail to
+    /// deserialize, which defaults to panicking in debug builds and rejecting them otherwise.
+    pub fn set_malformed_transaction_policy(&mut self, policy: MalformedTransactionPolicy) {
+        self.sequencer.malformed_transaction_policy = policy;
+    }
+
+    /// Replaces how the handler is throttled while execution lags behind.
+    pub fn set_backpressure(&mut self, backpressure: ConsensusBackpressure) {
+        self.backpressure = backpressure;
     }
 }
 
-fn update_index_and_hash(
+/// Updates the execution indexes based on the provided input. Returns true when the indexes are
+/// updated, which means that the transaction has been seen for first time.
+pub(crate) fn update_index_and_hash(
     last_consensus_stats: &mut ExecutionIndicesWithStats,
     index: ExecutionIndices,
     v: &[u8],
@@ -195,6 +244,433 @@ fn update_index_and_hash(
     true
 }
 
+/// Keys of the transactions sequenced by the last `window` commits, so a transaction included
+/// again by a nearby commit (common during leader failover) is only processed once.
+///
+/// The window is not persisted, so a validator starts with an empty window after a restart.
+/// Transactions are filtered after the consensus index and hash are updated, and before they are
+/// charged to the submission quota. Any transaction filtered here would also be discarded by
+/// [AuthorityPerEpochStore::is_consensus_message_processed], which is checked before charging the
+/// quota as well, so a restarted validator still ends up with the same state as the others.
+pub(crate) struct ConsensusDedupWindow {
+    window: u64,
+    /// The keys first sequenced by each commit in the window, oldest first.
+    commits: VecDeque<(u64, Vec<SequencedConsensusTransactionKey>)>,
+    /// The commit each key in the window was last sequenced by.
+    keys: HashMap<SequencedConsensusTransactionKey, u64>,
+}
+
+impl ConsensusDedupWindow {
+    pub(crate) fn new(window: u64) -> Self {
+        Self {
+            window,
+            commits: VecDeque::new(),
+            keys: HashMap::new(),
+        }
+    }
+
+    /// Starts the commit with the given sub dag index, forgetting the transactions of commits that
+    /// fell out of the window.
+    pub(crate) fn start_commit(&mut self, sub_dag_index: u64) {
+        while let Some((index, _)) = self.commits.front() {
+            if index.saturating_add(self.window) > sub_dag_index {
+                break;
+            }
+            let (index, keys) = self.commits.pop_front().unwrap();
+            for key in keys {
+                if self.keys.get(&key) == Some(&index) {
+                    self.keys.remove(&key);
+                }
+            }
+        }
+        self.commits.push_back((sub_dag_index, Vec::new()));
+    }
+
+    /// Returns true if the transaction was already sequenced within the window.
+    pub(crate) fn contains(&self, key: &SequencedConsensusTransactionKey) -> bool {
+        self.keys.contains_key(key)
+    }
+
+    /// Adds a transaction sequenced by the current commit to the window. Returns false if it was
+    /// already sequenced within the window, in which case it should be skipped.
+    pub(crate) fn insert(&mut self, key: SequencedConsensusTransactionKey) -> bool {
+        let Some((index, keys)) = self.commits.back_mut() else {
+            panic!("start_commit must be called before inserting transactions");
+        };
+        if self.keys.contains_key(&key) {
+            return false;
+        }
+        self.keys.insert(key.clone(), *index);
+        keys.push(key);
+        true
+    }
+}
+
+/// Limits the bytes of user transactions each authority gets processed over the last `window`
+/// commits, so that a single spamming authority cannot flood the node.
+///
+/// The bytes accepted from each authority by each commit are persisted with the commit, and the
+/// window is rebuilt from them on restart, so every validator drops the same transactions. Dropped
+/// transactions are not marked as processed and can still be sequenced when included by another
+/// authority.
+pub(crate) struct AuthoritySubmissionQuota {
+    window: u64,
+    max_bytes: u64,
+    /// The bytes accepted from each authority by each commit in the window, oldest first.
+    commits: VecDeque<(u64, Vec<u64>)>,
+    /// The bytes accepted from each authority over the window.
+    totals: Vec<u64>,
+}
+
+impl AuthoritySubmissionQuota {
+    /// Creates the quota from the bytes accepted by previous commits of the epoch, in commit order.
+    pub(crate) fn new(
+        window: u64,
+        max_bytes: u64,
+        committee_size: usize,
+        commits: Vec<(u64, Vec<u64>)>,
+    ) -> Self {
+        let mut totals = vec![0; committee_size];
+        for (_, bytes) in &commits {
+            for (total, bytes) in totals.iter_mut().zip(bytes) {
+                *total += bytes;
+            }
+        }
+        Self {
+            window,
+            max_bytes,
+            commits: commits.into(),
+            totals,
+        }
+    }
+
+    /// Starts the commit with the given sub dag index, releasing the quota used by commits that
+    /// fell out of the window.
+    pub(crate) fn start_commit(&mut self, sub_dag_index: u64) {
+        while let Some((index, _)) = self.commits.front() {
+            if index.saturating_add(self.window) > sub_dag_index {
+                break;
+            }
+            let (_, bytes) = self.commits.pop_front().unwrap();
+            for (total, bytes) in self.totals.iter_mut().zip(bytes) {
+                *total -= bytes;
+            }
+        }
+        self.commits
+            .push_back((sub_dag_index, vec![0; self.totals.len()]));
+    }
+
+    /// Charges `bytes` to the quota of `authority` in the current commit. Returns false if the
+    /// authority is over its quota, in which case the transaction should be dropped.
+    pub(crate) fn try_consume(&mut self, authority: AuthorityIndex, bytes: u64) -> bool {
+        let Some((_, commit_bytes)) = self.commits.back_mut() else {
+            panic!("start_commit must be called before consuming quota");
+        };
+        let authority = authority as usize;
+        if self.totals[authority] + bytes > self.max_bytes {
+            return false;
+        }
+        self.totals[authority] += bytes;
+        commit_bytes[authority] += bytes;
+        true
+    }
+
+    /// The bytes accepted from each authority by the current commit, to be persisted with it.
+    pub(crate) fn current_commit_bytes(&self) -> Vec<u64> {
+        self.commits
+            .back()
+            .map(|(_, bytes)| bytes.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// Sequences the transactions of consensus commits, deciding which of them are processed and in
+/// what order from the commits sequenced before. It does not read from or write to any store, so
+/// that commits are sequenced the same way by the consensus handler, which persists the state with
+/// each commit, and when replayed offline by [crate::consensus_replay::ConsensusReplay].
+pub(crate) struct ConsensusCommitSequencer {
+    epoch: EpochId,
+    epoch_start_timestamp_ms: u64,
+    /// Holds the indices, hash and stats after the last consensus commit
+    /// It is used for avoiding replaying already processed transactions,
+    /// checking chain consistency, and accumulating per-epoch consensus output stats.
+    pub(crate) last_consensus_stats: ExecutionIndicesWithStats,
+    /// Transactions sequenced by the last few commits, to quickly discard ones sequenced again
+    pub(crate) dedup_window: ConsensusDedupWindow,
+    /// Limits the user transaction bytes processed from each authority, when enabled by the
+    /// protocol config
+    pub(crate) submission_quota: Option<AuthoritySubmissionQuota>,
+    /// Decides commit timestamps from the ones reported by consensus
+    pub(crate) commit_timestamp_policy: Arc<dyn CommitTimestampPolicy>,
+    /// Whether commit timestamps are clamped to the timestamp of the previous commit, as enabled
+    /// by the protocol config
+    pub(crate) monotonic_commit_timestamps: bool,
+    /// Decides what happens to committed transactions that fail to deserialize
+    pub(crate) malformed_transaction_policy: MalformedTransactionPolicy,
+}
+
+/// The transactions of a commit, as sequenced by [ConsensusCommitSequencer::sequence], and what
+/// happened to the ones that were not sequenced.
+pub(crate) struct SequencedCommit {
+    pub round: u64,
+    pub sub_dag_index: u64,
+    pub timestamp_ms: u64,
+    pub commit_digest: ConsensusCommitDigest,
+    pub previous_commit_digest: Option<ConsensusCommitDigest>,
+    /// The transactions to process, in order, starting with the system transactions.
+    pub transactions: Vec<SequencedConsensusTransaction>,
+    /// The bytes of user transactions accepted from each authority, when the submission quota is
+    /// enabled.
+    pub submission_bytes: Option<Vec<u64>>,
+    /// For each certificate or block in the commit, its authority and how many of its
+    /// transactions were well-formed and valid.
+    pub authority_transactions: Vec<(AuthorityIndex, usize)>,
+    /// The bytes of the well-formed and valid transactions.
+    pub bytes: usize,
+    /// The kind of each well-formed and valid transaction, as in metrics.
+    pub kinds: Vec<&'static str>,
+    /// The authority that included each transaction that failed to deserialize.
+    pub malformed: Vec<AuthorityIndex>,
+    pub epoch_boundary_markers: EpochBoundaryMarkers,
+    /// Transactions skipped because their consensus index was already processed.
+    pub already_processed: usize,
+    /// Transactions skipped because a recent commit already sequenced them.
+    pub duplicates: usize,
+    /// The authority that included each transaction dropped because it was over its submission
+    /// quota.
+    pub over_quota: Vec<AuthorityIndex>,
+}
+
+impl ConsensusCommitSequencer {
+    pub(crate) fn new(
+        epoch: EpochId,
+        epoch_start_timestamp_ms: u64,
+        last_consensus_stats: ExecutionIndicesWithStats,
+        dedup_window: ConsensusDedupWindow,
+        submission_quota: Option<AuthoritySubmissionQuota>,
+    ) -> Self {
+        Self {
+            epoch,
+            epoch_start_timestamp_ms,
+            last_consensus_stats,
+            dedup_window,
+            submission_quota,
+            commit_timestamp_policy: Arc::new(ConsensusCommitTimestamps),
+            monotonic_commit_timestamps: false,
+            malformed_transaction_policy: MalformedTransactionPolicy::default(),
+        }
+    }
+
+    /// Digest of the last commit sequenced, which the next commit is linked to. It is persisted
+    /// with the rest of the stats, so the link survives restarts.
+    pub(crate) fn last_commit_digest(&self) -> Option<ConsensusCommitDigest> {
+        self.last_consensus_stats
+            .stats
+            .get_last_commit_digest()
+            .map(ConsensusCommitDigest::new)
+    }
+
+    /// Returns true if a commit for `round` was already sequenced, as happens when the same
+    /// commit is received twice after a restart. Such commits must be ignored.
+    pub(crate) fn is_committed(&self, round: u64) -> bool {
+        self.last_consensus_stats.index.last_committed_round == round
+    }
+
+    /// Sequences the transactions of `output`: the consensus commit prologue, then
+    /// `system_transactions`, then the transactions included by each authority that are
+    /// well-formed, were not processed or sequenced by a recent commit yet, and are within the
+    /// submission quota of their authority. Transactions carried by a soft bundle count as user
+    /// transactions of the authority that included the bundle.
+    ///
+    /// `is_processed` tells whether a transaction was processed by a previous commit, as persisted
+    /// by the epoch store. User transactions already processed are skipped before being charged to
+    /// the submission quota.
+    pub(crate) fn sequence(
+        &mut self,
+        output: &impl ConsensusOutputAPI,
+        committee: &dyn CommitteeAPI,
+        system_transactions: Vec<VerifiedExecutableTransaction>,
+        is_processed: impl Fn(&SequencedConsensusTransactionKey) -> bool,
+    ) -> SequencedCommit {
+        let round = output.leader_round();
+        let leader_author = output.leader_author_index();
+        let sub_dag_index = output.commit_sub_dag_index();
+        self.dedup_window.start_commit(sub_dag_index);
+        if let Some(submission_quota) = &mut self.submission_quota {
+            submission_quota.start_commit(sub_dag_index);
+        }
+
+        let last_commit_timestamp_ms = if self.monotonic_commit_timestamps {
+            self.last_consensus_stats
+                .stats
+                .get_last_commit_timestamp_ms()
+        } else {
+            0
+        };
+        let timestamp_ms = output.validated_commit_timestamp_ms(
+            self.commit_timestamp_policy.as_ref(),
+            self.epoch_start_timestamp_ms,
+            last_commit_timestamp_ms,
+        );
+        if self.monotonic_commit_timestamps {
+            // Persisted along with the rest of the stats at the commit boundary, so that
+            // timestamps stay monotonic across restarts.
+            self.last_consensus_stats
+                .stats
+                .set_last_commit_timestamp_ms(timestamp_ms);
+        }
+        let commit_digest = output.commit_digest();
+        self.last_consensus_stats
+            .stats
+            .set_last_commit_digest(commit_digest.digest);
+
+        let mut commit = SequencedCommit {
+            round,
+            sub_dag_index,
+            timestamp_ms,
+            commit_digest,
+            previous_commit_digest: output.previous_commit_digest(),
+            transactions: vec![],
+            submission_bytes: None,
+            authority_transactions: vec![],
+            bytes: 0,
+            kinds: vec![],
+            malformed: vec![],
+            epoch_boundary_markers: output
+                .epoch_boundary_markers(committee, self.malformed_transaction_policy),
+            already_processed: 0,
+            duplicates: 0,
+            over_quota: vec![],
+        };
+
+        /* (serialized, transaction, output_cert, origin) */
+        let mut transactions = vec![];
+        let prologue = VerifiedExecutableTransaction::new_system(
+            VerifiedTransaction::new_consensus_commit_prologue(self.epoch, round, timestamp_ms),
+            self.epoch,
+        );
+        let empty_bytes: &[u8] = &[];
+        for transaction in std::iter::once(prologue).chain(system_transactions) {
+            transactions.push((
+                empty_bytes,
+                SequencedConsensusTransactionKind::System(transaction),
+                leader_author,
+                None,
+            ));
+        }
+
+        for (authority_index, authority_transactions) in
+            output.transactions_iter(self.malformed_transaction_policy)
+        {
+            let mut num_authority_transactions = 0;
+            self.last_consensus_stats
+                .stats
+                .inc_narwhal_certificates(authority_index as usize);
+            for transaction in authority_transactions {
+                let (serialized_transaction, transaction, origin) = match transaction {
+                    Ok(transaction) => transaction,
+                    Err(origin) => {
+                        commit.malformed.push(origin.authority);
+                        continue;
+                    }
+                };
+                if commit.epoch_boundary_markers.is_invalid(&origin) {
+                    continue;
+                }
+                commit.bytes += serialized_transaction.len();
+                num_authority_transactions += 1;
+                commit.kinds.push(classify(&transaction));
+                for _ in transaction.user_certificates() {
+                    self.last_consensus_stats
+                        .stats
+                        .inc_user_transactions(authority_index as usize);
+                }
+                transactions.push((
+                    serialized_transaction,
+                    SequencedConsensusTransactionKind::External(transaction),
+                    authority_index,
+                    Some(origin),
+                ));
+            }
+            commit
+                .authority_transactions
+                .push((authority_index, num_authority_transactions));
+        }
+
+        for (seq, (serialized, transaction, cert_origin, origin)) in
+            transactions.into_iter().enumerate()
+        {
+            let index = ExecutionIndices {
+                last_committed_round: round,
+                sub_dag_index,
+                transaction_index: seq as u64,
+            };
+
+            if !update_index_and_hash(&mut self.last_consensus_stats, index, serialized) {
+                debug!(
+                    "Ignore consensus transaction at index {:?} as it appear to be already processed",
+                    index
+                );
+                commit.already_processed += 1;
+                continue;
+            }
+
+            let certificate_author = committee.authority_pubkey_by_index(cert_origin).unwrap();
+
+            let sequenced_transaction = SequencedConsensusTransaction {
+                certificate_author_index: cert_origin,
+                certificate_author,
+                consensus_index: index,
+                transaction,
+            };
+
+            let key = sequenced_transaction.key();
+            if self.dedup_window.contains(&key) {
+                debug!(
+                    "Skipping consensus transaction {:?} already sequenced, included at {:?}",
+                    key, origin
+                );
+                commit.duplicates += 1;
+                continue;
+            }
+
+            // Only user transactions count towards the quota, so that transactions needed to
+            // make progress are never dropped.
+            if let (Some(submission_quota), Some(origin)) = (&mut self.submission_quota, &origin) {
+                if sequenced_transaction.is_user_transaction() {
+                    // The dedup window is lost on restart, but which transactions were processed
+                    // is not, so every validator skips the same transactions here.
+                    if is_processed(&key) {
+                        debug!(
+                            "Ignore consensus transaction {:?} included at {:?} as it was already processed",
+                            key, origin
+                        );
+                        commit.already_processed += 1;
+                        continue;
+                    }
+                    if !submission_quota.try_consume(origin.authority, serialized.len() as u64) {
+                        debug!(
+                            "Dropping consensus transaction {:?} included at {:?} in commit led by {}, authority {} is over its submission quota",
+                            key, origin, leader_author, origin.authority
+                        );
+                        commit.over_quota.push(origin.authority);
+                        continue;
+                    }
+                }
+            }
+
+            self.dedup_window.insert(key);
+
+            commit.transactions.push(sequenced_transaction);
+        }
+        commit.submission_bytes = self
+            .submission_quota
+            .as_ref()
+            .map(AuthoritySubmissionQuota::current_commit_bytes);
+        commit
+    }
+}
+
 #[async_trait]
 impl<T: ObjectStore + Send + Sync, C: CheckpointServiceNotify + Send + Sync> ExecutionState
     for ConsensusHandler<T, C>
@@ -203,12 +679,16 @@ impl<T: ObjectStore + Send + Sync, C: CheckpointServiceNotify + Send + Sync> Exe
     #[instrument(level = "debug", skip_all)]
     async fn handle_consensus_output(&mut self, consensus_output: ConsensusOutput) {
         let _scope = monitored_scope("HandleConsensusOutput");
+        let consensus_output = NarwhalConsensusOutput {
+            output: consensus_output,
+            previous_commit_digest: self.sequencer.last_commit_digest(),
+        };
         self.handle_consensus_output_internal(consensus_output)
             .await;
     }
 
     async fn last_executed_sub_dag_index(&self) -> u64 {
-        self.last_consensus_stats.index.sub_dag_index
+        self.sequencer.last_consensus_stats.index.sub_dag_index
     }
 }
 
@@ -226,12 +706,16 @@ impl<T: ObjectStore + Send + Sync, C: CheckpointServiceNotify + Send + Sync>
             .protocol_config()
             .consensus_order_end_of_epoch_last());
 
-        let last_committed_round = self.last_consensus_stats.index.last_committed_round;
+        let last_committed_round = self
+            .sequencer
+            .last_consensus_stats
+            .index
+            .last_committed_round;
 
         let round = consensus_output.leader_round();
 
         assert!(round >= last_committed_round);
-        if last_committed_round == round {
+        if self.sequencer.is_committed(round) {
             // we can receive the same commit twice after restart
             // It is critical that the writes done by this function are atomic - otherwise we can
             // lose the later parts of a commit if we restart midway through processing it.
@@ -242,24 +726,10 @@ impl<T: ObjectStore + Send + Sync, C: CheckpointServiceNotify + Send + Sync>
             return;
         }
 
-        /* (serialized, transaction, output_cert) */
-        let mut transactions = vec![];
-        let timestamp = consensus_output.commit_timestamp_ms();
-        let leader_author = consensus_output.leader_author_index();
-        let commit_sub_dag_index = consensus_output.commit_sub_dag_index();
+        // Do not deserialize and schedule more transactions while execution is lagging behind.
+        self.backpressure.wait_for_execution().await;
 
-        let epoch_start = self
-            .epoch_store
-            .epoch_start_config()
-            .epoch_start_timestamp_ms();
-        let timestamp = if timestamp < epoch_start {
-            error!(
-                "Unexpected commit timestamp {timestamp} less then epoch start time {epoch_start}, author {leader_author}, round {round}",
-            );
-            epoch_start
-        } else {
-            timestamp
-        };
+        let leader_author = consensus_output.leader_author_index();
 
         info!(
             "Received consensus output {} at epoch {}",
@@ -267,14 +737,6 @@ impl<T: ObjectStore + Send + Sync, C: CheckpointServiceNotify + Send + Sync>
             self.epoch_store.epoch(),
         );
 
-        let prologue_transaction = self.consensus_commit_prologue_transaction(round, timestamp);
-        let empty_bytes = vec![];
-        transactions.push((
-            empty_bytes.as_slice(),
-            SequencedConsensusTransactionKind::System(prologue_transaction),
-            consensus_output.leader_author_index(),
-        ));
-
         // Load all jwks that became active in the previous round, and commit them in this round.
         // We want to delay one round because none of the transactions in the previous round could
         // have been authenticated with the jwks that became active in that round.
@@ -287,16 +749,10 @@ impl<T: ObjectStore + Send + Sync, C: CheckpointServiceNotify + Send + Sync>
             .get_new_jwks(last_committed_round)
             .expect("Unrecoverable error in consensus handler");
 
+        let mut system_transactions = vec![];
         if !new_jwks.is_empty() {
             debug!("adding AuthenticatorStateUpdate tx: {:?}", new_jwks);
-            let authenticator_state_update_transaction =
-                self.authenticator_state_update_transaction(round, new_jwks);
-
-            transactions.push((
-                empty_bytes.as_slice(),
-                SequencedConsensusTransactionKind::System(authenticator_state_update_transaction),
-                consensus_output.leader_author_index(),
-            ));
+            system_transactions.push(self.authenticator_state_update_transaction(round, new_jwks));
         }
 
         update_low_scoring_authorities(
@@ -314,109 +770,53 @@ impl<T: ObjectStore + Send + Sync, C: CheckpointServiceNotify + Send + Sync>
             .with_label_values(&[&leader_author.to_string()])
             .inc();
 
-        let mut bytes = 0usize;
-        {
-            let span = trace_span!("process_consensus_certs");
-            let _guard = span.enter();
-            for (authority_index, authority_transactions) in consensus_output.transactions() {
-                let num_certs = self
-                    .last_consensus_stats
-                    .stats
-                    .inc_narwhal_certificates(authority_index as usize);
-                self.metrics
-                    .consensus_committed_certificates
-                    .with_label_values(&[&authority_index.to_string()])
-                    .set(num_certs as i64);
-                for (serialized_transaction, transaction) in authority_transactions {
-                    bytes += serialized_transaction.len();
-                    self.metrics
-                        .consensus_handler_processed
-                        .with_label_values(&[classify(&transaction)])
-                        .inc();
-                    if matches!(
-                        &transaction.kind,
-                        ConsensusTransactionKind::UserTransaction(_)
-                    ) {
-                        let num_txns = self
-                            .last_consensus_stats
-                            .stats
-                            .inc_user_transactions(authority_index as usize);
-                        self.metrics
-                            .consensus_committed_user_transactions
-                            .with_label_values(&[&authority_index.to_string()])
-                            .set(num_txns as i64);
-                    }
-                    let transaction = SequencedConsensusTransactionKind::External(transaction);
-                    transactions.push((serialized_transaction, transaction, authority_index));
-                }
-            }
+        let consensus_protocol = consensus_output.consensus_protocol();
+        if last_committed_round > 0 {
+            self.metrics
+                .consensus_leader_round_gap
+                .with_label_values(&[consensus_protocol])
+                .observe((round - last_committed_round) as f64);
         }
-        self.metrics
-            .consensus_handler_processed_bytes
-            .inc_by(bytes as u64);
 
-        let mut all_transactions = Vec::new();
-        {
-            // We need a set here as well, since the processed_cache is a LRU cache and can drop
-            // entries while we're iterating over the sequenced transactions.
-            let mut processed_set = HashSet::new();
-
-            for (seq, (serialized, transaction, cert_origin)) in
-                transactions.into_iter().enumerate()
-            {
-                let index = ExecutionIndices {
-                    last_committed_round: round,
-                    sub_dag_index: commit_sub_dag_index,
-                    transaction_index: seq as u64,
-                };
-
-                let index_with_stats = if self.update_index_and_hash(index, serialized) {
-                    self.last_consensus_stats.clone()
-                } else {
-                    debug!(
-                        "Ignore consensus transaction at index {:?} as it appear to be already processed",
-                        index
-                    );
-                    continue;
-                };
-
-                let certificate_author = self
-                    .committee
-                    .authority_pubkey_by_index(cert_origin)
-                    .unwrap();
-
-                let sequenced_transaction = SequencedConsensusTransaction {
-                    certificate_author_index: cert_origin,
-                    certificate_author,
-                    consensus_index: index_with_stats.index,
-                    transaction,
-                };
-
-                let key = sequenced_transaction.key();
-                let in_set = !processed_set.insert(key);
-                let in_cache = self
-                    .processed_cache
-                    .put(sequenced_transaction.key(), ())
-                    .is_some();
-
-                if in_set || in_cache {
-                    self.metrics.skipped_consensus_txns_cache_hit.inc();
-                    continue;
-                }
-
-                all_transactions.push(sequenced_transaction);
-            }
+        let commit = {
+            let span = trace_span!("process_consensus_certs");
+            let _guard = span.enter();
+            let epoch_store = &self.epoch_store;
+            self.sequencer.sequence(
+                &consensus_output,
+                &self.committee,
+                system_transactions,
+                |key| {
+                    epoch_store
+                        .is_consensus_message_processed(key)
+                        .expect("Unrecoverable error in consensus handler")
+                },
+            )
+        };
+        self.report_sequenced_commit(&commit, consensus_protocol);
+        if !commit.epoch_boundary_markers.valid.is_empty() {
+            info!(
+                "Consensus output {} holds EndOfPublish from {:?}",
+                consensus_output,
+                commit
+                    .epoch_boundary_markers
+                    .valid
+                    .iter()
+                    .map(|marker| marker.authority.concise())
+                    .collect::<Vec<_>>()
+            );
         }
 
         let transactions_to_schedule = self
             .epoch_store
             .process_consensus_transactions_and_commit_boundary(
-                all_transactions,
-                &self.last_consensus_stats,
+                commit.transactions,
+                &self.sequencer.last_consensus_stats,
                 &self.checkpoint_service,
                 &self.object_store,
                 round,
-                timestamp,
+                commit.timestamp_ms,
+                commit.submission_bytes,
                 &self.metrics.skipped_consensus_txns,
             )
             .await
@@ -424,12 +824,72 @@ impl<T: ObjectStore + Send + Sync, C: CheckpointServiceNotify + Send + Sync>
 
         // update the calculated throughput
         self.throughput_calculator
-            .add_transactions(timestamp, transactions_to_schedule.len() as u64);
+            .add_transactions(commit.timestamp_ms, transactions_to_schedule.len() as u64);
 
         self.transaction_scheduler
             .schedule(transactions_to_schedule)
             .await;
     }
+
+    /// Updates the metrics of the transactions in a commit, whether they were sequenced or not.
+    fn report_sequenced_commit(&self, commit: &SequencedCommit, consensus_protocol: &str) {
+        let stats = &self.sequencer.last_consensus_stats.stats;
+        let mut num_transactions = 0;
+        for (authority_index, num_authority_transactions) in &commit.authority_transactions {
+            let authority = authority_index.to_string();
+            self.metrics
+                .consensus_committed_certificates
+                .with_label_values(&[&authority])
+                .set(stats.get_narwhal_certificates(*authority_index as usize) as i64);
+            self.metrics
+                .consensus_committed_user_transactions
+                .with_label_values(&[&authority])
+                .set(stats.get_user_transactions(*authority_index as usize) as i64);
+            self.metrics
+                .consensus_commit_authority_transactions
+                .with_label_values(&[consensus_protocol, &authority])
+                .inc_by(*num_authority_transactions as u64);
+            num_transactions += num_authority_transactions;
+        }
+        for authority_index in &commit.malformed {
+            self.metrics
+                .consensus_malformed_transactions
+                .with_label_values(&[&authority_index.to_string()])
+                .inc();
+        }
+        for marker in &commit.epoch_boundary_markers.invalid {
+            self.metrics
+                .consensus_invalid_epoch_boundary_markers
+                .with_label_values(&[&marker.origin.authority.to_string()])
+                .inc();
+        }
+        for kind in &commit.kinds {
+            self.metrics
+                .consensus_handler_processed
+                .with_label_values(&[kind])
+                .inc();
+        }
+        self.metrics
+            .skipped_consensus_txns_cache_hit
+            .inc_by(commit.duplicates as u64);
+        for authority_index in &commit.over_quota {
+            self.metrics
+                .consensus_dropped_over_quota_transactions
+                .with_label_values(&[&authority_index.to_string()])
+                .inc();
+        }
+        self.metrics
+            .consensus_handler_processed_bytes
+            .inc_by(commit.bytes as u64);
+        self.metrics
+            .consensus_commit_transactions
+            .with_label_values(&[consensus_protocol])
+            .observe(num_transactions as f64);
+        self.metrics
+            .consensus_commit_bytes
+            .with_label_values(&[consensus_protocol])
+            .observe(commit.bytes as f64);
+    }
 }
 
 struct AsyncTransactionScheduler {
@@ -479,10 +939,17 @@ impl MysticetiConsensusHandler {
             mysticeti_core::consensus::linearizer::CommittedSubDag,
         >,
     ) -> Self {
+        let mut scorer = MysticetiReputationScorer::new(
+            consensus_handler.committee.size(),
+            MYSTICETI_SCHEDULE_CHANGE_SUB_DAGS,
+        );
         let handle = spawn_monitored_task!(async move {
             while let Some(committed_subdag) = receiver.recv().await {
+                let mut consensus_output = scorer.score(committed_subdag);
+                consensus_output.previous_commit_digest =
+                    consensus_handler.sequencer.last_commit_digest();
                 consensus_handler
-                    .handle_consensus_output_internal(committed_subdag)
+                    .handle_consensus_output_internal(consensus_output)
                     .await;
             }
         });
@@ -497,19 +964,6 @@ impl Drop for MysticetiConsensusHandler {
 }
 
 impl<T, C> ConsensusHandler<T, C> {
-    fn consensus_commit_prologue_transaction(
-        &self,
-        round: u64,
-        commit_timestamp_ms: u64,
-    ) -> VerifiedExecutableTransaction {
-        let transaction = VerifiedTransaction::new_consensus_commit_prologue(
-            self.epoch(),
-            round,
-            commit_timestamp_ms,
-        );
-        VerifiedExecutableTransaction::new_system(transaction, self.epoch())
-    }
-
     fn authenticator_state_update_transaction(
         &self,
         round: u64,
@@ -537,19 +991,7 @@ impl<T, C> ConsensusHandler<T, C> {
 }
 
 pub(crate) fn classify(transaction: &ConsensusTransaction) -> &'static str {
-    match &transaction.kind {
-        ConsensusTransactionKind::UserTransaction(certificate) => {
-            if certificate.contains_shared_object() {
-                "shared_certificate"
-            } else {
-                "owned_certificate"
-            }
-        }
-        ConsensusTransactionKind::CheckpointSignature(_) => "checkpoint_signature",
-        ConsensusTransactionKind::EndOfPublish(_) => "end_of_publish",
-        ConsensusTransactionKind::CapabilityNotification(_) => "capability_notification",
-        ConsensusTransactionKind::NewJWKFetched(_, _, _) => "new_jwk_fetched",
-    }
+    transaction.kind_name()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -643,29 +1085,36 @@ impl SequencedConsensusTransactionKind {
 
     pub fn is_executable_transaction(&self) -> bool {
         match self {
-            SequencedConsensusTransactionKind::External(ext) => ext.is_user_certificate(),
+            SequencedConsensusTransactionKind::External(ext) => {
+                ext.is_user_certificate() || ext.is_soft_bundle()
+            }
             SequencedConsensusTransactionKind::System(_) => true,
         }
     }
 
-    pub fn executable_transaction_digest(&self) -> Option<TransactionDigest> {
+    /// Digests of the transactions executed for this consensus transaction: none, one, or all the
+    /// transactions of a soft bundle.
+    pub fn executable_transaction_digests(&self) -> Vec<TransactionDigest> {
         match self {
-            SequencedConsensusTransactionKind::External(ext) => {
-                if let ConsensusTransactionKind::UserTransaction(txn) = &ext.kind {
-                    Some(*txn.digest())
-                } else {
-                    None
-                }
-            }
-            SequencedConsensusTransactionKind::System(txn) => Some(*txn.digest()),
+            SequencedConsensusTransactionKind::External(ext) => ext
+                .user_certificates()
+                .iter()
+                .map(|cert| *cert.digest())
+                .collect(),
+            SequencedConsensusTransactionKind::System(txn) => vec![*txn.digest()],
         }
     }
 
     pub fn is_end_of_publish(&self) -> bool {
         match self {
-            SequencedConsensusTransactionKind::External(ext) => {
-                matches!(ext.kind, ConsensusTransactionKind::EndOfPublish(..))
-            }
+            SequencedConsensusTransactionKind::External(ext) => ext.is_end_of_publish(),
+            SequencedConsensusTransactionKind::System(_) => false,
+        }
+    }
+
+    pub fn is_soft_bundle(&self) -> bool {
+        match self {
+            SequencedConsensusTransactionKind::External(ext) => ext.is_soft_bundle(),
             SequencedConsensusTransactionKind::System(_) => false,
         }
     }
@@ -681,23 +1130,33 @@ impl SequencedConsensusTransaction {
     }
 
     pub fn is_end_of_publish(&self) -> bool {
-        if let SequencedConsensusTransactionKind::External(ref transaction) = self.transaction {
-            matches!(transaction.kind, ConsensusTransactionKind::EndOfPublish(..))
-        } else {
-            false
+        self.transaction.is_end_of_publish()
+    }
+
+    pub fn is_soft_bundle(&self) -> bool {
+        self.transaction.is_soft_bundle()
+    }
+
+    /// Whether the transaction carries user certificates, on its own or as a soft bundle.
+    pub fn is_user_transaction(&self) -> bool {
+        match &self.transaction {
+            SequencedConsensusTransactionKind::External(ext) => !ext.user_certificates().is_empty(),
+            SequencedConsensusTransactionKind::System(_) => false,
         }
     }
 
-    pub fn as_shared_object_txn(&self) -> Option<&SenderSignedData> {
+    pub fn as_shared_object_txns(&self) -> Vec<&SenderSignedData> {
         match &self.transaction {
-            SequencedConsensusTransactionKind::External(ConsensusTransaction {
-                kind: ConsensusTransactionKind::UserTransaction(certificate),
-                ..
-            }) if certificate.contains_shared_object() => Some(certificate.data()),
+            SequencedConsensusTransactionKind::External(ext) => ext
+                .user_certificates()
+                .iter()
+                .filter(|certificate| certificate.contains_shared_object())
+                .map(|certificate| certificate.data())
+                .collect(),
             SequencedConsensusTransactionKind::System(txn) if txn.contains_shared_object() => {
-                Some(txn.data())
+                vec![txn.data()]
             }
-            _ => None,
+            _ => vec![],
         }
     }
 }
@@ -730,6 +1189,7 @@ mod tests {
     use crate::authority::test_authority_builder::TestAuthorityBuilder;
     use crate::checkpoints::CheckpointServiceNoop;
     use crate::consensus_adapter::consensus_tests::{test_certificates, test_gas_objects};
+    use crate::consensus_types::test_consensus_output::TestConsensusOutput;
     use crate::post_consensus_tx_reorder::PostConsensusTxReorder;
     use narwhal_config::AuthorityIdentifier;
     use narwhal_test_utils::latest_protocol_version;
@@ -739,7 +1199,9 @@ mod tests {
     use prometheus::Registry;
     use shared_crypto::intent::Intent;
     use std::collections::BTreeSet;
-    use sui_protocol_config::{ConsensusTransactionOrdering, SupportedProtocolVersions};
+    use sui_protocol_config::{
+        ConsensusTransactionOrdering, ProtocolConfig, SupportedProtocolVersions,
+    };
     use sui_types::base_types::{random_object_ref, AuthorityName, SuiAddress};
     use sui_types::committee::Committee;
     use sui_types::messages_consensus::{
@@ -786,6 +1248,7 @@ mod tests {
             committee.clone(),
             metrics,
             Arc::new(throughput_calculator),
+            &OverloadThresholdConfig::default(),
         );
 
         // AND
@@ -845,7 +1308,7 @@ mod tests {
         // AND capturing the consensus stats
         let num_certificates = certificates.len();
         let num_transactions = transactions.len();
-        let last_consensus_stats_1 = consensus_handler.last_consensus_stats.clone();
+        let last_consensus_stats_1 = consensus_handler.sequencer.last_consensus_stats.clone();
         assert_eq!(
             last_consensus_stats_1.index.transaction_index,
             num_transactions as u64
@@ -868,11 +1331,391 @@ mod tests {
             consensus_handler
                 .handle_consensus_output(consensus_output.clone())
                 .await;
-            let last_consensus_stats_2 = consensus_handler.last_consensus_stats.clone();
+            let last_consensus_stats_2 = consensus_handler.sequencer.last_consensus_stats.clone();
             assert_eq!(last_consensus_stats_1, last_consensus_stats_2);
         }
     }
 
+    #[tokio::test]
+    async fn test_consensus_handler_with_test_output() {
+        let mut objects = test_gas_objects();
+        objects.push(Object::shared_for_testing());
+        let network_config =
+            sui_swarm_config::network_config_builder::ConfigBuilder::new_with_temp_dir()
+                .with_objects(objects)
+                .build();
+        let state = TestAuthorityBuilder::new()
+            .with_network_config(&network_config)
+            .build()
+            .await;
+        let epoch_store = state.epoch_store_for_testing().clone();
+        let committee = epoch_store.epoch_start_state().get_narwhal_committee();
+        let metrics = Arc::new(AuthorityMetrics::new(&Registry::new()));
+        let throughput_calculator = ConsensusThroughputCalculator::new(None, metrics.clone());
+        let mut consensus_handler = ConsensusHandler::new(
+            epoch_store,
+            Arc::new(CheckpointServiceNoop {}),
+            state.transaction_manager().clone(),
+            state.db(),
+            Arc::new(ArcSwap::default()),
+            committee,
+            metrics.clone(),
+            Arc::new(throughput_calculator),
+            &OverloadThresholdConfig::default(),
+        );
+        consensus_handler.set_malformed_transaction_policy(MalformedTransactionPolicy::Reject);
+
+        let transactions: Vec<_> = test_certificates(&state)
+            .await
+            .into_iter()
+            .map(|certificate| {
+                ConsensusTransaction::new_certificate_message(&state.name, certificate)
+            })
+            .collect();
+        let first = TestConsensusOutput::builder()
+            .with_sub_dag_index(10)
+            .with_leader(0, 5)
+            .with_transactions(0, 4, transactions.clone())
+            .with_serialized_transactions(0, 4, vec![vec![0xff]])
+            .build();
+        consensus_handler
+            .handle_consensus_output_internal(first.clone())
+            .await;
+
+        // the prologue and every well-formed transaction are sequenced
+        let last_consensus_stats = consensus_handler.sequencer.last_consensus_stats.clone();
+        assert_eq!(
+            last_consensus_stats.index.transaction_index,
+            transactions.len() as u64
+        );
+        assert_eq!(
+            last_consensus_stats.stats.get_user_transactions(0),
+            transactions.len() as u64
+        );
+        assert_eq!(
+            metrics
+                .consensus_malformed_transactions
+                .with_label_values(&["0"])
+                .get(),
+            1
+        );
+
+        // the next commit includes the same transactions again, as can happen during leader
+        // failover, and they are skipped
+        let second = TestConsensusOutput::builder()
+            .with_sub_dag_index(11)
+            .with_leader(0, 7)
+            .with_previous(&first)
+            .with_transactions(0, 6, transactions.clone())
+            .build();
+        consensus_handler
+            .handle_consensus_output_internal(second)
+            .await;
+        assert_eq!(
+            metrics.skipped_consensus_txns_cache_hit.get(),
+            transactions.len() as u64
+        );
+    }
+
+    /// A consensus handler whose scheduled transactions are captured instead of executed, with
+    /// soft bundles enabled or not, along with the test certificates it can sequence.
+    async fn soft_bundle_test_handler(
+        soft_bundle: bool,
+    ) -> (
+        Arc<AuthorityState>,
+        ConsensusHandler<Arc<AuthorityStore>, CheckpointServiceNoop>,
+        tokio::sync::mpsc::Receiver<Vec<VerifiedExecutableTransaction>>,
+        Vec<CertifiedTransaction>,
+    ) {
+        let mut objects = test_gas_objects();
+        objects.push(Object::shared_for_testing());
+        let network_config =
+            sui_swarm_config::network_config_builder::ConfigBuilder::new_with_temp_dir()
+                .with_objects(objects)
+                .build();
+        let mut protocol_config = ProtocolConfig::get_for_max_version_UNSAFE();
+        protocol_config.set_soft_bundle_for_testing(soft_bundle);
+        let state = TestAuthorityBuilder::new()
+            .with_network_config(&network_config)
+            .with_protocol_config(protocol_config)
+            .build()
+            .await;
+        let epoch_store = state.epoch_store_for_testing().clone();
+        let committee = epoch_store.epoch_start_state().get_narwhal_committee();
+        let metrics = Arc::new(AuthorityMetrics::new(&Registry::new()));
+        let throughput_calculator = ConsensusThroughputCalculator::new(None, metrics.clone());
+        let mut consensus_handler = ConsensusHandler::new(
+            epoch_store,
+            Arc::new(CheckpointServiceNoop {}),
+            state.transaction_manager().clone(),
+            state.db(),
+            Arc::new(ArcSwap::default()),
+            committee,
+            metrics,
+            Arc::new(throughput_calculator),
+            &OverloadThresholdConfig::default(),
+        );
+        let (sender, receiver) = tokio::sync::mpsc::channel(16);
+        consensus_handler.transaction_scheduler = AsyncTransactionScheduler { sender };
+        let certificates = test_certificates(&state).await;
+        (state, consensus_handler, receiver, certificates)
+    }
+
+    /// The user transactions scheduled by the last commit.
+    fn scheduled_user_transactions(
+        receiver: &mut tokio::sync::mpsc::Receiver<Vec<VerifiedExecutableTransaction>>,
+    ) -> Vec<TransactionDigest> {
+        receiver
+            .try_recv()
+            .expect("Every commit schedules its transactions")
+            .iter()
+            .filter(|transaction| !transaction.is_system_tx())
+            .map(|transaction| *transaction.digest())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_soft_bundle_is_sequenced_adjacently() {
+        let (state, mut consensus_handler, mut receiver, certificates) =
+            soft_bundle_test_handler(true).await;
+        let epoch_store = state.epoch_store_for_testing().clone();
+
+        let output = TestConsensusOutput::builder()
+            .with_leader(0, 5)
+            .with_transactions(
+                0,
+                4,
+                vec![
+                    ConsensusTransaction::new_certificate_message(
+                        &state.name,
+                        certificates[0].clone(),
+                    ),
+                    ConsensusTransaction::new_soft_bundle_message(
+                        &state.name,
+                        vec![certificates[1].clone(), certificates[2].clone()],
+                    ),
+                    ConsensusTransaction::new_certificate_message(
+                        &state.name,
+                        certificates[3].clone(),
+                    ),
+                ],
+            )
+            .build();
+        consensus_handler
+            .handle_consensus_output_internal(output)
+            .await;
+
+        let expected: Vec<_> = certificates.iter().map(|c| *c.digest()).collect();
+        assert_eq!(scheduled_user_transactions(&mut receiver), expected);
+        for certificate in &certificates {
+            assert!(epoch_store
+                .is_tx_cert_consensus_message_processed(certificate)
+                .unwrap());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_soft_bundle_rejected_if_already_sequenced() {
+        let (state, mut consensus_handler, mut receiver, certificates) =
+            soft_bundle_test_handler(true).await;
+        let epoch_store = state.epoch_store_for_testing().clone();
+
+        let first = TestConsensusOutput::builder()
+            .with_leader(0, 5)
+            .with_transactions(
+                0,
+                4,
+                vec![ConsensusTransaction::new_certificate_message(
+                    &state.name,
+                    certificates[0].clone(),
+                )],
+            )
+            .build();
+        consensus_handler
+            .handle_consensus_output_internal(first.clone())
+            .await;
+        assert_eq!(
+            scheduled_user_transactions(&mut receiver),
+            vec![*certificates[0].digest()]
+        );
+
+        // The bundle is rejected as a whole, including the transaction that was not sequenced
+        // yet.
+        let second = TestConsensusOutput::builder()
+            .with_sub_dag_index(2)
+            .with_leader(0, 7)
+            .with_previous(&first)
+            .with_transactions(
+                0,
+                6,
+                vec![ConsensusTransaction::new_soft_bundle_message(
+                    &state.name,
+                    vec![certificates[0].clone(), certificates[1].clone()],
+                )],
+            )
+            .build();
+        consensus_handler
+            .handle_consensus_output_internal(second)
+            .await;
+        assert!(scheduled_user_transactions(&mut receiver).is_empty());
+        assert!(!epoch_store
+            .is_tx_cert_consensus_message_processed(&certificates[1])
+            .unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_soft_bundle_and_certificate_in_one_commit() {
+        let (state, mut consensus_handler, mut receiver, certificates) =
+            soft_bundle_test_handler(true).await;
+        let certificate = |i: usize| {
+            ConsensusTransaction::new_certificate_message(&state.name, certificates[i].clone())
+        };
+        let bundle = |i: usize, j: usize| {
+            ConsensusTransaction::new_soft_bundle_message(
+                &state.name,
+                vec![certificates[i].clone(), certificates[j].clone()],
+            )
+        };
+
+        // Sequenced on its own first, so the bundle that comes second is rejected.
+        let first = TestConsensusOutput::builder()
+            .with_leader(0, 5)
+            .with_transactions(0, 4, vec![certificate(0), bundle(0, 1)])
+            .build();
+        consensus_handler
+            .handle_consensus_output_internal(first.clone())
+            .await;
+        assert_eq!(
+            scheduled_user_transactions(&mut receiver),
+            vec![*certificates[0].digest()]
+        );
+
+        // Sequenced in a bundle first, so the certificate that comes second is ignored.
+        let second = TestConsensusOutput::builder()
+            .with_sub_dag_index(2)
+            .with_leader(0, 7)
+            .with_previous(&first)
+            .with_transactions(0, 6, vec![bundle(2, 3), certificate(3)])
+            .build();
+        consensus_handler
+            .handle_consensus_output_internal(second)
+            .await;
+        assert_eq!(
+            scheduled_user_transactions(&mut receiver),
+            vec![*certificates[2].digest(), *certificates[3].digest()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_soft_bundle_is_deferred_as_a_whole() {
+        let (state, mut consensus_handler, mut receiver, certificates) =
+            soft_bundle_test_handler(true).await;
+        let epoch_store = state.epoch_store_for_testing().clone();
+
+        // Only the second transaction of the bundle has to be deferred.
+        epoch_store.defer_transaction_for_testing(*certificates[1].digest(), 7);
+        let first = TestConsensusOutput::builder()
+            .with_leader(0, 5)
+            .with_transactions(
+                0,
+                4,
+                vec![
+                    ConsensusTransaction::new_soft_bundle_message(
+                        &state.name,
+                        vec![certificates[0].clone(), certificates[1].clone()],
+                    ),
+                    ConsensusTransaction::new_certificate_message(
+                        &state.name,
+                        certificates[2].clone(),
+                    ),
+                ],
+            )
+            .build();
+        consensus_handler
+            .handle_consensus_output_internal(first.clone())
+            .await;
+        assert_eq!(
+            scheduled_user_transactions(&mut receiver),
+            vec![*certificates[2].digest()]
+        );
+        assert!(!epoch_store
+            .is_tx_cert_consensus_message_processed(&certificates[0])
+            .unwrap());
+
+        let second = TestConsensusOutput::builder()
+            .with_sub_dag_index(2)
+            .with_leader(0, 7)
+            .with_previous(&first)
+            .with_transactions(
+                0,
+                6,
+                vec![ConsensusTransaction::new_certificate_message(
+                    &state.name,
+                    certificates[3].clone(),
+                )],
+            )
+            .build();
+        consensus_handler
+            .handle_consensus_output_internal(second)
+            .await;
+        let scheduled = scheduled_user_transactions(&mut receiver);
+        assert_eq!(scheduled.len(), 3);
+        let position = scheduled
+            .iter()
+            .position(|digest| digest == certificates[0].digest())
+            .unwrap();
+        assert_eq!(scheduled[position + 1], *certificates[1].digest());
+        assert!(scheduled.contains(certificates[3].digest()));
+    }
+
+    #[tokio::test]
+    async fn test_soft_bundle_rejected_when_disabled() {
+        let (state, mut consensus_handler, mut receiver, certificates) =
+            soft_bundle_test_handler(false).await;
+        let epoch_store = state.epoch_store_for_testing().clone();
+
+        let first = TestConsensusOutput::builder()
+            .with_leader(0, 5)
+            .with_transactions(
+                0,
+                4,
+                vec![ConsensusTransaction::new_soft_bundle_message(
+                    &state.name,
+                    vec![certificates[0].clone(), certificates[1].clone()],
+                )],
+            )
+            .build();
+        consensus_handler
+            .handle_consensus_output_internal(first.clone())
+            .await;
+        assert!(scheduled_user_transactions(&mut receiver).is_empty());
+        assert!(!epoch_store
+            .is_tx_cert_consensus_message_processed(&certificates[0])
+            .unwrap());
+
+        // The transactions of the rejected bundle can still be sequenced on their own.
+        let second = TestConsensusOutput::builder()
+            .with_sub_dag_index(2)
+            .with_leader(0, 7)
+            .with_previous(&first)
+            .with_transactions(
+                0,
+                6,
+                vec![ConsensusTransaction::new_certificate_message(
+                    &state.name,
+                    certificates[0].clone(),
+                )],
+            )
+            .build();
+        consensus_handler
+            .handle_consensus_output_internal(second)
+            .await;
+        assert_eq!(
+            scheduled_user_transactions(&mut receiver),
+            vec![*certificates[0].digest()]
+        );
+    }
+
     #[test]
     pub fn test_update_index_and_hash() {
         let index0 = ExecutionIndices {
@@ -903,6 +1746,82 @@ mod tests {
         assert!(update_index_and_hash(&mut last_seen, index2, tx));
     }
 
+    #[test]
+    fn test_last_commit_digest_is_persisted_with_stats() {
+        let mut sequencer = ConsensusCommitSequencer::new(
+            0,
+            0,
+            ExecutionIndicesWithStats::default(),
+            ConsensusDedupWindow::new(0),
+            None,
+        );
+        assert_eq!(sequencer.last_commit_digest(), None);
+
+        let digest = ConsensusCommitDigest::new([7; 32]);
+        sequencer
+            .last_consensus_stats
+            .stats
+            .set_last_commit_digest(digest.digest);
+
+        // The digest is recovered along with the stats after a restart.
+        let bytes = bcs::to_bytes(&sequencer.last_consensus_stats).unwrap();
+        let recovered: ExecutionIndicesWithStats = bcs::from_bytes(&bytes).unwrap();
+        let sequencer =
+            ConsensusCommitSequencer::new(0, 0, recovered, ConsensusDedupWindow::new(0), None);
+        assert_eq!(sequencer.last_commit_digest(), Some(digest));
+    }
+
+    #[test]
+    fn test_consensus_dedup_window() {
+        let tx1 = SequencedConsensusTransactionKey::System(TransactionDigest::random());
+        let tx2 = SequencedConsensusTransactionKey::System(TransactionDigest::random());
+        let mut window = ConsensusDedupWindow::new(2);
+
+        window.start_commit(1);
+        assert!(window.insert(tx1.clone()));
+        assert!(!window.insert(tx1.clone()));
+
+        // Still within the window of commit 1.
+        window.start_commit(2);
+        assert!(!window.insert(tx1.clone()));
+        assert!(window.insert(tx2.clone()));
+
+        // Commit 1 falls out of the window, but commit 2 does not.
+        window.start_commit(3);
+        assert!(window.insert(tx1.clone()));
+        assert!(!window.insert(tx2.clone()));
+
+        // Commits can be skipped, e.g. after a restart, evicting everything older.
+        window.start_commit(10);
+        assert!(window.insert(tx1));
+        assert!(window.insert(tx2));
+    }
+
+    #[test]
+    fn test_authority_submission_quota() {
+        // Recovered from commit 2, with 60 bytes accepted from authority 0.
+        let mut quota = AuthoritySubmissionQuota::new(2, 100, 2, vec![(2, vec![60, 0])]);
+
+        quota.start_commit(3);
+        assert!(!quota.try_consume(0, 50));
+        assert!(quota.try_consume(0, 40));
+        assert!(quota.try_consume(1, 100));
+        assert!(!quota.try_consume(1, 1));
+        assert_eq!(quota.current_commit_bytes(), vec![40, 100]);
+
+        // Commit 2 falls out of the window, but commit 3 does not.
+        quota.start_commit(4);
+        assert!(quota.try_consume(0, 60));
+        assert!(!quota.try_consume(0, 1));
+        assert!(!quota.try_consume(1, 1));
+
+        // Commits 3 and 4 both fall out of the window.
+        quota.start_commit(6);
+        assert!(quota.try_consume(0, 100));
+        assert!(quota.try_consume(1, 100));
+        assert_eq!(quota.current_commit_bytes(), vec![100, 100]);
+    }
+
     #[test]
     fn test_order_by_gas_price() {
         let mut v = vec![cap_txn(10), user_txn(42), user_txn(100), cap_txn(1)];
@@ -963,6 +1882,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_order_by_gas_price_then_digest() {
+        let users: Vec<_> = (0..4).map(|_| user_txn(42)).collect();
+        let mut expected_digests: Vec<_> = users.iter().map(user_digest).collect();
+        expected_digests.sort();
+
+        // Transactions with the same gas price are ordered by digest, regardless of arrival order.
+        for arrival in [users.clone(), users.iter().rev().cloned().collect()] {
+            let mut v = vec![cap_txn(10), user_txn(100)];
+            v.extend(arrival);
+            v.push(cap_txn(1));
+            PostConsensusTxReorder::reorder(
+                &mut v,
+                ConsensusTransactionOrdering::ByGasPriceThenDigest,
+            );
+            assert_eq!(
+                extract(v[..3].to_vec()),
+                vec![
+                    "cap(10)".to_string(),
+                    "cap(1)".to_string(),
+                    "user(100)".to_string(),
+                ]
+            );
+            assert_eq!(
+                v[3..].iter().map(user_digest).collect::<Vec<_>>(),
+                expected_digests
+            );
+        }
+    }
+
+    fn user_digest(t: &VerifiedSequencedConsensusTransaction) -> TransactionDigest {
+        match &t.0.transaction {
+            SequencedConsensusTransactionKind::External(ext) => {
+                *ext.user_certificates()[0].digest()
+            }
+            SequencedConsensusTransactionKind::System(_) => unreachable!(),
+        }
+    }
+
     fn extract(v: Vec<VerifiedSequencedConsensusTransaction>) -> Vec<String> {
         v.into_iter().map(extract_one).collect()
     }
@@ -0,0 +1,499 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Replays consensus commits through the consensus output API, sequencing their transactions
+//! with the consensus handler's [ConsensusCommitSequencer], but without executing them or writing
+//! to any store. This lets operators re-run commits read from a validator's consensus store
+//! offline, to reproduce ordering bugs or measure how quickly commits are processed.
+
+use crate::authority::authority_per_epoch_store::{ConsensusStats, ExecutionIndicesWithStats};
+use crate::consensus_handler::{
+    AuthoritySubmissionQuota, ConsensusCommitSequencer, ConsensusDedupWindow,
+    SequencedConsensusTransactionKey, SequencedConsensusTransactionKind,
+};
+use crate::consensus_types::consensus_output_api::{
+    ConsensusCommitDigest, ConsensusOutputAPI, MalformedTransactionPolicy, NarwhalConsensusOutput,
+};
+use anyhow::anyhow;
+use narwhal_config::Committee;
+use narwhal_executor::ExecutionIndices;
+use narwhal_node::NodeStorage;
+use narwhal_types::{CertificateAPI, CommittedSubDag, ConsensusOutput, HeaderAPI};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use sui_types::base_types::EpochId;
+
+/// Reads the commits with sub-dag indices in `start..=end` from a Narwhal consensus store, along
+/// with the batches of their certificates, as they would have been output by consensus.
+pub fn read_narwhal_commits(
+    storage: &NodeStorage,
+    start: u64,
+    end: u64,
+) -> anyhow::Result<Vec<ConsensusOutput>> {
+    let mut outputs = vec![];
+    for commit in storage
+        .consensus_store
+        .read_committed_sub_dags_from(&start)?
+    {
+        if commit.sub_dag_index() > end {
+            break;
+        }
+
+        let certificates = storage
+            .certificate_store
+            .read_all(commit.certificates())?
+            .into_iter()
+            .zip(commit.certificates())
+            .map(|(certificate, digest)| {
+                certificate.ok_or_else(|| anyhow!("Certificate {digest} not found"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        let leader = storage
+            .certificate_store
+            .read(commit.leader())?
+            .ok_or_else(|| anyhow!("Leader certificate {} not found", commit.leader()))?;
+        let sub_dag = CommittedSubDag::from_commit(commit, certificates, leader);
+
+        // Like the Narwhal executor, a commit without any batches has no batches per certificate.
+        let mut batches = vec![];
+        if sub_dag.num_batches() > 0 {
+            for certificate in &sub_dag.certificates {
+                let digests: Vec<_> = certificate.header().payload().keys().copied().collect();
+                let certificate_batches = storage
+                    .batch_store
+                    .multi_get(&digests)?
+                    .into_iter()
+                    .zip(&digests)
+                    .map(|(batch, digest)| batch.ok_or_else(|| anyhow!("Batch {digest} not found")))
+                    .collect::<anyhow::Result<Vec<_>>>()?;
+                batches.push(certificate_batches);
+            }
+        }
+
+        outputs.push(ConsensusOutput {
+            sub_dag: Arc::new(sub_dag),
+            batches,
+        });
+    }
+    Ok(outputs)
+}
+
+/// Sequences the transactions of consensus commits the way the consensus handler does: commits
+/// for rounds that were already committed are ignored, commit timestamps are validated, a
+/// consensus commit prologue is sequenced at the start of each commit, transactions that were
+/// already sequenced are skipped, and authorities over their submission quota are throttled.
+///
+/// Authenticator state updates are not replayed, as they depend on the JWKs that were active in
+/// the epoch store at the time. Neither are the decisions made by the epoch store when the
+/// sequenced transactions are processed, such as rejecting soft bundles.
+pub struct ConsensusReplay {
+    committee: Committee,
+    sequencer: ConsensusCommitSequencer,
+    /// The transactions sequenced so far, standing in for the ones the epoch store marks as
+    /// processed
+    processed: HashSet<SequencedConsensusTransactionKey>,
+}
+
+/// The result of replaying a single commit.
+#[derive(Clone, Debug)]
+pub struct ReplayedCommit {
+    pub sub_dag_index: u64,
+    pub leader_round: u64,
+    pub commit_timestamp_ms: u64,
+    pub commit_digest: ConsensusCommitDigest,
+    pub previous_commit_digest: Option<ConsensusCommitDigest>,
+    /// The transactions sequenced by the commit, in order, with their consensus indices.
+    pub transactions: Vec<(ExecutionIndices, SequencedConsensusTransactionKey)>,
+    /// The number of user transactions sequenced, counting each transaction of a soft bundle.
+    pub user_transactions: usize,
+    /// The number of transactions skipped because they were already sequenced.
+    pub skipped: usize,
+    /// The number of transactions dropped because their authority was over its submission quota,
+    /// or because they were invalid epoch boundary markers.
+    pub dropped: usize,
+    /// The number of transactions rejected because they failed to deserialize.
+    pub malformed: usize,
+}
+
+/// Totals over a sequence of replayed commits.
+#[derive(Clone, Debug, Default)]
+pub struct ReplaySummary {
+    pub commits: usize,
+    /// Commits ignored because their round was already committed.
+    pub ignored_commits: usize,
+    pub transactions: usize,
+    pub user_transactions: usize,
+    pub skipped: usize,
+    pub dropped: usize,
+    pub malformed: usize,
+    pub elapsed: Duration,
+}
+
+impl ConsensusReplay {
+    /// Creates a replay of commits made by `committee` in `epoch`, deduplicating transactions over
+    /// the last `dedup_window_commits` commits, as recorded in the epoch store of the validator.
+    pub fn new(
+        epoch: EpochId,
+        epoch_start_timestamp_ms: u64,
+        committee: Committee,
+        dedup_window_commits: u64,
+    ) -> Self {
+        let last_consensus_stats = ExecutionIndicesWithStats {
+            stats: ConsensusStats::new(committee.size()),
+            ..Default::default()
+        };
+        let mut sequencer = ConsensusCommitSequencer::new(
+            epoch,
+            epoch_start_timestamp_ms,
+            last_consensus_stats,
+            ConsensusDedupWindow::new(dedup_window_commits),
+            None,
+        );
+        sequencer.malformed_transaction_policy = MalformedTransactionPolicy::Reject;
+        Self {
+            committee,
+            sequencer,
+            processed: HashSet::new(),
+        }
+    }
+
+    /// Limits the bytes of user transactions sequenced from each authority over the last
+    /// `window_commits` commits, as the protocol config of the epoch does.
+    pub fn with_submission_quota(mut self, window_commits: u64, max_bytes: u64) -> Self {
+        self.sequencer.submission_quota = Some(AuthoritySubmissionQuota::new(
+            window_commits,
+            max_bytes,
+            self.committee.size(),
+            vec![],
+        ));
+        self
+    }
+
+    /// Clamps each commit timestamp to the timestamp of the previous commit, as the protocol
+    /// config of the epoch does if it enables monotonic commit timestamps.
+    pub fn with_monotonic_commit_timestamps(mut self) -> Self {
+        self.sequencer.monotonic_commit_timestamps = true;
+        self
+    }
+
+    /// Replays a commit output by Narwhal, returning `None` if its round was already committed.
+    pub fn replay_narwhal(&mut self, output: ConsensusOutput) -> Option<ReplayedCommit> {
+        let output = NarwhalConsensusOutput {
+            output,
+            previous_commit_digest: self.sequencer.last_commit_digest(),
+        };
+        self.replay(&output)
+    }
+
+    /// Replays every commit in `outputs`, in order, and returns how many transactions they
+    /// sequenced and how long that took. `on_commit` is called with each commit that is replayed.
+    pub fn replay_all_narwhal(
+        &mut self,
+        outputs: impl IntoIterator<Item = ConsensusOutput>,
+        mut on_commit: impl FnMut(&ReplayedCommit),
+    ) -> ReplaySummary {
+        let mut summary = ReplaySummary::default();
+        let start = Instant::now();
+        for output in outputs {
+            summary.commits += 1;
+            let Some(commit) = self.replay_narwhal(output) else {
+                summary.ignored_commits += 1;
+                continue;
+            };
+            summary.transactions += commit.transactions.len();
+            summary.user_transactions += commit.user_transactions;
+            summary.skipped += commit.skipped;
+            summary.dropped += commit.dropped;
+            summary.malformed += commit.malformed;
+            on_commit(&commit);
+        }
+        summary.elapsed = start.elapsed();
+        summary
+    }
+
+    fn replay(&mut self, output: &impl ConsensusOutputAPI) -> Option<ReplayedCommit> {
+        if self.sequencer.is_committed(output.leader_round()) {
+            return None;
+        }
+        let processed = &self.processed;
+        let commit = self
+            .sequencer
+            .sequence(output, &self.committee, vec![], |key| {
+                processed.contains(key)
+            });
+        self.processed.extend(
+            commit
+                .transactions
+                .iter()
+                .map(|transaction| transaction.key()),
+        );
+        Some(ReplayedCommit {
+            sub_dag_index: commit.sub_dag_index,
+            leader_round: commit.round,
+            commit_timestamp_ms: commit.timestamp_ms,
+            commit_digest: commit.commit_digest,
+            previous_commit_digest: commit.previous_commit_digest,
+            transactions: commit
+                .transactions
+                .iter()
+                .map(|transaction| (transaction.consensus_index, transaction.key()))
+                .collect(),
+            user_transactions: commit
+                .transactions
+                .iter()
+                .map(|transaction| match &transaction.transaction {
+                    SequencedConsensusTransactionKind::External(transaction) => {
+                        transaction.user_certificates().len()
+                    }
+                    SequencedConsensusTransactionKind::System(_) => 0,
+                })
+                .sum(),
+            skipped: commit.already_processed + commit.duplicates,
+            dropped: commit.over_quota.len() + commit.epoch_boundary_markers.invalid.len(),
+            malformed: commit.malformed.len(),
+        })
+    }
+}
+
+impl ReplaySummary {
+    /// The number of transactions sequenced per second of replay.
+    pub fn transactions_per_second(&self) -> f64 {
+        self.transactions as f64 / self.elapsed.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus_handler::{
+        ScriptedCommitTimestamps, DEFAULT_CONSENSUS_DEDUP_WINDOW_COMMITS,
+    };
+    use crate::consensus_types::committee_api::CommitteeAPI;
+    use narwhal_config::AuthorityIdentifier;
+    use narwhal_test_utils::{latest_protocol_version, CommitteeFixture};
+    use narwhal_types::{Batch, Certificate, Header, HeaderV2Builder, ReputationScores};
+    use shared_crypto::intent::Intent;
+    use std::collections::{BTreeMap, BTreeSet};
+    use sui_types::base_types::{random_object_ref, AuthorityName, SuiAddress};
+    use sui_types::messages_consensus::{ConsensusTransaction, ConsensusTransactionKey};
+    use sui_types::transaction::{CertifiedTransaction, SenderSignedData, TransactionData};
+
+    fn narwhal_output(
+        committee: &Committee,
+        sub_dag_index: u64,
+        round: u64,
+        transactions: Vec<ConsensusTransaction>,
+    ) -> ConsensusOutput {
+        let protocol_config = latest_protocol_version();
+        let batch = Batch::new(
+            transactions
+                .iter()
+                .map(|transaction| bcs::to_bytes(transaction).unwrap())
+                .collect(),
+            &protocol_config,
+        );
+        let header = HeaderV2Builder::default()
+            .author(AuthorityIdentifier(0))
+            .round(round)
+            .epoch(0)
+            .created_at(round * 1_000)
+            .parents(BTreeSet::new())
+            .with_payload_batch(batch.clone(), 0, 0)
+            .build()
+            .unwrap();
+        let certificate =
+            Certificate::new_unsigned(&protocol_config, committee, Header::V2(header), vec![])
+                .unwrap();
+        ConsensusOutput {
+            sub_dag: Arc::new(CommittedSubDag::new(
+                vec![certificate.clone()],
+                certificate,
+                sub_dag_index,
+                ReputationScores::default(),
+                None,
+            )),
+            batches: vec![vec![batch]],
+        }
+    }
+
+    fn user_certificate() -> CertifiedTransaction {
+        let (committee, keypairs) = sui_types::committee::Committee::new_simple_test_committee();
+        let data = SenderSignedData::new(
+            TransactionData::new_transfer(
+                SuiAddress::default(),
+                random_object_ref(),
+                SuiAddress::default(),
+                random_object_ref(),
+                1000,
+                1,
+            ),
+            Intent::sui_transaction(),
+            vec![],
+        );
+        CertifiedTransaction::new_from_keypairs_for_testing(data, &keypairs, &committee)
+    }
+
+    #[test]
+    fn test_replay_sequences_like_handler() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+        let end_of_publish = ConsensusTransaction::new_end_of_publish(
+            committee.authority_pubkey_by_index(0).unwrap(),
+        );
+
+        let first = narwhal_output(&committee, 1, 2, vec![end_of_publish.clone()]);
+        let second = narwhal_output(&committee, 2, 4, vec![end_of_publish]);
+
+        let mut replay =
+            ConsensusReplay::new(0, 0, committee, DEFAULT_CONSENSUS_DEDUP_WINDOW_COMMITS);
+        let mut commits = vec![];
+        let summary = replay.replay_all_narwhal(vec![first.clone(), first, second], |commit| {
+            commits.push(commit.clone())
+        });
+
+        // the second copy of the first commit is ignored, as its round was already committed
+        assert_eq!(summary.commits, 3);
+        assert_eq!(summary.ignored_commits, 1);
+        assert_eq!(commits.len(), 2);
+
+        // the prologue and the transaction are sequenced by the first commit
+        assert_eq!(commits[0].transactions.len(), 2);
+        assert_eq!(commits[0].previous_commit_digest, None);
+
+        // the transaction is skipped by the second commit, as it was already sequenced
+        assert_eq!(commits[1].transactions.len(), 1);
+        assert_eq!(commits[1].skipped, 1);
+        assert_eq!(
+            commits[1].previous_commit_digest,
+            Some(commits[0].commit_digest)
+        );
+        assert!(matches!(
+            commits[1].transactions[0].1,
+            SequencedConsensusTransactionKey::System(_)
+        ));
+    }
+
+    #[test]
+    fn test_replay_soft_bundles_and_submission_quota() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+        let name = AuthorityName::ZERO;
+        let bundle = ConsensusTransaction::new_soft_bundle_message(
+            &name,
+            vec![user_certificate(), user_certificate()],
+        );
+        let bundle_bytes = bcs::to_bytes(&bundle).unwrap().len() as u64;
+        let certificate = ConsensusTransaction::new_certificate_message(&name, user_certificate());
+        // An epoch boundary marker included by a different authority than its own.
+        let invalid_end_of_publish = ConsensusTransaction::new_end_of_publish(name);
+
+        let output = narwhal_output(
+            &committee,
+            1,
+            2,
+            vec![bundle, certificate, invalid_end_of_publish],
+        );
+
+        // The quota only leaves room for the bundle, so the certificate after it is dropped.
+        let mut replay =
+            ConsensusReplay::new(0, 0, committee, DEFAULT_CONSENSUS_DEDUP_WINDOW_COMMITS)
+                .with_submission_quota(2, bundle_bytes);
+        let mut commits = vec![];
+        let summary =
+            replay.replay_all_narwhal(vec![output], |commit| commits.push(commit.clone()));
+
+        assert_eq!(commits[0].transactions.len(), 2);
+        assert!(matches!(
+            commits[0].transactions[1].1,
+            SequencedConsensusTransactionKey::External(ConsensusTransactionKey::SoftBundle(_))
+        ));
+        assert_eq!(summary.user_transactions, 2);
+        assert_eq!(summary.dropped, 2);
+        assert_eq!(summary.skipped, 0);
+    }
+
+    #[test]
+    fn test_submission_quota_after_restart() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+        let name = AuthorityName::ZERO;
+        let certificate = ConsensusTransaction::new_certificate_message(&name, user_certificate());
+        let other = ConsensusTransaction::new_certificate_message(&name, user_certificate());
+        let max_bytes = bcs::to_bytes(&certificate).unwrap().len() as u64
+            + bcs::to_bytes(&other).unwrap().len() as u64;
+
+        // The second commit includes the certificate of the first one again. The quota leaves
+        // room for both certificates, but not for the duplicate as well.
+        let first = narwhal_output(&committee, 1, 2, vec![certificate.clone()]);
+        let second = narwhal_output(&committee, 2, 4, vec![certificate, other]);
+        let new_replay = || {
+            ConsensusReplay::new(
+                0,
+                0,
+                committee.clone(),
+                DEFAULT_CONSENSUS_DEDUP_WINDOW_COMMITS,
+            )
+            .with_submission_quota(2, max_bytes)
+        };
+
+        let mut running = new_replay();
+        running.replay_narwhal(first.clone()).unwrap();
+        // A validator restarting after the first commit keeps the state persisted with it, but
+        // loses its dedup window.
+        let mut restarted = new_replay();
+        restarted.replay_narwhal(first).unwrap();
+        restarted.sequencer.dedup_window =
+            ConsensusDedupWindow::new(DEFAULT_CONSENSUS_DEDUP_WINDOW_COMMITS);
+
+        let expected = running.replay_narwhal(second.clone()).unwrap();
+        let actual = restarted.replay_narwhal(second).unwrap();
+        assert_eq!(expected.transactions.len(), 2);
+        assert_eq!(expected.dropped, 0);
+        assert_eq!(actual.transactions, expected.transactions);
+        assert_eq!((actual.skipped, actual.dropped), (1, 0));
+        let submission_bytes = |replay: &ConsensusReplay| {
+            replay
+                .sequencer
+                .submission_quota
+                .as_ref()
+                .unwrap()
+                .current_commit_bytes()
+        };
+        assert_eq!(submission_bytes(&restarted), submission_bytes(&running));
+    }
+
+    #[test]
+    fn test_replay_monotonic_commit_timestamps() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+        let outputs = vec![
+            narwhal_output(&committee, 1, 2, vec![]),
+            narwhal_output(&committee, 2, 4, vec![]),
+        ];
+        // The second commit reports an earlier timestamp than the first.
+        let timestamps = |replay: &mut ConsensusReplay| {
+            replay.sequencer.commit_timestamp_policy = Arc::new(ScriptedCommitTimestamps {
+                timestamps: BTreeMap::from([(1, 2_000), (2, 1_500)]),
+            });
+            let mut timestamps = vec![];
+            replay.replay_all_narwhal(outputs.clone(), |commit| {
+                timestamps.push(commit.commit_timestamp_ms)
+            });
+            timestamps
+        };
+
+        let mut replay = ConsensusReplay::new(
+            0,
+            0,
+            committee.clone(),
+            DEFAULT_CONSENSUS_DEDUP_WINDOW_COMMITS,
+        );
+        assert_eq!(timestamps(&mut replay), vec![2_000, 1_500]);
+
+        let mut replay =
+            ConsensusReplay::new(0, 0, committee, DEFAULT_CONSENSUS_DEDUP_WINDOW_COMMITS)
+                .with_monotonic_commit_timestamps();
+        assert_eq!(timestamps(&mut replay), vec![2_000, 2_000]);
+    }
+}
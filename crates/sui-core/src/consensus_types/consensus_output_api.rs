@@ -1,18 +1,185 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::consensus_types::committee_api::CommitteeAPI;
 use crate::consensus_types::AuthorityIndex;
-use fastcrypto::hash::Hash;
-use narwhal_types::{BatchAPI, CertificateAPI, HeaderAPI};
+use fastcrypto::hash::{Blake2b256, Digest, Hash, HashFunction};
+use mysticeti_core::consensus::linearizer::CommittedSubDag;
+use mysticeti_core::types::BlockReference;
+use narwhal_types::{BatchAPI, BatchDigest, CertificateAPI, ConsensusOutput, HeaderAPI};
+use std::collections::BTreeMap;
 use std::fmt::Display;
-use sui_types::messages_consensus::ConsensusTransaction;
+use sui_types::base_types::AuthorityName;
+use sui_types::messages_consensus::{ConsensusTransaction, ConsensusTransactionKind};
+use tracing::{debug, error, warn};
 
 /// A list of tuples of:
 /// (certificate origin authority index, all transactions corresponding to the certificate).
-/// For each transaction, returns the serialized transaction and the deserialized transaction.
-type ConsensusOutputTransactions<'a> = Vec<(AuthorityIndex, Vec<(&'a [u8], ConsensusTransaction)>)>;
+/// For each transaction, returns the serialized transaction, the deserialized transaction, and
+/// where the transaction was included in the commit.
+pub(crate) type ConsensusOutputTransactions<'a> =
+    Vec<(AuthorityIndex, Vec<ConsensusOutputTransaction<'a>>)>;
+
+/// A transaction in a commit: the serialized transaction, the deserialized transaction, and where
+/// it was included in the commit.
+pub(crate) type ConsensusOutputTransaction<'a> =
+    (&'a [u8], ConsensusTransaction, ConsensusTransactionOrigin);
+
+/// Lazily iterates over the transactions of a commit, as tuples of:
+/// (certificate origin authority index, transactions corresponding to the certificate).
+/// Transactions are only deserialized as they are reached. A transaction that fails to
+/// deserialize, and was rejected by the [MalformedTransactionPolicy], is returned as an error
+/// holding where it was included.
+pub(crate) type ConsensusOutputTransactionsIter<'a> =
+    Box<dyn Iterator<Item = (AuthorityIndex, ConsensusOutputTransactionIter<'a>)> + 'a>;
+
+pub(crate) type ConsensusOutputTransactionIter<'a> = Box<
+    dyn Iterator<Item = Result<ConsensusOutputTransaction<'a>, ConsensusTransactionOrigin>> + 'a,
+>;
+
+/// Where a transaction was included in a commit, so that latency and misbehaviour can be
+/// attributed to a specific certificate or block rather than only to its author.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ConsensusTransactionOrigin {
+    /// Author of the certificate or block that included the transaction.
+    pub authority: AuthorityIndex,
+    /// Round of the certificate or block that included the transaction.
+    pub round: u64,
+    pub source: ConsensusTransactionSource,
+    /// Position of the transaction within its batch (Narwhal) or block (Mysticeti).
+    pub position: usize,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum ConsensusTransactionSource {
+    /// The batch that the transaction was included in, referenced by a Narwhal certificate.
+    NarwhalBatch(BatchDigest),
+    /// The Mysticeti block that the transaction was included in.
+    MysticetiBlock(BlockReference),
+}
+
+/// Decides what happens to a committed transaction that fails to deserialize, which the consensus
+/// engine's own verification should have prevented.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MalformedTransactionPolicy {
+    /// Drops the transaction, and reports where it was included so that the authority that
+    /// proposed it can be identified.
+    Reject,
+    /// Panics, to surface gaps in verification early. The default in debug builds.
+    Panic,
+}
+
+impl Default for MalformedTransactionPolicy {
+    fn default() -> Self {
+        if cfg!(debug_assertions) {
+            Self::Panic
+        } else {
+            Self::Reject
+        }
+    }
+}
+
+/// Deserializes a committed transaction, applying `policy` if it is malformed.
+pub(crate) fn deserialize_transaction(
+    serialized_transaction: &[u8],
+    origin: &ConsensusTransactionOrigin,
+    policy: MalformedTransactionPolicy,
+) -> Option<ConsensusTransaction> {
+    match bcs::from_bytes::<ConsensusTransaction>(serialized_transaction) {
+        Ok(transaction) => Some(transaction),
+        Err(err) => match policy {
+            MalformedTransactionPolicy::Panic => panic!(
+                "Unexpected malformed transaction (failed to deserialize): {}\nOrigin={:?} Transaction={:?}",
+                err, origin, serialized_transaction
+            ),
+            MalformedTransactionPolicy::Reject => {
+                error!(
+                    "Rejecting malformed transaction (failed to deserialize): {}, included at {:?}",
+                    err, origin
+                );
+                None
+            }
+        },
+    }
+}
+
+/// An end of publish message in a commit, which marks that its authority will not submit any more
+/// user transactions in the epoch.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct EndOfPublishMarker {
+    /// The authority that stopped submitting transactions.
+    pub authority: AuthorityName,
+    pub origin: ConsensusTransactionOrigin,
+}
+
+/// The epoch boundary markers found in a commit. Shared by all consensus engines, so that a marker
+/// is accepted or rejected the same way whichever engine committed it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct EpochBoundaryMarkers {
+    /// Markers included by their own authority, in commit order. Later markers from an authority
+    /// that already has one in the commit are not repeated.
+    pub valid: Vec<EndOfPublishMarker>,
+    /// Markers included by a different authority than the one they are for, which must be
+    /// ignored since an authority can only end its own submissions.
+    pub invalid: Vec<EndOfPublishMarker>,
+}
+
+impl EpochBoundaryMarkers {
+    /// Records `transaction` if it is an epoch boundary marker. Returns false if it is an invalid
+    /// marker, which should be dropped.
+    pub fn observe(
+        &mut self,
+        transaction: &ConsensusTransaction,
+        origin: &ConsensusTransactionOrigin,
+        committee: &dyn CommitteeAPI,
+    ) -> bool {
+        let ConsensusTransactionKind::EndOfPublish(authority) = &transaction.kind else {
+            return true;
+        };
+        let marker = EndOfPublishMarker {
+            authority: *authority,
+            origin: origin.clone(),
+        };
+        if committee.authority_pubkey_by_index(origin.authority) != Some(*authority) {
+            warn!(
+                "EndOfPublish authority {} does not match the author of its certificate or block, included at {:?}",
+                authority.concise(),
+                origin
+            );
+            self.invalid.push(marker);
+            return false;
+        }
+        if !self.contains(authority) {
+            self.valid.push(marker);
+        }
+        true
+    }
+
+    /// Returns true if the transaction included at `origin` is an invalid marker, which should be
+    /// dropped.
+    pub fn is_invalid(&self, origin: &ConsensusTransactionOrigin) -> bool {
+        self.invalid.iter().any(|marker| &marker.origin == origin)
+    }
+
+    /// Returns true if the commit holds a valid marker from `authority`.
+    pub fn contains(&self, authority: &AuthorityName) -> bool {
+        self.valid
+            .iter()
+            .any(|marker| &marker.authority == authority)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.valid.is_empty() && self.invalid.is_empty()
+    }
+}
+
+/// Digest identifying a commit, whichever consensus engine produced it.
+pub(crate) type ConsensusCommitDigest = Digest<32>;
 
 pub(crate) trait ConsensusOutputAPI: Display {
+    /// Returns the name of the consensus protocol that produced the output, as used in metrics.
+    fn consensus_protocol(&self) -> &'static str;
+
     fn reputation_score_sorted_desc(&self) -> Option<Vec<(AuthorityIndex, u64)>>;
     fn leader_round(&self) -> u64;
     fn leader_author_index(&self) -> AuthorityIndex;
@@ -23,17 +190,160 @@ pub(crate) trait ConsensusOutputAPI: Display {
     /// Returns a unique global index for each committed sub-dag.
     fn commit_sub_dag_index(&self) -> u64;
 
-    /// Returns all transactions in the commit.
-    fn transactions(&self) -> ConsensusOutputTransactions<'_>;
+    /// Returns an iterator over the transactions in the commit, which deserializes them as they
+    /// are reached, so that large commits don't have to be buffered in memory.
+    fn transactions_iter(
+        &self,
+        policy: MalformedTransactionPolicy,
+    ) -> ConsensusOutputTransactionsIter<'_>;
+
+    /// Returns all transactions in the commit that could be deserialized, and where each of the
+    /// ones that could not be was included, if `policy` did not panic on them.
+    fn transactions(
+        &self,
+        policy: MalformedTransactionPolicy,
+    ) -> (
+        ConsensusOutputTransactions<'_>,
+        Vec<ConsensusTransactionOrigin>,
+    ) {
+        let mut malformed = vec![];
+        let transactions = self
+            .transactions_iter(policy)
+            .map(|(authority, transactions)| {
+                let transactions = transactions
+                    .filter_map(|transaction| {
+                        transaction.map_err(|origin| malformed.push(origin)).ok()
+                    })
+                    .collect();
+                (authority, transactions)
+            })
+            .collect();
+        (transactions, malformed)
+    }
+
+    /// Returns the epoch boundary markers in the commit, validated against `committee`.
+    fn epoch_boundary_markers(
+        &self,
+        committee: &dyn CommitteeAPI,
+        policy: MalformedTransactionPolicy,
+    ) -> EpochBoundaryMarkers {
+        let mut markers = EpochBoundaryMarkers::default();
+        for (_, transactions) in self.transactions_iter(policy) {
+            for (_, transaction, origin) in transactions.flatten() {
+                markers.observe(&transaction, &origin, committee);
+            }
+        }
+        markers
+    }
+
+    /// Returns the digest of the commit.
+    fn commit_digest(&self) -> ConsensusCommitDigest;
+
+    /// Returns the digest of the commit processed before this one, linking commits into a hash
+    /// chain. `None` for the first commit processed since the consensus handler started.
+    fn previous_commit_digest(&self) -> Option<ConsensusCommitDigest>;
+
+    /// Returns the commit timestamp chosen by `policy`, clamped so that it never precedes the
+    /// start of the epoch, or the timestamp of the previous commit (`last_commit_timestamp_ms`).
+    /// This keeps commit timestamps monotonic whichever consensus engine reported them.
+    fn validated_commit_timestamp_ms(
+        &self,
+        policy: &dyn CommitTimestampPolicy,
+        epoch_start_timestamp_ms: u64,
+        last_commit_timestamp_ms: u64,
+    ) -> u64 {
+        let timestamp =
+            policy.commit_timestamp_ms(self.commit_timestamp_ms(), self.commit_sub_dag_index());
+        let timestamp = if timestamp < epoch_start_timestamp_ms {
+            error!(
+                "Unexpected commit timestamp {timestamp} less then epoch start time {epoch_start_timestamp_ms}, author {}, round {}",
+                self.leader_author_index(),
+                self.leader_round(),
+            );
+            epoch_start_timestamp_ms
+        } else {
+            timestamp
+        };
+
+        if timestamp < last_commit_timestamp_ms {
+            debug!(
+                "Commit timestamp {timestamp} less than previous commit timestamp {last_commit_timestamp_ms}, author {}, round {}",
+                self.leader_author_index(),
+                self.leader_round(),
+            );
+            last_commit_timestamp_ms
+        } else {
+            timestamp
+        }
+    }
+}
+
+/// Decides the timestamp of a commit from the one reported by consensus. Validators use
+/// `ConsensusCommitTimestamps`; tests and local networks can inject another policy to simulate
+/// skewed or duplicate timestamps deterministically.
+pub trait CommitTimestampPolicy: Send + Sync {
+    fn commit_timestamp_ms(&self, consensus_timestamp_ms: u64, commit_sub_dag_index: u64) -> u64;
+}
+
+/// Uses the timestamp reported by consensus as-is.
+pub struct ConsensusCommitTimestamps;
+
+/// Shifts every consensus timestamp by a fixed amount, saturating at zero.
+pub struct SkewedCommitTimestamps {
+    pub skew_ms: i64,
+}
+
+/// Assigns fixed timestamps to specific commits, keyed by sub-dag index. Commits without an entry
+/// keep the timestamp reported by consensus.
+pub struct ScriptedCommitTimestamps {
+    pub timestamps: BTreeMap<u64, u64>,
+}
+
+impl CommitTimestampPolicy for ConsensusCommitTimestamps {
+    fn commit_timestamp_ms(&self, consensus_timestamp_ms: u64, _commit_sub_dag_index: u64) -> u64 {
+        consensus_timestamp_ms
+    }
+}
+
+impl CommitTimestampPolicy for SkewedCommitTimestamps {
+    fn commit_timestamp_ms(&self, consensus_timestamp_ms: u64, _commit_sub_dag_index: u64) -> u64 {
+        consensus_timestamp_ms.saturating_add_signed(self.skew_ms)
+    }
+}
+
+impl CommitTimestampPolicy for ScriptedCommitTimestamps {
+    fn commit_timestamp_ms(&self, consensus_timestamp_ms: u64, commit_sub_dag_index: u64) -> u64 {
+        self.timestamps
+            .get(&commit_sub_dag_index)
+            .copied()
+            .unwrap_or(consensus_timestamp_ms)
+    }
+}
+
+/// A sub-dag committed by Narwhal, along with the digest of the commit before it.
+pub(crate) struct NarwhalConsensusOutput {
+    pub output: ConsensusOutput,
+    pub previous_commit_digest: Option<ConsensusCommitDigest>,
 }
 
-impl ConsensusOutputAPI for narwhal_types::ConsensusOutput {
+impl Display for NarwhalConsensusOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.output)
+    }
+}
+
+impl ConsensusOutputAPI for NarwhalConsensusOutput {
+    fn consensus_protocol(&self) -> &'static str {
+        "narwhal"
+    }
+
     fn reputation_score_sorted_desc(&self) -> Option<Vec<(AuthorityIndex, u64)>> {
-        if !self.sub_dag.reputation_score.final_of_schedule {
+        if !self.output.sub_dag.reputation_score.final_of_schedule {
             return None;
         }
         Some(
-            self.sub_dag
+            self.output
+                .sub_dag
                 .reputation_score
                 .authorities_by_score_desc()
                 .into_iter()
@@ -43,99 +353,673 @@ impl ConsensusOutputAPI for narwhal_types::ConsensusOutput {
     }
 
     fn leader_round(&self) -> u64 {
-        self.sub_dag.leader_round()
+        self.output.sub_dag.leader_round()
     }
 
     fn leader_author_index(&self) -> AuthorityIndex {
-        self.sub_dag.leader.origin().0
+        self.output.sub_dag.leader.origin().0
     }
 
     fn commit_timestamp_ms(&self) -> u64 {
-        self.sub_dag.commit_timestamp()
+        self.output.sub_dag.commit_timestamp()
     }
 
     fn commit_sub_dag_index(&self) -> u64 {
-        self.sub_dag.sub_dag_index
+        self.output.sub_dag.sub_dag_index
+    }
+
+    fn transactions_iter(
+        &self,
+        policy: MalformedTransactionPolicy,
+    ) -> ConsensusOutputTransactionsIter<'_> {
+        Box::new(
+            self.output
+                .sub_dag
+                .certificates
+                .iter()
+                .zip(&self.output.batches)
+                .map(move |(cert, batches)| {
+                    assert_eq!(cert.header().payload().len(), batches.len());
+                    let authority = cert.origin().0;
+                    let round = cert.round();
+                    let transactions = batches.iter().flat_map(move |batch| {
+                        let digest = batch.digest();
+                        assert!(cert.header().payload().contains_key(&digest));
+                        batch.transactions().iter().enumerate().map(
+                            move |(position, serialized_transaction)| {
+                                let origin = ConsensusTransactionOrigin {
+                                    authority,
+                                    round,
+                                    source: ConsensusTransactionSource::NarwhalBatch(digest),
+                                    position,
+                                };
+                                // Malformed transactions should have been prevented by Narwhal
+                                // batch verification.
+                                match deserialize_transaction(
+                                    serialized_transaction,
+                                    &origin,
+                                    policy,
+                                ) {
+                                    Some(transaction) => {
+                                        Ok((serialized_transaction.as_ref(), transaction, origin))
+                                    }
+                                    None => Err(origin),
+                                }
+                            },
+                        )
+                    });
+                    (
+                        authority,
+                        Box::new(transactions) as ConsensusOutputTransactionIter<'_>,
+                    )
+                }),
+        )
+    }
+
+    fn commit_digest(&self) -> ConsensusCommitDigest {
+        self.output.sub_dag.digest().into()
+    }
+
+    fn previous_commit_digest(&self) -> Option<ConsensusCommitDigest> {
+        self.previous_commit_digest
+    }
+}
+
+/// The number of Mysticeti commits that reputation scores are accumulated over before they are
+/// reported and reset, matching the leader schedule window of Narwhal.
+pub(crate) const MYSTICETI_SCHEDULE_CHANGE_SUB_DAGS: u64 = 300;
+
+/// A sub-dag committed by Mysticeti, along with the reputation scores accumulated by
+/// [MysticetiReputationScorer] up to it, and the digest of the commit before it.
+pub(crate) struct MysticetiConsensusOutput {
+    pub sub_dag: CommittedSubDag,
+    /// Authorities and their scores, in descending order of score. Only set for the last commit
+    /// of a schedule, when the scores are final.
+    pub reputation_scores: Option<Vec<(AuthorityIndex, u64)>>,
+    pub previous_commit_digest: Option<ConsensusCommitDigest>,
+}
+
+/// Scores authorities on the commits that Mysticeti outputs, the same way that Bullshark does
+/// for Narwhal: every block in a commit that includes the leader of the previous commit earns its
+/// author a point. Scores are reset at the start of each schedule of `sub_dags_per_schedule`
+/// commits, and reported on the last commit of each schedule.
+///
+/// Scores are not persisted, so after a restart the first schedule is only scored from the
+/// commits seen since the restart.
+pub(crate) struct MysticetiReputationScorer {
+    sub_dags_per_schedule: u64,
+    scores: Vec<u64>,
+    last_leader: Option<BlockReference>,
+}
+
+impl MysticetiReputationScorer {
+    pub fn new(num_authorities: usize, sub_dags_per_schedule: u64) -> Self {
+        Self {
+            sub_dags_per_schedule,
+            scores: vec![0; num_authorities],
+            last_leader: None,
+        }
     }
 
-    fn transactions(&self) -> ConsensusOutputTransactions {
-        self.sub_dag
-            .certificates
+    pub fn score(&mut self, sub_dag: CommittedSubDag) -> MysticetiConsensusOutput {
+        if sub_dag.height % self.sub_dags_per_schedule == 0 {
+            self.scores.iter_mut().for_each(|score| *score = 0);
+        }
+
+        if let Some(last_leader) = &self.last_leader {
+            for block in &sub_dag.blocks {
+                if block.includes().contains(last_leader) {
+                    if let Some(score) = self.scores.get_mut(block.author() as usize) {
+                        *score += 1;
+                    }
+                }
+            }
+        }
+        self.last_leader = Some(sub_dag.anchor);
+
+        let final_of_schedule = (sub_dag.height + 1) % self.sub_dags_per_schedule == 0;
+        let reputation_scores = final_of_schedule.then(|| self.scores_sorted_desc());
+
+        MysticetiConsensusOutput {
+            sub_dag,
+            reputation_scores,
+            previous_commit_digest: None,
+        }
+    }
+
+    /// Authorities in descending order of score, breaking ties by descending authority index, as
+    /// Narwhal does.
+    fn scores_sorted_desc(&self) -> Vec<(AuthorityIndex, u64)> {
+        let mut scores: Vec<_> = self
+            .scores
             .iter()
-            .zip(&self.batches)
-            .map(|(cert, batches)| {
-                assert_eq!(cert.header().payload().len(), batches.len());
-                let transactions: Vec<(&[u8], ConsensusTransaction)> = batches.iter().flat_map(|batch| {
-                    let digest = batch.digest();
-                    assert!(cert.header().payload().contains_key(&digest));
-                    batch.transactions().iter().map(move |serialized_transaction| {
-                        let transaction = match bcs::from_bytes::<ConsensusTransaction>(
-                            serialized_transaction,
-                        ) {
-                            Ok(transaction) => transaction,
-                            Err(err) => {
-                                // This should have been prevented by Narwhal batch verification.
-                                panic!(
-                                    "Unexpected malformed transaction (failed to deserialize): {}\nCertificate={:?} BatchDigest={:?} Transaction={:?}",
-                                    err, cert, digest, serialized_transaction
-                                );
-                            }
-                        };
-                        (serialized_transaction.as_ref(), transaction)
-                    })
-                }).collect();
-                (cert.origin().0, transactions)
-            }).collect()
+            .enumerate()
+            .map(|(authority, score)| (authority as AuthorityIndex, *score))
+            .collect();
+        scores.sort_by(|(a1, s1), (a2, s2)| s2.cmp(s1).then_with(|| a2.cmp(a1)));
+        scores
     }
 }
 
-impl ConsensusOutputAPI for mysticeti_core::consensus::linearizer::CommittedSubDag {
+impl ConsensusOutputAPI for MysticetiConsensusOutput {
+    fn consensus_protocol(&self) -> &'static str {
+        "mysticeti"
+    }
+
     fn reputation_score_sorted_desc(&self) -> Option<Vec<(AuthorityIndex, u64)>> {
-        // TODO: Implement this in Mysticeti.
-        None
+        self.reputation_scores.clone()
     }
 
     fn leader_round(&self) -> u64 {
-        self.anchor.round
+        self.sub_dag.anchor.round
     }
 
     fn leader_author_index(&self) -> AuthorityIndex {
-        self.anchor.authority as AuthorityIndex
+        self.sub_dag.anchor.authority as AuthorityIndex
     }
 
     fn commit_timestamp_ms(&self) -> u64 {
-        // TODO: Enforce ordered timestamp in Mysticeti.
-        self.timestamp_ms
+        // Not necessarily monotonic, see `validated_commit_timestamp_ms`.
+        self.sub_dag.timestamp_ms
     }
 
     fn commit_sub_dag_index(&self) -> u64 {
-        self.height
+        self.sub_dag.height
     }
 
-    fn transactions(&self) -> ConsensusOutputTransactions {
-        self.blocks
-            .iter()
-            .map(|block| {
-                let round = block.round();
-                let author = block.author() as AuthorityIndex;
-                let transactions: Vec<_> = block
+    fn transactions_iter(
+        &self,
+        policy: MalformedTransactionPolicy,
+    ) -> ConsensusOutputTransactionsIter<'_> {
+        Box::new(self.sub_dag.blocks.iter().map(move |block| {
+            let author = block.author() as AuthorityIndex;
+            let round = block.round();
+            let reference = *block.reference();
+            let transactions =
+                block
                     .shared_transactions()
-                    .flat_map(|(_loc, tx)| {
-                        let transaction = bcs::from_bytes::<ConsensusTransaction>(tx.data());
-                        match transaction {
-                            Ok(transaction) => Some((
-                                tx.data(),
-                                transaction,
-                            )),
-                            Err(err) => {
-                                tracing::error!("Failed to deserialize sequenced consensus transaction(this should not happen) {} from {author} at {round}", err);
-                                None
-                            },
+                    .enumerate()
+                    .map(move |(position, (_loc, tx))| {
+                        let origin = ConsensusTransactionOrigin {
+                            authority: author,
+                            round,
+                            source: ConsensusTransactionSource::MysticetiBlock(reference),
+                            position,
+                        };
+                        match deserialize_transaction(tx.data(), &origin, policy) {
+                            Some(transaction) => Ok((tx.data(), transaction, origin)),
+                            None => Err(origin),
                         }
-                    })
-                    .collect();
-                (author, transactions)
+                    });
+            (
+                author,
+                Box::new(transactions) as ConsensusOutputTransactionIter<'_>,
+            )
+        }))
+    }
+
+    /// Mysticeti does not assign digests to its commits, so the digest is computed from the
+    /// references of the committed blocks (which commit to their contents), and the height and
+    /// timestamp of the commit.
+    fn commit_digest(&self) -> ConsensusCommitDigest {
+        let mut hasher = Blake2b256::default();
+        hasher.update(bcs::to_bytes(&self.sub_dag.anchor).expect("Serialization should not fail"));
+        for block in &self.sub_dag.blocks {
+            hasher.update(bcs::to_bytes(block.reference()).expect("Serialization should not fail"));
+        }
+        hasher.update(self.sub_dag.height.to_le_bytes());
+        hasher.update(self.sub_dag.timestamp_ms.to_le_bytes());
+        hasher.finalize()
+    }
+
+    fn previous_commit_digest(&self) -> Option<ConsensusCommitDigest> {
+        self.previous_commit_digest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus_types::test_consensus_output::TestConsensusOutput;
+    use indexmap::IndexMap;
+    use mysticeti_core::consensus::linearizer::CommittedSubDag as MysticetiSubDag;
+    use mysticeti_core::data::Data;
+    use mysticeti_core::types::{RoundNumber, StatementBlock};
+    use mysticeti_core::Signer;
+    use narwhal_config::AuthorityIdentifier;
+    use narwhal_test_utils::{latest_protocol_version, CommitteeFixture};
+    use narwhal_types::{
+        Batch, Certificate, CommittedSubDag, Header, HeaderV2Builder, ReputationScores,
+    };
+    use std::collections::BTreeSet;
+    use std::sync::Arc;
+    use sui_types::base_types::AuthorityName;
+
+    fn narwhal_output(
+        committee: &narwhal_config::Committee,
+        sub_dag_index: u64,
+        round: u64,
+        created_at: u64,
+        previous: Option<&NarwhalConsensusOutput>,
+    ) -> NarwhalConsensusOutput {
+        let header = HeaderV2Builder::default()
+            .author(AuthorityIdentifier(0))
+            .round(round)
+            .epoch(0)
+            .created_at(created_at)
+            .payload(IndexMap::new())
+            .parents(BTreeSet::new())
+            .build()
+            .unwrap();
+        let leader = Certificate::new_unsigned(
+            &latest_protocol_version(),
+            committee,
+            Header::V2(header),
+            vec![],
+        )
+        .unwrap();
+        NarwhalConsensusOutput {
+            output: ConsensusOutput {
+                sub_dag: Arc::new(CommittedSubDag::new(
+                    vec![leader.clone()],
+                    leader,
+                    sub_dag_index,
+                    ReputationScores::default(),
+                    previous.map(|previous| previous.output.sub_dag.as_ref()),
+                )),
+                batches: vec![vec![]],
+            },
+            previous_commit_digest: previous.map(|previous| previous.commit_digest()),
+        }
+    }
+
+    #[test]
+    fn test_commit_timestamp_policies() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+        let output = narwhal_output(&committee, 1, 2, 1_000, None);
+
+        assert_eq!(
+            output.validated_commit_timestamp_ms(&ConsensusCommitTimestamps, 0, 0),
+            1_000
+        );
+        assert_eq!(
+            output.validated_commit_timestamp_ms(&SkewedCommitTimestamps { skew_ms: 250 }, 0, 0),
+            1_250
+        );
+        assert_eq!(
+            output.validated_commit_timestamp_ms(&SkewedCommitTimestamps { skew_ms: -2_000 }, 0, 0),
+            0
+        );
+
+        let scripted = ScriptedCommitTimestamps {
+            timestamps: BTreeMap::from([(1, 42)]),
+        };
+        assert_eq!(output.validated_commit_timestamp_ms(&scripted, 0, 0), 42);
+    }
+
+    #[test]
+    fn test_commit_timestamp_clamped_to_epoch_start() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+        let output = narwhal_output(&committee, 1, 2, 1_000, None);
+
+        // a timestamp skewed before the start of the epoch is clamped to it
+        let skewed = SkewedCommitTimestamps { skew_ms: -500 };
+        assert_eq!(output.validated_commit_timestamp_ms(&skewed, 800, 0), 800);
+        assert_eq!(output.validated_commit_timestamp_ms(&skewed, 400, 0), 500);
+    }
+
+    #[test]
+    fn test_duplicate_and_decreasing_commit_timestamps() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+
+        // consensus never reports a timestamp lower than the previous commit's
+        let first = narwhal_output(&committee, 1, 2, 1_000, None);
+        let second = narwhal_output(&committee, 2, 4, 900, Some(&first));
+        let policy = ConsensusCommitTimestamps;
+        assert_eq!(first.validated_commit_timestamp_ms(&policy, 0, 0), 1_000);
+        assert_eq!(
+            second.validated_commit_timestamp_ms(&policy, 0, 1_000),
+            1_000
+        );
+
+        // a policy (or consensus engine) reporting decreasing timestamps is normalized to the
+        // previous commit's timestamp, so consumers only ever see monotonic timestamps
+        let scripted = ScriptedCommitTimestamps {
+            timestamps: BTreeMap::from([(1, 1_000), (2, 900)]),
+        };
+        assert_eq!(first.validated_commit_timestamp_ms(&scripted, 0, 0), 1_000);
+        assert_eq!(
+            second.validated_commit_timestamp_ms(&scripted, 0, 1_000),
+            1_000
+        );
+
+        // duplicate timestamps are allowed
+        let scripted = ScriptedCommitTimestamps {
+            timestamps: BTreeMap::from([(1, 1_000), (2, 1_000)]),
+        };
+        assert_eq!(
+            second.validated_commit_timestamp_ms(&scripted, 0, 1_000),
+            1_000
+        );
+    }
+
+    /// A Mysticeti block by `author` at `round`, including `includes`, without transactions.
+    fn mysticeti_block(
+        author: mysticeti_core::types::AuthorityIndex,
+        round: RoundNumber,
+        includes: Vec<BlockReference>,
+    ) -> Data<StatementBlock> {
+        let signers = Signer::new_for_test(author as usize + 1);
+        Data::new(StatementBlock::new_with_signer(
+            author,
+            round,
+            includes,
+            vec![],
+            0,
+            false,
+            &signers[author as usize],
+        ))
+    }
+
+    /// A Mysticeti commit of `blocks` at `height`, led by its last block.
+    fn mysticeti_sub_dag(
+        height: u64,
+        timestamp_ms: u64,
+        blocks: Vec<Data<StatementBlock>>,
+    ) -> MysticetiSubDag {
+        MysticetiSubDag {
+            anchor: *blocks.last().unwrap().reference(),
+            blocks,
+            timestamp_ms,
+            height,
+        }
+    }
+
+    #[test]
+    fn test_mysticeti_commit_timestamps() {
+        let mut scorer = MysticetiReputationScorer::new(4, MYSTICETI_SCHEDULE_CHANGE_SUB_DAGS);
+        let first = scorer.score(mysticeti_sub_dag(
+            1,
+            1_000,
+            vec![mysticeti_block(0, 1, vec![])],
+        ));
+        let second = scorer.score(mysticeti_sub_dag(
+            2,
+            900,
+            vec![mysticeti_block(1, 2, vec![])],
+        ));
+
+        assert_eq!(
+            first.validated_commit_timestamp_ms(&ConsensusCommitTimestamps, 0, 0),
+            1_000
+        );
+        assert_eq!(
+            first.validated_commit_timestamp_ms(&SkewedCommitTimestamps { skew_ms: 250 }, 0, 0),
+            1_250
+        );
+        assert_eq!(
+            first.validated_commit_timestamp_ms(&SkewedCommitTimestamps { skew_ms: -500 }, 800, 0),
+            800
+        );
+
+        // scripted timestamps are keyed by the height of the commit
+        let scripted = ScriptedCommitTimestamps {
+            timestamps: BTreeMap::from([(2, 1_500)]),
+        };
+        assert_eq!(first.validated_commit_timestamp_ms(&scripted, 0, 0), 1_000);
+        assert_eq!(
+            second.validated_commit_timestamp_ms(&scripted, 0, 1_000),
+            1_500
+        );
+
+        // Mysticeti timestamps are not monotonic, so a decreasing one is normalized to the
+        // previous commit's timestamp
+        assert_eq!(
+            second.validated_commit_timestamp_ms(&ConsensusCommitTimestamps, 0, 1_000),
+            1_000
+        );
+    }
+
+    #[test]
+    fn test_mysticeti_reputation_scores() {
+        let mut scorer = MysticetiReputationScorer::new(4, 3);
+
+        // the first commit has no previous leader to include
+        let leader_0 = mysticeti_block(0, 1, vec![]);
+        let output = scorer.score(mysticeti_sub_dag(0, 1_000, vec![leader_0.clone()]));
+        assert_eq!(output.reputation_score_sorted_desc(), None);
+
+        // authors of blocks including the previous leader earn a point, the others don't
+        let leader_1 = mysticeti_block(3, 2, vec![]);
+        let output = scorer.score(mysticeti_sub_dag(
+            1,
+            2_000,
+            vec![
+                mysticeti_block(1, 2, vec![*leader_0.reference()]),
+                mysticeti_block(2, 2, vec![*leader_0.reference()]),
+                leader_1.clone(),
+            ],
+        ));
+        assert_eq!(output.reputation_score_sorted_desc(), None);
+
+        // scores accumulate over the schedule, and are only reported on its last commit
+        let leader_2 = mysticeti_block(0, 3, vec![*leader_1.reference()]);
+        let output = scorer.score(mysticeti_sub_dag(
+            2,
+            3_000,
+            vec![
+                mysticeti_block(1, 3, vec![*leader_1.reference()]),
+                leader_2.clone(),
+            ],
+        ));
+        assert_eq!(
+            output.reputation_score_sorted_desc(),
+            Some(vec![(1, 2), (2, 1), (0, 1), (3, 0)])
+        );
+
+        // scores are reset at the start of the next schedule
+        let leader_3 = mysticeti_block(2, 4, vec![*leader_2.reference()]);
+        let output = scorer.score(mysticeti_sub_dag(3, 4_000, vec![leader_3.clone()]));
+        assert_eq!(output.reputation_score_sorted_desc(), None);
+
+        // including an older leader earns nothing
+        let leader_4 = mysticeti_block(3, 5, vec![*leader_0.reference()]);
+        let output = scorer.score(mysticeti_sub_dag(4, 5_000, vec![leader_4.clone()]));
+        assert_eq!(output.reputation_score_sorted_desc(), None);
+
+        let leader_5 = mysticeti_block(1, 6, vec![*leader_4.reference()]);
+        let output = scorer.score(mysticeti_sub_dag(5, 6_000, vec![leader_5]));
+        assert_eq!(
+            output.reputation_score_sorted_desc(),
+            Some(vec![(2, 1), (1, 1), (3, 0), (0, 0)])
+        );
+    }
+
+    #[test]
+    fn test_narwhal_commit_digest_chain() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+
+        let first = narwhal_output(&committee, 1, 2, 1_000, None);
+        let second = narwhal_output(&committee, 2, 4, 2_000, Some(&first));
+
+        assert_eq!(first.previous_commit_digest(), None);
+        assert_eq!(second.previous_commit_digest(), Some(first.commit_digest()));
+        assert_ne!(first.commit_digest(), second.commit_digest());
+    }
+
+    /// A commit of a single certificate from authority 1 at round 3, with a batch of
+    /// `transactions`.
+    fn narwhal_output_with_batch(
+        committee: &narwhal_config::Committee,
+        transactions: Vec<Vec<u8>>,
+    ) -> (NarwhalConsensusOutput, Batch) {
+        let protocol_config = latest_protocol_version();
+        let batch = Batch::new(transactions, &protocol_config);
+        let header = HeaderV2Builder::default()
+            .author(AuthorityIdentifier(1))
+            .round(3)
+            .epoch(0)
+            .parents(BTreeSet::new())
+            .with_payload_batch(batch.clone(), 0, 0)
+            .build()
+            .unwrap();
+        let certificate =
+            Certificate::new_unsigned(&protocol_config, committee, Header::V2(header), vec![])
+                .unwrap();
+        let output = NarwhalConsensusOutput {
+            output: ConsensusOutput {
+                sub_dag: Arc::new(CommittedSubDag::new(
+                    vec![certificate.clone()],
+                    certificate,
+                    1,
+                    ReputationScores::default(),
+                    None,
+                )),
+                batches: vec![vec![batch.clone()]],
+            },
+            previous_commit_digest: None,
+        };
+        (output, batch)
+    }
+
+    fn end_of_publish_bytes() -> Vec<u8> {
+        bcs::to_bytes(&ConsensusTransaction::new_end_of_publish(
+            AuthorityName::ZERO,
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_narwhal_transaction_origins() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+        let (output, batch) = narwhal_output_with_batch(
+            &committee,
+            vec![end_of_publish_bytes(), end_of_publish_bytes()],
+        );
+
+        let (transactions, malformed) = output.transactions(MalformedTransactionPolicy::Panic);
+        assert!(malformed.is_empty());
+        assert_eq!(transactions.len(), 1);
+        let (authority, transactions) = &transactions[0];
+        assert_eq!(*authority, 1);
+
+        let origins: Vec<_> = transactions
+            .iter()
+            .map(|(_, _, origin)| origin.clone())
+            .collect();
+        let expected: Vec<_> = (0..2)
+            .map(|position| ConsensusTransactionOrigin {
+                authority: 1,
+                round: 3,
+                source: ConsensusTransactionSource::NarwhalBatch(batch.digest()),
+                position,
             })
-            .collect()
+            .collect();
+        assert_eq!(origins, expected);
+    }
+
+    #[test]
+    fn test_transactions_iter_is_lazy() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+        let (output, _) =
+            narwhal_output_with_batch(&committee, vec![end_of_publish_bytes(), vec![0xff]]);
+
+        // the malformed transaction is never reached, so the panic policy is never applied
+        let (authority, mut transactions) = output
+            .transactions_iter(MalformedTransactionPolicy::Panic)
+            .next()
+            .unwrap();
+        assert_eq!(authority, 1);
+        let (_, _, origin) = transactions.next().unwrap().unwrap();
+        assert_eq!(origin.position, 0);
+    }
+
+    #[test]
+    fn test_malformed_transactions_rejected() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+        let (output, batch) =
+            narwhal_output_with_batch(&committee, vec![vec![0xff], end_of_publish_bytes()]);
+
+        let (transactions, malformed) = output.transactions(MalformedTransactionPolicy::Reject);
+        assert_eq!(transactions[0].1.len(), 1);
+        assert_eq!(transactions[0].1[0].2.position, 1);
+        assert_eq!(
+            malformed,
+            vec![ConsensusTransactionOrigin {
+                authority: 1,
+                round: 3,
+                source: ConsensusTransactionSource::NarwhalBatch(batch.digest()),
+                position: 0,
+            }]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Unexpected malformed transaction")]
+    fn test_malformed_transactions_panic() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+        let (output, _) = narwhal_output_with_batch(&committee, vec![vec![0xff]]);
+        output.transactions(MalformedTransactionPolicy::Panic);
+    }
+
+    #[test]
+    fn test_epoch_boundary_markers() {
+        let fixture = CommitteeFixture::builder().build();
+        let committee = fixture.committee();
+        let authority = |index| committee.authority_pubkey_by_index(index).unwrap();
+        let output = TestConsensusOutput::builder()
+            .with_transactions(
+                0,
+                3,
+                vec![
+                    ConsensusTransaction::new_end_of_publish(authority(0)),
+                    ConsensusTransaction::new_end_of_publish(authority(0)),
+                ],
+            )
+            .with_transactions(
+                1,
+                3,
+                vec![ConsensusTransaction::new_end_of_publish(authority(2))],
+            )
+            .build();
+
+        let markers = output.epoch_boundary_markers(&committee, MalformedTransactionPolicy::Reject);
+        // the repeated marker from authority 0 is only reported once
+        assert_eq!(markers.valid.len(), 1);
+        assert_eq!(markers.valid[0].authority, authority(0));
+        assert_eq!(markers.valid[0].origin.position, 0);
+        assert!(markers.contains(&authority(0)));
+        // authority 1 cannot end the submissions of authority 2
+        assert_eq!(markers.invalid.len(), 1);
+        assert_eq!(markers.invalid[0].authority, authority(2));
+        assert_eq!(markers.invalid[0].origin.authority, 1);
+        assert!(!markers.contains(&authority(2)));
+        assert!(markers.is_invalid(&markers.invalid[0].origin));
+        assert!(!markers.is_invalid(&markers.valid[0].origin));
+
+        let empty = TestConsensusOutput::builder().build();
+        assert!(empty
+            .epoch_boundary_markers(&committee, MalformedTransactionPolicy::Reject)
+            .is_empty());
+    }
+
+    #[test]
+    fn test_mysticeti_scores_sorted_desc() {
+        let mut scorer = MysticetiReputationScorer::new(4, MYSTICETI_SCHEDULE_CHANGE_SUB_DAGS);
+        scorer.scores = vec![3, 5, 3, 0];
+
+        // ties are broken by descending authority index, as in Narwhal
+        assert_eq!(
+            scorer.scores_sorted_desc(),
+            vec![(1, 5), (2, 3), (0, 3), (3, 0)]
+        );
     }
 }
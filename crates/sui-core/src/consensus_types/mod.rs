@@ -3,6 +3,8 @@
 
 pub(crate) mod committee_api;
 pub(crate) mod consensus_output_api;
+#[cfg(test)]
+pub(crate) mod test_consensus_output;
 
 /// An unique integer ID for a validator used by consensus.
 /// In Narwhal, this is the inner value of the `AuthorityIdentifier` type.
@@ -0,0 +1,263 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! An implementation of [ConsensusOutputAPI] for tests, which is built directly from transactions
+//! rather than from the certificates and batches (or blocks) of a real consensus engine.
+
+use crate::consensus_types::consensus_output_api::{
+    deserialize_transaction, ConsensusCommitDigest, ConsensusOutputAPI,
+    ConsensusOutputTransactionIter, ConsensusOutputTransactionsIter, ConsensusTransactionOrigin,
+    ConsensusTransactionSource, MalformedTransactionPolicy,
+};
+use crate::consensus_types::AuthorityIndex;
+use fastcrypto::hash::{Blake2b256, HashFunction};
+use narwhal_types::BatchDigest;
+use std::fmt::Display;
+use sui_types::messages_consensus::ConsensusTransaction;
+
+/// A commit built by [TestConsensusOutputBuilder]. Each group of transactions is reported as if it
+/// was a Narwhal batch, whose digest is the hash of its transactions.
+#[derive(Clone, Debug)]
+pub(crate) struct TestConsensusOutput {
+    leader_round: u64,
+    leader_author: AuthorityIndex,
+    commit_timestamp_ms: u64,
+    sub_dag_index: u64,
+    reputation_scores: Option<Vec<(AuthorityIndex, u64)>>,
+    previous_commit_digest: Option<ConsensusCommitDigest>,
+    groups: Vec<TestTransactionGroup>,
+}
+
+/// Transactions included in the commit by a single certificate or block.
+#[derive(Clone, Debug)]
+struct TestTransactionGroup {
+    authority: AuthorityIndex,
+    round: u64,
+    digest: BatchDigest,
+    transactions: Vec<Vec<u8>>,
+}
+
+/// Builds a [TestConsensusOutput]. By default the commit has sub-dag index 1, is led by authority
+/// 0 at round 2, has a timestamp of 0 and contains no transactions.
+#[derive(Clone, Debug)]
+pub(crate) struct TestConsensusOutputBuilder {
+    output: TestConsensusOutput,
+}
+
+impl TestConsensusOutput {
+    pub fn builder() -> TestConsensusOutputBuilder {
+        TestConsensusOutputBuilder {
+            output: TestConsensusOutput {
+                leader_round: 2,
+                leader_author: 0,
+                commit_timestamp_ms: 0,
+                sub_dag_index: 1,
+                reputation_scores: None,
+                previous_commit_digest: None,
+                groups: vec![],
+            },
+        }
+    }
+
+    /// The digest of the batch that the `index`th group of transactions is reported to be in.
+    pub fn batch_digest(&self, index: usize) -> BatchDigest {
+        self.groups[index].digest
+    }
+}
+
+impl TestConsensusOutputBuilder {
+    pub fn with_sub_dag_index(mut self, sub_dag_index: u64) -> Self {
+        self.output.sub_dag_index = sub_dag_index;
+        self
+    }
+
+    pub fn with_leader(mut self, author: AuthorityIndex, round: u64) -> Self {
+        self.output.leader_author = author;
+        self.output.leader_round = round;
+        self
+    }
+
+    pub fn with_commit_timestamp_ms(mut self, commit_timestamp_ms: u64) -> Self {
+        self.output.commit_timestamp_ms = commit_timestamp_ms;
+        self
+    }
+
+    /// Sets the reputation scores reported by the commit, which are only reported on the last
+    /// commit of a schedule.
+    pub fn with_reputation_scores(mut self, scores: Vec<(AuthorityIndex, u64)>) -> Self {
+        self.output.reputation_scores = Some(scores);
+        self
+    }
+
+    /// Links the commit to `previous`, as the consensus handler does.
+    pub fn with_previous(mut self, previous: &TestConsensusOutput) -> Self {
+        self.output.previous_commit_digest = Some(previous.commit_digest());
+        self
+    }
+
+    /// Adds the transactions of a certificate or block from `authority` at `round`.
+    pub fn with_transactions(
+        self,
+        authority: AuthorityIndex,
+        round: u64,
+        transactions: Vec<ConsensusTransaction>,
+    ) -> Self {
+        let transactions = transactions
+            .iter()
+            .map(|transaction| bcs::to_bytes(transaction).expect("Serialization should not fail"))
+            .collect();
+        self.with_serialized_transactions(authority, round, transactions)
+    }
+
+    /// Adds the transactions of a certificate or block from `authority` at `round` as they were
+    /// serialized, so that malformed transactions can be injected.
+    pub fn with_serialized_transactions(
+        mut self,
+        authority: AuthorityIndex,
+        round: u64,
+        transactions: Vec<Vec<u8>>,
+    ) -> Self {
+        let mut hasher = Blake2b256::default();
+        hasher.update(authority.to_le_bytes());
+        hasher.update(round.to_le_bytes());
+        for transaction in &transactions {
+            hasher.update(transaction);
+        }
+        self.output.groups.push(TestTransactionGroup {
+            authority,
+            round,
+            digest: BatchDigest::new(hasher.finalize().digest),
+            transactions,
+        });
+        self
+    }
+
+    pub fn build(self) -> TestConsensusOutput {
+        self.output
+    }
+}
+
+impl Display for TestConsensusOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "TestConsensusOutput(sub_dag_index={}, leader={}, round={}, groups={})",
+            self.sub_dag_index,
+            self.leader_author,
+            self.leader_round,
+            self.groups.len()
+        )
+    }
+}
+
+impl ConsensusOutputAPI for TestConsensusOutput {
+    fn consensus_protocol(&self) -> &'static str {
+        "test"
+    }
+
+    fn reputation_score_sorted_desc(&self) -> Option<Vec<(AuthorityIndex, u64)>> {
+        self.reputation_scores.clone()
+    }
+
+    fn leader_round(&self) -> u64 {
+        self.leader_round
+    }
+
+    fn leader_author_index(&self) -> AuthorityIndex {
+        self.leader_author
+    }
+
+    fn commit_timestamp_ms(&self) -> u64 {
+        self.commit_timestamp_ms
+    }
+
+    fn commit_sub_dag_index(&self) -> u64 {
+        self.sub_dag_index
+    }
+
+    fn transactions_iter(
+        &self,
+        policy: MalformedTransactionPolicy,
+    ) -> ConsensusOutputTransactionsIter<'_> {
+        Box::new(self.groups.iter().map(move |group| {
+            let transactions = group.transactions.iter().enumerate().map(
+                move |(position, serialized_transaction)| {
+                    let origin = ConsensusTransactionOrigin {
+                        authority: group.authority,
+                        round: group.round,
+                        source: ConsensusTransactionSource::NarwhalBatch(group.digest),
+                        position,
+                    };
+                    match deserialize_transaction(serialized_transaction, &origin, policy) {
+                        Some(transaction) => {
+                            Ok((serialized_transaction.as_slice(), transaction, origin))
+                        }
+                        None => Err(origin),
+                    }
+                },
+            );
+            (
+                group.authority,
+                Box::new(transactions) as ConsensusOutputTransactionIter<'_>,
+            )
+        }))
+    }
+
+    fn commit_digest(&self) -> ConsensusCommitDigest {
+        let mut hasher = Blake2b256::default();
+        hasher.update(self.sub_dag_index.to_le_bytes());
+        hasher.update(self.leader_author.to_le_bytes());
+        hasher.update(self.leader_round.to_le_bytes());
+        hasher.update(self.commit_timestamp_ms.to_le_bytes());
+        for group in &self.groups {
+            hasher.update(group.digest.0);
+        }
+        hasher.finalize()
+    }
+
+    fn previous_commit_digest(&self) -> Option<ConsensusCommitDigest> {
+        self.previous_commit_digest
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::AuthorityName;
+
+    #[test]
+    fn test_consensus_output_builder() {
+        let end_of_publish = ConsensusTransaction::new_end_of_publish(AuthorityName::ZERO);
+        let first = TestConsensusOutput::builder()
+            .with_leader(1, 4)
+            .with_commit_timestamp_ms(1_000)
+            .with_transactions(2, 3, vec![end_of_publish.clone()])
+            .with_serialized_transactions(3, 3, vec![vec![0xff]])
+            .build();
+        let second = TestConsensusOutput::builder()
+            .with_sub_dag_index(2)
+            .with_leader(2, 6)
+            .with_previous(&first)
+            .build();
+
+        assert_eq!(first.leader_author_index(), 1);
+        assert_eq!(first.leader_round(), 4);
+        assert_eq!(second.previous_commit_digest(), Some(first.commit_digest()));
+
+        let (transactions, malformed) = first.transactions(MalformedTransactionPolicy::Reject);
+        assert_eq!(transactions.len(), 2);
+        assert_eq!(transactions[0].0, 2);
+        assert_eq!(transactions[0].1.len(), 1);
+        assert_eq!(transactions[0].1[0].1.key(), end_of_publish.key());
+        assert!(transactions[1].1.is_empty());
+        assert_eq!(
+            malformed,
+            vec![ConsensusTransactionOrigin {
+                authority: 3,
+                round: 3,
+                source: ConsensusTransactionSource::NarwhalBatch(first.batch_digest(1)),
+                position: 0,
+            }]
+        );
+    }
+}
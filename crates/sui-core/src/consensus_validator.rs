@@ -65,6 +65,20 @@ impl SuiTxValidator {
                     //     owned_tx_certs.push(VerifiedCertificate::new_unchecked(*certificate));
                     // }
                 }
+                ConsensusTransactionKind::SoftBundle(certificates) => {
+                    let protocol_config = self.epoch_store.protocol_config();
+                    if !protocol_config.soft_bundle() {
+                        eyre::bail!("Soft bundles are not enabled");
+                    }
+                    let max_size = protocol_config.max_soft_bundle_size() as usize;
+                    if certificates.is_empty() || certificates.len() > max_size {
+                        eyre::bail!(
+                            "Soft bundle of {} transactions (allowed: 1 to {max_size})",
+                            certificates.len()
+                        );
+                    }
+                    cert_batch.extend(certificates);
+                }
                 ConsensusTransactionKind::CheckpointSignature(signature) => {
                     ckpt_messages.push(signature.clone());
                     ckpt_batch.push(signature.summary);
@@ -10,8 +10,10 @@ pub mod authority_client;
 pub mod authority_server;
 pub mod checkpoints;
 pub mod consensus_adapter;
+pub mod consensus_backpressure;
 pub mod consensus_handler;
 pub mod consensus_manager;
+pub mod consensus_replay;
 pub mod consensus_throughput_calculator;
 pub(crate) mod consensus_types;
 pub mod consensus_validator;
@@ -6,7 +6,7 @@ use crate::consensus_handler::{
 };
 use mysten_metrics::monitored_scope;
 use sui_protocol_config::ConsensusTransactionOrdering;
-use sui_types::messages_consensus::{ConsensusTransaction, ConsensusTransactionKind};
+use sui_types::digests::TransactionDigest;
 
 pub struct PostConsensusTxReorder {}
 
@@ -20,25 +20,55 @@ impl PostConsensusTxReorder {
         // are put to the beginning of the sequenced_transactions vector.
         match kind {
             ConsensusTransactionOrdering::ByGasPrice => Self::order_by_gas_price(transactions),
+            ConsensusTransactionOrdering::ByGasPriceThenDigest => {
+                Self::order_by_gas_price_then_digest(transactions)
+            }
             ConsensusTransactionOrdering::None => (),
         }
     }
 
     fn order_by_gas_price(transactions: &mut [VerifiedSequencedConsensusTransaction]) {
         let _scope = monitored_scope("HandleConsensusOutput::order_by_gas_price");
-        transactions.sort_by_key(|txn| {
-            // Reverse order, so that transactions with higher gas price are put to the beginning.
-            std::cmp::Reverse({
-                match &txn.0.transaction {
-                    SequencedConsensusTransactionKind::External(ConsensusTransaction {
-                        tracking_id: _,
-                        kind: ConsensusTransactionKind::UserTransaction(cert),
-                    }) => cert.gas_price(),
-                    // Non-user transactions are considered to have gas price of MAX u64 and are put to the beginning.
-                    // This way consensus commit prologue transactions will stay at the beginning.
-                    _ => u64::MAX,
-                }
-            })
+        // Reverse order, so that transactions with higher gas price are put to the beginning.
+        transactions.sort_by_key(|txn| std::cmp::Reverse(Self::gas_price(txn)))
+    }
+
+    fn order_by_gas_price_then_digest(transactions: &mut [VerifiedSequencedConsensusTransaction]) {
+        let _scope = monitored_scope("HandleConsensusOutput::order_by_gas_price_then_digest");
+        // Non-user transactions have no digest, and since `None` sorts first they keep their
+        // relative order at the beginning.
+        transactions.sort_by_cached_key(|txn| {
+            (
+                std::cmp::Reverse(Self::gas_price(txn)),
+                Self::first_user_certificate_digest(txn),
+            )
         })
     }
+
+    fn gas_price(txn: &VerifiedSequencedConsensusTransaction) -> u64 {
+        match &txn.0.transaction {
+            // A soft bundle is ordered as a whole, by its lowest gas price, so that its
+            // transactions stay adjacent.
+            SequencedConsensusTransactionKind::External(ext) => ext
+                .user_certificates()
+                .iter()
+                .map(|cert| cert.gas_price())
+                .min()
+                .unwrap_or(u64::MAX),
+            // Non-user transactions are considered to have gas price of MAX u64 and are put to the beginning.
+            // This way consensus commit prologue transactions will stay at the beginning.
+            _ => u64::MAX,
+        }
+    }
+
+    fn first_user_certificate_digest(
+        txn: &VerifiedSequencedConsensusTransaction,
+    ) -> Option<TransactionDigest> {
+        match &txn.0.transaction {
+            SequencedConsensusTransactionKind::External(ext) => {
+                ext.user_certificates().first().map(|cert| *cert.digest())
+            }
+            _ => None,
+        }
+    }
 }
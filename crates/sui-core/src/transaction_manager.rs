@@ -880,6 +880,11 @@ impl TransactionManager {
         inner.pending_certificates.len() + inner.executing_certificates.len()
     }
 
+    // Returns the number of transactions with all their inputs available, being executed right now.
+    pub(crate) fn executing_queue_len(&self) -> usize {
+        self.inner.read().executing_certificates.len()
+    }
+
     // Reconfigures the TransactionManager for a new epoch. Existing transactions will be dropped
     // because they are no longer relevant and may be incorrect in the new epoch.
     pub(crate) fn reconfigure(&self, new_epoch: EpochId) {
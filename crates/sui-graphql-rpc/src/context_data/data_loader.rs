@@ -0,0 +1,135 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::collections::HashMap;
+
+use async_graphql::{dataloader::*, *};
+use sui_indexer::models_v2::objects::StoredObject;
+use sui_types::object::Object as NativeSuiObject;
+
+use crate::{
+    error::Error,
+    types::{
+        checkpoint::Checkpoint, epoch::Epoch, move_object::MoveObject, object::Object,
+        sui_address::SuiAddress,
+    },
+};
+
+use super::db_data_provider::PgManager;
+
+/// Loads the epoch with this ID through a `DataLoader<PgManager>`.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub(crate) struct EpochKey(pub u64);
+
+/// Loads the checkpoint with this sequence number through a `DataLoader<PgManager>`.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub(crate) struct CheckpointKey(pub u64);
+
+/// Loads the latest version of the object at this address through a `DataLoader<PgManager>`.
+#[derive(Copy, Clone, Debug, Hash, PartialEq, Eq)]
+pub(crate) struct LatestObjectKey(pub SuiAddress);
+
+#[async_trait::async_trait]
+impl Loader<EpochKey> for PgManager {
+    type Value = Epoch;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[EpochKey]) -> Result<HashMap<EpochKey, Epoch>> {
+        let epoch_ids: Vec<_> = keys.iter().map(|k| k.0).collect();
+        let epochs = self.fetch_epochs(&epoch_ids).await.extend()?;
+        Ok(epochs
+            .into_iter()
+            .map(|epoch| (EpochKey(epoch.epoch_id), epoch))
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<CheckpointKey> for PgManager {
+    type Value = Checkpoint;
+    type Error = async_graphql::Error;
+
+    async fn load(&self, keys: &[CheckpointKey]) -> Result<HashMap<CheckpointKey, Checkpoint>> {
+        let sequence_numbers: Vec<_> = keys.iter().map(|k| k.0).collect();
+        let checkpoints = self
+            .fetch_checkpoints_by_sequence_numbers(&sequence_numbers)
+            .await
+            .extend()?;
+        Ok(checkpoints
+            .into_iter()
+            .map(|checkpoint| (CheckpointKey(checkpoint.sequence_number), checkpoint))
+            .collect())
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<LatestObjectKey> for PgManager {
+    type Value = StoredObject;
+    type Error = async_graphql::Error;
+
+    async fn load(
+        &self,
+        keys: &[LatestObjectKey],
+    ) -> Result<HashMap<LatestObjectKey, StoredObject>> {
+        let addresses: Vec<_> = keys.iter().map(|k| k.0).collect();
+        let stored_objs = self.fetch_stored_objs(&addresses).await.extend()?;
+        stored_objs
+            .into_iter()
+            .map(|o| {
+                let address = SuiAddress::from_bytes(&o.object_id)
+                    .map_err(|e| Error::Internal(format!("Invalid object ID: {e}")).extend())?;
+                Ok((LatestObjectKey(address), o))
+            })
+            .collect()
+    }
+}
+
+/// The epoch with ID `epoch_id`, which is expected to exist (e.g. because a checkpoint or
+/// transaction block refers to it). Lookups made while resolving the same query are batched.
+pub(crate) async fn load_epoch_strict(ctx: &Context<'_>, epoch_id: u64) -> Result<Epoch> {
+    ctx.data_unchecked::<DataLoader<PgManager>>()
+        .load_one(EpochKey(epoch_id))
+        .await?
+        .ok_or_else(|| Error::Internal(format!("Epoch {epoch_id} not found")).extend())
+}
+
+/// The checkpoint with sequence number `sequence_number`, if it exists. Lookups made while
+/// resolving the same query are batched.
+pub(crate) async fn load_checkpoint(
+    ctx: &Context<'_>,
+    sequence_number: u64,
+) -> Result<Option<Checkpoint>> {
+    ctx.data_unchecked::<DataLoader<PgManager>>()
+        .load_one(CheckpointKey(sequence_number))
+        .await
+}
+
+/// The latest version of the object at `address`, if it exists. Lookups made while resolving the
+/// same query are batched.
+pub(crate) async fn load_latest_obj(
+    ctx: &Context<'_>,
+    address: SuiAddress,
+) -> Result<Option<Object>> {
+    let stored_obj = ctx
+        .data_unchecked::<DataLoader<PgManager>>()
+        .load_one(LatestObjectKey(address))
+        .await?;
+    stored_obj.map(Object::try_from).transpose().extend()
+}
+
+/// The latest version of the Move object at `address`, if it exists. Lookups made while resolving
+/// the same query are batched.
+pub(crate) async fn load_latest_move_obj(
+    ctx: &Context<'_>,
+    address: SuiAddress,
+) -> Result<Option<MoveObject>> {
+    let stored_obj = ctx
+        .data_unchecked::<DataLoader<PgManager>>()
+        .load_one(LatestObjectKey(address))
+        .await?;
+    let native_object = stored_obj
+        .map(NativeSuiObject::try_from)
+        .transpose()
+        .map_err(|e| Error::from(e).extend())?;
+    Ok(native_object.map(|native_object| MoveObject { native_object }))
+}
@@ -2,18 +2,20 @@
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
     config::Limits,
+    context_data::package_cache::PackageCache,
     error::Error,
     types::{
         address::{Address, AddressTransactionBlockRelationship},
-        balance::Balance,
+        balance::{Balance, BalanceSnapshot},
         base64::Base64,
         big_int::BigInt,
         checkpoint::Checkpoint,
         coin::Coin,
+        coin_metadata::CoinMetadata,
         committee_member::CommitteeMember,
         date_time::DateTime,
         digest::Digest,
-        dynamic_field::DynamicField,
+        dynamic_field::{DynamicField, DynamicFieldName},
         end_of_epoch_data::EndOfEpochData,
         epoch::Epoch,
         event::{Event, EventFilter},
@@ -22,9 +24,11 @@ use crate::{
         move_object::MoveObject,
         move_package::MovePackage,
         move_type::MoveType,
-        object::{Object, ObjectFilter, ObjectKind},
+        object::{Object, ObjectFilter, ObjectKind, ObjectResult},
+        object_change::{ObjectChangeFilter, ObjectUpdate},
         protocol_config::{ProtocolConfigAttr, ProtocolConfigFeatureFlag, ProtocolConfigs},
         safe_mode::SafeMode,
+        sponsorship::{GasSponsor, GasSponsorStats, SponsoredTransactionStatus},
         stake::{Stake, StakeStatus},
         stake_subsidy::StakeSubsidy,
         storage_fund::StorageFund,
@@ -32,17 +36,23 @@ use crate::{
         sui_system_state_summary::SuiSystemStateSummary,
         system_parameters::SystemParameters,
         transaction_block::{TransactionBlock, TransactionBlockEffects, TransactionBlockFilter},
+        transaction_block_export::{ExportedTransactionBlock, TransactionBlockExportBatch},
         transaction_block_kind::{
             AuthenticatorStateUpdate, ChangeEpochTransaction, ConsensusCommitPrologueTransaction,
             EndOfEpochTransaction, GenesisTransaction, ProgrammableTransaction,
             TransactionBlockKind,
         },
         transaction_signature::TransactionSignature,
+        validator::Validator,
         validator_set::ValidatorSet,
     },
 };
-use async_graphql::connection::{Connection, Edge};
+use async_graphql::{
+    connection::{Connection, Edge},
+    OutputType,
+};
 use diesel::{
+    expression::SqlLiteral,
     pg::Pg,
     query_builder::{AstPass, BoxedSelectStatement, FromClause, QueryFragment, QueryId},
     sql_types::Text,
@@ -50,34 +60,39 @@ use diesel::{
     QueryResult, RunQueryDsl,
 };
 use move_core_types::language_storage::StructTag;
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr};
 use sui_indexer::{
     apis::GovernanceReadApiV2,
     indexer_reader::IndexerReader,
     models_v2::{
-        checkpoints::StoredCheckpoint, epoch::StoredEpochInfo, objects::StoredObject,
+        checkpoints::StoredCheckpoint,
+        epoch::StoredEpochInfo,
+        events::StoredEvent,
+        objects::{ObjectStatus, StoredHistoryObject, StoredObject},
         transactions::StoredTransaction,
+        tx_indices::StoredTxSponsor,
     },
     schema_v2::{
-        checkpoints, epochs, objects, transactions, tx_calls, tx_changed_objects, tx_input_objects,
-        tx_recipients, tx_senders,
+        checkpoints, epochs, events, objects, objects_history, transactions, tx_balance_changes,
+        tx_calls, tx_changed_objects, tx_input_objects, tx_recipients, tx_senders, tx_sponsors,
     },
-    types_v2::OwnerType,
+    types_v2::{IndexedObjectChange, OwnerType},
     PgConnectionPoolConfig,
 };
 use sui_json_rpc::{
     coin_api::parse_to_type_tag,
+    governance_api::ValidatorExchangeRates,
     name_service::{Domain, NameRecord, NameServiceConfig},
 };
 use sui_json_rpc_types::{
-    EventFilter as RpcEventFilter, ProtocolConfigResponse, Stake as SuiStake,
-    SuiTransactionBlockEffects,
+    EventFilter as RpcEventFilter, ProtocolConfigResponse, Stake as SuiStake, SuiCoinMetadata,
+    SuiEvent, SuiTransactionBlockEffects,
 };
 use sui_protocol_config::{ProtocolConfig, ProtocolVersion};
 use sui_sdk::types::{
     base_types::SuiAddress as NativeSuiAddress,
     digests::ChainIdentifier,
-    effects::TransactionEffects,
+    effects::{TransactionEffects, TransactionEffectsAPI},
     messages_checkpoint::{
         CheckpointCommitment, CheckpointDigest, EndOfEpochData as NativeEndOfEpochData,
     },
@@ -92,6 +107,7 @@ use sui_sdk::types::{
 use sui_types::{
     base_types::{MoveObjectType, ObjectID},
     digests::TransactionDigest,
+    display::DisplayVersionUpdatedEvent,
     dynamic_field::{DynamicFieldType, Field},
     event::EventID,
     governance::StakedSui,
@@ -116,6 +132,12 @@ pub enum DbValidationError {
     RequiresPackageAndModule,
     #[error("Requires package")]
     RequiresPackage,
+    #[error("A qualified function ('package::module::function') cannot be combined with 'package' or 'module'")]
+    QualifiedFunctionWithPackageOrModule,
+    #[error("Invalid function '{0}'. Expected 'package', 'package::module' or 'package::module::function'")]
+    InvalidQualifiedFunction(String),
+    #[error("Filtering transaction blocks by the address that paid for gas is not currently supported, use 'sponsorAddress' instead")]
+    UnsupportedPaidAddress,
     #[error("'first' can only be used with 'after")]
     FirstAfter,
     #[error("'last' can only be used with 'before'")]
@@ -126,6 +148,119 @@ pub enum DbValidationError {
     InvalidOwnerType,
     #[error("Query cost exceeded - cost: {0}, limit: {1}")]
     QueryCostExceeded(u64, u64),
+    #[error("Start checkpoint must not be greater than end checkpoint")]
+    InvalidExportCheckpointRange,
+    #[error("Export batch size exceeded - requested: {0}, limit: {1}")]
+    ExportBatchSizeExceeded(u64, u64),
+    #[error("Export batch size must be greater than 0")]
+    EmptyExportBatch,
+    #[error("Too many objects requested - requested: {0}, limit: {1}")]
+    MultiGetObjectsLimitExceeded(u64, u64),
+    #[error("Page size exceeded - requested: {0}, limit: {1}")]
+    PageSizeExceeded(u64, u64),
+}
+
+/// The number of transaction blocks to export in a batch, given the `limit` requested. An empty
+/// batch would never make progress, so a limit of 0 is rejected.
+fn export_batch_limit(limit: Option<u64>, max_batch_size: u64) -> Result<u64, Error> {
+    let limit = limit.unwrap_or(max_batch_size);
+    if limit == 0 {
+        return Err(DbValidationError::EmptyExportBatch.into());
+    }
+    if limit > max_batch_size {
+        return Err(DbValidationError::ExportBatchSizeExceeded(limit, max_batch_size).into());
+    }
+    Ok(limit)
+}
+
+/// The snapshots of a page of balance `changes` per checkpoint, in ascending order of checkpoint,
+/// given the `total_balance` after the last of them. Each earlier total is the next one without
+/// the change of the next checkpoint.
+fn balance_snapshots(
+    changes: Vec<(i64, i128)>,
+    mut total_balance: i128,
+) -> Vec<Edge<String, BalanceSnapshot>> {
+    let mut snapshots = vec![];
+    for (checkpoint, change) in changes.into_iter().rev() {
+        snapshots.push(Edge::new(
+            checkpoint.to_string(),
+            BalanceSnapshot {
+                checkpoint_sequence_number: checkpoint as u64,
+                balance_change: BigInt::from(change),
+                total_balance: BigInt::from(total_balance),
+            },
+        ));
+        total_balance -= change;
+    }
+    snapshots.reverse();
+    snapshots
+}
+
+/// The statistics of a sponsor, from its `tx_sponsors` rows aggregated into the number of
+/// transactions, the number of distinct senders, and the largest gas budget and price. An address
+/// that has not sponsored any transactions has no statistics.
+fn gas_sponsor_stats(
+    (count, sender_count, max_gas_budget, max_gas_price): (
+        Option<i64>,
+        Option<i64>,
+        Option<i64>,
+        Option<i64>,
+    ),
+) -> Option<GasSponsorStats> {
+    let count = count.filter(|count| *count > 0)?;
+    Some(GasSponsorStats {
+        sponsored_transaction_count: Some(count as u64),
+        sender_count: sender_count.map(|c| c as u64),
+        max_gas_budget: max_gas_budget.map(BigInt::from),
+        max_gas_price: max_gas_price.map(BigInt::from),
+    })
+}
+
+/// The sum of some balance changes, as read by [BALANCE_CHANGE_SUM]. There are no changes to sum
+/// if it is `None`.
+fn parse_balance_change_sum(sum: Option<String>) -> Result<i128, Error> {
+    let Some(sum) = sum else {
+        return Ok(0);
+    };
+    sum.parse()
+        .map_err(|e| Error::Internal(format!("Failed to parse balance change sum {sum}: {e}")))
+}
+
+/// The checkpoint that a page of a connection over objects is consistent with.
+#[derive(Clone, Copy, Debug)]
+enum Snapshot {
+    /// The checkpoint in the cursor of a page after the first, as of which the objects of the page
+    /// are read.
+    Cursor(i64),
+    /// The latest checkpoint when the first page of the connection was fetched.
+    Latest(i64),
+}
+
+/// Cursors of connections over objects, which are modified in place, are of the form
+/// `{checkpoint}:{position}`, where `checkpoint` is the latest checkpoint when the first page of
+/// the connection was fetched, as of which the later pages are read. `position` contains
+/// `separators` colons. Cursors from before the checkpoint was added are just the position, and
+/// have no checkpoint.
+fn parse_snapshot_cursor(cursor: &str, separators: usize) -> Result<(Option<i64>, &str), Error> {
+    let invalid = || Error::InvalidCursor(cursor.to_string());
+    if cursor.matches(':').count() == separators {
+        return Ok((None, cursor));
+    }
+    let (checkpoint, position) = cursor.split_once(':').ok_or_else(invalid)?;
+    let checkpoint = checkpoint.parse::<i64>().map_err(|_| invalid())?;
+    Ok((Some(checkpoint), position))
+}
+
+/// The checkpoint that the page of `stored_objs` is consistent with, to include in its cursors.
+fn page_checkpoint(snapshot: Snapshot, stored_objs: &[StoredObject]) -> i64 {
+    match snapshot {
+        Snapshot::Cursor(checkpoint) => checkpoint,
+        // Objects may have been indexed since the latest checkpoint was fetched.
+        Snapshot::Latest(checkpoint) => stored_objs
+            .iter()
+            .map(|o| o.checkpoint_sequence_number)
+            .fold(checkpoint, i64::max),
+    }
 }
 
 type BalanceQuery<'a> = BoxedSelectStatement<
@@ -199,8 +334,240 @@ pub fn extract_cost(explain_result: &str) -> Result<f64, Error> {
     }
 }
 
+type GasSponsorQuery<'a> = BoxedSelectStatement<
+    'a,
+    (
+        diesel::sql_types::Nullable<diesel::sql_types::BigInt>,
+        diesel::sql_types::Nullable<diesel::sql_types::BigInt>,
+        diesel::sql_types::Nullable<diesel::sql_types::BigInt>,
+        diesel::sql_types::Nullable<diesel::sql_types::BigInt>,
+    ),
+    FromClause<tx_sponsors::table>,
+    Pg,
+>;
+
+type BalanceHistoryQuery<'a> = BoxedSelectStatement<
+    'a,
+    (
+        diesel::sql_types::BigInt,
+        diesel::sql_types::Nullable<diesel::sql_types::Text>,
+    ),
+    FromClause<tx_balance_changes::table>,
+    Pg,
+    tx_balance_changes::dsl::checkpoint_sequence_number,
+>;
+
+/// The sum of the balance changes selected by a query, as a decimal string. The sums of each part
+/// of the amounts are NUMERIC, so they are combined without overflowing, and the result is read
+/// back as text because it may not fit in a BIGINT.
+const BALANCE_CHANGE_SUM: &str = "CAST(SUM(amount_high) * 4294967296 + SUM(amount_low) AS TEXT)";
+
+type BalanceChangeSumQuery<'a> = BoxedSelectStatement<
+    'a,
+    diesel::sql_types::Nullable<diesel::sql_types::Text>,
+    FromClause<tx_balance_changes::table>,
+    Pg,
+>;
+
+/// Defines the queries of the pages of connections over objects on `$table`, which is either
+/// `objects`, for the latest versions of the objects, or `objects_history`, for their versions as
+/// of a checkpoint (see [QueryBuilder::as_of_checkpoint]). Both tables have the same columns.
+macro_rules! object_page_queries {
+    (
+        $table:ident,
+        $multi_get_coins:ident,
+        $multi_get_coins_by_balance:ident,
+        $multi_get_objs:ident
+    ) => {
+        fn $multi_get_coins<'a>(
+            cursor: Option<Vec<u8>>,
+            descending_order: bool,
+            limit: i64,
+            address: Vec<u8>,
+            coin_type: Option<String>,
+        ) -> $table::BoxedQuery<'a, Pg> {
+            let mut query = $table::table.into_boxed();
+            if let Some(cursor) = cursor {
+                if descending_order {
+                    query = query.filter($table::dsl::object_id.lt(cursor));
+                } else {
+                    query = query.filter($table::dsl::object_id.gt(cursor));
+                }
+            }
+            if descending_order {
+                query = query.order($table::dsl::object_id.desc());
+            } else {
+                query = query.order($table::dsl::object_id.asc());
+            }
+            query = query.limit(limit + 1);
+
+            query = query
+                .filter($table::dsl::owner_id.eq(address))
+                .filter($table::dsl::owner_type.eq(OwnerType::Address as i16)); // Leverage index on objects table
+
+            if let Some(coin_type) = coin_type {
+                query = query.filter($table::dsl::coin_type.eq(coin_type));
+            }
+            query
+        }
+
+        /// Coins of `coin_type` owned by `address`, ordered by balance and then by object ID, with at
+        /// least `min_balance`, if one is given.
+        fn $multi_get_coins_by_balance<'a>(
+            cursor: Option<(i64, Vec<u8>)>,
+            descending_order: bool,
+            limit: i64,
+            address: Vec<u8>,
+            coin_type: String,
+            min_balance: Option<i64>,
+        ) -> $table::BoxedQuery<'a, Pg> {
+            let mut query = $table::table
+                .filter($table::dsl::owner_id.eq(address))
+                .filter($table::dsl::owner_type.eq(OwnerType::Address as i16))
+                .filter($table::dsl::coin_type.eq(coin_type))
+                .into_boxed();
+
+            if let Some(min_balance) = min_balance {
+                query = query.filter($table::dsl::coin_balance.ge(min_balance));
+            }
+
+            if let Some((balance, object_id)) = cursor {
+                if descending_order {
+                    query = query.filter(
+                        $table::dsl::coin_balance
+                            .lt(balance)
+                            .or($table::dsl::coin_balance
+                                .eq(balance)
+                                .and($table::dsl::object_id.lt(object_id))),
+                    );
+                } else {
+                    query = query.filter(
+                        $table::dsl::coin_balance
+                            .gt(balance)
+                            .or($table::dsl::coin_balance
+                                .eq(balance)
+                                .and($table::dsl::object_id.gt(object_id))),
+                    );
+                }
+            }
+
+            if descending_order {
+                query = query.order((
+                    $table::dsl::coin_balance.desc(),
+                    $table::dsl::object_id.desc(),
+                ));
+            } else {
+                query = query.order((
+                    $table::dsl::coin_balance.asc(),
+                    $table::dsl::object_id.asc(),
+                ));
+            }
+
+            query.limit(limit + 1)
+        }
+
+        fn $multi_get_objs<'a>(
+            cursor: Option<Vec<u8>>,
+            descending_order: bool,
+            limit: i64,
+            filter: Option<ObjectFilter>,
+            owner_type: Option<OwnerType>,
+        ) -> Result<$table::BoxedQuery<'a, Pg>, Error> {
+            let mut query = $table::table.into_boxed();
+
+            if let Some(cursor) = cursor {
+                if descending_order {
+                    query = query.filter($table::dsl::object_id.lt(cursor));
+                } else {
+                    query = query.filter($table::dsl::object_id.gt(cursor));
+                }
+            }
+
+            if descending_order {
+                query = query.order($table::dsl::object_id.desc());
+            } else {
+                query = query.order($table::dsl::object_id.asc());
+            }
+
+            query = query.limit(limit + 1);
+
+            if let Some(filter) = filter {
+                if let Some(object_ids) = filter.object_ids {
+                    query = query.filter(
+                        $table::dsl::object_id.eq_any(
+                            object_ids
+                                .into_iter()
+                                .map(|id| id.into_vec())
+                                .collect::<Vec<_>>(),
+                        ),
+                    );
+                }
+
+                if let Some(owner) = filter.owner {
+                    query = query.filter($table::dsl::owner_id.eq(owner.into_vec()));
+
+                    match owner_type {
+                        Some(OwnerType::Address) => {
+                            query =
+                                query.filter($table::dsl::owner_type.eq(OwnerType::Address as i16));
+                        }
+                        Some(OwnerType::Object) => {
+                            query =
+                                query.filter($table::dsl::owner_type.eq(OwnerType::Object as i16));
+                        }
+                        None => {
+                            query = query.filter(
+                                $table::dsl::owner_type
+                                    .eq(OwnerType::Address as i16)
+                                    .or($table::dsl::owner_type.eq(OwnerType::Object as i16)),
+                            );
+                        }
+                        _ => Err(DbValidationError::InvalidOwnerType)?,
+                    }
+                }
+
+                if let Some(object_type) = filter.ty {
+                    query = query.filter($table::dsl::object_type.eq(object_type));
+                }
+            }
+
+            Ok(query)
+        }
+    };
+}
+
 pub struct QueryBuilder;
 impl QueryBuilder {
+    object_page_queries!(
+        objects,
+        multi_get_coins,
+        multi_get_coins_by_balance,
+        multi_get_objs
+    );
+    object_page_queries!(
+        objects_history,
+        multi_get_coins_history,
+        multi_get_coins_by_balance_history,
+        multi_get_objs_history
+    );
+
+    /// Keeps the rows of `objects_history` that hold the version of each object as of
+    /// `checkpoint`, leaving out the objects that did not exist then. The filters of a query of
+    /// objects apply to these versions.
+    fn as_of_checkpoint(checkpoint: i64) -> SqlLiteral<diesel::sql_types::Bool> {
+        diesel::dsl::sql::<diesel::sql_types::Bool>(&format!(
+            "objects_history.checkpoint_sequence_number <= {checkpoint} \
+             AND objects_history.object_status = {active} \
+             AND NOT EXISTS (\
+                 SELECT 1 FROM objects_history later \
+                 WHERE later.object_id = objects_history.object_id \
+                 AND later.checkpoint_sequence_number <= {checkpoint} \
+                 AND later.object_version > objects_history.object_version\
+             )",
+            active = ObjectStatus::Active as i16,
+        ))
+    }
+
     fn get_tx_by_digest<'a>(digest: Vec<u8>) -> transactions::BoxedQuery<'a, Pg> {
         transactions::dsl::transactions
             .filter(transactions::dsl::transaction_digest.eq(digest))
@@ -217,12 +584,142 @@ impl QueryBuilder {
         query
     }
 
+    /// The version of the object at `address` that is either exactly `version`, or the latest as
+    /// of `checkpoint`. This may be a tombstone, if the object was deleted or wrapped by then.
+    fn get_obj_at<'a>(
+        address: Vec<u8>,
+        version: Option<i64>,
+        checkpoint: Option<i64>,
+    ) -> objects_history::BoxedQuery<'a, Pg> {
+        let mut query = objects_history::dsl::objects_history.into_boxed();
+        query = query.filter(objects_history::dsl::object_id.eq(address));
+
+        if let Some(version) = version {
+            query = query.filter(objects_history::dsl::object_version.eq(version));
+        }
+
+        if let Some(checkpoint) = checkpoint {
+            query = query.filter(objects_history::dsl::checkpoint_sequence_number.le(checkpoint));
+        }
+
+        query
+            .order(objects_history::dsl::object_version.desc())
+            .limit(1)
+    }
+
+    fn multi_get_objs_by_ids<'a>(addresses: Vec<Vec<u8>>) -> objects::BoxedQuery<'a, Pg> {
+        objects::dsl::objects
+            .filter(objects::dsl::object_id.eq_any(addresses))
+            .into_boxed()
+    }
+
+    fn get_dynamic_field<'a>(
+        parent: Vec<u8>,
+        name_type: String,
+        name_bcs: Vec<u8>,
+    ) -> objects::BoxedQuery<'a, Pg> {
+        objects::dsl::objects
+            .filter(objects::dsl::owner_id.eq(parent))
+            .filter(objects::dsl::owner_type.eq(OwnerType::Object as i16))
+            .filter(objects::dsl::df_name_type.eq(name_type))
+            .filter(objects::dsl::df_name_bcs.eq(name_bcs))
+            .into_boxed()
+    }
+
+    fn get_tx_sponsor<'a>(tx_sequence_number: i64) -> tx_sponsors::BoxedQuery<'a, Pg> {
+        tx_sponsors::dsl::tx_sponsors
+            .filter(tx_sponsors::dsl::tx_sequence_number.eq(tx_sequence_number))
+            .limit(1)
+            .into_boxed()
+    }
+
+    fn get_gas_sponsor<'a>(sponsor: Vec<u8>) -> GasSponsorQuery<'a> {
+        tx_sponsors::dsl::tx_sponsors
+            .select((
+                diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::BigInt>>(
+                    "COUNT(*)",
+                ),
+                diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::BigInt>>(
+                    "COUNT(DISTINCT sender)",
+                ),
+                diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::BigInt>>(
+                    "MAX(gas_budget)",
+                ),
+                diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::BigInt>>(
+                    "MAX(gas_price)",
+                ),
+            ))
+            .filter(tx_sponsors::dsl::sponsor.eq(sponsor))
+            .into_boxed()
+    }
+
+    fn multi_get_balance_changes<'a>(
+        owner: Vec<u8>,
+        coin_type: String,
+        cursor: Option<i64>,
+        descending_order: bool,
+        limit: i64,
+    ) -> BalanceHistoryQuery<'a> {
+        let mut query = tx_balance_changes::dsl::tx_balance_changes
+            .group_by(tx_balance_changes::dsl::checkpoint_sequence_number)
+            .select((
+                tx_balance_changes::dsl::checkpoint_sequence_number,
+                diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::Text>>(
+                    BALANCE_CHANGE_SUM,
+                ),
+            ))
+            .filter(tx_balance_changes::dsl::owner.eq(owner))
+            .filter(tx_balance_changes::dsl::coin_type.eq(coin_type))
+            .into_boxed();
+
+        if let Some(cursor) = cursor {
+            if descending_order {
+                query =
+                    query.filter(tx_balance_changes::dsl::checkpoint_sequence_number.lt(cursor));
+            } else {
+                query =
+                    query.filter(tx_balance_changes::dsl::checkpoint_sequence_number.gt(cursor));
+            }
+        }
+
+        if descending_order {
+            query = query.order(tx_balance_changes::dsl::checkpoint_sequence_number.desc());
+        } else {
+            query = query.order(tx_balance_changes::dsl::checkpoint_sequence_number.asc());
+        }
+
+        query.limit(limit + 1)
+    }
+
+    fn get_balance_change_after<'a>(
+        owner: Vec<u8>,
+        coin_type: String,
+        checkpoint_sequence_number: i64,
+    ) -> BalanceChangeSumQuery<'a> {
+        tx_balance_changes::dsl::tx_balance_changes
+            .select(diesel::dsl::sql::<
+                diesel::sql_types::Nullable<diesel::sql_types::Text>,
+            >(BALANCE_CHANGE_SUM))
+            .filter(tx_balance_changes::dsl::owner.eq(owner))
+            .filter(tx_balance_changes::dsl::coin_type.eq(coin_type))
+            .filter(
+                tx_balance_changes::dsl::checkpoint_sequence_number.gt(checkpoint_sequence_number),
+            )
+            .into_boxed()
+    }
+
     fn get_epoch<'a>(epoch_id: i64) -> epochs::BoxedQuery<'a, Pg> {
         epochs::dsl::epochs
             .filter(epochs::dsl::epoch.eq(epoch_id))
             .into_boxed()
     }
 
+    fn multi_get_epochs<'a>(epoch_ids: Vec<i64>) -> epochs::BoxedQuery<'a, Pg> {
+        epochs::dsl::epochs
+            .filter(epochs::dsl::epoch.eq_any(epoch_ids))
+            .into_boxed()
+    }
+
     fn get_latest_epoch<'a>() -> epochs::BoxedQuery<'a, Pg> {
         epochs::dsl::epochs
             .order_by(epochs::dsl::epoch.desc())
@@ -236,6 +733,14 @@ impl QueryBuilder {
             .into_boxed()
     }
 
+    fn multi_get_checkpoints_by_sequence_numbers<'a>(
+        sequence_numbers: Vec<i64>,
+    ) -> checkpoints::BoxedQuery<'a, Pg> {
+        checkpoints::dsl::checkpoints
+            .filter(checkpoints::dsl::sequence_number.eq_any(sequence_numbers))
+            .into_boxed()
+    }
+
     fn get_checkpoint_by_sequence_number<'a>(
         sequence_number: i64,
     ) -> checkpoints::BoxedQuery<'a, Pg> {
@@ -261,24 +766,23 @@ impl QueryBuilder {
     ) -> Result<transactions::BoxedQuery<'a, Pg>, Error> {
         let mut query = transactions::dsl::transactions.into_boxed();
 
-        if let Some(cursor_val) = cursor {
+        // Exclusive bounds on the sequence numbers of the transactions in the page, combining the
+        // cursor with the checkpoint filters. They are also applied to the lookups in the other
+        // tables, whose indices end in the sequence number, to limit the range those scan.
+        let (mut lower, mut upper) = (after_tx_seq_num, before_tx_seq_num);
+        if let Some(cursor) = cursor {
             if descending_order {
-                let filter_value =
-                    before_tx_seq_num.map_or(cursor_val, |b| std::cmp::min(b, cursor_val));
-                query = query.filter(transactions::dsl::tx_sequence_number.lt(filter_value));
+                upper = Some(upper.map_or(cursor, |u| u.min(cursor)));
             } else {
-                let filter_value =
-                    after_tx_seq_num.map_or(cursor_val, |a| std::cmp::max(a, cursor_val));
-                query = query.filter(transactions::dsl::tx_sequence_number.gt(filter_value));
-            }
-        } else {
-            if let Some(av) = after_tx_seq_num {
-                query = query.filter(transactions::dsl::tx_sequence_number.gt(av));
-            }
-            if let Some(bv) = before_tx_seq_num {
-                query = query.filter(transactions::dsl::tx_sequence_number.lt(bv));
+                lower = Some(lower.map_or(cursor, |l| l.max(cursor)));
             }
         }
+        let lower = lower.unwrap_or(-1);
+        let upper = upper.unwrap_or(i64::MAX);
+
+        query = query
+            .filter(transactions::dsl::tx_sequence_number.gt(lower))
+            .filter(transactions::dsl::tx_sequence_number.lt(upper));
 
         if descending_order {
             query = query.order(transactions::dsl::tx_sequence_number.desc());
@@ -288,229 +792,358 @@ impl QueryBuilder {
 
         query = query.limit(limit + 1);
 
-        if let Some(filter) = filter {
-            // Filters for transaction table
-            // at_checkpoint mutually exclusive with before_ and after_checkpoint
-            if let Some(checkpoint) = filter.at_checkpoint {
-                query = query
-                    .filter(transactions::dsl::checkpoint_sequence_number.eq(checkpoint as i64));
+        let Some(filter) = filter else {
+            return Ok(query);
+        };
+
+        // Filters for transaction table
+        if let Some(kind) = filter.kind {
+            query = query.filter(transactions::dsl::transaction_kind.eq(kind as i16));
+        }
+        if let Some(transaction_ids) = filter.transaction_ids {
+            let digests = transaction_ids
+                .into_iter()
+                .map(|id| Ok::<Vec<u8>, Error>(Digest::from_str(&id)?.into_vec()))
+                .collect::<Result<Vec<_>, _>>()?;
+            query = query.filter(transactions::dsl::transaction_digest.eq_any(digests));
+        }
+
+        // Queries on foreign tables
+        if let Some(package) = filter.package {
+            let mut subquery = tx_calls::dsl::tx_calls
+                .filter(tx_calls::dsl::package.eq(package.into_vec()))
+                .filter(tx_calls::dsl::tx_sequence_number.gt(lower))
+                .filter(tx_calls::dsl::tx_sequence_number.lt(upper))
+                .select(tx_calls::dsl::tx_sequence_number)
+                .into_boxed();
+
+            if let Some(module) = filter.module {
+                subquery = subquery.filter(tx_calls::dsl::module.eq(module));
             }
-            if let Some(transaction_ids) = filter.transaction_ids {
-                let digests = transaction_ids
-                    .into_iter()
-                    .map(|id| Ok::<Vec<u8>, Error>(Digest::from_str(&id)?.into_vec()))
-                    .collect::<Result<Vec<_>, _>>()?;
-                query = query.filter(transactions::dsl::transaction_digest.eq_any(digests));
+            if let Some(function) = filter.function {
+                subquery = subquery.filter(tx_calls::dsl::func.eq(function));
             }
 
-            // Queries on foreign tables
-            match (filter.package, filter.module, filter.function) {
-                (Some(p), None, None) => {
-                    let subquery = tx_calls::dsl::tx_calls
-                        .filter(tx_calls::dsl::package.eq(p.into_vec()))
-                        .select(tx_calls::dsl::tx_sequence_number);
-
-                    query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-                }
-                (Some(p), Some(m), None) => {
-                    let subquery = tx_calls::dsl::tx_calls
-                        .filter(tx_calls::dsl::package.eq(p.into_vec()))
-                        .filter(tx_calls::dsl::module.eq(m))
-                        .select(tx_calls::dsl::tx_sequence_number);
+            query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+        }
 
-                    query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-                }
-                (Some(p), Some(m), Some(f)) => {
-                    let subquery = tx_calls::dsl::tx_calls
-                        .filter(tx_calls::dsl::package.eq(p.into_vec()))
-                        .filter(tx_calls::dsl::module.eq(m))
-                        .filter(tx_calls::dsl::func.eq(f))
-                        .select(tx_calls::dsl::tx_sequence_number);
-
-                    query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-                }
-                _ => {}
-            }
+        let senders: Vec<_> = [filter.sign_address, filter.sent_address]
+            .into_iter()
+            .flatten()
+            .map(|address| address.into_vec())
+            .collect();
+        if !senders.is_empty() {
+            let subquery = tx_senders::dsl::tx_senders
+                .filter(tx_senders::dsl::sender.eq_any(senders))
+                .filter(tx_senders::dsl::tx_sequence_number.gt(lower))
+                .filter(tx_senders::dsl::tx_sequence_number.lt(upper))
+                .select(tx_senders::dsl::tx_sequence_number);
+
+            query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+        }
+        if let Some(recipient) = filter.recv_address {
+            let subquery = tx_recipients::dsl::tx_recipients
+                .filter(tx_recipients::dsl::recipient.eq(recipient.into_vec()))
+                .filter(tx_recipients::dsl::tx_sequence_number.gt(lower))
+                .filter(tx_recipients::dsl::tx_sequence_number.lt(upper))
+                .select(tx_recipients::dsl::tx_sequence_number);
+
+            query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+        }
+        if let Some(sponsor) = filter.sponsor_address {
+            let subquery = tx_sponsors::dsl::tx_sponsors
+                .filter(tx_sponsors::dsl::sponsor.eq(sponsor.into_vec()))
+                .filter(tx_sponsors::dsl::tx_sequence_number.gt(lower))
+                .filter(tx_sponsors::dsl::tx_sequence_number.lt(upper))
+                .select(tx_sponsors::dsl::tx_sequence_number);
+
+            query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+        }
 
-            if let Some(signer) = filter.sign_address {
-                if let Some(sender) = filter.sent_address {
-                    let subquery = tx_senders::dsl::tx_senders
-                        .filter(
-                            tx_senders::dsl::sender
-                                .eq(signer.into_vec())
-                                .or(tx_senders::dsl::sender.eq(sender.into_vec())),
-                        )
-                        .select(tx_senders::dsl::tx_sequence_number);
-
-                    query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-                } else {
-                    let subquery = tx_senders::dsl::tx_senders
-                        .filter(tx_senders::dsl::sender.eq(signer.into_vec()))
-                        .select(tx_senders::dsl::tx_sequence_number);
+        if let Some(input_object) = filter.input_object {
+            let subquery = tx_input_objects::dsl::tx_input_objects
+                .filter(tx_input_objects::dsl::object_id.eq(input_object.into_vec()))
+                .filter(tx_input_objects::dsl::tx_sequence_number.gt(lower))
+                .filter(tx_input_objects::dsl::tx_sequence_number.lt(upper))
+                .select(tx_input_objects::dsl::tx_sequence_number);
 
-                    query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-                }
-            } else if let Some(sender) = filter.sent_address {
-                let subquery = tx_senders::dsl::tx_senders
-                    .filter(tx_senders::dsl::sender.eq(sender.into_vec()))
-                    .select(tx_senders::dsl::tx_sequence_number);
+            query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+        }
+        if let Some(changed_object) = filter.changed_object {
+            let subquery = tx_changed_objects::dsl::tx_changed_objects
+                .filter(tx_changed_objects::dsl::object_id.eq(changed_object.into_vec()))
+                .filter(tx_changed_objects::dsl::tx_sequence_number.gt(lower))
+                .filter(tx_changed_objects::dsl::tx_sequence_number.lt(upper))
+                .select(tx_changed_objects::dsl::tx_sequence_number);
+
+            query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
+        }
 
-                query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-            }
-            if let Some(recipient) = filter.recv_address {
-                let subquery = tx_recipients::dsl::tx_recipients
-                    .filter(tx_recipients::dsl::recipient.eq(recipient.into_vec()))
-                    .select(tx_recipients::dsl::tx_sequence_number);
+        Ok(query)
+    }
 
-                query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-            }
-            if filter.paid_address.is_some() {
-                return Err(Error::Internal(
-                    "Paid address filter not supported".to_string(),
-                ));
-            }
+    fn multi_get_balances<'a>(address: Vec<u8>) -> BalanceQuery<'a> {
+        let query = objects::dsl::objects
+            .group_by(objects::dsl::coin_type)
+            .select((
+                diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::BigInt>>(
+                    "CAST(SUM(coin_balance) AS BIGINT)",
+                ),
+                diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::BigInt>>(
+                    "COUNT(*)",
+                ),
+                objects::dsl::coin_type,
+            ))
+            .filter(objects::dsl::owner_id.eq(address))
+            .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16))
+            .filter(objects::dsl::coin_type.is_not_null())
+            .into_boxed();
 
-            if let Some(input_object) = filter.input_object {
-                let subquery = tx_input_objects::dsl::tx_input_objects
-                    .filter(tx_input_objects::dsl::object_id.eq(input_object.into_vec()))
-                    .select(tx_input_objects::dsl::tx_sequence_number);
+        query
+    }
 
-                query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-            }
-            if let Some(changed_object) = filter.changed_object {
-                let subquery = tx_changed_objects::dsl::tx_changed_objects
-                    .filter(tx_changed_objects::dsl::object_id.eq(changed_object.into_vec()))
-                    .select(tx_changed_objects::dsl::tx_sequence_number);
+    fn get_txs_for_export<'a>(
+        start_checkpoint: i64,
+        end_checkpoint: i64,
+        cursor: Option<i64>,
+        limit: i64,
+    ) -> transactions::BoxedQuery<'a, Pg> {
+        let mut query = transactions::dsl::transactions
+            .filter(transactions::dsl::checkpoint_sequence_number.ge(start_checkpoint))
+            .filter(transactions::dsl::checkpoint_sequence_number.le(end_checkpoint))
+            .into_boxed();
 
-                query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
-            }
-        };
+        if let Some(cursor) = cursor {
+            query = query.filter(transactions::dsl::tx_sequence_number.gt(cursor));
+        }
 
-        Ok(query)
+        query
+            .order(transactions::dsl::tx_sequence_number.asc())
+            .limit(limit + 1)
     }
 
-    fn multi_get_coins<'a>(
-        cursor: Option<Vec<u8>>,
-        descending_order: bool,
+    /// The transactions after `cursor` that can make changes matching `filter`: those changing
+    /// one of its objects, and those that an owner can be involved in, by sending or sponsoring
+    /// them, by receiving objects from them, or by being an object they take as input.
+    fn get_txs_after<'a>(
+        cursor: Option<i64>,
         limit: i64,
-        address: Vec<u8>,
-        coin_type: Option<String>,
-    ) -> objects::BoxedQuery<'a, Pg> {
-        let mut query = objects::dsl::objects.into_boxed();
-        if let Some(cursor) = cursor {
-            if descending_order {
-                query = query.filter(objects::dsl::object_id.lt(cursor));
-            } else {
-                query = query.filter(objects::dsl::object_id.gt(cursor));
-            }
+        filter: &ObjectChangeFilter,
+    ) -> transactions::BoxedQuery<'a, Pg> {
+        let mut query = transactions::dsl::transactions.into_boxed();
+
+        let lower = cursor.unwrap_or(-1);
+        query = query.filter(transactions::dsl::tx_sequence_number.gt(lower));
+
+        if let Some(object_ids) = &filter.object_ids {
+            let object_ids: Vec<_> = object_ids.iter().map(|id| id.into_vec()).collect();
+            let subquery = tx_changed_objects::dsl::tx_changed_objects
+                .filter(tx_changed_objects::dsl::object_id.eq_any(object_ids))
+                .filter(tx_changed_objects::dsl::tx_sequence_number.gt(lower))
+                .select(tx_changed_objects::dsl::tx_sequence_number);
+
+            query = query.filter(transactions::dsl::tx_sequence_number.eq_any(subquery));
         }
-        if descending_order {
-            query = query.order(objects::dsl::object_id.desc());
-        } else {
-            query = query.order(objects::dsl::object_id.asc());
+        if let Some(owner) = &filter.owner {
+            let senders = tx_senders::dsl::tx_senders
+                .filter(tx_senders::dsl::sender.eq(owner.into_vec()))
+                .filter(tx_senders::dsl::tx_sequence_number.gt(lower))
+                .select(tx_senders::dsl::tx_sequence_number);
+            let sponsors = tx_sponsors::dsl::tx_sponsors
+                .filter(tx_sponsors::dsl::sponsor.eq(owner.into_vec()))
+                .filter(tx_sponsors::dsl::tx_sequence_number.gt(lower))
+                .select(tx_sponsors::dsl::tx_sequence_number);
+            let recipients = tx_recipients::dsl::tx_recipients
+                .filter(tx_recipients::dsl::recipient.eq(owner.into_vec()))
+                .filter(tx_recipients::dsl::tx_sequence_number.gt(lower))
+                .select(tx_recipients::dsl::tx_sequence_number);
+            let inputs = tx_input_objects::dsl::tx_input_objects
+                .filter(tx_input_objects::dsl::object_id.eq(owner.into_vec()))
+                .filter(tx_input_objects::dsl::tx_sequence_number.gt(lower))
+                .select(tx_input_objects::dsl::tx_sequence_number);
+
+            query = query.filter(
+                transactions::dsl::tx_sequence_number
+                    .eq_any(senders)
+                    .or(transactions::dsl::tx_sequence_number.eq_any(sponsors))
+                    .or(transactions::dsl::tx_sequence_number.eq_any(recipients))
+                    .or(transactions::dsl::tx_sequence_number.eq_any(inputs)),
+            );
         }
-        query = query.limit(limit + 1);
 
-        query = query
-            .filter(objects::dsl::owner_id.eq(address))
-            .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16)); // Leverage index on objects table
+        query
+            .order(transactions::dsl::tx_sequence_number.asc())
+            .limit(limit)
+    }
 
-        if let Some(coin_type) = coin_type {
-            query = query.filter(objects::dsl::coin_type.eq(coin_type));
+    /// The given versions of objects, from `objects_history`
+    fn multi_get_history_objs<'a>(
+        versions: Vec<(Vec<u8>, i64)>,
+    ) -> objects_history::BoxedQuery<'a, Pg> {
+        let mut query = objects_history::dsl::objects_history.into_boxed();
+        for (object_id, version) in versions {
+            query = query.or_filter(
+                objects_history::dsl::object_id
+                    .eq(object_id)
+                    .and(objects_history::dsl::object_version.eq(version)),
+            );
         }
         query
     }
 
-    fn multi_get_objs<'a>(
-        cursor: Option<Vec<u8>>,
+    fn get_latest_tx_sequence_number<'a>() -> transactions::BoxedQuery<'a, Pg> {
+        transactions::dsl::transactions
+            .order(transactions::dsl::tx_sequence_number.desc())
+            .limit(1)
+            .into_boxed()
+    }
+
+    fn get_balance<'a>(address: Vec<u8>, coin_type: String) -> BalanceQuery<'a> {
+        let query = QueryBuilder::multi_get_balances(address);
+        query.filter(objects::dsl::coin_type.eq(coin_type))
+    }
+
+    /// Events matching every criterion set in `filter`, emitted strictly between
+    /// `after_checkpoint` and `before_checkpoint`, in the order they were emitted: by transaction
+    /// (and so by checkpoint), then by their position in the transaction.
+    fn multi_get_events<'a>(
+        cursor: Option<(i64, i64)>,
         descending_order: bool,
         limit: i64,
-        filter: Option<ObjectFilter>,
-        owner_type: Option<OwnerType>,
-    ) -> Result<objects::BoxedQuery<'a, Pg>, Error> {
-        let mut query = objects::dsl::objects.into_boxed();
+        filter: EventFilter,
+        after_checkpoint: Option<i64>,
+        before_checkpoint: Option<i64>,
+    ) -> Result<events::BoxedQuery<'a, Pg>, Error> {
+        let mut query = events::dsl::events.into_boxed();
 
-        if let Some(cursor) = cursor {
+        if let Some((tx, event)) = cursor {
             if descending_order {
-                query = query.filter(objects::dsl::object_id.lt(cursor));
+                query = query.filter(
+                    events::dsl::tx_sequence_number
+                        .lt(tx)
+                        .or(events::dsl::tx_sequence_number
+                            .eq(tx)
+                            .and(events::dsl::event_sequence_number.lt(event))),
+                );
             } else {
-                query = query.filter(objects::dsl::object_id.gt(cursor));
+                query = query.filter(
+                    events::dsl::tx_sequence_number
+                        .gt(tx)
+                        .or(events::dsl::tx_sequence_number
+                            .eq(tx)
+                            .and(events::dsl::event_sequence_number.gt(event))),
+                );
             }
         }
 
-        if descending_order {
-            query = query.order(objects::dsl::object_id.desc());
-        } else {
-            query = query.order(objects::dsl::object_id.asc());
+        if let Some(checkpoint) = after_checkpoint {
+            query = query.filter(events::dsl::checkpoint_sequence_number.gt(checkpoint));
         }
 
-        query = query.limit(limit + 1);
+        if let Some(checkpoint) = before_checkpoint {
+            query = query.filter(events::dsl::checkpoint_sequence_number.lt(checkpoint));
+        }
 
-        if let Some(filter) = filter {
-            if let Some(object_ids) = filter.object_ids {
-                query = query.filter(
-                    objects::dsl::object_id.eq_any(
-                        object_ids
-                            .into_iter()
-                            .map(|id| id.into_vec())
-                            .collect::<Vec<_>>(),
-                    ),
-                );
-            }
+        if let Some(sender) = filter.sender {
+            let subquery = tx_senders::dsl::tx_senders
+                .filter(tx_senders::dsl::sender.eq(sender.into_vec()))
+                .select(tx_senders::dsl::tx_sequence_number);
 
-            if let Some(owner) = filter.owner {
-                query = query.filter(objects::dsl::owner_id.eq(owner.into_vec()));
+            query = query.filter(events::dsl::tx_sequence_number.eq_any(subquery));
+        }
 
-                match owner_type {
-                    Some(OwnerType::Address) => {
-                        query =
-                            query.filter(objects::dsl::owner_type.eq(OwnerType::Address as i16));
-                    }
-                    Some(OwnerType::Object) => {
-                        query = query.filter(objects::dsl::owner_type.eq(OwnerType::Object as i16));
-                    }
-                    None => {
-                        query = query.filter(
-                            objects::dsl::owner_type
-                                .eq(OwnerType::Address as i16)
-                                .or(objects::dsl::owner_type.eq(OwnerType::Object as i16)),
-                        );
-                    }
-                    _ => Err(DbValidationError::InvalidOwnerType)?,
+        if let Some(digest) = filter.transaction_digest {
+            let digest = Digest::from_str(&digest)
+                .map_err(|_| Error::InvalidFilter)?
+                .into_vec();
+            let subquery = transactions::dsl::transactions
+                .filter(transactions::dsl::transaction_digest.eq(digest))
+                .select(transactions::dsl::tx_sequence_number);
+
+            query = query.filter(events::dsl::tx_sequence_number.eq_any(subquery));
+        }
+
+        match (filter.emitting_package, filter.emitting_module) {
+            (Some(package), module) => {
+                query = query.filter(events::dsl::package.eq(package.into_vec()));
+                if let Some(module) = module {
+                    query = query.filter(events::dsl::module.eq(module));
                 }
             }
+            (None, Some(_)) => return Err(Error::InvalidFilter),
+            (None, None) => {}
+        }
 
-            if let Some(object_type) = filter.ty {
-                query = query.filter(objects::dsl::object_type.eq(object_type));
+        if let Some(event_type) = filter.event_type {
+            let event_type = StructTag::from_str(&event_type).map_err(|_| Error::InvalidFilter)?;
+            query = query.filter(
+                events::dsl::event_type.eq(event_type.to_canonical_string(/* with_prefix */ true)),
+            );
+        }
+
+        // Event types are stored in canonical form, `{package}::{module}::{name}<{params}>`, so
+        // types defined in a package or module share a prefix.
+        let event_type_prefix = match (filter.event_package, filter.event_module) {
+            (Some(package), Some(module)) => {
+                let module = Identifier::from_str(&module).map_err(|_| Error::InvalidFilter)?;
+                Some(format!("{package}::{module}::"))
             }
+            (Some(package), None) => Some(format!("{package}::")),
+            (None, Some(_)) => return Err(Error::InvalidFilter),
+            (None, None) => None,
+        };
+
+        if let Some(prefix) = event_type_prefix {
+            // `_` is a wildcard in LIKE patterns, but may appear in module names.
+            let pattern = format!("{}%", prefix.replace('_', "\\_"));
+            query = query.filter(events::dsl::event_type.like(pattern));
         }
 
+        if descending_order {
+            query = query.order((
+                events::dsl::tx_sequence_number.desc(),
+                events::dsl::event_sequence_number.desc(),
+            ));
+        } else {
+            query = query.order((
+                events::dsl::tx_sequence_number.asc(),
+                events::dsl::event_sequence_number.asc(),
+            ));
+        }
+
+        query = query.limit(limit + 1);
+
         Ok(query)
     }
 
-    fn multi_get_balances<'a>(address: Vec<u8>) -> BalanceQuery<'a> {
-        let query = objects::dsl::objects
-            .group_by(objects::dsl::coin_type)
-            .select((
-                diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::BigInt>>(
-                    "CAST(SUM(coin_balance) AS BIGINT)",
-                ),
-                diesel::dsl::sql::<diesel::sql_types::Nullable<diesel::sql_types::BigInt>>(
-                    "COUNT(*)",
-                ),
-                objects::dsl::coin_type,
-            ))
-            .filter(objects::dsl::owner_id.eq(address))
-            .filter(objects::dsl::owner_type.eq(OwnerType::Address as i16))
-            .filter(objects::dsl::coin_type.is_not_null())
+    fn multi_get_obj_versions<'a>(
+        address: Vec<u8>,
+        before_version: i64,
+        cursor: Option<i64>,
+        descending_order: bool,
+        limit: i64,
+    ) -> objects_history::BoxedQuery<'a, Pg> {
+        let mut query = objects_history::dsl::objects_history
+            .filter(objects_history::dsl::object_id.eq(address))
+            .filter(objects_history::dsl::object_version.lt(before_version))
+            .filter(objects_history::dsl::object_status.eq(ObjectStatus::Active as i16))
             .into_boxed();
 
-        query
-    }
+        if let Some(cursor) = cursor {
+            if descending_order {
+                query = query.filter(objects_history::dsl::object_version.lt(cursor));
+            } else {
+                query = query.filter(objects_history::dsl::object_version.gt(cursor));
+            }
+        }
+        if descending_order {
+            query = query.order(objects_history::dsl::object_version.desc());
+        } else {
+            query = query.order(objects_history::dsl::object_version.asc());
+        }
+        query = query.limit(limit + 1);
 
-    fn get_balance<'a>(address: Vec<u8>, coin_type: String) -> BalanceQuery<'a> {
-        let query = QueryBuilder::multi_get_balances(address);
-        query.filter(objects::dsl::coin_type.eq(coin_type))
+        query
     }
 
     fn multi_get_checkpoints<'a>(
@@ -542,6 +1175,7 @@ impl QueryBuilder {
     }
 }
 
+#[derive(Clone)]
 pub(crate) struct PgManager {
     pub inner: IndexerReader,
     pub limits: Limits,
@@ -552,6 +1186,16 @@ impl PgManager {
         Self { inner, limits }
     }
 
+    /// Rejects pages larger than the maximum page size. The query limits checker rejects them
+    /// before the query runs, but it can be disabled.
+    pub(crate) fn validate_page_size(
+        &self,
+        first: &Option<u64>,
+        last: &Option<u64>,
+    ) -> Result<(), Error> {
+        check_page_size(first, last, self.limits.max_page_size)
+    }
+
     /// Create a new underlying reader, which is used by this type as well as other data providers.
     pub(crate) fn reader(db_url: impl Into<String>) -> Result<IndexerReader, Error> {
         let mut config = PgConnectionPoolConfig::default();
@@ -617,25 +1261,219 @@ impl PgManager {
             })
             .await
     }
-}
+}
+
+/// Implement methods to query db and return StoredData
+impl PgManager {
+    async fn get_tx(&self, digest: Vec<u8>) -> Result<Option<StoredTransaction>, Error> {
+        self.run_query_async_with_cost(
+            move || Ok(QueryBuilder::get_tx_by_digest(digest.clone())),
+            |query| move |conn| query.get_result::<StoredTransaction>(conn).optional(),
+        )
+        .await
+    }
+
+    async fn get_tx_sponsor(
+        &self,
+        tx_sequence_number: i64,
+    ) -> Result<Option<StoredTxSponsor>, Error> {
+        self.run_query_async_with_cost(
+            move || Ok(QueryBuilder::get_tx_sponsor(tx_sequence_number)),
+            |query| move |conn| query.get_result::<StoredTxSponsor>(conn).optional(),
+        )
+        .await
+    }
+
+    async fn get_gas_sponsor(
+        &self,
+        sponsor: Vec<u8>,
+    ) -> Result<Option<(Option<i64>, Option<i64>, Option<i64>, Option<i64>)>, Error> {
+        self.run_query_async_with_cost(
+            move || Ok(QueryBuilder::get_gas_sponsor(sponsor.clone())),
+            |query| move |conn| query.get_result(conn).optional(),
+        )
+        .await
+    }
+
+    async fn multi_get_coins_by_balance(
+        &self,
+        address: Vec<u8>,
+        coin_type: String,
+        min_balance: Option<i64>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Option<(Vec<StoredObject>, bool, bool, i64)>, Error> {
+        let descending_order = last.is_some();
+        let (snapshot, cursor) = match after.or(before) {
+            Some(cursor) => {
+                let (checkpoint, position) = parse_snapshot_cursor(&cursor, 1)?;
+                (checkpoint, Some(self.parse_coin_balance_cursor(position)?))
+            }
+            None => (None, None),
+        };
+        let has_cursor = cursor.is_some();
+        let limit = first.or(last).unwrap_or(DEFAULT_PAGE_SIZE) as i64;
+
+        // Coins move between pages when their balances change, so the later pages are read as of
+        // the checkpoint of the first one.
+        let snapshot = self.snapshot_checkpoint(snapshot).await?;
+        let history_query = {
+            let (cursor, address, coin_type) = (cursor.clone(), address.clone(), coin_type.clone());
+            move || {
+                Ok(QueryBuilder::multi_get_coins_by_balance_history(
+                    cursor.clone(),
+                    descending_order,
+                    limit,
+                    address.clone(),
+                    coin_type.clone(),
+                    min_balance,
+                ))
+            }
+        };
+        let query = move || {
+            Ok(QueryBuilder::multi_get_coins_by_balance(
+                cursor.clone(),
+                descending_order,
+                limit,
+                address.clone(),
+                coin_type.clone(),
+                min_balance,
+            ))
+        };
+
+        let result = self.load_objs_page(snapshot, query, history_query).await?;
+        Ok(result.map(|stored_objs| {
+            let (stored_objs, has_previous_page, has_next_page) =
+                page_of_rows(stored_objs, limit, descending_order, has_cursor);
+            let checkpoint = page_checkpoint(snapshot, &stored_objs);
+            (stored_objs, has_previous_page, has_next_page, checkpoint)
+        }))
+    }
+
+    async fn multi_get_balance_changes(
+        &self,
+        owner: Vec<u8>,
+        coin_type: String,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Option<(Vec<(i64, i128)>, bool)>, Error> {
+        let descending_order = last.is_some();
+        let cursor = after
+            .or(before)
+            .map(|cursor| self.parse_checkpoint_cursor(&cursor))
+            .transpose()?;
+        let limit = first.or(last).unwrap_or(DEFAULT_PAGE_SIZE) as i64;
+
+        let query = move || {
+            Ok(QueryBuilder::multi_get_balance_changes(
+                owner.clone(),
+                coin_type.clone(),
+                cursor,
+                descending_order,
+                limit,
+            ))
+        };
+
+        let result: Option<Vec<(i64, Option<String>)>> = self
+            .run_query_async_with_cost(query, |query| move |conn| query.load(conn).optional())
+            .await?;
+        let Some(mut changes) = result else {
+            return Ok(None);
+        };
+
+        let has_next_page = changes.len() as i64 > limit;
+        if has_next_page {
+            changes.pop();
+        }
+
+        let changes = changes
+            .into_iter()
+            .map(|(checkpoint, sum)| Ok((checkpoint, parse_balance_change_sum(sum)?)))
+            .collect::<Result<_, Error>>()?;
+        Ok(Some((changes, has_next_page)))
+    }
+
+    async fn get_balance_change_after(
+        &self,
+        owner: Vec<u8>,
+        coin_type: String,
+        checkpoint_sequence_number: i64,
+    ) -> Result<i128, Error> {
+        let sum: Option<String> = self
+            .run_query_async_with_cost(
+                move || {
+                    Ok(QueryBuilder::get_balance_change_after(
+                        owner.clone(),
+                        coin_type.clone(),
+                        checkpoint_sequence_number,
+                    ))
+                },
+                |query| move |conn| query.get_result(conn),
+            )
+            .await?;
+        parse_balance_change_sum(sum)
+    }
 
-/// Implement methods to query db and return StoredData
-impl PgManager {
-    async fn get_tx(&self, digest: Vec<u8>) -> Result<Option<StoredTransaction>, Error> {
+    async fn get_obj(
+        &self,
+        address: Vec<u8>,
+        version: Option<i64>,
+    ) -> Result<Option<StoredObject>, Error> {
         self.run_query_async_with_cost(
-            move || Ok(QueryBuilder::get_tx_by_digest(digest.clone())),
-            |query| move |conn| query.get_result::<StoredTransaction>(conn).optional(),
+            move || Ok(QueryBuilder::get_obj(address.clone(), version)),
+            |query| move |conn| query.get_result::<StoredObject>(conn).optional(),
         )
         .await
     }
 
-    async fn get_obj(
+    async fn get_obj_at(
         &self,
         address: Vec<u8>,
         version: Option<i64>,
+        checkpoint: Option<i64>,
+    ) -> Result<Option<StoredHistoryObject>, Error> {
+        self.run_query_async_with_cost(
+            move || {
+                Ok(QueryBuilder::get_obj_at(
+                    address.clone(),
+                    version,
+                    checkpoint,
+                ))
+            },
+            |query| move |conn| query.get_result::<StoredHistoryObject>(conn).optional(),
+        )
+        .await
+    }
+
+    async fn multi_get_objs_by_ids(
+        &self,
+        addresses: Vec<Vec<u8>>,
+    ) -> Result<Vec<StoredObject>, Error> {
+        self.run_query_async_with_cost(
+            move || Ok(QueryBuilder::multi_get_objs_by_ids(addresses.clone())),
+            |query| move |conn| query.load(conn),
+        )
+        .await
+    }
+
+    async fn get_dynamic_field(
+        &self,
+        parent: Vec<u8>,
+        name_type: String,
+        name_bcs: Vec<u8>,
     ) -> Result<Option<StoredObject>, Error> {
         self.run_query_async_with_cost(
-            move || Ok(QueryBuilder::get_obj(address.clone(), version)),
+            move || {
+                Ok(QueryBuilder::get_dynamic_field(
+                    parent.clone(),
+                    name_type.clone(),
+                    name_bcs.clone(),
+                ))
+            },
             |query| move |conn| query.get_result::<StoredObject>(conn).optional(),
         )
         .await
@@ -655,6 +1493,29 @@ impl PgManager {
         .await
     }
 
+    async fn multi_get_epochs(&self, epoch_ids: Vec<i64>) -> Result<Vec<StoredEpochInfo>, Error> {
+        self.run_query_async_with_cost(
+            move || Ok(QueryBuilder::multi_get_epochs(epoch_ids.clone())),
+            |query| move |conn| query.load(conn),
+        )
+        .await
+    }
+
+    async fn multi_get_checkpoints_by_sequence_numbers(
+        &self,
+        sequence_numbers: Vec<i64>,
+    ) -> Result<Vec<StoredCheckpoint>, Error> {
+        self.run_query_async_with_cost(
+            move || {
+                Ok(QueryBuilder::multi_get_checkpoints_by_sequence_numbers(
+                    sequence_numbers.clone(),
+                ))
+            },
+            |query| move |conn| query.load(conn),
+        )
+        .await
+    }
+
     async fn get_checkpoint(
         &self,
         digest: Option<Vec<u8>>,
@@ -679,6 +1540,13 @@ impl PgManager {
         .await
     }
 
+    /// The number of transactions in the network up to and including `checkpoint`, if it has been
+    /// indexed.
+    async fn get_network_total_transactions(&self, checkpoint: u64) -> Result<Option<i64>, Error> {
+        let checkpoint = self.get_checkpoint(None, Some(checkpoint as i64)).await?;
+        Ok(checkpoint.map(|c| c.network_total_transactions))
+    }
+
     async fn get_chain_identifier(&self) -> Result<ChainIdentifier, Error> {
         let result = self
             .get_checkpoint(None, Some(0))
@@ -701,39 +1569,44 @@ impl PgManager {
         after: Option<String>,
         last: Option<u64>,
         before: Option<String>,
-    ) -> Result<Option<(Vec<StoredObject>, bool)>, Error> {
+    ) -> Result<Option<(Vec<StoredObject>, bool, i64)>, Error> {
         let descending_order = last.is_some();
-        let cursor = after
-            .or(before)
-            .map(|cursor| self.parse_obj_cursor(&cursor))
-            .transpose()?;
+        let (snapshot, cursor) = self.parse_snapshot_obj_cursor(after.or(before))?;
         let limit = first.or(last).unwrap_or(DEFAULT_PAGE_SIZE) as i64;
 
-        let result: Option<Vec<StoredObject>> = self
-            .run_query_async_with_cost(
-                move || {
-                    Ok(QueryBuilder::multi_get_coins(
-                        cursor.clone(),
-                        descending_order,
-                        limit,
-                        address.clone(),
-                        coin_type.clone(),
-                    ))
-                },
-                |query| move |conn| query.load(conn).optional(),
-            )
-            .await?;
+        let snapshot = self.snapshot_checkpoint(snapshot).await?;
+        let history_query = {
+            let (cursor, address, coin_type) = (cursor.clone(), address.clone(), coin_type.clone());
+            move || {
+                Ok(QueryBuilder::multi_get_coins_history(
+                    cursor.clone(),
+                    descending_order,
+                    limit,
+                    address.clone(),
+                    coin_type.clone(),
+                ))
+            }
+        };
+        let query = move || {
+            Ok(QueryBuilder::multi_get_coins(
+                cursor.clone(),
+                descending_order,
+                limit,
+                address.clone(),
+                coin_type.clone(),
+            ))
+        };
 
-        result
-            .map(|mut stored_objs| {
-                let has_next_page = stored_objs.len() as i64 > limit;
-                if has_next_page {
-                    stored_objs.pop();
-                }
+        let result = self.load_objs_page(snapshot, query, history_query).await?;
+        Ok(result.map(|mut stored_objs| {
+            let has_next_page = stored_objs.len() as i64 > limit;
+            if has_next_page {
+                stored_objs.pop();
+            }
 
-                Ok((stored_objs, has_next_page))
-            })
-            .transpose()
+            let checkpoint = page_checkpoint(snapshot, &stored_objs);
+            (stored_objs, has_next_page, checkpoint)
+        }))
     }
 
     async fn get_balance(
@@ -788,42 +1661,34 @@ impl PgManager {
             .map(|cursor| self.parse_tx_cursor(&cursor))
             .transpose()?;
         let limit = first.or(last).unwrap_or(DEFAULT_PAGE_SIZE) as i64;
+
+        // The checkpoint filters are translated into (exclusive) bounds on transaction sequence
+        // numbers, which every table that transactions are looked up in is indexed by. The
+        // transactions in checkpoint `c` are the ones with sequence numbers in the range
+        // `[total(c - 1), total(c))`, where `total` is its network total transactions.
         let mut after_tx_seq_num: Option<i64> = None;
         let mut before_tx_seq_num: Option<i64> = None;
         if let Some(filter) = &filter {
-            if let Some(checkpoint) = filter.after_checkpoint {
-                let subquery = transactions::dsl::transactions
-                    .filter(transactions::dsl::checkpoint_sequence_number.eq(checkpoint as i64))
-                    .order(transactions::dsl::tx_sequence_number.asc())
-                    .select(transactions::dsl::tx_sequence_number)
-                    .limit(1)
-                    .into_boxed();
-
-                after_tx_seq_num = self
-                    .run_query_async(|conn| subquery.get_result::<i64>(conn).optional())
-                    .await?;
+            let Some((checkpoint_before_range, last_checkpoint_in_range)) =
+                checkpoint_bounds(filter)
+            else {
+                return Ok(None);
+            };
 
-                // Return early if we cannot find txs after the specified checkpoint
-                if after_tx_seq_num.is_none() {
+            if let Some(checkpoint) = checkpoint_before_range {
+                // Return early if the checkpoint has not been indexed
+                let Some(total) = self.get_network_total_transactions(checkpoint).await? else {
                     return Ok(None);
-                }
+                };
+                after_tx_seq_num = Some(total - 1);
             }
 
-            if let Some(checkpoint) = filter.before_checkpoint {
-                let subquery = transactions::dsl::transactions
-                    .filter(transactions::dsl::checkpoint_sequence_number.eq(checkpoint as i64))
-                    .order(transactions::dsl::tx_sequence_number.desc())
-                    .select(transactions::dsl::tx_sequence_number)
-                    .into_boxed();
-
-                before_tx_seq_num = self
-                    .run_query_async(|conn| subquery.get_result::<i64>(conn).optional())
-                    .await?;
-
-                // Return early if we cannot find tx before the specified checkpoint
-                if before_tx_seq_num.is_none() {
+            if let Some(checkpoint) = last_checkpoint_in_range {
+                // Return early if the checkpoint has not been indexed
+                let Some(total) = self.get_network_total_transactions(checkpoint).await? else {
                     return Ok(None);
-                }
+                };
+                before_tx_seq_num = Some(total);
             }
         }
 
@@ -854,6 +1719,140 @@ impl PgManager {
             .transpose()
     }
 
+    async fn get_txs_for_export(
+        &self,
+        start_checkpoint: i64,
+        end_checkpoint: i64,
+        cursor: Option<i64>,
+        limit: i64,
+    ) -> Result<(Vec<StoredTransaction>, bool), Error> {
+        let mut stored_txs: Vec<StoredTransaction> = self
+            .run_query_async_with_cost(
+                move || {
+                    Ok(QueryBuilder::get_txs_for_export(
+                        start_checkpoint,
+                        end_checkpoint,
+                        cursor,
+                        limit,
+                    ))
+                },
+                |query| move |conn| query.load(conn),
+            )
+            .await?;
+
+        let has_next_page = stored_txs.len() as i64 > limit;
+        if has_next_page {
+            stored_txs.pop();
+        }
+
+        Ok((stored_txs, has_next_page))
+    }
+
+    async fn get_txs_after(
+        &self,
+        cursor: Option<i64>,
+        limit: i64,
+        filter: &ObjectChangeFilter,
+    ) -> Result<Vec<StoredTransaction>, Error> {
+        let filter = filter.clone();
+        self.run_query_async_with_cost(
+            move || Ok(QueryBuilder::get_txs_after(cursor, limit, &filter)),
+            |query| move |conn| query.load(conn),
+        )
+        .await
+    }
+
+    async fn multi_get_history_objs(
+        &self,
+        versions: Vec<(Vec<u8>, i64)>,
+    ) -> Result<Vec<StoredHistoryObject>, Error> {
+        if versions.is_empty() {
+            return Ok(vec![]);
+        }
+        self.run_query_async_with_cost(
+            move || Ok(QueryBuilder::multi_get_history_objs(versions.clone())),
+            |query| move |conn| query.load(conn),
+        )
+        .await
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn multi_get_events(
+        &self,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: EventFilter,
+        after_checkpoint: Option<u64>,
+        before_checkpoint: Option<u64>,
+    ) -> Result<Option<(Vec<StoredEvent>, bool, bool)>, Error> {
+        let descending_order = last.is_some();
+        let cursor = after
+            .or(before)
+            .map(|cursor| self.parse_event_seq_cursor(&cursor))
+            .transpose()?;
+        let limit = first.or(last).unwrap_or(DEFAULT_PAGE_SIZE) as i64;
+        let after_checkpoint = after_checkpoint.map(|c| c as i64);
+        let before_checkpoint = before_checkpoint.map(|c| c as i64);
+
+        let result: Option<Vec<StoredEvent>> = self
+            .run_query_async_with_cost(
+                move || {
+                    QueryBuilder::multi_get_events(
+                        cursor,
+                        descending_order,
+                        limit,
+                        filter.clone(),
+                        after_checkpoint,
+                        before_checkpoint,
+                    )
+                },
+                |query| move |conn| query.load(conn).optional(),
+            )
+            .await?;
+
+        Ok(result.map(|stored_events| {
+            page_of_rows(stored_events, limit, descending_order, cursor.is_some())
+        }))
+    }
+
+    async fn multi_get_obj_versions(
+        &self,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        address: Vec<u8>,
+        before_version: i64,
+    ) -> Result<Option<(Vec<StoredHistoryObject>, bool, bool)>, Error> {
+        let descending_order = last.is_some();
+        let cursor = after
+            .or(before)
+            .map(|cursor| self.parse_obj_version_cursor(&cursor))
+            .transpose()?;
+        let limit = first.or(last).unwrap_or(DEFAULT_PAGE_SIZE) as i64;
+
+        let result: Option<Vec<StoredHistoryObject>> = self
+            .run_query_async_with_cost(
+                move || {
+                    Ok(QueryBuilder::multi_get_obj_versions(
+                        address.clone(),
+                        before_version,
+                        cursor,
+                        descending_order,
+                        limit,
+                    ))
+                },
+                |query| move |conn| query.load(conn).optional(),
+            )
+            .await?;
+
+        Ok(result.map(|stored_objs| {
+            page_of_rows(stored_objs, limit, descending_order, cursor.is_some())
+        }))
+    }
+
     async fn multi_get_checkpoints(
         &self,
         first: Option<u64>,
@@ -903,14 +1902,24 @@ impl PgManager {
         before: Option<String>,
         filter: Option<ObjectFilter>,
         owner_type: Option<OwnerType>,
-    ) -> Result<Option<(Vec<StoredObject>, bool)>, Error> {
+    ) -> Result<Option<(Vec<StoredObject>, bool, i64)>, Error> {
         let descending_order = last.is_some();
-        let cursor = after
-            .or(before)
-            .map(|cursor| self.parse_obj_cursor(&cursor))
-            .transpose()?;
+        let (snapshot, cursor) = self.parse_snapshot_obj_cursor(after.or(before))?;
         let limit = first.or(last).unwrap_or(DEFAULT_PAGE_SIZE) as i64;
 
+        let snapshot = self.snapshot_checkpoint(snapshot).await?;
+        let history_query = {
+            let (cursor, filter) = (cursor.clone(), filter.clone());
+            move || {
+                QueryBuilder::multi_get_objs_history(
+                    cursor.clone(),
+                    descending_order,
+                    limit,
+                    filter.clone(),
+                    owner_type,
+                )
+            }
+        };
         let query = move || {
             QueryBuilder::multi_get_objs(
                 cursor.clone(),
@@ -921,20 +1930,65 @@ impl PgManager {
             )
         };
 
-        let result: Option<Vec<StoredObject>> = self
-            .run_query_async_with_cost(query, |query| move |conn| query.load(conn).optional())
-            .await?;
-        result
-            .map(|mut stored_objs| {
-                let has_next_page = stored_objs.len() as i64 > limit;
-                if has_next_page {
-                    stored_objs.pop();
-                }
+        let result = self.load_objs_page(snapshot, query, history_query).await?;
+        Ok(result.map(|mut stored_objs| {
+            let has_next_page = stored_objs.len() as i64 > limit;
+            if has_next_page {
+                stored_objs.pop();
+            }
+
+            let checkpoint = page_checkpoint(snapshot, &stored_objs);
+            (stored_objs, has_next_page, checkpoint)
+        }))
+    }
+
+    /// Loads a page of a connection over objects: the first page from the latest versions of the
+    /// objects, and the later pages from their versions as of the checkpoint in the cursor, so
+    /// that they stay consistent with the first page while the objects are modified.
+    async fn load_objs_page<Q, H>(
+        &self,
+        snapshot: Snapshot,
+        query: Q,
+        history_query: H,
+    ) -> Result<Option<Vec<StoredObject>>, Error>
+    where
+        Q: FnMut() -> Result<objects::BoxedQuery<'static, Pg>, Error> + Send + 'static,
+        H: FnMut() -> Result<objects_history::BoxedQuery<'static, Pg>, Error> + Send + 'static,
+    {
+        let Snapshot::Cursor(checkpoint) = snapshot else {
+            return self
+                .run_query_async_with_cost(query, |query| move |conn| query.load(conn).optional())
+                .await;
+        };
 
-                Ok((stored_objs, has_next_page))
+        let mut history_query = history_query;
+        let stored_objs: Option<Vec<StoredHistoryObject>> = self
+            .run_query_async_with_cost(
+                move || Ok(history_query()?.filter(QueryBuilder::as_of_checkpoint(checkpoint))),
+                |query| move |conn| query.load(conn).optional(),
+            )
+            .await?;
+        stored_objs
+            .map(|stored_objs| {
+                stored_objs
+                    .into_iter()
+                    .map(|stored_obj| Ok(StoredObject::try_from(stored_obj)?))
+                    .collect()
             })
             .transpose()
     }
+
+    /// The checkpoint that a page of a connection over objects needs to be consistent with: the
+    /// one in its cursor, or the latest checkpoint, for the first page of the connection, or of a
+    /// connection paginated with a cursor from before snapshots were added to cursors.
+    async fn snapshot_checkpoint(&self, cursor_checkpoint: Option<i64>) -> Result<Snapshot, Error> {
+        if let Some(checkpoint) = cursor_checkpoint {
+            return Ok(Snapshot::Cursor(checkpoint));
+        }
+
+        let latest = self.get_checkpoint(None, None).await?;
+        Ok(Snapshot::Latest(latest.map_or(0, |c| c.sequence_number)))
+    }
 }
 
 /// Implement methods to be used by graphql resolvers
@@ -952,6 +2006,45 @@ impl PgManager {
             .into_vec())
     }
 
+    /// Parses a cursor of a connection over objects ordered by object ID into its checkpoint and
+    /// object ID.
+    pub(crate) fn parse_snapshot_obj_cursor(
+        &self,
+        cursor: Option<String>,
+    ) -> Result<(Option<i64>, Option<Vec<u8>>), Error> {
+        let Some(cursor) = cursor else {
+            return Ok((None, None));
+        };
+        let (checkpoint, position) = parse_snapshot_cursor(&cursor, 0)?;
+        Ok((checkpoint, Some(self.parse_obj_cursor(position)?)))
+    }
+
+    /// Positions of coins ordered by balance are of the form `{balance}:{object_id}`.
+    pub(crate) fn parse_coin_balance_cursor(&self, cursor: &str) -> Result<(i64, Vec<u8>), Error> {
+        let invalid = || Error::InvalidCursor("coin".to_string());
+        let (balance, object_id) = cursor.split_once(':').ok_or_else(invalid)?;
+        let balance = balance.parse::<i64>().map_err(|_| invalid())?;
+        Ok((balance, self.parse_obj_cursor(object_id)?))
+    }
+
+    /// Cursors of events read straight from the events table are of the form
+    /// `{tx_sequence_number}:{event_sequence_number}`, and so stay valid as new events are indexed.
+    pub(crate) fn parse_event_seq_cursor(&self, cursor: &str) -> Result<(i64, i64), Error> {
+        let invalid = || Error::InvalidCursor("event".to_string());
+        let (tx, event) = cursor.split_once(':').ok_or_else(invalid)?;
+        let tx = tx.parse::<i64>().map_err(|_| invalid())?;
+        let event = event.parse::<i64>().map_err(|_| invalid())?;
+        Ok((tx, event))
+    }
+
+    /// Versions of an object never change once written, so their cursors are just the version.
+    pub(crate) fn parse_obj_version_cursor(&self, cursor: &str) -> Result<i64, Error> {
+        let version = cursor
+            .parse::<i64>()
+            .map_err(|_| Error::InvalidCursor("object version".to_string()))?;
+        Ok(version)
+    }
+
     pub(crate) fn parse_checkpoint_cursor(&self, cursor: &str) -> Result<i64, Error> {
         let sequence_number = cursor
             .parse::<i64>()
@@ -964,7 +2057,6 @@ impl PgManager {
     }
 
     pub(crate) fn validate_package_dependencies(
-        &self,
         package: Option<&SuiAddress>,
         module: Option<&String>,
         function: Option<&String>,
@@ -979,10 +2071,33 @@ impl PgManager {
         Ok(())
     }
 
+    /// Validates the filter, expanding a qualified `function` into its `package`, `module` and
+    /// `function` parts.
     pub(crate) fn validate_tx_block_filter(
-        &self,
-        filter: &TransactionBlockFilter,
+        filter: &mut TransactionBlockFilter,
     ) -> Result<(), Error> {
+        if filter.paid_address.is_some() {
+            return Err(DbValidationError::UnsupportedPaidAddress.into());
+        }
+        if let Some(function) = filter.function.clone().filter(|f| f.contains("::")) {
+            if filter.package.is_some() || filter.module.is_some() {
+                return Err(DbValidationError::QualifiedFunctionWithPackageOrModule.into());
+            }
+
+            let parts: Vec<_> = function.split("::").collect();
+            let (package, module, func) = match parts.as_slice() {
+                [p, m] if !m.is_empty() => (p, Some(m), None),
+                [p, m, f] if !m.is_empty() && !f.is_empty() => (p, Some(m), Some(f)),
+                _ => return Err(DbValidationError::InvalidQualifiedFunction(function).into()),
+            };
+            let Ok(package) = SuiAddress::from_str(package) else {
+                return Err(DbValidationError::InvalidQualifiedFunction(function).into());
+            };
+
+            filter.package = Some(package);
+            filter.module = module.map(|m| m.to_string());
+            filter.function = func.map(|f| f.to_string());
+        }
         if filter.at_checkpoint.is_some()
             && (filter.before_checkpoint.is_some() || filter.after_checkpoint.is_some())
         {
@@ -993,7 +2108,7 @@ impl PgManager {
                 return Err(DbValidationError::InvalidCheckpointOrder.into());
             }
         }
-        self.validate_package_dependencies(
+        Self::validate_package_dependencies(
             filter.package.as_ref(),
             filter.module.as_ref(),
             filter.function.as_ref(),
@@ -1021,6 +2136,52 @@ impl PgManager {
             .transpose()
     }
 
+    pub(crate) async fn fetch_sponsored_tx_status(
+        &self,
+        digest: &str,
+    ) -> Result<Option<SponsoredTransactionStatus>, Error> {
+        let digest = Digest::from_str(digest)?.into_vec();
+        let Some(stored_tx) = self.get_tx(digest).await? else {
+            return Ok(None);
+        };
+
+        let sponsor = self.get_tx_sponsor(stored_tx.tx_sequence_number).await?;
+        let tx = TransactionBlock::try_from(stored_tx)?;
+        let status = tx.effects.as_ref().map(|effects| effects.status);
+        let (gas_budget, gas_price) = tx
+            .gas_input
+            .as_ref()
+            .map(|gas| (gas.budget, gas.price))
+            .unzip();
+
+        Ok(Some(SponsoredTransactionStatus {
+            sponsored: sponsor.is_some(),
+            sponsor: sponsor
+                .map(|s| {
+                    SuiAddress::from_bytes(s.sponsor)
+                        .map(|address| Address { address })
+                        .map_err(|e| Error::Internal(format!("{e}")))
+                })
+                .transpose()?,
+            sender: tx.sender,
+            gas_budget: gas_budget.map(BigInt::from),
+            gas_price: gas_price.map(BigInt::from),
+            status,
+            transaction_block: Some(tx),
+        }))
+    }
+
+    pub(crate) async fn fetch_gas_sponsor(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Option<GasSponsor>, Error> {
+        let result = self.get_gas_sponsor(address.into_vec()).await?;
+        Ok(result.and_then(gas_sponsor_stats).map(|stats| GasSponsor {
+            address,
+            stats: Some(stats),
+        }))
+    }
+
     pub(crate) async fn fetch_latest_epoch(&self) -> Result<Epoch, Error> {
         let result = self
             .get_epoch(None)
@@ -1040,14 +2201,15 @@ impl PgManager {
             .transpose()
     }
 
-    // To be used in scenarios where epoch is expected to exist
-    // For example, epoch of a transaction or checkpoint
-    pub(crate) async fn fetch_epoch_strict(&self, epoch_id: u64) -> Result<Epoch, Error> {
-        let result = self.fetch_epoch(epoch_id).await?;
-        match result {
-            Some(epoch) => Ok(epoch),
-            None => Err(Error::Internal(format!("Epoch {} not found", epoch_id))),
-        }
+    /// Fetches the epochs with the given IDs in a single query. Epochs that do not exist are
+    /// omitted from the result.
+    pub(crate) async fn fetch_epochs(&self, epoch_ids: &[u64]) -> Result<Vec<Epoch>, Error> {
+        let epoch_ids = epoch_ids.iter().map(|id| *id as i64).collect();
+        self.multi_get_epochs(epoch_ids)
+            .await?
+            .into_iter()
+            .map(Epoch::try_from)
+            .collect()
     }
 
     pub(crate) async fn fetch_latest_checkpoint(&self) -> Result<Checkpoint, Error> {
@@ -1058,6 +2220,20 @@ impl PgManager {
         }
     }
 
+    /// Fetches the checkpoints with the given sequence numbers in a single query. Checkpoints
+    /// that do not exist are omitted from the result.
+    pub(crate) async fn fetch_checkpoints_by_sequence_numbers(
+        &self,
+        sequence_numbers: &[u64],
+    ) -> Result<Vec<Checkpoint>, Error> {
+        let sequence_numbers = sequence_numbers.iter().map(|s| *s as i64).collect();
+        self.multi_get_checkpoints_by_sequence_numbers(sequence_numbers)
+            .await?
+            .into_iter()
+            .map(Checkpoint::try_from)
+            .collect()
+    }
+
     pub(crate) async fn fetch_checkpoint(
         &self,
         digest: Option<&str>,
@@ -1115,11 +2291,12 @@ impl PgManager {
         after: Option<String>,
         last: Option<u64>,
         before: Option<String>,
-        filter: Option<TransactionBlockFilter>,
+        mut filter: Option<TransactionBlockFilter>,
     ) -> Result<Option<Connection<String, TransactionBlock>>, Error> {
         validate_cursor_pagination(&first, &after, &last, &before)?;
-        if let Some(filter) = &filter {
-            self.validate_tx_block_filter(filter)?;
+        self.validate_page_size(&first, &last)?;
+        if let Some(filter) = &mut filter {
+            Self::validate_tx_block_filter(filter)?;
         }
 
         let transactions = self
@@ -1143,6 +2320,144 @@ impl PgManager {
         }
     }
 
+    /// Exports the transaction blocks included in checkpoints `start_checkpoint` to
+    /// `end_checkpoint` (inclusive), resuming after the transaction block at cursor `after`.
+    /// Batches are limited to `Limits::max_export_batch_size` transaction blocks.
+    pub(crate) async fn fetch_tx_export_batch(
+        &self,
+        start_checkpoint: u64,
+        end_checkpoint: u64,
+        after: Option<String>,
+        limit: Option<u64>,
+    ) -> Result<TransactionBlockExportBatch, Error> {
+        if start_checkpoint > end_checkpoint {
+            return Err(DbValidationError::InvalidExportCheckpointRange.into());
+        }
+        let limit = export_batch_limit(limit, self.limits.max_export_batch_size)?;
+        let cursor = after
+            .as_deref()
+            .map(|cursor| self.parse_tx_cursor(cursor))
+            .transpose()?;
+
+        let (stored_txs, has_next_page) = self
+            .get_txs_for_export(
+                start_checkpoint as i64,
+                end_checkpoint as i64,
+                cursor,
+                limit as i64,
+            )
+            .await?;
+
+        let end_cursor = stored_txs
+            .last()
+            .map(|tx| tx.tx_sequence_number.to_string())
+            .or(after);
+        let transactions = stored_txs
+            .into_iter()
+            .map(ExportedTransactionBlock::try_from)
+            .collect::<Result<_, _>>()?;
+
+        Ok(TransactionBlockExportBatch {
+            transactions,
+            end_cursor,
+            has_next_page,
+        })
+    }
+
+    /// The cursor of the latest indexed transaction block, or `None` if none has been indexed yet
+    pub(crate) async fn fetch_latest_tx_cursor(&self) -> Result<Option<String>, Error> {
+        let stored_tx: Option<StoredTransaction> = self
+            .run_query_async_with_cost(
+                move || Ok(QueryBuilder::get_latest_tx_sequence_number()),
+                |query| move |conn| query.get_result(conn).optional(),
+            )
+            .await?;
+        Ok(stored_tx.map(|tx| tx.tx_sequence_number.to_string()))
+    }
+
+    /// The changes matching `filter` made by the (at most `limit`) transaction blocks indexed after
+    /// cursor `after` that can make such changes, in order, with the cursor of the last
+    /// transaction block read.
+    pub(crate) async fn fetch_object_updates(
+        &self,
+        after: Option<String>,
+        limit: u64,
+        filter: &ObjectChangeFilter,
+    ) -> Result<(Vec<ObjectUpdate>, Option<String>), Error> {
+        let cursor = after
+            .as_deref()
+            .map(|cursor| self.parse_tx_cursor(cursor))
+            .transpose()?;
+        let stored_txs = self.get_txs_after(cursor, limit as i64, filter).await?;
+
+        let end_cursor = stored_txs
+            .last()
+            .map(|tx| tx.tx_sequence_number.to_string())
+            .or(after);
+
+        // Each change, with the version of its object before the change, if it existed before
+        let mut updates = vec![];
+        for tx in stored_txs {
+            let digest = Digest::try_from(tx.transaction_digest.as_slice())?;
+            let effects: TransactionEffects = bcs::from_bytes(&tx.raw_effects).map_err(|e| {
+                Error::Internal(format!(
+                    "Can't convert raw_effects into TransactionEffects. Error: {e}",
+                ))
+            })?;
+            let previous_versions: BTreeMap<_, _> =
+                effects.modified_at_versions().into_iter().collect();
+            for change in tx.object_changes.into_iter().flatten() {
+                let change: IndexedObjectChange = bcs::from_bytes(&change).map_err(|e| {
+                    Error::Internal(format!(
+                        "Can't convert object_change bytes into IndexedObjectChange. Error: {e}",
+                    ))
+                })?;
+                let update = ObjectUpdate::from_indexed(
+                    change,
+                    digest.to_string(),
+                    tx.checkpoint_sequence_number as u64,
+                );
+                let object_id = ObjectID::new(update.object_id.into_array());
+                let previous_version = previous_versions.get(&object_id).map(|v| v.value());
+                updates.push((update, previous_version));
+            }
+        }
+
+        // The owners of the objects before the changes, for the changes that do not leave an
+        // object with the owner of the filter
+        let mut previous_owners = BTreeMap::new();
+        if let Some(owner) = &filter.owner {
+            let versions = updates
+                .iter()
+                .filter(|(update, _)| update.owner.as_ref() != Some(owner))
+                .filter_map(|(update, version)| {
+                    Some((update.object_id.into_vec(), (*version)? as i64))
+                })
+                .collect();
+            for object in self.multi_get_history_objs(versions).await? {
+                let Some(owner_id) = object.owner_id else {
+                    continue;
+                };
+                let object_id = SuiAddress::from_bytes(&object.object_id)
+                    .map_err(|e| Error::Internal(format!("Invalid object id: {e}")))?;
+                let owner = SuiAddress::from_bytes(&owner_id)
+                    .map_err(|e| Error::Internal(format!("Invalid owner id: {e}")))?;
+                previous_owners.insert((object_id, object.object_version as u64), owner);
+            }
+        }
+
+        let updates = updates
+            .into_iter()
+            .filter(|(update, previous_version)| {
+                let previous_owner = previous_version
+                    .and_then(|version| previous_owners.get(&(update.object_id, version)));
+                update.matches(filter, previous_owner)
+            })
+            .map(|(update, _)| update)
+            .collect();
+        Ok((updates, end_cursor))
+    }
+
     pub(crate) async fn fetch_txs_by_digests(
         &self,
         digests: &[TransactionDigest],
@@ -1159,6 +2474,7 @@ impl PgManager {
             sent_address: None,
             recv_address: None,
             paid_address: None,
+            sponsor_address: None,
             input_object: None,
             changed_object: None,
             transaction_ids: Some(digests.iter().map(|x| x.to_string()).collect::<Vec<_>>()),
@@ -1187,19 +2503,123 @@ impl PgManager {
         stored_obj.map(Object::try_from).transpose()
     }
 
-    pub(crate) async fn fetch_move_obj(
+    /// Fetches the object at `address` as it was at exactly `version`, or as of `checkpoint` (the
+    /// latest version written at or before it), from the history of all its versions. Exactly one
+    /// of `version` and `checkpoint` must be provided.
+    pub(crate) async fn fetch_obj_at(
         &self,
         address: SuiAddress,
         version: Option<u64>,
-    ) -> Result<Option<MoveObject>, Error> {
-        let address = address.into_vec();
-        let version = version.map(|v| v as i64);
+        checkpoint: Option<u64>,
+    ) -> Result<Option<Object>, Error> {
+        if version.is_some() == checkpoint.is_some() {
+            return Err(Error::InvalidObjectAtQuery);
+        }
 
-        let stored_obj = self.get_obj(address, version).await?;
-        let sui_object = stored_obj.map(SuiObject::try_from).transpose()?;
-        let move_object = sui_object.map(|o| MoveObject { native_object: o });
+        let stored_obj = self
+            .get_obj_at(
+                address.into_vec(),
+                version.map(|v| v as i64),
+                checkpoint.map(|c| c as i64),
+            )
+            .await?;
+
+        // The object did not exist at that point, if it had been deleted or wrapped.
+        stored_obj
+            .filter(|o| !o.is_tombstone())
+            .map(|o| Object::try_from(StoredObject::try_from(o)?))
+            .transpose()
+    }
+
+    /// Fetches the versions of the object at `address` that precede `version`, in order of
+    /// version. Versions at which the object was deleted or wrapped are skipped.
+    pub(crate) async fn fetch_previous_obj_versions(
+        &self,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        address: SuiAddress,
+        version: u64,
+    ) -> Result<Option<Connection<String, Object>>, Error> {
+        validate_cursor_pagination(&first, &after, &last, &before)?;
+        self.validate_page_size(&first, &last)?;
+        let objs = self
+            .multi_get_obj_versions(
+                first,
+                after,
+                last,
+                before,
+                address.into_vec(),
+                version as i64,
+            )
+            .await?;
+
+        let Some((stored_objs, has_previous_page, has_next_page)) = objs else {
+            return Ok(None);
+        };
+
+        let mut connection = Connection::new(has_previous_page, has_next_page);
+        for stored_obj in stored_objs {
+            let cursor = stored_obj.object_version.to_string();
+            let stored_obj = StoredObject::try_from(stored_obj)?;
+            connection
+                .edges
+                .push(Edge::new(cursor, Object::try_from(stored_obj)?));
+        }
+
+        Ok(Some(connection))
+    }
+
+    /// Fetches the latest versions of the objects at `addresses` in a single query, as they are
+    /// stored. Objects that do not exist are omitted from the result.
+    pub(crate) async fn fetch_stored_objs(
+        &self,
+        addresses: &[SuiAddress],
+    ) -> Result<Vec<StoredObject>, Error> {
+        self.multi_get_objs_by_ids(addresses.iter().map(|a| a.into_vec()).collect())
+            .await
+    }
+
+    /// Fetches the latest versions of the objects at `addresses` in a single query, returning a
+    /// result per address, in the order they were requested.
+    pub(crate) async fn fetch_objs(
+        &self,
+        addresses: Vec<SuiAddress>,
+    ) -> Result<Vec<ObjectResult>, Error> {
+        let max_objects = self.limits.max_multi_get_objects;
+        if addresses.len() as u64 > max_objects {
+            return Err(DbValidationError::MultiGetObjectsLimitExceeded(
+                addresses.len() as u64,
+                max_objects,
+            )
+            .into());
+        }
+
+        let stored_objs = self.fetch_stored_objs(&addresses).await?;
+        let stored_objs: BTreeMap<_, _> = stored_objs
+            .into_iter()
+            .map(|o| (o.object_id.clone(), o))
+            .collect();
 
-        Ok(move_object)
+        Ok(addresses
+            .into_iter()
+            .map(|address| {
+                let stored_obj = stored_objs.get(&address.into_vec()).cloned();
+                match stored_obj.map(Object::try_from).transpose() {
+                    Ok(object) => ObjectResult {
+                        address,
+                        object,
+                        error: None,
+                    },
+                    Err(e) => ObjectResult {
+                        address,
+                        object: None,
+                        error: Some(e.to_string()),
+                    },
+                }
+            })
+            .collect())
     }
 
     pub(crate) async fn fetch_move_package(
@@ -1242,6 +2662,7 @@ impl PgManager {
         filter: Option<ObjectFilter>,
     ) -> Result<Option<Connection<String, Object>>, Error> {
         validate_cursor_pagination(&first, &after, &last, &before)?;
+        self.validate_page_size(&first, &last)?;
         if let Some(filter) = &filter {
             self.validate_obj_filter(filter)?;
         }
@@ -1249,7 +2670,7 @@ impl PgManager {
             .multi_get_objs(first, after, last, before, filter, None)
             .await?;
 
-        if let Some((stored_objs, has_next_page)) = objects {
+        if let Some((stored_objs, has_next_page, checkpoint)) = objects {
             let mut connection = Connection::new(false, has_next_page);
             connection
                 .edges
@@ -1257,7 +2678,7 @@ impl PgManager {
                     Object::try_from(stored_obj)
                         .map_err(|e| eprintln!("Error converting object: {:?}", e))
                         .ok()
-                        .map(|obj| Edge::new(obj.address.to_string(), obj))
+                        .map(|obj| Edge::new(format!("{checkpoint}:{}", obj.address), obj))
                 }));
             Ok(Some(connection))
         } else {
@@ -1274,6 +2695,7 @@ impl PgManager {
         epoch: Option<u64>,
     ) -> Result<Option<Connection<String, Checkpoint>>, Error> {
         validate_cursor_pagination(&first, &after, &last, &before)?;
+        self.validate_page_size(&first, &last)?;
         let checkpoints = self
             .multi_get_checkpoints(first, after, last, before, epoch)
             .await?;
@@ -1339,6 +2761,7 @@ impl PgManager {
         last: Option<u64>,
         before: Option<String>,
     ) -> Result<Option<Connection<String, Balance>>, Error> {
+        self.validate_page_size(&first, &last)?;
         let address = address.into_vec();
 
         let balances = self
@@ -1369,6 +2792,63 @@ impl PgManager {
         }
     }
 
+    /// The change of the balance of `coin_type` of `address` over each checkpoint that changed it,
+    /// with the total balance after the checkpoint. The coin type defaults to SUI.
+    pub(crate) async fn fetch_balance_history(
+        &self,
+        address: SuiAddress,
+        coin_type: Option<String>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Option<Connection<String, BalanceSnapshot>>, Error> {
+        validate_cursor_pagination(&first, &after, &last, &before)?;
+        self.validate_page_size(&first, &last)?;
+        let Ok(coin_type) = parse_to_type_tag(coin_type) else {
+            // The provided `coin_type` cannot be parsed to a type tag so return None here.
+            return Ok(None);
+        };
+        let coin_type = coin_type.to_canonical_string(/* with_prefix */ true);
+        let address = address.into_vec();
+
+        let Some((mut changes, has_next_page)) = self
+            .multi_get_balance_changes(
+                address.clone(),
+                coin_type.clone(),
+                first,
+                after,
+                last,
+                before,
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+        changes.sort_by_key(|(checkpoint, _)| *checkpoint);
+
+        // The total after the latest checkpoint of the page is the current balance without the
+        // changes of the later checkpoints.
+        let total_balance = match changes.last() {
+            Some((checkpoint, _)) => {
+                let current = match self.get_balance(address.clone(), coin_type.clone()).await? {
+                    Some((balance, _, _)) => balance.unwrap_or(0),
+                    None => 0,
+                };
+                let later = self
+                    .get_balance_change_after(address, coin_type, *checkpoint)
+                    .await?;
+                current as i128 - later
+            }
+            None => 0,
+        };
+        let snapshots = balance_snapshots(changes, total_balance);
+
+        let mut connection = Connection::new(false, has_next_page);
+        connection.edges.extend(snapshots);
+        Ok(Some(connection))
+    }
+
     pub(crate) async fn fetch_coins(
         &self,
         address: SuiAddress,
@@ -1378,13 +2858,14 @@ impl PgManager {
         last: Option<u64>,
         before: Option<String>,
     ) -> Result<Option<Connection<String, Coin>>, Error> {
+        self.validate_page_size(&first, &last)?;
         let address = address.into_vec();
 
         let coins = self
             .multi_get_coins(address, coin_type, first, after, last, before)
             .await?;
 
-        if let Some((stored_objs, has_next_page)) = coins {
+        if let Some((stored_objs, has_next_page, checkpoint)) = coins {
             let mut connection = Connection::new(false, has_next_page);
             connection
                 .edges
@@ -1393,11 +2874,12 @@ impl PgManager {
                         .map_err(|e| eprintln!("Error converting object to coin: {:?}", e))
                         .ok()
                         .map(|coin| {
+                            let id = coin.move_obj.native_object.id();
                             Edge::new(
-                                coin.move_obj
-                                    .native_object
-                                    .id()
-                                    .to_canonical_string(/* with_prefix */ true),
+                                format!(
+                                    "{checkpoint}:{}",
+                                    id.to_canonical_string(/* with_prefix */ true)
+                                ),
                                 coin,
                             )
                         })
@@ -1408,6 +2890,76 @@ impl PgManager {
         }
     }
 
+    /// The coins of `coin_type` (SUI by default) owned by `address`, in order of balance (and
+    /// then of object ID), with at least `min_balance`, if one is given. Pages requested with
+    /// `last` are in descending order of balance.
+    pub(crate) async fn fetch_coins_by_balance(
+        &self,
+        address: SuiAddress,
+        coin_type: Option<String>,
+        min_balance: Option<u64>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Option<Connection<String, Coin>>, Error> {
+        validate_cursor_pagination(&first, &after, &last, &before)?;
+        self.validate_page_size(&first, &last)?;
+        let Ok(coin_type) = parse_to_type_tag(coin_type) else {
+            // The provided `coin_type` cannot be parsed to a type tag so return None here.
+            return Ok(None);
+        };
+        let coin_type = coin_type.to_canonical_string(/* with_prefix */ true);
+        let address = address.into_vec();
+        // Balances are stored as `i64`s, so no coin has a balance above `i64::MAX`.
+        let min_balance = min_balance.map(|b| i64::try_from(b).unwrap_or(i64::MAX));
+
+        let Some((stored_objs, has_previous_page, has_next_page, checkpoint)) = self
+            .multi_get_coins_by_balance(address, coin_type, min_balance, first, after, last, before)
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        let mut connection = Connection::new(has_previous_page, has_next_page);
+        for stored_obj in stored_objs {
+            let balance = stored_obj
+                .coin_balance
+                .ok_or_else(|| Error::Internal("Coin object is missing its balance".to_string()))?;
+            let coin = Coin::try_from(stored_obj)?;
+            let cursor = format!(
+                "{checkpoint}:{balance}:{}",
+                coin.move_obj
+                    .native_object
+                    .id()
+                    .to_canonical_string(/* with_prefix */ true)
+            );
+            connection.edges.push(Edge::new(cursor, coin));
+        }
+
+        Ok(Some(connection))
+    }
+
+    pub(crate) async fn fetch_coin_metadata(
+        &self,
+        coin_struct: StructTag,
+    ) -> Result<Option<CoinMetadata>, Error> {
+        let metadata = self
+            .inner
+            .get_coin_metadata_in_blocking_task(coin_struct)
+            .await?;
+        Ok(metadata.map(CoinMetadata::from))
+    }
+
+    /// The latest version of the Display template for objects of type `object_type`, if one has
+    /// been published.
+    pub(crate) async fn fetch_display_object_by_type(
+        &self,
+        object_type: &StructTag,
+    ) -> Result<Option<DisplayVersionUpdatedEvent>, Error> {
+        Ok(self.inner.get_display_object_by_type(object_type).await?)
+    }
+
     pub(crate) async fn resolve_name_service_address(
         &self,
         name_service_config: &NameServiceConfig,
@@ -1521,6 +3073,8 @@ impl PgManager {
         last: Option<u64>,
         before: Option<String>,
     ) -> Result<Option<Connection<String, Stake>>, Error> {
+        validate_cursor_pagination(&first, &after, &last, &before)?;
+        self.validate_page_size(&first, &last)?;
         let obj_filter = ObjectFilter {
             package: None,
             module: None,
@@ -1541,7 +3095,7 @@ impl PgManager {
             )
             .await?;
 
-        if let Some((stored_objs, has_next_page)) = objs {
+        if let Some((stored_objs, has_next_page, checkpoint)) = objs {
             let mut connection = Connection::new(false, has_next_page);
             let mut edges = vec![];
             let governance_api = GovernanceReadApiV2::new(self.inner.clone());
@@ -1559,6 +3113,7 @@ impl PgManager {
                     Some(stake_object)
                 })
                 .collect::<Vec<_>>();
+            let stake_objects = stakes.clone();
 
             // retrieve the delegated stakes
             // at the first invocation, it will likely fail because data is not cached
@@ -1567,16 +3122,24 @@ impl PgManager {
                 .await
                 .map_err(|e| Error::Internal(format!("Error fetching delegated stakes. {e}")))?;
 
-            let stakes = delegated_stakes
+            // the delegated stakes are grouped by staking pool, so they are put back in the order
+            // of the objects, which is the order of the cursors
+            let mut stakes = delegated_stakes
                 .into_iter()
                 .flat_map(|x| x.stakes)
-                .collect::<Vec<_>>();
+                .map(|stk| (stk.staked_sui_id, stk))
+                .collect::<BTreeMap<_, _>>();
 
-            for stk in stakes {
-                let cursor = stk
-                    .staked_sui_id
-                    .to_canonical_string(/* with_prefix */ true);
-                let stake = Stake::from(stk);
+            for native in stake_objects {
+                let Some(stk) = stakes.remove(&native.id()) else {
+                    continue;
+                };
+                let cursor = format!(
+                    "{checkpoint}:{}",
+                    stk.staked_sui_id
+                        .to_canonical_string(/* with_prefix */ true)
+                );
+                let stake = Stake::from((native, stk));
                 edges.push(Edge::new(cursor, stake));
             }
             connection.edges.extend(edges);
@@ -1586,6 +3149,51 @@ impl PgManager {
         }
     }
 
+    /// The exchange rates of the staking pool `pool_id`, in descending order by epoch, or `None`
+    /// if no validator has this pool.
+    pub(crate) async fn fetch_pool_exchange_rates(
+        &self,
+        pool_id: ObjectID,
+    ) -> Result<Option<ValidatorExchangeRates>, Error> {
+        let rates = GovernanceReadApiV2::new(self.inner.clone())
+            .get_exchange_rates()
+            .await
+            .map_err(|e| Error::Internal(format!("Error fetching exchange rates. {e}")))?;
+        Ok(rates.into_iter().find(|rates| rates.pool_id == pool_id))
+    }
+
+    /// The active validator whose staking pool is `pool_id`
+    pub(crate) async fn fetch_pool_validator(
+        &self,
+        pool_id: ObjectID,
+    ) -> Result<Option<Validator>, Error> {
+        let pool_id = SuiAddress::from(pool_id);
+        let system_state = self.fetch_latest_sui_system_state().await?;
+        Ok(system_state
+            .validator_set
+            .and_then(|set| set.active_validators)
+            .into_iter()
+            .flatten()
+            .find(|validator| validator.staking_pool_id == pool_id))
+    }
+
+    /// The APY of the staking pool of the validator at `address`, as of the latest epoch
+    pub(crate) async fn fetch_validator_apy(
+        &self,
+        address: SuiAddress,
+    ) -> Result<Option<f64>, Error> {
+        let address = NativeSuiAddress::from(address);
+        let apys = GovernanceReadApiV2::new(self.inner.clone())
+            .get_validators_apy()
+            .await
+            .map_err(|e| Error::Internal(format!("Error fetching validator APYs. {e}")))?;
+        Ok(apys
+            .apys
+            .into_iter()
+            .find(|apy| apy.address == address)
+            .map(|apy| apy.apy))
+    }
+
     pub(crate) async fn fetch_events(
         &self,
         first: Option<u64>,
@@ -1594,41 +3202,8 @@ impl PgManager {
         before: Option<String>,
         filter: EventFilter,
     ) -> Result<Option<Connection<String, Event>>, Error> {
-        let event_filter: Result<RpcEventFilter, Error> = if let Some(sender) = filter.sender {
-            let sender = NativeSuiAddress::from_bytes(sender.into_array())
-                .map_err(|_| Error::InvalidFilter)?;
-            Ok(RpcEventFilter::Sender(sender))
-        } else if let Some(digest) = filter.transaction_digest {
-            let digest = TransactionDigest::from_str(&digest).map_err(|_| Error::InvalidFilter)?;
-            Ok(RpcEventFilter::Transaction(digest))
-        } else if let Some(package) = filter.emitting_package {
-            if let Some(module) = filter.emitting_module {
-                let package =
-                    ObjectID::from_bytes(package.into_array()).map_err(|_| Error::InvalidFilter)?;
-                let module = Identifier::from_str(&module).map_err(|_| Error::InvalidFilter)?;
-                Ok(RpcEventFilter::MoveModule { package, module })
-            } else {
-                let package =
-                    ObjectID::from_bytes(package.into_array()).map_err(|_| Error::InvalidFilter)?;
-                Ok(RpcEventFilter::Package(package))
-            }
-        } else if let Some(event_type) = filter.event_type {
-            let event_type = StructTag::from_str(&event_type).map_err(|_| Error::InvalidFilter)?;
-            Ok(RpcEventFilter::MoveEventType(event_type))
-        } else if let Some(package) = filter.event_package {
-            if let Some(module) = filter.event_module {
-                let package =
-                    ObjectID::from_bytes(package.into_array()).map_err(|_| Error::InvalidFilter)?;
-                let module = Identifier::from_str(&module).map_err(|_| Error::InvalidFilter)?;
-                Ok(RpcEventFilter::MoveModule { package, module })
-            } else {
-                let package =
-                    ObjectID::from_bytes(package.into_array()).map_err(|_| Error::InvalidFilter)?;
-                Ok(RpcEventFilter::Package(package))
-            }
-        } else {
-            return Err(Error::InvalidFilter);
-        };
+        self.validate_page_size(&first, &last)?;
+        let event_filter = rpc_event_filter(filter)?;
 
         let descending_order = before.is_some();
         let limit = first.or(last).unwrap_or(DEFAULT_PAGE_SIZE) as usize;
@@ -1636,39 +3211,90 @@ impl PgManager {
             .or(before)
             .map(|c| self.parse_event_cursor(c))
             .transpose()?;
-        if let Ok(event_filter) = event_filter {
-            let results = self
-                .inner
-                .query_events_in_blocking_task(event_filter, cursor, limit, descending_order)
-                .await?;
+        let results = self
+            .inner
+            .query_events_in_blocking_task(event_filter, cursor, limit, descending_order)
+            .await?;
 
-            let has_next_page = results.len() > limit;
+        let has_next_page = results.len() > limit;
 
-            let mut connection = Connection::new(false, has_next_page);
-            connection.edges.extend(results.into_iter().map(|e| {
-                let cursor = String::from(e.id);
-                let event = Event {
-                    sending_module_id: Some(MoveModuleId {
-                        package: SuiAddress::from_array(**e.package_id),
-                        name: e.transaction_module.to_string(),
-                    }),
-                    event_type: Some(MoveType::new(
-                        e.type_.to_canonical_string(/* with_prefix */ true),
-                    )),
-                    senders: Some(vec![Address {
-                        address: SuiAddress::from_array(e.sender.to_inner()),
-                    }]),
-                    timestamp: e.timestamp_ms.and_then(|t| DateTime::from_ms(t as i64)),
-                    json: Some(e.parsed_json.to_string()),
-                    bcs: Some(Base64::from(e.bcs)),
-                };
+        let mut connection = Connection::new(false, has_next_page);
+        connection.edges.extend(results.into_iter().map(|e| {
+            let cursor = String::from(e.id);
+            Edge::new(cursor, Event::from(e))
+        }));
+        Ok(Some(connection))
+    }
 
-                Edge::new(cursor, event)
-            }));
-            Ok(Some(connection))
-        } else {
-            Err(Error::InvalidFilter)
-        }
+    /// Fetches the events matching every criterion set in `filter` that were emitted strictly
+    /// between `after_checkpoint` and `before_checkpoint`, in the order they were emitted.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) async fn fetch_filtered_events(
+        &self,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: EventFilter,
+        after_checkpoint: Option<u64>,
+        before_checkpoint: Option<u64>,
+    ) -> Result<Option<Connection<String, Event>>, Error> {
+        validate_cursor_pagination(&first, &after, &last, &before)?;
+        self.validate_page_size(&first, &last)?;
+        let events = self
+            .multi_get_events(
+                first,
+                after,
+                last,
+                before,
+                filter,
+                after_checkpoint,
+                before_checkpoint,
+            )
+            .await?;
+
+        let Some((stored_events, has_previous_page, has_next_page)) = events else {
+            return Ok(None);
+        };
+
+        let cursors: Vec<_> = stored_events
+            .iter()
+            .map(|e| format!("{}:{}", e.tx_sequence_number, e.event_sequence_number))
+            .collect();
+
+        // Rendering events as JSON requires their types' layouts, which are read from the DB.
+        let sui_events = self
+            .inner
+            .spawn_blocking(move |this| {
+                stored_events
+                    .into_iter()
+                    .map(|e| e.try_into_sui_event(&this))
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .await?;
+
+        let mut connection = Connection::new(has_previous_page, has_next_page);
+        connection.edges.extend(
+            cursors
+                .into_iter()
+                .zip(sui_events)
+                .map(|(cursor, e)| Edge::new(cursor, Event::from(e))),
+        );
+
+        Ok(Some(connection))
+    }
+
+    /// The cursor of the latest event matching `filter`, or `None` if no event matches it yet
+    pub(crate) async fn fetch_latest_event_cursor(
+        &self,
+        filter: EventFilter,
+    ) -> Result<Option<String>, Error> {
+        let event_filter = rpc_event_filter(filter)?;
+        let results = self
+            .inner
+            .query_events_in_blocking_task(event_filter, None, 1, /* descending_order */ true)
+            .await?;
+        Ok(results.into_iter().next().map(|e| String::from(e.id)))
     }
 
     pub(crate) async fn fetch_dynamic_fields(
@@ -1679,6 +3305,7 @@ impl PgManager {
         before: Option<String>,
         address: SuiAddress,
     ) -> Result<Option<Connection<String, DynamicField>>, Error> {
+        self.validate_page_size(&first, &last)?;
         let filter = ObjectFilter {
             owner: Some(address),
             ..Default::default()
@@ -1695,38 +3322,68 @@ impl PgManager {
             )
             .await?;
 
-        let Some((stored_objs, has_next_page)) = objs else {
+        let Some((stored_objs, has_next_page, checkpoint)) = objs else {
             return Ok(None);
         };
 
         let mut connection = Connection::new(false, has_next_page);
 
         for stored_obj in stored_objs {
-            let df_object_id = stored_obj.df_object_id.as_ref().ok_or_else(|| {
-                Error::Internal("Dynamic field does not have df_object_id".to_string())
-            })?;
-            let cursor = SuiAddress::from_bytes(df_object_id)
-                .map_err(|e| Error::Internal(format!("{e}")))?;
-            let df_kind = match stored_obj.df_kind {
-                None => Err(Error::Internal("Dynamic field type is not set".to_string())),
-                Some(df_kind) => match df_kind {
-                    0 => Ok(DynamicFieldType::DynamicField),
-                    1 => Ok(DynamicFieldType::DynamicObject),
-                    _ => Err(Error::Internal("Unexpected df_kind value".to_string())),
-                },
-            }?;
+            let dynamic_field = DynamicField::try_from(stored_obj)?;
+            connection.edges.push(Edge::new(
+                format!("{checkpoint}:{}", dynamic_field.df_object_id),
+                dynamic_field,
+            ));
+        }
+
+        Ok(Some(connection))
+    }
+
+    /// Look up the dynamic field of `parent` whose name has the given type and value, using the
+    /// indexed name columns instead of scanning all of the parent's fields. Names given as JSON
+    /// are encoded with their type's layout, from `cache`.
+    pub(crate) async fn fetch_dynamic_field(
+        &self,
+        parent: SuiAddress,
+        name: DynamicFieldName,
+        cache: &PackageCache,
+    ) -> Result<Option<DynamicField>, Error> {
+        let (name_type, name_bcs) = name.into_type_and_bcs(cache).await?;
+        let stored_obj = self
+            .get_dynamic_field(
+                parent.into_vec(),
+                name_type.to_canonical_string(/* with_prefix */ true),
+                name_bcs,
+            )
+            .await?;
+
+        stored_obj.map(DynamicField::try_from).transpose()
+    }
+}
 
-            connection.edges.push(Edge::new(
-                cursor.to_string(),
-                DynamicField {
-                    stored_object: stored_obj,
-                    df_object_id: cursor,
-                    df_kind,
-                },
-            ));
-        }
+impl TryFrom<StoredObject> for DynamicField {
+    type Error = Error;
 
-        Ok(Some(connection))
+    fn try_from(stored_obj: StoredObject) -> Result<Self, Self::Error> {
+        let df_object_id = stored_obj.df_object_id.as_ref().ok_or_else(|| {
+            Error::Internal("Dynamic field does not have df_object_id".to_string())
+        })?;
+        let df_object_id =
+            SuiAddress::from_bytes(df_object_id).map_err(|e| Error::Internal(format!("{e}")))?;
+        let df_kind = match stored_obj.df_kind {
+            None => Err(Error::Internal("Dynamic field type is not set".to_string())),
+            Some(df_kind) => match df_kind {
+                0 => Ok(DynamicFieldType::DynamicField),
+                1 => Ok(DynamicFieldType::DynamicObject),
+                _ => Err(Error::Internal("Unexpected df_kind value".to_string())),
+            },
+        }?;
+
+        Ok(DynamicField {
+            stored_object: stored_obj,
+            df_object_id,
+            df_kind,
+        })
     }
 }
 
@@ -1798,6 +3455,7 @@ impl TryFrom<StoredCheckpoint> for Checkpoint {
                 .transpose()?,
             live_object_set_digest,
             network_total_transactions: Some(c.network_total_transactions as u64),
+            transaction_count: Some(c.tx_digests.len() as u64),
             rolling_gas_summary: Some(GasCostSummary {
                 computation_cost: c.computation_cost as u64,
                 storage_cost: c.storage_cost as u64,
@@ -1805,6 +3463,7 @@ impl TryFrom<StoredCheckpoint> for Checkpoint {
                 non_refundable_storage_fee: c.non_refundable_storage_fee as u64,
             }),
             epoch_id: c.epoch as u64,
+            is_last_in_epoch: Some(c.end_of_epoch),
             end_of_epoch,
         })
     }
@@ -1848,6 +3507,7 @@ impl TryFrom<StoredTransaction> for TransactionBlock {
                     balance_changes,
                     tx.checkpoint_sequence_number as u64,
                     object_changes,
+                    tx.raw_effects,
                     &effects,
                     digest,
                 );
@@ -1888,34 +3548,50 @@ impl TryFrom<StoredTransaction> for TransactionBlock {
     }
 }
 
+impl TryFrom<StoredTransaction> for ExportedTransactionBlock {
+    type Error = Error;
+
+    fn try_from(tx: StoredTransaction) -> Result<Self, Self::Error> {
+        Ok(Self {
+            sequence_number: BigInt::from(tx.tx_sequence_number),
+            digest: Digest::try_from(tx.transaction_digest.as_slice())?,
+            checkpoint: tx.checkpoint_sequence_number as u64,
+            timestamp: DateTime::from_ms(tx.timestamp_ms),
+            transaction_bcs: Base64::from(tx.raw_transaction),
+            effects_bcs: Base64::from(tx.raw_effects),
+        })
+    }
+}
+
 impl TryFrom<StoredObject> for Object {
     type Error = Error;
 
     fn try_from(o: StoredObject) -> Result<Self, Self::Error> {
         let version = o.object_version as u64;
         let (object_id, _sequence_number, digest) = &o.get_object_ref()?;
-        let object: SuiObject = o.try_into()?;
 
-        let kind = if object.owner.is_immutable() {
-            Some(ObjectKind::Immutable)
-        } else if object.owner.is_shared() {
-            Some(ObjectKind::Shared)
-        } else if object.owner.is_child_object() {
-            Some(ObjectKind::Child)
-        } else if object.owner.is_address_owned() {
-            Some(ObjectKind::Owned)
-        } else {
-            None
+        // The owner comes from the columns the indexer filters on, rather than the serialized
+        // object, so that it agrees with the queries that found the object.
+        let kind = ObjectKind::from_owner_type(o.owner_type);
+        let owner = match (kind, &o.owner_id) {
+            (ObjectKind::Immutable | ObjectKind::Shared, Some(_)) => {
+                return Err(Error::Internal(
+                    "Immutable or Shared object should not have an owner_id".to_string(),
+                ));
+            }
+            (ObjectKind::Owned | ObjectKind::Child, None) => {
+                return Err(Error::Internal(
+                    "Owned or Child object should have an owner_id".to_string(),
+                ));
+            }
+            (_, Some(owner_id)) => Some(
+                SuiAddress::from_bytes(owner_id)
+                    .map_err(|e| Error::Internal(format!("Invalid owner_id: {e}")))?,
+            ),
+            (_, None) => None,
         };
 
-        let owner_address = object.owner.get_owner_address().ok();
-        if matches!(kind, Some(ObjectKind::Immutable) | Some(ObjectKind::Shared))
-            && owner_address.is_some()
-        {
-            return Err(Error::Internal(
-                "Immutable or Shared object should not have an owner_id".to_string(),
-            ));
-        }
+        let object: SuiObject = o.try_into()?;
 
         let bcs = Base64::from(
             bcs::to_bytes(&object)
@@ -1927,12 +3603,12 @@ impl TryFrom<StoredObject> for Object {
             version,
             digest: digest.base58_encode(),
             storage_rebate: Some(BigInt::from(object.storage_rebate)),
-            owner: owner_address.map(SuiAddress::from),
+            owner,
             bcs: Some(bcs),
             previous_transaction: Some(Digest::from_array(
                 object.previous_transaction.into_inner(),
             )),
-            kind,
+            kind: Some(kind),
         })
     }
 }
@@ -1968,6 +3644,19 @@ impl TryFrom<StoredEpochInfo> for Epoch {
             validator_set: Some(validator_set),
             start_timestamp: DateTime::from_ms(e.epoch_start_timestamp),
             end_timestamp: e.epoch_end_timestamp.and_then(DateTime::from_ms),
+            total_transactions: e.epoch_total_transactions.map(|t| t as u64),
+            total_gas_fees: e.total_gas_fees.map(|f| BigInt::from(f as u64)),
+            total_stake_rewards: e
+                .total_stake_rewards_distributed
+                .map(|r| BigInt::from(r as u64)),
+            total_stake_subsidies: e.stake_subsidy_amount.map(|s| BigInt::from(s as u64)),
+            fund_size: e.storage_fund_balance.map(|b| BigInt::from(b as u64)),
+            fund_inflow: e.storage_charge.map(|c| BigInt::from(c as u64)),
+            fund_outflow: e.storage_rebate.map(|r| BigInt::from(r as u64)),
+            fund_reinvestment: e.storage_fund_reinvestment.map(|r| BigInt::from(r as u64)),
+            leftover_fund_inflow: e
+                .leftover_storage_fund_inflow
+                .map(|i| BigInt::from(i as u64)),
         })
     }
 }
@@ -2137,8 +3826,40 @@ impl TryFrom<StoredObject> for Coin {
     }
 }
 
-impl From<SuiStake> for Stake {
-    fn from(value: SuiStake) -> Self {
+impl From<SuiEvent> for Event {
+    fn from(e: SuiEvent) -> Self {
+        Event {
+            sending_module_id: Some(MoveModuleId {
+                package: SuiAddress::from_array(**e.package_id),
+                name: e.transaction_module.to_string(),
+            }),
+            event_type: Some(MoveType::new(
+                e.type_.to_canonical_string(/* with_prefix */ true),
+            )),
+            senders: Some(vec![Address {
+                address: SuiAddress::from_array(e.sender.to_inner()),
+            }]),
+            timestamp: e.timestamp_ms.and_then(|t| DateTime::from_ms(t as i64)),
+            json: Some(e.parsed_json.to_string()),
+            bcs: Some(Base64::from(e.bcs)),
+        }
+    }
+}
+
+impl From<SuiCoinMetadata> for CoinMetadata {
+    fn from(metadata: SuiCoinMetadata) -> Self {
+        CoinMetadata {
+            decimals: Some(metadata.decimals),
+            name: Some(metadata.name),
+            symbol: Some(metadata.symbol),
+            description: Some(metadata.description),
+            icon_url: metadata.icon_url,
+        }
+    }
+}
+
+impl From<(StakedSui, SuiStake)> for Stake {
+    fn from((native, value): (StakedSui, SuiStake)) -> Self {
         let mut reward = None;
         let status = match value.status {
             sui_json_rpc_types::StakeStatus::Pending => StakeStatus::Pending,
@@ -2155,8 +3876,80 @@ impl From<SuiStake> for Stake {
             principal: Some(value.principal.into()),
             request_epoch_id: Some(value.stake_request_epoch),
             status: Some(status),
-            staked_sui_id: value.staked_sui_id,
+            native,
+        }
+    }
+}
+
+/// The event filter of the indexer for `filter`. The indexer selects events by a single criterion,
+/// so filters that set more than one are rejected rather than partially applied.
+fn rpc_event_filter(filter: EventFilter) -> Result<RpcEventFilter, Error> {
+    let EventFilter {
+        sender,
+        transaction_digest,
+        emitting_package,
+        emitting_module,
+        event_package,
+        event_module,
+        event_type,
+    } = filter;
+
+    if emitting_module.is_some() && emitting_package.is_none() {
+        return Err(Error::UnsupportedFilter(
+            "'emittingModule' requires 'emittingPackage'".to_string(),
+        ));
+    }
+    if event_module.is_some() && event_package.is_none() {
+        return Err(Error::UnsupportedFilter(
+            "'eventModule' requires 'eventPackage'".to_string(),
+        ));
+    }
+    let criteria = [
+        sender.is_some(),
+        transaction_digest.is_some(),
+        emitting_package.is_some(),
+        event_package.is_some(),
+        event_type.is_some(),
+    ];
+    if criteria.into_iter().filter(|set| *set).count() > 1 {
+        return Err(Error::UnsupportedFilter(
+            "Only one of 'sender', 'transactionDigest', 'emittingPackage', 'eventPackage' and \
+             'eventType' can be set"
+                .to_string(),
+        ));
+    }
+
+    if let Some(sender) = sender {
+        let sender =
+            NativeSuiAddress::from_bytes(sender.into_array()).map_err(|_| Error::InvalidFilter)?;
+        Ok(RpcEventFilter::Sender(sender))
+    } else if let Some(digest) = transaction_digest {
+        let digest = TransactionDigest::from_str(&digest).map_err(|_| Error::InvalidFilter)?;
+        Ok(RpcEventFilter::Transaction(digest))
+    } else if let Some(package) = emitting_package {
+        let package =
+            ObjectID::from_bytes(package.into_array()).map_err(|_| Error::InvalidFilter)?;
+        if let Some(module) = emitting_module {
+            let module = Identifier::from_str(&module).map_err(|_| Error::InvalidFilter)?;
+            Ok(RpcEventFilter::MoveModule { package, module })
+        } else {
+            Ok(RpcEventFilter::Package(package))
         }
+    } else if let Some(event_type) = event_type {
+        let event_type = StructTag::from_str(&event_type).map_err(|_| Error::InvalidFilter)?;
+        Ok(RpcEventFilter::MoveEventType(event_type))
+    } else if let Some(package) = event_package {
+        let Some(module) = event_module else {
+            return Err(Error::UnsupportedFilter(
+                "'eventPackage' requires 'eventModule'".to_string(),
+            ));
+        };
+        let package =
+            ObjectID::from_bytes(package.into_array()).map_err(|_| Error::InvalidFilter)?;
+        let module = Identifier::from_str(&module).map_err(|_| Error::InvalidFilter)?;
+        Ok(RpcEventFilter::MoveEventModule { package, module })
+    } else {
+        Err(Error::InvalidFilter)
     }
 }
 
@@ -2186,9 +3979,116 @@ pub(crate) fn validate_cursor_pagination(
     Ok(())
 }
 
+/// Rejects pages larger than `max_page_size`. Pages are [DEFAULT_PAGE_SIZE] elements long when
+/// neither `first` nor `last` is given.
+pub(crate) fn check_page_size(
+    first: &Option<u64>,
+    last: &Option<u64>,
+    max_page_size: u64,
+) -> Result<(), Error> {
+    let page_size = first.or(*last).unwrap_or(DEFAULT_PAGE_SIZE);
+    if page_size > max_page_size {
+        return Err(DbValidationError::PageSizeExceeded(page_size, max_page_size).into());
+    }
+    Ok(())
+}
+
+/// The checkpoints bounding the transactions selected by the checkpoint criteria of `filter`: the
+/// last checkpoint before them, and the last checkpoint they can be in, each `None` if unbounded.
+/// Returns `None` if no checkpoint can match.
+fn checkpoint_bounds(filter: &TransactionBlockFilter) -> Option<(Option<u64>, Option<u64>)> {
+    let checkpoint_before_range = match (filter.at_checkpoint, filter.after_checkpoint) {
+        (Some(at), _) => at.checked_sub(1),
+        (None, after) => after,
+    };
+
+    let last_checkpoint_in_range = match (filter.at_checkpoint, filter.before_checkpoint) {
+        (Some(at), _) => Some(at),
+        // Nothing comes before the genesis checkpoint
+        (None, Some(0)) => return None,
+        (None, before) => before.map(|before| before - 1),
+    };
+
+    Some((checkpoint_before_range, last_checkpoint_in_range))
+}
+
+/// Trims the rows of a page that was fetched with one extra row (`limit + 1`) to find out whether
+/// there are more. Pages of `last` elements are fetched in descending order and put back in
+/// ascending order. Returns the rows, and whether there are previous and next pages: there is a
+/// page on the side of the cursor if one was given, and on the other side if the extra row was
+/// found.
+pub(crate) fn page_of_rows<T>(
+    mut rows: Vec<T>,
+    limit: i64,
+    descending_order: bool,
+    has_cursor: bool,
+) -> (Vec<T>, bool, bool) {
+    let has_more = rows.len() as i64 > limit;
+    if has_more {
+        rows.pop();
+    }
+
+    if descending_order {
+        rows.reverse();
+        (rows, has_more, has_cursor)
+    } else {
+        (rows, has_cursor, has_more)
+    }
+}
+
+/// Paginates elements that are already in memory, identified by their (increasing) index in the
+/// list they come from, which is also their cursor. Pages are [DEFAULT_PAGE_SIZE] elements long
+/// when neither `first` nor `last` is given.
+pub(crate) fn page_of_indexed<T: OutputType>(
+    elements: impl IntoIterator<Item = (usize, T)>,
+    first: Option<u64>,
+    after: Option<String>,
+    last: Option<u64>,
+    before: Option<String>,
+) -> Result<Connection<String, T>, Error> {
+    let parse_cursor = |cursor: String| {
+        cursor
+            .parse::<usize>()
+            .map_err(|_| Error::InvalidCursor(cursor))
+    };
+    let after = after.map(parse_cursor).transpose()?;
+    let before = before.map(parse_cursor).transpose()?;
+
+    let mut has_previous_page = false;
+    let mut has_next_page = false;
+    let mut page = vec![];
+    for (ix, element) in elements {
+        if after.is_some_and(|a| ix <= a) {
+            has_previous_page = true;
+        } else if before.is_some_and(|b| ix >= b) {
+            has_next_page = true;
+        } else {
+            page.push((ix, element));
+        }
+    }
+
+    if let Some(last) = last {
+        let excess = page.len().saturating_sub(last as usize);
+        has_previous_page |= excess > 0;
+        page.drain(..excess);
+    } else {
+        let first = first.unwrap_or(DEFAULT_PAGE_SIZE) as usize;
+        has_next_page |= page.len() > first;
+        page.truncate(first);
+    }
+
+    let mut connection = Connection::new(has_previous_page, has_next_page);
+    connection.edges.extend(
+        page.into_iter()
+            .map(|(ix, element)| Edge::new(ix.to_string(), element)),
+    );
+    Ok(connection)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use sui_indexer::types_v2::IndexedObject;
 
     #[test]
     fn test_invalid_json() {
@@ -2231,4 +4131,580 @@ mod tests {
         let result = extract_cost(explain_result).unwrap();
         assert_eq!(result, 1.0);
     }
+
+    fn stored_obj_at(checkpoint_sequence_number: i64) -> StoredObject {
+        StoredObject {
+            object_id: vec![0; 32],
+            object_version: 1,
+            object_digest: vec![0; 32],
+            checkpoint_sequence_number,
+            owner_type: OwnerType::Address as i16,
+            owner_id: Some(vec![0; 32]),
+            object_type: None,
+            serialized_object: vec![],
+            coin_type: None,
+            coin_balance: None,
+            df_kind: None,
+            df_name: None,
+            df_object_type: None,
+            df_object_id: None,
+            df_name_type: None,
+            df_name_bcs: None,
+        }
+    }
+
+    #[test]
+    fn test_snapshot_of_first_page() {
+        let objs = [stored_obj_at(3), stored_obj_at(7)];
+        assert_eq!(page_checkpoint(Snapshot::Latest(5), &objs), 7);
+        assert_eq!(page_checkpoint(Snapshot::Latest(9), &objs), 9);
+        assert_eq!(page_checkpoint(Snapshot::Latest(9), &[]), 9);
+    }
+
+    #[test]
+    fn test_export_batch_limit() {
+        assert_eq!(export_batch_limit(None, 100).unwrap(), 100);
+        assert_eq!(export_batch_limit(Some(10), 100).unwrap(), 10);
+        assert!(matches!(
+            export_batch_limit(Some(0), 100),
+            Err(Error::DbValidation(DbValidationError::EmptyExportBatch))
+        ));
+        assert!(matches!(
+            export_batch_limit(None, 0),
+            Err(Error::DbValidation(DbValidationError::EmptyExportBatch))
+        ));
+        assert!(matches!(
+            export_batch_limit(Some(101), 100),
+            Err(Error::DbValidation(
+                DbValidationError::ExportBatchSizeExceeded(101, 100)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_balance_snapshots() {
+        let summarize = |snapshots: Vec<Edge<String, BalanceSnapshot>>| -> Vec<_> {
+            snapshots
+                .into_iter()
+                .map(|edge| {
+                    (
+                        edge.cursor,
+                        edge.node.checkpoint_sequence_number,
+                        edge.node.balance_change,
+                        edge.node.total_balance,
+                    )
+                })
+                .collect()
+        };
+        let expected = |snapshots: &[(u64, i128, i128)]| -> Vec<_> {
+            snapshots
+                .iter()
+                .map(|(checkpoint, change, total)| {
+                    (
+                        checkpoint.to_string(),
+                        *checkpoint,
+                        BigInt::from(*change),
+                        BigInt::from(*total),
+                    )
+                })
+                .collect()
+        };
+
+        // A balance of 100 after it went up by 70, down by 20, and up by 50.
+        assert_eq!(
+            summarize(balance_snapshots(vec![(3, 70), (5, -20), (8, 50)], 100)),
+            expected(&[(3, 70, 70), (5, -20, 50), (8, 50, 100)]),
+        );
+
+        // Neither changes nor totals are limited to the range of an i64.
+        let max = u64::MAX as i128;
+        assert_eq!(
+            summarize(balance_snapshots(vec![(1, max), (2, max)], 2 * max)),
+            expected(&[(1, max, max), (2, max, 2 * max)]),
+        );
+
+        assert!(balance_snapshots(vec![], 0).is_empty());
+    }
+
+    #[test]
+    fn test_object_kind_from_stored_owner_type() {
+        let owner = SuiAddress::from_array([1; 32]);
+        let stored = |owner_type: i16, owner_id: Option<Vec<u8>>| StoredObject {
+            owner_type,
+            owner_id,
+            ..StoredObject::from(IndexedObject::from_object(
+                0,
+                SuiObject::with_owner_for_testing(NativeSuiAddress::from(owner)),
+                None,
+            ))
+        };
+
+        let object = Object::try_from(stored(OwnerType::Address as i16, Some(owner.into_vec())));
+        assert_eq!(object.unwrap().kind, Some(ObjectKind::Owned));
+
+        // An owner type the indexer introduced after this service was built still loads.
+        let object = Object::try_from(stored(42, Some(owner.into_vec())));
+        let object = object.unwrap();
+        assert_eq!(object.kind, Some(ObjectKind::Unknown));
+        assert_eq!(object.owner, Some(owner));
+
+        assert!(matches!(
+            Object::try_from(stored(OwnerType::Shared as i16, Some(owner.into_vec()))),
+            Err(Error::Internal(_))
+        ));
+        assert!(matches!(
+            Object::try_from(stored(OwnerType::Address as i16, None)),
+            Err(Error::Internal(_))
+        ));
+    }
+
+    #[test]
+    fn test_object_owner_from_stored_columns() {
+        let serialized_owner = SuiAddress::from_array([1; 32]);
+        let indexed_owner = SuiAddress::from_array([2; 32]);
+        let stored = |owner_type: i16, owner_id: Option<Vec<u8>>| StoredObject {
+            owner_type,
+            owner_id,
+            ..StoredObject::from(IndexedObject::from_object(
+                0,
+                SuiObject::with_owner_for_testing(NativeSuiAddress::from(serialized_owner)),
+                None,
+            ))
+        };
+
+        // When the columns disagree with the serialized object, the columns win.
+        let object = Object::try_from(stored(
+            OwnerType::Object as i16,
+            Some(indexed_owner.into_vec()),
+        ))
+        .unwrap();
+        assert_eq!(object.kind, Some(ObjectKind::Child));
+        assert_eq!(object.owner, Some(indexed_owner));
+
+        let object = Object::try_from(stored(OwnerType::Immutable as i16, None)).unwrap();
+        assert_eq!(object.kind, Some(ObjectKind::Immutable));
+        assert_eq!(object.owner, None);
+    }
+
+    #[test]
+    fn test_gas_sponsor_stats() {
+        assert_eq!(gas_sponsor_stats((None, None, None, None)), None);
+        assert_eq!(gas_sponsor_stats((Some(0), Some(0), None, None)), None);
+        assert_eq!(
+            gas_sponsor_stats((Some(3), Some(2), Some(5_000_000), Some(1_000))),
+            Some(GasSponsorStats {
+                sponsored_transaction_count: Some(3),
+                sender_count: Some(2),
+                max_gas_budget: Some(BigInt::from(5_000_000i64)),
+                max_gas_price: Some(BigInt::from(1_000i64)),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_balance_change_sum() {
+        assert_eq!(parse_balance_change_sum(None).unwrap(), 0);
+        assert_eq!(
+            parse_balance_change_sum(Some("-36893488147419103230".to_string())).unwrap(),
+            -2 * u64::MAX as i128,
+        );
+        assert!(parse_balance_change_sum(Some("1.5".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_of_later_page() {
+        // later pages are read as of the checkpoint in their cursor, whatever the objects' own
+        let objs = [stored_obj_at(3), stored_obj_at(7)];
+        assert_eq!(page_checkpoint(Snapshot::Cursor(7), &objs), 7);
+        assert_eq!(page_checkpoint(Snapshot::Cursor(6), &objs), 6);
+    }
+
+    #[test]
+    fn test_parse_snapshot_cursor() {
+        assert_eq!(parse_snapshot_cursor("7:0x1", 0).unwrap(), (Some(7), "0x1"));
+        assert_eq!(
+            parse_snapshot_cursor("7:10:0x1", 1).unwrap(),
+            (Some(7), "10:0x1")
+        );
+        // cursors from before the checkpoint was added are still accepted
+        assert_eq!(parse_snapshot_cursor("0x1", 0).unwrap(), (None, "0x1"));
+        assert_eq!(
+            parse_snapshot_cursor("10:0x1", 1).unwrap(),
+            (None, "10:0x1")
+        );
+        assert!(parse_snapshot_cursor("x:0x1", 0).is_err());
+        assert!(parse_snapshot_cursor("0x1", 1).is_err());
+    }
+
+    #[test]
+    fn test_as_of_checkpoint() {
+        let query = QueryBuilder::multi_get_objs_history(None, false, 10, None, None)
+            .unwrap()
+            .filter(QueryBuilder::as_of_checkpoint(42));
+        let sql = diesel::debug_query::<Pg, _>(&query).to_string();
+        assert!(
+            sql.contains("objects_history.checkpoint_sequence_number <= 42"),
+            "{sql}"
+        );
+        assert!(
+            sql.contains("later.checkpoint_sequence_number <= 42"),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn test_check_page_size() {
+        assert!(check_page_size(&Some(50), &None, 50).is_ok());
+        assert!(check_page_size(&None, &Some(50), 50).is_ok());
+        assert!(check_page_size(&None, &None, DEFAULT_PAGE_SIZE).is_ok());
+        assert!(matches!(
+            check_page_size(&Some(51), &None, 50),
+            Err(Error::DbValidation(DbValidationError::PageSizeExceeded(
+                51, 50
+            )))
+        ));
+        assert!(matches!(
+            check_page_size(&None, &Some(51), 50),
+            Err(Error::DbValidation(DbValidationError::PageSizeExceeded(
+                51, 50
+            )))
+        ));
+        assert!(check_page_size(&None, &None, DEFAULT_PAGE_SIZE - 1).is_err());
+    }
+
+    #[test]
+    fn test_page_of_rows_first() {
+        // The extra row means there is a next page, and no cursor means no previous page.
+        assert_eq!(
+            page_of_rows(vec![1, 2, 3], 2, false, false),
+            (vec![1, 2], false, true)
+        );
+        assert_eq!(
+            page_of_rows(vec![3, 4], 2, false, true),
+            (vec![3, 4], true, false)
+        );
+    }
+
+    #[test]
+    fn test_page_of_rows_last() {
+        // Rows come back in descending order, and are returned in ascending order.
+        assert_eq!(
+            page_of_rows(vec![9, 8, 7], 2, true, false),
+            (vec![8, 9], true, false)
+        );
+        assert_eq!(
+            page_of_rows(vec![6, 5], 2, true, true),
+            (vec![5, 6], false, true)
+        );
+    }
+
+    #[test]
+    fn test_history_tombstone_has_no_object() {
+        let indexed = IndexedObject::from_object(1, SuiObject::new_gas_for_testing(), None);
+        let oref = indexed.object.compute_object_reference();
+        let tombstone = StoredHistoryObject::tombstone(oref, 1);
+        assert!(tombstone.is_tombstone());
+        assert!(StoredObject::try_from(tombstone).is_err());
+
+        let stored = StoredHistoryObject::from(StoredObject::from(indexed));
+        let object = Object::try_from(StoredObject::try_from(stored).unwrap()).unwrap();
+        assert_eq!(object.version, oref.1.value());
+    }
+
+    fn page_indices(connection: &Connection<String, u64>) -> Vec<u64> {
+        connection.edges.iter().map(|edge| edge.node).collect()
+    }
+
+    #[test]
+    fn test_page_of_indexed_first() {
+        let elements = || (0..5usize).map(|ix| (ix, ix as u64));
+        let page = page_of_indexed(elements(), Some(2), None, None, None).unwrap();
+        assert_eq!(page_indices(&page), vec![0, 1]);
+        assert!(!page.has_previous_page && page.has_next_page);
+
+        let page = page_of_indexed(elements(), Some(2), Some("2".to_string()), None, None).unwrap();
+        assert_eq!(page_indices(&page), vec![3, 4]);
+        assert!(page.has_previous_page && !page.has_next_page);
+    }
+
+    #[test]
+    fn test_page_of_indexed_last() {
+        let elements = || (0..5usize).map(|ix| (ix, ix as u64));
+        let page = page_of_indexed(elements(), None, None, Some(2), Some("4".to_string())).unwrap();
+        assert_eq!(page_indices(&page), vec![2, 3]);
+        assert!(page.has_previous_page && page.has_next_page);
+
+        let page = page_of_indexed(elements(), None, None, Some(10), None).unwrap();
+        assert_eq!(page_indices(&page), vec![0, 1, 2, 3, 4]);
+        assert!(!page.has_previous_page && !page.has_next_page);
+    }
+
+    #[test]
+    fn test_page_of_indexed_default_page_size() {
+        let elements = (0..20usize).map(|ix| (ix, ix as u64));
+        let page = page_of_indexed(elements, None, None, None, None).unwrap();
+        assert_eq!(page.edges.len(), DEFAULT_PAGE_SIZE as usize);
+        assert!(page.has_next_page);
+    }
+
+    #[test]
+    fn test_page_of_indexed_invalid_cursor() {
+        let elements = (0..5usize).map(|ix| (ix, ix as u64));
+        let result = page_of_indexed(elements, None, Some("x".to_string()), None, None);
+        assert!(matches!(result, Err(Error::InvalidCursor(_))));
+    }
+
+    #[test]
+    fn test_rpc_event_filter_single_criterion() {
+        let package = SuiAddress::from_array([1; 32]);
+        let filter = EventFilter {
+            event_package: Some(package),
+            event_module: Some("m".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            rpc_event_filter(filter),
+            Ok(RpcEventFilter::MoveEventModule { module, .. }) if module.as_str() == "m"
+        ));
+
+        let filter = EventFilter {
+            emitting_package: Some(package),
+            ..Default::default()
+        };
+        assert!(matches!(
+            rpc_event_filter(filter),
+            Ok(RpcEventFilter::Package(_))
+        ));
+    }
+
+    #[test]
+    fn test_rpc_event_filter_rejects_combinations() {
+        let package = SuiAddress::from_array([1; 32]);
+        let combined = EventFilter {
+            sender: Some(package),
+            event_type: Some("0x2::coin::CoinMetadata".to_string()),
+            ..Default::default()
+        };
+        let module_only = EventFilter {
+            emitting_module: Some("m".to_string()),
+            ..Default::default()
+        };
+        let package_only = EventFilter {
+            event_package: Some(package),
+            ..Default::default()
+        };
+        for filter in [combined, module_only, package_only] {
+            assert!(matches!(
+                rpc_event_filter(filter),
+                Err(Error::UnsupportedFilter(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_multi_get_events_last_page() {
+        let query = QueryBuilder::multi_get_events(
+            Some((5, 2)),
+            /* descending_order */ true,
+            10,
+            EventFilter::default(),
+            None,
+            None,
+        )
+        .unwrap();
+        let sql = diesel::debug_query::<Pg, _>(&query).to_string();
+        assert!(
+            sql.contains(r#""events"."tx_sequence_number" < $"#),
+            "{sql}"
+        );
+        assert!(
+            sql.contains(r#"ORDER BY "events"."tx_sequence_number" DESC"#),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn test_multi_get_events_by_event_package() {
+        let package = SuiAddress::from_array([1; 32]);
+        let filter = EventFilter {
+            event_package: Some(package),
+            ..Default::default()
+        };
+        let query = QueryBuilder::multi_get_events(None, false, 10, filter, None, None).unwrap();
+        let sql = diesel::debug_query::<Pg, _>(&query).to_string();
+        assert!(sql.contains(r#""events"."event_type" LIKE $"#), "{sql}");
+        assert!(sql.contains(&format!("{package}::%")), "{sql}");
+        assert!(!sql.contains(r#""events"."package" ="#), "{sql}");
+    }
+
+    #[test]
+    fn test_validate_qualified_function() {
+        let package = SuiAddress::from_array([2; 32]);
+        let mut filter = TransactionBlockFilter {
+            function: Some(format!("{package}::coin::split")),
+            ..Default::default()
+        };
+        PgManager::validate_tx_block_filter(&mut filter).unwrap();
+        assert_eq!(filter.package, Some(package));
+        assert_eq!(filter.module.as_deref(), Some("coin"));
+        assert_eq!(filter.function.as_deref(), Some("split"));
+
+        // A prefix of a qualified function selects all the functions of the module
+        let mut filter = TransactionBlockFilter {
+            function: Some("0x2::coin".to_string()),
+            ..Default::default()
+        };
+        PgManager::validate_tx_block_filter(&mut filter).unwrap();
+        assert_eq!(filter.package, Some(SuiAddress::from_str("0x2").unwrap()));
+        assert_eq!(filter.module.as_deref(), Some("coin"));
+        assert_eq!(filter.function, None);
+    }
+
+    #[test]
+    fn test_validate_invalid_qualified_function() {
+        for function in [
+            "0x2::",
+            "0x2::coin::",
+            "0x2::coin::split::extra",
+            "pkg::coin::split",
+        ] {
+            let mut filter = TransactionBlockFilter {
+                function: Some(function.to_string()),
+                ..Default::default()
+            };
+            assert!(
+                matches!(
+                    PgManager::validate_tx_block_filter(&mut filter),
+                    Err(Error::DbValidation(
+                        DbValidationError::InvalidQualifiedFunction(_)
+                    ))
+                ),
+                "{function}"
+            );
+        }
+
+        let mut filter = TransactionBlockFilter {
+            package: Some(SuiAddress::from_array([2; 32])),
+            function: Some("0x2::coin::split".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            PgManager::validate_tx_block_filter(&mut filter),
+            Err(Error::DbValidation(
+                DbValidationError::QualifiedFunctionWithPackageOrModule
+            ))
+        ));
+
+        // An unqualified function needs its package and module
+        let mut filter = TransactionBlockFilter {
+            function: Some("split".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            PgManager::validate_tx_block_filter(&mut filter),
+            Err(Error::DbValidation(
+                DbValidationError::RequiresPackageAndModule
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_validate_checkpoint_criteria() {
+        let mut filter = TransactionBlockFilter {
+            at_checkpoint: Some(5),
+            after_checkpoint: Some(4),
+            ..Default::default()
+        };
+        assert!(matches!(
+            PgManager::validate_tx_block_filter(&mut filter),
+            Err(Error::DbValidation(
+                DbValidationError::InvalidCheckpointCombination
+            ))
+        ));
+
+        let mut filter = TransactionBlockFilter {
+            after_checkpoint: Some(5),
+            before_checkpoint: Some(5),
+            ..Default::default()
+        };
+        assert!(matches!(
+            PgManager::validate_tx_block_filter(&mut filter),
+            Err(Error::DbValidation(
+                DbValidationError::InvalidCheckpointOrder
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_checkpoint_bounds() {
+        let bounds = |after_checkpoint, at_checkpoint, before_checkpoint| {
+            checkpoint_bounds(&TransactionBlockFilter {
+                after_checkpoint,
+                at_checkpoint,
+                before_checkpoint,
+                ..Default::default()
+            })
+        };
+
+        assert_eq!(bounds(None, None, None), Some((None, None)));
+        // The transactions of a checkpoint come after those of the checkpoint before it
+        assert_eq!(bounds(None, Some(5), None), Some((Some(4), Some(5))));
+        assert_eq!(bounds(None, Some(0), None), Some((None, Some(0))));
+        assert_eq!(bounds(Some(3), None, Some(7)), Some((Some(3), Some(6))));
+        assert_eq!(bounds(Some(3), None, None), Some((Some(3), None)));
+        assert_eq!(bounds(None, None, Some(1)), Some((None, Some(0))));
+        // Nothing comes before the genesis checkpoint
+        assert_eq!(bounds(None, None, Some(0)), None);
+    }
+
+    #[test]
+    fn test_multi_get_coins_by_balance_last_page() {
+        let query = QueryBuilder::multi_get_coins_by_balance(
+            Some((100, vec![1; 32])),
+            /* descending_order */ true,
+            10,
+            vec![2; 32],
+            "0x2::sui::SUI".to_string(),
+            Some(50),
+        );
+        let sql = diesel::debug_query::<Pg, _>(&query).to_string();
+        assert!(sql.contains(r#""objects"."coin_balance" >= $"#), "{sql}");
+        assert!(sql.contains(r#""objects"."coin_balance" < $"#), "{sql}");
+        assert!(
+            sql.contains(r#"ORDER BY "objects"."coin_balance" DESC, "objects"."object_id" DESC"#),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn test_multi_get_coins_by_balance_history_first_page() {
+        let query = QueryBuilder::multi_get_coins_by_balance_history(
+            None,
+            /* descending_order */ false,
+            10,
+            vec![2; 32],
+            "0x2::sui::SUI".to_string(),
+            None,
+        );
+        let sql = diesel::debug_query::<Pg, _>(&query).to_string();
+        assert!(
+            !sql.contains(r#""objects_history"."coin_balance" >="#),
+            "{sql}"
+        );
+        assert!(
+            sql.contains(r#"ORDER BY "objects_history"."coin_balance" ASC"#),
+            "{sql}"
+        );
+    }
+
+    #[test]
+    fn test_page_of_coins_by_balance() {
+        // The last page of coins is read in descending order of balance, and put back in
+        // ascending order, with the coins before it as its previous page.
+        let (coins, has_previous_page, has_next_page) =
+            page_of_rows(vec![30, 20, 10], 2, /* descending_order */ true, true);
+        assert_eq!(coins, vec![20, 30]);
+        assert!(has_previous_page);
+        assert!(has_next_page);
+    }
 }
@@ -10,11 +10,26 @@ use crate::types::sui_address::SuiAddress;
 use crate::types::validator::Validator;
 use crate::types::validator_credentials::ValidatorCredentials;
 
+use crate::error::Error;
+
+use async_graphql::Context;
 use sui_sdk::types::sui_system_state::sui_system_state_summary::SuiSystemStateSummary;
 use sui_sdk::types::{
     base_types::SuiAddress as NativeSuiAddress,
     sui_system_state::sui_system_state_summary::SuiValidatorSummary,
 };
+use sui_sdk::SuiClient;
+
+/// The client of the fullnode that transactions are executed and simulated through, if one is
+/// configured.
+pub(crate) fn sui_sdk_client<'ctx>(ctx: &Context<'ctx>) -> Result<&'ctx SuiClient, Error> {
+    match ctx.data_opt::<Option<SuiClient>>() {
+        Some(Some(sui_sdk_client)) => Ok(sui_sdk_client),
+        _ => Err(Error::Internal(
+            "Transactions cannot be executed or simulated without a fullnode".to_string(),
+        )),
+    }
+}
 
 pub(crate) fn convert_to_validators(
     validators: Vec<SuiValidatorSummary>,
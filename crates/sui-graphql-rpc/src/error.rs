@@ -1,8 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use async_graphql::{ErrorExtensionValues, ErrorExtensions, Pos, Response, ServerError};
+use async_graphql::{ErrorExtensionValues, ErrorExtensions, Pos, Response, ServerError, Value};
 use async_graphql_axum::GraphQLResponse;
+use serde_json::json;
 use sui_indexer::errors::IndexerError;
 use sui_json_rpc::name_service::DomainParseError;
 
@@ -11,10 +12,35 @@ use crate::context_data::db_data_provider::DbValidationError;
 /// Error codes for the `extensions.code` field of a GraphQL error that originates from outside
 /// GraphQL.
 /// `<https://www.apollographql.com/docs/apollo-server/data/errors/#built-in-error-codes>`
+///
+/// Every error produced by the service also sets `extensions.retryable`, to tell clients whether
+/// the same request could succeed if retried later (see [retryable]), and errors that carry
+/// structured information about their cause set it as an object in `extensions.details`.
 pub(crate) mod code {
     pub const BAD_REQUEST: &str = "BAD_REQUEST";
     pub const BAD_USER_INPUT: &str = "BAD_USER_INPUT";
     pub const INTERNAL_SERVER_ERROR: &str = "INTERNAL_SERVER_ERROR";
+    pub const NOT_FOUND: &str = "NOT_FOUND";
+    pub const RATE_LIMITED: &str = "RATE_LIMITED";
+    pub const REQUEST_TIMEOUT: &str = "REQUEST_TIMEOUT";
+}
+
+/// Whether a request that failed with an error with this `code` could succeed if it was retried
+/// unchanged (with backoff): after the rate limit resets, once the service is under less load, or
+/// once the internal failure has been resolved.
+pub(crate) fn retryable(code: &str) -> bool {
+    matches!(
+        code,
+        code::INTERNAL_SERVER_ERROR | code::RATE_LIMITED | code::REQUEST_TIMEOUT
+    )
+}
+
+/// The `extensions` of an error with this `code`.
+fn error_extensions(code: &str) -> ErrorExtensionValues {
+    let mut ext = ErrorExtensionValues::default();
+    ext.set("code", code);
+    ext.set("retryable", retryable(code));
+    ext
 }
 
 /// Create a GraphQL Response containing an Error.
@@ -32,32 +58,26 @@ pub(crate) fn graphql_error_response(code: &str, message: impl Into<String>) ->
 ///
 /// This error has no path, source, or locations, just a message and an error code.
 pub(crate) fn graphql_error(code: &str, message: impl Into<String>) -> ServerError {
-    let mut ext = ErrorExtensionValues::default();
-    ext.set("code", code);
-
     ServerError {
         message: message.into(),
         source: None,
         locations: vec![],
         path: vec![],
-        extensions: Some(ext),
+        extensions: Some(error_extensions(code)),
     }
 }
 
 pub(crate) fn graphql_error_at_pos(
     code: &str,
     message: impl Into<String>,
-    pos: Pos,
+    pos: impl Into<Option<Pos>>,
 ) -> ServerError {
-    let mut ext = ErrorExtensionValues::default();
-    ext.set("code", code);
-
     ServerError {
         message: message.into(),
         source: None,
-        locations: vec![pos],
+        locations: pos.into().into_iter().collect(),
         path: vec![],
-        extensions: Some(ext),
+        extensions: Some(error_extensions(code)),
     }
 }
 
@@ -69,12 +89,18 @@ pub enum Error {
     ProtocolVersionUnsupported(u64, u64),
     #[error("Invalid filter option or value provided")]
     InvalidFilter,
+    #[error("Unsupported filter: {0}")]
+    UnsupportedFilter(String),
+    #[error("Invalid dynamic field name: {0}")]
+    InvalidDynamicFieldName(String),
     #[error(transparent)]
     DomainParse(#[from] DomainParseError),
     #[error(transparent)]
     DbValidation(#[from] DbValidationError),
     #[error("Provide one of digest or sequence_number, not both")]
     InvalidCheckpointQuery,
+    #[error("Provide exactly one of version or checkpoint")]
+    InvalidObjectAtQuery,
     #[error("String is not valid base58: {0}")]
     InvalidBase58(String),
     #[error("Invalid digest length: expected {expected}, actual {actual}")]
@@ -87,35 +113,71 @@ pub enum Error {
     _CursorNoReversePagination,
     #[error("Invalid cursor: {0}")]
     InvalidCursor(String),
-    #[error("Data has changed since cursor was generated: {0}")]
-    _CursorConnectionFetchFailed(String),
     #[error("Error received in multi-get query: {0}")]
     MultiGet(String),
+    #[error("Invalid transaction: {0}")]
+    InvalidTransaction(String),
+    #[error("{0} not found")]
+    NotFound(String),
     #[error("Internal error occurred while processing request: {0}")]
     Internal(String),
 }
 
-impl ErrorExtensions for Error {
-    fn extend(&self) -> async_graphql::Error {
-        async_graphql::Error::new(format!("{}", self)).extend_with(|_err, e| match self {
+impl Error {
+    /// The code that clients can use to tell this kind of error apart from others.
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
             Error::DynamicFieldOnAddress
             | Error::InvalidFilter
+            | Error::UnsupportedFilter(_)
+            | Error::InvalidDynamicFieldName(_)
             | Error::ProtocolVersionUnsupported { .. }
             | Error::DomainParse(_)
             | Error::DbValidation(_)
             | Error::InvalidCheckpointQuery
+            | Error::InvalidObjectAtQuery
             | Error::CursorNoBeforeAfter
             | Error::CursorNoFirstLast
             | Error::_CursorNoReversePagination
             | Error::InvalidCursor(_)
-            | Error::_CursorConnectionFetchFailed(_)
             | Error::MultiGet(_)
+            | Error::InvalidTransaction(_)
             | Error::InvalidBase58(_)
-            | Error::InvalidDigestLength { .. } => {
-                e.set("code", code::BAD_USER_INPUT);
+            | Error::InvalidDigestLength { .. } => code::BAD_USER_INPUT,
+            Error::NotFound(_) => code::NOT_FOUND,
+            Error::Internal(_) => code::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// Structured information about the cause of the error, for errors that have any.
+    fn details(&self) -> Option<Value> {
+        let details = match self {
+            Error::ProtocolVersionUnsupported(min, max) => json!({ "min": min, "max": max }),
+            Error::InvalidDigestLength { expected, actual } => {
+                json!({ "expected": expected, "actual": actual })
             }
-            Error::Internal(_) => {
-                e.set("code", code::INTERNAL_SERVER_ERROR);
+            Error::DbValidation(DbValidationError::QueryCostExceeded(cost, limit)) => {
+                json!({ "cost": cost, "limit": limit })
+            }
+            Error::DbValidation(
+                DbValidationError::ExportBatchSizeExceeded(requested, limit)
+                | DbValidationError::MultiGetObjectsLimitExceeded(requested, limit),
+            ) => json!({ "requested": requested, "limit": limit }),
+            _ => return None,
+        };
+
+        Value::from_json(details).ok()
+    }
+}
+
+impl ErrorExtensions for Error {
+    fn extend(&self) -> async_graphql::Error {
+        async_graphql::Error::new(format!("{}", self)).extend_with(|_err, e| {
+            let code = self.code();
+            e.set("code", code);
+            e.set("retryable", retryable(code));
+            if let Some(details) = self.details() {
+                e.set("details", details);
             }
         })
     }
@@ -126,3 +188,46 @@ impl From<IndexerError> for Error {
         Error::Internal(e.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn extension(err: &async_graphql::Error, name: &str) -> Option<Value> {
+        err.extensions.as_ref()?.get(name).cloned()
+    }
+
+    #[test]
+    fn test_error_extensions() {
+        let err = Error::InvalidDigestLength {
+            expected: 32,
+            actual: 16,
+        }
+        .extend();
+        assert_eq!(
+            extension(&err, "code"),
+            Some(Value::from(code::BAD_USER_INPUT))
+        );
+        assert_eq!(extension(&err, "retryable"), Some(Value::from(false)));
+        assert_eq!(
+            extension(&err, "details"),
+            Value::from_json(json!({ "expected": 32, "actual": 16 })).ok()
+        );
+
+        let err = Error::Internal("boom".to_string()).extend();
+        assert_eq!(
+            extension(&err, "code"),
+            Some(Value::from(code::INTERNAL_SERVER_ERROR))
+        );
+        assert_eq!(extension(&err, "retryable"), Some(Value::from(true)));
+        assert_eq!(extension(&err, "details"), None);
+    }
+
+    #[test]
+    fn test_server_error_extensions() {
+        let err = graphql_error(code::RATE_LIMITED, "Too many requests");
+        let ext = err.extensions.unwrap();
+        assert_eq!(ext.get("code"), Some(&Value::from(code::RATE_LIMITED)));
+        assert_eq!(ext.get("retryable"), Some(&Value::from(true)));
+    }
+}
@@ -0,0 +1,357 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use async_graphql::{
+    extensions::{
+        Extension, ExtensionContext, ExtensionFactory, NextExecute, NextParseQuery, NextRequest,
+        NextResolve, ResolveInfo,
+    },
+    parser::types::{
+        ExecutableDocument, FragmentDefinition, OperationType, Selection, SelectionSet,
+    },
+    value, Name, Positioned, Response, ServerResult, Value, Variables,
+};
+use async_trait::async_trait;
+
+use crate::{
+    config::ServiceConfig, extensions::query_limits_checker::ShowUsage,
+    server::response_cache::ResponseCache,
+};
+
+/// Types whose fields never change once the value has been produced, e.g. because they describe
+/// a transaction or checkpoint that has already been finalized. Whether the value itself can
+/// change is up to the field that produced it.
+const IMMUTABLE_TYPES: &[&str] = &[
+    "AuthenticatorStateUpdate",
+    "BalanceChange",
+    "ChangeEpochTransaction",
+    "Checkpoint",
+    "CommitteeMember",
+    "ConsensusCommitPrologueTransaction",
+    "EndOfEpochData",
+    "EndOfEpochTransaction",
+    "Event",
+    "GasCostSummary",
+    "GasEffects",
+    "GasInput",
+    "GenesisTransaction",
+    "ObjectChange",
+    "ProgrammableTransaction",
+    "ProtocolConfigAttr",
+    "ProtocolConfigFeatureFlag",
+    "ProtocolConfigs",
+    "TransactionBlock",
+    "TransactionBlockEffects",
+    "TransactionSignature",
+];
+
+/// Fields that never change, on types that are not immutable overall.
+const IMMUTABLE_FIELDS: &[(&str, &str)] = &[
+    // These are looked up by an ID, which is checked for in `parse_query`.
+    ("Query", "checkpoint"),
+    ("Query", "epoch"),
+    ("Query", "transactionBlock"),
+    // These are fixed at the start of an epoch.
+    ("Epoch", "epochId"),
+    ("Epoch", "protocolConfigs"),
+    ("Epoch", "referenceGasPrice"),
+    ("Epoch", "startTimestamp"),
+];
+
+/// Fields that can change, on types that are otherwise immutable.
+const MUTABLE_FIELDS: &[(&str, &str)] = &[
+    // Not set until the transaction is included in a checkpoint.
+    ("TransactionBlockEffects", "checkpoint"),
+];
+
+/// Fields whose values depend on who is asking, so responses that include them are never cached.
+const UNCACHEABLE_FIELDS: &[(&str, &str)] = &[("Query", "apiKeyUsage")];
+
+/// Top-level fields that fetch the latest value when they are not given an `id`.
+const LATEST_BY_DEFAULT: &[&str] = &["checkpoint", "epoch"];
+
+/// Classifies the fields a query resolves as immutable or mutable, and sets the `max-age` of the
+/// response's cache control to the TTL configured for the most volatile class among them. The
+/// response also reports its max age as `extensions.cacheControl.maxAge`.
+///
+/// If the schema has a [ResponseCache], responses are also served from and stored in it. Cache
+/// hits are served in place of executing the request, after it has been parsed and validated, so
+/// the other extensions (e.g. rate limits and quotas) still apply to them. Mutations,
+/// subscriptions and responses that include [UNCACHEABLE_FIELDS] are never cached.
+#[derive(Debug, Default)]
+pub(crate) struct CacheTtl {
+    /// Whether any part of the request could produce a different result if it was run again.
+    mutable: AtomicBool,
+    /// Whether the response to the request must not be cached at all.
+    uncacheable: AtomicBool,
+    /// Whether the response was served from the cache.
+    hit: AtomicBool,
+    /// The query and variables of the request, once it has been parsed.
+    request: Mutex<Option<(String, Variables)>>,
+}
+
+impl ExtensionFactory for CacheTtl {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(CacheTtl::default())
+    }
+}
+
+#[async_trait]
+impl Extension for CacheTtl {
+    async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> Response {
+        let resp = next.run(ctx).await;
+
+        // Cached responses carry the time they have left in the cache as their max age.
+        if self.hit.load(Ordering::Relaxed) {
+            return resp;
+        }
+
+        let cfg = ctx
+            .data::<ServiceConfig>()
+            .expect("No service config provided in schema data");
+
+        let ttl = self.ttl(cfg);
+        if !resp.is_ok() || ttl == 0 {
+            return resp;
+        }
+
+        let mut cache_control = resp.cache_control;
+        cache_control.max_age = ttl as _;
+        resp.cache_control(cache_control)
+            .extension("cacheControl", value!({ "maxAge": ttl }))
+    }
+
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let doc = next.run(ctx, query, variables).await?;
+
+        for (_name, oper) in doc.operations.iter() {
+            if oper.node.ty != OperationType::Query {
+                self.uncacheable.store(true, Ordering::Relaxed);
+            } else if fetches_latest(&doc.fragments, variables, &oper.node.selection_set) {
+                self.mutable.store(true, Ordering::Relaxed);
+            }
+        }
+
+        *self.request.lock().unwrap() = Some((query.to_string(), variables.clone()));
+        Ok(doc)
+    }
+
+    async fn execute(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        operation_name: Option<&str>,
+        next: NextExecute<'_>,
+    ) -> Response {
+        // Usage is specific to each request, so requests that ask for it bypass the cache.
+        let cache = ctx
+            .data_opt::<Arc<ResponseCache>>()
+            .filter(|_| ctx.data_opt::<ShowUsage>().is_none());
+        let key = self
+            .request
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|(query, variables)| ResponseCache::key(operation_name, query, variables));
+
+        let (Some(cache), Some(key)) = (cache, key) else {
+            return next.run(ctx, operation_name).await;
+        };
+
+        if !self.uncacheable.load(Ordering::Relaxed) {
+            if let Some(resp) = cache.get(&key) {
+                self.hit.store(true, Ordering::Relaxed);
+                return resp;
+            }
+        }
+
+        let resp = next.run(ctx, operation_name).await;
+
+        let cfg = ctx
+            .data::<ServiceConfig>()
+            .expect("No service config provided in schema data");
+        cache.insert(key, &resp, self.ttl(cfg));
+        resp
+    }
+
+    async fn resolve(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        info: ResolveInfo<'_>,
+        next: NextResolve<'_>,
+    ) -> ServerResult<Option<Value>> {
+        let top_level = info.parent_type == "Query";
+        if !info.is_for_introspection && !is_immutable(info.parent_type, info.name) {
+            self.mutable.store(true, Ordering::Relaxed);
+        }
+        if UNCACHEABLE_FIELDS.contains(&(info.parent_type, info.name)) {
+            self.uncacheable.store(true, Ordering::Relaxed);
+        }
+
+        let value = next.run(ctx, info).await?;
+
+        // A lookup that found nothing may succeed later, once the indexer has caught up.
+        if top_level && matches!(value, None | Some(Value::Null)) {
+            self.mutable.store(true, Ordering::Relaxed);
+        }
+
+        Ok(value)
+    }
+}
+
+impl CacheTtl {
+    /// How long the response to the request can be cached for, in seconds, once it has been
+    /// executed. 0 if it can't be cached.
+    fn ttl(&self, cfg: &ServiceConfig) -> u64 {
+        if self.uncacheable.load(Ordering::Relaxed) {
+            0
+        } else if self.mutable.load(Ordering::Relaxed) {
+            cfg.cache.mutable_ttl_secs
+        } else {
+            cfg.cache.immutable_ttl_secs
+        }
+    }
+}
+
+/// Whether `field` on `parent_type` always resolves to the same value, given the same parent.
+fn is_immutable(parent_type: &str, field: &str) -> bool {
+    // Connections and their edges are only as mutable as the field that produced them.
+    if parent_type.ends_with("Connection") || parent_type.ends_with("Edge") {
+        return true;
+    }
+
+    if parent_type == "PageInfo" || field.starts_with("__") {
+        return true;
+    }
+
+    if MUTABLE_FIELDS.contains(&(parent_type, field)) {
+        return false;
+    }
+
+    IMMUTABLE_TYPES.contains(&parent_type) || IMMUTABLE_FIELDS.contains(&(parent_type, field))
+}
+
+/// Whether any of the top-level selections in `sel_set` fetches the latest value of something,
+/// by omitting the `id` argument of a field in [LATEST_BY_DEFAULT] (or setting it to `null`).
+fn fetches_latest(
+    fragment_defs: &HashMap<Name, Positioned<FragmentDefinition>>,
+    variables: &Variables,
+    sel_set: &Positioned<SelectionSet>,
+) -> bool {
+    sel_set.node.items.iter().any(|sel| match &sel.node {
+        Selection::Field(f) => {
+            if !LATEST_BY_DEFAULT.contains(&f.node.name.node.as_str()) {
+                return false;
+            }
+
+            let Some(id) = f.node.get_argument("id") else {
+                return true;
+            };
+
+            let id = id
+                .node
+                .clone()
+                .into_const_with(|name| variables.get(&name).cloned().ok_or(()));
+
+            matches!(id, Ok(Value::Null) | Err(_))
+        }
+        Selection::FragmentSpread(fs) => fragment_defs
+            .get(&fs.node.fragment_name.node)
+            .map_or(true, |def| {
+                fetches_latest(fragment_defs, variables, &def.node.selection_set)
+            }),
+        Selection::InlineFragment(fs) => {
+            fetches_latest(fragment_defs, variables, &fs.node.selection_set)
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ResponseCacheConfig;
+    use async_graphql::parser::parse_query;
+
+    fn latest(query: &str, variables: Variables) -> bool {
+        let doc = parse_query(query).unwrap();
+        doc.operations
+            .iter()
+            .any(|(_, oper)| fetches_latest(&doc.fragments, &variables, &oper.node.selection_set))
+    }
+
+    #[test]
+    fn test_immutable_fields() {
+        assert!(is_immutable("Checkpoint", "digest"));
+        assert!(is_immutable("TransactionBlockConnection", "nodes"));
+        assert!(is_immutable("Epoch", "epochId"));
+        assert!(is_immutable("Query", "transactionBlock"));
+
+        assert!(!is_immutable("Epoch", "endTimestamp"));
+        assert!(!is_immutable("Address", "balance"));
+        assert!(!is_immutable("Query", "transactionBlockConnection"));
+        assert!(!is_immutable("TransactionBlockEffects", "checkpoint"));
+    }
+
+    #[test]
+    fn test_ttl() {
+        let cfg = ServiceConfig {
+            cache: ResponseCacheConfig {
+                immutable_ttl_secs: 600,
+                mutable_ttl_secs: 2,
+                max_entries: 10,
+            },
+            ..Default::default()
+        };
+
+        let ext = CacheTtl::default();
+        assert_eq!(ext.ttl(&cfg), 600);
+        ext.mutable.store(true, Ordering::Relaxed);
+        assert_eq!(ext.ttl(&cfg), 2);
+        ext.uncacheable.store(true, Ordering::Relaxed);
+        assert_eq!(ext.ttl(&cfg), 0);
+    }
+
+    #[test]
+    fn test_fetches_latest() {
+        assert!(latest("{ checkpoint { digest } }", Variables::default()));
+        assert!(latest(
+            "{ epoch(id: null) { epochId } }",
+            Variables::default()
+        ));
+        assert!(!latest(
+            "{ checkpoint(id: { sequenceNumber: 3 }) { digest } }",
+            Variables::default(),
+        ));
+        assert!(!latest(
+            r#"{ transactionBlock(digest: "abc") { digest } }"#,
+            Variables::default(),
+        ));
+    }
+
+    #[test]
+    fn test_fetches_latest_through_variables_and_fragments() {
+        let query = r#"
+            query ($id: Int) { ...Epoch }
+            fragment Epoch on Query { epoch(id: $id) { epochId } }
+        "#;
+
+        assert!(latest(query, Variables::default()));
+        assert!(!latest(
+            query,
+            Variables::from_json(serde_json::json!({ "id": 4 })),
+        ));
+    }
+}
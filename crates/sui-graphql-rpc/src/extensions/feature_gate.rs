@@ -5,7 +5,7 @@ use std::sync::Arc;
 
 use async_graphql::{
     extensions::{Extension, ExtensionContext, ExtensionFactory, NextResolve, ResolveInfo},
-    ServerError, ServerResult, Value,
+    ServerResult, Value,
 };
 use async_trait::async_trait;
 
@@ -57,7 +57,8 @@ impl Extension for FeatureGate {
                 return if *is_for_introspection {
                     Ok(None)
                 } else {
-                    Err(ServerError::new(
+                    Err(graphql_error(
+                        code::BAD_REQUEST,
                         format!(
                             "Cannot query field \"{name}\" on type \"{parent_type}\". \
                              Feature {} is disabled.",
@@ -66,7 +67,6 @@ impl Extension for FeatureGate {
                         // TODO: Fork `async-graphl` to add field position information to
                         // `ResolveInfo`, so the error can take advantage of it.  Similarly for
                         // utilising the `path_node` to set the error path.
-                        None,
                     ))
                 };
             }
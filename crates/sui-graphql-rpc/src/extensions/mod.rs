@@ -1,7 +1,9 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub(crate) mod cache_ttl;
 pub(crate) mod feature_gate;
 pub(crate) mod logger;
 pub mod query_limits_checker;
+pub(crate) mod rate_limiter;
 pub(crate) mod timeout;
@@ -3,12 +3,15 @@
 
 use crate::config::Limits;
 use crate::config::ServiceConfig;
-use crate::error::code::INTERNAL_SERVER_ERROR;
-use crate::error::graphql_error_at_pos;
+use crate::context_data::DEFAULT_PAGE_SIZE;
+use crate::error::code::{BAD_USER_INPUT, INTERNAL_SERVER_ERROR};
+use crate::error::{graphql_error, graphql_error_at_pos};
 use crate::metrics::RequestMetrics;
 use async_graphql::extensions::NextParseQuery;
 use async_graphql::extensions::NextRequest;
+use async_graphql::extensions::{Extension, ExtensionContext, ExtensionFactory};
 use async_graphql::parser::types::ExecutableDocument;
+use async_graphql::parser::types::Field;
 use async_graphql::parser::types::FragmentDefinition;
 use async_graphql::parser::types::Selection;
 use async_graphql::parser::types::SelectionSet;
@@ -18,11 +21,8 @@ use async_graphql::Pos;
 use async_graphql::Positioned;
 use async_graphql::Response;
 use async_graphql::ServerResult;
+use async_graphql::Value;
 use async_graphql::Variables;
-use async_graphql::{
-    extensions::{Extension, ExtensionContext, ExtensionFactory},
-    ServerError,
-};
 use axum::headers;
 use axum::http::HeaderName;
 use axum::http::HeaderValue;
@@ -42,6 +42,7 @@ struct ValidationRes {
     depth: u32,
     num_variables: u32,
     num_fragments: u32,
+    cost: u64,
 }
 
 #[derive(Debug, Default)]
@@ -103,6 +104,7 @@ impl Extension for QueryLimitsChecker {
                     "depth": validation_result.depth,
                     "variables": validation_result.num_variables,
                     "fragments": validation_result.num_fragments,
+                    "cost": validation_result.cost,
                 }),
             )
         } else {
@@ -128,12 +130,12 @@ impl Extension for QueryLimitsChecker {
             .expect("No service config provided in schema data");
 
         if variables.len() > cfg.limits.max_query_variables as usize {
-            return Err(ServerError::new(
+            return Err(graphql_error(
+                BAD_USER_INPUT,
                 format!(
                     "Query has too many variables. The maximum allowed is {}",
                     cfg.limits.max_query_variables
                 ),
-                None,
             ));
         }
 
@@ -141,12 +143,12 @@ impl Extension for QueryLimitsChecker {
         let doc = next.run(ctx, query, variables).await?;
 
         if doc.fragments.len() > cfg.limits.max_query_fragments as usize {
-            return Err(ServerError::new(
+            return Err(graphql_error(
+                BAD_USER_INPUT,
                 format!(
                     "Query has too many fragments definitions. The maximum allowed is {}",
                     cfg.limits.max_query_fragments
                 ),
-                None,
             ));
         }
 
@@ -169,17 +171,34 @@ impl Extension for QueryLimitsChecker {
             max_depth_seen = max_depth_seen.max(running_costs.depth);
         }
 
+        // The depth limit has been checked by this point, so the cost analysis (which follows
+        // fragment spreads recursively) is guaranteed to terminate, even for cyclic fragments.
+        let query_cost = query_cost(&cfg.limits, &doc, variables)?;
+
+        if query_cost > cfg.limits.max_query_cost {
+            return Err(graphql_error(
+                BAD_USER_INPUT,
+                format!(
+                    "Query is too expensive. Its estimated cost is {query_cost}, and the maximum \
+                     allowed is {}",
+                    cfg.limits.max_query_cost
+                ),
+            ));
+        }
+
         if ctx.data_opt::<ShowUsage>().is_some() {
             *self.validation_result.lock().await = Some(ValidationRes {
                 num_nodes: running_costs.num_nodes,
                 depth: running_costs.depth,
                 num_variables: variables.len() as u32,
                 num_fragments: doc.fragments.len() as u32,
+                cost: query_cost,
             });
         }
         if let Some(metrics) = ctx.data_opt::<Arc<RequestMetrics>>() {
             metrics.num_nodes.observe(running_costs.num_nodes as f64);
             metrics.query_depth.observe(running_costs.depth as f64);
+            metrics.query_cost.observe(query_cost as f64);
             metrics.query_payload_size.observe(query.len() as f64);
         }
         Ok(doc)
@@ -268,9 +287,101 @@ impl QueryLimitsChecker {
     }
 }
 
+/// Estimated cost of resolving all the operations in `doc` (see [selection_set_cost]). Fails if
+/// any of them requests a page that is too large.
+pub(crate) fn query_cost(
+    limits: &Limits,
+    doc: &ExecutableDocument,
+    variables: &Variables,
+) -> ServerResult<u64> {
+    let mut cost = 0u64;
+    for (_name, oper) in doc.operations.iter() {
+        cost = cost.saturating_add(selection_set_cost(
+            limits,
+            &doc.fragments,
+            variables,
+            &oper.node.selection_set,
+        )?);
+    }
+    Ok(cost)
+}
+
+/// Estimated cost of resolving the selections in `sel_set`. Every field costs one unit, plus the
+/// cost of its sub-selections. The sub-selections of a connection are resolved once per element of
+/// the page, so they are counted `first` (or `last`) times, which must not exceed the maximum
+/// page size.
+fn selection_set_cost(
+    limits: &Limits,
+    fragment_defs: &HashMap<Name, Positioned<FragmentDefinition>>,
+    variables: &Variables,
+    sel_set: &Positioned<SelectionSet>,
+) -> ServerResult<u64> {
+    let mut cost = 0u64;
+    for sel in sel_set.node.items.iter() {
+        let sel_cost = match &sel.node {
+            Selection::Field(f) => {
+                let page_size = page_size(&f.node, variables);
+                if page_size > limits.max_page_size {
+                    return Err(graphql_error_at_pos(
+                        BAD_USER_INPUT,
+                        format!(
+                            "Page size of '{}' is too large. The maximum allowed is {}",
+                            f.node.name.node, limits.max_page_size
+                        ),
+                        Some(sel.pos),
+                    ));
+                }
+
+                let children =
+                    selection_set_cost(limits, fragment_defs, variables, &f.node.selection_set)?;
+                children.saturating_mul(page_size).saturating_add(1)
+            }
+            Selection::FragmentSpread(fs) => match fragment_defs.get(&fs.node.fragment_name.node) {
+                Some(def) => {
+                    selection_set_cost(limits, fragment_defs, variables, &def.node.selection_set)?
+                }
+                None => 0,
+            },
+            Selection::InlineFragment(fs) => {
+                selection_set_cost(limits, fragment_defs, variables, &fs.node.selection_set)?
+            }
+        };
+        cost = cost.saturating_add(sel_cost);
+    }
+    Ok(cost)
+}
+
+/// The number of times the sub-selections of `field` are expected to be resolved: the page size
+/// requested through its `first` or `last` argument, [DEFAULT_PAGE_SIZE] for connections that
+/// don't specify one, and one for all other fields.
+fn page_size(field: &Field, variables: &Variables) -> u64 {
+    let limit = field
+        .get_argument("first")
+        .or_else(|| field.get_argument("last"));
+
+    let Some(limit) = limit else {
+        return if field.name.node.ends_with("Connection") {
+            DEFAULT_PAGE_SIZE
+        } else {
+            1
+        };
+    };
+
+    let limit = limit
+        .node
+        .clone()
+        .into_const_with(|name| variables.get(&name).cloned().ok_or(()));
+
+    match limit {
+        Ok(Value::Number(n)) => n.as_u64().unwrap_or(DEFAULT_PAGE_SIZE),
+        _ => DEFAULT_PAGE_SIZE,
+    }
+}
+
 fn check_limits(limits: &Limits, nodes: u32, depth: u32, pos: Option<Pos>) -> ServerResult<()> {
     if nodes > limits.max_query_nodes {
-        return Err(ServerError::new(
+        return Err(graphql_error_at_pos(
+            BAD_USER_INPUT,
             format!(
                 "Query has too many nodes. The maximum allowed is {}",
                 limits.max_query_nodes
@@ -280,7 +391,8 @@ fn check_limits(limits: &Limits, nodes: u32, depth: u32, pos: Option<Pos>) -> Se
     }
 
     if depth > limits.max_query_depth {
-        return Err(ServerError::new(
+        return Err(graphql_error_at_pos(
+            BAD_USER_INPUT,
             format!(
                 "Query has too many levels of nesting. The maximum allowed is {}",
                 limits.max_query_depth
@@ -291,3 +403,110 @@ fn check_limits(limits: &Limits, nodes: u32, depth: u32, pos: Option<Pos>) -> Se
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::parser::parse_query;
+
+    fn try_query_cost(query: &str, variables: Variables) -> ServerResult<u64> {
+        let doc = parse_query(query).unwrap();
+        super::query_cost(&Limits::default(), &doc, &variables)
+    }
+
+    fn query_cost(query: &str, variables: Variables) -> u64 {
+        try_query_cost(query, variables).unwrap()
+    }
+
+    #[test]
+    fn test_field_cost() {
+        assert_eq!(query_cost("{ chainIdentifier }", Variables::default()), 1);
+        assert_eq!(
+            query_cost(
+                "{ chainIdentifier protocolConfig { configs { value key } } }",
+                Variables::default(),
+            ),
+            5,
+        );
+    }
+
+    #[test]
+    fn test_connection_cost() {
+        // Explicit page size.
+        assert_eq!(
+            query_cost(
+                "{ checkpointConnection(first: 20) { nodes { digest } } }",
+                Variables::default(),
+            ),
+            1 + 20 * 2,
+        );
+
+        // Default page size.
+        assert_eq!(
+            query_cost(
+                "{ checkpointConnection { nodes { digest } } }",
+                Variables::default(),
+            ),
+            1 + DEFAULT_PAGE_SIZE * 2,
+        );
+
+        // Page size from a variable.
+        assert_eq!(
+            query_cost(
+                "query ($n: Int) { checkpointConnection(last: $n) { nodes { digest } } }",
+                Variables::from_json(serde_json::json!({ "n": 3 })),
+            ),
+            1 + 3 * 2,
+        );
+    }
+
+    #[test]
+    fn test_nested_connection_cost() {
+        let query = r#"
+            {
+                checkpointConnection(first: 5) {
+                    nodes {
+                        transactionBlockConnection(first: 10) { nodes { digest } }
+                    }
+                }
+            }
+        "#;
+
+        assert_eq!(
+            query_cost(query, Variables::default()),
+            1 + 5 * (1 + (1 + 10 * 2)),
+        );
+    }
+
+    #[test]
+    fn test_fragment_cost() {
+        let query = r#"
+            query {
+                checkpointConnection(first: 4) { nodes { ...CheckpointFields } }
+            }
+
+            fragment CheckpointFields on Checkpoint {
+                digest
+                ... on Checkpoint { sequenceNumber }
+            }
+        "#;
+
+        assert_eq!(query_cost(query, Variables::default()), 1 + 4 * (1 + 2));
+    }
+
+    #[test]
+    fn test_page_size_limit() {
+        let max = Limits::default().max_page_size;
+        let err = try_query_cost(
+            "query ($n: Int) { checkpointConnection(first: $n) { nodes { digest } } }",
+            Variables::from_json(serde_json::json!({ "n": max + 1 })),
+        )
+        .unwrap_err();
+        assert_eq!(
+            err.message,
+            format!(
+                "Page size of 'checkpointConnection' is too large. The maximum allowed is {max}"
+            ),
+        );
+    }
+}
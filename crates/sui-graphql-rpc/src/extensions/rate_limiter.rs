@@ -0,0 +1,423 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use async_graphql::{
+    extensions::{Extension, ExtensionContext, ExtensionFactory, NextParseQuery, NextRequest},
+    parser::types::ExecutableDocument,
+    Response, ServerResult, SimpleObject, Variables,
+};
+use axum::{
+    headers,
+    http::{HeaderName, HeaderValue},
+};
+
+use crate::{
+    config::ServiceConfig,
+    error::{code, graphql_error},
+    extensions::query_limits_checker::query_cost,
+    types::big_int::BigInt,
+};
+
+pub static API_KEY_HEADER: HeaderName = HeaderName::from_static("x-sui-rpc-api-key");
+
+const SECS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Maximum number of clients whose usage is tracked at once. Clients that appear once this many
+/// are being tracked share a single budget, until the tracker is cleared at the end of the day.
+const MAX_TRACKED_CLIENTS: usize = 100_000;
+
+/// The API key a request was made with, from the [API_KEY_HEADER] header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ApiKey(pub String);
+
+/// Attributes requests to the API key they were made with (or to the client's IP address, if they
+/// were made without one, or with a key that is not in the service's configuration), and rejects
+/// them if that client has made too many requests in the current second, or if their estimated
+/// cost would take the client over its daily quota.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct RateLimiter;
+
+/// Usage of the service by each client, shared between requests.
+#[derive(Debug, Default)]
+pub(crate) struct ApiKeyUsageTracker {
+    inner: Mutex<TrackerInner>,
+}
+
+#[derive(Debug, Default)]
+struct TrackerInner {
+    /// The day (since the UNIX epoch) that `usage` was accumulated in. Usage from previous days is
+    /// dropped as soon as the tracker sees a request from a later day.
+    day: u64,
+    usage: HashMap<Client, Usage>,
+}
+
+/// Who a request's usage is attributed to.
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Client {
+    ApiKey(String),
+    Ip(IpAddr),
+    Anonymous,
+    /// Clients that appeared after [MAX_TRACKED_CLIENTS] were already being tracked.
+    Overflow,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Usage {
+    /// The second (since the UNIX epoch) that `requests_this_second` were made in.
+    second: u64,
+    requests_this_second: u32,
+    /// The day (since the UNIX epoch) that `requests_today` and `cost_today` were accumulated in.
+    day: u64,
+    requests_today: u64,
+    cost_today: u64,
+}
+
+/// The usage of the service attributed to one client, on the current (UTC) day.
+#[derive(SimpleObject, Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ApiKeyUsage {
+    /// The API key, or the IP address of the client, for requests made without a configured key.
+    /// Both are redacted: only the first few characters of a key, and the network part of an
+    /// address, are shown.
+    pub api_key: String,
+    pub requests_today: BigInt,
+    /// The sum of the estimated costs of the queries made today (see `ServiceConfig.maxQueryCost`).
+    pub cost_today: BigInt,
+}
+
+impl headers::Header for ApiKey {
+    fn name() -> &'static HeaderName {
+        &API_KEY_HEADER
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let value = values.next().ok_or_else(headers::Error::invalid)?;
+        let key = value.to_str().map_err(|_| headers::Error::invalid())?;
+        Ok(ApiKey(key.to_string()))
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, _: &mut E) {
+        unimplemented!()
+    }
+}
+
+impl ExtensionFactory for RateLimiter {
+    fn create(&self) -> Arc<dyn Extension> {
+        Arc::new(RateLimiter)
+    }
+}
+
+#[async_trait::async_trait]
+impl Extension for RateLimiter {
+    async fn request(&self, ctx: &ExtensionContext<'_>, next: NextRequest<'_>) -> Response {
+        let cfg = ctx
+            .data::<ServiceConfig>()
+            .expect("No service config provided in schema data");
+        let tracker = ctx
+            .data::<Arc<ApiKeyUsageTracker>>()
+            .expect("No API key usage tracker provided in schema data");
+
+        let client = client(ctx, cfg);
+        if let Err(message) =
+            tracker.record_request(client, now_secs(), cfg.rate_limits.requests_per_second)
+        {
+            return Response::from_errors(vec![graphql_error(code::RATE_LIMITED, message)]);
+        }
+
+        next.run(ctx).await
+    }
+
+    async fn parse_query(
+        &self,
+        ctx: &ExtensionContext<'_>,
+        query: &str,
+        variables: &Variables,
+        next: NextParseQuery<'_>,
+    ) -> ServerResult<ExecutableDocument> {
+        let doc = next.run(ctx, query, variables).await?;
+
+        let cfg = ctx
+            .data::<ServiceConfig>()
+            .expect("No service config provided in schema data");
+        let tracker = ctx
+            .data::<Arc<ApiKeyUsageTracker>>()
+            .expect("No API key usage tracker provided in schema data");
+
+        let cost = query_cost(&cfg.limits, &doc, variables)?;
+        tracker
+            .record_cost(
+                client(ctx, cfg),
+                now_secs(),
+                cost,
+                cfg.rate_limits.daily_cost_quota,
+            )
+            .map_err(|message| graphql_error(code::RATE_LIMITED, message))?;
+
+        Ok(doc)
+    }
+}
+
+impl ApiKeyUsageTracker {
+    /// Counts a request made by `client` at time `now` (in seconds since the UNIX epoch), unless
+    /// the client has already made `requests_per_second` requests in the same second. A limit of 0
+    /// means requests are not limited.
+    fn record_request(
+        &self,
+        client: Client,
+        now: u64,
+        requests_per_second: u32,
+    ) -> Result<(), String> {
+        let mut inner = self.inner.lock().unwrap();
+        let usage = inner.usage_at(client, now);
+
+        if requests_per_second > 0 && usage.requests_this_second >= requests_per_second {
+            return Err(format!(
+                "Too many requests. The maximum allowed is {requests_per_second} per second"
+            ));
+        }
+
+        usage.requests_this_second += 1;
+        usage.requests_today += 1;
+        Ok(())
+    }
+
+    /// Adds `cost` to the cost of the queries made by `client` on the day of `now` (in seconds
+    /// since the UNIX epoch), unless that would take it over `daily_cost_quota`. A quota of 0 means
+    /// the cost is not limited.
+    fn record_cost(
+        &self,
+        client: Client,
+        now: u64,
+        cost: u64,
+        daily_cost_quota: u64,
+    ) -> Result<(), String> {
+        let mut inner = self.inner.lock().unwrap();
+        let usage = inner.usage_at(client, now);
+
+        let cost_today = usage.cost_today.saturating_add(cost);
+        if daily_cost_quota > 0 && cost_today > daily_cost_quota {
+            return Err(format!(
+                "Daily query cost quota exceeded. The quota is {daily_cost_quota}, {} has been used \
+                 today, and this query costs {cost}",
+                usage.cost_today,
+            ));
+        }
+
+        usage.cost_today = cost_today;
+        Ok(())
+    }
+
+    /// The usage of every client that has made requests today, ordered by client, with their keys
+    /// and addresses redacted.
+    pub(crate) fn usage_today(&self) -> Vec<ApiKeyUsage> {
+        self.usage_on(now_secs() / SECS_PER_DAY)
+    }
+
+    fn usage_on(&self, day: u64) -> Vec<ApiKeyUsage> {
+        let inner = self.inner.lock().unwrap();
+        if inner.day != day {
+            return vec![];
+        }
+
+        let mut usage: Vec<_> = inner.usage.iter().collect();
+        usage.sort_by(|(a, _), (b, _)| a.cmp(b));
+        usage
+            .into_iter()
+            .map(|(client, usage)| ApiKeyUsage {
+                api_key: client.redacted(),
+                requests_today: BigInt::from(usage.requests_today),
+                cost_today: BigInt::from(usage.cost_today),
+            })
+            .collect()
+    }
+}
+
+impl TrackerInner {
+    /// The usage of `client` at time `now`, dropping the usage of every client if `now` is on a
+    /// later day than the tracker has seen so far. Once [MAX_TRACKED_CLIENTS] are being tracked,
+    /// new clients share the usage of [Client::Overflow].
+    fn usage_at(&mut self, client: Client, now: u64) -> &mut Usage {
+        let day = now / SECS_PER_DAY;
+        if day > self.day {
+            self.day = day;
+            self.usage.clear();
+        }
+
+        let client = if self.usage.len() >= MAX_TRACKED_CLIENTS && !self.usage.contains_key(&client)
+        {
+            Client::Overflow
+        } else {
+            client
+        };
+
+        self.usage.entry(client).or_default().at(now)
+    }
+}
+
+impl Client {
+    /// A representation of the client that identifies it to an operator without revealing its key
+    /// or full address.
+    fn redacted(&self) -> String {
+        match self {
+            Client::ApiKey(key) => {
+                let prefix: String = key.chars().take(4).collect();
+                format!("{prefix}...")
+            }
+            Client::Ip(IpAddr::V4(ip)) => {
+                let [a, b, _, _] = ip.octets();
+                format!("{a}.{b}.x.x")
+            }
+            Client::Ip(IpAddr::V6(ip)) => {
+                let [a, b, c, ..] = ip.segments();
+                format!("{a:x}:{b:x}:{c:x}::/48")
+            }
+            Client::Anonymous => "anonymous".to_string(),
+            Client::Overflow => "overflow".to_string(),
+        }
+    }
+}
+
+impl Usage {
+    /// Resets the counters that belong to a previous second or day than `now`.
+    fn at(&mut self, now: u64) -> &mut Self {
+        if self.second != now {
+            self.second = now;
+            self.requests_this_second = 0;
+        }
+
+        let day = now / SECS_PER_DAY;
+        if self.day != day {
+            self.day = day;
+            self.requests_today = 0;
+            self.cost_today = 0;
+        }
+
+        self
+    }
+}
+
+/// The client that usage of the request is attributed to: its API key if it has one that is
+/// configured for the service, otherwise the IP address of the client. Keys that are not
+/// configured are ignored, so that clients can't escape their limits by making up new keys.
+fn client(ctx: &ExtensionContext<'_>, cfg: &ServiceConfig) -> Client {
+    match ctx.data_opt::<ApiKey>() {
+        Some(ApiKey(key)) if cfg.rate_limits.is_known_key(key) => Client::ApiKey(key.clone()),
+        _ => match ctx.data_opt::<SocketAddr>() {
+            Some(addr) => Client::Ip(addr.ip()),
+            None => Client::Anonymous,
+        },
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(k: &str) -> Client {
+        Client::ApiKey(k.to_string())
+    }
+
+    #[test]
+    fn test_requests_per_second() {
+        let tracker = ApiKeyUsageTracker::default();
+        assert!(tracker.record_request(key("a"), 100, 2).is_ok());
+        assert!(tracker.record_request(key("a"), 100, 2).is_ok());
+        assert!(tracker.record_request(key("a"), 100, 2).is_err());
+
+        // Other keys, and later seconds, have their own budget.
+        assert!(tracker.record_request(key("b"), 100, 2).is_ok());
+        assert!(tracker.record_request(key("a"), 101, 2).is_ok());
+    }
+
+    #[test]
+    fn test_unlimited_requests() {
+        let tracker = ApiKeyUsageTracker::default();
+        for _ in 0..100 {
+            assert!(tracker.record_request(key("a"), 100, 0).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_daily_cost_quota() {
+        let tracker = ApiKeyUsageTracker::default();
+        assert!(tracker.record_cost(key("a"), 100, 60, 100).is_ok());
+        assert!(tracker.record_cost(key("a"), 200, 60, 100).is_err());
+        assert!(tracker.record_cost(key("a"), 200, 40, 100).is_ok());
+
+        // The quota is reset at the start of the next day.
+        assert!(tracker
+            .record_cost(key("a"), SECS_PER_DAY + 100, 60, 100)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_usage_evicted_at_end_of_day() {
+        let tracker = ApiKeyUsageTracker::default();
+        tracker.record_cost(key("a"), 100, 10, 0).unwrap();
+        tracker.record_cost(key("b"), 200, 10, 0).unwrap();
+        assert_eq!(tracker.usage_on(0).len(), 2);
+
+        // The first request of the next day drops every client's usage from the day before.
+        tracker.record_cost(key("b"), SECS_PER_DAY, 5, 0).unwrap();
+        assert!(tracker.usage_on(0).is_empty());
+        assert_eq!(
+            tracker.usage_on(1),
+            vec![ApiKeyUsage {
+                api_key: "b...".to_string(),
+                requests_today: BigInt::from(0),
+                cost_today: BigInt::from(5),
+            }],
+        );
+        assert_eq!(tracker.inner.lock().unwrap().usage.len(), 1);
+    }
+
+    #[test]
+    fn test_tracked_clients_capped() {
+        let tracker = ApiKeyUsageTracker::default();
+        for i in 0..MAX_TRACKED_CLIENTS as u32 {
+            let ip = IpAddr::from(i.to_be_bytes());
+            tracker.record_request(Client::Ip(ip), 100, 1).unwrap();
+        }
+
+        // Clients beyond the cap share a budget, while clients that are already tracked keep
+        // their own.
+        let late = |i: u32| Client::Ip(IpAddr::from((u32::MAX - i).to_be_bytes()));
+        assert!(tracker.record_request(late(0), 100, 1).is_ok());
+        assert!(tracker.record_request(late(1), 100, 1).is_err());
+        assert!(tracker
+            .record_request(Client::Ip(IpAddr::from([0, 0, 0, 0])), 101, 1)
+            .is_ok());
+        assert_eq!(
+            tracker.inner.lock().unwrap().usage.len(),
+            MAX_TRACKED_CLIENTS + 1
+        );
+    }
+
+    #[test]
+    fn test_redacted_clients() {
+        assert_eq!(key("secret-key").redacted(), "secr...");
+        assert_eq!(
+            Client::Ip(IpAddr::from([10, 20, 30, 40])).redacted(),
+            "10.20.x.x"
+        );
+        assert_eq!(
+            Client::Ip("2001:db8:1:2::1".parse().unwrap()).redacted(),
+            "2001:db8:1::/48"
+        );
+    }
+}
@@ -3,13 +3,14 @@
 
 use async_graphql::{
     extensions::{Extension, ExtensionContext, ExtensionFactory, NextRequest},
-    Response, ServerError,
+    Response,
 };
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::timeout;
 
 use crate::config::ServiceConfig;
+use crate::error::{code, graphql_error};
 
 #[derive(Clone, Debug, Default)]
 pub(crate) struct Timeout;
@@ -31,12 +32,12 @@ impl Extension for Timeout {
         timeout(request_timeout, next.run(ctx))
             .await
             .unwrap_or_else(|_| {
-                Response::from_errors(vec![ServerError::new(
+                Response::from_errors(vec![graphql_error(
+                    code::REQUEST_TIMEOUT,
                     format!(
                         "Request timed out. Limit: {}s",
                         request_timeout.as_secs_f32()
                     ),
-                    None,
                 )])
             })
     }
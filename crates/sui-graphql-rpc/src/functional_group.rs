@@ -68,13 +68,19 @@ fn functional_groups() -> &'static BTreeMap<(&'static str, &'static str), Functi
             (("Address", "balance"), G::Coins),
             (("Address", "balanceConnection"), G::Coins),
             (("Address", "coinConnection"), G::Coins),
+            (("Address", "coins"), G::Coins),
             (("Address", "defaultNameServiceName"), G::NameService),
+            (("Address", "defaultSuinsName"), G::NameService),
+            (("Address", "dynamicField"), G::DynamicFields),
+            (("Address", "dynamicFieldConnection"), G::DynamicFields),
             // (("Address", "nameServiceConnection"), G::NameService),
             (("Checkpoint", "addressMetrics"), G::Analytics),
             (("Checkpoint", "networkTotalTransactions"), G::Analytics),
             (("Epoch", "protocolConfigs"), G::SystemState),
             (("Epoch", "referenceGasPrice"), G::SystemState),
             (("Epoch", "validatorSet"), G::SystemState),
+            (("MoveObject", "dynamicField"), G::DynamicFields),
+            (("MoveObject", "dynamicFieldConnection"), G::DynamicFields),
             (("SuiSystemStateSummary", "protocolConfigs"), G::SystemState),
             (
                 ("SuiSystemStateSummary", "referenceGasPrice"),
@@ -94,6 +100,7 @@ fn functional_groups() -> &'static BTreeMap<(&'static str, &'static str), Functi
             (("Object", "balance"), G::Coins),
             (("Object", "balanceConnection"), G::Coins),
             (("Object", "coinConnection"), G::Coins),
+            (("Object", "coins"), G::Coins),
             (("Object", "defaultNameServiceName"), G::NameService),
             (("Object", "dynamicField"), G::DynamicFields),
             (("Object", "dynamicFieldConnection"), G::DynamicFields),
@@ -101,14 +108,20 @@ fn functional_groups() -> &'static BTreeMap<(&'static str, &'static str), Functi
             (("Owner", "balance"), G::Coins),
             (("Owner", "balanceConnection"), G::Coins),
             (("Owner", "coinConnection"), G::Coins),
+            (("Owner", "coins"), G::Coins),
             (("Owner", "defaultNameServiceName"), G::NameService),
+            (("Owner", "defaultSuinsName"), G::NameService),
+            (("Owner", "dynamicField"), G::DynamicFields),
+            (("Owner", "dynamicFieldConnection"), G::DynamicFields),
             // (("Owner", "nameServiceConnection"), G::NameService),
             (("Query", "coinMetadata"), G::Coins),
             (("Query", "moveCallMetrics"), G::Analytics),
             (("Query", "networkMetrics"), G::Analytics),
             (("Query", "protocolConfig"), G::SystemState),
             (("Query", "resolveNameServiceAddress"), G::NameService),
+            (("Query", "resolveSuinsAddress"), G::NameService),
             (("Subscription", "events"), G::Subscriptions),
+            (("Subscription", "objectChanges"), G::Subscriptions),
             (("Subscription", "transactions"), G::Subscriptions),
         ])
     });
@@ -127,9 +140,9 @@ mod tests {
     use std::collections::BTreeSet;
 
     use async_graphql::registry::Registry;
-    use async_graphql::OutputType;
+    use async_graphql::{OutputType, SubscriptionType};
 
-    use crate::types::query::Query;
+    use crate::types::{query::Query, subscription::Subscription};
 
     use super::*;
 
@@ -140,15 +153,14 @@ mod tests {
     fn test_groups_match_schema() {
         let mut registry = Registry::default();
         Query::create_type_info(&mut registry);
+        Subscription::create_type_info(&mut registry);
 
         let unimplemented = BTreeSet::from_iter([
             ("Checkpoint", "addressMetrics"),
             ("Epoch", "protocolConfig"),
-            ("Object", "dynamicField"),
             ("Query", "coinMetadata"),
             ("Query", "moveCallMetrics"),
             ("Query", "networkMetrics"),
-            ("Subscription", "events"),
             ("Subscription", "transactions"),
         ]);
 
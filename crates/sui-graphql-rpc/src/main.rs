@@ -57,8 +57,10 @@ async fn main() {
             config,
             prom_host,
             prom_port,
+            node_rpc_url,
         } => {
-            let connection = ConnectionConfig::new(port, host, db_url, prom_host, prom_port);
+            let connection =
+                ConnectionConfig::new(port, host, db_url, prom_host, prom_port, node_rpc_url);
             let service_config = service_config(config);
             let _guard = telemetry_subscribers::TelemetryConfig::new()
                 .with_env()
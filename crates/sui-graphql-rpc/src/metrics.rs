@@ -7,6 +7,7 @@ use prometheus::{register_histogram_with_registry, Histogram, Registry};
 pub struct RequestMetrics {
     pub(crate) num_nodes: Histogram,
     pub(crate) query_depth: Histogram,
+    pub(crate) query_cost: Histogram,
     pub(crate) query_payload_size: Histogram,
     pub(crate) _db_query_cost: Histogram,
 }
@@ -18,6 +19,9 @@ const NUM_NODES_BUCKETS: &[f64] = &[
 const QUERY_DEPTH_BUCKETS: &[f64] = &[
     1., 2., 4., 8., 12., 16., 24., 32., 48., 64., 96., 128., 256., 512., 1024.,
 ];
+const QUERY_COST_BUCKETS: &[f64] = &[
+    1., 2., 4., 8., 16., 32., 64., 128., 256., 512., 1024., 2048., 4096., 8192., 16384.,
+];
 const QUERY_PAYLOAD_SIZE_BUCKETS: &[f64] = &[
     100., 200., 400., 800., 1200., 1600., 2400., 3200., 4800., 6400., 9600., 12800., 25600.,
     51200., 102400.,
@@ -43,6 +47,13 @@ impl RequestMetrics {
                 registry,
             )
             .unwrap(),
+            query_cost: register_histogram_with_registry!(
+                "query_cost",
+                "Estimated cost of the query",
+                QUERY_COST_BUCKETS.to_vec(),
+                registry,
+            )
+            .unwrap(),
             query_payload_size: register_histogram_with_registry!(
                 "query_payload_size",
                 "Size of the query payload string",
@@ -6,29 +6,46 @@ use crate::{
     context_data::{db_data_provider::PgManager, package_cache::PackageCache},
     error::Error,
     extensions::{
+        cache_ttl::CacheTtl,
         feature_gate::FeatureGate,
         logger::Logger,
         query_limits_checker::{QueryLimitsChecker, ShowUsage},
+        rate_limiter::{ApiKey, ApiKeyUsageTracker, RateLimiter},
         timeout::Timeout,
     },
     metrics::RequestMetrics,
-    server::version::{check_version_middleware, set_version_middleware},
-    types::query::{Query, SuiGraphQLSchema},
+    server::{
+        response_cache::ResponseCache,
+        version::{check_version_middleware, set_version_middleware},
+    },
+    types::{
+        mutation::Mutation,
+        query::{Query, SuiGraphQLSchema},
+        subscription::Subscription,
+    },
+};
+use async_graphql::{
+    dataloader::DataLoader, extensions::ExtensionFactory, Schema, SchemaBuilder, Value,
 };
-use async_graphql::{extensions::ExtensionFactory, Schema, SchemaBuilder};
-use async_graphql::{EmptyMutation, EmptySubscription};
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse};
-use axum::http::HeaderMap;
+use async_graphql_axum::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
+use axum::http::{HeaderMap, StatusCode};
 use axum::{
     extract::{connect_info::IntoMakeServiceWithConnectInfo, ConnectInfo},
     middleware,
 };
 use axum::{headers::Header, Router};
+use chrono::{DateTime as ChronoDateTime, Utc};
 use hyper::server::conn::AddrIncoming as HyperAddrIncoming;
 use hyper::Server as HyperServer;
-use std::{any::Any, net::SocketAddr, sync::Arc, time::Instant};
+use serde::Deserialize;
+use std::{any::Any, net::SocketAddr, num::NonZeroUsize, sync::Arc, time::Instant};
+use sui_sdk::SuiClientBuilder;
 use tokio::sync::OnceCell;
 
+/// The latest checkpoint can be this old (in milliseconds) before the health check reports that
+/// the service has fallen behind the network.
+const DEFAULT_MAX_CHECKPOINT_LAG_MS: u64 = 300_000;
+
 pub struct Server {
     pub server: HyperServer<HyperAddrIncoming, IntoMakeServiceWithConnectInfo<Router, SocketAddr>>,
 }
@@ -56,6 +73,17 @@ impl Server {
         let pg_conn_pool = PgManager::new(reader.clone(), config.service.limits);
         let package_cache = PackageCache::new(reader);
 
+        // Transactions are executed through a fullnode, if one is configured
+        let sui_sdk_client = match &config.connection.node_rpc_url {
+            Some(node_rpc_url) => Some(
+                SuiClientBuilder::default()
+                    .build(node_rpc_url)
+                    .await
+                    .map_err(|e| Error::Internal(format!("Failed to create SuiClient: {}", e)))?,
+            ),
+            None => None,
+        };
+
         let prom_addr: SocketAddr = format!(
             "{}:{}",
             config.connection.prom_url, config.connection.prom_port
@@ -77,11 +105,14 @@ impl Server {
             .max_query_depth(config.service.limits.max_query_depth)
             .max_query_nodes(config.service.limits.max_query_nodes)
             .context_data(config.service.clone())
+            .context_data(DataLoader::new(pg_conn_pool.clone(), tokio::spawn))
             .context_data(pg_conn_pool)
             .context_data(package_cache)
             .context_data(name_service_config)
+            .context_data(sui_sdk_client)
             .ide_title(config.ide.ide_title.clone())
             .context_data(Arc::new(metrics))
+            .context_data(Arc::new(ApiKeyUsageTracker::default()))
             .context_data(config.clone());
 
         if config.internal_features.feature_gate {
@@ -93,9 +124,18 @@ impl Server {
         if config.internal_features.query_limits_checker {
             builder = builder.extension(QueryLimitsChecker::default());
         }
+        if config.internal_features.rate_limiter {
+            builder = builder.extension(RateLimiter);
+        }
         if config.internal_features.query_timeout {
             builder = builder.extension(Timeout);
         }
+        if config.internal_features.response_cache {
+            builder = builder.extension(CacheTtl::default());
+            if let Some(capacity) = NonZeroUsize::new(config.service.cache.max_entries) {
+                builder = builder.context_data(Arc::new(ResponseCache::new(capacity)));
+            }
+        }
 
         builder.build()
     }
@@ -105,7 +145,7 @@ pub(crate) struct ServerBuilder {
     port: u16,
     host: String,
 
-    schema: SchemaBuilder<Query, EmptyMutation, EmptySubscription>,
+    schema: SchemaBuilder<Query, Mutation, Subscription>,
     ide_title: Option<String>,
 }
 
@@ -114,7 +154,7 @@ impl ServerBuilder {
         Self {
             port,
             host,
-            schema: async_graphql::Schema::build(Query, EmptyMutation, EmptySubscription),
+            schema: async_graphql::Schema::build(Query, Mutation, Subscription),
             ide_title: None,
         }
     }
@@ -148,7 +188,7 @@ impl ServerBuilder {
         self
     }
 
-    fn build_schema(self) -> Schema<Query, EmptyMutation, EmptySubscription> {
+    fn build_schema(self) -> Schema<Query, Mutation, Subscription> {
         self.schema.finish()
     }
 
@@ -159,6 +199,7 @@ impl ServerBuilder {
 
         let app = axum::Router::new()
             .route("/", axum::routing::get(graphiql).post(graphql_handler))
+            .route_service("/subscriptions", GraphQLSubscription::new(schema.clone()))
             .route("/schema", axum::routing::get(get_schema))
             .route("/health", axum::routing::get(health_checks))
             .layer(axum::extract::Extension(schema))
@@ -199,6 +240,9 @@ async fn graphql_handler(
     if headers.contains_key(ShowUsage::name()) {
         req.data.insert(ShowUsage)
     }
+    if let Some(api_key) = headers.get(ApiKey::name()).and_then(|v| v.to_str().ok()) {
+        req.data.insert(ApiKey(api_key.to_string()))
+    }
     // Capture the IP address of the client
     // Note: if a load balancer is used it must be configured to forward the client IP address
     req.data.insert(addr);
@@ -206,7 +250,9 @@ async fn graphql_handler(
 }
 
 async fn graphiql(ide_title: axum::Extension<Option<String>>) -> impl axum::response::IntoResponse {
-    let gq = async_graphql::http::GraphiQLSource::build().endpoint("/");
+    let gq = async_graphql::http::GraphiQLSource::build()
+        .endpoint("/")
+        .subscription_endpoint("/subscriptions");
     if let axum::Extension(Some(title)) = ide_title {
         axum::response::Html(gq.title(&title).finish())
     } else {
@@ -214,29 +260,76 @@ async fn graphiql(ide_title: axum::Extension<Option<String>>) -> impl axum::resp
     }
 }
 
+/// Parameters of the health check.
+#[derive(Deserialize)]
+struct HealthParams {
+    /// How far the latest indexed checkpoint can fall behind the current time (in milliseconds)
+    /// before the service is considered unhealthy. Defaults to [DEFAULT_MAX_CHECKPOINT_LAG_MS].
+    max_checkpoint_lag_ms: Option<u64>,
+}
+
+/// Reports the service as unavailable if the DB cannot be reached, or if the data it serves lags
+/// too far behind the network (by the age of the latest checkpoint), so that load balancers can
+/// route requests away from it.
 async fn health_checks(
     schema: axum::Extension<SuiGraphQLSchema>,
+    axum::extract::Query(params): axum::extract::Query<HealthParams>,
 ) -> impl axum::response::IntoResponse {
-    // Simple request to check if the DB is up
-    // TODO: add more checks
+    // Fetching the latest checkpoint checks both that the DB is up, and how fresh its data is.
     let req = r#"
         query {
-            chainIdentifier
+            checkpoint {
+                timestamp
+            }
         }
         "#;
-    let db_up = match schema.execute(req).await.is_ok() {
-        true => "UP",
-        false => "DOWN",
-    };
+    let resp = schema.execute(req).await;
+    let db_up = resp.is_ok();
+
+    let checkpoint_lag_ms = latest_checkpoint_timestamp(&resp.data)
+        .map(|timestamp| (Utc::now() - timestamp).num_milliseconds().max(0) as u64);
+    let max_checkpoint_lag_ms = params
+        .max_checkpoint_lag_ms
+        .unwrap_or(DEFAULT_MAX_CHECKPOINT_LAG_MS);
+    let checkpoint_fresh = checkpoint_lag_ms.is_some_and(|lag| lag <= max_checkpoint_lag_ms);
+
     let uptime = get_or_init_server_start_time()
         .await
         .elapsed()
         .as_secs_f64();
-    format!(
-        r#"{{"status": "UP","uptime": {},"checks": {{"DB": "{}",}}}}
-        "#,
-        uptime, db_up
-    )
+
+    let up_or_down = |up: bool| if up { "UP" } else { "DOWN" };
+    let status = if db_up && checkpoint_fresh {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+
+    let body = serde_json::json!({
+        "status": up_or_down(status == StatusCode::OK),
+        "uptime": uptime,
+        "checkpointLagMs": checkpoint_lag_ms,
+        "checks": {
+            "DB": up_or_down(db_up),
+            "Checkpoint": up_or_down(checkpoint_fresh),
+        },
+    });
+
+    (status, axum::Json(body))
+}
+
+/// The timestamp of the checkpoint in the response to the health check's query, if there is one.
+fn latest_checkpoint_timestamp(data: &Value) -> Option<ChronoDateTime<Utc>> {
+    let Value::Object(data) = data else {
+        return None;
+    };
+    let Some(Value::Object(checkpoint)) = data.get("checkpoint") else {
+        return None;
+    };
+    let Some(Value::String(timestamp)) = checkpoint.get("timestamp") else {
+        return None;
+    };
+    timestamp.parse().ok()
 }
 
 // One server per proc, so this is okay
@@ -4,4 +4,5 @@
 pub mod simple_server;
 
 pub mod builder;
+pub(crate) mod response_cache;
 pub mod version;
@@ -0,0 +1,139 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    num::NonZeroUsize,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use async_graphql::{value, CacheControl, Response, Value, Variables};
+use lru::LruCache;
+
+/// In-process cache of responses to queries, keyed by the query (with its whitespace normalized),
+/// its operation name and its variables. The `CacheTtl` extension serves responses from it after
+/// the request has been parsed and validated (so that every other extension still sees cache
+/// hits), and only caches successful responses to queries, for their TTL.
+pub(crate) struct ResponseCache {
+    entries: Mutex<LruCache<String, CachedResponse>>,
+}
+
+struct CachedResponse {
+    data: Value,
+    expires_at: Instant,
+}
+
+impl ResponseCache {
+    pub(crate) fn new(capacity: NonZeroUsize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// The key that the response to running `operation` from `query` with `variables` is cached
+    /// under.
+    pub(crate) fn key(operation: Option<&str>, query: &str, variables: &Variables) -> String {
+        let query = query.split_whitespace().collect::<Vec<_>>().join(" ");
+        let variables = serde_json::to_string(variables).unwrap_or_default();
+        let operation = operation.unwrap_or_default();
+        format!("{operation}\n{query}\n{variables}")
+    }
+
+    /// The response cached under `key`, if there is one and it has not expired yet. Its max age
+    /// is the time it has left in the cache.
+    pub(crate) fn get(&self, key: &str) -> Option<Response> {
+        let mut entries = self.entries.lock().unwrap();
+        let entry = entries.get(key)?;
+
+        let now = Instant::now();
+        if entry.expires_at > now {
+            let max_age = (entry.expires_at - now).as_secs();
+            let mut cache_control = CacheControl::default();
+            cache_control.max_age = max_age as _;
+
+            return Some(
+                Response::new(entry.data.clone())
+                    .cache_control(cache_control)
+                    .extension("cacheControl", value!({ "maxAge": max_age })),
+            );
+        }
+
+        entries.pop(key);
+        None
+    }
+
+    /// Cache `response` under `key` for `ttl_secs`, if it succeeded. A TTL of 0 means the
+    /// response is not cached.
+    pub(crate) fn insert(&self, key: String, response: &Response, ttl_secs: u64) {
+        if !response.is_ok() || ttl_secs == 0 {
+            return;
+        }
+
+        let entry = CachedResponse {
+            data: response.data.clone(),
+            expires_at: Instant::now() + Duration::from_secs(ttl_secs),
+        };
+
+        self.entries.lock().unwrap().put(key, entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_graphql::ServerError;
+
+    fn cache() -> ResponseCache {
+        ResponseCache::new(NonZeroUsize::new(2).unwrap())
+    }
+
+    fn response() -> Response {
+        Response::new(value!({ "chainIdentifier": "4c78adac" }))
+    }
+
+    #[test]
+    fn test_key_normalizes_whitespace() {
+        let vars = Variables::default();
+        let a = ResponseCache::key(None, "{ checkpoint { digest } }", &vars);
+        let b = ResponseCache::key(None, "{\n  checkpoint {\n    digest\n  }\n}", &vars);
+        assert_eq!(a, b);
+
+        let c = ResponseCache::key(Some("Other"), "{ checkpoint { digest } }", &vars);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn test_cache_hit() {
+        let cache = cache();
+        cache.insert("key".to_string(), &response(), 60);
+
+        let hit = cache.get("key").unwrap();
+        assert_eq!(hit.data, value!({ "chainIdentifier": "4c78adac" }));
+        assert!(hit.cache_control.max_age as i64 > 0);
+        assert!(cache.get("other").is_none());
+    }
+
+    #[test]
+    fn test_uncacheable_responses() {
+        let cache = cache();
+
+        cache.insert("no-ttl".to_string(), &response(), 0);
+        assert!(cache.get("no-ttl").is_none());
+
+        let failed = Response::from_errors(vec![ServerError::new("Failed", None)]);
+        cache.insert("failed".to_string(), &failed, 60);
+        assert!(cache.get("failed").is_none());
+    }
+
+    #[test]
+    fn test_eviction() {
+        let cache = cache();
+        cache.insert("a".to_string(), &response(), 60);
+        cache.insert("b".to_string(), &response(), 60);
+        cache.insert("c".to_string(), &response(), 60);
+
+        assert!(cache.get("a").is_none());
+        assert!(cache.get("b").is_some());
+        assert!(cache.get("c").is_some());
+    }
+}
@@ -95,7 +95,7 @@ pub(crate) async fn check_version_middleware<B>(
             return (
                 StatusCode::MISDIRECTED_REQUEST,
                 graphql_error_response(
-                    code::INTERNAL_SERVER_ERROR,
+                    code::BAD_REQUEST,
                     format!("Version '{req_version}' not supported."),
                 ),
             )
@@ -235,7 +235,8 @@ mod tests {
                 {
                   "message": "Version '0.0' not supported.",
                   "extensions": {
-                    "code": "INTERNAL_SERVER_ERROR"
+                    "code": "BAD_REQUEST",
+                    "retryable": false
                   }
                 }
               ]
@@ -267,7 +268,8 @@ mod tests {
                 {
                   "message": "Failed to parse x-sui-rpc-version: Multiple possible versions found.",
                   "extensions": {
-                    "code": "BAD_REQUEST"
+                    "code": "BAD_REQUEST",
+                    "retryable": false
                   }
                 }
               ]
@@ -296,7 +298,8 @@ mod tests {
                 {
                   "message": "Failed to parse x-sui-rpc-version: 'not-a-version' not a valid <YEAR>.<MONTH> version.",
                   "extensions": {
-                    "code": "BAD_REQUEST"
+                    "code": "BAD_REQUEST",
+                    "retryable": false
                   }
                 }
               ]
@@ -328,7 +331,8 @@ mod tests {
                 {
                   "message": "Failed to parse x-sui-rpc-version: Not a UTF8 string.",
                   "extensions": {
-                    "code": "BAD_REQUEST"
+                    "code": "BAD_REQUEST",
+                    "retryable": false
                   }
                 }
               ]
@@ -7,9 +7,9 @@ use sui_json_rpc::name_service::NameServiceConfig;
 use crate::{context_data::db_data_provider::PgManager, error::Error};
 
 use super::{
-    balance::Balance,
+    balance::{Balance, BalanceSnapshot},
     coin::Coin,
-    dynamic_field::DynamicField,
+    dynamic_field::{DynamicField, DynamicFieldName},
     object::{Object, ObjectFilter},
     stake::Stake,
     sui_address::SuiAddress,
@@ -106,6 +106,23 @@ impl Address {
             .extend()
     }
 
+    /// The change of the balance of a coin type over each checkpoint that changed it, with the
+    /// total balance after the checkpoint, in checkpoint order. The coin type defaults to SUI.
+    pub async fn balance_history(
+        &self,
+        ctx: &Context<'_>,
+        coin_type: Option<String>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Option<Connection<String, BalanceSnapshot>>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_balance_history(self.address, coin_type, first, after, last, before)
+            .await
+            .extend()
+    }
+
     /// The coin objects for the given address.
     /// The type field is a string of the inner type of the coin
     /// by which to filter (e.g., 0x2::sui::SUI).
@@ -124,6 +141,25 @@ impl Address {
             .extend()
     }
 
+    /// The coin objects of `type` (defaults to 0x2::sui::SUI) owned by the given address, in
+    /// order of balance, to support coin selection. Paginating with `last` starts from the coins
+    /// with the largest balances. Coins with a balance below `minBalance` are excluded.
+    pub async fn coins(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        type_: Option<String>,
+        min_balance: Option<u64>,
+    ) -> Result<Option<Connection<String, Coin>>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_coins_by_balance(self.address, type_, min_balance, first, after, last, before)
+            .await
+            .extend()
+    }
+
     /// The `0x3::staking_pool::StakedSui` objects owned by the given address.
     pub async fn stake_connection(
         &self,
@@ -139,6 +175,24 @@ impl Address {
             .extend()
     }
 
+    /// The `0x3::staking_pool::StakedSui` objects owned by the given address, ordered by object ID.
+    /// The cursor of a stake is the ID of its object, so it stays valid as other stakes are added
+    /// or removed.
+    pub async fn staked_suis(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Option<Connection<String, Stake>>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_staked_sui(self.address, first, after, last, before)
+            .await
+            .extend()
+    }
+
+    /// The domain that a user address has explicitly configured as their default domain
     pub async fn default_name_service_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
         ctx.data_unchecked::<PgManager>()
             .default_name_service_name(ctx.data_unchecked::<NameServiceConfig>(), self.address)
@@ -146,6 +200,15 @@ impl Address {
             .extend()
     }
 
+    /// The SuiNS name that a user address has explicitly configured as their default name, the
+    /// same as `defaultNameServiceName`
+    pub async fn default_suins_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        ctx.data_unchecked::<PgManager>()
+            .default_name_service_name(ctx.data_unchecked::<NameServiceConfig>(), self.address)
+            .await
+            .extend()
+    }
+
     // TODO disabled-for-rpc-1.5
     // pub async fn name_service_connection(
     //     &self,
@@ -166,4 +229,11 @@ impl Address {
     ) -> Result<Option<Connection<String, DynamicField>>, Error> {
         Err(crate::error::Error::DynamicFieldOnAddress)
     }
+
+    pub async fn dynamic_field(
+        &self,
+        _name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>, Error> {
+        Err(crate::error::Error::DynamicFieldOnAddress)
+    }
 }
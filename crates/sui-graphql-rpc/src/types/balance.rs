@@ -1,11 +1,17 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{big_int::BigInt, move_type::MoveType};
-use crate::types::owner::Owner;
+use std::str::FromStr;
+
+use super::{
+    big_int::BigInt, coin_metadata::CoinMetadata, move_type::MoveType, sui_address::SuiAddress,
+};
+use crate::{context_data::db_data_provider::PgManager, error::Error, types::owner::Owner};
 use async_graphql::*;
+use move_core_types::language_storage::TypeTag;
 
 #[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+#[graphql(complex)]
 pub(crate) struct Balance {
     /// Coin type for the balance, such as 0x2::sui::SUI
     pub(crate) coin_type: Option<MoveType>,
@@ -15,9 +21,175 @@ pub(crate) struct Balance {
     pub(crate) total_balance: Option<BigInt>,
 }
 
+#[ComplexObject]
+impl Balance {
+    /// The metadata of the coin type, such as its decimals and symbol, to display the balance in
+    /// human units
+    async fn coin_metadata(&self, ctx: &Context<'_>) -> Result<Option<CoinMetadata>> {
+        let Some(coin_type) = &self.coin_type else {
+            return Ok(None);
+        };
+        let TypeTag::Struct(coin_struct) = coin_type.native_type_tag()? else {
+            return Ok(None);
+        };
+        ctx.data_unchecked::<PgManager>()
+            .fetch_coin_metadata(*coin_struct)
+            .await
+            .extend()
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
 pub(crate) struct BalanceChange {
     pub(crate) owner: Option<Owner>,
     pub(crate) amount: Option<BigInt>,
     pub(crate) coin_type: Option<MoveType>,
 }
+
+#[derive(InputObject, Debug, Default, Clone)]
+pub(crate) struct BalanceChangeFilter {
+    /// Limit to changes of the balances of this address
+    pub owner: Option<SuiAddress>,
+    /// Limit to changes of balances of this coin type, such as 0x2::sui::SUI
+    pub coin_type: Option<String>,
+}
+
+impl BalanceChangeFilter {
+    /// The changes of `changes` matching the filter, with their positions in `changes`
+    pub(crate) fn apply<'a>(
+        &self,
+        changes: &'a [Option<BalanceChange>],
+    ) -> Result<Vec<(usize, &'a BalanceChange)>, Error> {
+        let coin_type = self
+            .coin_type
+            .as_ref()
+            .map(|coin_type| TypeTag::from_str(coin_type).map_err(|_| Error::InvalidFilter))
+            .transpose()?;
+
+        let mut matching = vec![];
+        for (ix, change) in changes.iter().enumerate() {
+            let Some(change) = change else {
+                continue;
+            };
+            if let Some(owner) = &self.owner {
+                if change.owner.as_ref().map(|o| &o.address) != Some(owner) {
+                    continue;
+                }
+            }
+            if let Some(coin_type) = &coin_type {
+                let change_type = change
+                    .coin_type
+                    .as_ref()
+                    .and_then(|t| t.native_type_tag().ok());
+                if change_type.as_ref() != Some(coin_type) {
+                    continue;
+                }
+            }
+            matching.push((ix, change));
+        }
+        Ok(matching)
+    }
+}
+
+/// The change of a balance over the transactions of a checkpoint
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct BalanceSnapshot {
+    /// The checkpoint whose transactions changed the balance
+    pub(crate) checkpoint_sequence_number: u64,
+    /// The total change of the balance over the checkpoint, negative if it decreased
+    pub(crate) balance_change: BigInt,
+    /// The total balance after the checkpoint
+    pub(crate) total_balance: BigInt,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(owner: u8, coin_type: &str) -> Option<BalanceChange> {
+        Some(BalanceChange {
+            owner: Some(Owner {
+                address: SuiAddress::from_array([owner; 32]),
+            }),
+            amount: None,
+            coin_type: Some(MoveType::new(coin_type.to_string())),
+        })
+    }
+
+    fn positions(filter: BalanceChangeFilter, changes: &[Option<BalanceChange>]) -> Vec<usize> {
+        filter
+            .apply(changes)
+            .unwrap()
+            .into_iter()
+            .map(|(ix, _)| ix)
+            .collect()
+    }
+
+    #[test]
+    fn test_filter_by_owner_and_coin_type() {
+        let changes = vec![
+            change(1, "0x2::sui::SUI"),
+            None,
+            change(2, "0x2::sui::SUI"),
+            change(1, "0x42::usdc::USDC"),
+        ];
+
+        assert_eq!(
+            positions(BalanceChangeFilter::default(), &changes),
+            vec![0, 2, 3]
+        );
+
+        let by_owner = BalanceChangeFilter {
+            owner: Some(SuiAddress::from_array([1; 32])),
+            ..Default::default()
+        };
+        assert_eq!(positions(by_owner, &changes), vec![0, 3]);
+
+        // Coin types are compared as types, not as strings.
+        let by_coin_type = BalanceChangeFilter {
+            coin_type: Some(
+                "0x0000000000000000000000000000000000000000000000000000000000000002::sui::SUI"
+                    .to_string(),
+            ),
+            ..Default::default()
+        };
+        assert_eq!(positions(by_coin_type, &changes), vec![0, 2]);
+
+        let both = BalanceChangeFilter {
+            owner: Some(SuiAddress::from_array([1; 32])),
+            coin_type: Some("0x42::usdc::USDC".to_string()),
+        };
+        assert_eq!(positions(both, &changes), vec![3]);
+    }
+
+    #[test]
+    fn test_filter_skips_changes_without_owner_or_type() {
+        let changes = vec![Some(BalanceChange {
+            owner: None,
+            amount: None,
+            coin_type: None,
+        })];
+        let by_owner = BalanceChangeFilter {
+            owner: Some(SuiAddress::from_array([1; 32])),
+            ..Default::default()
+        };
+        let by_coin_type = BalanceChangeFilter {
+            coin_type: Some("0x2::sui::SUI".to_string()),
+            ..Default::default()
+        };
+        assert!(positions(by_owner, &changes).is_empty());
+        assert!(positions(by_coin_type, &changes).is_empty());
+    }
+
+    #[test]
+    fn test_filter_rejects_invalid_coin_type() {
+        let filter = BalanceChangeFilter {
+            coin_type: Some("not a type".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            filter.apply(&[change(1, "0x2::sui::SUI")]),
+            Err(Error::InvalidFilter)
+        ));
+    }
+}
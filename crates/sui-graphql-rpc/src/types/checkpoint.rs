@@ -1,7 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::context_data::db_data_provider::PgManager;
+use crate::context_data::{
+    data_loader::{load_checkpoint, load_epoch_strict},
+    db_data_provider::PgManager,
+};
 
 use super::{
     base64::Base64,
@@ -41,12 +44,16 @@ pub(crate) struct Checkpoint {
     pub live_object_set_digest: Option<String>,
     /// Tracks the total number of transaction blocks in the network at the time of the checkpoint.
     pub network_total_transactions: Option<u64>,
+    /// The number of transaction blocks included in this checkpoint.
+    pub transaction_count: Option<u64>,
     /// The computation and storage cost, storage rebate, and nonrefundable storage fee accumulated
     /// during this epoch, up to and including this checkpoint.
     /// These values increase monotonically across checkpoints in the same epoch.
     pub rolling_gas_summary: Option<GasCostSummary>,
     #[graphql(skip)]
     pub epoch_id: u64,
+    /// Whether this is the final checkpoint of its epoch, after which the network changes epoch.
+    pub is_last_in_epoch: Option<bool>,
     /// End of epoch data is only available on the final checkpoint of an epoch.
     /// This field provides information on the new committee and protocol version for the next epoch.
     pub end_of_epoch: Option<EndOfEpochData>,
@@ -54,16 +61,24 @@ pub(crate) struct Checkpoint {
 
 #[ComplexObject]
 impl Checkpoint {
+    /// The epoch this checkpoint is part of.
     async fn epoch(&self, ctx: &Context<'_>) -> Result<Option<Epoch>> {
-        let epoch = ctx
-            .data_unchecked::<PgManager>()
-            .fetch_epoch_strict(self.epoch_id)
-            .await
-            .extend()?;
+        let epoch = load_epoch_strict(ctx, self.epoch_id).await?;
 
         Ok(Some(epoch))
     }
 
+    /// Whether this is the first checkpoint of its epoch, either the genesis checkpoint or the one
+    /// following the final checkpoint of the previous epoch.
+    async fn is_first_in_epoch(&self, ctx: &Context<'_>) -> Result<Option<bool>> {
+        let Some(previous) = self.sequence_number.checked_sub(1) else {
+            return Ok(Some(true));
+        };
+        let previous = load_checkpoint(ctx, previous).await?;
+        Ok(previous.map(|previous| previous.epoch_id != self.epoch_id))
+    }
+
+    /// The transaction blocks included in this checkpoint, in execution order.
     async fn transaction_block_connection(
         &self,
         ctx: &Context<'_>,
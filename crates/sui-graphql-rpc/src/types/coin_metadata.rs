@@ -0,0 +1,19 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+
+/// The metadata of a coin type, from its `0x2::coin::CoinMetadata` object
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct CoinMetadata {
+    /// Number of decimal places the coin uses, to display its amounts in human units
+    pub decimals: Option<u8>,
+    /// Name for the token
+    pub name: Option<String>,
+    /// Symbol for the token
+    pub symbol: Option<String>,
+    /// Description of the token
+    pub description: Option<String>,
+    /// URL for the token logo
+    pub icon_url: Option<String>,
+}
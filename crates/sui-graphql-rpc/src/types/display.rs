@@ -2,9 +2,124 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_graphql::*;
+use move_core_types::value::MoveStruct;
+use sui_json_rpc::read_api::get_rendered_fields;
+use sui_types::collection_types::{Entry, VecMap};
+use sui_types::display::DisplayVersionUpdatedEvent;
 
+/// A field of an object's Display template, rendered with the contents of the object.
 #[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
 pub(crate) struct DisplayEntry {
+    /// The name of the field in the Display template
     pub key: String,
+    /// The field's template, with the values it references substituted in. Empty if the field
+    /// failed to render
     pub value: String,
+    /// Why the field failed to render, if it did
+    pub error: Option<String>,
+}
+
+/// Render each field of `display` against `move_struct`, separately, so that a field that fails
+/// to render does not prevent the others from being returned.
+pub(crate) fn render_display(
+    display: DisplayVersionUpdatedEvent,
+    move_struct: &MoveStruct,
+) -> Vec<DisplayEntry> {
+    display
+        .fields
+        .contents
+        .into_iter()
+        .map(|Entry { key, value }| {
+            let template = VecMap {
+                contents: vec![Entry {
+                    key: key.clone(),
+                    value,
+                }],
+            };
+
+            match get_rendered_fields(template, move_struct) {
+                Ok(rendered) => {
+                    let value = rendered.data.and_then(|mut data| data.remove(&key));
+                    let error = rendered.error.map(|e| e.to_string());
+                    DisplayEntry {
+                        key,
+                        value: value.unwrap_or_default(),
+                        error,
+                    }
+                }
+                Err(e) => DisplayEntry {
+                    key,
+                    value: String::new(),
+                    error: Some(e.to_string()),
+                },
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::{identifier::Identifier, value::MoveValue};
+    use sui_types::{base_types::ObjectID, id::ID};
+
+    fn display(fields: &[(&str, &str)]) -> DisplayVersionUpdatedEvent {
+        DisplayVersionUpdatedEvent {
+            id: ID::new(ObjectID::ZERO),
+            version: 1,
+            fields: VecMap {
+                contents: fields
+                    .iter()
+                    .map(|(key, value)| Entry {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                    })
+                    .collect(),
+            },
+        }
+    }
+
+    fn hero() -> MoveStruct {
+        MoveStruct::WithFields(vec![
+            (Identifier::new("name").unwrap(), MoveValue::U64(7)),
+            (Identifier::new("level").unwrap(), MoveValue::U8(3)),
+        ])
+    }
+
+    #[test]
+    fn test_render_display_substitutes_fields() {
+        let entries = render_display(
+            display(&[("name", "Hero #{name}"), ("description", "Level {level}")]),
+            &hero(),
+        );
+        assert_eq!(
+            entries,
+            vec![
+                DisplayEntry {
+                    key: "name".to_string(),
+                    value: "Hero #7".to_string(),
+                    error: None,
+                },
+                DisplayEntry {
+                    key: "description".to_string(),
+                    value: "Level 3".to_string(),
+                    error: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_display_reports_errors_per_field() {
+        let entries = render_display(
+            display(&[("name", "Hero #{name}"), ("image_url", "{missing}")]),
+            &hero(),
+        );
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].value, "Hero #7");
+        assert_eq!(entries[0].error, None);
+        assert_eq!(entries[1].key, "image_url");
+        assert_eq!(entries[1].value, "");
+        assert!(entries[1].error.is_some());
+    }
 }
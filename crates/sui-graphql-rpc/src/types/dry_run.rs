@@ -0,0 +1,233 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use move_core_types::language_storage::TypeTag;
+use sui_json_rpc_types::{
+    DevInspectResults, DryRunTransactionBlockResponse, SuiArgument, SuiExecutionResult,
+    SuiExecutionStatus, SuiTransactionBlockEffectsAPI, SuiTypeTag,
+};
+
+use super::{
+    base64::Base64, big_int::BigInt, digest::Digest, event::Event, gas::GasCostSummary,
+    move_value::MoveValue, transaction_block::TransactionBlockEffects,
+};
+use crate::error::Error;
+
+/// Gas units added to the computation cost of a simulated transaction block when suggesting a
+/// budget for it, as its cost can differ between simulation and execution.
+const GAS_SAFE_OVERHEAD: u64 = 1000;
+
+/// The result of simulating a transaction block, without committing its effects on chain
+#[derive(SimpleObject)]
+pub(crate) struct DryRunResult {
+    /// The error the transaction block would fail with, if it would fail
+    pub error: Option<String>,
+    /// The effects the transaction block would have, including its balance changes and gas usage
+    pub effects: TransactionBlockEffects,
+    /// The events the transaction block would emit
+    pub events: Option<Vec<Event>>,
+    /// The values mutated and returned by each command of the transaction block, only available
+    /// for dev-inspected transaction blocks
+    pub results: Option<Vec<DryRunEffect>>,
+}
+
+/// An estimate of the gas a transaction block would use, from simulating it
+#[derive(SimpleObject)]
+pub(crate) struct GasEstimate {
+    /// The error the transaction block would fail with, if it would fail
+    pub error: Option<String>,
+    /// The computation and storage costs, and storage rebate, of the transaction block
+    pub gas_summary: GasCostSummary,
+    /// A gas budget that covers the costs of the transaction block, with a safety margin on its
+    /// computation cost
+    pub suggested_budget: BigInt,
+}
+
+/// The values a command of a dev-inspected transaction block mutates and returns
+#[derive(SimpleObject)]
+pub(crate) struct DryRunEffect {
+    /// Arguments of the command that were mutably borrowed, with their values after the command
+    pub mutated_references: Option<Vec<DryRunMutation>>,
+    /// Values returned by the command
+    pub return_values: Option<Vec<MoveValue>>,
+}
+
+#[derive(SimpleObject)]
+pub(crate) struct DryRunMutation {
+    pub input: TransactionArgument,
+    pub value: MoveValue,
+}
+
+/// An argument to a command of a programmable transaction block
+#[derive(SimpleObject, Clone, Copy, Eq, PartialEq)]
+pub(crate) struct TransactionArgument {
+    pub kind: TransactionArgumentKind,
+    /// The index of the input, or of the command whose result is used
+    pub ix: Option<u16>,
+    /// The index of the value used, for commands that return more than one value
+    pub result_ix: Option<u16>,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub(crate) enum TransactionArgumentKind {
+    GasCoin,
+    Input,
+    Result,
+}
+
+impl TryFrom<DryRunTransactionBlockResponse> for DryRunResult {
+    type Error = Error;
+
+    fn try_from(response: DryRunTransactionBlockResponse) -> Result<Self, Self::Error> {
+        let digest = Digest::from_array(response.effects.transaction_digest().into_inner());
+        let effects = TransactionBlockEffects::from_executed_transaction(
+            Some(response.balance_changes),
+            &response.effects,
+            digest,
+        )?;
+        Ok(Self {
+            error: effects.errors.clone(),
+            effects,
+            events: Some(response.events.data.into_iter().map(Event::from).collect()),
+            results: None,
+        })
+    }
+}
+
+impl GasEstimate {
+    /// Estimates the gas used by a transaction block paying `gas_price` per unit of gas, from the
+    /// results of dev-inspecting its commands, which skips computing its balance and object
+    /// changes.
+    pub(crate) fn from_dev_inspect(results: &DevInspectResults, gas_price: u64) -> Self {
+        let error = match results.effects.status() {
+            SuiExecutionStatus::Success => results.error.clone(),
+            SuiExecutionStatus::Failure { error } => Some(error.clone()),
+        };
+
+        let gas_summary = GasCostSummary::from(results.effects.gas_cost_summary());
+        let suggested_budget = suggested_budget(&gas_summary, gas_price);
+
+        Self {
+            error,
+            gas_summary,
+            suggested_budget: BigInt::from(suggested_budget),
+        }
+    }
+}
+
+impl TryFrom<DevInspectResults> for DryRunResult {
+    type Error = Error;
+
+    fn try_from(results: DevInspectResults) -> Result<Self, Self::Error> {
+        let digest = Digest::from_array(results.effects.transaction_digest().into_inner());
+        let effects =
+            TransactionBlockEffects::from_executed_transaction(None, &results.effects, digest)?;
+        let commands = results
+            .results
+            .map(|commands| commands.into_iter().map(DryRunEffect::try_from).collect())
+            .transpose()?;
+        Ok(Self {
+            error: results.error.or_else(|| effects.errors.clone()),
+            effects,
+            events: Some(results.events.data.into_iter().map(Event::from).collect()),
+            results: commands,
+        })
+    }
+}
+
+impl TryFrom<SuiExecutionResult> for DryRunEffect {
+    type Error = Error;
+
+    fn try_from(result: SuiExecutionResult) -> Result<Self, Self::Error> {
+        let mutated_references = result
+            .mutable_reference_outputs
+            .into_iter()
+            .map(|(argument, bcs, type_)| {
+                Ok(DryRunMutation {
+                    input: TransactionArgument::from(argument),
+                    value: move_value(bcs, type_)?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+        let return_values = result
+            .return_values
+            .into_iter()
+            .map(|(bcs, type_)| move_value(bcs, type_))
+            .collect::<Result<_, Error>>()?;
+        Ok(Self {
+            mutated_references: Some(mutated_references),
+            return_values: Some(return_values),
+        })
+    }
+}
+
+impl From<SuiArgument> for TransactionArgument {
+    fn from(argument: SuiArgument) -> Self {
+        use TransactionArgumentKind as K;
+        let (kind, ix, result_ix) = match argument {
+            SuiArgument::GasCoin => (K::GasCoin, None, None),
+            SuiArgument::Input(ix) => (K::Input, Some(ix), None),
+            SuiArgument::Result(ix) => (K::Result, Some(ix), None),
+            SuiArgument::NestedResult(ix, result_ix) => (K::Result, Some(ix), Some(result_ix)),
+        };
+        Self {
+            kind,
+            ix,
+            result_ix,
+        }
+    }
+}
+
+/// The computation cost, with a safety margin, plus the storage cost net of rebates, if the
+/// transaction block would have to pay for storage overall.
+fn suggested_budget(gas_summary: &GasCostSummary, gas_price: u64) -> u64 {
+    let computation_cost = gas_summary
+        .computation_cost
+        .saturating_add(GAS_SAFE_OVERHEAD.saturating_mul(gas_price));
+    computation_cost.max(
+        computation_cost
+            .saturating_add(gas_summary.storage_cost)
+            .saturating_sub(gas_summary.storage_rebate),
+    )
+}
+
+fn move_value(bcs: Vec<u8>, type_: SuiTypeTag) -> Result<MoveValue, Error> {
+    let type_: TypeTag = type_
+        .try_into()
+        .map_err(|e| Error::Internal(format!("Cannot parse the type of a value: {e}")))?;
+    Ok(MoveValue::new(
+        type_.to_canonical_string(/* with_prefix */ true),
+        Base64::from(bcs),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gas_summary(
+        computation_cost: u64,
+        storage_cost: u64,
+        storage_rebate: u64,
+    ) -> GasCostSummary {
+        GasCostSummary {
+            computation_cost,
+            storage_cost,
+            storage_rebate,
+            non_refundable_storage_fee: 0,
+        }
+    }
+
+    #[test]
+    fn test_suggested_budget_covers_net_storage_cost() {
+        let summary = gas_summary(1_000_000, 2_000_000, 500_000);
+        assert_eq!(suggested_budget(&summary, 1000), 3_500_000);
+    }
+
+    #[test]
+    fn test_suggested_budget_ignores_net_rebate() {
+        let summary = gas_summary(1_000_000, 0, 5_000_000);
+        assert_eq!(suggested_budget(&summary, 1000), 2_000_000);
+    }
+}
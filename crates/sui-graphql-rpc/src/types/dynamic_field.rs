@@ -2,18 +2,24 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use async_graphql::*;
+use move_core_types::account_address::AccountAddress;
+use move_core_types::identifier::IdentStr;
 use move_core_types::language_storage::StructTag;
-use move_core_types::value::{self, MoveStruct, MoveTypeLayout};
+use move_core_types::u256::U256;
+use move_core_types::value::{self, MoveFieldLayout, MoveStruct, MoveStructLayout, MoveTypeLayout};
+use std::str::FromStr;
 use sui_indexer::models_v2::objects::StoredObject;
+use sui_types::base_types::{RESOLVED_ASCII_STR, RESOLVED_STD_OPTION, RESOLVED_UTF8_STR};
 use sui_types::dynamic_field::DynamicFieldInfo;
+use sui_types::id::{RESOLVED_SUI_ID, RESOLVED_SUI_UID};
 use sui_types::{dynamic_field::DynamicFieldType, TypeTag};
 
 use super::{
-    base64::Base64, move_object::MoveObject, move_value::MoveValue, sui_address::SuiAddress,
+    base64::Base64, json::Json, move_object::MoveObject, move_value::MoveValue,
+    sui_address::SuiAddress,
 };
-use crate::context_data::package_cache::PackageCache;
-use crate::error::{code, Error};
-use crate::{context_data::db_data_provider::PgManager, error::graphql_error};
+use crate::context_data::{data_loader::load_latest_move_obj, package_cache::PackageCache};
+use crate::error::{code, graphql_error, Error};
 use sui_types::object::Object as NativeSuiObject;
 
 pub(crate) struct DynamicField {
@@ -28,10 +34,16 @@ pub(crate) enum DynamicFieldValue {
     MoveValue(MoveValue),   // DynamicField
 }
 
+/// Identifies a dynamic field by the type and value of its name. The value can be given either as
+/// BCS bytes, or as JSON, encoded according to the layout of the name's type (e.g. `42` for a
+/// `u64`, `"key"` for a `0x1::string::String`, or `{"id": 1}` for a struct with a single field
+/// `id`). Integers may be passed as JSON numbers or strings, IDs as strings, and options as `null`
+/// or their value.
 #[derive(InputObject)] // used as input object
 pub(crate) struct DynamicFieldName {
     pub type_: String,
-    pub bcs: Base64,
+    pub bcs: Option<Base64>,
+    pub value: Option<Json>,
 }
 
 #[Object]
@@ -82,11 +94,7 @@ impl DynamicField {
 
     async fn value(&self, ctx: &Context<'_>) -> Result<Option<DynamicFieldValue>> {
         if self.df_kind == DynamicFieldType::DynamicObject {
-            let obj = ctx
-                .data_unchecked::<PgManager>()
-                .fetch_move_obj(self.df_object_id, None)
-                .await
-                .extend()?;
+            let obj = load_latest_move_obj(ctx, self.df_object_id).await?;
             Ok(obj.map(DynamicFieldValue::MoveObject))
         } else {
             let cache: &PackageCache = ctx.data().map_err(|_| {
@@ -116,6 +124,149 @@ impl DynamicField {
     }
 }
 
+impl DynamicFieldName {
+    /// Parse the name's type, and encode its value as BCS, in the form the indexer stores them.
+    /// Values given as JSON are encoded according to the layout of the name's type, read from
+    /// `cache`.
+    pub(crate) async fn into_type_and_bcs(
+        self,
+        cache: &PackageCache,
+    ) -> Result<(TypeTag, Vec<u8>), Error> {
+        let type_tag = TypeTag::from_str(&self.type_)
+            .map_err(|e| Error::InvalidDynamicFieldName(format!("Invalid type: {e}")))?;
+
+        let bcs = match (self.bcs, self.value) {
+            (Some(bcs), None) => bcs.0,
+            (None, Some(value)) => {
+                let layout = cache.type_layout(type_tag.clone()).await.map_err(|e| {
+                    Error::InvalidDynamicFieldName(format!("Invalid type {type_tag}: {e}"))
+                })?;
+                encode_name_value(&layout, &value.0)?
+            }
+            _ => {
+                return Err(Error::InvalidDynamicFieldName(
+                    "Provide exactly one of 'bcs' or 'value'".to_string(),
+                ))
+            }
+        };
+
+        Ok((type_tag, bcs))
+    }
+}
+
+/// BCS-encode a JSON name value according to the `layout` of its type.
+fn encode_name_value(layout: &MoveTypeLayout, value: &Value) -> Result<Vec<u8>, Error> {
+    json_to_move_value(layout, value)?
+        .simple_serialize()
+        .ok_or_else(|| Error::Internal("Failed to serialize dynamic field name".to_string()))
+}
+
+/// Convert a JSON value into a Move value of the given `layout`:
+///
+/// - Booleans are JSON booleans, and integers are JSON numbers or strings.
+/// - Addresses, `0x2::object::ID`s and `0x2::object::UID`s are strings.
+/// - `0x1::ascii::String`s and `0x1::string::String`s are strings.
+/// - `0x1::option::Option`s are `null`, or their value.
+/// - Vectors are lists, and other structs are objects with every field of the struct.
+fn json_to_move_value(layout: &MoveTypeLayout, value: &Value) -> Result<value::MoveValue, Error> {
+    use value::MoveValue as V;
+    use MoveTypeLayout as L;
+
+    fn parse<T: FromStr>(value: &Value) -> Option<T> {
+        match value {
+            Value::Number(n) => n.to_string().parse().ok(),
+            Value::String(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+
+    let invalid =
+        || Error::InvalidDynamicFieldName(format!("Value {value} is not a valid {layout}"));
+
+    Ok(match (layout, value) {
+        (L::Bool, Value::Boolean(b)) => V::Bool(*b),
+        (L::U8, _) => V::U8(parse(value).ok_or_else(invalid)?),
+        (L::U16, _) => V::U16(parse(value).ok_or_else(invalid)?),
+        (L::U32, _) => V::U32(parse(value).ok_or_else(invalid)?),
+        (L::U64, _) => V::U64(parse(value).ok_or_else(invalid)?),
+        (L::U128, _) => V::U128(parse(value).ok_or_else(invalid)?),
+        (L::U256, _) => V::U256(parse::<U256>(value).ok_or_else(invalid)?),
+        (L::Address, Value::String(s)) => V::Address(parse_address(s).ok_or_else(invalid)?),
+        (L::Vector(element), Value::List(elements)) => V::Vector(
+            elements
+                .iter()
+                .map(|e| json_to_move_value(element, e))
+                .collect::<Result<_, _>>()?,
+        ),
+
+        (L::Struct(MoveStructLayout::WithTypes { type_, fields }), _) => {
+            let fields =
+                if is_struct(type_, RESOLVED_ASCII_STR) || is_struct(type_, RESOLVED_UTF8_STR) {
+                    let Value::String(s) = value else {
+                        return Err(invalid());
+                    };
+                    vec![V::Vector(s.bytes().map(V::U8).collect())]
+                } else if is_struct(type_, RESOLVED_SUI_ID) || is_struct(type_, RESOLVED_SUI_UID) {
+                    let Value::String(s) = value else {
+                        return Err(invalid());
+                    };
+                    let id = V::Address(parse_address(s).ok_or_else(invalid)?);
+                    if is_struct(type_, RESOLVED_SUI_ID) {
+                        vec![id]
+                    } else {
+                        vec![V::Struct(MoveStruct::Runtime(vec![id]))]
+                    }
+                } else if is_struct(type_, RESOLVED_STD_OPTION) {
+                    let [MoveFieldLayout {
+                        layout: L::Vector(element),
+                        ..
+                    }] = fields.as_slice()
+                    else {
+                        return Err(invalid());
+                    };
+                    match value {
+                        Value::Null => vec![V::Vector(vec![])],
+                        value => vec![V::Vector(vec![json_to_move_value(element, value)?])],
+                    }
+                } else {
+                    let Value::Object(object) = value else {
+                        return Err(invalid());
+                    };
+                    if object.len() != fields.len() {
+                        return Err(invalid());
+                    }
+                    fields
+                        .iter()
+                        .map(|field| {
+                            let value = object.get(field.name.as_str()).ok_or_else(invalid)?;
+                            json_to_move_value(&field.layout, value)
+                        })
+                        .collect::<Result<_, _>>()?
+                };
+
+            V::Struct(MoveStruct::Runtime(fields))
+        }
+
+        _ => return Err(invalid()),
+    })
+}
+
+fn parse_address(s: &str) -> Option<AccountAddress> {
+    Some(AccountAddress::new(
+        SuiAddress::from_str(s).ok()?.into_array(),
+    ))
+}
+
+/// Whether `tag` is the struct identified by `resolved`, which are its address, module and name.
+fn is_struct(
+    tag: &StructTag,
+    (address, module, name): (&AccountAddress, &IdentStr, &IdentStr),
+) -> bool {
+    tag.address == *address
+        && tag.module.as_ident_str() == module
+        && tag.name.as_ident_str() == name
+}
+
 pub(crate) async fn deserialize_move_struct(
     serialized_object: &[u8],
     cache: &PackageCache,
@@ -159,3 +310,144 @@ pub fn extract_field_from_move_struct(
         _ => Err(Error::Internal("Unexpected Move struct type".to_string()).extend()),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_core_types::identifier::Identifier;
+    use sui_types::base_types::ObjectID;
+
+    fn struct_layout(
+        (address, module, name): (&AccountAddress, &IdentStr, &IdentStr),
+        fields: Vec<(&str, MoveTypeLayout)>,
+    ) -> MoveTypeLayout {
+        MoveTypeLayout::Struct(MoveStructLayout::WithTypes {
+            type_: StructTag {
+                address: *address,
+                module: module.to_owned(),
+                name: name.to_owned(),
+                type_params: vec![],
+            },
+            fields: fields
+                .into_iter()
+                .map(|(name, layout)| MoveFieldLayout::new(Identifier::new(name).unwrap(), layout))
+                .collect(),
+        })
+    }
+
+    fn string_layout() -> MoveTypeLayout {
+        struct_layout(
+            RESOLVED_UTF8_STR,
+            vec![(
+                "bytes",
+                MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)),
+            )],
+        )
+    }
+
+    fn json(value: serde_json::Value) -> Value {
+        Value::from_json(value).unwrap()
+    }
+
+    #[test]
+    fn test_encode_primitives() {
+        assert_eq!(
+            encode_name_value(&MoveTypeLayout::U64, &json(serde_json::json!(42))).unwrap(),
+            bcs::to_bytes(&42u64).unwrap()
+        );
+        assert_eq!(
+            encode_name_value(&MoveTypeLayout::U128, &json(serde_json::json!("42"))).unwrap(),
+            bcs::to_bytes(&42u128).unwrap()
+        );
+        assert_eq!(
+            encode_name_value(&MoveTypeLayout::Bool, &json(serde_json::json!(true))).unwrap(),
+            bcs::to_bytes(&true).unwrap()
+        );
+
+        let address = ObjectID::random();
+        assert_eq!(
+            encode_name_value(
+                &MoveTypeLayout::Address,
+                &json(serde_json::json!(address.to_string()))
+            )
+            .unwrap(),
+            address.to_vec()
+        );
+    }
+
+    #[test]
+    fn test_encode_strings_and_ids() {
+        assert_eq!(
+            encode_name_value(&string_layout(), &json(serde_json::json!("key"))).unwrap(),
+            bcs::to_bytes("key").unwrap()
+        );
+
+        let id = ObjectID::random();
+        let id_layout = struct_layout(RESOLVED_SUI_ID, vec![("bytes", MoveTypeLayout::Address)]);
+        assert_eq!(
+            encode_name_value(&id_layout, &json(serde_json::json!(id.to_string()))).unwrap(),
+            id.to_vec()
+        );
+    }
+
+    #[test]
+    fn test_encode_vectors_options_and_structs() {
+        let vector = MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U16));
+        assert_eq!(
+            encode_name_value(&vector, &json(serde_json::json!([1, "2"]))).unwrap(),
+            bcs::to_bytes(&vec![1u16, 2]).unwrap()
+        );
+
+        let option = struct_layout(
+            RESOLVED_STD_OPTION,
+            vec![("vec", MoveTypeLayout::Vector(Box::new(MoveTypeLayout::U8)))],
+        );
+        assert_eq!(
+            encode_name_value(&option, &json(serde_json::json!(null))).unwrap(),
+            bcs::to_bytes(&None::<u8>).unwrap()
+        );
+        assert_eq!(
+            encode_name_value(&option, &json(serde_json::json!(7))).unwrap(),
+            bcs::to_bytes(&Some(7u8)).unwrap()
+        );
+
+        // A name that is a struct of its own, with fields in a different order than the layout.
+        let key = struct_layout(
+            (
+                &AccountAddress::TWO,
+                IdentStr::new("m").unwrap(),
+                IdentStr::new("Key").unwrap(),
+            ),
+            vec![("id", MoveTypeLayout::U64), ("label", string_layout())],
+        );
+        assert_eq!(
+            encode_name_value(&key, &json(serde_json::json!({"label": "a", "id": 1}))).unwrap(),
+            bcs::to_bytes(&(1u64, "a")).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encode_invalid_values() {
+        let key = struct_layout(
+            (
+                &AccountAddress::TWO,
+                IdentStr::new("m").unwrap(),
+                IdentStr::new("Key").unwrap(),
+            ),
+            vec![("id", MoveTypeLayout::U64)],
+        );
+        for (layout, value) in [
+            (MoveTypeLayout::U8, serde_json::json!(256)),
+            (MoveTypeLayout::Bool, serde_json::json!("true")),
+            (MoveTypeLayout::Address, serde_json::json!("not an address")),
+            (string_layout(), serde_json::json!(1)),
+            (key.clone(), serde_json::json!({"id": 1, "extra": 2})),
+            (key, serde_json::json!({"other": 1})),
+        ] {
+            assert!(matches!(
+                encode_name_value(&layout, &json(value)),
+                Err(Error::InvalidDynamicFieldName(_))
+            ));
+        }
+    }
+}
@@ -7,6 +7,7 @@ use crate::error::Error;
 use super::big_int::BigInt;
 use super::checkpoint::Checkpoint;
 use super::date_time::DateTime;
+use super::event::{Event, EventFilter};
 use super::protocol_config::ProtocolConfigs;
 use super::transaction_block::{TransactionBlock, TransactionBlockFilter};
 use super::validator_set::ValidatorSet;
@@ -29,6 +30,30 @@ pub(crate) struct Epoch {
     pub start_timestamp: Option<DateTime>,
     /// The epoch's ending timestamp
     pub end_timestamp: Option<DateTime>,
+    /// The total number of transaction blocks in this epoch. Null until the epoch ends
+    pub total_transactions: Option<u64>,
+    /// The total amount of gas fees, in MIST, paid in this epoch. Null until the epoch ends
+    pub total_gas_fees: Option<BigInt>,
+    /// The total amount of staking rewards, in MIST, distributed at the end of this epoch. Null
+    /// until the epoch ends
+    pub total_stake_rewards: Option<BigInt>,
+    /// The amount of stake subsidies, in MIST, distributed at the end of this epoch. Null until the
+    /// epoch ends
+    pub total_stake_subsidies: Option<BigInt>,
+    /// The storage fund's balance, in MIST, at the end of this epoch. Null until the epoch ends
+    pub fund_size: Option<BigInt>,
+    /// The storage charges, in MIST, paid into the storage fund in this epoch. Null until the
+    /// epoch ends
+    pub fund_inflow: Option<BigInt>,
+    /// The storage rebates, in MIST, paid out of the storage fund in this epoch. Null until the
+    /// epoch ends
+    pub fund_outflow: Option<BigInt>,
+    /// The storage fund's earnings, in MIST, reinvested into it at the end of this epoch. Null
+    /// until the epoch ends
+    pub fund_reinvestment: Option<BigInt>,
+    /// Rewards, in MIST, left over after distribution that flowed into the storage fund at the
+    /// end of this epoch. Null until the epoch ends
+    pub leftover_fund_inflow: Option<BigInt>,
 }
 
 #[ComplexObject]
@@ -91,4 +116,46 @@ impl Epoch {
             .await
             .extend()
     }
+
+    /// Events emitted in this epoch, in the order they were emitted (by checkpoint, transaction
+    /// block, then position in the transaction block). Events must also match every criterion set
+    /// in `filter`. Cursors stay valid as new events are indexed.
+    async fn event_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<EventFilter>,
+    ) -> Result<Option<Connection<String, Event>>> {
+        let stored_epoch = ctx
+            .data_unchecked::<PgManager>()
+            .get_epoch(Some(self.epoch_id as i64))
+            .await
+            .extend()?
+            .ok_or(Error::Internal(
+                "Epoch should be able to find itself".to_string(),
+            ))?;
+
+        let after_checkpoint = if stored_epoch.first_checkpoint_id > 0 {
+            Some((stored_epoch.first_checkpoint_id - 1) as u64)
+        } else {
+            None
+        };
+        let before_checkpoint = stored_epoch.last_checkpoint_id.map(|id| (id + 1) as u64);
+
+        ctx.data_unchecked::<PgManager>()
+            .fetch_filtered_events(
+                first,
+                after,
+                last,
+                before,
+                filter.unwrap_or_default(),
+                after_checkpoint,
+                before_checkpoint,
+            )
+            .await
+            .extend()
+    }
 }
@@ -23,7 +23,7 @@ pub(crate) struct Event {
     pub bcs: Option<Base64>,
 }
 
-#[derive(InputObject)]
+#[derive(InputObject, Clone, Default)]
 pub(crate) struct EventFilter {
     pub sender: Option<SuiAddress>,
     pub transaction_digest: Option<String>,
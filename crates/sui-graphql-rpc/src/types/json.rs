@@ -7,7 +7,7 @@ use async_graphql::*;
 
 /// Arbitrary JSON data.
 #[derive(Debug)]
-pub(crate) struct Json(Value);
+pub(crate) struct Json(pub(crate) Value);
 
 #[Scalar(name = "JSON", use_type_description = true)]
 impl ScalarType for Json {
@@ -7,10 +7,12 @@ pub(crate) mod base64;
 pub(crate) mod big_int;
 pub(crate) mod checkpoint;
 pub(crate) mod coin;
+pub(crate) mod coin_metadata;
 pub(crate) mod committee_member;
 pub(crate) mod date_time;
 pub(crate) mod digest;
 pub(crate) mod display;
+pub(crate) mod dry_run;
 pub(crate) mod dynamic_field;
 pub(crate) mod end_of_epoch_data;
 pub(crate) mod epoch;
@@ -22,6 +24,7 @@ pub(crate) mod move_object;
 pub(crate) mod move_package;
 pub(crate) mod move_type;
 pub(crate) mod move_value;
+pub(crate) mod mutation;
 pub(crate) mod name_service;
 pub(crate) mod object;
 pub(crate) mod object_change;
@@ -29,13 +32,16 @@ pub(crate) mod owner;
 pub(crate) mod protocol_config;
 pub(crate) mod query;
 pub(crate) mod safe_mode;
+pub(crate) mod sponsorship;
 pub(crate) mod stake;
 pub(crate) mod stake_subsidy;
 pub(crate) mod storage_fund;
+pub(crate) mod subscription;
 pub(crate) mod sui_address;
 pub(crate) mod sui_system_state_summary;
 pub(crate) mod system_parameters;
 pub(crate) mod transaction_block;
+pub(crate) mod transaction_block_export;
 pub(crate) mod transaction_block_kind;
 pub(crate) mod transaction_signature;
 pub(crate) mod validator;
@@ -66,7 +66,7 @@ impl MoveModuleId {
 
         match result {
             Some(result) => Ok(result),
-            None => Err(Error::Internal("Package not found".to_string()).extend()),
+            None => Err(Error::NotFound(format!("Package {}", self.package)).extend()),
         }
     }
 }
@@ -2,14 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::big_int::BigInt;
+use super::display::{render_display, DisplayEntry};
+use super::dynamic_field::{DynamicField, DynamicFieldName};
 use super::move_value::MoveValue;
 use super::stake::StakeStatus;
 use super::{coin::Coin, object::Object};
 use crate::context_data::db_data_provider::PgManager;
-use crate::error::Error;
+use crate::context_data::package_cache::PackageCache;
+use crate::error::{code, graphql_error, Error};
 use crate::types::stake::Stake;
+use async_graphql::connection::Connection;
 use async_graphql::*;
 use move_core_types::language_storage::TypeTag;
+use move_core_types::value::{MoveStruct, MoveTypeLayout};
 use sui_types::governance::StakedSui;
 use sui_types::object::Object as NativeSuiObject;
 
@@ -105,7 +110,85 @@ impl MoveObject {
             principal: Some(BigInt::from(stake.principal())),
             request_epoch_id: Some(stake.activation_epoch().saturating_sub(1)),
             status: Some(status),
-            staked_sui_id: stake.id(),
+            native: stake,
         }))
     }
+
+    /// The object's fields rendered according to the latest Display template published for its
+    /// type, or null if there is no such template. Fields that fail to render report an error
+    /// instead of a value, without affecting the other fields.
+    async fn display(&self, ctx: &Context<'_>) -> Result<Option<Vec<DisplayEntry>>> {
+        let Some(struct_tag) = self.native_object.data.struct_tag() else {
+            return Ok(None);
+        };
+
+        let Some(display) = ctx
+            .data_unchecked::<PgManager>()
+            .fetch_display_object_by_type(&struct_tag)
+            .await
+            .extend()?
+        else {
+            return Ok(None);
+        };
+
+        let cache: &PackageCache = ctx.data().map_err(|_| {
+            graphql_error(
+                code::INTERNAL_SERVER_ERROR,
+                "Unable to fetch Package Cache.",
+            )
+        })?;
+        let layout = cache
+            .type_layout(TypeTag::Struct(Box::new(struct_tag)))
+            .await?;
+        let MoveTypeLayout::Struct(layout) = layout else {
+            return Err(Error::Internal("Object is not a move struct".to_string()).extend());
+        };
+
+        let contents = self
+            .native_object
+            .data
+            .try_as_move()
+            .ok_or_else(|| {
+                Error::Internal(format!(
+                    "Failed to convert native object to move object: {}",
+                    self.native_object.id()
+                ))
+            })?
+            .contents();
+        let move_struct = MoveStruct::simple_deserialize(contents, &layout)
+            .map_err(|e| Error::Internal(format!("Failed to deserialize object: {e}")))?;
+
+        Ok(Some(render_display(display, &move_struct)))
+    }
+
+    /// The dynamic fields owned by this object, with their names and values decoded.
+    async fn dynamic_field_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Option<Connection<String, DynamicField>>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_dynamic_fields(first, after, last, before, self.native_object.id().into())
+            .await
+            .extend()
+    }
+
+    /// Access a dynamic field owned by this object by the type and value of its name.
+    async fn dynamic_field(
+        &self,
+        ctx: &Context<'_>,
+        name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_dynamic_field(
+                self.native_object.id().into(),
+                name,
+                ctx.data_unchecked::<PackageCache>(),
+            )
+            .await
+            .extend()
+    }
 }
@@ -2,10 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::base64::Base64;
+use super::event::{Event, EventFilter};
 use super::move_module::MoveModule;
 use super::object::Object;
 use super::sui_address::SuiAddress;
-use crate::context_data::db_data_provider::validate_cursor_pagination;
+use crate::context_data::db_data_provider::{validate_cursor_pagination, PgManager};
 use crate::error::code::INTERNAL_SERVER_ERROR;
 use crate::error::{graphql_error, Error};
 use async_graphql::connection::{Connection, Edge};
@@ -73,6 +74,7 @@ impl MovePackage {
     /// Paginate through the MoveModules defined in this package.
     pub async fn module_connection(
         &self,
+        ctx: &Context<'_>,
         first: Option<u64>,
         after: Option<String>,
         last: Option<u64>,
@@ -81,6 +83,8 @@ impl MovePackage {
         // TODO: make cursor opaque.
         // for now it same as module name
         validate_cursor_pagination(&first, &after, &last, &before)?;
+        ctx.data_unchecked::<PgManager>()
+            .validate_page_size(&first, &last)?;
 
         if let Some(mod_map) = self
             .native_object
@@ -214,6 +218,29 @@ impl MovePackage {
     async fn as_object(&self) -> Option<Object> {
         Some(Object::from(&self.native_object))
     }
+
+    /// Events whose types are defined in this package, in the order they were emitted (by
+    /// checkpoint, transaction block, then position in the transaction block). Events must also
+    /// match every criterion set in `filter`. Cursors stay valid as new events are indexed.
+    async fn event_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<EventFilter>,
+    ) -> Result<Option<Connection<String, Event>>> {
+        let filter = EventFilter {
+            event_package: Some(SuiAddress::from(self.native_object.id())),
+            ..filter.unwrap_or_default()
+        };
+
+        ctx.data_unchecked::<PgManager>()
+            .fetch_filtered_events(first, after, last, before, filter, None, None)
+            .await
+            .extend()
+    }
 }
 
 impl MovePackage {
@@ -140,7 +140,7 @@ impl MoveType {
             })
     }
 
-    fn native_type_tag(&self) -> Result<TypeTag> {
+    pub(crate) fn native_type_tag(&self) -> Result<TypeTag> {
         TypeTag::from_str(&self.repr).map_err(|e| {
             graphql_error(
                 code::INTERNAL_SERVER_ERROR,
@@ -12,6 +12,7 @@ use move_core_types::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    config::ServiceConfig,
     error::{code, graphql_error},
     types::json::Json,
     types::move_type::unexpected_signer_error,
@@ -86,8 +87,10 @@ impl MoveValue {
             )
         })?;
 
+        let max_depth = max_move_value_depth(ctx);
+
         // Factor out into its own non-GraphQL, non-async function for better testability
-        self.data_impl(self.type_.layout_impl(cache).await?)
+        self.data_impl(self.type_.layout_impl(cache).await?, max_depth)
     }
 
     /// Representation of a Move value in JSON, where:
@@ -102,6 +105,9 @@ impl MoveValue {
     ///
     /// This form is offered as a less verbose convenience in cases where the layout of the type is
     /// known by the client.
+    ///
+    /// Both `data` and `json` fail for values whose vectors and structs are nested deeper than the
+    /// service's `maxMoveValueDepth`.
     async fn json(&self, ctx: &Context<'_>) -> Result<Json> {
         let cache = ctx.data().map_err(|_| {
             graphql_error(
@@ -110,8 +116,10 @@ impl MoveValue {
             )
         })?;
 
+        let max_depth = max_move_value_depth(ctx);
+
         // Factor out into its own non-GraphQL, non-async function for better testability
-        self.json_impl(self.type_.layout_impl(cache).await?)
+        self.json_impl(self.type_.layout_impl(cache).await?, max_depth)
     }
 }
 
@@ -121,7 +129,22 @@ impl MoveValue {
         Self { type_, bcs }
     }
 
-    fn value_impl(&self, layout: value::MoveTypeLayout) -> Result<value::MoveValue> {
+    fn value_impl(
+        &self,
+        layout: value::MoveTypeLayout,
+        max_depth: usize,
+    ) -> Result<value::MoveValue> {
+        let depth = layout_depth(&layout);
+        if depth > max_depth {
+            return Err(graphql_error(
+                code::BAD_USER_INPUT,
+                format!(
+                    "Move value is nested {depth} levels deep, exceeding the limit of {max_depth}"
+                ),
+            )
+            .into());
+        }
+
         // TODO: If this becomes a performance bottleneck, it can be made more efficient by not
         // deserializing via `value::MoveValue` (but this is significantly more code).
         Ok(bcs::from_bytes_seed(&layout, &self.bcs.0[..]).map_err(|_| {
@@ -136,12 +159,36 @@ impl MoveValue {
         })?)
     }
 
-    fn data_impl(&self, layout: value::MoveTypeLayout) -> Result<MoveData> {
-        MoveData::try_from(self.value_impl(layout)?)
+    fn data_impl(&self, layout: value::MoveTypeLayout, max_depth: usize) -> Result<MoveData> {
+        MoveData::try_from(self.value_impl(layout, max_depth)?)
     }
 
-    fn json_impl(&self, layout: value::MoveTypeLayout) -> Result<Json> {
-        Ok(try_to_json_value(self.value_impl(layout)?)?.into())
+    fn json_impl(&self, layout: value::MoveTypeLayout, max_depth: usize) -> Result<Json> {
+        Ok(try_to_json_value(self.value_impl(layout, max_depth)?)?.into())
+    }
+}
+
+/// The maximum depth of Move values this service will decode, from its configuration.
+fn max_move_value_depth(ctx: &Context<'_>) -> usize {
+    ctx.data_unchecked::<ServiceConfig>()
+        .limits
+        .max_move_value_depth as usize
+}
+
+/// The number of vectors and structs that values of this `layout` are nested within, at most.
+fn layout_depth(layout: &value::MoveTypeLayout) -> usize {
+    use value::{MoveStructLayout as S, MoveTypeLayout as L};
+    match layout {
+        L::Vector(inner) => 1 + layout_depth(inner),
+        L::Struct(S::Runtime(fields)) => 1 + fields.iter().map(layout_depth).max().unwrap_or(0),
+        L::Struct(S::WithFields(fields) | S::WithTypes { fields, .. }) => {
+            1 + fields
+                .iter()
+                .map(|f| layout_depth(&f.layout))
+                .max()
+                .unwrap_or(0)
+        }
+        _ => 0,
     }
 }
 
@@ -434,6 +481,8 @@ mod tests {
 
     use super::*;
 
+    const MAX_DEPTH: usize = 64;
+
     macro_rules! struct_layout {
         ($type:literal { $($name:literal : $layout:expr),* $(,)?}) => {
             value::MoveTypeLayout::Struct(S::WithTypes {
@@ -472,14 +521,22 @@ mod tests {
     ) -> Result<MoveData> {
         let type_ = MoveType::new(tag.into());
         let bcs = Base64(bcs::to_bytes(&data).unwrap());
-        MoveValue { type_, bcs }.data_impl(layout)
+        MoveValue { type_, bcs }.data_impl(layout, MAX_DEPTH)
     }
 
     fn json<T: Serialize>(layout: value::MoveTypeLayout, data: T) -> Result<Json> {
+        json_with_depth(layout, data, MAX_DEPTH)
+    }
+
+    fn json_with_depth<T: Serialize>(
+        layout: value::MoveTypeLayout,
+        data: T,
+        max_depth: usize,
+    ) -> Result<Json> {
         let tag: TypeTag = (&layout).try_into().expect("Error fetching type tag");
         let type_ = MoveType::new(tag.to_canonical_string(/* with_prefix */ true));
         let bcs = Base64(bcs::to_bytes(&data).unwrap());
-        MoveValue { type_, bcs }.json_impl(layout)
+        MoveValue { type_, bcs }.json_impl(layout, max_depth)
     }
 
     #[test]
@@ -984,4 +1041,30 @@ mod tests {
         ]];
         expect.assert_eq(&format!("{err:?}"));
     }
+
+    #[test]
+    fn nested_json_within_depth() {
+        let v = json_with_depth(
+            vector_layout!(vector_layout!(L::U8)),
+            vec![vec![1u8, 2], vec![3]],
+            2,
+        )
+        .unwrap();
+        let expect = expect!["[[1,2],[3]]"];
+        expect.assert_eq(&format!("{v}"));
+    }
+
+    #[test]
+    fn nested_json_too_deep() {
+        let err = json_with_depth(
+            vector_layout!(vector_layout!(L::U8)),
+            vec![vec![1u8, 2], vec![3]],
+            1,
+        )
+        .unwrap_err();
+        let expect = expect![[
+            r#"Error { message: "Move value is nested 2 levels deep, exceeding the limit of 1", extensions: None }"#
+        ]];
+        expect.assert_eq(&format!("{err:?}"));
+    }
 }
@@ -0,0 +1,146 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+use fastcrypto::traits::ToFromBytes;
+use shared_crypto::intent::Intent;
+use sui_json_rpc_types::SuiTransactionBlockResponseOptions;
+use sui_sdk::{error::Error as SdkError, json_rpc_error::Error as JsonRpcError};
+use sui_types::{
+    quorum_driver_types::ExecuteTransactionRequestType,
+    signature::GenericSignature,
+    transaction::{Transaction, TransactionData},
+};
+
+use super::{
+    base64::Base64, digest::Digest, event::Event, transaction_block::TransactionBlockEffects,
+};
+use crate::{context_data::sui_sdk_data_provider::sui_sdk_client, error::Error};
+
+pub(crate) struct Mutation;
+
+/// The result of executing a transaction block
+#[derive(SimpleObject)]
+pub(crate) struct ExecutionResult {
+    /// Errors that occurred while executing the transaction block, such as an abort
+    pub errors: Option<Vec<String>>,
+    /// The effects of executing the transaction block, including the balance changes it made
+    pub effects: TransactionBlockEffects,
+    /// The events emitted by the transaction block
+    pub events: Option<Vec<Event>>,
+}
+
+#[Object]
+impl Mutation {
+    /// Execute a transaction block, committing its effects on chain. Waits for the effects to be
+    /// certified by a quorum of validators, but not for the transaction to be checkpointed.
+    ///
+    /// `txBytes` is the BCS serialization of the `TransactionData` of the transaction block, and
+    /// `signatures` are the serialized signatures of its sender, and of its sponsor, if it has one.
+    async fn execute_transaction_block(
+        &self,
+        ctx: &Context<'_>,
+        tx_bytes: Base64,
+        signatures: Vec<Base64>,
+    ) -> Result<ExecutionResult> {
+        let sui_sdk_client = sui_sdk_client(ctx).extend()?;
+
+        let tx_data: TransactionData = bcs::from_bytes(&tx_bytes.0)
+            .map_err(|e| {
+                Error::InvalidTransaction(format!("Cannot deserialize transaction data: {e}"))
+            })
+            .extend()?;
+        let signatures = signatures
+            .iter()
+            .map(|sig| {
+                GenericSignature::from_bytes(&sig.0).map_err(|e| {
+                    Error::InvalidTransaction(format!("Cannot deserialize signature: {e}"))
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .extend()?;
+        let transaction =
+            Transaction::from_generic_sig_data(tx_data, Intent::sui_transaction(), signatures);
+
+        let options = SuiTransactionBlockResponseOptions::new()
+            .with_effects()
+            .with_events()
+            .with_balance_changes();
+        let response = sui_sdk_client
+            .quorum_driver_api()
+            .execute_transaction_block(
+                transaction,
+                options,
+                Some(ExecuteTransactionRequestType::WaitForEffectsCert),
+            )
+            .await
+            .map_err(execution_error)
+            .extend()?;
+
+        let Some(effects) = response.effects else {
+            return Err(Error::Internal(
+                "Execution did not return the effects of the transaction block".to_string(),
+            ))
+            .extend();
+        };
+        let effects = TransactionBlockEffects::from_executed_transaction(
+            response.balance_changes,
+            &effects,
+            Digest::from_array(response.digest.into_inner()),
+        )
+        .extend()?;
+        let errors = effects.errors.clone().map(|error| vec![error]);
+        let events = response
+            .events
+            .map(|events| events.data.into_iter().map(Event::from).collect());
+
+        Ok(ExecutionResult {
+            errors,
+            effects,
+            events,
+        })
+    }
+}
+
+/// Errors from executing a transaction block that the fullnode attributes to the transaction block
+/// itself, such as an invalid signature or an insufficient gas budget, are reported as invalid
+/// transactions, and any others as internal errors.
+fn execution_error(error: SdkError) -> Error {
+    let rpc_error = match error {
+        SdkError::RpcError(e) => JsonRpcError::from(e),
+        SdkError::JsonRpcError(e) => e,
+        SdkError::UserInputError(e) => return Error::InvalidTransaction(e.to_string()),
+        e => return Error::Internal(format!("Failed to execute transaction block: {e}")),
+    };
+    if rpc_error.is_client_error() {
+        Error::InvalidTransaction(rpc_error.message)
+    } else {
+        Error::Internal(format!("Failed to execute transaction block: {rpc_error}"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_sdk::json_rpc_error::{TRANSACTION_EXECUTION_CLIENT_ERROR_CODE, TRANSIENT_ERROR_CODE};
+
+    fn rpc_error(code: i32) -> SdkError {
+        SdkError::JsonRpcError(JsonRpcError {
+            code,
+            message: "Signature is not valid".to_string(),
+            data: None,
+        })
+    }
+
+    #[test]
+    fn test_rejected_transaction_is_invalid() {
+        let error = execution_error(rpc_error(TRANSACTION_EXECUTION_CLIENT_ERROR_CODE));
+        assert!(matches!(error, Error::InvalidTransaction(msg) if msg == "Signature is not valid"));
+    }
+
+    #[test]
+    fn test_fullnode_failure_is_internal() {
+        let error = execution_error(rpc_error(TRANSIENT_ERROR_CODE));
+        assert!(matches!(error, Error::Internal(_)));
+    }
+}
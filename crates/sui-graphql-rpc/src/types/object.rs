@@ -6,7 +6,7 @@ use sui_json_rpc::name_service::NameServiceConfig;
 
 use super::big_int::BigInt;
 use super::digest::Digest;
-use super::dynamic_field::DynamicField;
+use super::dynamic_field::{DynamicField, DynamicFieldName};
 use super::move_object::MoveObject;
 use super::move_package::MovePackage;
 use super::{
@@ -14,9 +14,13 @@ use super::{
     transaction_block::TransactionBlock,
 };
 use crate::context_data::db_data_provider::PgManager;
+use crate::context_data::package_cache::PackageCache;
 use crate::error::{code, graphql_error};
 use crate::types::base64::Base64;
-use sui_types::object::{Data as NativeSuiObjectData, Object as NativeSuiObject};
+use sui_indexer::types_v2::OwnerType;
+use sui_types::object::{
+    Data as NativeSuiObjectData, Object as NativeSuiObject, Owner as NativeOwner,
+};
 
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub(crate) struct Object {
@@ -30,12 +34,25 @@ pub(crate) struct Object {
     pub kind: Option<ObjectKind>,
 }
 
+/// The outcome of fetching one of the objects requested from `objects`.
+#[derive(Clone, Eq, PartialEq, Debug, SimpleObject)]
+pub(crate) struct ObjectResult {
+    /// The address of the requested object
+    pub address: SuiAddress,
+    /// The latest version of the object, or null if it does not exist or failed to load
+    pub object: Option<Object>,
+    /// Why the object failed to load, if it did
+    pub error: Option<String>,
+}
+
 #[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
 pub(crate) enum ObjectKind {
     Owned,
     Child,
     Shared,
     Immutable,
+    /// The indexer recorded an ownership kind that this service does not recognize yet.
+    Unknown,
 }
 
 #[derive(InputObject, Default, Clone)]
@@ -92,6 +109,22 @@ impl Object {
         }
     }
 
+    /// The versions of this object before this one, in order of version. Paginating with `last`
+    /// starts from the most recent of them.
+    async fn previous_versions(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Option<Connection<String, Object>>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_previous_obj_versions(first, after, last, before, self.address, self.version)
+            .await
+            .extend()
+    }
+
     /// Objects can either be immutable, shared, owned by an address,
     /// or are child objects (part of a dynamic field)
     async fn kind(&self) -> Option<ObjectKind> {
@@ -215,6 +248,25 @@ impl Object {
             .extend()
     }
 
+    /// The coin objects of `type` (defaults to 0x2::sui::SUI) owned by the given object, in
+    /// order of balance, to support coin selection. Paginating with `last` starts from the coins
+    /// with the largest balances. Coins with a balance below `minBalance` are excluded.
+    pub async fn coins(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        type_: Option<String>,
+        min_balance: Option<u64>,
+    ) -> Result<Option<Connection<String, Coin>>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_coins_by_balance(self.address, type_, min_balance, first, after, last, before)
+            .await
+            .extend()
+    }
+
     /// The `0x3::staking_pool::StakedSui` objects owned by the given object.
     pub async fn stake_connection(
         &self,
@@ -230,6 +282,23 @@ impl Object {
             .extend()
     }
 
+    /// The `0x3::staking_pool::StakedSui` objects owned by the given object, ordered by object ID.
+    /// The cursor of a stake is the ID of its object, so it stays valid as other stakes are added
+    /// or removed.
+    pub async fn staked_suis(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Option<Connection<String, Stake>>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_staked_sui(self.address, first, after, last, before)
+            .await
+            .extend()
+    }
+
     /// The domain that a user address has explicitly configured as their default domain
     pub async fn default_name_service_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
         ctx.data_unchecked::<PgManager>()
@@ -263,25 +332,53 @@ impl Object {
             .await
             .extend()
     }
+
+    /// Access a dynamic field on this object by the type and value of its name.
+    pub async fn dynamic_field(
+        &self,
+        ctx: &Context<'_>,
+        name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_dynamic_field(self.address, name, ctx.data_unchecked::<PackageCache>())
+            .await
+            .extend()
+    }
+}
+
+impl ObjectKind {
+    /// Classify an object by its owner.
+    pub(crate) fn from_owner(owner: &NativeOwner) -> Self {
+        match owner {
+            NativeOwner::AddressOwner(_) => ObjectKind::Owned,
+            NativeOwner::ObjectOwner(_) => ObjectKind::Child,
+            NativeOwner::Shared { .. } => ObjectKind::Shared,
+            NativeOwner::Immutable => ObjectKind::Immutable,
+        }
+    }
+
+    /// Classify an object by the `owner_type` the indexer stored for it. Owner types added to the
+    /// indexer after this service was built map to `Unknown` rather than failing the read.
+    pub(crate) fn from_owner_type(owner_type: i16) -> Self {
+        const IMMUTABLE: i16 = OwnerType::Immutable as i16;
+        const ADDRESS: i16 = OwnerType::Address as i16;
+        const OBJECT: i16 = OwnerType::Object as i16;
+        const SHARED: i16 = OwnerType::Shared as i16;
+
+        match owner_type {
+            IMMUTABLE => ObjectKind::Immutable,
+            ADDRESS => ObjectKind::Owned,
+            OBJECT => ObjectKind::Child,
+            SHARED => ObjectKind::Shared,
+            _ => ObjectKind::Unknown,
+        }
+    }
 }
 
 impl From<&NativeSuiObject> for Object {
     fn from(o: &NativeSuiObject) -> Self {
-        let kind = Some(match o.owner {
-            sui_types::object::Owner::AddressOwner(_) => ObjectKind::Owned,
-            sui_types::object::Owner::ObjectOwner(_) => ObjectKind::Child,
-            sui_types::object::Owner::Shared {
-                initial_shared_version: _,
-            } => ObjectKind::Shared,
-            sui_types::object::Owner::Immutable => ObjectKind::Immutable,
-        });
-
-        let owner_address = o.owner.get_owner_address().ok();
-        if matches!(kind, Some(ObjectKind::Immutable) | Some(ObjectKind::Shared))
-            && owner_address.is_some()
-        {
-            panic!("Immutable or Shared object should not have an owner_id");
-        }
+        let kind = Some(ObjectKind::from_owner(&o.owner));
+        let owner_address = o.owner.owner_address();
 
         let bcs = Base64::from(
             bcs::to_bytes(o)
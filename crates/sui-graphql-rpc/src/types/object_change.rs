@@ -1,8 +1,10 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use super::object::Object;
-use async_graphql::SimpleObject;
+use super::{move_type::MoveType, object::Object, sui_address::SuiAddress};
+use async_graphql::*;
+use sui_indexer::types_v2::IndexedObjectChange;
+use sui_types::object::Owner as NativeOwner;
 
 #[derive(PartialEq, Eq, Clone, SimpleObject)]
 pub(crate) struct ObjectChange {
@@ -11,3 +13,239 @@ pub(crate) struct ObjectChange {
     pub id_created: Option<bool>,
     pub id_deleted: Option<bool>,
 }
+
+/// A change to an object, made by a transaction block, as pushed to subscribers of
+/// `objectChanges`
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct ObjectUpdate {
+    pub kind: ObjectUpdateKind,
+    pub object_id: SuiAddress,
+    /// The version of the object after the change
+    pub version: u64,
+    /// The digest of the object after the change. Null for deleted and wrapped objects
+    pub digest: Option<String>,
+    pub object_type: Option<MoveType>,
+    /// The address or object owning the object after the change. Null for deleted and wrapped
+    /// objects, and for shared and immutable objects
+    pub owner: Option<SuiAddress>,
+    /// The transaction block that made the change
+    pub transaction_digest: String,
+    pub checkpoint_sequence_number: u64,
+}
+
+#[derive(Enum, Copy, Clone, Debug, Eq, PartialEq)]
+pub(crate) enum ObjectUpdateKind {
+    /// A new object, or a newly published package
+    Created,
+    /// An existing object was modified or transferred
+    Mutated,
+    /// The object was wrapped into another object
+    Wrapped,
+    Deleted,
+}
+
+#[derive(InputObject, Debug, Default, Clone)]
+pub(crate) struct ObjectChangeFilter {
+    /// Limit to changes to objects owned by this address, before or after the change
+    pub owner: Option<SuiAddress>,
+    /// Limit to changes to these objects
+    pub object_ids: Option<Vec<SuiAddress>>,
+}
+
+impl ObjectUpdate {
+    /// The change made by `change`, in the transaction block with digest `transaction_digest`.
+    pub(crate) fn from_indexed(
+        change: IndexedObjectChange,
+        transaction_digest: String,
+        checkpoint_sequence_number: u64,
+    ) -> Self {
+        use IndexedObjectChange as C;
+        use ObjectUpdateKind as K;
+        let (kind, object_id, version, digest, object_type, owner) = match change {
+            C::Published {
+                package_id,
+                version,
+                digest,
+                ..
+            } => (K::Created, package_id, version, Some(digest), None, None),
+            C::Created {
+                owner,
+                object_type,
+                object_id,
+                version,
+                digest,
+                ..
+            } => (
+                K::Created,
+                object_id,
+                version,
+                Some(digest),
+                Some(object_type),
+                Some(owner),
+            ),
+            C::Transferred {
+                recipient: owner,
+                object_type,
+                object_id,
+                version,
+                digest,
+                ..
+            }
+            | C::Mutated {
+                owner,
+                object_type,
+                object_id,
+                version,
+                digest,
+                ..
+            } => (
+                K::Mutated,
+                object_id,
+                version,
+                Some(digest),
+                Some(object_type),
+                Some(owner),
+            ),
+            C::Wrapped {
+                object_type,
+                object_id,
+                version,
+                ..
+            } => (
+                K::Wrapped,
+                object_id,
+                version,
+                None,
+                Some(object_type),
+                None,
+            ),
+            C::Deleted {
+                object_type,
+                object_id,
+                version,
+                ..
+            } => (
+                K::Deleted,
+                object_id,
+                version,
+                None,
+                Some(object_type),
+                None,
+            ),
+        };
+
+        let owner = owner.and_then(|owner| match owner {
+            NativeOwner::AddressOwner(address) => Some(SuiAddress::from_array(address.to_inner())),
+            NativeOwner::ObjectOwner(address) => Some(SuiAddress::from_array(address.to_inner())),
+            NativeOwner::Shared { .. } | NativeOwner::Immutable => None,
+        });
+
+        Self {
+            kind,
+            object_id: SuiAddress::from(object_id),
+            version: version.value(),
+            digest: digest.map(|d| d.to_string()),
+            object_type: object_type
+                .map(|t| MoveType::new(t.to_canonical_string(/* with_prefix */ true))),
+            owner,
+            transaction_digest,
+            checkpoint_sequence_number,
+        }
+    }
+
+    /// Whether the change is to an object matching `filter`: one of its objects, owned by its
+    /// owner before the change (`previous_owner`) or after it.
+    pub(crate) fn matches(
+        &self,
+        filter: &ObjectChangeFilter,
+        previous_owner: Option<&SuiAddress>,
+    ) -> bool {
+        if let Some(ids) = &filter.object_ids {
+            if !ids.contains(&self.object_id) {
+                return false;
+            }
+        }
+        if let Some(address) = &filter.owner {
+            if self.owner.as_ref() != Some(address) && previous_owner != Some(address) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::{
+        base_types::{ObjectDigest, ObjectID, SequenceNumber, SuiAddress as NativeSuiAddress},
+        parse_sui_struct_tag,
+    };
+
+    fn address(byte: u8) -> SuiAddress {
+        SuiAddress::from_array([byte; 32])
+    }
+
+    fn native(address: SuiAddress) -> NativeSuiAddress {
+        NativeSuiAddress::from_bytes(address.into_array()).unwrap()
+    }
+
+    /// A coin owned by `owner`, transferred by `sender` (who owned it before)
+    fn transferred(sender: SuiAddress, owner: SuiAddress) -> ObjectUpdate {
+        ObjectUpdate::from_indexed(
+            IndexedObjectChange::Transferred {
+                sender: native(sender),
+                recipient: NativeOwner::AddressOwner(native(owner)),
+                object_type: parse_sui_struct_tag("0x2::coin::Coin<0x2::sui::SUI>").unwrap(),
+                object_id: ObjectID::new(address(9).into_array()),
+                version: SequenceNumber::from_u64(3),
+                digest: ObjectDigest::random(),
+            },
+            "digest".to_string(),
+            1,
+        )
+    }
+
+    #[test]
+    fn test_owner_filter_matches_owner_after_change() {
+        let update = transferred(address(1), address(2));
+        assert_eq!(update.owner, Some(address(2)));
+        let filter = ObjectChangeFilter {
+            owner: Some(address(2)),
+            ..Default::default()
+        };
+        assert!(update.matches(&filter, None));
+    }
+
+    #[test]
+    fn test_owner_filter_matches_previous_owner_not_sender() {
+        let update = transferred(address(1), address(2));
+        let filter = ObjectChangeFilter {
+            owner: Some(address(3)),
+            ..Default::default()
+        };
+        // The sender is not taken as the previous owner
+        let sender_filter = ObjectChangeFilter {
+            owner: Some(address(1)),
+            ..Default::default()
+        };
+        assert!(!update.matches(&sender_filter, None));
+        assert!(!update.matches(&filter, Some(&address(1))));
+        assert!(update.matches(&filter, Some(&address(3))));
+    }
+
+    #[test]
+    fn test_object_ids_filter() {
+        let update = transferred(address(1), address(2));
+        let matching = ObjectChangeFilter {
+            object_ids: Some(vec![address(9)]),
+            ..Default::default()
+        };
+        let other = ObjectChangeFilter {
+            object_ids: Some(vec![address(8)]),
+            ..Default::default()
+        };
+        assert!(update.matches(&matching, None));
+        assert!(!update.matches(&other, None));
+    }
+}
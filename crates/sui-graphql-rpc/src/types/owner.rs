@@ -2,9 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::address::Address;
-use super::dynamic_field::DynamicField;
+use super::dynamic_field::{DynamicField, DynamicFieldName};
 use super::stake::Stake;
 use crate::context_data::db_data_provider::PgManager;
+use crate::context_data::package_cache::PackageCache;
 use crate::types::balance::*;
 use crate::types::coin::*;
 use crate::types::object::*;
@@ -48,6 +49,16 @@ use sui_json_rpc::name_service::NameServiceConfig;
         arg(name = "before", ty = "Option<String>"),
         arg(name = "type", ty = "Option<String>")
     ),
+    field(
+        name = "coins",
+        ty = "Option<Connection<String, Coin>>",
+        arg(name = "first", ty = "Option<u64>"),
+        arg(name = "after", ty = "Option<String>"),
+        arg(name = "last", ty = "Option<u64>"),
+        arg(name = "before", ty = "Option<String>"),
+        arg(name = "type", ty = "Option<String>"),
+        arg(name = "min_balance", ty = "Option<u64>")
+    ),
     field(
         name = "stake_connection",
         ty = "Option<Connection<String, Stake>>",
@@ -56,6 +67,14 @@ use sui_json_rpc::name_service::NameServiceConfig;
         arg(name = "last", ty = "Option<u64>"),
         arg(name = "before", ty = "Option<String>")
     ),
+    field(
+        name = "staked_suis",
+        ty = "Option<Connection<String, Stake>>",
+        arg(name = "first", ty = "Option<u64>"),
+        arg(name = "after", ty = "Option<String>"),
+        arg(name = "last", ty = "Option<u64>"),
+        arg(name = "before", ty = "Option<String>")
+    ),
     field(name = "default_name_service_name", ty = "Option<String>"),
     // TODO disabled-for-rpc-1.5
     // field(
@@ -73,6 +92,11 @@ use sui_json_rpc::name_service::NameServiceConfig;
         arg(name = "after", ty = "Option<String>"),
         arg(name = "last", ty = "Option<u64>"),
         arg(name = "before", ty = "Option<String>"),
+    ),
+    field(
+        name = "dynamic_field",
+        ty = "Option<DynamicField>",
+        arg(name = "name", ty = "DynamicFieldName")
     )
 )]
 #[derive(Clone, Eq, PartialEq, Debug)]
@@ -148,6 +172,23 @@ impl Owner {
             .extend()
     }
 
+    /// The change of the balance of a coin type over each checkpoint that changed it, with the
+    /// total balance after the checkpoint, in checkpoint order. The coin type defaults to SUI.
+    pub async fn balance_history(
+        &self,
+        ctx: &Context<'_>,
+        coin_type: Option<String>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Option<Connection<String, BalanceSnapshot>>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_balance_history(self.address, coin_type, first, after, last, before)
+            .await
+            .extend()
+    }
+
     /// The coin objects for the given address.
     /// The type field is a string of the inner type of the coin
     /// by which to filter (e.g., 0x2::sui::SUI).
@@ -166,6 +207,25 @@ impl Owner {
             .extend()
     }
 
+    /// The coin objects of `type` (defaults to 0x2::sui::SUI) owned by the given address, in
+    /// order of balance, to support coin selection. Paginating with `last` starts from the coins
+    /// with the largest balances. Coins with a balance below `minBalance` are excluded.
+    pub async fn coins(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        type_: Option<String>,
+        min_balance: Option<u64>,
+    ) -> Result<Option<Connection<String, Coin>>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_coins_by_balance(self.address, type_, min_balance, first, after, last, before)
+            .await
+            .extend()
+    }
+
     /// The stake objects for the given address
     pub async fn stake_connection(
         &self,
@@ -181,6 +241,24 @@ impl Owner {
             .extend()
     }
 
+    /// The `0x3::staking_pool::StakedSui` objects owned by the given address, ordered by object ID.
+    /// The cursor of a stake is the ID of its object, so it stays valid as other stakes are added
+    /// or removed.
+    pub async fn staked_suis(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Option<Connection<String, Stake>>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_staked_sui(self.address, first, after, last, before)
+            .await
+            .extend()
+    }
+
+    /// The domain that a user address has explicitly configured as their default domain
     pub async fn default_name_service_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
         ctx.data_unchecked::<PgManager>()
             .default_name_service_name(ctx.data_unchecked::<NameServiceConfig>(), self.address)
@@ -188,6 +266,15 @@ impl Owner {
             .extend()
     }
 
+    /// The SuiNS name that a user address has explicitly configured as their default name, the
+    /// same as `defaultNameServiceName`
+    pub async fn default_suins_name(&self, ctx: &Context<'_>) -> Result<Option<String>> {
+        ctx.data_unchecked::<PgManager>()
+            .default_name_service_name(ctx.data_unchecked::<NameServiceConfig>(), self.address)
+            .await
+            .extend()
+    }
+
     // TODO disabled-for-rpc-1.5
     // pub async fn name_service_connection(
     //     &self,
@@ -213,4 +300,16 @@ impl Owner {
             .await
             .extend()
     }
+
+    /// Access a dynamic field owned by this object by the type and value of its name.
+    pub async fn dynamic_field(
+        &self,
+        ctx: &Context<'_>,
+        name: DynamicFieldName,
+    ) -> Result<Option<DynamicField>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_dynamic_field(self.address, name, ctx.data_unchecked::<PackageCache>())
+            .await
+            .extend()
+    }
 }
@@ -1,29 +1,45 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use std::sync::Arc;
+
 use async_graphql::{connection::Connection, *};
 use sui_json_rpc::name_service::NameServiceConfig;
+use sui_types::{
+    base_types::SuiAddress as NativeSuiAddress,
+    transaction::{TransactionData, TransactionDataAPI, TransactionKind},
+};
 
 use super::{
     address::Address,
+    base64::Base64,
     checkpoint::{Checkpoint, CheckpointId},
+    dry_run::{DryRunResult, GasEstimate},
     epoch::Epoch,
     event::{Event, EventFilter},
-    object::{Object, ObjectFilter},
+    mutation::Mutation,
+    object::{Object, ObjectFilter, ObjectResult},
     owner::{ObjectOwner, Owner},
     protocol_config::ProtocolConfigs,
+    sponsorship::{GasSponsor, SponsoredTransactionStatus},
+    subscription::Subscription,
     sui_address::SuiAddress,
     sui_system_state_summary::SuiSystemStateSummary,
     transaction_block::{TransactionBlock, TransactionBlockFilter},
+    transaction_block_export::TransactionBlockExportBatch,
 };
 use crate::{
     config::ServiceConfig,
-    context_data::db_data_provider::PgManager,
+    context_data::{
+        data_loader::load_latest_obj, db_data_provider::PgManager,
+        sui_sdk_data_provider::sui_sdk_client,
+    },
     error::{code, graphql_error, Error},
+    extensions::rate_limiter::{ApiKey, ApiKeyUsage, ApiKeyUsageTracker},
 };
 
 pub(crate) struct Query;
-pub(crate) type SuiGraphQLSchema = async_graphql::Schema<Query, EmptyMutation, EmptySubscription>;
+pub(crate) type SuiGraphQLSchema = async_graphql::Schema<Query, Mutation, Subscription>;
 
 #[Object]
 impl Query {
@@ -49,6 +65,31 @@ impl Query {
             .cloned()?)
     }
 
+    /// The usage of the service attributed to each client today, with their keys and addresses
+    /// redacted. Only available to requests made with one of the operator keys in the service's
+    /// configuration.
+    async fn api_key_usage(&self, ctx: &Context<'_>) -> Result<Vec<ApiKeyUsage>> {
+        let cfg: &ServiceConfig = ctx.data_unchecked();
+        let is_operator = ctx
+            .data_opt::<ApiKey>()
+            .is_some_and(|ApiKey(key)| cfg.rate_limits.operator_keys.contains(key));
+        if !is_operator {
+            return Err(graphql_error(
+                code::BAD_REQUEST,
+                "API key usage is only available to operators",
+            )
+            .into());
+        }
+
+        let tracker = ctx.data_opt::<Arc<ApiKeyUsageTracker>>().ok_or_else(|| {
+            graphql_error(
+                code::INTERNAL_SERVER_ERROR,
+                "API key usage is not being tracked",
+            )
+        })?;
+        Ok(tracker.usage_today())
+    }
+
     // availableRange - pending impl. on IndexerV2
     // dryRunTransactionBlock
     // coinMetadata
@@ -62,9 +103,40 @@ impl Query {
         ctx: &Context<'_>,
         address: SuiAddress,
         version: Option<u64>,
+    ) -> Result<Option<Object>> {
+        match version {
+            Some(version) => ctx
+                .data_unchecked::<PgManager>()
+                .fetch_obj(address, Some(version))
+                .await
+                .extend(),
+            None => load_latest_obj(ctx, address).await,
+        }
+    }
+
+    /// Fetch the object at `address` as it was at exactly `version`, or as of `checkpoint` (the
+    /// latest version of the object written at or before it). Exactly one of `version` and
+    /// `checkpoint` must be provided. Unlike `object`, this can return versions that have since
+    /// been mutated or deleted.
+    async fn object_at(
+        &self,
+        ctx: &Context<'_>,
+        address: SuiAddress,
+        version: Option<u64>,
+        checkpoint: Option<u64>,
     ) -> Result<Option<Object>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_obj(address, version)
+            .fetch_obj_at(address, version, checkpoint)
+            .await
+            .extend()
+    }
+
+    /// Fetch the latest versions of the objects with the given `ids`, in one batch of at most
+    /// `maxMultiGetObjects` objects. Results are in the order of `ids`, and report their own
+    /// errors, so that one object failing to load does not fail the others.
+    async fn objects(&self, ctx: &Context<'_>, ids: Vec<SuiAddress>) -> Result<Vec<ObjectResult>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_objs(ids)
             .await
             .extend()
     }
@@ -90,6 +162,8 @@ impl Query {
         }
     }
 
+    /// Fetch a checkpoint by its digest or sequence number (but not both), or the latest
+    /// checkpoint if `id` is not provided.
     async fn checkpoint(
         &self,
         ctx: &Context<'_>,
@@ -126,6 +200,124 @@ impl Query {
             .extend()
     }
 
+    /// The sponsorship details of the transaction with the given digest, or null if the
+    /// transaction is not known to the indexer
+    async fn sponsored_transaction_status(
+        &self,
+        ctx: &Context<'_>,
+        digest: String,
+    ) -> Result<Option<SponsoredTransactionStatus>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_sponsored_tx_status(&digest)
+            .await
+            .extend()
+    }
+
+    /// The sponsorship metadata of an address that pays for gas on behalf of other senders, or
+    /// null if the address has not sponsored any transactions
+    async fn gas_sponsor(
+        &self,
+        ctx: &Context<'_>,
+        address: SuiAddress,
+    ) -> Result<Option<GasSponsor>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_gas_sponsor(address)
+            .await
+            .extend()
+    }
+
+    /// Simulate running a transaction block, without committing its effects on chain. The
+    /// transaction block is checked as if it were executed, except for its signatures.
+    ///
+    /// `txBytes` is the BCS serialization of the `TransactionData` of the transaction block.
+    async fn dry_run_transaction_block(
+        &self,
+        ctx: &Context<'_>,
+        tx_bytes: Base64,
+    ) -> Result<DryRunResult> {
+        let tx_data: TransactionData = bcs::from_bytes(&tx_bytes.0)
+            .map_err(|e| {
+                Error::InvalidTransaction(format!("Cannot deserialize transaction data: {e}"))
+            })
+            .extend()?;
+        let response = sui_sdk_client(ctx)
+            .extend()?
+            .read_api()
+            .dry_run_transaction_block(tx_data)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to dry run transaction block: {e}")))
+            .extend()?;
+        DryRunResult::try_from(response).extend()
+    }
+
+    /// Estimate the gas a transaction block would use, by simulating its commands, and suggest a
+    /// budget for it that covers its costs with a safety margin. Only the gas used is computed:
+    /// the budget and gas coins of the transaction block are not checked, and its balance and
+    /// object changes are not computed, so a transaction block that passes may still be rejected
+    /// when it is executed.
+    ///
+    /// `txBytes` is the BCS serialization of the `TransactionData` of the transaction block.
+    async fn estimate_gas(&self, ctx: &Context<'_>, tx_bytes: Base64) -> Result<GasEstimate> {
+        let tx_data: TransactionData = bcs::from_bytes(&tx_bytes.0)
+            .map_err(|e| {
+                Error::InvalidTransaction(format!("Cannot deserialize transaction data: {e}"))
+            })
+            .extend()?;
+        let sender = tx_data.sender();
+        let gas_price = tx_data.gas_price();
+        let results = sui_sdk_client(ctx)
+            .extend()?
+            .read_api()
+            .dev_inspect_transaction_block(
+                sender,
+                tx_data.into_kind(),
+                Some(gas_price.into()),
+                None,
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to simulate transaction block: {e}")))
+            .extend()?;
+        Ok(GasEstimate::from_dev_inspect(&results, gas_price))
+    }
+
+    /// Simulate running the commands of a transaction block as `sender`, to inspect the values
+    /// they mutate and return. Checks on gas and on the visibility of the functions called are
+    /// skipped, so the transaction block may not be executable as it is.
+    ///
+    /// `txBytes` is the BCS serialization of the `TransactionKind` of the transaction block.
+    /// `gasPrice` and `epoch` default to the reference gas price and the current epoch.
+    async fn dev_inspect_transaction_block(
+        &self,
+        ctx: &Context<'_>,
+        tx_bytes: Base64,
+        sender: SuiAddress,
+        gas_price: Option<u64>,
+        epoch: Option<u64>,
+    ) -> Result<DryRunResult> {
+        let tx_kind: TransactionKind = bcs::from_bytes(&tx_bytes.0)
+            .map_err(|e| {
+                Error::InvalidTransaction(format!("Cannot deserialize transaction kind: {e}"))
+            })
+            .extend()?;
+        let sender = NativeSuiAddress::from_bytes(sender.into_array())
+            .map_err(|e| Error::Internal(format!("Cannot convert sender address: {e}")))
+            .extend()?;
+        let results = sui_sdk_client(ctx)
+            .extend()?
+            .read_api()
+            .dev_inspect_transaction_block(
+                sender,
+                tx_kind,
+                gas_price.map(Into::into),
+                epoch.map(Into::into),
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to dev inspect transaction block: {e}")))
+            .extend()?;
+        DryRunResult::try_from(results).extend()
+    }
+
+    /// The checkpoints of the network, in sequence number order.
     async fn checkpoint_connection(
         &self,
         ctx: &Context<'_>,
@@ -155,6 +347,24 @@ impl Query {
             .extend()
     }
 
+    /// Exports the transaction blocks included in checkpoints `startCheckpoint` to `endCheckpoint`
+    /// (inclusive) as raw BCS, in ascending order of sequence number. The export is resumed by
+    /// passing the `endCursor` of the previous batch as `after`. `limit` defaults to, and must
+    /// not exceed, the service's `maxExportBatchSize`.
+    async fn transaction_block_export(
+        &self,
+        ctx: &Context<'_>,
+        start_checkpoint: u64,
+        end_checkpoint: u64,
+        after: Option<String>,
+        limit: Option<u64>,
+    ) -> Result<TransactionBlockExportBatch> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_tx_export_batch(start_checkpoint, end_checkpoint, after, limit)
+            .await
+            .extend()
+    }
+
     async fn event_connection(
         &self,
         ctx: &Context<'_>,
@@ -208,6 +418,19 @@ impl Query {
             .extend()
     }
 
+    /// Resolves the owner address of the provided SuiNS name, the same as
+    /// `resolveNameServiceAddress`
+    async fn resolve_suins_address(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+    ) -> Result<Option<Address>> {
+        ctx.data_unchecked::<PgManager>()
+            .resolve_name_service_address(ctx.data_unchecked::<NameServiceConfig>(), name)
+            .await
+            .extend()
+    }
+
     async fn latest_sui_system_state(&self, ctx: &Context<'_>) -> Result<SuiSystemStateSummary> {
         ctx.data_unchecked::<PgManager>()
             .fetch_latest_sui_system_state()
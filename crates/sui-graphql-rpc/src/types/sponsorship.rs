@@ -0,0 +1,94 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::{connection::Connection, *};
+
+use crate::context_data::db_data_provider::PgManager;
+
+use super::{
+    address::Address,
+    big_int::BigInt,
+    coin::Coin,
+    sui_address::SuiAddress,
+    transaction_block::{ExecutionStatus, TransactionBlock, TransactionBlockFilter},
+};
+
+/// An address that has paid for gas on behalf of other senders (a gas station), described by the
+/// transactions it has sponsored. Sponsorship providers don't publish their pools or policies on
+/// chain, so neither is available here.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+#[graphql(complex)]
+pub(crate) struct GasSponsor {
+    /// The address paying for gas
+    pub address: SuiAddress,
+    /// Aggregate statistics over the transactions this address has sponsored
+    pub stats: Option<GasSponsorStats>,
+}
+
+/// Totals and maxima over the transactions a sponsor has paid gas for. These describe what the
+/// sponsor has accepted so far, not a policy it enforces.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct GasSponsorStats {
+    /// Number of transactions whose gas was paid by this sponsor
+    pub sponsored_transaction_count: Option<u64>,
+    /// Number of distinct senders this sponsor has paid gas for
+    pub sender_count: Option<u64>,
+    /// The largest gas budget this sponsor has accepted
+    pub max_gas_budget: Option<BigInt>,
+    /// The largest gas price this sponsor has accepted
+    pub max_gas_price: Option<BigInt>,
+}
+
+/// The sponsorship details of a transaction, as tracked by the indexer.
+#[derive(Clone, SimpleObject)]
+pub(crate) struct SponsoredTransactionStatus {
+    /// Whether the gas for this transaction was paid by an address other than its sender
+    pub sponsored: bool,
+    /// The owner of the gas objects, if different from the sender
+    pub sponsor: Option<Address>,
+    pub sender: Option<Address>,
+    pub gas_budget: Option<BigInt>,
+    pub gas_price: Option<BigInt>,
+    pub status: Option<ExecutionStatus>,
+    pub transaction_block: Option<TransactionBlock>,
+}
+
+#[ComplexObject]
+impl GasSponsor {
+    /// The SUI coins currently held by the sponsor, available to pay for gas, in order of
+    /// balance
+    async fn gas_coin_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Option<Connection<String, Coin>>> {
+        // Without a coin type, coins are fetched for the gas coin type.
+        ctx.data_unchecked::<PgManager>()
+            .fetch_coins_by_balance(self.address, None, None, first, after, last, before)
+            .await
+            .extend()
+    }
+
+    /// The transaction blocks whose gas was paid by this sponsor
+    async fn transaction_block_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<TransactionBlockFilter>,
+    ) -> Result<Option<Connection<String, TransactionBlock>>> {
+        let filter = TransactionBlockFilter {
+            sponsor_address: Some(self.address),
+            ..filter.unwrap_or_default()
+        };
+        ctx.data_unchecked::<PgManager>()
+            .fetch_txs(first, after, last, before, Some(filter))
+            .await
+            .extend()
+    }
+}
@@ -1,11 +1,16 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::context_data::db_data_provider::PgManager;
+use crate::context_data::{data_loader::load_epoch_strict, db_data_provider::PgManager};
 
-use super::{big_int::BigInt, epoch::Epoch, move_object::MoveObject};
+use super::{
+    big_int::BigInt, epoch::Epoch, move_object::MoveObject, sui_address::SuiAddress,
+    validator::Validator,
+};
 use async_graphql::*;
-use sui_types::base_types::ObjectID;
+use sui_types::{
+    committee::EpochId, governance::StakedSui, sui_system_state::PoolTokenExchangeRate,
+};
 
 #[derive(Copy, Clone, Enum, PartialEq, Eq)]
 pub(crate) enum StakeStatus {
@@ -33,7 +38,7 @@ pub(crate) struct Stake {
     /// The status of this stake object: Active, Pending, Unstaked
     pub status: Option<StakeStatus>,
     #[graphql(skip)]
-    pub staked_sui_id: ObjectID,
+    pub native: StakedSui,
 }
 
 #[ComplexObject]
@@ -41,12 +46,7 @@ impl Stake {
     /// The epoch at which this stake became active
     async fn active_epoch(&self, ctx: &Context<'_>) -> Result<Option<Epoch>> {
         if let Some(epoch_id) = self.active_epoch_id {
-            let epoch = ctx
-                .data_unchecked::<PgManager>()
-                .fetch_epoch_strict(epoch_id)
-                .await
-                .extend()?;
-            Ok(Some(epoch))
+            Ok(Some(load_epoch_strict(ctx, epoch_id).await?))
         } else {
             Ok(None)
         }
@@ -55,12 +55,7 @@ impl Stake {
     /// The epoch at which this object was requested to join a stake pool
     async fn request_epoch(&self, ctx: &Context<'_>) -> Result<Option<Epoch>> {
         if let Some(epoch_id) = self.request_epoch_id {
-            let epoch = ctx
-                .data_unchecked::<PgManager>()
-                .fetch_epoch_strict(epoch_id)
-                .await
-                .extend()?;
-            Ok(Some(epoch))
+            Ok(Some(load_epoch_strict(ctx, epoch_id).await?))
         } else {
             Ok(None)
         }
@@ -71,8 +66,120 @@ impl Stake {
         let obj = ctx
             .data_unchecked::<PgManager>()
             .inner
-            .get_object_in_blocking_task(self.staked_sui_id)
+            .get_object_in_blocking_task(self.native.id())
             .await?;
         Ok(obj.map(|x| MoveObject { native_object: x }))
     }
+
+    /// The validator whose staking pool holds this stake, if it is an active validator
+    async fn validator(&self, ctx: &Context<'_>) -> Result<Option<Validator>> {
+        ctx.data_unchecked::<PgManager>()
+            .fetch_pool_validator(self.native.pool_id())
+            .await
+            .extend()
+    }
+
+    /// The ID of the staking pool holding this stake
+    async fn staking_pool(&self) -> SuiAddress {
+        SuiAddress::from(self.native.pool_id())
+    }
+
+    /// The estimated reward for this stake object if it is withdrawn at `epoch`, computed from the
+    /// exchange rates of its staking pool. Past the latest epoch, the exchange rate is projected
+    /// with its average change per epoch since the stake became active.
+    async fn estimated_reward_at_epoch(
+        &self,
+        ctx: &Context<'_>,
+        epoch: u64,
+    ) -> Result<Option<BigInt>> {
+        let Some(rates) = ctx
+            .data_unchecked::<PgManager>()
+            .fetch_pool_exchange_rates(self.native.pool_id())
+            .await
+            .extend()?
+        else {
+            return Ok(None);
+        };
+        Ok(projected_reward(&self.native, &rates.rates, epoch).map(BigInt::from))
+    }
+}
+
+/// The reward of `stake` if it is withdrawn at `epoch`, given the exchange `rates` of its pool in
+/// descending order by epoch. `None` if the pool has no rates.
+fn projected_reward(
+    stake: &StakedSui,
+    rates: &[(EpochId, PoolTokenExchangeRate)],
+    epoch: EpochId,
+) -> Option<u64> {
+    let activation_epoch = stake.activation_epoch();
+    let (latest_epoch, latest_rate) = rates.first()?;
+    if epoch <= activation_epoch {
+        return Some(0);
+    }
+    // same as the estimated reward of the stake, an unknown activation rate is the initial rate
+    let stake_rate = rates
+        .iter()
+        .find(|(rate_epoch, _)| *rate_epoch == activation_epoch)
+        .map_or_else(
+            || PoolTokenExchangeRate::default().rate(),
+            |(_, rate)| rate.rate(),
+        );
+    let rate = if epoch <= *latest_epoch {
+        // the rate of the epoch, or of the latest epoch before it with a rate
+        rates
+            .iter()
+            .find(|(rate_epoch, _)| *rate_epoch <= epoch)
+            .map(|(_, rate)| rate.rate())?
+    } else if *latest_epoch > activation_epoch {
+        let elapsed = (*latest_epoch - activation_epoch) as f64;
+        let growth = (stake_rate / latest_rate.rate()).powf(1.0 / elapsed);
+        latest_rate.rate() / growth.powf((epoch - *latest_epoch) as f64)
+    } else {
+        latest_rate.rate()
+    };
+    let reward = ((stake_rate / rate) - 1.0) * stake.principal() as f64;
+    Some(reward.max(0.0).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stake(activation_epoch: EpochId, principal: u64) -> StakedSui {
+        let mut bytes = vec![0u8; 64];
+        bytes.extend(bcs::to_bytes(&(activation_epoch, principal)).unwrap());
+        bcs::from_bytes(&bytes).unwrap()
+    }
+
+    fn rate(sui_amount: u64, pool_token_amount: u64) -> PoolTokenExchangeRate {
+        bcs::from_bytes(&bcs::to_bytes(&(sui_amount, pool_token_amount)).unwrap()).unwrap()
+    }
+
+    fn rates() -> Vec<(EpochId, PoolTokenExchangeRate)> {
+        vec![
+            (4, rate(121, 100)),
+            (3, rate(110, 100)),
+            (2, rate(100, 100)),
+        ]
+    }
+
+    #[test]
+    fn reward_from_known_rates() {
+        let stake = stake(2, 1_000);
+        assert_eq!(projected_reward(&stake, &rates(), 2), Some(0));
+        assert_eq!(projected_reward(&stake, &rates(), 3), Some(100));
+        assert_eq!(projected_reward(&stake, &rates(), 4), Some(210));
+    }
+
+    #[test]
+    fn reward_projected_past_latest_epoch() {
+        let stake = stake(2, 1_000);
+        // the rate grew by 10% per epoch since the stake became active
+        assert_eq!(projected_reward(&stake, &rates(), 6), Some(464));
+    }
+
+    #[test]
+    fn no_reward_without_rates() {
+        assert_eq!(projected_reward(&stake(2, 1_000), &[], 3), None);
+    }
 }
@@ -0,0 +1,125 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use std::time::Duration;
+
+use async_graphql::*;
+use futures::{stream, Stream, StreamExt};
+
+use super::{
+    event::{Event, EventFilter},
+    object_change::{ObjectChangeFilter, ObjectUpdate},
+};
+use crate::context_data::db_data_provider::PgManager;
+
+/// How long to wait before reading new events again, when there were none
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// The maximum number of events, or transaction blocks, read at once
+const POLL_BATCH_SIZE: u64 = 50;
+
+pub(crate) struct Subscription;
+
+/// The state of an event subscription: the latest event sent to the subscriber, or `None` once
+/// the subscription has failed
+type EventPoller = Option<(PgManager, EventFilter, Option<String>)>;
+
+/// The state of an object change subscription: the latest transaction block read, or `None` once
+/// the subscription has failed
+type ObjectUpdatePoller = Option<(PgManager, ObjectChangeFilter, Option<String>)>;
+
+#[Subscription]
+impl Subscription {
+    /// Events matching `filter`, emitted after the subscription started, as they are indexed.
+    /// Only one of the criteria of `filter` can be set, as for `eventConnection`. The
+    /// subscription ends after the first error.
+    async fn events(
+        &self,
+        ctx: &Context<'_>,
+        filter: EventFilter,
+    ) -> Result<impl Stream<Item = Result<Event>>> {
+        let pg_manager = ctx.data_unchecked::<PgManager>().clone();
+        let cursor = pg_manager
+            .fetch_latest_event_cursor(filter.clone())
+            .await
+            .extend()?;
+
+        let poller: EventPoller = Some((pg_manager, filter, cursor));
+        Ok(stream::unfold(poller, poll_events).flatten())
+    }
+
+    /// Changes to objects matching `filter`, made by transaction blocks indexed after the
+    /// subscription started, in order. Changes are pushed as the transaction blocks making them
+    /// are indexed. The subscription ends after the first error.
+    async fn object_changes(
+        &self,
+        ctx: &Context<'_>,
+        filter: ObjectChangeFilter,
+    ) -> Result<impl Stream<Item = Result<ObjectUpdate>>> {
+        let pg_manager = ctx.data_unchecked::<PgManager>().clone();
+        let cursor = pg_manager.fetch_latest_tx_cursor().await.extend()?;
+
+        let poller: ObjectUpdatePoller = Some((pg_manager, filter, cursor));
+        Ok(stream::unfold(poller, poll_object_updates).flatten())
+    }
+}
+
+/// Waits for the events after the cursor of `poller`, and returns them with the poller moved past
+/// them.
+async fn poll_events(
+    poller: EventPoller,
+) -> Option<(stream::Iter<std::vec::IntoIter<Result<Event>>>, EventPoller)> {
+    let (pg_manager, filter, mut cursor) = poller?;
+    loop {
+        let page = pg_manager
+            .fetch_events(
+                Some(POLL_BATCH_SIZE),
+                cursor.clone(),
+                None,
+                None,
+                filter.clone(),
+            )
+            .await;
+
+        match page {
+            Ok(Some(page)) if !page.edges.is_empty() => {
+                let mut events = vec![];
+                for edge in page.edges {
+                    cursor = Some(edge.cursor);
+                    events.push(Ok(edge.node));
+                }
+                return Some((stream::iter(events), Some((pg_manager, filter, cursor))));
+            }
+            Ok(_) => tokio::time::sleep(POLL_INTERVAL).await,
+            Err(e) => return Some((stream::iter(vec![Err(e.extend())]), None)),
+        }
+    }
+}
+
+/// Waits for the changes made by transaction blocks after the cursor of `poller`, and returns
+/// those matching its filter with the poller moved past the transaction blocks read.
+async fn poll_object_updates(
+    poller: ObjectUpdatePoller,
+) -> Option<(
+    stream::Iter<std::vec::IntoIter<Result<ObjectUpdate>>>,
+    ObjectUpdatePoller,
+)> {
+    let (pg_manager, filter, mut cursor) = poller?;
+    loop {
+        let batch = pg_manager
+            .fetch_object_updates(cursor.clone(), POLL_BATCH_SIZE, &filter)
+            .await;
+
+        match batch {
+            Ok((updates, end_cursor)) if end_cursor != cursor => {
+                cursor = end_cursor;
+                if !updates.is_empty() {
+                    let updates = updates.into_iter().map(Ok).collect::<Vec<_>>();
+                    return Some((stream::iter(updates), Some((pg_manager, filter, cursor))));
+                }
+            }
+            Ok(_) => tokio::time::sleep(POLL_INTERVAL).await,
+            Err(e) => return Some((stream::iter(vec![Err(e.extend())]), None)),
+        }
+    }
+}
@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::context_data::db_data_provider::PgManager;
+use crate::context_data::{data_loader::load_epoch_strict, db_data_provider::PgManager};
 
 use super::big_int::BigInt;
 use super::date_time::DateTime;
@@ -57,11 +57,7 @@ pub(crate) struct SuiSystemStateSummary {
 impl SuiSystemStateSummary {
     /// The epoch for which this is the system state.
     async fn epoch(&self, ctx: &Context<'_>) -> Result<Option<Epoch>> {
-        let epoch = ctx
-            .data_unchecked::<PgManager>()
-            .fetch_epoch_strict(self.epoch_id)
-            .await
-            .extend()?;
+        let epoch = load_epoch_strict(ctx, self.epoch_id).await?;
 
         Ok(Some(epoch))
     }
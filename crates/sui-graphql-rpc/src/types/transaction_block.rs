@@ -5,7 +5,7 @@ use std::str::FromStr;
 
 use super::{
     address::Address,
-    balance::BalanceChange,
+    balance::{BalanceChange, BalanceChangeFilter},
     base64::Base64,
     big_int::BigInt,
     checkpoint::Checkpoint,
@@ -19,8 +19,14 @@ use super::{
     transaction_block_kind::TransactionBlockKind,
     transaction_signature::TransactionSignature,
 };
-use crate::{context_data::db_data_provider::PgManager, error};
-use async_graphql::*;
+use crate::{
+    context_data::{
+        data_loader::{load_checkpoint, load_epoch_strict},
+        db_data_provider::{page_of_indexed, validate_cursor_pagination, PgManager},
+    },
+    error,
+};
+use async_graphql::{connection::Connection, *};
 
 use sui_indexer::types_v2::IndexedObjectChange;
 use sui_json_rpc_types::{
@@ -67,16 +73,43 @@ impl TransactionBlock {
     async fn expiration(&self, ctx: &Context<'_>) -> Result<Option<Epoch>> {
         match self.epoch_id {
             None => Ok(None),
-            Some(epoch_id) => {
-                let epoch = ctx
-                    .data_unchecked::<PgManager>()
-                    .fetch_epoch_strict(epoch_id)
-                    .await
-                    .extend()?;
-                Ok(Some(epoch))
-            }
+            Some(epoch_id) => Ok(Some(load_epoch_strict(ctx, epoch_id).await?)),
         }
     }
+
+    /// The changes this transaction block made to the balances of addresses, in the order of its
+    /// effects, optionally limited to an owner or coin type
+    async fn balance_changes(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+        filter: Option<BalanceChangeFilter>,
+    ) -> Result<Option<Connection<String, BalanceChange>>> {
+        validate_cursor_pagination(&first, &after, &last, &before).extend()?;
+        ctx.data_unchecked::<PgManager>()
+            .validate_page_size(&first, &last)
+            .extend()?;
+        let Some(changes) = self
+            .effects
+            .as_ref()
+            .and_then(|effects| effects.balance_changes.as_ref())
+        else {
+            return Ok(None);
+        };
+
+        let changes = filter
+            .unwrap_or_default()
+            .apply(changes)
+            .extend()?
+            .into_iter()
+            .map(|(ix, change)| (ix, change.clone()));
+        page_of_indexed(changes, first, after, last, before)
+            .map(Some)
+            .extend()
+    }
 }
 
 #[derive(Clone, Eq, PartialEq, SimpleObject)]
@@ -93,6 +126,10 @@ pub(crate) struct TransactionBlockEffects {
     #[graphql(skip)]
     pub dependencies: Vec<TransactionDigest>,
     pub lamport_version: Option<u64>,
+    /// The effects of the transaction block in BCS format, which can be used to check them against
+    /// the effects digest. Not available for transaction blocks that were just executed or
+    /// simulated.
+    pub bcs: Option<Base64>,
     // unclear what object reads is about, TODO @ashok
     // pub object_reads: Vec<Object>,
     #[graphql(skip)]
@@ -103,8 +140,9 @@ pub(crate) struct TransactionBlockEffects {
     pub epoch_id: u64,
     // pub epoch: Option<Epoch>,
     // pub checkpoint: Option<Checkpoint>,
+    /// Not known yet for transactions that were just executed
     #[graphql(skip)]
-    checkpoint_seq_number: u64,
+    checkpoint_seq_number: Option<u64>,
 }
 
 impl TransactionBlockEffects {
@@ -112,6 +150,7 @@ impl TransactionBlockEffects {
         balance_changes: Vec<Option<Vec<u8>>>,
         checkpoint_seq_number: u64,
         object_changes: Vec<Option<Vec<u8>>>,
+        raw_effects: Vec<u8>,
         tx_effects: &SuiTransactionBlockEffects,
         tx_block_digest: Digest,
     ) -> Result<Option<Self>> {
@@ -121,36 +160,72 @@ impl TransactionBlockEffects {
                 (ExecutionStatus::Failure, Some(error.clone()))
             }
         };
-        let lamport_version = tx_effects
-            .created()
-            .first()
-            .map(|x| x.reference.version.value());
-        let balance_changes = BalanceChange::from(balance_changes)?;
+        let lamport_version = lamport_version(tx_effects);
+        let balance_changes = BalanceChange::from(balance_changes).extend()?;
 
         Ok(Some(Self {
             gas_effects: GasEffects::from((tx_effects.gas_cost_summary(), tx_effects.gas_object())),
             status,
             errors,
             lamport_version,
+            bcs: Some(Base64::from(raw_effects)),
             dependencies: tx_effects.dependencies().to_vec(),
             balance_changes: Some(balance_changes),
             epoch_id: tx_effects.executed_epoch(),
             tx_block_digest,
             object_changes_as_bcs: object_changes,
-            checkpoint_seq_number,
+            checkpoint_seq_number: Some(checkpoint_seq_number),
         }))
     }
+
+    /// The effects of a transaction block executed or simulated through a fullnode, which are
+    /// known before the checkpoint the transaction block is included in. Balance changes are not
+    /// known for dev-inspected transaction blocks.
+    pub fn from_executed_transaction(
+        balance_changes: Option<Vec<NativeBalanceChange>>,
+        tx_effects: &SuiTransactionBlockEffects,
+        tx_block_digest: Digest,
+    ) -> Result<Self, error::Error> {
+        let (status, errors) = match tx_effects.status() {
+            SuiExecutionStatus::Success => (ExecutionStatus::Success, None),
+            SuiExecutionStatus::Failure { error } => {
+                (ExecutionStatus::Failure, Some(error.clone()))
+            }
+        };
+        let lamport_version = lamport_version(tx_effects);
+        let balance_changes = balance_changes
+            .map(|changes| {
+                changes
+                    .into_iter()
+                    .map(|change| BalanceChange::from_native(change).map(Some))
+                    .collect::<Result<_, _>>()
+            })
+            .transpose()?;
+
+        Ok(Self {
+            gas_effects: GasEffects::from((tx_effects.gas_cost_summary(), tx_effects.gas_object())),
+            status,
+            errors,
+            lamport_version,
+            bcs: None,
+            dependencies: tx_effects.dependencies().to_vec(),
+            balance_changes,
+            epoch_id: tx_effects.executed_epoch(),
+            tx_block_digest,
+            object_changes_as_bcs: vec![],
+            checkpoint_seq_number: None,
+        })
+    }
 }
 
 #[ComplexObject]
 impl TransactionBlockEffects {
     // the lamport version is the sequence number?
     async fn checkpoint(&self, ctx: &Context<'_>) -> Result<Option<Checkpoint>> {
-        let checkpoint = ctx
-            .data_unchecked::<PgManager>()
-            .fetch_checkpoint(None, Some(self.checkpoint_seq_number))
-            .await?;
-        Ok(checkpoint)
+        let Some(checkpoint_seq_number) = self.checkpoint_seq_number else {
+            return Ok(None);
+        };
+        load_checkpoint(ctx, checkpoint_seq_number).await
     }
 
     // resolve the dependencies based on the transaction digests
@@ -167,11 +242,7 @@ impl TransactionBlockEffects {
     }
 
     async fn epoch(&self, ctx: &Context<'_>) -> Result<Option<Epoch>> {
-        let epoch = ctx
-            .data_unchecked::<PgManager>()
-            .fetch_epoch_strict(self.epoch_id)
-            .await?;
-        Ok(Some(epoch))
+        Ok(Some(load_epoch_strict(ctx, self.epoch_id).await?))
     }
 
     async fn gas_effects(&self) -> Option<GasEffects> {
@@ -215,17 +286,24 @@ pub enum ExecutionStatus {
 pub(crate) struct TransactionBlockFilter {
     pub package: Option<SuiAddress>,
     pub module: Option<String>,
+    /// Limit to transactions that called this function. Can also be a fully qualified
+    /// `package::module::function` (or a prefix of one, like `package::module`), in which case
+    /// `package` and `module` must not be set.
     pub function: Option<String>,
 
     pub kind: Option<TransactionBlockKindInput>,
+    /// Limit to transactions in checkpoints after (but not including) this one
     pub after_checkpoint: Option<u64>,
     pub at_checkpoint: Option<u64>,
+    /// Limit to transactions in checkpoints before (but not including) this one
     pub before_checkpoint: Option<u64>,
 
     pub sign_address: Option<SuiAddress>,
     pub sent_address: Option<SuiAddress>,
     pub recv_address: Option<SuiAddress>,
     pub paid_address: Option<SuiAddress>,
+    /// Limit to transactions whose gas was paid by this address on behalf of another sender
+    pub sponsor_address: Option<SuiAddress>,
 
     pub input_object: Option<SuiAddress>,
     pub changed_object: Option<SuiAddress>,
@@ -234,43 +312,57 @@ pub(crate) struct TransactionBlockFilter {
 }
 
 impl BalanceChange {
-    fn from(balance_changes: Vec<Option<Vec<u8>>>) -> Result<Vec<Option<BalanceChange>>> {
+    fn from(
+        balance_changes: Vec<Option<Vec<u8>>>,
+    ) -> Result<Vec<Option<BalanceChange>>, error::Error> {
         let mut output = vec![];
         for balance_change_bcs in balance_changes.into_iter().flatten() {
             let balance_change: NativeBalanceChange = bcs::from_bytes(&balance_change_bcs)
                 .map_err(|_| {
                     error::Error::Internal("Cannot convert bcs bytes to BalanceChange".to_string())
                 })?;
-            let balance_change_owner_address =
-                balance_change.owner.get_owner_address().map_err(|_| {
-                    error::Error::Internal(
-                        "Cannot get the balance change owner's address".to_string(),
-                    )
-                })?;
-
-            let address =
-                SuiAddress::from_bytes(balance_change_owner_address.to_vec()).map_err(|_| {
-                    error::Error::Internal(
-                        "Cannot get a SuiAddress from the balance change owner address".to_string(),
-                    )
-                })?;
-            let owner = Owner { address };
-            let amount =
-                BigInt::from_str(balance_change.amount.to_string().as_str()).map_err(|_| {
-                    error::Error::Internal(
-                        "Cannot convert balance change amount to BigInt amount".to_string(),
-                    )
-                })?;
-            output.push(Some(BalanceChange {
-                owner: Some(owner),
-                amount: Some(amount),
-                coin_type: Some(MoveType::new(
-                    balance_change.coin_type.to_canonical_string(true),
-                )),
-            }))
+            output.push(Some(BalanceChange::from_native(balance_change)?));
         }
         Ok(output)
     }
+
+    fn from_native(balance_change: NativeBalanceChange) -> Result<BalanceChange, error::Error> {
+        // Shared and immutable owners have no address
+        let owner = balance_change
+            .owner
+            .get_owner_address()
+            .ok()
+            .map(|address| Owner {
+                address: SuiAddress::from_array(address.to_inner()),
+            });
+        let amount =
+            BigInt::from_str(balance_change.amount.to_string().as_str()).map_err(|_| {
+                error::Error::Internal(
+                    "Cannot convert balance change amount to BigInt amount".to_string(),
+                )
+            })?;
+        Ok(BalanceChange {
+            owner,
+            amount: Some(amount),
+            coin_type: Some(MoveType::new(
+                balance_change.coin_type.to_canonical_string(true),
+            )),
+        })
+    }
+}
+
+/// The version the transaction block writes objects at, which is the same for all the objects it
+/// changes, including its gas object. System transaction blocks pay no gas, so their placeholder
+/// gas object is not relied on alone.
+fn lamport_version(tx_effects: &SuiTransactionBlockEffects) -> Option<u64> {
+    tx_effects
+        .all_changed_objects()
+        .into_iter()
+        .map(|(object, _)| object.reference.version.value())
+        .chain(std::iter::once(
+            tx_effects.gas_object().reference.version.value(),
+        ))
+        .max()
 }
 
 // TODO this should be replaced together with the whole TXBLOCKEFFECTS once the indexer has this stuff implemented
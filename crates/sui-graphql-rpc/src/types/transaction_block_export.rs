@@ -0,0 +1,37 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use async_graphql::*;
+
+use super::{base64::Base64, big_int::BigInt, date_time::DateTime, digest::Digest};
+
+/// A batch of transaction blocks exported in the order they were sequenced, intended for bulk
+/// (ETL) clients that want raw data rather than the fully resolved `TransactionBlock` type.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct TransactionBlockExportBatch {
+    /// The transaction blocks in this batch, in ascending order of sequence number
+    pub transactions: Vec<ExportedTransactionBlock>,
+    /// Cursor to resume the export from, as the `after` argument of the next request. Cursors are
+    /// strictly increasing across successive non-empty batches of the same export. When the batch
+    /// is empty, this is the cursor the request was made with (or null if there was none).
+    pub end_cursor: Option<String>,
+    /// Whether there are more transaction blocks in the requested checkpoint range after this
+    /// batch
+    pub has_next_page: bool,
+}
+
+/// A transaction block in its raw, BCS-encoded form.
+#[derive(Clone, Debug, PartialEq, Eq, SimpleObject)]
+pub(crate) struct ExportedTransactionBlock {
+    /// Position of this transaction block in the global order of transactions executed by the
+    /// network
+    pub sequence_number: BigInt,
+    pub digest: Digest,
+    /// The checkpoint this transaction block was included in
+    pub checkpoint: u64,
+    pub timestamp: Option<DateTime>,
+    /// BCS-encoded `SenderSignedData` of the transaction block
+    pub transaction_bcs: Base64,
+    /// BCS-encoded `TransactionEffects` of the transaction block
+    pub effects_bcs: Base64,
+}
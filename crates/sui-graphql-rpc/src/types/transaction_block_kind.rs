@@ -2,8 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::{big_int::BigInt, date_time::DateTime, epoch::Epoch, sui_address::SuiAddress};
-use crate::context_data::db_data_provider::PgManager;
-use async_graphql::{ComplexObject, Context, Result, ResultExt, SimpleObject, Union};
+use crate::context_data::data_loader::load_epoch_strict;
+use async_graphql::{ComplexObject, Context, Result, SimpleObject, Union};
 
 #[derive(Union, PartialEq, Clone, Eq)]
 pub(crate) enum TransactionBlockKind {
@@ -66,11 +66,7 @@ pub(crate) struct ChangeEpochTransaction {
 #[ComplexObject]
 impl ConsensusCommitPrologueTransaction {
     async fn epoch(&self, ctx: &Context<'_>) -> Result<Option<Epoch>> {
-        let epoch = ctx
-            .data_unchecked::<PgManager>()
-            .fetch_epoch_strict(self.epoch_id)
-            .await
-            .extend()?;
+        let epoch = load_epoch_strict(ctx, self.epoch_id).await?;
 
         Ok(Some(epoch))
     }
@@ -79,11 +75,7 @@ impl ConsensusCommitPrologueTransaction {
 #[ComplexObject]
 impl ChangeEpochTransaction {
     async fn epoch(&self, ctx: &Context<'_>) -> Result<Option<Epoch>> {
-        let epoch = ctx
-            .data_unchecked::<PgManager>()
-            .fetch_epoch_strict(self.epoch_id)
-            .await
-            .extend()?;
+        let epoch = load_epoch_strict(ctx, self.epoch_id).await?;
 
         Ok(Some(epoch))
     }
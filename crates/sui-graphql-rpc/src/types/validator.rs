@@ -1,7 +1,7 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::context_data::db_data_provider::PgManager;
+use crate::context_data::{data_loader::load_latest_move_obj, db_data_provider::PgManager};
 
 use super::address::Address;
 use super::big_int::BigInt;
@@ -43,28 +43,26 @@ pub(crate) struct Validator {
     pub next_epoch_commission_rate: Option<u64>,
     pub at_risk: Option<u64>, // only available on sui_system_state_summary
     pub report_records: Option<Vec<SuiAddress>>, // only available on sui_system_state_summary
-                              // pub apy: Option<u64>, // TODO: Defer for StakedSui implementation
 }
 
 #[ComplexObject]
 impl Validator {
     async fn operation_cap(&self, ctx: &Context<'_>) -> Result<Option<MoveObject>> {
-        ctx.data_unchecked::<PgManager>()
-            .fetch_move_obj(self.operation_cap_id, None)
-            .await
-            .extend()
+        load_latest_move_obj(ctx, self.operation_cap_id).await
     }
 
     async fn staking_pool(&self, ctx: &Context<'_>) -> Result<Option<MoveObject>> {
-        ctx.data_unchecked::<PgManager>()
-            .fetch_move_obj(self.operation_cap_id, None)
-            .await
-            .extend()
+        load_latest_move_obj(ctx, self.operation_cap_id).await
     }
 
     async fn exchange_rates(&self, ctx: &Context<'_>) -> Result<Option<MoveObject>> {
+        load_latest_move_obj(ctx, self.operation_cap_id).await
+    }
+
+    /// The APY of the staking pool of this validator, as of the latest epoch
+    async fn apy(&self, ctx: &Context<'_>) -> Result<Option<f64>> {
         ctx.data_unchecked::<PgManager>()
-            .fetch_move_obj(self.operation_cap_id, None)
+            .fetch_validator_apy(self.address.address)
             .await
             .extend()
     }
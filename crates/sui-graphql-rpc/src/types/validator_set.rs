@@ -3,10 +3,14 @@
 
 use super::big_int::BigInt;
 use super::validator::Validator;
-use async_graphql::*;
+use crate::context_data::db_data_provider::{
+    page_of_indexed, validate_cursor_pagination, PgManager,
+};
+use async_graphql::{connection::Connection, *};
 
 /// Representation of `0x3::validator_set::ValidatorSet`.
 #[derive(Clone, Debug, PartialEq, Eq, SimpleObject, Default)]
+#[graphql(complex)]
 pub(crate) struct ValidatorSet {
     /// Total amount of stake for all active validators at the beginning of the epoch.
     pub total_stake: Option<BigInt>,
@@ -27,3 +31,30 @@ pub(crate) struct ValidatorSet {
     // validator_candidates: Option<MoveObject>,
     pub validator_candidates_size: Option<u64>,
 }
+
+#[ComplexObject]
+impl ValidatorSet {
+    /// The active validators, paginated in the order of `activeValidators`. Cursors are the
+    /// validators' indices in that list.
+    async fn active_validator_connection(
+        &self,
+        ctx: &Context<'_>,
+        first: Option<u64>,
+        after: Option<String>,
+        last: Option<u64>,
+        before: Option<String>,
+    ) -> Result<Option<Connection<String, Validator>>> {
+        validate_cursor_pagination(&first, &after, &last, &before).extend()?;
+        ctx.data_unchecked::<PgManager>()
+            .validate_page_size(&first, &last)
+            .extend()?;
+        let Some(validators) = &self.active_validators else {
+            return Ok(None);
+        };
+
+        let validators = validators.iter().cloned().enumerate();
+        page_of_indexed(validators, first, after, last, before)
+            .map(Some)
+            .extend()
+    }
+}
@@ -6,9 +6,11 @@ mod tests {
     use diesel::OptionalExtension;
     use diesel::RunQueryDsl;
     use diesel::{ExpressionMethods, QueryDsl};
+    use fastcrypto::traits::KeyPair;
     use rand::rngs::StdRng;
     use rand::SeedableRng;
     use serial_test::serial;
+    use shared_crypto::intent::Intent;
     use simulacrum::Simulacrum;
     use std::sync::Arc;
     use std::time::Duration;
@@ -20,7 +22,11 @@ mod tests {
     use sui_indexer::schema_v2::objects;
     use sui_indexer::utils::reset_database;
     use sui_indexer::PgConnectionPoolConfig;
+    use sui_types::crypto::{get_key_pair_from_rng, AccountKeyPair};
     use sui_types::digests::ChainIdentifier;
+    use sui_types::gas_coin::MIST_PER_SUI;
+    use sui_types::programmable_transaction_builder::ProgrammableTransactionBuilder;
+    use sui_types::transaction::{GasData, Transaction, TransactionData, TransactionKind};
     use tokio::time::sleep;
 
     #[tokio::test]
@@ -166,6 +172,91 @@ mod tests {
         assert_eq!(*usage.get("depth").unwrap(), 1);
         assert_eq!(*usage.get("variables").unwrap(), 0);
         assert_eq!(*usage.get("fragments").unwrap(), 0);
+        assert_eq!(*usage.get("cost").unwrap(), 1);
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn test_sponsored_transaction_queries() {
+        sleep(Duration::from_secs(5)).await;
+        let rng = StdRng::from_seed([12; 32]);
+        let mut sim = Simulacrum::new_with_rng(rng);
+
+        let (sender, sender_key): (_, AccountKeyPair) = get_key_pair_from_rng(sim.rng());
+        let (sponsor, sponsor_key) = sim
+            .keystore()
+            .accounts()
+            .map(|(address, key)| (*address, key.copy()))
+            .next()
+            .unwrap();
+        let gas = sim
+            .store()
+            .owned_objects(sponsor)
+            .find(|object| object.is_gas_coin())
+            .unwrap()
+            .compute_object_reference();
+
+        let gas_data = GasData {
+            payment: vec![gas],
+            owner: sponsor,
+            price: sim.reference_gas_price(),
+            budget: MIST_PER_SUI,
+        };
+        let kind = TransactionKind::ProgrammableTransaction(
+            ProgrammableTransactionBuilder::new().finish(),
+        );
+        let tx_data = TransactionData::new_with_gas_data(kind, sender, gas_data);
+        let tx = Transaction::from_data_and_signer(
+            tx_data,
+            Intent::sui_transaction(),
+            vec![&sender_key, &sponsor_key],
+        );
+        let digest = *tx.digest();
+        sim.execute_transaction(tx).unwrap();
+        sim.create_checkpoint();
+
+        let connection_config = ConnectionConfig::ci_integration_test_cfg();
+        let cluster =
+            sui_graphql_rpc::cluster::serve_simulator(connection_config, 3000, Arc::new(sim)).await;
+
+        // Wait for the indexer to catch up
+        sleep(Duration::from_secs(10)).await;
+
+        let query = format!(
+            r#"
+            query {{
+                gasSponsor(address: "{sponsor}") {{
+                    stats {{
+                        sponsoredTransactionCount
+                        senderCount
+                        maxGasBudget
+                    }}
+                }}
+                sponsoredTransactionStatus(digest: "{digest}") {{
+                    sponsored
+                    sponsor {{ location }}
+                    sender {{ location }}
+                }}
+                notASponsor: gasSponsor(address: "{sender}") {{
+                    stats {{ sponsoredTransactionCount }}
+                }}
+            }}
+        "#
+        );
+        let res = cluster.graphql_client.execute(query, vec![]).await.unwrap();
+
+        let data = &res["data"];
+        let stats = &data["gasSponsor"]["stats"];
+        assert_eq!(stats["sponsoredTransactionCount"], 1);
+        assert_eq!(stats["senderCount"], 1);
+        assert_eq!(stats["maxGasBudget"], MIST_PER_SUI.to_string());
+
+        let status = &data["sponsoredTransactionStatus"];
+        assert_eq!(status["sponsored"], true);
+        assert_eq!(status["sponsor"]["location"], sponsor.to_string());
+        assert_eq!(status["sender"]["location"], sender.to_string());
+
+        assert!(data["notASponsor"].is_null());
     }
 
     use sui_graphql_rpc::server::builder::tests::*;
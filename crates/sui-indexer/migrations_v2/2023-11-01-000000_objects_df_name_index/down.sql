@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS objects_df_name;
+ALTER TABLE objects DROP COLUMN IF EXISTS df_name_bcs;
+ALTER TABLE objects DROP COLUMN IF EXISTS df_name_type;
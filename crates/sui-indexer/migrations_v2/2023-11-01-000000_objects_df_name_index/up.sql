@@ -0,0 +1,8 @@
+-- Canonical type of the dynamic field name, e.g. `u64` or `0x1::string::String`.
+-- Non-null when the object is a dynamic field
+ALTER TABLE objects ADD COLUMN df_name_type text;
+-- bcs serialized value of the dynamic field name (without the type), for typed lookups.
+-- Non-null when the object is a dynamic field
+ALTER TABLE objects ADD COLUMN df_name_bcs bytea;
+
+CREATE INDEX objects_df_name ON objects (owner_id, df_name_type, df_name_bcs) WHERE df_kind IS NOT NULL;
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS tx_sponsors;
+DROP INDEX IF EXISTS tx_sponsors_tx_sequence_number_index;
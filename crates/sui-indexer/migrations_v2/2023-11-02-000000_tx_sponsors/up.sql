@@ -0,0 +1,12 @@
+-- Transactions whose gas was paid by an address other than the sender.
+CREATE TABLE tx_sponsors (
+    tx_sequence_number          BIGINT       NOT NULL,
+    -- SuiAddress in bytes of the gas owner.
+    sponsor                     BYTEA        NOT NULL,
+    -- SuiAddress in bytes of the sender.
+    sender                      BYTEA        NOT NULL,
+    gas_budget                  BIGINT       NOT NULL,
+    gas_price                   BIGINT       NOT NULL,
+    PRIMARY KEY(sponsor, tx_sequence_number)
+);
+CREATE INDEX tx_sponsors_tx_sequence_number_index ON tx_sponsors (tx_sequence_number ASC);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS tx_balance_changes;
+DROP INDEX IF EXISTS tx_balance_changes_owner_coin_type_checkpoint_index;
@@ -0,0 +1,16 @@
+-- The changes to the balances of address owners made by each transaction.
+CREATE TABLE tx_balance_changes (
+    tx_sequence_number          BIGINT       NOT NULL,
+    checkpoint_sequence_number  BIGINT       NOT NULL,
+    -- SuiAddress in bytes of the owner of the balance.
+    owner                       BYTEA        NOT NULL,
+    -- Canonical string of the coin type, with the 0x prefix.
+    coin_type                   TEXT         NOT NULL,
+    -- The amount the balance changed by (negative when it decreased) is
+    -- amount_high * 2^32 + amount_low, where 0 <= amount_low < 2^32, so that it is not limited to
+    -- the range of a BIGINT, and sums of both parts over many changes still fit in one.
+    amount_high                 BIGINT       NOT NULL,
+    amount_low                  BIGINT       NOT NULL,
+    PRIMARY KEY(owner, coin_type, tx_sequence_number)
+);
+CREATE INDEX tx_balance_changes_owner_coin_type_checkpoint_index ON tx_balance_changes (owner, coin_type, checkpoint_sequence_number);
@@ -0,0 +1,3 @@
+-- This file should undo anything in `up.sql`
+DROP INDEX IF EXISTS objects_history_checkpoint_sequence_number;
+DROP TABLE IF EXISTS objects_history;
@@ -0,0 +1,47 @@
+-- The version of every object at the end of each checkpoint that changed it, including the
+-- versions that have since been mutated, and the versions at which objects were deleted or
+-- wrapped. Columns mirror `objects`, see 2023-08-19-044023_objects and
+-- 2023-11-01-000000_objects_df_name_index.
+CREATE TABLE objects_history (
+    object_id                   bytea         NOT NULL,
+    object_version              bigint        NOT NULL,
+    -- 0 if the object exists at this version, 1 if it was deleted or wrapped at this version, in
+    -- which case the row is a tombstone and only holds the object's id, version, digest and
+    -- checkpoint.
+    object_status               smallint      NOT NULL,
+    object_digest               bytea         NOT NULL,
+    -- The checkpoint that this version of the object was written in.
+    checkpoint_sequence_number  bigint        NOT NULL,
+    owner_type                  smallint,
+    owner_id                    bytea,
+    object_type                 text,
+    serialized_object           bytea,
+    coin_type                   text,
+    coin_balance                bigint,
+    df_kind                     smallint,
+    df_name                     bytea,
+    df_object_type              text,
+    df_object_id                bytea,
+    df_name_type                text,
+    df_name_bcs                 bytea,
+    PRIMARY KEY(object_id, object_version)
+);
+CREATE INDEX objects_history_checkpoint_sequence_number ON objects_history (object_id, checkpoint_sequence_number);
+-- Connections over objects read pages after the first as of the checkpoint of the first page, with
+-- the same filters as on `objects`.
+CREATE INDEX objects_history_owner ON objects_history (owner_type, owner_id) WHERE owner_type BETWEEN 1 AND 2 AND owner_id IS NOT NULL;
+CREATE INDEX objects_history_coin ON objects_history (owner_id, coin_type) WHERE coin_type IS NOT NULL AND owner_type = 1;
+CREATE INDEX objects_history_type ON objects_history (object_type);
+
+-- Backfill the versions of the objects that already exist, which were indexed before the history
+-- was kept.
+INSERT INTO objects_history (
+    object_id, object_version, object_status, object_digest, checkpoint_sequence_number,
+    owner_type, owner_id, object_type, serialized_object, coin_type, coin_balance, df_kind,
+    df_name, df_object_type, df_object_id, df_name_type, df_name_bcs
+)
+SELECT
+    object_id, object_version, 0, object_digest, checkpoint_sequence_number,
+    owner_type, owner_id, object_type, serialized_object, coin_type, coin_balance, df_kind,
+    df_name, df_object_type, df_object_id, df_name_type, df_name_bcs
+FROM objects;
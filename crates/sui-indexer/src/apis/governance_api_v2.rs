@@ -165,6 +165,28 @@ impl GovernanceReadApiV2 {
         }
         Ok(delegated_stakes)
     }
+
+    pub async fn get_validators_apy(&self) -> Result<ValidatorApys, IndexerError> {
+        let system_state_summary = self.get_latest_sui_system_state().await?;
+        let epoch = system_state_summary.epoch;
+        let stake_subsidy_start_epoch = system_state_summary.stake_subsidy_start_epoch;
+
+        let exchange_rate_table = exchange_rates(self, system_state_summary).await?;
+
+        let apys = sui_json_rpc::governance_api::calculate_apys(
+            stake_subsidy_start_epoch,
+            exchange_rate_table,
+        );
+
+        Ok(ValidatorApys { apys, epoch })
+    }
+
+    /// The exchange rates of the staking pools of the active and inactive validators, as of the
+    /// latest epoch. The rates of each pool are in descending order by epoch.
+    pub async fn get_exchange_rates(&self) -> Result<Vec<ValidatorExchangeRates>, IndexerError> {
+        let system_state_summary = self.get_latest_sui_system_state().await?;
+        exchange_rates(self, system_state_summary).await
+    }
 }
 
 /// Cached exchange rates for validators for the given epoch, the cache size is 1, it will be cleared when the epoch changes.
@@ -297,19 +319,7 @@ impl GovernanceReadApiServer for GovernanceReadApiV2 {
     }
 
     async fn get_validators_apy(&self) -> RpcResult<ValidatorApys> {
-        let system_state_summary: SuiSystemStateSummary =
-            self.get_latest_sui_system_state().await?;
-        let epoch = system_state_summary.epoch;
-        let stake_subsidy_start_epoch = system_state_summary.stake_subsidy_start_epoch;
-
-        let exchange_rate_table = exchange_rates(self, system_state_summary).await?;
-
-        let apys = sui_json_rpc::governance_api::calculate_apys(
-            stake_subsidy_start_epoch,
-            exchange_rate_table,
-        );
-
-        Ok(ValidatorApys { apys, epoch })
+        self.get_validators_apy().await.map_err(Into::into)
     }
 }
 
@@ -4,6 +4,7 @@
 use crate::handlers::committer::start_tx_checkpoint_commit_task;
 use crate::handlers::tx_processor::IndexingPackageCache;
 use crate::models_v2::display::StoredDisplay;
+use crate::models_v2::tx_indices::split_balance_change;
 use async_trait::async_trait;
 use itertools::Itertools;
 use move_bytecode_utils::module_cache::GetModule;
@@ -435,6 +436,30 @@ where
                     .get_changes(tx, &fx, &tx_digest)
                     .await?;
 
+            // Balance changes of address owners, which are stored split into two 64-bit integers
+            let balance_changes = balance_change
+                .iter()
+                .filter_map(|change| match change.owner {
+                    Owner::AddressOwner(address) => Some((address, change)),
+                    _ => None,
+                })
+                .map(|(address, change)| {
+                    let (amount_high, amount_low) = split_balance_change(change.amount)
+                        .ok_or_else(|| {
+                            IndexerError::DataTransformationError(format!(
+                                "Balance change {} of {} for {} in transaction {} is out of range",
+                                change.amount, change.coin_type, address, tx_digest
+                            ))
+                        })?;
+                    Ok((
+                        address,
+                        change.coin_type.to_canonical_string(/* with_prefix */ true),
+                        amount_high,
+                        amount_low,
+                    ))
+                })
+                .collect::<Result<Vec<_>, IndexerError>>()?;
+
             let db_txn = IndexedTransaction {
                 tx_sequence_number,
                 tx_digest,
@@ -504,6 +529,9 @@ where
                 payers,
                 recipients,
                 move_calls,
+                gas_budget: tx.gas_budget(),
+                gas_price: tx.gas_price(),
+                balance_changes,
             });
         }
         Ok((db_transactions, db_events, db_indices, db_displays))
@@ -575,6 +603,7 @@ where
             })
             .collect();
         TransactionObjectChangesToCommit {
+            checkpoint_sequence_number: checkpoint_seq,
             changed_objects,
             deleted_objects,
         }
@@ -32,6 +32,7 @@ pub struct CheckpointDataToCommit {
 
 #[derive(Debug)]
 pub struct TransactionObjectChangesToCommit {
+    pub checkpoint_sequence_number: u64,
     pub changed_objects: Vec<IndexedObject>,
     pub deleted_objects: Vec<ObjectRef>,
 }
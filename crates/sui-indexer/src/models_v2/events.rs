@@ -6,13 +6,11 @@ use std::str::FromStr;
 use diesel::prelude::*;
 use move_bytecode_utils::module_cache::GetModule;
 use move_core_types::identifier::Identifier;
-use move_core_types::value::MoveStruct;
 
-use sui_json_rpc_types::{SuiEvent, SuiMoveStruct};
+use sui_json_rpc_types::SuiEvent;
 use sui_types::base_types::{ObjectID, SuiAddress};
 use sui_types::digests::TransactionDigest;
 use sui_types::event::EventID;
-use sui_types::object::{MoveObject, ObjectFormatOptions};
 use sui_types::parse_sui_struct_tag;
 
 use crate::errors::IndexerError;
@@ -107,14 +105,7 @@ impl StoredEvent {
 
         let type_ = parse_sui_struct_tag(&self.event_type)?;
 
-        let layout = MoveObject::get_layout_from_struct_tag(
-            type_.clone(),
-            ObjectFormatOptions::default(),
-            module_cache,
-        )?;
-        let move_object = MoveStruct::simple_deserialize(&self.bcs, &layout)
-            .map_err(|e| IndexerError::SerdeError(e.to_string()))?;
-        let parsed_json = SuiMoveStruct::from(move_object).to_json_value();
+        let parsed_json = SuiEvent::parse_json(&type_, &self.bcs, module_cache)?;
         let tx_digest =
             TransactionDigest::try_from(self.transaction_digest.as_slice()).map_err(|e| {
                 IndexerError::SerdeError(format!(
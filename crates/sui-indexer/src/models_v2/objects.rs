@@ -14,7 +14,7 @@ use sui_types::object::Object;
 use sui_types::object::{ObjectFormatOptions, ObjectRead};
 
 use crate::errors::IndexerError;
-use crate::schema_v2::objects;
+use crate::schema_v2::{objects, objects_history};
 use crate::types_v2::IndexedObject;
 
 #[derive(Queryable)]
@@ -57,6 +57,42 @@ pub struct StoredObject {
     pub df_name: Option<Vec<u8>>,
     pub df_object_type: Option<String>,
     pub df_object_id: Option<Vec<u8>>,
+    /// Canonical type of the dynamic field name, used with `df_name_bcs` for typed lookups.
+    pub df_name_type: Option<String>,
+    pub df_name_bcs: Option<Vec<u8>>,
+}
+
+/// Whether a row of `objects_history` holds a version of an object, or marks that the object was
+/// deleted or wrapped at that version.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ObjectStatus {
+    Active = 0,
+    WrappedOrDeleted = 1,
+}
+
+/// A version of an object, as it was written in `checkpoint_sequence_number`. Its columns mirror
+/// `StoredObject`'s, except that the object's contents are missing from tombstones, the rows that
+/// mark the version an object was deleted or wrapped at.
+#[derive(Queryable, Insertable, Debug, Identifiable, Clone, QueryableByName)]
+#[diesel(table_name = objects_history, primary_key(object_id, object_version))]
+pub struct StoredHistoryObject {
+    pub object_id: Vec<u8>,
+    pub object_version: i64,
+    pub object_status: i16,
+    pub object_digest: Vec<u8>,
+    pub checkpoint_sequence_number: i64,
+    pub owner_type: Option<i16>,
+    pub owner_id: Option<Vec<u8>>,
+    pub object_type: Option<String>,
+    pub serialized_object: Option<Vec<u8>>,
+    pub coin_type: Option<String>,
+    pub coin_balance: Option<i64>,
+    pub df_kind: Option<i16>,
+    pub df_name: Option<Vec<u8>>,
+    pub df_object_type: Option<String>,
+    pub df_object_id: Option<Vec<u8>>,
+    pub df_name_type: Option<String>,
+    pub df_name_bcs: Option<Vec<u8>>,
 }
 
 #[derive(Queryable, Insertable, Debug, Identifiable, Clone, QueryableByName)]
@@ -88,8 +124,103 @@ impl From<IndexedObject> for StoredObject {
             df_name: o.df_info.as_ref().map(|n| bcs::to_bytes(&n.name).unwrap()),
             df_object_type: o.df_info.as_ref().map(|v| v.object_type.clone()),
             df_object_id: o.df_info.as_ref().map(|v| v.object_id.to_vec()),
+            df_name_type: o
+                .df_info
+                .as_ref()
+                .map(|n| n.name.type_.to_canonical_string(/* with_prefix */ true)),
+            df_name_bcs: o.df_info.as_ref().map(|n| n.bcs_name.clone()),
+        }
+    }
+}
+
+impl From<StoredObject> for StoredHistoryObject {
+    fn from(o: StoredObject) -> Self {
+        Self {
+            object_id: o.object_id,
+            object_version: o.object_version,
+            object_status: ObjectStatus::Active as i16,
+            object_digest: o.object_digest,
+            checkpoint_sequence_number: o.checkpoint_sequence_number,
+            owner_type: Some(o.owner_type),
+            owner_id: o.owner_id,
+            object_type: o.object_type,
+            serialized_object: Some(o.serialized_object),
+            coin_type: o.coin_type,
+            coin_balance: o.coin_balance,
+            df_kind: o.df_kind,
+            df_name: o.df_name,
+            df_object_type: o.df_object_type,
+            df_object_id: o.df_object_id,
+            df_name_type: o.df_name_type,
+            df_name_bcs: o.df_name_bcs,
+        }
+    }
+}
+
+impl StoredHistoryObject {
+    /// The tombstone of an object that was deleted or wrapped at the version in `oref`, in
+    /// `checkpoint_sequence_number`.
+    pub fn tombstone(oref: ObjectRef, checkpoint_sequence_number: u64) -> Self {
+        let (object_id, object_version, object_digest) = oref;
+        Self {
+            object_id: object_id.to_vec(),
+            object_version: object_version.value() as i64,
+            object_status: ObjectStatus::WrappedOrDeleted as i16,
+            object_digest: object_digest.into_inner().to_vec(),
+            checkpoint_sequence_number: checkpoint_sequence_number as i64,
+            owner_type: None,
+            owner_id: None,
+            object_type: None,
+            serialized_object: None,
+            coin_type: None,
+            coin_balance: None,
+            df_kind: None,
+            df_name: None,
+            df_object_type: None,
+            df_object_id: None,
+            df_name_type: None,
+            df_name_bcs: None,
         }
     }
+
+    /// Whether this row marks that the object was deleted or wrapped at its version.
+    pub fn is_tombstone(&self) -> bool {
+        self.object_status == ObjectStatus::WrappedOrDeleted as i16
+    }
+}
+
+impl TryFrom<StoredHistoryObject> for StoredObject {
+    type Error = IndexerError;
+
+    fn try_from(o: StoredHistoryObject) -> Result<Self, Self::Error> {
+        let (false, Some(owner_type), Some(serialized_object)) =
+            (o.is_tombstone(), o.owner_type, o.serialized_object)
+        else {
+            return Err(IndexerError::PersistentStorageDataCorruptionError(format!(
+                "Object {:?} has no contents at version {}",
+                o.object_id, o.object_version
+            )));
+        };
+
+        Ok(Self {
+            object_id: o.object_id,
+            object_version: o.object_version,
+            object_digest: o.object_digest,
+            checkpoint_sequence_number: o.checkpoint_sequence_number,
+            owner_type,
+            owner_id: o.owner_id,
+            object_type: o.object_type,
+            serialized_object,
+            coin_type: o.coin_type,
+            coin_balance: o.coin_balance,
+            df_kind: o.df_kind,
+            df_name: o.df_name,
+            df_object_type: o.df_object_type,
+            df_object_id: o.df_object_id,
+            df_name_type: o.df_name_type,
+            df_name_bcs: o.df_name_bcs,
+        })
+    }
 }
 
 impl TryFrom<StoredObject> for Object {
@@ -393,4 +524,30 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_history_object_round_trip() {
+        let indexed_obj = IndexedObject::from_object(1, Object::new_gas_for_testing(), None);
+        let stored_obj = StoredObject::from(indexed_obj);
+
+        let history_obj = StoredHistoryObject::from(stored_obj.clone());
+        assert!(!history_obj.is_tombstone());
+
+        let round_trip = StoredObject::try_from(history_obj).unwrap();
+        assert_eq!(round_trip.object_id, stored_obj.object_id);
+        assert_eq!(round_trip.object_version, stored_obj.object_version);
+        assert_eq!(round_trip.serialized_object, stored_obj.serialized_object);
+    }
+
+    #[test]
+    fn test_history_tombstone() {
+        let oref = Object::new_gas_for_testing().compute_object_reference();
+        let tombstone = StoredHistoryObject::tombstone(oref, 7);
+
+        assert!(tombstone.is_tombstone());
+        assert_eq!(tombstone.object_id, oref.0.to_vec());
+        assert_eq!(tombstone.object_version, oref.1.value() as i64);
+        assert_eq!(tombstone.checkpoint_sequence_number, 7);
+        assert!(StoredObject::try_from(tombstone).is_err());
+    }
 }
@@ -2,7 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    schema_v2::{tx_calls, tx_changed_objects, tx_input_objects, tx_recipients, tx_senders},
+    schema_v2::{
+        tx_balance_changes, tx_calls, tx_changed_objects, tx_input_objects, tx_recipients,
+        tx_senders, tx_sponsors,
+    },
     types_v2::TxIndex,
 };
 use diesel::prelude::*;
@@ -40,6 +43,40 @@ pub struct StoredTxSenders {
     pub sender: Vec<u8>,
 }
 
+/// A transaction whose gas was paid by an address other than its sender.
+#[derive(Queryable, Insertable, Debug, Clone, Default)]
+#[diesel(table_name = tx_sponsors)]
+pub struct StoredTxSponsor {
+    pub tx_sequence_number: i64,
+    pub sponsor: Vec<u8>,
+    pub sender: Vec<u8>,
+    pub gas_budget: i64,
+    pub gas_price: i64,
+}
+
+/// The change to the balance of a coin type of an address made by a transaction. The amount is
+/// `amount_high * 2^32 + amount_low` (see [split_balance_change]).
+#[derive(Queryable, Insertable, Debug, Clone, Default)]
+#[diesel(table_name = tx_balance_changes)]
+pub struct StoredTxBalanceChange {
+    pub tx_sequence_number: i64,
+    pub checkpoint_sequence_number: i64,
+    pub owner: Vec<u8>,
+    pub coin_type: String,
+    pub amount_high: i64,
+    pub amount_low: i64,
+}
+
+/// Splits a balance change into a high part and a low part in `[0, 2^32)`, such that
+/// `amount == high * 2^32 + low`, or `None` if the high part does not fit in an `i64`. Balance
+/// changes are bounded by the supply of a coin, which fits in a `u64`, so in practice every change
+/// can be split.
+pub fn split_balance_change(amount: i128) -> Option<(i64, i64)> {
+    let high = i64::try_from(amount >> 32).ok()?;
+    let low = (amount & 0xFFFF_FFFF) as i64;
+    Some((high, low))
+}
+
 #[derive(Queryable, Insertable, Debug, Clone, Default)]
 #[diesel(table_name = tx_recipients)]
 pub struct StoredTxRecipients {
@@ -66,6 +103,8 @@ impl TxIndex {
         Vec<StoredTxInputObject>,
         Vec<StoredTxChangedObject>,
         Vec<StoredTxCalls>,
+        Vec<StoredTxSponsor>,
+        Vec<StoredTxBalanceChange>,
     ) {
         let tx_sequence_number = self.tx_sequence_number as i64;
         let tx_senders = self
@@ -110,12 +149,108 @@ impl TxIndex {
                 func: f.to_string(),
             })
             .collect();
+        // Any payer that did not also send the transaction sponsored it.
+        let tx_sponsors = self
+            .payers
+            .iter()
+            .filter(|p| !self.senders.contains(p))
+            .flat_map(|p| {
+                self.senders.iter().map(|s| StoredTxSponsor {
+                    tx_sequence_number,
+                    sponsor: p.to_vec(),
+                    sender: s.to_vec(),
+                    gas_budget: self.gas_budget as i64,
+                    gas_price: self.gas_price as i64,
+                })
+            })
+            .collect();
+        let tx_balance_changes = self
+            .balance_changes
+            .iter()
+            .map(
+                |(owner, coin_type, amount_high, amount_low)| StoredTxBalanceChange {
+                    tx_sequence_number,
+                    checkpoint_sequence_number: self.checkpoint_sequence_number as i64,
+                    owner: owner.to_vec(),
+                    coin_type: coin_type.clone(),
+                    amount_high: *amount_high,
+                    amount_low: *amount_low,
+                },
+            )
+            .collect();
         (
             tx_senders,
             tx_recipients,
             tx_input_objects,
             tx_changed_objects,
             tx_calls,
+            tx_sponsors,
+            tx_balance_changes,
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sui_types::base_types::{SuiAddress, TransactionDigest};
+
+    fn tx_index(senders: Vec<SuiAddress>, payers: Vec<SuiAddress>) -> TxIndex {
+        TxIndex {
+            tx_sequence_number: 7,
+            transaction_digest: TransactionDigest::default(),
+            checkpoint_sequence_number: 3,
+            input_objects: vec![],
+            changed_objects: vec![],
+            payers,
+            senders,
+            recipients: vec![],
+            move_calls: vec![],
+            gas_budget: 5_000_000,
+            gas_price: 1_000,
+            balance_changes: vec![],
+        }
+    }
+
+    #[test]
+    fn test_split_sponsored_transaction() {
+        let sender = SuiAddress::random_for_testing_only();
+        let sponsor = SuiAddress::random_for_testing_only();
+
+        let (.., tx_sponsors, _) = tx_index(vec![sender], vec![sponsor]).split();
+        assert_eq!(tx_sponsors.len(), 1);
+        let tx_sponsor = &tx_sponsors[0];
+        assert_eq!(tx_sponsor.tx_sequence_number, 7);
+        assert_eq!(tx_sponsor.sponsor, sponsor.to_vec());
+        assert_eq!(tx_sponsor.sender, sender.to_vec());
+        assert_eq!(tx_sponsor.gas_budget, 5_000_000);
+        assert_eq!(tx_sponsor.gas_price, 1_000);
+
+        // A sender paying for its own gas is not a sponsor.
+        let (.., tx_sponsors, _) = tx_index(vec![sender], vec![sender]).split();
+        assert!(tx_sponsors.is_empty());
+    }
+
+    #[test]
+    fn test_split_balance_change() {
+        let join = |(high, low): (i64, i64)| ((high as i128) << 32) + low as i128;
+        for amount in [
+            0,
+            1,
+            -1,
+            u32::MAX as i128,
+            -(u32::MAX as i128),
+            i64::MAX as i128,
+            i64::MIN as i128,
+            u64::MAX as i128,
+            -(u64::MAX as i128),
+        ] {
+            let (high, low) = split_balance_change(amount).unwrap();
+            assert!((0..1 << 32).contains(&low));
+            assert_eq!(join((high, low)), amount);
+        }
+
+        assert!(split_balance_change(i128::MAX).is_none());
+        assert!(split_balance_change(i128::MIN).is_none());
+    }
+}
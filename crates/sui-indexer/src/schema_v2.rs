@@ -158,6 +158,30 @@ diesel::table! {
         df_name -> Nullable<Bytea>,
         df_object_type -> Nullable<Text>,
         df_object_id -> Nullable<Bytea>,
+        df_name_type -> Nullable<Text>,
+        df_name_bcs -> Nullable<Bytea>,
+    }
+}
+
+diesel::table! {
+    objects_history (object_id, object_version) {
+        object_id -> Bytea,
+        object_version -> Int8,
+        object_status -> Int2,
+        object_digest -> Bytea,
+        checkpoint_sequence_number -> Int8,
+        owner_type -> Nullable<Int2>,
+        owner_id -> Nullable<Bytea>,
+        object_type -> Nullable<Text>,
+        serialized_object -> Nullable<Bytea>,
+        coin_type -> Nullable<Text>,
+        coin_balance -> Nullable<Int8>,
+        df_kind -> Nullable<Int2>,
+        df_name -> Nullable<Bytea>,
+        df_object_type -> Nullable<Text>,
+        df_object_id -> Nullable<Bytea>,
+        df_name_type -> Nullable<Text>,
+        df_name_bcs -> Nullable<Bytea>,
     }
 }
 
@@ -235,6 +259,27 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    tx_balance_changes (owner, coin_type, tx_sequence_number) {
+        tx_sequence_number -> Int8,
+        checkpoint_sequence_number -> Int8,
+        owner -> Bytea,
+        coin_type -> Text,
+        amount_high -> Int8,
+        amount_low -> Int8,
+    }
+}
+
+diesel::table! {
+    tx_sponsors (sponsor, tx_sequence_number) {
+        tx_sequence_number -> Int8,
+        sponsor -> Bytea,
+        sender -> Bytea,
+        gas_budget -> Int8,
+        gas_price -> Int8,
+    }
+}
+
 diesel::table! {
     tx_indices (tx_sequence_number) {
         tx_sequence_number -> Int8,
@@ -263,6 +308,7 @@ diesel::allow_tables_to_appear_in_same_query!(
     move_calls,
     network_metrics,
     objects,
+    objects_history,
     packages,
     transactions,
     tx_calls,
@@ -271,6 +317,8 @@ diesel::allow_tables_to_appear_in_same_query!(
     tx_input_objects,
     tx_recipients,
     tx_senders,
+    tx_sponsors,
+    tx_balance_changes,
     tx_indices,
 );
 
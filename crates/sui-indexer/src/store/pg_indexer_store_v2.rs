@@ -33,12 +33,13 @@ use crate::models_v2::checkpoints::StoredCheckpoint;
 use crate::models_v2::display::StoredDisplay;
 use crate::models_v2::epoch::StoredEpochInfo;
 use crate::models_v2::events::StoredEvent;
-use crate::models_v2::objects::StoredObject;
+use crate::models_v2::objects::{StoredHistoryObject, StoredObject};
 use crate::models_v2::packages::StoredPackage;
 use crate::models_v2::transactions::StoredTransaction;
 use crate::schema_v2::{
-    checkpoints, display, epochs, events, objects, packages, transactions, tx_calls,
-    tx_changed_objects, tx_input_objects, tx_recipients, tx_senders,
+    checkpoints, display, epochs, events, objects, objects_history, packages, transactions,
+    tx_balance_changes, tx_calls, tx_changed_objects, tx_input_objects, tx_recipients, tx_senders,
+    tx_sponsors,
 };
 use crate::store::diesel_macro::{read_only_blocking, transactional_blocking_with_retry};
 use crate::store::module_resolver_v2::IndexerStoreModuleResolver;
@@ -166,6 +167,37 @@ impl PgIndexerStoreV2 {
         Ok(())
     }
 
+    fn persist_object_history_chunk(
+        &self,
+        objects: Vec<StoredHistoryObject>,
+    ) -> Result<(), IndexerError> {
+        let guard = self
+            .metrics
+            .checkpoint_db_commit_latency_objects_chunks
+            .start_timer();
+        let len = objects.len();
+
+        transactional_blocking_with_retry!(
+            &self.blocking_cp,
+            |conn| {
+                for history_chunk in objects.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                    diesel::insert_into(objects_history::table)
+                        .values(history_chunk)
+                        .on_conflict_do_nothing()
+                        .execute(conn)
+                        .map_err(IndexerError::from)
+                        .context("Failed to write object history to PostgresDB")?;
+                }
+                Ok::<(), IndexerError>(())
+            },
+            Duration::from_secs(60)
+        )
+        .tap(|_| {
+            let elapsed = guard.stop_and_record();
+            info!(elapsed, "Persisted {} object history rows", len);
+        })
+    }
+
     fn persist_objects_chunk(
         &self,
         objects: Vec<ObjectChangeToCommit>,
@@ -215,6 +247,8 @@ impl PgIndexerStoreV2 {
                             objects::df_name.eq(excluded(objects::df_name)),
                             objects::df_object_type.eq(excluded(objects::df_object_type)),
                             objects::df_object_id.eq(excluded(objects::df_object_id)),
+                            objects::df_name_type.eq(excluded(objects::df_name_type)),
+                            objects::df_name_bcs.eq(excluded(objects::df_name_bcs)),
                         ))
                         .execute(conn)
                         .map_err(IndexerError::from)
@@ -407,15 +441,25 @@ impl PgIndexerStoreV2 {
             .checkpoint_db_commit_latency_tx_indices_chunks
             .start_timer();
         let len = indices.len();
-        let (senders, recipients, input_objects, changed_objects, calls) =
+        let (senders, recipients, input_objects, changed_objects, calls, sponsors, balance_changes) =
             indices.into_iter().map(|i| i.split()).fold(
-                (Vec::new(), Vec::new(), Vec::new(), Vec::new(), Vec::new()),
+                (
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                    Vec::new(),
+                ),
                 |(
                     mut tx_senders,
                     mut tx_recipients,
                     mut tx_input_objects,
                     mut tx_changed_objects,
                     mut tx_calls,
+                    mut tx_sponsors,
+                    mut tx_balance_changes,
                 ),
                  index| {
                     tx_senders.extend(index.0);
@@ -423,6 +467,8 @@ impl PgIndexerStoreV2 {
                     tx_input_objects.extend(index.2);
                     tx_changed_objects.extend(index.3);
                     tx_calls.extend(index.4);
+                    tx_sponsors.extend(index.5);
+                    tx_balance_changes.extend(index.6);
 
                     (
                         tx_senders,
@@ -430,6 +476,8 @@ impl PgIndexerStoreV2 {
                         tx_input_objects,
                         tx_changed_objects,
                         tx_calls,
+                        tx_sponsors,
+                        tx_balance_changes,
                     )
                 },
             );
@@ -548,6 +596,58 @@ impl PgIndexerStoreV2 {
                 info!(elapsed, "Persisted {} rows to tx_calls tables", calls_len);
             })
         }));
+        futures.push(self.spawn_blocking_task(move |this| {
+            let now = Instant::now();
+            let sponsors_len = sponsors.len();
+            transactional_blocking_with_retry!(
+                &this.blocking_cp,
+                |conn| {
+                    for chunk in sponsors.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                        diesel::insert_into(tx_sponsors::table)
+                            .values(chunk)
+                            .on_conflict_do_nothing()
+                            .execute(conn)
+                            .map_err(IndexerError::from)
+                            .context("Failed to write tx_sponsors chunk to PostgresDB")?;
+                    }
+                    Ok::<(), IndexerError>(())
+                },
+                Duration::from_secs(60)
+            )
+            .tap(|_| {
+                let elapsed = now.elapsed().as_secs_f64();
+                info!(
+                    elapsed,
+                    "Persisted {} rows to tx_sponsors table", sponsors_len
+                );
+            })
+        }));
+        futures.push(self.spawn_blocking_task(move |this| {
+            let now = Instant::now();
+            let balance_changes_len = balance_changes.len();
+            transactional_blocking_with_retry!(
+                &this.blocking_cp,
+                |conn| {
+                    for chunk in balance_changes.chunks(PG_COMMIT_CHUNK_SIZE_INTRA_DB_TX) {
+                        diesel::insert_into(tx_balance_changes::table)
+                            .values(chunk)
+                            .on_conflict_do_nothing()
+                            .execute(conn)
+                            .map_err(IndexerError::from)
+                            .context("Failed to write tx_balance_changes chunk to PostgresDB")?;
+                    }
+                    Ok::<(), IndexerError>(())
+                },
+                Duration::from_secs(60)
+            )
+            .tap(|_| {
+                let elapsed = now.elapsed().as_secs_f64();
+                info!(
+                    elapsed,
+                    "Persisted {} rows to tx_balance_changes table", balance_changes_len
+                );
+            })
+        }));
         futures::future::join_all(futures)
             .await
             .into_iter()
@@ -720,12 +820,19 @@ impl IndexerStoreV2 for PgIndexerStoreV2 {
             .metrics
             .checkpoint_db_commit_latency_objects
             .start_timer();
+        // The history is built before the final list, which drops the versions that are
+        // overwritten within the batch, because the history keeps all of them.
+        let history = make_object_history_to_commit(&object_changes);
         let objects = make_final_list_of_objects_to_commit(object_changes);
         let len = objects.len();
         let chunks = chunk!(objects, self.parallel_objects_chunk_size);
+        let history_chunks = chunk!(history, self.parallel_objects_chunk_size);
         let futures = chunks
             .into_iter()
             .map(|c| self.spawn_blocking_task(move |this| this.persist_objects_chunk(c)))
+            .chain(history_chunks.into_iter().map(|c| {
+                self.spawn_blocking_task(move |this| this.persist_object_history_chunk(c))
+            }))
             .collect::<Vec<_>>();
 
         futures::future::join_all(futures)
@@ -886,6 +993,28 @@ impl IndexerStoreV2 for PgIndexerStoreV2 {
     }
 }
 
+/// Construct the rows of `objects_history` to commit: every object version written by the
+/// checkpoints, and a tombstone for every object they deleted or wrapped.
+fn make_object_history_to_commit(
+    tx_object_changes: &[TransactionObjectChangesToCommit],
+) -> Vec<StoredHistoryObject> {
+    tx_object_changes
+        .iter()
+        .flat_map(|changes| {
+            let mutated = changes
+                .changed_objects
+                .iter()
+                .cloned()
+                .map(StoredObject::from)
+                .map(StoredHistoryObject::from);
+            let deleted = changes.deleted_objects.iter().map(|oref| {
+                StoredHistoryObject::tombstone(*oref, changes.checkpoint_sequence_number)
+            });
+            mutated.chain(deleted)
+        })
+        .collect()
+}
+
 /// Construct deleted objects and mutated objects to commit.
 /// In particular, filter mutated objects updates that would
 /// be override immediately.
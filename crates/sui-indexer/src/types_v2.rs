@@ -309,6 +309,11 @@ pub struct TxIndex {
     pub senders: Vec<SuiAddress>,
     pub recipients: Vec<SuiAddress>,
     pub move_calls: Vec<(ObjectID, String, String)>,
+    pub gas_budget: u64,
+    pub gas_price: u64,
+    /// The balance changes of address owners, as owner, canonical coin type, and the high and low
+    /// parts of the amount (see [crate::models_v2::tx_indices::split_balance_change])
+    pub balance_changes: Vec<(SuiAddress, String, i64, i64)>,
 }
 
 // ObjectChange is not bcs deserializable, IndexedObjectChange is.
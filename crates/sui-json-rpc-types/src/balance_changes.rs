@@ -25,6 +25,16 @@ pub struct BalanceChange {
     pub amount: i128,
 }
 
+impl From<sui_types::balance_change::BalanceChange> for BalanceChange {
+    fn from(change: sui_types::balance_change::BalanceChange) -> Self {
+        Self {
+            owner: change.owner,
+            coin_type: change.coin_type,
+            amount: change.amount,
+        }
+    }
+}
+
 impl Display for BalanceChange {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         writeln!(
@@ -12,6 +12,7 @@ use serde_json::Value;
 use serde_with::serde_as;
 use serde_with::DisplayFromStr;
 use sui_types::base_types::{ObjectID, SuiAddress, TransactionDigest};
+use sui_types::effects::TransactionEvents;
 use sui_types::error::SuiResult;
 use sui_types::event::{Event, EventEnvelope, EventID};
 use sui_types::sui_serde::BigInt;
@@ -101,10 +102,7 @@ impl SuiEvent {
             contents,
         } = event;
 
-        let bcs = contents.to_vec();
-
-        let move_struct = Event::move_event_to_move_struct(&type_, &contents, resolver)?;
-        let (type_, field) = type_and_fields_from_move_struct(&type_, move_struct);
+        let parsed_json = Self::parse_json(&type_, &contents, resolver)?;
 
         Ok(SuiEvent {
             id: EventID {
@@ -115,11 +113,34 @@ impl SuiEvent {
             transaction_module,
             sender,
             type_,
-            parsed_json: field.to_json_value(),
-            bcs,
+            parsed_json,
+            bcs: contents,
             timestamp_ms,
         })
     }
+
+    /// Decodes the BCS `contents` of an event of type `type_` into the JSON served for it.
+    pub fn parse_json(
+        type_: &StructTag,
+        contents: &[u8],
+        resolver: &impl GetModule,
+    ) -> SuiResult<Value> {
+        let move_struct = Event::move_event_to_move_struct(type_, contents, resolver)?;
+        let (_, fields) = type_and_fields_from_move_struct(type_, move_struct);
+        Ok(fields.to_json_value())
+    }
+
+    /// Decodes the contents of every event emitted by a transaction into JSON, in order.
+    pub fn parse_json_for_events(
+        events: &TransactionEvents,
+        resolver: &impl GetModule,
+    ) -> SuiResult<Vec<Value>> {
+        events
+            .data
+            .iter()
+            .map(|event| Self::parse_json(&event.type_, &event.contents, resolver))
+            .collect()
+    }
 }
 
 #[serde_as]
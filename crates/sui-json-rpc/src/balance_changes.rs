@@ -5,17 +5,16 @@ use std::collections::{BTreeMap, HashMap, HashSet};
 use std::ops::Neg;
 
 use async_trait::async_trait;
-use move_core_types::language_storage::TypeTag;
 use tokio::sync::RwLock;
 
 use sui_json_rpc_types::BalanceChange;
+use sui_types::balance_change::derive_balance_changes;
 use sui_types::base_types::{ObjectID, ObjectRef, SequenceNumber};
-use sui_types::coin::Coin;
 use sui_types::digests::ObjectDigest;
 use sui_types::effects::{TransactionEffects, TransactionEffectsAPI};
 use sui_types::execution_status::ExecutionStatus;
 use sui_types::gas_coin::GAS;
-use sui_types::object::{Object, Owner};
+use sui_types::object::Object;
 use sui_types::storage::WriteKind;
 use sui_types::transaction::InputObjectKind;
 
@@ -85,68 +84,32 @@ pub async fn get_balance_changes<P: ObjectProvider<Error = E>, E>(
     modified_at_version: &[(ObjectID, SequenceNumber, Option<ObjectDigest>)],
     all_mutated: &[(ObjectID, SequenceNumber, Option<ObjectDigest>)],
 ) -> Result<Vec<BalanceChange>, E> {
-    // 1. subtract all input coins
-    let balances = fetch_coins(object_provider, modified_at_version)
-        .await?
+    let inputs = fetch_coins(object_provider, modified_at_version).await?;
+    let outputs = fetch_coins(object_provider, all_mutated).await?;
+    Ok(derive_balance_changes(&inputs, &outputs)
         .into_iter()
-        .fold(
-            BTreeMap::<_, i128>::new(),
-            |mut acc, (owner, type_, amount)| {
-                *acc.entry((owner, type_)).or_default() -= amount as i128;
-                acc
-            },
-        );
-    // 2. add all mutated coins
-    let balances = fetch_coins(object_provider, all_mutated)
-        .await?
-        .into_iter()
-        .fold(balances, |mut acc, (owner, type_, amount)| {
-            *acc.entry((owner, type_)).or_default() += amount as i128;
-            acc
-        });
-
-    Ok(balances
-        .into_iter()
-        .filter_map(|((owner, coin_type), amount)| {
-            if amount == 0 {
-                return None;
-            }
-            Some(BalanceChange {
-                owner,
-                coin_type,
-                amount,
-            })
-        })
+        .map(BalanceChange::from)
         .collect())
 }
 
 async fn fetch_coins<P: ObjectProvider<Error = E>, E>(
     object_provider: &P,
     objects: &[(ObjectID, SequenceNumber, Option<ObjectDigest>)],
-) -> Result<Vec<(Owner, TypeTag, u64)>, E> {
+) -> Result<Vec<Object>, E> {
     let mut all_mutated_coins = vec![];
     for (id, version, digest_opt) in objects {
         // TODO: use multi get object
         let o = object_provider.get_object(id, version).await?;
-        if let Some(type_) = o.type_() {
-            if type_.is_coin() {
-                if let Some(digest) = digest_opt {
-                    // TODO: can we return Err here instead?
-                    assert_eq!(
-                        *digest,
-                        o.digest(),
-                        "Object digest mismatch--got bad data from object_provider?"
-                    )
-                }
-                let [coin_type]: [TypeTag; 1] =
-                    type_.clone().into_type_params().try_into().unwrap();
-                all_mutated_coins.push((
-                    o.owner,
-                    coin_type,
-                    // we know this is a coin, safe to unwrap
-                    Coin::extract_balance_if_coin(&o).unwrap().unwrap(),
-                ))
+        if o.is_coin() {
+            if let Some(digest) = digest_opt {
+                // TODO: can we return Err here instead?
+                assert_eq!(
+                    *digest,
+                    o.digest(),
+                    "Object digest mismatch--got bad data from object_provider?"
+                )
             }
+            all_mutated_coins.push(o)
         }
     }
     Ok(all_mutated_coins)
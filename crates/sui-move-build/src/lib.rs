@@ -57,8 +57,8 @@ use sui_verifier::verifier as sui_bytecode_verifier;
 use crate::linters::{
     coin_field::CoinFieldVisitor, collection_equality::CollectionEqualityVisitor,
     custom_state_change::CustomStateChangeVerifier, freeze_wrapped::FreezeWrappedVisitor,
-    known_filters, self_transfer::SelfTransferVerifier, share_owned::ShareOwnedVerifier,
-    LINT_WARNING_PREFIX,
+    known_filters, object_lifecycle::ObjectLifecycleVerifier, self_transfer::SelfTransferVerifier,
+    share_owned::ShareOwnedVerifier, LINT_WARNING_PREFIX,
 };
 
 #[cfg(test)]
@@ -145,6 +145,7 @@ impl BuildConfig {
                     CoinFieldVisitor.visitor(),
                     FreezeWrappedVisitor.visitor(),
                     CollectionEqualityVisitor.visitor(),
+                    ObjectLifecycleVerifier.visitor(),
                 ];
                 let (filter_attr_name, filters) = known_filters();
                 compiler
@@ -8,10 +8,12 @@ pub mod coin_field;
 pub mod collection_equality;
 pub mod custom_state_change;
 pub mod freeze_wrapped;
+pub mod object_lifecycle;
 pub mod self_transfer;
 pub mod share_owned;
 
 pub const SUI_PKG_NAME: &str = "sui";
+pub const STD_PKG_NAME: &str = "std";
 
 pub const TRANSFER_MOD_NAME: &str = "transfer";
 pub const TRANSFER_FUN: &str = "transfer";
@@ -48,6 +50,9 @@ pub const VEC_MAP_STRUCT_NAME: &str = "VecMap";
 pub const VEC_SET_MOD_NAME: &str = "vec_set";
 pub const VEC_SET_STRUCT_NAME: &str = "VecSet";
 
+pub const OPTION_MOD_NAME: &str = "option";
+pub const VECTOR_MOD_NAME: &str = "vector";
+
 pub const ALLOW_ATTR_NAME: &str = "lint_allow";
 pub const LINT_WARNING_PREFIX: &str = "Lint ";
 
@@ -57,6 +62,7 @@ pub const CUSTOM_STATE_CHANGE_FILTER_NAME: &str = "custom_state_change";
 pub const COIN_FIELD_FILTER_NAME: &str = "coin_field";
 pub const FREEZE_WRAPPED_FILTER_NAME: &str = "freeze_wrapped";
 pub const COLLECTION_EQUALITY_FILTER_NAME: &str = "collection_equality";
+pub const OBJECT_LIFECYCLE_FILTER_NAME: &str = "object_lifecycle";
 
 pub const INVALID_LOC: Loc = Loc::invalid();
 
@@ -67,6 +73,7 @@ pub enum LinterDiagCategory {
     CoinField,
     FreezeWrapped,
     CollectionEquality,
+    ObjectLifecycle,
 }
 
 /// A default code for each linter category (as long as only one code per category is used, no other
@@ -114,6 +121,12 @@ pub fn known_filters() -> (E::AttributeName_, Vec<WarningFilter>) {
                 LINTER_DEFAULT_DIAG_CODE,
                 Some(COLLECTION_EQUALITY_FILTER_NAME),
             ),
+            WarningFilter::code(
+                Some(LINT_WARNING_PREFIX),
+                LinterDiagCategory::ObjectLifecycle as u8,
+                LINTER_DEFAULT_DIAG_CODE,
+                Some(OBJECT_LIFECYCLE_FILTER_NAME),
+            ),
         ],
     )
 }
@@ -0,0 +1,272 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! This analysis flags objects (instances of structs with the key ability) created in a function
+//! that end up being discarded instead of transferred, shared, frozen, or deleted. Since objects
+//! cannot be dropped, the only way to lose them is to place them in a container (an `Option` or a
+//! `vector`) and destroy that container as if it was empty, which always aborts at runtime.
+
+use move_ir_types::location::*;
+
+use move_compiler::{
+    cfgir::{
+        absint::JoinResult,
+        ast::Program,
+        visitor::{
+            default_values_for_ty, LocalState, SimpleAbsInt, SimpleAbsIntConstructor, SimpleDomain,
+            SimpleExecutionContext,
+        },
+        CFGContext,
+    },
+    diag,
+    diagnostics::{
+        codes::{custom, DiagnosticInfo, Severity},
+        Diagnostic, Diagnostics,
+    },
+    hlir::ast::{BaseType_, Exp, Label, ModuleCall, SingleType, SingleType_, Type, Var},
+    parser::ast::Ability_,
+    shared::CompilationEnv,
+};
+use std::collections::BTreeMap;
+
+use super::{
+    LinterDiagCategory, LINTER_DEFAULT_DIAG_CODE, LINT_WARNING_PREFIX, OPTION_MOD_NAME,
+    STD_PKG_NAME, VECTOR_MOD_NAME,
+};
+
+/// Functions moving their second argument into the container referenced by their first argument
+const INSERT_FUNCTIONS: &[(&str, &str, &str)] = &[
+    (STD_PKG_NAME, OPTION_MOD_NAME, "fill"),
+    (STD_PKG_NAME, VECTOR_MOD_NAME, "push_back"),
+];
+
+/// Functions returning a new container holding their argument
+const WRAP_FUNCTIONS: &[(&str, &str, &str)] = &[
+    (STD_PKG_NAME, OPTION_MOD_NAME, "some"),
+    (STD_PKG_NAME, VECTOR_MOD_NAME, "singleton"),
+];
+
+/// Functions destroying a container, aborting if it is not empty
+const DESTROY_EMPTY_FUNCTIONS: &[(&str, &str, &str)] = &[
+    (STD_PKG_NAME, OPTION_MOD_NAME, "destroy_none"),
+    (STD_PKG_NAME, VECTOR_MOD_NAME, "destroy_empty"),
+];
+
+const OBJECT_LIFECYCLE_DIAG: DiagnosticInfo = custom(
+    LINT_WARNING_PREFIX,
+    Severity::Warning,
+    LinterDiagCategory::ObjectLifecycle as u8,
+    LINTER_DEFAULT_DIAG_CODE,
+    "object neither transferred, shared, frozen, nor deleted",
+);
+
+//**************************************************************************************************
+// types
+//**************************************************************************************************
+
+pub struct ObjectLifecycleVerifier;
+pub struct ObjectLifecycleVerifierAI;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub enum Value {
+    /// a fresh object resulting from packing, or a container holding one (on some path)
+    FreshObj(Loc),
+    /// a mutable reference to a local variable
+    MutRef(Var),
+    #[default]
+    Other,
+}
+
+pub struct ExecutionContext {
+    diags: Diagnostics,
+}
+
+#[derive(Clone, Debug)]
+pub struct State {
+    locals: BTreeMap<Var, LocalState<Value>>,
+}
+
+//**************************************************************************************************
+// impls
+//**************************************************************************************************
+
+impl SimpleAbsIntConstructor for ObjectLifecycleVerifier {
+    type AI<'a> = ObjectLifecycleVerifierAI;
+
+    fn new<'a>(
+        _env: &CompilationEnv,
+        _program: &'a Program,
+        context: &'a CFGContext<'a>,
+        _init_state: &mut <Self::AI<'a> as SimpleAbsInt>::State,
+    ) -> Option<Self::AI<'a>> {
+        let Some(_) = &context.module else {
+            return None;
+        };
+        Some(ObjectLifecycleVerifierAI)
+    }
+}
+
+impl SimpleAbsInt for ObjectLifecycleVerifierAI {
+    type State = State;
+    type ExecutionContext = ExecutionContext;
+
+    fn finish(&mut self, _final_states: BTreeMap<Label, State>, diags: Diagnostics) -> Diagnostics {
+        diags
+    }
+
+    fn start_command(&self, _: &mut State) -> ExecutionContext {
+        ExecutionContext {
+            diags: Diagnostics::new(),
+        }
+    }
+
+    fn finish_command(&self, context: ExecutionContext, _state: &mut State) -> Diagnostics {
+        let ExecutionContext { diags } = context;
+        diags
+    }
+
+    fn exp_custom(
+        &self,
+        context: &mut ExecutionContext,
+        state: &mut State,
+        e: &Exp,
+    ) -> Option<Vec<Value>> {
+        use move_compiler::hlir::ast::UnannotatedExp_ as E;
+
+        match &e.exp.value {
+            E::Pack(_, _, fields) => {
+                for (_, _, inner) in fields.iter() {
+                    self.exp(context, state, inner);
+                }
+                let v = if is_obj_type(&e.ty) {
+                    Value::FreshObj(e.exp.loc)
+                } else {
+                    Value::Other
+                };
+                Some(vec![v])
+            }
+            E::Vector(_, _, _, args) => {
+                let values = args
+                    .iter()
+                    .flat_map(|arg| self.exp(context, state, arg))
+                    .collect::<Vec<_>>();
+                let v = values
+                    .into_iter()
+                    .find(|v| matches!(v, Value::FreshObj(_)))
+                    .unwrap_or_default();
+                Some(vec![v])
+            }
+            E::BorrowLocal(true, var) => Some(vec![Value::MutRef(*var)]),
+            _ => None,
+        }
+    }
+
+    fn call_custom(
+        &self,
+        context: &mut ExecutionContext,
+        state: &mut State,
+        loc: &Loc,
+        return_ty: &Type,
+        f: &ModuleCall,
+        args: Vec<Value>,
+    ) -> Option<Vec<Value>> {
+        let is_one_of = |funs: &[(&str, &str, &str)]| {
+            funs.iter()
+                .any(|(addr, module, fun)| f.is(addr, module, fun))
+        };
+
+        if is_one_of(DESTROY_EMPTY_FUNCTIONS) {
+            if let Some(Value::FreshObj(created)) = args.first() {
+                let msg = "Destroying this container aborts, as it can hold an object created in \
+                    this function";
+                let created_msg =
+                    "Object created here is not transferred, shared, frozen, or deleted on \
+                    some path";
+                context.add_diag(diag!(
+                    OBJECT_LIFECYCLE_DIAG,
+                    (*loc, msg),
+                    (*created, created_msg)
+                ));
+            }
+        } else if is_one_of(INSERT_FUNCTIONS) {
+            if let (Some(Value::MutRef(container)), Some(Value::FreshObj(created))) =
+                (args.first(), args.get(1))
+            {
+                set_local_value(state, container, Value::FreshObj(*created));
+            }
+        } else if is_one_of(WRAP_FUNCTIONS) {
+            if let Some(fresh @ Value::FreshObj(_)) = args.first() {
+                return Some(vec![*fresh]);
+            }
+        } else {
+            // any other function given mutable access to a container might remove the object
+            for arg in &args {
+                if let Value::MutRef(container) = arg {
+                    set_local_value(state, container, Value::Other);
+                }
+            }
+        }
+        Some(default_values_for_ty(return_ty))
+    }
+}
+
+fn set_local_value(state: &mut State, var: &Var, value: Value) {
+    if let Some(LocalState::Available(loc, _)) = state.locals.get(var) {
+        let loc = *loc;
+        state.locals.insert(*var, LocalState::Available(loc, value));
+    }
+}
+
+fn is_obj_type(ty: &Type) -> bool {
+    use move_compiler::hlir::ast::Type_ as T;
+    let T::Single(st) = &ty.value else {
+        return false;
+    };
+    is_obj_single_type(st)
+}
+
+fn is_obj_single_type(sp!(_, st_): &SingleType) -> bool {
+    let SingleType_::Base(sp!(_, bt_)) = st_ else {
+        return false;
+    };
+    if let BaseType_::Apply(abilities, _, _) = bt_ {
+        if abilities.has_ability_(Ability_::Key) {
+            return true;
+        }
+    }
+    false
+}
+
+impl SimpleDomain for State {
+    type Value = Value;
+
+    fn new(_: &CFGContext, locals: BTreeMap<Var, LocalState<Value>>) -> Self {
+        State { locals }
+    }
+
+    fn locals_mut(&mut self) -> &mut BTreeMap<Var, LocalState<Value>> {
+        &mut self.locals
+    }
+
+    fn locals(&self) -> &BTreeMap<Var, LocalState<Value>> {
+        &self.locals
+    }
+
+    fn join_value(v1: &Value, v2: &Value) -> Value {
+        match (v1, v2) {
+            (fresh @ Value::FreshObj(_), _) | (_, fresh @ Value::FreshObj(_)) => *fresh,
+            (Value::MutRef(r1), Value::MutRef(r2)) if r1 == r2 => *v1,
+            (Value::MutRef(_), _) | (_, Value::MutRef(_)) | (Value::Other, Value::Other) => {
+                Value::Other
+            }
+        }
+    }
+
+    fn join_impl(&mut self, _: &Self, _: &mut JoinResult) {}
+}
+
+impl SimpleExecutionContext for ExecutionContext {
+    fn add_diag(&mut self, diag: Diagnostic) {
+        self.diags.add(diag)
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+module 0x42::test {
+    use std::option;
+    use std::vector;
+    use sui::object::{Self, UID};
+    use sui::transfer;
+    use sui::tx_context::TxContext;
+
+    struct Obj has key, store {
+        id: UID
+    }
+
+    public fun destroy_some_option(ctx: &mut TxContext) {
+        let o = option::some(Obj { id: object::new(ctx) });
+        option::destroy_none(o);
+    }
+
+    public fun destroy_filled_option(cond: bool, ctx: &mut TxContext) {
+        let o = option::none();
+        if (cond) {
+            option::fill(&mut o, Obj { id: object::new(ctx) });
+        };
+        option::destroy_none(o);
+    }
+
+    public fun destroy_vector(ctx: &mut TxContext) {
+        let v = vector::empty();
+        vector::push_back(&mut v, Obj { id: object::new(ctx) });
+        vector::destroy_empty(v);
+    }
+
+    public fun destroy_vector_literal(ctx: &mut TxContext) {
+        let v = vector[Obj { id: object::new(ctx) }];
+        vector::destroy_empty(v);
+    }
+
+    #[lint_allow(object_lifecycle)]
+    public fun destroy_suppressed(ctx: &mut TxContext) {
+        let o = option::some(Obj { id: object::new(ctx) });
+        option::destroy_none(o);
+    }
+
+    // no warnings below
+
+    public fun extract_and_transfer(recipient: address, ctx: &mut TxContext) {
+        let o = option::some(Obj { id: object::new(ctx) });
+        let obj = option::extract(&mut o);
+        transfer::transfer(obj, recipient);
+        option::destroy_none(o);
+    }
+
+    public fun pop_and_freeze(ctx: &mut TxContext) {
+        let v = vector::empty();
+        vector::push_back(&mut v, Obj { id: object::new(ctx) });
+        transfer::freeze_object(vector::pop_back(&mut v));
+        vector::destroy_empty(v);
+    }
+
+    public fun delete(ctx: &mut TxContext) {
+        let Obj { id } = Obj { id: object::new(ctx) };
+        object::delete(id);
+    }
+
+    public fun destroy_param(o: option::Option<Obj>) {
+        option::destroy_none(o);
+    }
+}
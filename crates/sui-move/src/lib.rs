@@ -68,6 +68,8 @@ pub fn execute_move_command(
                 report_statistics: c.test.report_statistics.clone(),
                 check_stackless_vm: c.test.check_stackless_vm,
                 verbose: c.test.verbose_mode,
+                seed: c.test.seed,
+                rand_num_iters: c.test.rand_num_iters,
                 ..UnitTestingConfig::default_with_bound(None)
             };
             let result = c.execute(package_path, build_config, unit_test_config)?;
@@ -49,6 +49,15 @@ fn main() -> Result<()> {
                 .codec_path(codec_path)
                 .build(),
         )
+        .method(
+            Method::builder()
+                .name("handle_soft_bundle_certificates")
+                .route_name("SoftBundleCertifiedTransactions")
+                .input_type("sui_types::messages_grpc::HandleSoftBundleCertificatesRequest")
+                .output_type("sui_types::messages_grpc::HandleSoftBundleCertificatesResponse")
+                .codec_path(codec_path)
+                .build(),
+        )
         .method(
             Method::builder()
                 .name("submit_certificate")
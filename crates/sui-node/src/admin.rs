@@ -47,6 +47,14 @@ use tracing::info;
 // Reset tracing to the TRACE_FILTER env var.
 //
 //   $ curl -X POST 'http://127.0.0.1:1337/reset-tracing'
+//
+// Start measuring bytes written per store by transaction commits (clears previous measurements):
+//
+//   $ curl -X POST 'http://127.0.0.1:1337/write-amplification?enable=true'
+//
+// View the bytes written, aggregated per checkpoint and per module of the called functions:
+//
+//   $ curl 'http://127.0.0.1:1337/write-amplification'
 
 const LOGGING_ROUTE: &str = "/logging";
 const TRACING_ROUTE: &str = "/enable-tracing";
@@ -56,6 +64,7 @@ const CLEAR_BUFFER_STAKE_ROUTE: &str = "/clear-override-buffer-stake";
 const FORCE_CLOSE_EPOCH: &str = "/force-close-epoch";
 const CAPABILITIES: &str = "/capabilities";
 const NODE_CONFIG: &str = "/node-config";
+const WRITE_AMPLIFICATION: &str = "/write-amplification";
 
 struct AppState {
     node: Arc<SuiNode>,
@@ -74,6 +83,7 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
         .route(LOGGING_ROUTE, get(get_filter))
         .route(CAPABILITIES, get(capabilities))
         .route(NODE_CONFIG, get(node_config))
+        .route(WRITE_AMPLIFICATION, get(write_amplification_report))
         .route(LOGGING_ROUTE, post(set_filter))
         .route(
             SET_BUFFER_STAKE_ROUTE,
@@ -86,6 +96,7 @@ pub async fn run_admin_server(node: Arc<SuiNode>, port: u16, tracing_handle: Tra
         .route(FORCE_CLOSE_EPOCH, post(force_close_epoch))
         .route(TRACING_ROUTE, post(enable_tracing))
         .route(TRACING_RESET_ROUTE, post(reset_tracing))
+        .route(WRITE_AMPLIFICATION, post(set_write_amplification_profiling))
         .with_state(Arc::new(app_state));
 
     let socket_address = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), port);
@@ -216,6 +227,43 @@ async fn node_config(State(state): State<Arc<AppState>>) -> (StatusCode, String)
     (StatusCode::OK, format!("{:#?}\n", node_config))
 }
 
+async fn write_amplification_report(State(state): State<Arc<AppState>>) -> (StatusCode, String) {
+    let profiler = state.node.state().database.write_amplification_profiler();
+    if !profiler.is_enabled() {
+        return (
+            StatusCode::OK,
+            "write amplification profiling is disabled\n".to_string(),
+        );
+    }
+    (StatusCode::OK, format!("{:#?}\n", profiler.report()))
+}
+
+#[derive(Deserialize)]
+struct WriteAmplificationProfiling {
+    enable: bool,
+}
+
+async fn set_write_amplification_profiling(
+    State(state): State<Arc<AppState>>,
+    profiling: Query<WriteAmplificationProfiling>,
+) -> (StatusCode, String) {
+    let Query(WriteAmplificationProfiling { enable }) = profiling;
+    let profiler = state.node.state().database.write_amplification_profiler();
+    if enable {
+        profiler.enable();
+        (
+            StatusCode::OK,
+            "write amplification profiling enabled\n".to_string(),
+        )
+    } else {
+        profiler.disable();
+        (
+            StatusCode::OK,
+            "write amplification profiling disabled\n".to_string(),
+        )
+    }
+}
+
 #[derive(Deserialize)]
 struct Epoch {
     epoch: u64,
@@ -337,6 +337,16 @@ struct FeatureFlags {
     // If true, recompute has_public_transfer from the type instead of what is stored in the object
     #[serde(skip_serializing_if = "is_false")]
     recompute_has_public_transfer_in_execution: bool,
+
+    // If true, validators accept soft bundles of user transactions, which are sequenced adjacently
+    // by consensus and accepted or rejected together.
+    #[serde(skip_serializing_if = "is_false")]
+    soft_bundle: bool,
+
+    // If true, the timestamp of a consensus commit is never allowed to precede the timestamp of
+    // the previous commit.
+    #[serde(skip_serializing_if = "is_false")]
+    monotonic_commit_timestamps: bool,
 }
 
 fn is_false(b: &bool) -> bool {
@@ -355,6 +365,9 @@ pub enum ConsensusTransactionOrdering {
     None,
     /// Order transactions by gas price, highest first.
     ByGasPrice,
+    /// Order transactions by gas price, highest first, and transactions with the same gas price
+    /// by digest, so that their order does not depend on the order they arrived in.
+    ByGasPriceThenDigest,
 }
 
 impl ConsensusTransactionOrdering {
@@ -835,6 +848,16 @@ pub struct ProtocolConfig {
     // will cause the new epoch to start with JWKs from the previous epoch still valid.
     max_age_of_jwk_in_epochs: Option<u64>,
 
+    // The maximum number of transactions in a soft bundle.
+    max_soft_bundle_size: Option<u64>,
+
+    // The number of consecutive consensus commits over which the bytes of user transactions
+    // submitted by each authority are limited by `max_authority_submission_bytes_per_window`.
+    authority_submission_quota_window_commits: Option<u64>,
+    // The maximum bytes of user transactions submitted by a single authority that are processed
+    // within the quota window. Transactions beyond the quota are dropped.
+    max_authority_submission_bytes_per_window: Option<u64>,
+
     /// === random beacon ===
 
     /// Maximum allowed precision loss when reducing voting weights for the random beacon
@@ -1027,6 +1050,14 @@ impl ProtocolConfig {
         self.feature_flags.narwhal_certificate_v2
     }
 
+    pub fn soft_bundle(&self) -> bool {
+        self.feature_flags.soft_bundle
+    }
+
+    pub fn monotonic_commit_timestamps(&self) -> bool {
+        self.feature_flags.monotonic_commit_timestamps
+    }
+
     pub fn verify_legacy_zklogin_address(&self) -> bool {
         self.feature_flags.verify_legacy_zklogin_address
     }
@@ -1408,6 +1439,11 @@ impl ProtocolConfig {
 
             max_age_of_jwk_in_epochs: None,
 
+            max_soft_bundle_size: None,
+
+            authority_submission_quota_window_commits: None,
+            max_authority_submission_bytes_per_window: None,
+
             random_beacon_reduction_allowed_delta: None,
 
             // When adding a new constant, set it to None in the earliest version, like this:
@@ -1716,6 +1752,23 @@ impl ProtocolConfig {
     pub fn set_consensus_bad_nodes_stake_threshold(&mut self, val: u64) {
         self.consensus_bad_nodes_stake_threshold = Some(val);
     }
+    pub fn set_soft_bundle_for_testing(&mut self, val: bool) {
+        self.feature_flags.soft_bundle = val;
+        if val && self.max_soft_bundle_size.is_none() {
+            self.max_soft_bundle_size = Some(5);
+        }
+    }
+    pub fn set_monotonic_commit_timestamps_for_testing(&mut self, val: bool) {
+        self.feature_flags.monotonic_commit_timestamps = val;
+    }
+    pub fn set_authority_submission_quota_for_testing(
+        &mut self,
+        window_commits: u64,
+        max_bytes: u64,
+    ) {
+        self.authority_submission_quota_window_commits = Some(window_commits);
+        self.max_authority_submission_bytes_per_window = Some(max_bytes);
+    }
     pub fn set_receive_object_for_testing(&mut self, val: bool) {
         self.feature_flags.receive_objects = val
     }
@@ -449,6 +449,8 @@ impl IndexStore {
         Ok(cache_updates)
     }
 
+    /// Indexes a transaction, returning the sequence number assigned to it and the number of bytes
+    /// written to the index tables.
     pub async fn index_tx(
         &self,
         sender: SuiAddress,
@@ -461,7 +463,7 @@ impl IndexStore {
         timestamp_ms: u64,
         tx_coins: Option<TxCoins>,
         loaded_child_objects: &BTreeMap<ObjectID, DynamicallyLoadedObjectMetadata>,
-    ) -> SuiResult<u64> {
+    ) -> SuiResult<(u64, usize)> {
         let sequence = self.next_sequence_number.fetch_add(1, Ordering::SeqCst);
         let mut batch = self.tables.transactions_from_addr.batch();
 
@@ -631,6 +633,7 @@ impl IndexStore {
             .await?;
         }
 
+        let bytes_written = batch.size_in_bytes();
         batch.write()?;
 
         if !invalidate_caches {
@@ -643,7 +646,7 @@ impl IndexStore {
             self.update_all_balance_cache(cache_updates.all_balance_changes)
                 .await?;
         }
-        Ok(sequence)
+        Ok((sequence, bytes_written))
     }
 
     pub fn next_sequence_number(&self) -> TxSequenceNumber {
@@ -10,12 +10,16 @@ use narwhal_storage::NodeStorage;
 use std::path::{Path, PathBuf};
 use sui_core::authority::authority_per_epoch_store::AuthorityEpochTables;
 use sui_core::authority::authority_store_tables::AuthorityPerpetualTables;
+use sui_core::authority::epoch_start_configuration::EpochStartConfigTrait;
 use sui_core::checkpoints::CheckpointStore;
+use sui_core::consensus_handler::DEFAULT_CONSENSUS_DEDUP_WINDOW_COMMITS;
+use sui_core::consensus_replay::{read_narwhal_commits, ConsensusReplay};
 use sui_types::base_types::{EpochId, ObjectID, SequenceNumber};
 use sui_types::digests::{CheckpointContentsDigest, TransactionDigest};
 use sui_types::effects::TransactionEffectsAPI;
 use sui_types::messages_checkpoint::CheckpointDigest;
 use sui_types::storage::ObjectKey;
+use sui_types::sui_system_state::epoch_start_sui_system_state::EpochStartSystemStateTrait;
 use sui_types::sui_system_state::{get_sui_system_state, SuiSystemStateTrait};
 use typed_store::rocks::MetricConf;
 pub mod db_dump;
@@ -33,6 +37,7 @@ pub enum DbToolCommand {
     ListDBMetadata(Options),
     PrintLastConsensusIndex,
     PrintConsensusCommit(PrintConsensusCommitOptions),
+    ReplayConsensus(ReplayConsensusOptions),
     PrintTransaction(PrintTransactionOptions),
     PrintCheckpoint(PrintCheckpointOptions),
     PrintCheckpointContent(PrintCheckpointContentOptions),
@@ -98,6 +103,45 @@ pub struct PrintConsensusCommitOptions {
     seqnum: u64,
 }
 
+#[derive(Parser)]
+#[command(rename_all = "kebab-case")]
+pub struct ReplayConsensusOptions {
+    #[arg(long, help = "Sequence number of the first consensus commit to replay")]
+    start: u64,
+    #[arg(
+        long,
+        help = "Sequence number of the last consensus commit to replay, defaults to the latest"
+    )]
+    end: Option<u64>,
+    /// The authority store of the validator, whose current epoch gives the committee that made
+    /// the commits, and the defaults of the epoch options.
+    #[arg(long)]
+    store_path: PathBuf,
+    /// The epoch the commits were made in, which the consensus commit prologue depends on.
+    #[arg(long)]
+    epoch: Option<EpochId>,
+    /// The start of the epoch, which commit timestamps are clamped to.
+    #[arg(long)]
+    epoch_start_timestamp_ms: Option<u64>,
+    /// The number of commits transactions are deduplicated over, as recorded in the epoch store.
+    #[arg(long, default_value_t = DEFAULT_CONSENSUS_DEDUP_WINDOW_COMMITS)]
+    dedup_window_commits: u64,
+    /// The number of commits the submission quota of each authority applies over, if the
+    /// protocol config of the epoch enables it.
+    #[arg(long, requires = "max_submission_bytes_per_window")]
+    submission_quota_window_commits: Option<u64>,
+    /// The bytes of user transactions each authority can submit over the quota window.
+    #[arg(long, requires = "submission_quota_window_commits")]
+    max_submission_bytes_per_window: Option<u64>,
+    /// Clamp each commit timestamp to the timestamp of the previous commit, if the protocol
+    /// config of the epoch enables monotonic commit timestamps.
+    #[arg(long)]
+    monotonic_commit_timestamps: bool,
+    /// Print every transaction sequenced, not just a line per commit.
+    #[arg(long)]
+    verbose: bool,
+}
+
 #[derive(Parser)]
 #[command(rename_all = "kebab-case")]
 pub struct PrintTransactionOptions {
@@ -180,6 +224,7 @@ pub async fn execute_db_tool_command(db_path: PathBuf, cmd: DbToolCommand) -> an
         }
         DbToolCommand::PrintLastConsensusIndex => print_last_consensus_index(&db_path),
         DbToolCommand::PrintConsensusCommit(d) => print_consensus_commit(&db_path, d),
+        DbToolCommand::ReplayConsensus(d) => replay_consensus(&db_path, d),
         DbToolCommand::PrintTransaction(d) => print_transaction(&db_path, d),
         DbToolCommand::PrintCheckpoint(d) => print_checkpoint(&db_path, d),
         DbToolCommand::PrintCheckpointContent(d) => print_checkpoint_content(&db_path, d),
@@ -258,6 +303,72 @@ pub fn print_consensus_commit(path: &Path, opt: PrintConsensusCommitOptions) ->
     Ok(())
 }
 
+/// Replays consensus commits from the consensus DB at `path`, printing the order that the
+/// consensus handler sequences their transactions in, without executing them.
+pub fn replay_consensus(path: &Path, opt: ReplayConsensusOptions) -> anyhow::Result<()> {
+    let consensus_db = NodeStorage::reopen(path, None);
+    let end = opt
+        .end
+        .unwrap_or_else(|| consensus_db.consensus_store.get_latest_sub_dag_index());
+    let outputs = read_narwhal_commits(&consensus_db, opt.start, end)?;
+    let epoch_start_state = AuthorityPerpetualTables::open(&opt.store_path, None)
+        .get_epoch_start_configuration()?
+        .ok_or_else(|| anyhow!("No epoch start configuration in the authority store"))?
+        .epoch_start_state()
+        .clone();
+
+    let mut replay = ConsensusReplay::new(
+        opt.epoch.unwrap_or_else(|| epoch_start_state.epoch()),
+        opt.epoch_start_timestamp_ms
+            .unwrap_or_else(|| epoch_start_state.epoch_start_timestamp_ms()),
+        epoch_start_state.get_narwhal_committee(),
+        opt.dedup_window_commits,
+    );
+    if let (Some(window_commits), Some(max_bytes)) = (
+        opt.submission_quota_window_commits,
+        opt.max_submission_bytes_per_window,
+    ) {
+        replay = replay.with_submission_quota(window_commits, max_bytes);
+    }
+    if opt.monotonic_commit_timestamps {
+        replay = replay.with_monotonic_commit_timestamps();
+    }
+    let summary = replay.replay_all_narwhal(outputs, |commit| {
+        println!(
+            "Commit {} (round {}, timestamp {}, digest {}, previous {:?}): {} sequenced ({} user transactions), {} skipped, {} dropped, {} malformed",
+            commit.sub_dag_index,
+            commit.leader_round,
+            commit.commit_timestamp_ms,
+            commit.commit_digest,
+            commit.previous_commit_digest,
+            commit.transactions.len(),
+            commit.user_transactions,
+            commit.skipped,
+            commit.dropped,
+            commit.malformed,
+        );
+        if opt.verbose {
+            for (index, key) in &commit.transactions {
+                println!("  {:?}: {:?}", index, key);
+            }
+        }
+    });
+
+    println!(
+        "Replayed {} commits ({} ignored) in {:?}: {} transactions sequenced ({:.0} per second, {} user transactions), {} skipped, {} dropped, {} malformed",
+        summary.commits,
+        summary.ignored_commits,
+        summary.elapsed,
+        summary.transactions,
+        summary.transactions_per_second(),
+        summary.user_transactions,
+        summary.skipped,
+        summary.dropped,
+        summary.malformed,
+    );
+    Ok(())
+}
+
 pub fn print_transaction(path: &Path, opt: PrintTransactionOptions) -> anyhow::Result<()> {
     let perpetual_db = AuthorityPerpetualTables::open(&path.join("store"), None);
     if let Some((epoch, checkpoint_seq_num)) =
@@ -0,0 +1,56 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::coin::Coin;
+use crate::object::{Object, Owner};
+use move_core_types::language_storage::TypeTag;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// A change to the total balance of a coin type held by an owner, made by a transaction.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceChange {
+    pub owner: Owner,
+    pub coin_type: TypeTag,
+    /// Negative when the owner spent coins of the type, positive when it received them.
+    pub amount: i128,
+}
+
+/// Derives the balance changes of a transaction from the coins it consumed (`inputs`, at the
+/// versions they had before the transaction) and the coins it left behind (`outputs`). Objects
+/// that are not coins are ignored, and balances that did not change are left out. Changes are
+/// ordered by owner, then by coin type.
+///
+/// This is the rule every layer reporting balance changes should use, so that they all agree.
+pub fn derive_balance_changes<'a>(
+    inputs: impl IntoIterator<Item = &'a Object>,
+    outputs: impl IntoIterator<Item = &'a Object>,
+) -> Vec<BalanceChange> {
+    let mut balances = BTreeMap::<(Owner, TypeTag), i128>::new();
+    for (owner, coin_type, amount) in inputs.into_iter().filter_map(coin_balance) {
+        *balances.entry((owner, coin_type)).or_default() -= amount as i128;
+    }
+    for (owner, coin_type, amount) in outputs.into_iter().filter_map(coin_balance) {
+        *balances.entry((owner, coin_type)).or_default() += amount as i128;
+    }
+
+    balances
+        .into_iter()
+        .filter(|(_, amount)| *amount != 0)
+        .map(|((owner, coin_type), amount)| BalanceChange {
+            owner,
+            coin_type,
+            amount,
+        })
+        .collect()
+}
+
+/// The owner, coin type and value of `object`, if it is a coin.
+fn coin_balance(object: &Object) -> Option<(Owner, TypeTag, u64)> {
+    let coin_type = object.coin_type_maybe()?;
+    // The object is known to be a coin, so its contents are a valid coin.
+    let amount = Coin::extract_balance_if_coin(object)
+        .expect("Coin contents should deserialize")
+        .expect("Object should be a coin");
+    Some((object.owner, coin_type, amount))
+}
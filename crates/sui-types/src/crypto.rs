@@ -1436,6 +1436,7 @@ mod bcs_signable {
 
     impl BcsSignable for crate::effects::TransactionEffects {}
     impl BcsSignable for crate::effects::TransactionEvents {}
+    impl BcsSignable for crate::inner_temporary_store::InnerTemporaryStore {}
     impl BcsSignable for crate::transaction::TransactionData {}
     impl BcsSignable for crate::transaction::SenderSignedData {}
     impl BcsSignable for crate::object::Object {}
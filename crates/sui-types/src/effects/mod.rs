@@ -24,6 +24,9 @@ use crate::transaction::{SenderSignedData, TransactionDataAPI, VersionedProtocol
 use effects_v1::TransactionEffectsV1;
 pub use effects_v2::UnchangedSharedKind;
 use enum_dispatch::enum_dispatch;
+use move_bytecode_utils::module_cache::GetModule;
+use move_core_types::language_storage::StructTag;
+use move_core_types::value::MoveStruct;
 pub use object_change::{EffectsObjectChange, IDOperation, ObjectIn, ObjectOut};
 use serde::{Deserialize, Serialize};
 use shared_crypto::intent::IntentScope;
@@ -425,6 +428,26 @@ impl TransactionEvents {
     pub fn digest(&self) -> TransactionEventsDigest {
         TransactionEventsDigest::new(default_hash(self))
     }
+
+    /// Returns the events of exactly `type_`, type parameters included.
+    pub fn events_of_type<'a>(&'a self, type_: &'a StructTag) -> impl Iterator<Item = &'a Event> {
+        self.data.iter().filter(move |event| &event.type_ == type_)
+    }
+
+    /// Returns the events emitted by functions of `package`.
+    pub fn events_from_package(&self, package: ObjectID) -> impl Iterator<Item = &Event> {
+        self.data
+            .iter()
+            .filter(move |event| event.package_id == package)
+    }
+
+    /// Decodes the contents of every event, in order, using the layouts of their types.
+    pub fn to_move_structs(&self, resolver: &impl GetModule) -> SuiResult<Vec<MoveStruct>> {
+        self.data
+            .iter()
+            .map(|event| Event::move_event_to_move_struct(&event.type_, &event.contents, resolver))
+            .collect()
+    }
 }
 
 #[derive(Debug)]
@@ -453,3 +476,34 @@ pub type VerifiedTransactionEffectsEnvelope<S> = VerifiedEnvelope<TransactionEff
 pub type VerifiedSignedTransactionEffects = VerifiedTransactionEffectsEnvelope<AuthoritySignInfo>;
 pub type VerifiedCertifiedTransactionEffects =
     VerifiedTransactionEffectsEnvelope<AuthorityStrongQuorumSignInfo>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::coin::Coin;
+    use crate::gas_coin::GAS;
+    use move_core_types::language_storage::TypeTag;
+
+    #[test]
+    fn test_filter_transaction_events() {
+        let coin_event = Event {
+            type_: Coin::type_(TypeTag::Struct(Box::new(GAS::type_()))),
+            ..Event::random_for_testing()
+        };
+        let other_event = Event::random_for_testing();
+        let events = TransactionEvents {
+            data: vec![coin_event.clone(), other_event.clone(), coin_event.clone()],
+        };
+
+        assert_eq!(
+            events.events_of_type(&coin_event.type_).collect::<Vec<_>>(),
+            vec![&coin_event, &coin_event]
+        );
+        assert_eq!(
+            events
+                .events_from_package(other_event.package_id)
+                .collect::<Vec<_>>(),
+            vec![&other_event]
+        );
+    }
+}
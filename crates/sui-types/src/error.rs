@@ -538,6 +538,14 @@ pub enum SuiError {
     #[error("Fullnode does not support handle_certificate")]
     FullNodeCantHandleCertificate,
 
+    // Soft bundle errors.
+    #[error("Invalid soft bundle: {error}")]
+    InvalidSoftBundle { error: String },
+    #[error(
+        "Soft bundle was not sequenced, as some of its transactions were sequenced without it"
+    )]
+    SoftBundleRejected,
+
     // Epoch related errors.
     #[error("Validator temporarily stopped processing transactions due to epoch change")]
     ValidatorHaltedAtEpochEnd,
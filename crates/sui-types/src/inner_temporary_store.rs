@@ -1,24 +1,32 @@
 // Copyright (c) Mysten Labs, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::base_types::{SequenceNumber, VersionDigest};
+use crate::balance_change::{derive_balance_changes, BalanceChange};
+use crate::base_types::{ObjectDigest, SequenceNumber, VersionDigest};
+use crate::crypto::default_hash;
+use crate::digests::InnerTemporaryStoreDigest;
 use crate::effects::TransactionEvents;
 use crate::execution::DynamicallyLoadedObjectMetadata;
+use crate::move_package::MovePackage;
 use crate::{
     base_types::ObjectID,
     object::{Object, Owner},
 };
 use move_binary_format::CompiledModule;
 use move_bytecode_utils::module_cache::GetModule;
-use move_core_types::language_storage::ModuleId;
-use std::collections::BTreeMap;
-use std::sync::Arc;
+use move_core_types::language_storage::{ModuleId, StructTag};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, RwLock};
 
 pub type WrittenObjects = BTreeMap<ObjectID, Object>;
 pub type ObjectMap = BTreeMap<ObjectID, Arc<Object>>;
 pub type TxCoins = (ObjectMap, WrittenObjects);
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// The serialized form of this struct is persisted by replay and fuzzing tools to compare
+/// execution results across versions. Fields are serialized in declaration order, so new fields
+/// must only be appended at the end.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct InnerTemporaryStore {
     pub input_objects: ObjectMap,
     pub mutable_inputs: BTreeMap<ObjectID, (VersionDigest, Owner)>,
@@ -32,9 +40,254 @@ pub struct InnerTemporaryStore {
     pub lamport_version: SequenceNumber,
 }
 
+impl InnerTemporaryStore {
+    /// Digest of the BCS serialization of the store. Two executions of the same transaction
+    /// produced the same result if and only if their digests are equal.
+    pub fn digest(&self) -> InnerTemporaryStoreDigest {
+        InnerTemporaryStoreDigest::new(default_hash(self))
+    }
+
+    /// Returns the written objects grouped by their owner.
+    pub fn written_by_owner(&self) -> BTreeMap<Owner, Vec<&Object>> {
+        let mut grouped: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for object in self.written.values() {
+            grouped.entry(object.owner).or_default().push(object);
+        }
+        grouped
+    }
+
+    /// Returns the written Move objects grouped by their type. Packages have no type, and are
+    /// left out.
+    pub fn written_by_type(&self) -> BTreeMap<StructTag, Vec<&Object>> {
+        let mut grouped: BTreeMap<_, Vec<_>> = BTreeMap::new();
+        for object in self.written.values() {
+            if let Some(type_) = object.struct_tag() {
+                grouped.entry(type_).or_default().push(object);
+            }
+        }
+        grouped
+    }
+
+    /// Returns the written coin objects, of any coin type.
+    pub fn written_coins(&self) -> impl Iterator<Item = (&ObjectID, &Object)> {
+        self.written.iter().filter(|(_, object)| object.is_coin())
+    }
+
+    /// Returns the balance changes made by the transaction: the coins it wrote, less the coins
+    /// it mutated, deleted or wrapped, as JSON-RPC reports them.
+    ///
+    /// The contents of objects loaded at runtime (e.g. dynamic object fields) before the
+    /// transaction are not held by the store: `runtime_objects` must hold the ones the
+    /// transaction mutated, deleted or wrapped, at the versions they were loaded at. Those given
+    /// at any other version are ignored, and a written object loaded at runtime whose previous
+    /// contents are not given is left out, rather than counted as received in full.
+    pub fn compute_balance_changes(
+        &self,
+        runtime_objects: &BTreeMap<ObjectID, Object>,
+    ) -> Vec<BalanceChange> {
+        let runtime_objects: BTreeMap<_, _> = runtime_objects
+            .iter()
+            .filter(|(id, object)| {
+                self.loaded_runtime_objects
+                    .get(id)
+                    .is_some_and(|metadata| metadata.version == object.version())
+            })
+            .collect();
+        let inputs = self
+            .mutable_inputs
+            .keys()
+            .filter_map(|id| self.input_objects.get(id))
+            .map(|object| object.as_ref())
+            .chain(runtime_objects.values().copied());
+        let outputs = self
+            .written
+            .iter()
+            .filter(|(id, _)| {
+                !self.loaded_runtime_objects.contains_key(id) || runtime_objects.contains_key(id)
+            })
+            .map(|(_, object)| object);
+        derive_balance_changes(inputs, outputs)
+    }
+
+    /// Checks that every written Move object has the lamport version, and that every input Move
+    /// object is older than it. Packages are versioned independently, and are not checked.
+    /// Returns all the violations found.
+    pub fn check_lamport_version(&self) -> Result<(), Vec<LamportVersionViolation>> {
+        let written = self
+            .written
+            .iter()
+            .filter(|(_, object)| !object.is_package())
+            .filter(|(_, object)| object.version() != self.lamport_version)
+            .map(
+                |(id, object)| LamportVersionViolation::WrittenVersionMismatch {
+                    id: *id,
+                    version: object.version(),
+                },
+            );
+        let inputs = self
+            .input_objects
+            .iter()
+            .filter(|(_, object)| !object.is_package())
+            .filter(|(_, object)| object.version() >= self.lamport_version)
+            .map(
+                |(id, object)| LamportVersionViolation::InputVersionNotBelow {
+                    id: *id,
+                    version: object.version(),
+                },
+            );
+
+        let violations: Vec<_> = written.chain(inputs).collect();
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Returns every object the transaction read, whether passed as an input or loaded at
+    /// runtime (e.g. dynamic fields), at the version it was read.
+    pub fn read_set(&self) -> BTreeMap<ObjectID, ReadObject> {
+        let mut read_set = BTreeMap::new();
+        for (id, object) in &self.input_objects {
+            let (version, digest, owner) = match self.mutable_inputs.get(id) {
+                Some(((version, digest), owner)) => (*version, *digest, *owner),
+                None => (object.version(), object.digest(), object.owner),
+            };
+            read_set.insert(
+                *id,
+                ReadObject {
+                    version,
+                    digest,
+                    owner,
+                    mutable: self.mutable_inputs.contains_key(id),
+                    loaded_at_runtime: false,
+                },
+            );
+        }
+        for (id, metadata) in &self.loaded_runtime_objects {
+            read_set.entry(*id).or_insert(ReadObject {
+                version: metadata.version,
+                digest: metadata.digest,
+                owner: metadata.owner,
+                mutable: self.written.contains_key(id),
+                loaded_at_runtime: true,
+            });
+        }
+        read_set
+    }
+
+    /// Returns how the transaction changed the storage taken up by each object it created,
+    /// mutated, deleted or wrapped. Read-only inputs are left out.
+    ///
+    /// Objects loaded at runtime that the transaction deleted or wrapped are neither inputs nor
+    /// written, so they cannot be told apart from ones that were only read: `deleted` must hold
+    /// the objects the transaction deleted or wrapped, as recorded in its effects.
+    pub fn object_storage_changes(
+        &self,
+        deleted: &BTreeSet<ObjectID>,
+    ) -> BTreeMap<ObjectID, ObjectStorageChange> {
+        let mut changes: BTreeMap<_, ObjectStorageChange> = BTreeMap::new();
+        for id in self.mutable_inputs.keys() {
+            if let Some(object) = self.input_objects.get(id) {
+                let change = changes.entry(*id).or_default();
+                change.old_size = Some(object.object_size_for_gas_metering());
+                change.old_storage_rebate = object.storage_rebate;
+            }
+        }
+        for (id, object) in &self.written {
+            let change = changes.entry(*id).or_default();
+            change.new_size = Some(object.object_size_for_gas_metering());
+            change.new_storage_rebate = object.storage_rebate;
+        }
+        // The contents of objects loaded at runtime are not held by the store, but the rebate
+        // they held is.
+        for (id, metadata) in &self.loaded_runtime_objects {
+            if self.written.contains_key(id) || deleted.contains(id) {
+                changes.entry(*id).or_default().old_storage_rebate = metadata.storage_rebate;
+            }
+        }
+        changes
+    }
+
+    /// Net change in serialized size of the objects changed by the transaction, in bytes, given
+    /// the objects it `deleted` or wrapped. The previous size of objects loaded at runtime is
+    /// unknown, and counted as zero.
+    pub fn storage_size_delta(&self, deleted: &BTreeSet<ObjectID>) -> i64 {
+        self.object_storage_changes(deleted)
+            .values()
+            .map(ObjectStorageChange::size_delta)
+            .sum()
+    }
+
+    /// Net change in the storage rebate held by the objects changed by the transaction, given
+    /// the objects it `deleted` or wrapped: the storage cost charged for the objects it wrote,
+    /// less the rebate of the objects it mutated, deleted or wrapped.
+    pub fn storage_rebate_delta(&self, deleted: &BTreeSet<ObjectID>) -> i64 {
+        self.object_storage_changes(deleted)
+            .values()
+            .map(ObjectStorageChange::storage_rebate_delta)
+            .sum()
+    }
+}
+
+/// A version held by an `InnerTemporaryStore` that is inconsistent with its lamport version.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LamportVersionViolation {
+    /// A written Move object does not have the lamport version.
+    WrittenVersionMismatch {
+        id: ObjectID,
+        version: SequenceNumber,
+    },
+    /// An input Move object is not older than the lamport version.
+    InputVersionNotBelow {
+        id: ObjectID,
+        version: SequenceNumber,
+    },
+}
+
+/// An object read by a transaction, at the version it was read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReadObject {
+    pub version: SequenceNumber,
+    pub digest: ObjectDigest,
+    pub owner: Owner,
+    /// For inputs, whether the object was taken by mutable reference. For objects loaded at
+    /// runtime, whether the transaction wrote them: runtime objects that were deleted cannot be
+    /// told apart from objects that were only read.
+    pub mutable: bool,
+    pub loaded_at_runtime: bool,
+}
+
+/// How a transaction changed the storage taken up by one object.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ObjectStorageChange {
+    /// Size of the object before the transaction, if it was an input. `None` for created
+    /// objects, and for objects loaded at runtime, including ones that were deleted or wrapped.
+    pub old_size: Option<usize>,
+    /// Size of the object after the transaction. `None` if it was deleted or wrapped.
+    pub new_size: Option<usize>,
+    /// Storage rebate held by the object before the transaction, refunded by it.
+    pub old_storage_rebate: u64,
+    /// Storage rebate held by the object after the transaction, charged by it.
+    pub new_storage_rebate: u64,
+}
+
+impl ObjectStorageChange {
+    pub fn size_delta(&self) -> i64 {
+        self.new_size.unwrap_or_default() as i64 - self.old_size.unwrap_or_default() as i64
+    }
+
+    pub fn storage_rebate_delta(&self) -> i64 {
+        self.new_storage_rebate as i64 - self.old_storage_rebate as i64
+    }
+}
+
 pub struct TemporaryModuleResolver<'a, R> {
     temp_store: &'a InnerTemporaryStore,
     fallback: R,
+    /// Modules deserialized from packages written by the transaction, so that repeated lookups
+    /// into a freshly published package only deserialize each module once.
+    written_modules: RwLock<BTreeMap<ModuleId, Arc<CompiledModule>>>,
 }
 
 impl<'a, R> TemporaryModuleResolver<'a, R> {
@@ -42,10 +295,64 @@ impl<'a, R> TemporaryModuleResolver<'a, R> {
         Self {
             temp_store,
             fallback,
+            written_modules: RwLock::new(BTreeMap::new()),
         }
     }
 }
 
+impl<R> TemporaryModuleResolver<'_, R>
+where
+    R: GetModule<Item = Arc<CompiledModule>, Error = anyhow::Error>,
+{
+    /// Resolves `runtime_id`, as referred to by code in `linkage`, to the module it links against.
+    /// Modules of `linkage` itself resolve to `linkage`, and modules of its dependencies resolve
+    /// to the (possibly upgraded) package recorded in its linkage table.
+    pub fn get_linked_module(
+        &self,
+        linkage: &MovePackage,
+        runtime_id: &ModuleId,
+    ) -> anyhow::Result<Arc<CompiledModule>> {
+        let runtime_package = ObjectID::from(*runtime_id.address());
+        let storage_package = if runtime_package == linkage.original_package_id() {
+            linkage.id()
+        } else {
+            linkage
+                .linkage_table()
+                .get(&runtime_package)
+                .map(|info| info.upgraded_id)
+                .ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Package {} has no linkage entry for package {}",
+                        linkage.id(),
+                        runtime_package,
+                    )
+                })?
+        };
+
+        let storage_id = ModuleId::new(storage_package.into(), runtime_id.name().to_owned());
+        self.get_module_by_id(&storage_id)
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Failed to load module {} through linkage entry {} => {} of package {}: {}",
+                    runtime_id.name(),
+                    runtime_package,
+                    storage_package,
+                    linkage.id(),
+                    e,
+                )
+            })?
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Module {} not found through linkage entry {} => {} of package {}",
+                    runtime_id.name(),
+                    runtime_package,
+                    storage_package,
+                    linkage.id(),
+                )
+            })
+    }
+}
+
 impl<R> GetModule for TemporaryModuleResolver<'_, R>
 where
     R: GetModule<Item = Arc<CompiledModule>, Error = anyhow::Error>,
@@ -54,16 +361,508 @@ where
     type Item = Arc<CompiledModule>;
 
     fn get_module_by_id(&self, id: &ModuleId) -> anyhow::Result<Option<Self::Item>, Self::Error> {
+        if let Some(module) = self.written_modules.read().unwrap().get(id) {
+            return Ok(Some(module.clone()));
+        }
         let obj = self.temp_store.written.get(&ObjectID::from(*id.address()));
         if let Some(o) = obj {
             if let Some(p) = o.data.try_as_package() {
-                return Ok(Some(Arc::new(p.deserialize_module(
+                let module = Arc::new(p.deserialize_module(
                     &id.name().into(),
                     self.temp_store.max_binary_format_version,
                     self.temp_store.no_extraneous_module_bytes,
-                )?)));
+                )?);
+                self.written_modules
+                    .write()
+                    .unwrap()
+                    .insert(id.clone(), module.clone());
+                return Ok(Some(module));
             }
         }
         self.fallback.get_module_by_id(id)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base_types::{SuiAddress, TransactionDigest};
+    use crate::coin::Coin;
+    use crate::gas_coin::GAS;
+    use crate::move_package::UpgradeInfo;
+    use crate::object::{Data, MoveObject, OBJECT_START_VERSION};
+    use crate::parse_sui_type_tag;
+    use move_binary_format::file_format::empty_module;
+    use move_binary_format::file_format_common::VERSION_MAX;
+
+    fn temporary_store(written: Vec<Object>) -> InnerTemporaryStore {
+        InnerTemporaryStore {
+            input_objects: BTreeMap::new(),
+            mutable_inputs: BTreeMap::new(),
+            written: written.into_iter().map(|o| (o.id(), o)).collect(),
+            loaded_runtime_objects: BTreeMap::new(),
+            events: TransactionEvents::default(),
+            max_binary_format_version: 0,
+            no_extraneous_module_bytes: false,
+            runtime_packages_loaded_from_db: BTreeMap::new(),
+            lamport_version: OBJECT_START_VERSION,
+        }
+    }
+
+    #[test]
+    fn test_written_object_views() {
+        let owner = SuiAddress::default();
+        let other_coin_type = Coin::type_(parse_sui_type_tag("0x42::usdc::USDC").unwrap());
+        let other_coin = Object::new_move(
+            MoveObject::new_coin(
+                other_coin_type.clone().into(),
+                OBJECT_START_VERSION,
+                ObjectID::random(),
+                10,
+            ),
+            Owner::AddressOwner(owner),
+            TransactionDigest::genesis(),
+        );
+        let gas = Object::with_owner_for_testing(owner);
+        let immutable = Object::immutable_for_testing();
+        let store = temporary_store(vec![other_coin.clone(), gas.clone(), immutable.clone()]);
+
+        let by_owner = store.written_by_owner();
+        assert_eq!(by_owner.len(), 2);
+        assert_eq!(by_owner[&Owner::AddressOwner(owner)].len(), 2);
+        assert_eq!(by_owner[&Owner::Immutable], vec![&immutable]);
+
+        let by_type = store.written_by_type();
+        assert_eq!(by_type.len(), 2);
+        assert_eq!(by_type[&other_coin_type], vec![&other_coin]);
+        assert_eq!(by_type[&gas.struct_tag().unwrap()].len(), 2);
+
+        assert_eq!(store.written_coins().count(), 3);
+    }
+
+    struct NoModules;
+
+    impl GetModule for NoModules {
+        type Error = anyhow::Error;
+        type Item = Arc<CompiledModule>;
+
+        fn get_module_by_id(&self, _id: &ModuleId) -> anyhow::Result<Option<Self::Item>> {
+            Ok(None)
+        }
+    }
+
+    #[test]
+    fn test_resolver_memoizes_written_modules() {
+        let module = empty_module();
+        let package =
+            Object::new_package_for_testing(&[module.clone()], TransactionDigest::genesis(), [])
+                .unwrap();
+        let mut store = temporary_store(vec![package]);
+        store.max_binary_format_version = VERSION_MAX;
+        let resolver = TemporaryModuleResolver::new(&store, NoModules);
+
+        let first = resolver
+            .get_module_by_id(&module.self_id())
+            .unwrap()
+            .unwrap();
+        let second = resolver
+            .get_module_by_id(&module.self_id())
+            .unwrap()
+            .unwrap();
+        assert_eq!(*first, module);
+        assert!(Arc::ptr_eq(&first, &second));
+    }
+
+    #[test]
+    fn test_resolver_follows_linkage() {
+        let runtime_package = ObjectID::random();
+        let upgraded_package = ObjectID::random();
+        let mut module = empty_module();
+        module.address_identifiers[0] = runtime_package.into();
+        let mut module_bytes = vec![];
+        module.serialize(&mut module_bytes).unwrap();
+        let name = module.self_id().name().to_string();
+
+        // The upgraded package is stored at a new ID, but its modules keep the original address.
+        let upgraded = MovePackage::new(
+            upgraded_package,
+            OBJECT_START_VERSION.next(),
+            BTreeMap::from([(name.clone(), module_bytes.clone())]),
+            u64::MAX,
+            vec![],
+            BTreeMap::new(),
+        )
+        .unwrap();
+        let linkage_package = ObjectID::random();
+        let mut linkage_module = empty_module();
+        linkage_module.address_identifiers[0] = linkage_package.into();
+        let mut linkage_module_bytes = vec![];
+        linkage_module.serialize(&mut linkage_module_bytes).unwrap();
+        let linkage = MovePackage::new(
+            linkage_package,
+            OBJECT_START_VERSION,
+            BTreeMap::from([(name, linkage_module_bytes)]),
+            u64::MAX,
+            vec![],
+            BTreeMap::from([(
+                runtime_package,
+                UpgradeInfo {
+                    upgraded_id: upgraded_package,
+                    upgraded_version: upgraded.version(),
+                },
+            )]),
+        )
+        .unwrap();
+        let mut store = temporary_store(vec![Object::new_package_from_data(
+            Data::Package(upgraded),
+            TransactionDigest::genesis(),
+        )]);
+        store.max_binary_format_version = VERSION_MAX;
+        let resolver = TemporaryModuleResolver::new(&store, NoModules);
+
+        // Modules are not stored at their runtime address.
+        assert!(resolver
+            .get_module_by_id(&module.self_id())
+            .unwrap()
+            .is_none());
+        let linked = resolver
+            .get_linked_module(&linkage, &module.self_id())
+            .unwrap();
+        assert_eq!(*linked, module);
+
+        let unknown = ModuleId::new(ObjectID::random().into(), module.self_id().name().into());
+        let err = resolver.get_linked_module(&linkage, &unknown).unwrap_err();
+        assert!(err.to_string().contains("no linkage entry"));
+    }
+
+    #[test]
+    fn test_serialization_and_digest() {
+        let store = temporary_store(vec![
+            Object::immutable_for_testing(),
+            Object::with_owner_for_testing(SuiAddress::default()),
+        ]);
+        let bytes = bcs::to_bytes(&store).unwrap();
+        let decoded: InnerTemporaryStore = bcs::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, store);
+        assert_eq!(decoded.digest(), store.digest());
+
+        let mut changed = store.clone();
+        changed.lamport_version = changed.lamport_version.next();
+        assert_ne!(changed.digest(), store.digest());
+    }
+
+    #[test]
+    fn test_check_lamport_version() {
+        let owner = SuiAddress::default();
+        let lamport_version = SequenceNumber::from_u64(5);
+        let input = Object::with_owner_for_testing(owner);
+        let mut output = input.clone();
+        output
+            .data
+            .try_as_move_mut()
+            .unwrap()
+            .increment_version_to(lamport_version);
+        let package =
+            Object::new_package_for_testing(&[empty_module()], TransactionDigest::genesis(), [])
+                .unwrap();
+
+        let mut store = temporary_store(vec![output, package]);
+        store.lamport_version = lamport_version;
+        store
+            .input_objects
+            .insert(input.id(), Arc::new(input.clone()));
+        assert_eq!(store.check_lamport_version(), Ok(()));
+
+        let late_input =
+            Object::with_id_owner_version_for_testing(ObjectID::random(), lamport_version, owner);
+        let stale_output = Object::with_owner_for_testing(owner);
+        store
+            .input_objects
+            .insert(late_input.id(), Arc::new(late_input.clone()));
+        store
+            .written
+            .insert(stale_output.id(), stale_output.clone());
+        assert_eq!(
+            store.check_lamport_version(),
+            Err(vec![
+                LamportVersionViolation::WrittenVersionMismatch {
+                    id: stale_output.id(),
+                    version: stale_output.version(),
+                },
+                LamportVersionViolation::InputVersionNotBelow {
+                    id: late_input.id(),
+                    version: lamport_version,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_read_set() {
+        let owner = SuiAddress::default();
+        let mutable = Object::with_owner_for_testing(owner);
+        let read_only = Object::immutable_for_testing();
+        let child = Object::with_owner_for_testing(owner);
+        let read_child = Object::with_owner_for_testing(owner);
+
+        let mut store = temporary_store(vec![mutable.clone(), child.clone()]);
+        for input in [&mutable, &read_only] {
+            store
+                .input_objects
+                .insert(input.id(), Arc::new(input.clone()));
+        }
+        store.mutable_inputs.insert(
+            mutable.id(),
+            ((mutable.version(), mutable.digest()), mutable.owner),
+        );
+        for object in [&child, &read_child] {
+            store.loaded_runtime_objects.insert(
+                object.id(),
+                DynamicallyLoadedObjectMetadata {
+                    version: object.version(),
+                    digest: object.digest(),
+                    owner: object.owner,
+                    storage_rebate: object.storage_rebate,
+                    previous_transaction: object.previous_transaction,
+                },
+            );
+        }
+
+        let read_set = store.read_set();
+        assert_eq!(read_set.len(), 4);
+        assert_eq!(
+            read_set[&mutable.id()],
+            ReadObject {
+                version: mutable.version(),
+                digest: mutable.digest(),
+                owner: mutable.owner,
+                mutable: true,
+                loaded_at_runtime: false,
+            }
+        );
+        assert!(!read_set[&read_only.id()].mutable);
+        assert!(read_set[&child.id()].mutable && read_set[&child.id()].loaded_at_runtime);
+        assert!(!read_set[&read_child.id()].mutable);
+    }
+
+    #[test]
+    fn test_object_storage_changes() {
+        let owner = SuiAddress::default();
+        let mut mutated = Object::with_owner_for_testing(owner);
+        mutated.storage_rebate = 100;
+        let mut deleted = Object::with_owner_for_testing(owner);
+        deleted.storage_rebate = 50;
+        let mut read_only = Object::immutable_for_testing();
+        read_only.storage_rebate = 10;
+
+        let mut mutated_output = mutated.clone();
+        mutated_output.storage_rebate = 120;
+        let mut created = Object::with_owner_for_testing(owner);
+        created.storage_rebate = 80;
+        let mut store = temporary_store(vec![mutated_output.clone(), created.clone()]);
+        for input in [&mutated, &deleted, &read_only] {
+            store
+                .input_objects
+                .insert(input.id(), Arc::new(input.clone()));
+        }
+        for input in [&mutated, &deleted] {
+            store
+                .mutable_inputs
+                .insert(input.id(), ((input.version(), input.digest()), input.owner));
+        }
+
+        // The input that was deleted is known to the store without the effects.
+        let changes = store.object_storage_changes(&BTreeSet::new());
+        assert_eq!(changes.len(), 3);
+        assert!(!changes.contains_key(&read_only.id()));
+        let mutated_size = mutated.object_size_for_gas_metering();
+        assert_eq!(
+            changes[&mutated.id()],
+            ObjectStorageChange {
+                old_size: Some(mutated_size),
+                new_size: Some(mutated_size),
+                old_storage_rebate: 100,
+                new_storage_rebate: 120,
+            }
+        );
+        assert_eq!(changes[&deleted.id()].new_size, None);
+        assert_eq!(changes[&created.id()].old_size, None);
+
+        let deleted_ids = BTreeSet::from([deleted.id()]);
+        assert_eq!(
+            store.storage_size_delta(&deleted_ids),
+            created.object_size_for_gas_metering() as i64
+                - deleted.object_size_for_gas_metering() as i64
+        );
+        assert_eq!(
+            store.storage_rebate_delta(&deleted_ids),
+            120 - 100 + 80 - 50
+        );
+    }
+
+    #[test]
+    fn test_object_storage_changes_of_runtime_objects() {
+        let owner = SuiAddress::default();
+        let mut mutated_child = Object::with_owner_for_testing(owner);
+        mutated_child.storage_rebate = 30;
+        let mut deleted_child = Object::with_owner_for_testing(owner);
+        deleted_child.storage_rebate = 40;
+        let mut read_child = Object::with_owner_for_testing(owner);
+        read_child.storage_rebate = 60;
+
+        let mut mutated_child_output = mutated_child.clone();
+        mutated_child_output.storage_rebate = 35;
+        let mut store = temporary_store(vec![mutated_child_output]);
+        for object in [&mutated_child, &deleted_child, &read_child] {
+            store.loaded_runtime_objects.insert(
+                object.id(),
+                DynamicallyLoadedObjectMetadata {
+                    version: object.version(),
+                    digest: object.digest(),
+                    owner: object.owner,
+                    storage_rebate: object.storage_rebate,
+                    previous_transaction: object.previous_transaction,
+                },
+            );
+        }
+
+        let deleted = BTreeSet::from([deleted_child.id()]);
+        let changes = store.object_storage_changes(&deleted);
+        assert_eq!(changes.len(), 2);
+        assert!(!changes.contains_key(&read_child.id()));
+        assert_eq!(
+            changes[&mutated_child.id()],
+            ObjectStorageChange {
+                old_size: None,
+                new_size: Some(mutated_child.object_size_for_gas_metering()),
+                old_storage_rebate: 30,
+                new_storage_rebate: 35,
+            }
+        );
+        assert_eq!(
+            changes[&deleted_child.id()],
+            ObjectStorageChange {
+                old_size: None,
+                new_size: None,
+                old_storage_rebate: 40,
+                new_storage_rebate: 0,
+            }
+        );
+        assert_eq!(store.storage_rebate_delta(&deleted), 35 - 30 - 40);
+
+        // Without the effects, the deleted child cannot be told apart from the one only read.
+        assert_eq!(store.storage_rebate_delta(&BTreeSet::new()), 35 - 30);
+    }
+
+    #[test]
+    fn test_compute_balance_changes() {
+        // The zero address is ordered first.
+        let sender = SuiAddress::default();
+        let recipient = SuiAddress::from(ObjectID::random());
+        let gas_id = ObjectID::random();
+        let deleted_id = ObjectID::random();
+        let input_gas = Object::with_id_owner_gas_for_testing(gas_id, sender, 1_000);
+        let input_deleted = Object::with_id_owner_gas_for_testing(deleted_id, sender, 500);
+        // Read-only inputs do not change balances.
+        let read_only = Object::immutable_for_testing();
+
+        let mut store = temporary_store(vec![
+            Object::with_id_owner_gas_for_testing(gas_id, sender, 900),
+            Object::with_id_owner_gas_for_testing(ObjectID::random(), recipient, 500),
+        ]);
+        for input in [&input_gas, &input_deleted, &read_only] {
+            store
+                .input_objects
+                .insert(input.id(), Arc::new(input.clone()));
+        }
+        for input in [&input_gas, &input_deleted] {
+            store
+                .mutable_inputs
+                .insert(input.id(), ((input.version(), input.digest()), input.owner));
+        }
+
+        let changes = store.compute_balance_changes(&BTreeMap::new());
+        let sui = GAS::type_tag();
+        assert_eq!(
+            changes,
+            vec![
+                BalanceChange {
+                    owner: Owner::AddressOwner(sender),
+                    coin_type: sui.clone(),
+                    amount: -600,
+                },
+                BalanceChange {
+                    owner: Owner::AddressOwner(recipient),
+                    coin_type: sui,
+                    amount: 500,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_compute_balance_changes_with_runtime_objects() {
+        let owner = SuiAddress::default();
+        let parent = ObjectID::random();
+        let child_id = ObjectID::random();
+        let deleted_id = ObjectID::random();
+        // Coins held as dynamic object fields, loaded at runtime: one is split, the other is
+        // deleted, and the split off coin is sent to the owner.
+        let child_coin = |id, value| {
+            let mut coin = Object::with_id_owner_gas_for_testing(id, owner, value);
+            coin.owner = Owner::ObjectOwner(parent.into());
+            coin
+        };
+        let child = child_coin(child_id, 1_000);
+        let deleted = child_coin(deleted_id, 300);
+
+        let mut store = temporary_store(vec![
+            child_coin(child_id, 600),
+            Object::with_id_owner_gas_for_testing(ObjectID::random(), owner, 700),
+        ]);
+        for object in [&child, &deleted] {
+            store.loaded_runtime_objects.insert(
+                object.id(),
+                DynamicallyLoadedObjectMetadata {
+                    version: object.version(),
+                    digest: object.digest(),
+                    owner: object.owner,
+                    storage_rebate: object.storage_rebate,
+                    previous_transaction: object.previous_transaction,
+                },
+            );
+        }
+
+        let sui = GAS::type_tag();
+        let runtime_objects = [&child, &deleted]
+            .into_iter()
+            .map(|object| (object.id(), object.clone()))
+            .collect();
+        assert_eq!(
+            store.compute_balance_changes(&runtime_objects),
+            vec![
+                BalanceChange {
+                    owner: Owner::AddressOwner(owner),
+                    coin_type: sui.clone(),
+                    amount: 700,
+                },
+                BalanceChange {
+                    owner: Owner::ObjectOwner(parent.into()),
+                    coin_type: sui.clone(),
+                    amount: -700,
+                },
+            ]
+        );
+
+        // Without their previous contents, the changes to the coins loaded at runtime are left
+        // out, and only the coin sent to the owner is seen.
+        assert_eq!(
+            store.compute_balance_changes(&BTreeMap::new()),
+            vec![BalanceChange {
+                owner: Owner::AddressOwner(owner),
+                coin_type: sui,
+                amount: 700,
+            }]
+        );
+    }
+}
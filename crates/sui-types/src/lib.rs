@@ -28,6 +28,7 @@ pub mod error;
 pub mod accumulator;
 pub mod authenticator_state;
 pub mod balance;
+pub mod balance_change;
 pub mod base_types;
 pub mod clock;
 pub mod coin;
@@ -72,6 +73,7 @@ pub mod transaction;
 pub mod transfer;
 pub mod type_resolver;
 pub mod versioned;
+pub mod written_objects_diff;
 pub mod zk_login_authenticator;
 pub mod zk_login_util;
 
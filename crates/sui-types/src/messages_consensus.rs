@@ -53,6 +53,7 @@ pub enum ConsensusTransactionKey {
     // Key must include both id and jwk, because honest validators could be given multiple jwks for
     // the same id by malfunctioning providers.
     NewJWKFetched(Box<(AuthorityName, JwkId, JWK)>),
+    SoftBundle(Vec<TransactionDigest>),
 }
 
 impl Debug for ConsensusTransactionKey {
@@ -79,6 +80,7 @@ impl Debug for ConsensusTransactionKey {
                     jwk
                 )
             }
+            Self::SoftBundle(digests) => write!(f, "SoftBundle({:?})", digests),
         }
     }
 }
@@ -146,6 +148,54 @@ pub enum ConsensusTransactionKind {
     EndOfPublish(AuthorityName),
     CapabilityNotification(AuthorityCapabilities),
     NewJWKFetched(AuthorityName, JwkId, JWK),
+    /// User certificates that must be sequenced adjacently, in order, and are either all accepted
+    /// or all rejected. Only accepted when the `soft_bundle` feature is enabled.
+    SoftBundle(Vec<CertifiedTransaction>),
+}
+
+impl ConsensusTransactionKind {
+    /// Short name of the kind, used for metrics labels and logging. User certificates are split
+    /// by whether they touch shared objects, since those go through different execution paths.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::UserTransaction(certificate) => {
+                if certificate.contains_shared_object() {
+                    "shared_certificate"
+                } else {
+                    "owned_certificate"
+                }
+            }
+            Self::CheckpointSignature(_) => "checkpoint_signature",
+            Self::EndOfPublish(_) => "end_of_publish",
+            Self::CapabilityNotification(_) => "capability_notification",
+            Self::NewJWKFetched(_, _, _) => "new_jwk_fetched",
+            Self::SoftBundle(_) => "soft_bundle",
+        }
+    }
+
+    /// The authority that originated the message, for kinds that carry one. User certificates
+    /// can be submitted by any authority, so they have none.
+    pub fn origin_authority(&self) -> Option<AuthorityName> {
+        match self {
+            Self::UserTransaction(_) | Self::SoftBundle(_) => None,
+            Self::CheckpointSignature(data) => Some(data.summary.auth_sig().authority),
+            Self::EndOfPublish(authority) => Some(*authority),
+            Self::CapabilityNotification(capabilities) => Some(capabilities.authority),
+            Self::NewJWKFetched(authority, _, _) => Some(*authority),
+        }
+    }
+
+    /// Whether the message must be retried until sequenced, and warned about when it is not,
+    /// because the epoch cannot progress without it.
+    pub fn is_epoch_critical(&self) -> bool {
+        match self {
+            Self::EndOfPublish(_) | Self::CapabilityNotification(_) => true,
+            Self::UserTransaction(_)
+            | Self::CheckpointSignature(_)
+            | Self::NewJWKFetched(_, _, _)
+            | Self::SoftBundle(_) => false,
+        }
+    }
 }
 
 impl ConsensusTransaction {
@@ -221,6 +271,22 @@ impl ConsensusTransaction {
         }
     }
 
+    pub fn new_soft_bundle_message(
+        authority: &AuthorityName,
+        certificates: Vec<CertifiedTransaction>,
+    ) -> Self {
+        let mut hasher = DefaultHasher::new();
+        for certificate in &certificates {
+            certificate.digest().hash(&mut hasher);
+        }
+        authority.hash(&mut hasher);
+        let tracking_id = hasher.finish().to_le_bytes();
+        Self {
+            tracking_id,
+            kind: ConsensusTransactionKind::SoftBundle(certificates),
+        }
+    }
+
     pub fn get_tracking_id(&self) -> u64 {
         (&self.tracking_id[..])
             .read_u64::<BigEndian>()
@@ -251,6 +317,14 @@ impl ConsensusTransaction {
                     key.clone(),
                 )))
             }
+            ConsensusTransactionKind::SoftBundle(certificates) => {
+                ConsensusTransactionKey::SoftBundle(
+                    certificates
+                        .iter()
+                        .map(|certificate| *certificate.digest())
+                        .collect(),
+                )
+            }
         }
     }
 
@@ -261,6 +335,74 @@ impl ConsensusTransaction {
     pub fn is_end_of_publish(&self) -> bool {
         matches!(self.kind, ConsensusTransactionKind::EndOfPublish(_))
     }
+
+    pub fn is_checkpoint_signature(&self) -> bool {
+        matches!(self.kind, ConsensusTransactionKind::CheckpointSignature(_))
+    }
+
+    pub fn is_capability_notification(&self) -> bool {
+        matches!(
+            self.kind,
+            ConsensusTransactionKind::CapabilityNotification(_)
+        )
+    }
+
+    pub fn is_new_jwk_fetched(&self) -> bool {
+        matches!(self.kind, ConsensusTransactionKind::NewJWKFetched(..))
+    }
+
+    pub fn is_soft_bundle(&self) -> bool {
+        matches!(self.kind, ConsensusTransactionKind::SoftBundle(_))
+    }
+
+    pub fn as_user_certificate(&self) -> Option<&CertifiedTransaction> {
+        match &self.kind {
+            ConsensusTransactionKind::UserTransaction(certificate) => Some(certificate),
+            _ => None,
+        }
+    }
+
+    pub fn as_soft_bundle(&self) -> Option<&[CertifiedTransaction]> {
+        match &self.kind {
+            ConsensusTransactionKind::SoftBundle(certificates) => Some(certificates),
+            _ => None,
+        }
+    }
+
+    /// The user certificates carried by this transaction: the certificate of a user transaction,
+    /// or the certificates of a soft bundle, in order.
+    pub fn user_certificates(&self) -> &[CertifiedTransaction] {
+        match &self.kind {
+            ConsensusTransactionKind::UserTransaction(certificate) => {
+                std::slice::from_ref(&**certificate)
+            }
+            ConsensusTransactionKind::SoftBundle(certificates) => certificates,
+            _ => &[],
+        }
+    }
+
+    pub fn as_checkpoint_signature(&self) -> Option<&CheckpointSignatureMessage> {
+        match &self.kind {
+            ConsensusTransactionKind::CheckpointSignature(data) => Some(data),
+            _ => None,
+        }
+    }
+
+    pub fn as_capability_notification(&self) -> Option<&AuthorityCapabilities> {
+        match &self.kind {
+            ConsensusTransactionKind::CapabilityNotification(capabilities) => Some(capabilities),
+            _ => None,
+        }
+    }
+
+    pub fn kind_name(&self) -> &'static str {
+        self.kind.name()
+    }
+
+    /// Size in bytes of the BCS serialization of this transaction, as submitted to consensus.
+    pub fn serialized_size(&self) -> usize {
+        bcs::serialized_size(self).expect("Serializing consensus transaction should not fail")
+    }
 }
 
 #[test]
@@ -289,3 +431,58 @@ fn test_jwk_compatibility() {
     let id_bcs = bcs::to_bytes(&id).unwrap();
     assert_eq!(id_bcs, expected_id_bytes);
 }
+
+#[test]
+fn test_consensus_transaction_kind_helpers() {
+    let authority = AuthorityName::ZERO;
+
+    let end_of_publish = ConsensusTransaction::new_end_of_publish(authority);
+    assert!(end_of_publish.is_end_of_publish());
+    assert!(!end_of_publish.is_user_certificate());
+    assert!(end_of_publish.as_user_certificate().is_none());
+    assert!(end_of_publish.kind.is_epoch_critical());
+    assert_eq!(end_of_publish.kind_name(), "end_of_publish");
+    assert_eq!(end_of_publish.kind.origin_authority(), Some(authority));
+
+    let capabilities =
+        AuthorityCapabilities::new(authority, SupportedProtocolVersions::SYSTEM_DEFAULT, vec![]);
+    let notification = ConsensusTransaction::new_capability_notification(capabilities);
+    assert!(notification.is_capability_notification());
+    assert!(notification.kind.is_epoch_critical());
+    assert_eq!(
+        notification.as_capability_notification().unwrap().authority,
+        authority
+    );
+    assert_eq!(notification.kind_name(), "capability_notification");
+    assert_eq!(
+        notification.serialized_size(),
+        bcs::to_bytes(&notification).unwrap().len()
+    );
+
+    let jwk = ConsensusTransaction::new_jwk_fetched(
+        authority,
+        JwkId {
+            iss: "abc".to_string(),
+            kid: "def".to_string(),
+        },
+        JWK {
+            kty: "a".to_string(),
+            e: "b".to_string(),
+            n: "c".to_string(),
+            alg: "d".to_string(),
+        },
+    );
+    assert!(jwk.is_new_jwk_fetched());
+    assert!(!jwk.kind.is_epoch_critical());
+    assert!(jwk.as_checkpoint_signature().is_none());
+    assert_eq!(jwk.kind_name(), "new_jwk_fetched");
+
+    let bundle = ConsensusTransaction::new_soft_bundle_message(&authority, vec![]);
+    assert!(bundle.is_soft_bundle());
+    assert!(!bundle.is_user_certificate());
+    assert!(bundle.user_certificates().is_empty());
+    assert!(!bundle.kind.is_epoch_critical());
+    assert_eq!(bundle.kind.origin_authority(), None);
+    assert_eq!(bundle.kind_name(), "soft_bundle");
+    assert_eq!(bundle.key(), ConsensusTransactionKey::SoftBundle(vec![]));
+}
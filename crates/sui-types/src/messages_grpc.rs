@@ -7,7 +7,7 @@ use crate::effects::{
     SignedTransactionEffects, TransactionEvents, VerifiedSignedTransactionEffects,
 };
 use crate::object::{Object, ObjectFormatOptions};
-use crate::transaction::{SenderSignedData, SignedTransaction};
+use crate::transaction::{CertifiedTransaction, SenderSignedData, SignedTransaction};
 use move_core_types::value::MoveStructLayout;
 use serde::{Deserialize, Serialize};
 
@@ -175,6 +175,19 @@ pub struct HandleCertificateResponseV2 {
     pub fastpath_input_objects: Vec<Object>,
 }
 
+/// Certificates to be sequenced adjacently by consensus, in order, and either all executed or
+/// all rejected.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HandleSoftBundleCertificatesRequest {
+    pub certificates: Vec<CertifiedTransaction>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HandleSoftBundleCertificatesResponse {
+    /// The responses for each certificate of the bundle, in the same order.
+    pub responses: Vec<HandleCertificateResponseV2>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SubmitCertificateResponse {
     /// If transaction is already executed, return same result as handle_certificate
@@ -605,6 +605,26 @@ impl Owner {
     pub fn is_shared(&self) -> bool {
         matches!(self, Owner::Shared { .. })
     }
+
+    /// Returns the version at which the object became shared, or `None` if the object is not
+    /// shared.
+    pub fn initial_shared_version(&self) -> Option<SequenceNumber> {
+        match self {
+            Self::Shared {
+                initial_shared_version,
+            } => Some(*initial_shared_version),
+            Self::AddressOwner(_) | Self::ObjectOwner(_) | Self::Immutable => None,
+        }
+    }
+
+    /// Like `get_owner_address`, but returns `None` instead of an error for owners that do not
+    /// have an address (shared and immutable objects).
+    pub fn owner_address(&self) -> Option<SuiAddress> {
+        match self {
+            Self::AddressOwner(address) | Self::ObjectOwner(address) => Some(*address),
+            Self::Shared { .. } | Self::Immutable => None,
+        }
+    }
 }
 
 impl PartialEq<SuiAddress> for Owner {
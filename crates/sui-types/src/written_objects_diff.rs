@@ -0,0 +1,225 @@
+// Copyright (c) Mysten Labs, Inc.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compares the objects written by two executions of the same transaction, e.g. when a replay
+//! diverges from the original execution.
+
+use crate::base_types::{ObjectID, SequenceNumber};
+use crate::inner_temporary_store::WrittenObjects;
+use crate::object::{Object, ObjectFormatOptions, Owner};
+use crate::type_resolver::LayoutResolver;
+use move_core_types::value::{MoveStruct, MoveValue};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{Display, Formatter};
+
+/// The difference between two sets of written objects, relative to `before`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WrittenObjectsDiff {
+    /// Objects only written in `after`.
+    pub created: BTreeSet<ObjectID>,
+    /// Objects written in both, with different contents.
+    pub mutated: BTreeMap<ObjectID, ObjectDiff>,
+    /// Objects only written in `before`.
+    pub deleted: BTreeSet<ObjectID>,
+}
+
+impl WrittenObjectsDiff {
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.mutated.is_empty() && self.deleted.is_empty()
+    }
+}
+
+/// How one object differs between two sets of written objects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ObjectDiff {
+    pub version: (SequenceNumber, SequenceNumber),
+    /// Set if the owner changed.
+    pub owner: Option<(Owner, Owner)>,
+    /// Top-level fields that changed. Empty if the contents could not be laid out (e.g. for
+    /// packages), in which case `contents_changed` is the only summary of the contents.
+    pub fields: Vec<FieldDiff>,
+    pub contents_changed: bool,
+}
+
+/// A top-level field of a Move object, with its value in both sets of written objects.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldDiff {
+    pub name: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// Compares `before` and `after`, using `layout_resolver` to summarize the fields that changed in
+/// Move objects.
+pub fn diff(
+    before: &WrittenObjects,
+    after: &WrittenObjects,
+    layout_resolver: &mut dyn LayoutResolver,
+) -> WrittenObjectsDiff {
+    let mut changes = WrittenObjectsDiff {
+        created: after
+            .keys()
+            .filter(|id| !before.contains_key(id))
+            .copied()
+            .collect(),
+        deleted: before
+            .keys()
+            .filter(|id| !after.contains_key(id))
+            .copied()
+            .collect(),
+        ..Default::default()
+    };
+    for (id, old) in before {
+        let Some(new) = after.get(id) else {
+            continue;
+        };
+        if old == new {
+            continue;
+        }
+        let contents_changed = old.data != new.data;
+        changes.mutated.insert(
+            *id,
+            ObjectDiff {
+                version: (old.version(), new.version()),
+                owner: (old.owner != new.owner).then_some((old.owner, new.owner)),
+                fields: if contents_changed {
+                    field_diffs(old, new, layout_resolver).unwrap_or_default()
+                } else {
+                    vec![]
+                },
+                contents_changed,
+            },
+        );
+    }
+    changes
+}
+
+/// The top-level fields that differ between `old` and `new`, if both are Move objects of the same
+/// type that can be laid out.
+fn field_diffs(
+    old: &Object,
+    new: &Object,
+    layout_resolver: &mut dyn LayoutResolver,
+) -> Option<Vec<FieldDiff>> {
+    let (old, new) = (old.data.try_as_move()?, new.data.try_as_move()?);
+    if old.type_() != new.type_() {
+        return None;
+    }
+    let layout = layout_resolver
+        .get_layout(old, ObjectFormatOptions::default())
+        .ok()?;
+    let old_fields = named_fields(old.to_move_struct(&layout).ok()?)?;
+    let new_fields = named_fields(new.to_move_struct(&layout).ok()?)?;
+    Some(
+        old_fields
+            .into_iter()
+            .zip(new_fields)
+            .filter(|((_, before), (_, after))| before != after)
+            .map(|((name, before), (_, after))| FieldDiff {
+                name,
+                before: before.to_string(),
+                after: after.to_string(),
+            })
+            .collect(),
+    )
+}
+
+fn named_fields(value: MoveStruct) -> Option<Vec<(String, MoveValue)>> {
+    match value {
+        MoveStruct::WithFields(fields) | MoveStruct::WithTypes { fields, .. } => Some(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name.to_string(), value))
+                .collect(),
+        ),
+        MoveStruct::Runtime(_) => None,
+    }
+}
+
+impl Display for WrittenObjectsDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No differences");
+        }
+        for id in &self.created {
+            writeln!(f, "Created {id}")?;
+        }
+        for (id, object) in &self.mutated {
+            writeln!(
+                f,
+                "Mutated {id} (version {} -> {})",
+                object.version.0, object.version.1
+            )?;
+            if let Some((before, after)) = &object.owner {
+                writeln!(f, "  owner: {before} -> {after}")?;
+            }
+            for field in &object.fields {
+                writeln!(f, "  {}: {} -> {}", field.name, field.before, field.after)?;
+            }
+            if object.contents_changed && object.fields.is_empty() {
+                writeln!(f, "  contents changed")?;
+            }
+        }
+        for id in &self.deleted {
+            writeln!(f, "Deleted {id}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base_types::SuiAddress;
+    use crate::error::SuiError;
+    use crate::gas_coin::GasCoin;
+    use crate::object::MoveObject;
+    use move_core_types::value::MoveStructLayout;
+
+    struct GasCoinLayout;
+
+    impl LayoutResolver for GasCoinLayout {
+        fn get_layout(
+            &mut self,
+            _object: &MoveObject,
+            _format: ObjectFormatOptions,
+        ) -> Result<MoveStructLayout, SuiError> {
+            Ok(GasCoin::layout())
+        }
+    }
+
+    fn written(objects: &[&Object]) -> WrittenObjects {
+        objects.iter().map(|o| (o.id(), (*o).clone())).collect()
+    }
+
+    #[test]
+    fn test_diff_written_objects() {
+        let owner = SuiAddress::default();
+        let unchanged = Object::with_owner_for_testing(owner);
+        let deleted = Object::with_owner_for_testing(owner);
+        let created = Object::with_owner_for_testing(owner);
+        let gas_id = ObjectID::random();
+        let old_gas = Object::with_id_owner_gas_for_testing(gas_id, owner, 100);
+        let new_gas = Object::with_id_owner_gas_for_testing(gas_id, SuiAddress::from(gas_id), 90);
+
+        let before = written(&[&unchanged, &deleted, &old_gas]);
+        let after = written(&[&unchanged, &created, &new_gas]);
+        let changes = diff(&before, &after, &mut GasCoinLayout);
+
+        assert_eq!(changes.created, BTreeSet::from([created.id()]));
+        assert_eq!(changes.deleted, BTreeSet::from([deleted.id()]));
+        assert_eq!(changes.mutated.len(), 1);
+        let gas = &changes.mutated[&gas_id];
+        assert_eq!(gas.owner, Some((old_gas.owner, new_gas.owner)));
+        assert!(gas.contents_changed);
+        assert_eq!(gas.fields.len(), 1);
+        assert_eq!(gas.fields[0].name, "balance");
+
+        let printed = changes.to_string();
+        assert!(printed.contains(&format!("Created {}", created.id())));
+        assert!(printed.contains(&format!("Deleted {}", deleted.id())));
+        assert!(printed.contains("  balance: "));
+
+        assert!(diff(&before, &before, &mut GasCoinLayout).is_empty());
+    }
+}
@@ -1171,6 +1171,11 @@ impl DBBatch {
         }
     }
 
+    /// Size in bytes of the operations accumulated in the batch so far
+    pub fn size_in_bytes(&self) -> usize {
+        self.batch.size_in_bytes()
+    }
+
     /// Consume the batch and write its operations to the database
     #[instrument(level = "trace", skip_all, err)]
     pub fn write(self) -> Result<(), TypedStoreError> {
@@ -0,0 +1,68 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use criterion_cpu_time::PosixTime;
+use language_benchmarks::{
+    measurement::{allocation_measurement, cpu_time_measurement, record_allocation, Allocations},
+    move_compiler::bench_expansion,
+};
+use std::alloc::{GlobalAlloc, Layout, System};
+
+/// Counts every allocation, so the expansion benchmarks can report allocations as well as time
+struct CountingAllocator;
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        record_allocation();
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        record_allocation();
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+//
+// Move compiler benchmarks
+//
+
+fn expansion_source(c: &mut Criterion<PosixTime>) {
+    bench_expansion(c, "time", false);
+}
+
+fn expansion_dependency(c: &mut Criterion<PosixTime>) {
+    bench_expansion(c, "time", true);
+}
+
+fn expansion_source_allocations(c: &mut Criterion<Allocations>) {
+    bench_expansion(c, "allocations", false);
+}
+
+fn expansion_dependency_allocations(c: &mut Criterion<Allocations>) {
+    bench_expansion(c, "allocations", true);
+}
+
+criterion_group!(
+    name = compiler_benches;
+    config = cpu_time_measurement();
+    targets = expansion_source,
+    expansion_dependency
+);
+
+criterion_group!(
+    name = compiler_allocation_benches;
+    config = allocation_measurement();
+    targets = expansion_source_allocations,
+    expansion_dependency_allocations
+);
+
+criterion_main!(compiler_benches, compiler_allocation_benches);
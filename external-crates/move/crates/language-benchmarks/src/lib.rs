@@ -5,4 +5,5 @@
 #![forbid(unsafe_code)]
 
 pub mod measurement;
+pub mod move_compiler;
 pub mod move_vm;
@@ -2,8 +2,12 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use criterion::Criterion;
+use criterion::{
+    measurement::{Measurement, ValueFormatter},
+    Criterion, Throughput,
+};
 use criterion_cpu_time::PosixTime;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 pub fn cpu_time_measurement() -> Criterion<PosixTime> {
     Criterion::default().with_measurement(PosixTime::UserAndSystemTime)
@@ -12,3 +16,74 @@ pub fn cpu_time_measurement() -> Criterion<PosixTime> {
 pub fn wall_time_measurement() -> Criterion {
     Criterion::default()
 }
+
+/// Number of heap allocations made so far. Only counted when the benchmark binary installs a
+/// global allocator that calls `record_allocation`
+static ALLOCATIONS: AtomicU64 = AtomicU64::new(0);
+
+pub fn record_allocation() {
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn allocation_measurement() -> Criterion<Allocations> {
+    Criterion::default().with_measurement(Allocations)
+}
+
+/// Measures the number of heap allocations made by the benchmarked routine
+pub struct Allocations;
+
+impl Measurement for Allocations {
+    type Intermediate = u64;
+    type Value = u64;
+
+    fn start(&self) -> u64 {
+        ALLOCATIONS.load(Ordering::Relaxed)
+    }
+
+    fn end(&self, start: u64) -> u64 {
+        ALLOCATIONS.load(Ordering::Relaxed) - start
+    }
+
+    fn add(&self, v1: &u64, v2: &u64) -> u64 {
+        v1 + v2
+    }
+
+    fn zero(&self) -> u64 {
+        0
+    }
+
+    fn to_f64(&self, value: &u64) -> f64 {
+        *value as f64
+    }
+
+    fn formatter(&self) -> &dyn ValueFormatter {
+        &AllocationsFormatter
+    }
+}
+
+struct AllocationsFormatter;
+
+impl ValueFormatter for AllocationsFormatter {
+    fn scale_values(&self, _typical_value: f64, _values: &mut [f64]) -> &'static str {
+        "allocs"
+    }
+
+    fn scale_throughputs(
+        &self,
+        _typical_value: f64,
+        throughput: &Throughput,
+        values: &mut [f64],
+    ) -> &'static str {
+        let n = match throughput {
+            Throughput::Bytes(n) | Throughput::Elements(n) => *n as f64,
+        };
+        for value in values {
+            *value /= n;
+        }
+        "allocs/elem"
+    }
+
+    fn scale_for_machines(&self, _values: &mut [f64]) -> &'static str {
+        "allocs"
+    }
+}
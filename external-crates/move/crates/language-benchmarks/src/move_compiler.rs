@@ -0,0 +1,52 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use criterion::{measurement::Measurement, BatchSize, Criterion};
+use move_compiler::{Compiler, SteppedCompiler, PASS_EXPANSION, PASS_PARSER};
+use once_cell::sync::Lazy;
+use std::path::PathBuf;
+
+static MOVE_BENCH_SRC_PATH: Lazy<PathBuf> = Lazy::new(|| {
+    vec![env!("CARGO_MANIFEST_DIR"), "src", "bench.move"]
+        .into_iter()
+        .collect()
+});
+
+/// Benchmarks the expansion of the Move stdlib, compiled either as source or as a dependency of
+/// `bench.move`. Parsing is done outside of the measurement. The measurement name keeps the results
+/// of different measurements of the same pass apart.
+pub fn bench_expansion<M: Measurement + 'static>(
+    c: &mut Criterion<M>,
+    measurement: &str,
+    as_dependency: bool,
+) {
+    let kind = if as_dependency {
+        "dependency"
+    } else {
+        "source"
+    };
+    c.bench_function(&format!("expansion_{kind}_{measurement}"), |b| {
+        b.iter_batched(
+            || parse(as_dependency),
+            |stepped| stepped.run::<PASS_EXPANSION>().expect("Error expanding..."),
+            BatchSize::LargeInput,
+        )
+    });
+}
+
+fn parse(as_dependency: bool) -> SteppedCompiler<'static, PASS_PARSER> {
+    let (targets, deps) = if as_dependency {
+        (
+            vec![MOVE_BENCH_SRC_PATH.to_str().unwrap().to_owned()],
+            move_stdlib::move_stdlib_files(),
+        )
+    } else {
+        (move_stdlib::move_stdlib_files(), vec![])
+    };
+    let (_files, res) =
+        Compiler::from_files(targets, deps, move_stdlib::move_stdlib_named_addresses())
+            .run::<PASS_PARSER>()
+            .expect("Error parsing...");
+    let (_comments, stepped) = res.expect("Error parsing...");
+    stepped
+}
@@ -14,7 +14,7 @@ use move_compiler::{
 };
 use move_coverage::coverage_map::{output_map_to_file, CoverageMap};
 use move_package::{compilation::build_plan::BuildPlan, BuildConfig};
-use move_unit_test::UnitTestingConfig;
+use move_unit_test::{test_runner::DEFAULT_RAND_NUM_ITERS, UnitTestingConfig};
 use move_vm_test_utils::gas_schedule::CostTable;
 use std::{
     collections::HashMap,
@@ -69,6 +69,16 @@ pub struct Test {
     /// Collect coverage information for later use with the various `move coverage` subcommands
     #[clap(long = "coverage")]
     pub compute_coverage: bool,
+    /// Seed used to generate the arguments of #[random_test]s, which are then run only once
+    #[clap(long = "seed")]
+    pub seed: Option<u64>,
+    /// Number of times each #[random_test] is run, with arguments generated from a random seed
+    #[clap(
+        long = "rand-num-iters",
+        default_value_t = DEFAULT_RAND_NUM_ITERS,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub rand_num_iters: u64,
 }
 
 impl Test {
@@ -89,6 +99,8 @@ impl Test {
             check_stackless_vm,
             verbose_mode,
             compute_coverage,
+            seed,
+            rand_num_iters,
         } = self;
         let unit_test_config = UnitTestingConfig {
             gas_limit,
@@ -98,6 +110,8 @@ impl Test {
             report_statistics,
             check_stackless_vm,
             verbose: verbose_mode,
+            seed,
+            rand_num_iters,
             ..UnitTestingConfig::default_with_bound(None)
         };
         let result = run_move_unit_tests(
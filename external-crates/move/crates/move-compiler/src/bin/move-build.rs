@@ -7,8 +7,14 @@
 use clap::*;
 use move_command_line_common::files::verify_and_create_named_address_mapping;
 use move_compiler::{
-    command_line::{self as cli},
+    command_line::{
+        self as cli,
+        attestation::parse_severity_threshold,
+        compiler::{env_named_address_resolver, output_transaction_templates, ModuleNaming},
+    },
+    diagnostics::{self, codes::Severity},
     shared::{self, Flags, NumericalAddress},
+    typing::api_report::{self, ApiReport},
 };
 
 #[derive(Debug, Parser)]
@@ -61,6 +67,78 @@ pub struct Options {
     )]
     pub named_addresses: Vec<(String, NumericalAddress)>,
 
+    /// Named addresses whose values are read from the environment variables `MOVE_ADDRESS_<NAME>`,
+    /// for packages that do not assign them a value
+    #[clap(
+        name = "DEFERRED_ADDRESSES",
+        long = cli::DEFERRED_ADDRESSES,
+        num_args(1..),
+    )]
+    pub deferred_addresses: Vec<String>,
+
+    /// After a successful build, write an attestation that no diagnostic at or above the
+    /// attestation severity was reported to this path. The build fails if one was.
+    #[clap(
+        name = "PATH_TO_ATTESTATION",
+        long = cli::ATTESTATION,
+    )]
+    pub attestation: Option<String>,
+
+    /// The lowest severity preventing the attestation: 'warning', 'error', or 'bug'
+    #[clap(
+        name = "ATTESTATION_SEVERITY",
+        long = cli::ATTESTATION_SEVERITY,
+        default_value = "warning",
+        value_parser = parse_severity_threshold,
+    )]
+    pub attestation_severity: Severity,
+
+    /// Emit one artifact set per bytecode version, each in a `bytecode_v<version>` sub directory
+    /// of the output directory. Overrides the bytecode version of the flags
+    #[clap(
+        name = "BYTECODE_VERSIONS",
+        long = cli::BYTECODE_VERSIONS,
+        value_delimiter = ',',
+    )]
+    pub bytecode_versions: Vec<u32>,
+
+    /// How the files of the compiled modules are named. With 'qualified', a manifest of the modules
+    /// in dependency order is written to the output directory
+    #[clap(
+        long = cli::MODULE_NAMING,
+        value_enum,
+        default_value_t = ModuleNaming::Indexed,
+    )]
+    pub module_naming: ModuleNaming,
+
+    /// Instead of compiling, translate the scripts into programmable transaction block templates,
+    /// written as JSON to the output directory
+    #[clap(long = cli::TRANSACTION_TEMPLATES)]
+    pub transaction_templates: bool,
+
+    /// Instead of compiling, write a report of the public API of the sources to this path, as JSON
+    #[clap(
+        name = "PATH_TO_API_REPORT",
+        long = cli::API_REPORT,
+    )]
+    pub api_report: Option<String>,
+
+    /// Instead of compiling, compare the public API of the sources with the report at this path,
+    /// printing the changes. Fails if a change is breaking
+    #[clap(
+        name = "PATH_TO_PREVIOUS_API_REPORT",
+        long = cli::CHECK_API,
+    )]
+    pub check_api: Option<String>,
+
+    /// Instead of compiling, write a report of the functions of the sources callable from outside
+    /// their module to this path, as JSON, and print it
+    #[clap(
+        name = "PATH_TO_VISIBILITY_REPORT",
+        long = cli::VISIBILITY_REPORT,
+    )]
+    pub visibility_report: Option<String>,
+
     #[clap(flatten)]
     pub flags: Flags,
 }
@@ -73,21 +151,82 @@ pub fn main() -> anyhow::Result<()> {
         emit_source_map,
         flags,
         named_addresses,
+        deferred_addresses,
+        attestation,
+        attestation_severity,
+        mut bytecode_versions,
+        module_naming,
+        transaction_templates,
+        api_report,
+        check_api,
+        visibility_report,
     } = Options::parse();
 
     let interface_files_dir = format!("{}/generated_interface_files", out_dir);
     let named_addr_map = verify_and_create_named_address_mapping(named_addresses)?;
-    let bytecode_version = flags.bytecode_version();
-    let (files, compiled_units) =
-        move_compiler::Compiler::from_files(source_files, dependencies, named_addr_map)
-            .set_interface_files_dir(interface_files_dir)
-            .set_flags(flags)
-            .build_and_report()?;
+    if bytecode_versions.is_empty() {
+        bytecode_versions.extend(flags.bytecode_version())
+    }
+    let report_named_addresses = flags.named_address_report();
+    let compiler = move_compiler::Compiler::from_files(source_files, dependencies, named_addr_map)
+        .set_interface_files_dir(interface_files_dir)
+        .add_deferred_named_addresses(deferred_addresses)
+        .set_named_address_resolver(env_named_address_resolver())
+        .set_flags(flags);
+    if report_named_addresses {
+        print!("{}", compiler.named_address_report());
+    }
+    if transaction_templates {
+        let (files, res) = compiler.generate_transaction_templates()?;
+        let templates = diagnostics::unwrap_or_report_diagnostics(&files, res);
+        return output_transaction_templates(&templates, &out_dir);
+    }
+    if api_report.is_some() || check_api.is_some() {
+        let (files, res) = compiler.generate_api_report()?;
+        let report = diagnostics::unwrap_or_report_diagnostics(&files, res);
+        if let Some(path) = api_report {
+            std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+        }
+        if let Some(path) = check_api {
+            let previous: ApiReport = serde_json::from_str(&std::fs::read_to_string(path)?)?;
+            let changes = api_report::compare(&previous, &report);
+            for change in &changes {
+                println!("{}", change);
+            }
+            let breaking = changes.iter().filter(|change| change.is_breaking()).count();
+            anyhow::ensure!(breaking == 0, "{} breaking API changes", breaking);
+        }
+        return Ok(());
+    }
+    if let Some(path) = visibility_report {
+        let (files, res) = compiler.generate_visibility_report()?;
+        let report = diagnostics::unwrap_or_report_diagnostics(&files, res);
+        print!("{}", report);
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+        return Ok(());
+    }
+    let (files, compiled_units, attestation) = match attestation {
+        None => {
+            let (files, compiled_units) = compiler.build_and_report()?;
+            (files, compiled_units, None)
+        }
+        Some(path) => {
+            let (files, compiled_units, attestation) =
+                compiler.build_and_attest(attestation_severity)?;
+            (files, compiled_units, Some((path, attestation)))
+        }
+    };
     move_compiler::output_compiled_units(
-        bytecode_version,
+        &bytecode_versions,
+        module_naming,
         emit_source_map,
         files,
         compiled_units,
         &out_dir,
-    )
+    )?;
+    // only attest to builds whose artifacts were all written
+    if let Some((path, attestation)) = attestation {
+        attestation.write(path)?;
+    }
+    Ok(())
 }
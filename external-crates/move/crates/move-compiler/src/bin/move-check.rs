@@ -7,7 +7,8 @@
 use clap::*;
 use move_command_line_common::files::verify_and_create_named_address_mapping;
 use move_compiler::{
-    command_line::{self as cli},
+    command_line::{self as cli, compiler::env_named_address_resolver},
+    diagnostics::{codes::Severity, report_diagnostics, report_warnings},
     shared::{self, Flags, NumericalAddress},
 };
 
@@ -53,6 +54,20 @@ pub struct Options {
     )]
     pub named_addresses: Vec<(String, NumericalAddress)>,
 
+    /// Named addresses whose values are read from the environment variables `MOVE_ADDRESS_<NAME>`,
+    /// for packages that do not assign them a value
+    #[clap(
+        name = "DEFERRED_ADDRESSES",
+        long = cli::DEFERRED_ADDRESSES,
+        num_args(1..),
+    )]
+    pub deferred_addresses: Vec<String>,
+
+    /// Rewrite the source files in place, applying the changes needed to move them to the 2024
+    /// edition. Changes that cannot be made automatically are reported as diagnostics
+    #[clap(long = cli::MIGRATE)]
+    pub migrate: bool,
+
     #[clap(flatten)]
     pub flags: Flags,
 }
@@ -64,11 +79,34 @@ pub fn main() -> anyhow::Result<()> {
         out_dir,
         flags,
         named_addresses,
+        deferred_addresses,
+        migrate,
     } = Options::parse();
     let named_addr_map = verify_and_create_named_address_mapping(named_addresses)?;
-    let _files = move_compiler::Compiler::from_files(source_files, dependencies, named_addr_map)
+    let report_named_addresses = flags.named_address_report();
+    let compiler = move_compiler::Compiler::from_files(source_files, dependencies, named_addr_map)
         .set_interface_files_dir_opt(out_dir)
-        .set_flags(flags)
-        .check_and_report()?;
+        .add_deferred_named_addresses(deferred_addresses)
+        .set_named_address_resolver(env_named_address_resolver())
+        .set_flags(flags);
+    if report_named_addresses {
+        print!("{}", compiler.named_address_report());
+    }
+    if migrate {
+        let migration = compiler.generate_migration_patch(None)?;
+        for (fname, contents) in migration.render_output() {
+            std::fs::write(fname.as_str(), contents)?;
+        }
+        let unresolved = migration.unresolved().clone();
+        if unresolved
+            .max_severity()
+            .is_some_and(|sev| sev > Severity::Warning)
+        {
+            report_diagnostics(migration.files(), unresolved)
+        }
+        report_warnings(migration.files(), unresolved);
+        return Ok(());
+    }
+    let _files = compiler.check_and_report()?;
     Ok(())
 }
@@ -0,0 +1,107 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! The registry of the constants used as abort codes. A constant is an abort code if a function of
+//! its module aborts with it, as found in the CFGIR pass. The registry maps each of them to its
+//! value and its doc comment, so that tools can translate an abort code into the name of the
+//! constant, e.g. `MoveAbort(.., 7)` into `EInsufficientBalance`.
+
+use crate::{
+    cfgir::ast::{self as G, BasicBlocks},
+    compiled_unit::{AnnotatedCompiledModule, AnnotatedCompiledUnit},
+    hlir::ast::{Command_, UnannotatedExp_},
+    parser::ast::ConstantName,
+    shared::{unique_map::UniqueMap, CompilationEnv},
+};
+use move_core_types::value::MoveValue;
+use move_symbol_pool::Symbol;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Name of the registry file emitted in the output directory
+pub const ABORT_CODES_FILE: &str = "abort_codes.json";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbortCode {
+    pub name: Symbol,
+    pub value: u64,
+    /// The doc comment of the constant, if any
+    pub doc: Option<String>,
+}
+
+#[derive(Serialize)]
+struct RegistryEntry<'a> {
+    value: u64,
+    doc: Option<&'a str>,
+}
+
+/// Adds the constants that the blocks of a function abort with to `constants`
+pub(crate) fn collect_abort_constants(
+    blocks: &BasicBlocks,
+    constants: &mut BTreeSet<ConstantName>,
+) {
+    let aborted_with = blocks
+        .values()
+        .flatten()
+        .filter_map(|cmd| match &cmd.value {
+            Command_::Abort(e) => match &e.exp.value {
+                UnannotatedExp_::Constant(name) => Some(*name),
+                _ => None,
+            },
+            _ => None,
+        });
+    constants.extend(aborted_with)
+}
+
+/// The abort codes of a module, given the constants used as abort codes. Constants whose value
+/// could not be computed are skipped.
+pub(crate) fn abort_codes(
+    env: &CompilationEnv,
+    constants: &UniqueMap<ConstantName, G::Constant>,
+    aborted_with: &BTreeSet<ConstantName>,
+) -> Vec<AbortCode> {
+    aborted_with
+        .iter()
+        .filter_map(|name| {
+            let constant = constants.get(name)?;
+            let Some(MoveValue::U64(value)) = constant.value else {
+                return None;
+            };
+            Some(AbortCode {
+                name: name.0.value,
+                value,
+                doc: env.doc_comment(constant.loc).map(str::to_string),
+            })
+        })
+        .collect()
+}
+
+/// Renders the registry of the abort codes of the modules as JSON, keyed by `<module>::<CONST>`
+/// where the module is given by its address and name. `None` if no module has an abort code.
+pub fn registry_json(units: &[AnnotatedCompiledUnit]) -> Option<String> {
+    let entries: BTreeMap<String, RegistryEntry> = units
+        .iter()
+        .filter_map(|unit| match unit {
+            AnnotatedCompiledUnit::Module(m) => Some(m),
+            AnnotatedCompiledUnit::Script(_) => None,
+        })
+        .flat_map(|m: &AnnotatedCompiledModule| {
+            let module = format!(
+                "{}::{}",
+                m.named_module.address.into_inner().to_hex_literal(),
+                m.named_module.name
+            );
+            m.abort_codes.iter().map(move |code| {
+                let entry = RegistryEntry {
+                    value: code.value,
+                    doc: code.doc.as_deref(),
+                };
+                (format!("{}::{}", module, code.name), entry)
+            })
+        })
+        .collect();
+    if entries.is_empty() {
+        return None;
+    }
+    Some(serde_json::to_string_pretty(&entries).unwrap())
+}
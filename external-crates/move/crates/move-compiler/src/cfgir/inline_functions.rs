@@ -0,0 +1,399 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Opt-in inlining of small private functions into their callers in the same module. A function
+//! can be inlined if it is private, has no type parameters, no references among its locals and
+//! return values, and its body is a single block ending in a return. Calls are only inlined when
+//! their result is assigned, ignored or returned, and each caller can only grow by a fixed budget.
+//! Callers are then optimized again, with the constants of the module folded into their bodies.
+
+use crate::{
+    cfgir::{self, ast as G, cfg::MutForwardCFG},
+    expansion::ast::ModuleIdent,
+    hlir::ast::{
+        BasicBlock, Command, Command_, Exp, LValue, LValue_, SingleType, SingleType_, Type_,
+        UnannotatedExp_, Value, Var, Visibility,
+    },
+    parser::ast::{ConstantName, FunctionName},
+    shared::unique_map::UniqueMap,
+};
+use move_ir_types::location::*;
+use std::{collections::BTreeMap, mem};
+
+/// The maximum size of an inlined function body, in expressions
+const MAX_INLINED_SIZE: usize = 24;
+/// The maximum number of expressions added to a caller by inlining
+const MAX_CALLER_GROWTH: usize = 256;
+
+const INLINED_NAME_DELIM: &str = "#inlined#";
+
+struct Inlinable {
+    locals: UniqueMap<Var, SingleType>,
+    parameters: Vec<(Var, SingleType)>,
+    // The body without its final return
+    commands: BasicBlock,
+    result: Exp,
+    size: usize,
+}
+
+//**************************************************************************************************
+// Entry
+//**************************************************************************************************
+
+pub(super) fn module(
+    level: u8,
+    mident: ModuleIdent,
+    constant_values: &UniqueMap<ConstantName, Value>,
+    functions: &mut UniqueMap<FunctionName, G::Function>,
+) {
+    // callees are inlined as they were before any inlining, so recursion cannot unfold
+    let inlinable: BTreeMap<FunctionName, Inlinable> = functions
+        .key_cloned_iter()
+        .filter_map(|(name, f)| Some((name, inlinable(f)?)))
+        .collect();
+    for (name, f) in functions.key_cloned_iter_mut() {
+        function(level, mident, &inlinable, constant_values, name, f)
+    }
+}
+
+fn inlinable(f: &G::Function) -> Option<Inlinable> {
+    let G::FunctionBody_::Defined { locals, blocks, .. } = &f.body.value else {
+        return None;
+    };
+    if f.visibility != Visibility::Internal
+        || !f.signature.type_parameters.is_empty()
+        || blocks.len() != 1
+        || locals.iter().any(|(_, _, t)| is_ref(t))
+    {
+        return None;
+    }
+    let returns_ref = match &f.signature.return_type.value {
+        Type_::Unit => false,
+        Type_::Single(t) => is_ref(t),
+        Type_::Multiple(ts) => ts.iter().any(is_ref),
+    };
+    if returns_ref {
+        return None;
+    }
+    let mut commands = blocks.values().next().unwrap().clone();
+    let Some(sp!(_, Command_::Return { exp: result, .. })) = commands.pop_back() else {
+        return None;
+    };
+    let mut size = exp_size(&result)?;
+    for cmd in &commands {
+        size += command_size(cmd)?;
+    }
+    if size > MAX_INLINED_SIZE {
+        return None;
+    }
+    Some(Inlinable {
+        locals: locals.clone(),
+        parameters: f.signature.parameters.clone(),
+        commands,
+        result,
+        size,
+    })
+}
+
+fn is_ref(t: &SingleType) -> bool {
+    matches!(&t.value, SingleType_::Ref(_, _))
+}
+
+fn function(
+    level: u8,
+    mident: ModuleIdent,
+    inlinable: &BTreeMap<FunctionName, Inlinable>,
+    constant_values: &UniqueMap<ConstantName, Value>,
+    name: FunctionName,
+    f: &mut G::Function,
+) {
+    let G::FunctionBody_::Defined {
+        locals,
+        start,
+        block_info,
+        blocks,
+    } = &mut f.body.value
+    else {
+        return;
+    };
+    let mut context = Context {
+        mident,
+        stack: vec![name],
+        inlinable,
+        locals,
+        growth: 0,
+        inlined_count: 0,
+    };
+    for block in blocks.values_mut() {
+        *block = context.inline_calls(mem::take(block));
+    }
+    if context.inlined_count == 0 && constant_values.is_empty() {
+        return;
+    }
+    let (mut cfg, _infinite_loop_starts, _diags) =
+        MutForwardCFG::new(*start, blocks, block_info.iter());
+    cfgir::optimize(level, &f.signature, locals, constant_values, &mut cfg);
+    block_info.retain(|lbl, _info| blocks.contains_key(lbl));
+}
+
+//**************************************************************************************************
+// Inlining
+//**************************************************************************************************
+
+struct Context<'a> {
+    mident: ModuleIdent,
+    // The caller, followed by the functions being inlined into it
+    stack: Vec<FunctionName>,
+    inlinable: &'a BTreeMap<FunctionName, Inlinable>,
+    locals: &'a mut UniqueMap<Var, SingleType>,
+    growth: usize,
+    inlined_count: usize,
+}
+
+impl<'a> Context<'a> {
+    fn inline_calls(&mut self, commands: BasicBlock) -> BasicBlock {
+        let mut block = BasicBlock::new();
+        for cmd in commands {
+            match self.inline_call(cmd) {
+                Ok(inlined) => block.extend(inlined),
+                Err(cmd) => block.push_back(cmd),
+            }
+        }
+        block
+    }
+
+    /// Inlines the call of `cmd`, if any, returning the command back if it could not be inlined
+    fn inline_call(&mut self, cmd: Command) -> Result<BasicBlock, Command> {
+        let sp!(cloc, cmd_) = cmd;
+        let call = match &cmd_ {
+            Command_::Assign(_, e)
+            | Command_::IgnoreAndPop { exp: e, .. }
+            | Command_::Return { exp: e, .. } => match &e.exp.value {
+                UnannotatedExp_::ModuleCall(call)
+                    if call.module == self.mident && !self.stack.contains(&call.name) =>
+                {
+                    Some(call)
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+        let Some((name, callee)) = call.and_then(|call| {
+            let callee = self.inlinable.get(&call.name)?;
+            Some((call.name, callee))
+        }) else {
+            return Err(sp(cloc, cmd_));
+        };
+        if self.growth + callee.size > MAX_CALLER_GROWTH {
+            return Err(sp(cloc, cmd_));
+        }
+        self.growth += callee.size;
+
+        let renaming = self.declare_locals(callee);
+        let mut inlined = BasicBlock::new();
+        let (rebuild, arguments): (Box<dyn FnOnce(Exp) -> Command_>, _) = match cmd_ {
+            Command_::Assign(lvalues, e) => (
+                Box::new(move |e| Command_::Assign(lvalues, e)),
+                call_arguments(e),
+            ),
+            Command_::IgnoreAndPop { pop_num, exp } => (
+                Box::new(move |exp| Command_::IgnoreAndPop { pop_num, exp }),
+                call_arguments(exp),
+            ),
+            Command_::Return { from_user, exp } => (
+                Box::new(move |exp| Command_::Return { from_user, exp }),
+                call_arguments(exp),
+            ),
+            _ => unreachable!(),
+        };
+        if let Some(bind) = bind_parameters(cloc, &renaming, &callee.parameters, arguments) {
+            inlined.push_back(bind)
+        }
+        for cmd in &callee.commands {
+            let mut cmd = cmd.clone();
+            rename_command(&renaming, &mut cmd);
+            inlined.push_back(cmd)
+        }
+        let mut result = callee.result.clone();
+        rename_exp(&renaming, &mut result);
+        inlined.push_back(sp(cloc, rebuild(result)));
+        // calls in the inlined body are inlined in turn, within the budget of the caller
+        self.stack.push(name);
+        let inlined = self.inline_calls(inlined);
+        self.stack.pop();
+        Ok(inlined)
+    }
+
+    /// Declares fresh locals in the caller for the locals of the callee
+    fn declare_locals(&mut self, callee: &Inlinable) -> BTreeMap<Var, Var> {
+        let count = self.inlined_count;
+        self.inlined_count += 1;
+        callee
+            .locals
+            .key_cloned_iter()
+            .map(|(v, t)| {
+                let fresh = format!("{}{}{}", v.0.value, INLINED_NAME_DELIM, count);
+                let new_v = Var(sp(v.0.loc, fresh.into()));
+                self.locals
+                    .add(new_v, t.clone())
+                    .expect("ICE inlined local name collision");
+                (v, new_v)
+            })
+            .collect()
+    }
+}
+
+fn call_arguments(e: Exp) -> Vec<Exp> {
+    match e.exp.value {
+        UnannotatedExp_::ModuleCall(call) => call.arguments,
+        _ => unreachable!(),
+    }
+}
+
+fn bind_parameters(
+    loc: Loc,
+    renaming: &BTreeMap<Var, Var>,
+    parameters: &[(Var, SingleType)],
+    mut arguments: Vec<Exp>,
+) -> Option<Command> {
+    assert!(parameters.len() == arguments.len());
+    let lvalues = parameters
+        .iter()
+        .map(|(v, t)| {
+            let lvalue_ = LValue_::Var(renaming[v], Box::new(t.clone()));
+            sp(v.0.loc, lvalue_) as LValue
+        })
+        .collect();
+    let rhs = match arguments.len() {
+        0 => return None,
+        1 => arguments.pop().unwrap(),
+        _ => {
+            let tys = arguments
+                .iter()
+                .map(|arg| match &arg.ty.value {
+                    Type_::Single(t) => t.clone(),
+                    _ => panic!("ICE argument should have a single type"),
+                })
+                .collect();
+            Exp {
+                ty: sp(loc, Type_::Multiple(tys)),
+                exp: sp(loc, UnannotatedExp_::Multiple(arguments)),
+            }
+        }
+    };
+    Some(sp(loc, Command_::Assign(lvalues, rhs)))
+}
+
+//**************************************************************************************************
+// Size
+//**************************************************************************************************
+
+// None if the command cannot be inlined
+fn command_size(sp!(_, cmd_): &Command) -> Option<usize> {
+    use Command_ as C;
+    match cmd_ {
+        C::Assign(_, e) | C::IgnoreAndPop { exp: e, .. } | C::Abort(e) => exp_size(e),
+        C::Mutate(el, er) => Some(exp_size(el)? + exp_size(er)?),
+        C::Return { .. } | C::Break | C::Continue | C::Jump { .. } | C::JumpIf { .. } => None,
+    }
+}
+
+// None if the expression cannot be inlined
+fn exp_size(e: &Exp) -> Option<usize> {
+    use UnannotatedExp_ as E;
+    let inner = match &e.exp.value {
+        E::Unit { .. }
+        | E::Value(_)
+        | E::Move { .. }
+        | E::Copy { .. }
+        | E::Constant(_)
+        | E::BorrowLocal(_, _)
+        | E::Unreachable => 0,
+        E::Spec(_, _) | E::UnresolvedError => return None,
+        E::ModuleCall(call) => exps_size(&call.arguments)?,
+        E::Vector(_, _, _, es) | E::Multiple(es) => exps_size(es)?,
+        E::Pack(_, _, fields) => exps_size(fields.iter().map(|(_, _, e)| e))?,
+        E::Freeze(e)
+        | E::Dereference(e)
+        | E::UnaryExp(_, e)
+        | E::Borrow(_, e, _, _)
+        | E::Cast(e, _) => exp_size(e)?,
+        E::BinopExp(e1, _, e2) => exp_size(e1)? + exp_size(e2)?,
+    };
+    Some(inner + 1)
+}
+
+fn exps_size<'a>(es: impl IntoIterator<Item = &'a Exp>) -> Option<usize> {
+    es.into_iter().map(exp_size).sum()
+}
+
+//**************************************************************************************************
+// Renaming
+//**************************************************************************************************
+
+fn rename_command(renaming: &BTreeMap<Var, Var>, sp!(_, cmd_): &mut Command) {
+    use Command_ as C;
+    match cmd_ {
+        C::Assign(ls, e) => {
+            ls.iter_mut().for_each(|l| rename_lvalue(renaming, l));
+            rename_exp(renaming, e)
+        }
+        C::Mutate(el, er) => {
+            rename_exp(renaming, el);
+            rename_exp(renaming, er)
+        }
+        C::Abort(e) | C::Return { exp: e, .. } | C::IgnoreAndPop { exp: e, .. } => {
+            rename_exp(renaming, e)
+        }
+        C::JumpIf { cond, .. } => rename_exp(renaming, cond),
+        C::Break | C::Continue | C::Jump { .. } => (),
+    }
+}
+
+fn rename_lvalue(renaming: &BTreeMap<Var, Var>, sp!(_, l_): &mut LValue) {
+    match l_ {
+        LValue_::Ignore => (),
+        LValue_::Var(v, _) => rename_var(renaming, v),
+        LValue_::Unpack(_, _, fields) => fields
+            .iter_mut()
+            .for_each(|(_, l)| rename_lvalue(renaming, l)),
+    }
+}
+
+fn rename_exp(renaming: &BTreeMap<Var, Var>, e: &mut Exp) {
+    use UnannotatedExp_ as E;
+    match &mut e.exp.value {
+        E::Unit { .. }
+        | E::Value(_)
+        | E::Constant(_)
+        | E::Unreachable
+        | E::UnresolvedError
+        | E::Spec(_, _) => (),
+        E::Move { var, .. } | E::Copy { var, .. } | E::BorrowLocal(_, var) => {
+            rename_var(renaming, var)
+        }
+        E::ModuleCall(call) => call
+            .arguments
+            .iter_mut()
+            .for_each(|e| rename_exp(renaming, e)),
+        E::Vector(_, _, _, es) | E::Multiple(es) => {
+            es.iter_mut().for_each(|e| rename_exp(renaming, e))
+        }
+        E::Pack(_, _, fields) => fields
+            .iter_mut()
+            .for_each(|(_, _, e)| rename_exp(renaming, e)),
+        E::Freeze(e)
+        | E::Dereference(e)
+        | E::UnaryExp(_, e)
+        | E::Borrow(_, e, _, _)
+        | E::Cast(e, _) => rename_exp(renaming, e),
+        E::BinopExp(e1, _, e2) => {
+            rename_exp(renaming, e1);
+            rename_exp(renaming, e2)
+        }
+    }
+}
+
+fn rename_var(renaming: &BTreeMap<Var, Var>, v: &mut Var) {
+    *v = renaming[v]
+}
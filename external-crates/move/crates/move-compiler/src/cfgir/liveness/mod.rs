@@ -79,6 +79,13 @@ fn analyze(
     (final_invariants, liveness.states)
 }
 
+/// Updates `live`, the locals live after `cmd`, to the locals live before it
+pub(super) fn command_live_locals(live: &mut BTreeSet<Var>, cmd: &Command) {
+    let mut state = LivenessState(std::mem::take(live));
+    command(&mut state, cmd);
+    *live = state.0
+}
+
 fn command(state: &mut LivenessState, sp!(_, cmd_): &Command) {
     use Command_ as C;
     match cmd_ {
@@ -2,10 +2,12 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod abort_codes;
 pub mod absint;
 pub mod ast;
 mod borrows;
 pub mod cfg;
+mod inline_functions;
 mod liveness;
 mod locals;
 mod remove_no_ops;
@@ -0,0 +1,205 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    cfgir::cfg::MutForwardCFG,
+    hlir::ast::{
+        BasicBlocks, Command, Command_, Exp, FunctionSignature, LValue, LValue_, SingleType,
+        UnannotatedExp_, Value, Var,
+    },
+    parser::ast::{Ability_, ConstantName},
+    shared::unique_map::UniqueMap,
+};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Within a block, replaces the uses of a local assigned another local, or a value, with a copy of
+/// that local or with that value. Locals that are borrowed are never propagated, as they can be
+/// modified through a reference.
+/// returns true if anything changed
+pub fn optimize(
+    _signature: &FunctionSignature,
+    locals: &UniqueMap<Var, SingleType>,
+    _constants: &UniqueMap<ConstantName, Value>,
+    cfg: &mut MutForwardCFG,
+) -> bool {
+    let borrowed = borrowed_locals(cfg.blocks_mut());
+    let mut changed = false;
+    for block in cfg.blocks_mut().values_mut() {
+        // the copy or value held by each local
+        let mut copies = BTreeMap::new();
+        for cmd in block {
+            changed = propagate_cmd(locals, &borrowed, &mut copies, cmd) || changed;
+        }
+    }
+    changed
+}
+
+fn propagate_cmd(
+    locals: &UniqueMap<Var, SingleType>,
+    borrowed: &BTreeSet<Var>,
+    copies: &mut BTreeMap<Var, UnannotatedExp_>,
+    sp!(_, cmd_): &mut Command,
+) -> bool {
+    use Command_ as C;
+    use UnannotatedExp_ as E;
+
+    // a copy is no longer available once its source is moved, even in the same command
+    let mut moved = BTreeSet::new();
+    command_exps(cmd_, |e| moved_locals(&mut moved, e));
+    copies.retain(|_, source| !matches!(source, E::Copy { var, .. } if moved.contains(var)));
+
+    let mut changed = false;
+    command_exps(cmd_, |e| changed = substitute(copies, e) || changed);
+
+    let mut assigned = BTreeSet::new();
+    if let C::Assign(ls, _) = cmd_ {
+        ls.iter().for_each(|l| assigned_locals(&mut assigned, l))
+    }
+    copies.retain(|v, source| {
+        !assigned.contains(v) && !matches!(source, E::Copy { var, .. } if assigned.contains(var))
+    });
+
+    if let C::Assign(ls, e) = cmd_ {
+        if let [sp!(_, LValue_::Var(v, _))] = &ls[..] {
+            let propagated = match &e.exp.value {
+                E::Value(_) => true,
+                E::Copy { var, .. } | E::Move { var, .. } => var != v && !borrowed.contains(var),
+                _ => false,
+            };
+            if propagated && !borrowed.contains(v) && has_copy_and_drop(locals, v) {
+                // the source is copied instead, so that it remains available for the uses of `v`
+                if let E::Move { var, .. } = e.exp.value {
+                    e.exp.value = E::Copy {
+                        from_user: false,
+                        var,
+                    };
+                    changed = true
+                }
+                copies.insert(*v, e.exp.value.clone());
+            }
+        }
+    }
+    changed
+}
+
+fn has_copy_and_drop(locals: &UniqueMap<Var, SingleType>, v: &Var) -> bool {
+    let ty = locals.get(v).unwrap();
+    let abilities = ty.value.abilities(ty.loc);
+    abilities.has_ability_(Ability_::Copy) && abilities.has_ability_(Ability_::Drop)
+}
+
+fn substitute(copies: &BTreeMap<Var, UnannotatedExp_>, e: &mut Exp) -> bool {
+    use UnannotatedExp_ as E;
+    match &mut e.exp.value {
+        E::Copy { var, .. } | E::Move { var, .. } => match copies.get(var) {
+            Some(E::Copy { var: source, .. }) => {
+                e.exp.value = E::Copy {
+                    from_user: false,
+                    var: *source,
+                };
+                true
+            }
+            Some(source) => {
+                e.exp.value = source.clone();
+                true
+            }
+            None => false,
+        },
+        _ => {
+            let mut changed = false;
+            sub_exps(e, |e| changed = substitute(copies, e) || changed);
+            changed
+        }
+    }
+}
+
+//**************************************************************************************************
+// Locals
+//**************************************************************************************************
+
+fn borrowed_locals(blocks: &mut BasicBlocks) -> BTreeSet<Var> {
+    fn exp(borrowed: &mut BTreeSet<Var>, e: &mut Exp) {
+        use UnannotatedExp_ as E;
+        match &e.exp.value {
+            E::BorrowLocal(_, var) => {
+                borrowed.insert(*var);
+            }
+            E::Spec(_, used_locals) => borrowed.extend(used_locals.keys().copied()),
+            _ => sub_exps(e, |e| exp(borrowed, e)),
+        }
+    }
+
+    let mut borrowed = BTreeSet::new();
+    for cmd in blocks.values_mut().flatten() {
+        command_exps(&mut cmd.value, |e| exp(&mut borrowed, e));
+    }
+    borrowed
+}
+
+fn moved_locals(moved: &mut BTreeSet<Var>, e: &mut Exp) {
+    match &e.exp.value {
+        UnannotatedExp_::Move { var, .. } => {
+            moved.insert(*var);
+        }
+        _ => sub_exps(e, |e| moved_locals(moved, e)),
+    }
+}
+
+fn assigned_locals(assigned: &mut BTreeSet<Var>, sp!(_, l_): &LValue) {
+    match l_ {
+        LValue_::Ignore => (),
+        LValue_::Var(v, _) => {
+            assigned.insert(*v);
+        }
+        LValue_::Unpack(_, _, fields) => fields
+            .iter()
+            .for_each(|(_, l)| assigned_locals(assigned, l)),
+    }
+}
+
+//**************************************************************************************************
+// Traversal
+//**************************************************************************************************
+
+fn command_exps(cmd_: &mut Command_, mut f: impl FnMut(&mut Exp)) {
+    use Command_ as C;
+    match cmd_ {
+        C::Assign(_, e)
+        | C::Abort(e)
+        | C::Return { exp: e, .. }
+        | C::IgnoreAndPop { exp: e, .. }
+        | C::JumpIf { cond: e, .. } => f(e),
+        C::Mutate(el, er) => {
+            f(er);
+            f(el)
+        }
+        C::Break | C::Continue | C::Jump { .. } => (),
+    }
+}
+
+fn sub_exps(e: &mut Exp, mut f: impl FnMut(&mut Exp)) {
+    use UnannotatedExp_ as E;
+    match &mut e.exp.value {
+        E::Unit { .. }
+        | E::Value(_)
+        | E::Move { .. }
+        | E::Copy { .. }
+        | E::Constant(_)
+        | E::BorrowLocal(_, _)
+        | E::Spec(_, _)
+        | E::Unreachable
+        | E::UnresolvedError => (),
+        E::ModuleCall(call) => call.arguments.iter_mut().for_each(f),
+        E::Vector(_, _, _, es) | E::Multiple(es) => es.iter_mut().for_each(f),
+        E::Pack(_, _, fields) => fields.iter_mut().for_each(|(_, _, e)| f(e)),
+        E::Freeze(e)
+        | E::Dereference(e)
+        | E::UnaryExp(_, e)
+        | E::Borrow(_, e, _, _)
+        | E::Cast(e, _) => f(e),
+        E::BinopExp(e1, _, e2) => {
+            f(e1);
+            f(e2)
+        }
+    }
+}
@@ -0,0 +1,135 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    cfgir::{
+        cfg::{MutForwardCFG, CFG},
+        liveness::command_live_locals,
+    },
+    hlir::ast::{
+        Command, Command_, Exp, FunctionSignature, LValue, LValue_, Label, SingleType,
+        UnannotatedExp_, Value, Var,
+    },
+    parser::ast::{Ability_, ConstantName},
+    shared::unique_map::UniqueMap,
+};
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+/// Removes the assignments to locals that are not used afterwards. The assigned expression is
+/// kept, and popped, unless it has no effect.
+/// returns true if anything changed
+pub fn optimize(
+    _signature: &FunctionSignature,
+    locals: &UniqueMap<Var, SingleType>,
+    _constants: &UniqueMap<ConstantName, Value>,
+    cfg: &mut MutForwardCFG,
+) -> bool {
+    let live_out = live_out(cfg);
+    let mut changed = false;
+    for (lbl, block) in cfg.blocks_mut() {
+        let mut live = live_out[lbl].clone();
+        let mut new_block = VecDeque::new();
+        for mut cmd in std::mem::take(block).into_iter().rev() {
+            changed = eliminate_cmd(locals, &live, &mut cmd) || changed;
+            if removable(&cmd) {
+                changed = true;
+                continue;
+            }
+            command_live_locals(&mut live, &cmd);
+            new_block.push_front(cmd);
+        }
+        *block = new_block;
+    }
+    changed
+}
+
+/// The locals live at the end of each block
+fn live_out(cfg: &MutForwardCFG) -> BTreeMap<Label, BTreeSet<Var>> {
+    let blocks = cfg.blocks();
+    let block_live_out = |live_in: &BTreeMap<Label, BTreeSet<Var>>, lbl: Label| {
+        let mut live = BTreeSet::new();
+        for succ in cfg.successors(lbl) {
+            live.extend(live_in[succ].iter().copied())
+        }
+        live
+    };
+    let mut live_in: BTreeMap<Label, BTreeSet<Var>> =
+        blocks.keys().map(|lbl| (*lbl, BTreeSet::new())).collect();
+    let mut changed = true;
+    while changed {
+        changed = false;
+        for (lbl, block) in blocks.iter().rev() {
+            let mut live = block_live_out(&live_in, *lbl);
+            for cmd in block.iter().rev() {
+                command_live_locals(&mut live, cmd)
+            }
+            if live != live_in[lbl] {
+                live_in.insert(*lbl, live);
+                changed = true
+            }
+        }
+    }
+    blocks
+        .keys()
+        .map(|lbl| (*lbl, block_live_out(&live_in, *lbl)))
+        .collect()
+}
+
+// Ignores the locals assigned by the command that are not live afterwards
+fn eliminate_cmd(
+    locals: &UniqueMap<Var, SingleType>,
+    live: &BTreeSet<Var>,
+    sp!(_, cmd_): &mut Command,
+) -> bool {
+    match cmd_ {
+        Command_::Assign(ls, _) => ls.iter_mut().fold(false, |changed, l| {
+            eliminate_lvalue(locals, live, l) || changed
+        }),
+        _ => false,
+    }
+}
+
+fn eliminate_lvalue(
+    locals: &UniqueMap<Var, SingleType>,
+    live: &BTreeSet<Var>,
+    l: &mut LValue,
+) -> bool {
+    match &mut l.value {
+        LValue_::Var(v, _) if !live.contains(v) && has_drop(locals, v) => {
+            l.value = LValue_::Ignore;
+            true
+        }
+        LValue_::Ignore | LValue_::Var(_, _) => false,
+        LValue_::Unpack(_, _, fields) => fields.iter_mut().fold(false, |changed, (_, l)| {
+            eliminate_lvalue(locals, live, l) || changed
+        }),
+    }
+}
+
+fn has_drop(locals: &UniqueMap<Var, SingleType>, v: &Var) -> bool {
+    let ty = locals.get(v).unwrap();
+    ty.value.abilities(ty.loc).has_ability_(Ability_::Drop)
+}
+
+fn removable(sp!(_, cmd_): &Command) -> bool {
+    match cmd_ {
+        Command_::Assign(ls, e) => {
+            ls.iter().all(|l| matches!(l.value, LValue_::Ignore)) && no_effect(e)
+        }
+        _ => false,
+    }
+}
+
+// An expression that cannot abort, and does not move a local. Its value can be dropped, as it was
+// assigned to ignored locals
+fn no_effect(e: &Exp) -> bool {
+    use UnannotatedExp_ as E;
+    match &e.exp.value {
+        E::Unit { .. } | E::Value(_) | E::Constant(_) | E::Copy { .. } | E::BorrowLocal(_, _) => {
+            true
+        }
+        E::Freeze(e) | E::Borrow(_, e, _, _) => no_effect(e),
+        E::Multiple(es) => es.iter().all(no_effect),
+        _ => false,
+    }
+}
@@ -2,8 +2,11 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod constant_fold;
+mod copy_propagation;
+mod dead_stores;
 mod eliminate_locals;
 mod inline_blocks;
+mod simplify_branches;
 mod simplify_jumps;
 
 use crate::{
@@ -25,18 +28,35 @@ const OPTIMIZATIONS: &[Optimization] = &[
     inline_blocks::optimize,
 ];
 
+/// The optimizations run from optimization level 1
+const FULL_OPTIMIZATIONS: &[Optimization] = &[
+    eliminate_locals::optimize,
+    constant_fold::optimize,
+    copy_propagation::optimize,
+    dead_stores::optimize,
+    simplify_jumps::optimize,
+    simplify_branches::optimize,
+    inline_blocks::optimize,
+];
+
 pub fn optimize(
+    level: u8,
     signature: &FunctionSignature,
     locals: &UniqueMap<Var, SingleType>,
     constants: &UniqueMap<ConstantName, Value>,
     cfg: &mut MutForwardCFG,
 ) {
+    let optimizations = if level == 0 {
+        OPTIMIZATIONS
+    } else {
+        FULL_OPTIMIZATIONS
+    };
     let mut count = 0;
-    for optimization in OPTIMIZATIONS.iter().cycle() {
+    for optimization in optimizations.iter().cycle() {
         // if we have fully cycled through the list of optimizations without a change,
         // it is safe to stop
-        if count >= OPTIMIZATIONS.len() {
-            debug_assert_eq!(count, OPTIMIZATIONS.len());
+        if count >= optimizations.len() {
+            debug_assert_eq!(count, optimizations.len());
             break;
         }
 
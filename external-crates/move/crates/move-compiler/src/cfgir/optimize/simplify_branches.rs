@@ -0,0 +1,95 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    cfgir::cfg::MutForwardCFG,
+    hlir::ast::{
+        BasicBlocks, Command, Command_, FunctionSignature, Label, SingleType, UnannotatedExp_,
+        Value, Var,
+    },
+    parser::ast::{ConstantName, UnaryOp_},
+    shared::unique_map::UniqueMap,
+};
+use std::collections::BTreeMap;
+
+/// Simplifies the branches of the function:
+/// - Jumps to a block that only jumps are redirected to the target of that block
+/// - A branch on a negated condition branches on the condition, with its targets swapped
+/// - A branch to the same block on both sides becomes a jump, if the condition has no effect
+/// returns true if anything changed
+pub fn optimize(
+    _signature: &FunctionSignature,
+    _locals: &UniqueMap<Var, SingleType>,
+    _constants: &UniqueMap<ConstantName, Value>,
+    cfg: &mut MutForwardCFG,
+) -> bool {
+    let forwarding = forwarding_blocks(cfg.blocks());
+    let mut changed = false;
+    for block in cfg.blocks_mut().values_mut() {
+        if let Some(cmd) = block.back_mut() {
+            changed = simplify_cmd(&forwarding, cmd) || changed;
+        }
+    }
+    if changed {
+        let _dead_blocks = cfg.recompute();
+    }
+    changed
+}
+
+// The blocks that only jump to another block, and their targets
+fn forwarding_blocks(blocks: &BasicBlocks) -> BTreeMap<Label, Label> {
+    blocks
+        .iter()
+        .filter(|(_lbl, block)| block.len() == 1)
+        .filter_map(|(lbl, block)| match &block[0].value {
+            Command_::Jump { target, .. } if target != lbl => Some((*lbl, *target)),
+            _ => None,
+        })
+        .collect()
+}
+
+fn simplify_cmd(forwarding: &BTreeMap<Label, Label>, sp!(_, cmd_): &mut Command) -> bool {
+    use Command_ as C;
+    use UnannotatedExp_ as E;
+    let forward = |lbl: &mut Label| match forwarding.get(lbl) {
+        Some(target) => {
+            *lbl = *target;
+            true
+        }
+        None => false,
+    };
+    match cmd_ {
+        C::Jump { target, .. } => forward(target),
+        C::JumpIf {
+            cond,
+            if_true,
+            if_false,
+        } => {
+            let mut changed = forward(if_true);
+            changed = forward(if_false) || changed;
+            if matches!(&cond.exp.value, E::UnaryExp(sp!(_, UnaryOp_::Not), _)) {
+                let E::UnaryExp(_, inner) =
+                    std::mem::replace(&mut cond.exp.value, E::UnresolvedError)
+                else {
+                    unreachable!()
+                };
+                *cond = *inner;
+                std::mem::swap(if_true, if_false);
+                changed = true;
+            }
+            let no_effect = matches!(
+                &cond.exp.value,
+                E::Value(_) | E::Constant(_) | E::Copy { .. } | E::Move { .. }
+            );
+            if if_true == if_false && no_effect {
+                *cmd_ = C::Jump {
+                    from_user: false,
+                    target: *if_true,
+                };
+                changed = true;
+            }
+            changed
+        }
+        _ => false,
+    }
+}
@@ -4,9 +4,10 @@
 
 use crate::{
     cfgir::{
-        self,
+        self, abort_codes,
         ast::{self as G, BasicBlock, BasicBlocks, BlockInfo},
         cfg::{ImmForwardCFG, MutForwardCFG},
+        inline_functions,
     },
     diag,
     diagnostics::Diagnostics,
@@ -46,6 +47,10 @@ struct Context<'env> {
     // Used for populating block_info
     loop_bounds: BTreeMap<Label, G::LoopInfo>,
     block_info: Vec<(Label, BlockInfo)>,
+    // The constants the functions of the current module abort with
+    abort_constants: BTreeSet<ConstantName>,
+    // The folded constants of each module, used when inlining functions
+    constant_values: BTreeMap<ModuleIdent, UniqueMap<ConstantName, Value>>,
 }
 
 impl<'env> Context<'env> {
@@ -80,6 +85,8 @@ impl<'env> Context<'env> {
             block_ordering: BTreeMap::new(),
             block_info: vec![],
             loop_bounds: BTreeMap::new(),
+            abort_constants: BTreeSet::new(),
+            constant_values: BTreeMap::new(),
         }
     }
 
@@ -169,8 +176,16 @@ pub fn program(
     let modules = modules(&mut context, hmodules);
     let scripts = scripts(&mut context, hscripts);
 
-    let program = G::Program { modules, scripts };
+    let mut program = G::Program { modules, scripts };
     visit_program(&mut context, &program);
+    // inlining runs after the visitors, so that the inlined code is not checked a second time
+    if context.env.flags().inline_functions() && !context.env.has_errors() {
+        for (mident, mdef) in program.modules.key_cloned_iter_mut() {
+            let constant_values = context.constant_values.remove(&mident).unwrap_or_default();
+            let level = context.env.flags().optimization_level();
+            inline_functions::module(level, mident, &constant_values, &mut mdef.functions);
+        }
+    }
     program
 }
 
@@ -189,6 +204,7 @@ fn module(
     module_ident: ModuleIdent,
     mdef: H::ModuleDefinition,
 ) -> (ModuleIdent, G::ModuleDefinition) {
+    context.env.set_current_module(module_ident.loc);
     let H::ModuleDefinition {
         warning_filter,
         package_name,
@@ -202,8 +218,16 @@ fn module(
     } = mdef;
 
     context.env.add_warning_filter_scope(warning_filter.clone());
-    let constants = constants(context, Some(module_ident), hconstants);
+    let (constants, constant_values) = constants(context, Some(module_ident), hconstants);
+    if context.env.flags().inline_functions() {
+        context
+            .constant_values
+            .insert(module_ident, constant_values);
+    }
     let functions = hfunctions.map(|name, f| function(context, Some(module_ident), name, f));
+    let abort_constants = mem::take(&mut context.abort_constants);
+    let abort_codes = abort_codes::abort_codes(context.env, &constants, &abort_constants);
+    context.env.add_abort_codes(module_ident, abort_codes);
     context.env.pop_warning_filter_scope();
     (
         module_ident,
@@ -241,8 +265,9 @@ fn script(context: &mut Context, hscript: H::Script) -> G::Script {
         function_name,
         function: hfunction,
     } = hscript;
+    context.env.set_current_module(function_name.0.loc);
     context.env.add_warning_filter_scope(warning_filter.clone());
-    let constants = constants(context, None, hconstants);
+    let (constants, _) = constants(context, None, hconstants);
     let function = function(context, None, function_name, hfunction);
     context.env.pop_warning_filter_scope();
     G::Script {
@@ -264,7 +289,10 @@ fn constants(
     context: &mut Context,
     module: Option<ModuleIdent>,
     mut consts: UniqueMap<ConstantName, H::Constant>,
-) -> UniqueMap<ConstantName, G::Constant> {
+) -> (
+    UniqueMap<ConstantName, G::Constant>,
+    UniqueMap<ConstantName, Value>,
+) {
     // Traverse the constants and compute the dependency graph between constants: if one mentions
     // another, an edge is added between them.
     let mut graph = DiGraphMap::new();
@@ -352,7 +380,7 @@ fn constants(
             .expect("ICE constant name collision");
     }
 
-    out_map
+    (out_map, constant_values)
 }
 
 fn dependent_constants(constant: &H::Constant) -> BTreeSet<ConstantName> {
@@ -441,6 +469,7 @@ fn constant(
     name: ConstantName,
     c: H::Constant,
 ) -> G::Constant {
+    context.env.set_current_member(name.0.loc);
     let H::Constant {
         warning_filter,
         index,
@@ -531,7 +560,8 @@ fn constant_(
         "{}",
         ICE_MSG
     );
-    cfgir::optimize(&fake_signature, &locals, constant_values, &mut cfg);
+    let level = context.env.flags().optimization_level();
+    cfgir::optimize(level, &fake_signature, &locals, constant_values, &mut cfg);
 
     if blocks.len() != 1 {
         context.env.add_diag(diag!(
@@ -605,6 +635,7 @@ fn function(
     name: FunctionName,
     f: H::Function,
 ) -> G::Function {
+    context.env.set_current_member(name.0.loc);
     let H::Function {
         warning_filter,
         index,
@@ -669,7 +700,11 @@ fn function_body(
             cfgir::refine_inference_and_verify(context.env, &function_context, &mut cfg);
             // do not optimize if there are errors, warnings are okay
             if !context.env.has_errors() {
-                cfgir::optimize(signature, &locals, &UniqueMap::new(), &mut cfg);
+                let level = context.env.flags().optimization_level();
+                cfgir::optimize(level, signature, &locals, &UniqueMap::new(), &mut cfg);
+            }
+            if module.is_some() {
+                abort_codes::collect_abort_constants(&blocks, &mut context.abort_constants);
             }
 
             let block_info = block_info
@@ -20,7 +20,7 @@ use crate::{
 };
 use move_ir_types::location::*;
 
-pub type AbsIntVisitorObj = Box<dyn AbstractInterpreterVisitor>;
+pub type AbsIntVisitorObj = Box<dyn AbstractInterpreterVisitor + Send>;
 
 pub trait AbstractInterpreterVisitor {
     fn verify(
@@ -33,7 +33,7 @@ pub trait AbstractInterpreterVisitor {
 
     fn visitor(self) -> Visitor
     where
-        Self: 'static + Sized,
+        Self: 'static + Sized + Send,
     {
         Visitor::AbsIntVisitor(Box::new(self))
     }
@@ -0,0 +1,170 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Attestations that a build succeeded without any diagnostic at or above a given severity. The
+//! attestation records the flags used, digests of every input file and compiled unit, and a
+//! summary of the diagnostics that were reported. It is serialized deterministically, so that it
+//! can be signed and checked into release pipelines.
+
+use crate::{
+    compiled_unit::{AnnotatedCompiledUnit, CompiledUnitEnum, NamedCompiledModule},
+    diagnostics::{codes::Severity, Diagnostics, FilesSourceText},
+    shared::Flags,
+};
+use anyhow::bail;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{collections::BTreeMap, fs, path::Path};
+
+//**************************************************************************************************
+// Types
+//**************************************************************************************************
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BuildAttestation {
+    /// Version of the compiler that produced the build
+    pub compiler_version: String,
+    pub flags: AttestedFlags,
+    /// No diagnostic at or above this severity was reported during the build
+    pub severity_threshold: String,
+    /// SHA-256 digests of the source and dependency files, by path
+    pub files: BTreeMap<String, String>,
+    /// SHA-256 digests of the serialized compiled units, by fully qualified name
+    pub compiled_units: BTreeMap<String, String>,
+    pub diagnostics: DiagnosticsSummary,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AttestedFlags {
+    pub test: bool,
+    pub verify: bool,
+    pub sources_shadow_deps: bool,
+    /// The name of the `DuplicateModuleStrategy` used
+    pub duplicate_modules: String,
+    pub warnings_are_errors: bool,
+    pub bytecode_version: Option<u32>,
+    pub inline_functions: bool,
+    pub optimization_level: u8,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DiagnosticsSummary {
+    /// Number of diagnostics reported, by severity
+    pub by_severity: BTreeMap<String, usize>,
+    /// Number of diagnostics reported, by diagnostic code
+    pub by_code: BTreeMap<String, usize>,
+}
+
+//**************************************************************************************************
+// Impls
+//**************************************************************************************************
+
+impl BuildAttestation {
+    /// Attests to a successful build, failing if `diags` contains any diagnostic at or above
+    /// `threshold`, or if warnings were silenced and thus could not have been checked.
+    pub fn new(
+        flags: &Flags,
+        threshold: Severity,
+        files: &FilesSourceText,
+        compiled_units: &[AnnotatedCompiledUnit],
+        diags: &Diagnostics,
+    ) -> anyhow::Result<Self> {
+        if flags.silence_warnings() {
+            bail!("Cannot attest to a build with silenced warnings");
+        }
+        if let Some(max) = diags.max_severity() {
+            if max >= threshold {
+                bail!(
+                    "Cannot attest to the build: {} diagnostic(s) reported, at least one with \
+                    severity '{}'",
+                    diags.len(),
+                    severity_name(max)
+                );
+            }
+        }
+
+        let flags = AttestedFlags {
+            test: flags.is_testing(),
+            verify: flags.is_verification(),
+            sources_shadow_deps: flags.sources_shadow_deps(),
+            duplicate_modules: flags.duplicate_module_strategy().name().to_string(),
+            warnings_are_errors: flags.warnings_are_errors(),
+            bytecode_version: flags.bytecode_version(),
+            inline_functions: flags.inline_functions(),
+            optimization_level: flags.optimization_level(),
+        };
+        let files = files
+            .iter()
+            .map(|(hash, (path, _))| (path.to_string(), hash.to_string()))
+            .collect();
+        let compiled_units = compiled_units
+            .iter()
+            .map(|unit| {
+                let unit = unit.clone().into_compiled_unit();
+                let name = match &unit {
+                    CompiledUnitEnum::Module(NamedCompiledModule { address, name, .. }) => {
+                        format!("{}::{}", address, name)
+                    }
+                    CompiledUnitEnum::Script(_) => unit.name().to_string(),
+                };
+                let digest = Sha256::digest(&unit.serialize(flags.bytecode_version));
+                (name, hex::encode(digest))
+            })
+            .collect();
+        let mut diagnostics = DiagnosticsSummary::default();
+        for diag in diags.clone().into_vec() {
+            let info = diag.info();
+            *diagnostics
+                .by_severity
+                .entry(severity_name(info.severity()).to_string())
+                .or_default() += 1;
+            *diagnostics
+                .by_code
+                .entry(info.clone().render().0)
+                .or_default() += 1;
+        }
+
+        Ok(Self {
+            compiler_version: env!("CARGO_PKG_VERSION").to_string(),
+            flags,
+            severity_threshold: severity_name(threshold).to_string(),
+            files,
+            compiled_units,
+            diagnostics,
+        })
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("attestation serialization cannot fail")
+    }
+
+    pub fn write(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_json())?;
+        Ok(())
+    }
+}
+
+/// Parses a severity threshold given on the command line
+pub fn parse_severity_threshold(s: &str) -> anyhow::Result<Severity> {
+    Ok(match s {
+        "warning" => Severity::Warning,
+        "error" => Severity::NonblockingError,
+        "bug" => Severity::Bug,
+        _ => bail!(
+            "Invalid severity threshold '{}'. Expected one of 'warning', 'error', or 'bug'",
+            s
+        ),
+    })
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Warning => "warning",
+        Severity::NonblockingError | Severity::BlockingError => "error",
+        Severity::Bug => "bug",
+    }
+}
@@ -3,36 +3,61 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    cfgir::{self, visitor::AbsIntVisitorObj},
-    command_line::{DEFAULT_OUTPUT_DIR, MOVE_COMPILED_INTERFACES_DIR},
+    cfgir::{
+        self,
+        abort_codes::{self, ABORT_CODES_FILE},
+        visitor::AbsIntVisitorObj,
+    },
+    command_line::{
+        attestation::BuildAttestation, BYTECODE_VERSION_DIR, DEFAULT_OUTPUT_DIR,
+        MOVE_COMPILED_INTERFACES_DIR, NAMED_ADDRESS_ENV_VAR_PREFIX,
+    },
     compiled_unit,
     compiled_unit::AnnotatedCompiledUnit,
+    diag,
     diagnostics::{
         codes::{Severity, WarningFilter},
         *,
     },
+    editions::{migration::Migration, Edition, SyntaxEdition},
     expansion,
-    expansion::ast as E,
+    expansion::{address_report::NamedAddressReport, ast as E, rename::RenameEdit},
     hlir, interface_generator, naming, parser,
-    parser::{comments::*, *},
+    parser::{
+        comments::*,
+        lexer::{Lexer, Tok},
+        *,
+    },
     shared::{
-        CompilationEnv, Flags, IndexedPackagePath, NamedAddressMap, NamedAddressMaps,
-        NumericalAddress, PackageConfig, PackagePaths,
+        abort_messages::{self, ABORT_MESSAGES_EXTENSION},
+        package_builder::{PackageBuilder, PackageConfigError},
+        CancellationToken, CompilationEnv, CompilationPosition, Flags, IndexedPackagePath,
+        NamedAddressMap, NamedAddressMaps, NumericalAddress, PackageConfig, PackagePaths,
+        WorkspacePackage,
     },
     to_bytecode,
-    typing::{self, visitor::TypingVisitorObj},
+    typing::{
+        self, api_report::ApiReport, transaction_templates::TransactionTemplate,
+        visibility_report::VisibilityReport, visitor::TypingVisitorObj,
+    },
     unit_test, verification,
 };
+use move_binary_format::access::ModuleAccess;
 use move_command_line_common::files::{
-    extension_equals, find_filenames, MOVE_COMPILED_EXTENSION, MOVE_EXTENSION, SOURCE_MAP_EXTENSION,
+    extension_equals, find_filenames, FileHash, MOVE_COMPILED_EXTENSION, MOVE_EXTENSION,
+    SOURCE_MAP_EXTENSION,
 };
 use move_core_types::language_storage::ModuleId as CompiledModuleId;
+use move_ir_types::location::Loc;
 use move_symbol_pool::Symbol;
+use serde::Serialize;
 use std::{
+    any::Any,
     collections::{BTreeMap, BTreeSet},
-    fs,
+    fmt, fs,
     fs::File,
     io::{Read, Write},
+    panic::AssertUnwindSafe,
     path::{Path, PathBuf},
 };
 use tempfile::NamedTempFile;
@@ -65,6 +90,15 @@ pub struct Compiler<'a> {
     known_warning_filters: BTreeSet<KnownFiltersInfo>,
     package_configs: BTreeMap<Symbol, PackageConfig>,
     default_config: Option<PackageConfig>,
+    cancellation: Option<CancellationToken>,
+    diagnostics_sink: Option<DiagnosticsSink>,
+    #[cfg(feature = "prover")]
+    prover_backend: Option<crate::prover::ProverBackendObj>,
+    /// Named addresses whose values are given by `named_address_resolver` when run
+    deferred_named_addresses: BTreeSet<Symbol>,
+    named_address_resolver: Option<NamedAddressResolver>,
+    /// For each package of a workspace, the workspace packages it depends on, transitively
+    workspace_dependencies: BTreeMap<Symbol, BTreeSet<Symbol>>,
 }
 
 pub struct SteppedCompiler<'a, const P: Pass> {
@@ -79,9 +113,12 @@ pub const PASS_PARSER: Pass = 1;
 pub const PASS_EXPANSION: Pass = 2;
 pub const PASS_NAMING: Pass = 3;
 pub const PASS_TYPING: Pass = 4;
-pub const PASS_HLIR: Pass = 5;
-pub const PASS_CFGIR: Pass = 6;
-pub const PASS_COMPILATION: Pass = 7;
+/// Runs the prover backend, if one is set, on the typed program. Otherwise does nothing. No backend
+/// is provided by the compiler itself (see `crate::prover`).
+pub const PASS_PROVER: Pass = 5;
+pub const PASS_HLIR: Pass = 6;
+pub const PASS_CFGIR: Pass = 7;
+pub const PASS_COMPILATION: Pass = 8;
 
 #[derive(Debug)]
 enum PassResult {
@@ -89,6 +126,7 @@ enum PassResult {
     Expansion(expansion::ast::Program),
     Naming(naming::ast::Program),
     Typing(typing::ast::Program),
+    Prover(typing::ast::Program),
     HLIR(hlir::ast::Program),
     CFGIR(cfgir::ast::Program),
     Compilation(Vec<AnnotatedCompiledUnit>, /* warnings */ Diagnostics),
@@ -107,11 +145,60 @@ pub struct FullyCompiledProgram {
     pub compiled: Vec<AnnotatedCompiledUnit>,
 }
 
+/// Gives the value of a deferred named address, e.g. from the environment, or `None` if it has no
+/// value
+pub type NamedAddressResolver = Box<dyn Fn(Symbol) -> Option<NumericalAddress> + Send>;
+
 pub enum Visitor {
     TypingVisitor(TypingVisitorObj),
     AbsIntVisitor(AbsIntVisitorObj),
 }
 
+/// How `output_compiled_units` names the files of the compiled modules
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ModuleNaming {
+    /// `<index>_<module>.mv`, where the index is the position of the module in dependency order
+    #[default]
+    Indexed,
+    /// `<address>__<module>.mv`, which does not depend on the other modules. The dependency order
+    /// is recorded in the `MODULE_MANIFEST_FILE` instead
+    Qualified,
+}
+
+/// The manifest of the modules written with `ModuleNaming::Qualified`, in dependency order
+pub const MODULE_MANIFEST_FILE: &str = "module_manifest.json";
+
+/// The file listing the transaction templates of the scripts, in the output directory
+pub const TRANSACTION_TEMPLATES_FILE: &str = "transaction_templates.json";
+
+#[derive(Serialize)]
+struct ModuleManifestEntry {
+    /// `<address>::<module>`
+    module: String,
+    /// The file of the module, relative to the modules directory
+    file: String,
+    /// The modules used by the module, including the ones not in the manifest
+    dependencies: Vec<String>,
+}
+
+/// Receives the diagnostics of a compilation as they are found, along with the pass that found
+/// them. Parser diagnostics are received after each file, and the others after each pass.
+pub type DiagnosticsSink = Box<dyn FnMut(Pass, &Diagnostic) + Send>;
+
+/// Cancels the token when dropped, unless it has been disarmed
+struct CancelOnDrop(Option<CancellationToken>);
+
+// The compiler and its outputs must be able to move between threads for `run_async`
+const _: fn() = || {
+    fn assert_send<T: Send>() {}
+    assert_send::<Compiler<'static>>();
+    assert_send::<FilesSourceText>();
+    assert_send::<CommentMap>();
+    assert_send::<AnnotatedCompiledUnit>();
+    assert_send::<Diagnostics>();
+    assert_send::<Migration>();
+};
+
 //**************************************************************************************************
 // Entry points and impls
 //**************************************************************************************************
@@ -134,8 +221,9 @@ impl<'a> Compiler<'a> {
             } in all_pkgs
             {
                 let name = if let Some((name, config)) = name {
-                    let prev = package_configs.insert(name, config);
-                    anyhow::ensure!(prev.is_none(), "Duplicate package entry for '{name}'");
+                    if package_configs.insert(name, config).is_some() {
+                        return Err(PackageConfigError::DuplicatePackage(name).into());
+                    }
                     Some(name)
                 } else {
                     None
@@ -172,9 +260,73 @@ impl<'a> Compiler<'a> {
             known_warning_filters: BTreeSet::new(),
             package_configs,
             default_config: None,
+            cancellation: None,
+            diagnostics_sink: None,
+            #[cfg(feature = "prover")]
+            prover_backend: None,
+            deferred_named_addresses: BTreeSet::new(),
+            named_address_resolver: None,
+            workspace_dependencies: BTreeMap::new(),
         })
     }
 
+    /// Compiles packages configured with `PackageBuilder`, reporting the first invalid package
+    pub fn from_packages(
+        targets: Vec<PackageBuilder>,
+        deps: Vec<PackageBuilder>,
+    ) -> anyhow::Result<Self> {
+        let targets = targets
+            .into_iter()
+            .map(PackageBuilder::build)
+            .collect::<Result<Vec<_>, _>>()?;
+        let deps = deps
+            .into_iter()
+            .map(PackageBuilder::build)
+            .collect::<Result<Vec<_>, _>>()?;
+        Self::from_package_paths(targets, deps)
+    }
+
+    /// Compiles the packages of a workspace as targets of a single compilation, after their
+    /// dependencies in the workspace. A module using a module of a workspace package that its
+    /// package does not depend on is reported.
+    pub fn from_workspace<Paths: Into<Symbol>, NamedAddress: Into<Symbol>>(
+        packages: Vec<WorkspacePackage<Paths, NamedAddress>>,
+        deps: Vec<PackagePaths<Paths, NamedAddress>>,
+    ) -> anyhow::Result<Self> {
+        let mut dependencies = BTreeMap::new();
+        for package in &packages {
+            let prev = dependencies.insert(package.name, package.dependencies.clone());
+            if prev.is_some() {
+                return Err(PackageConfigError::DuplicatePackage(package.name).into());
+            }
+        }
+        let (order, workspace_dependencies) = workspace_order(&packages, &dependencies)?;
+        let mut packages: BTreeMap<_, _> = packages
+            .into_iter()
+            .map(|package| (package.name, package))
+            .collect();
+        let targets = order
+            .iter()
+            .map(|name| {
+                let WorkspacePackage {
+                    name,
+                    config,
+                    paths,
+                    named_address_map,
+                    dependencies: _,
+                } = packages.remove(name).unwrap();
+                PackagePaths {
+                    name: Some((name, config)),
+                    paths,
+                    named_address_map,
+                }
+            })
+            .collect();
+        let mut compiler = Self::from_package_paths(targets, deps)?;
+        compiler.workspace_dependencies = workspace_dependencies;
+        Ok(compiler)
+    }
+
     pub fn from_files<Paths: Into<Symbol>, NamedAddress: Into<Symbol> + Clone>(
         targets: Vec<Paths>,
         deps: Vec<Paths>,
@@ -263,6 +415,55 @@ impl<'a> Compiler<'a> {
         self
     }
 
+    /// Sets the backend run at `PASS_PROVER`
+    #[cfg(feature = "prover")]
+    pub fn set_prover_backend(mut self, backend: crate::prover::ProverBackendObj) -> Self {
+        assert!(self.prover_backend.is_none());
+        self.prover_backend = Some(backend);
+        self
+    }
+
+    /// Declares named addresses whose values are not known until the compiler is run. They are
+    /// assigned the value given by the resolver in every package that does not assign them a value
+    /// already. Uses of the names left without a value are reported together in one diagnostic.
+    pub fn add_deferred_named_addresses(
+        mut self,
+        names: impl IntoIterator<Item = impl Into<Symbol>>,
+    ) -> Self {
+        self.deferred_named_addresses
+            .extend(names.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets the resolver giving the values of the deferred named addresses
+    pub fn set_named_address_resolver(mut self, resolver: NamedAddressResolver) -> Self {
+        assert!(self.named_address_resolver.is_none());
+        self.named_address_resolver = Some(resolver);
+        self
+    }
+
+    /// The named addresses of the targets and dependencies, their values, and their conflicts
+    pub fn named_address_report(&self) -> NamedAddressReport {
+        let paths = self
+            .targets
+            .iter()
+            .chain(&self.deps)
+            .map(|path| (path.package, path.named_address_map));
+        let pre_compiled = self.pre_compiled_lib.map(|pre| {
+            let prog = &pre.parser;
+            let packages: Vec<_> = prog
+                .source_definitions
+                .iter()
+                .chain(&prog.lib_definitions)
+                .map(|def| (def.package, def.named_address_map))
+                .collect();
+            (&prog.named_address_maps, packages)
+        });
+        NamedAddressReport::from_maps(
+            std::iter::once((&self.maps, paths.collect::<Vec<_>>())).chain(pre_compiled),
+        )
+    }
+
     /// Sets the PackageConfig for files without a specified package
     pub fn set_default_config(mut self, config: PackageConfig) -> Self {
         assert!(self.default_config.is_none());
@@ -270,6 +471,23 @@ impl<'a> Compiler<'a> {
         self
     }
 
+    /// Sets a token that can be used to stop compilation between passes. If the token is cancelled,
+    /// the `Compiler` entry points return a `Cancelled` error.
+    pub fn set_cancellation_token(mut self, token: CancellationToken) -> Self {
+        assert!(self.cancellation.is_none());
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Sets a sink receiving diagnostics as soon as they are found, so that frontends can show
+    /// them before compilation finishes. Diagnostics filtered out by warning filters are not
+    /// received, and the diagnostics received are still returned by the `Compiler` entry points.
+    pub fn set_diagnostics_sink(mut self, sink: DiagnosticsSink) -> Self {
+        assert!(self.diagnostics_sink.is_none());
+        self.diagnostics_sink = Some(sink);
+        self
+    }
+
     pub fn run<const TARGET: Pass>(
         self,
     ) -> anyhow::Result<(
@@ -277,7 +495,7 @@ impl<'a> Compiler<'a> {
         Result<(CommentMap, SteppedCompiler<'a, TARGET>), Diagnostics>,
     )> {
         let Self {
-            maps,
+            mut maps,
             targets,
             mut deps,
             interface_files_dir_opt,
@@ -289,7 +507,15 @@ impl<'a> Compiler<'a> {
             known_warning_filters,
             package_configs,
             default_config,
+            cancellation,
+            diagnostics_sink,
+            #[cfg(feature = "prover")]
+            prover_backend,
+            deferred_named_addresses,
+            named_address_resolver,
+            workspace_dependencies,
         } = self;
+        let cancellation = cancellation.unwrap_or_default();
         generate_interface_files_for_deps(
             &mut deps,
             interface_files_dir_opt,
@@ -307,13 +533,51 @@ impl<'a> Compiler<'a> {
         {
             compilation_env.add_custom_known_filters(filters, filter_attr_name)?;
         }
+        compilation_env.set_cancellation_token(cancellation.clone());
+        if let Some(sink) = diagnostics_sink {
+            compilation_env.set_diagnostics_sink(sink);
+        }
+        #[cfg(feature = "prover")]
+        if let Some(backend) = prover_backend {
+            compilation_env.set_prover_backend(backend);
+        }
+        let unresolved = resolve_deferred_named_addresses(
+            &mut maps,
+            deferred_named_addresses,
+            named_address_resolver,
+        );
+        compilation_env.set_unresolved_deferred_addresses(unresolved);
+        compilation_env.set_workspace_dependencies(workspace_dependencies);
+        let position = compilation_env.position();
+        let ice_repro_dir = compilation_env.flags().ice_repro_dir().map(str::to_owned);
+        cancellation.check()?;
         let (source_text, pprog_and_comments_res) =
             parse_program(&mut compilation_env, maps, targets, deps)?;
-        let res: Result<_, Diagnostics> = pprog_and_comments_res.and_then(|(pprog, comments)| {
-            SteppedCompiler::new_at_parser(compilation_env, pre_compiled_lib, pprog)
-                .run::<TARGET>()
-                .map(|compiler| (comments, compiler))
-        });
+        // a panic is reported as a bug at the item being compiled, instead of aborting the process.
+        // This relies on unwinding: in binaries built with `panic = 'abort'`, such as the release
+        // builds of the Sui binaries, the process aborts at the panic before it can be reported.
+        let res = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            pprog_and_comments_res.and_then(|(pprog, comments)| {
+                compilation_env.set_doc_comments(comments.clone());
+                SteppedCompiler::new_at_parser(compilation_env, pre_compiled_lib, pprog)
+                    .run::<TARGET>()
+                    .map(|compiler| (comments, compiler))
+            })
+        }));
+        let res: Result<_, Diagnostics> = match res {
+            Ok(res) => res,
+            Err(payload) => Err(compiler_panic(
+                &source_text,
+                &position,
+                ice_repro_dir.as_deref(),
+                payload,
+            )),
+        };
+        // a compilation stopped between passes fails without diagnostics, so it is reported as
+        // `Cancelled` instead. One that finished before noticing the cancellation is kept.
+        if res.is_err() {
+            cancellation.check()?;
+        }
         Ok((source_text, res))
     }
 
@@ -347,6 +611,162 @@ impl<'a> Compiler<'a> {
         report_warnings(&files, warnings);
         Ok((files, units))
     }
+
+    /// Builds and reports diagnostics like `build_and_report`, additionally producing an
+    /// attestation that no diagnostic at or above `threshold` was reported. Fails if one was.
+    pub fn build_and_attest(
+        self,
+        threshold: Severity,
+    ) -> anyhow::Result<(
+        FilesSourceText,
+        Vec<AnnotatedCompiledUnit>,
+        BuildAttestation,
+    )> {
+        let flags = self.flags.clone();
+        let (files, units_res) = self.build()?;
+        let (units, warnings) = unwrap_or_report_diagnostics(&files, units_res);
+        let attestation = BuildAttestation::new(&flags, threshold, &files, &units, &warnings);
+        report_warnings(&files, warnings);
+        Ok((files, units, attestation?))
+    }
+
+    /// Compiles `package` under the migration edition, collecting the rewrites needed to move it
+    /// to the 2024 edition. If `package` is `None`, the unnamed packages (those using the default
+    /// config) are migrated instead. Diagnostics that cannot be fixed automatically are kept in
+    /// `Migration::unresolved`.
+    pub fn generate_migration_patch(
+        mut self,
+        package: Option<Symbol>,
+    ) -> anyhow::Result<Migration> {
+        let config = match package {
+            Some(package) => {
+                let Some(config) = self.package_configs.get_mut(&package) else {
+                    anyhow::bail!("No package config found for '{package}'")
+                };
+                config
+            }
+            None => self
+                .default_config
+                .get_or_insert_with(PackageConfig::default),
+        };
+        config.edition = Edition::E2024_MIGRATION;
+        let (files, res) = self.build()?;
+        let diags = match res {
+            Ok((_units, warnings)) => warnings,
+            Err(diags) => diags,
+        };
+        Ok(Migration::new(files, diags))
+    }
+
+    /// Computes the edits renaming the function, struct, or constant whose name is at `def_loc`
+    /// to `new_name`, across all source files, including method calls of functions. The new name
+    /// is checked as if it were declared in place of the old one. Returns the diagnostics instead
+    /// if typing fails or the new name is invalid.
+    pub fn generate_rename_edits(
+        mut self,
+        def_loc: Loc,
+        new_name: Symbol,
+    ) -> anyhow::Result<(FilesSourceText, Result<Vec<RenameEdit>, Diagnostics>)> {
+        self.flags = self.flags.set_symbol_index(true);
+        // method calls are resolved during typing
+        let (files, res) = self.run::<PASS_TYPING>()?;
+        let mut stepped = match res {
+            Ok((_comments, stepped)) => stepped,
+            Err(diags) => return Ok((files, Err(diags))),
+        };
+        let edits =
+            expansion::rename::rename_edits(stepped.compilation_env(), &files, def_loc, new_name)?;
+        Ok((files, edits))
+    }
+
+    /// Translates the scripts of the program into programmable transaction block templates, for
+    /// migrating script based workflows. Scripts that are not a sequence of module calls are
+    /// reported instead.
+    pub fn generate_transaction_templates(
+        self,
+    ) -> anyhow::Result<(
+        FilesSourceText,
+        Result<Vec<TransactionTemplate>, Diagnostics>,
+    )> {
+        let (files, res) = self.run::<PASS_TYPING>()?;
+        let typing = match res {
+            Ok((_comments, stepped)) => stepped.into_ast().1,
+            Err(diags) => return Ok((files, Err(diags))),
+        };
+        let templates = typing::transaction_templates::program(&typing);
+        Ok((files, templates))
+    }
+
+    /// The public API of the targets, to be compared with the API of a previous version with
+    /// `api_report::compare`
+    pub fn generate_api_report(
+        self,
+    ) -> anyhow::Result<(FilesSourceText, Result<ApiReport, Diagnostics>)> {
+        let (files, res) = self.run::<PASS_TYPING>()?;
+        let typing = match res {
+            Ok((_comments, stepped)) => stepped.into_ast().1,
+            Err(diags) => return Ok((files, Err(diags))),
+        };
+        Ok((files, Ok(ApiReport::new(&typing))))
+    }
+
+    /// The functions of the targets callable from outside their module, with the modules allowed
+    /// to call the `public(friend)` and `public(package)` ones
+    pub fn generate_visibility_report(
+        self,
+    ) -> anyhow::Result<(FilesSourceText, Result<VisibilityReport, Diagnostics>)> {
+        let (files, res) = self.run::<PASS_TYPING>()?;
+        let typing = match res {
+            Ok((_comments, stepped)) => stepped.into_ast().1,
+            Err(diags) => return Ok((files, Err(diags))),
+        };
+        Ok((files, Ok(VisibilityReport::new(&typing))))
+    }
+}
+
+impl Compiler<'static> {
+    /// Runs `f` on this compiler using tokio's blocking thread pool, so that compilation does not
+    /// stall the async executor. If the returned future is dropped before `f` finishes, the
+    /// compilation is cancelled at its next pass boundary.
+    pub async fn run_async<T, F>(mut self, f: F) -> anyhow::Result<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(Self) -> anyhow::Result<T> + Send + 'static,
+    {
+        let token = self
+            .cancellation
+            .get_or_insert_with(CancellationToken::new)
+            .clone();
+        let mut guard = CancelOnDrop(Some(token));
+        let result = tokio::task::spawn_blocking(move || f(self)).await;
+        guard.0 = None;
+        match result {
+            Ok(res) => res,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    pub async fn check_async(self) -> anyhow::Result<(FilesSourceText, Result<(), Diagnostics>)> {
+        self.run_async(Self::check).await
+    }
+
+    pub async fn build_async(
+        self,
+    ) -> anyhow::Result<(
+        FilesSourceText,
+        Result<(Vec<AnnotatedCompiledUnit>, Diagnostics), Diagnostics>,
+    )> {
+        self.run_async(Self::build).await
+    }
+}
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        if let Some(token) = self.0.take() {
+            token.cancel()
+        }
+    }
 }
 
 impl<'a, const P: Pass> SteppedCompiler<'a, P> {
@@ -487,6 +907,7 @@ ast_stepped_compilers!(
     ),
     (PASS_NAMING, naming, Naming, at_naming, new_at_naming),
     (PASS_TYPING, typing, Typing, at_typing, new_at_typing),
+    (PASS_PROVER, typing, Prover, at_prover, new_at_prover),
     (PASS_HLIR, hlir, HLIR, at_hlir, new_at_hlir),
     (PASS_CFGIR, cfgir, CFGIR, at_cfgir, new_at_cfgir)
 );
@@ -551,6 +972,8 @@ pub fn construct_pre_compiled_lib<Paths: Into<Symbol>, NamedAddress: Into<Symbol
             assert!(typing.is_none());
             typing = Some(tprog.clone())
         }
+        // the same program as at typing
+        PassResult::Prover(_) => (),
         PassResult::HLIR(hprog) => {
             assert!(hlir.is_none());
             hlir = Some(hprog.clone());
@@ -618,13 +1041,86 @@ pub fn sanity_check_compiled_units(
     }
 }
 
-/// Given a file map and a set of compiled programs, saves the compiled programs to disk
+/// Writes the transaction templates of the scripts to `TRANSACTION_TEMPLATES_FILE` in `out_dir`
+pub fn output_transaction_templates(
+    templates: &[TransactionTemplate],
+    out_dir: &str,
+) -> anyhow::Result<()> {
+    std::fs::create_dir_all(out_dir)?;
+    let json = serde_json::to_string_pretty(templates)?;
+    fs::write(dir_path!(out_dir, TRANSACTION_TEMPLATES_FILE), json)?;
+    Ok(())
+}
+
+/// Writes the compiled units, with their source maps and abort code metadata, to `out_dir`.
+/// With no bytecode version, the units are serialized in the default version. With a single
+/// version, they are serialized in that version. With several versions, one artifact set is
+/// written per version, in a `bytecode_v<version>` sub directory of `out_dir`. Constructs that
+/// cannot be represented in one of the versions are reported, against the files in `files`,
+/// before anything is written.
+/// The modules are expected in dependency order, as returned by the compiler, and their files are
+/// named according to `module_naming`.
 pub fn output_compiled_units(
-    bytecode_version: Option<u32>,
+    bytecode_versions: &[u32],
+    module_naming: ModuleNaming,
     emit_source_maps: bool,
     files: FilesSourceText,
     compiled_units: Vec<AnnotatedCompiledUnit>,
     out_dir: &str,
+) -> anyhow::Result<()> {
+    let version_errors =
+        compiled_unit::check_bytecode_versions(&compiled_units, bytecode_versions)?;
+    if !version_errors.is_empty() {
+        report_diagnostics(&files, version_errors)
+    }
+
+    let ice_errors = compiled_unit::verify_units(&compiled_units);
+    match bytecode_versions {
+        [] => output_units_for_version(
+            None,
+            module_naming,
+            emit_source_maps,
+            &files,
+            compiled_units,
+            out_dir,
+        )?,
+        [version] => output_units_for_version(
+            Some(*version),
+            module_naming,
+            emit_source_maps,
+            &files,
+            compiled_units,
+            out_dir,
+        )?,
+        versions => {
+            for version in versions {
+                let version_dir =
+                    dir_path!(out_dir, format!("{}{}", BYTECODE_VERSION_DIR, version));
+                output_units_for_version(
+                    Some(*version),
+                    module_naming,
+                    emit_source_maps,
+                    &files,
+                    compiled_units.clone(),
+                    &version_dir.to_string_lossy(),
+                )?
+            }
+        }
+    }
+
+    if !ice_errors.is_empty() {
+        report_diagnostics(&files, ice_errors)
+    }
+    Ok(())
+}
+
+fn output_units_for_version(
+    bytecode_version: Option<u32>,
+    module_naming: ModuleNaming,
+    emit_source_maps: bool,
+    files: &FilesSourceText,
+    compiled_units: Vec<AnnotatedCompiledUnit>,
+    out_dir: &str,
 ) -> anyhow::Result<()> {
     const SCRIPT_SUB_DIR: &str = "scripts";
     const MODULE_SUB_DIR: &str = "modules";
@@ -647,7 +1143,10 @@ pub fn output_compiled_units(
         }};
     }
 
-    let ice_errors = compiled_unit::verify_units(&compiled_units);
+    if let Some(registry) = abort_codes::registry_json(&compiled_units) {
+        std::fs::create_dir_all(out_dir)?;
+        fs::write(dir_path!(out_dir, ABORT_CODES_FILE), registry)?;
+    }
     let (modules, scripts): (Vec<_>, Vec<_>) = compiled_units
         .into_iter()
         .partition(|u| matches!(u, AnnotatedCompiledUnit::Module(_)));
@@ -657,16 +1156,49 @@ pub fn output_compiled_units(
         std::fs::create_dir_all(dir_path!(out_dir, MODULE_SUB_DIR))?;
     }
     let digit_width = num_digits(modules.len());
+    let mut manifest = vec![];
     for (idx, unit) in modules.into_iter().enumerate() {
+        let AnnotatedCompiledUnit::Module(m) = &unit else {
+            unreachable!()
+        };
+        let abort_messages = m.abort_messages.clone();
+        let file_name = match module_naming {
+            ModuleNaming::Indexed => {
+                format!("{}_{}", format_idx(idx, digit_width), m.named_module.name)
+            }
+            ModuleNaming::Qualified => {
+                let address = m.named_module.address.into_inner().to_hex_literal();
+                let module = &m.named_module.module;
+                manifest.push(ModuleManifestEntry {
+                    module: format!("{}::{}", address, m.named_module.name),
+                    file: format!(
+                        "{}__{}.{}",
+                        address, m.named_module.name, MOVE_COMPILED_EXTENSION
+                    ),
+                    dependencies: module
+                        .immediate_dependencies()
+                        .iter()
+                        .map(|id| format!("{}::{}", id.address().to_hex_literal(), id.name()))
+                        .collect(),
+                });
+                format!("{}__{}", address, m.named_module.name)
+            }
+        };
         let unit = unit.into_compiled_unit();
-        let mut path = dir_path!(
-            out_dir,
-            MODULE_SUB_DIR,
-            format!("{}_{}", format_idx(idx, digit_width), unit.name())
-        );
+        let mut path = dir_path!(out_dir, MODULE_SUB_DIR, file_name);
+        if !abort_messages.is_empty() {
+            let json = abort_messages::catalogue_json(files, &abort_messages);
+            fs::write(path.with_extension(ABORT_MESSAGES_EXTENSION), json)?;
+        }
         emit_unit!(path, unit);
     }
 
+    if module_naming == ModuleNaming::Qualified {
+        std::fs::create_dir_all(out_dir)?;
+        let manifest = serde_json::to_string_pretty(&manifest).unwrap();
+        fs::write(dir_path!(out_dir, MODULE_MANIFEST_FILE), manifest)?;
+    }
+
     // scripts
     if !scripts.is_empty() {
         std::fs::create_dir_all(dir_path!(out_dir, SCRIPT_SUB_DIR))?;
@@ -676,13 +1208,224 @@ pub fn output_compiled_units(
         let mut path = dir_path!(out_dir, SCRIPT_SUB_DIR, unit.name().as_str());
         emit_unit!(path, unit);
     }
+    Ok(())
+}
 
-    if !ice_errors.is_empty() {
-        report_diagnostics(&files, ice_errors)
+/// The file describing the panic in a reproduction written to `Flags::ice_repro_dir`
+pub const ICE_REPRO_FILE: &str = "ice.txt";
+
+/// Converts a panic of the compiler into a bug diagnostic at the item being compiled, writing the
+/// source of that item to `repro_dir` if set. If no item was being compiled, the diagnostic has no
+/// location and nothing is written. Only panics that unwind can be caught, see `run`.
+fn compiler_panic(
+    files: &FilesSourceText,
+    position: &CompilationPosition,
+    repro_dir: Option<&str>,
+    payload: Box<dyn Any + Send>,
+) -> Diagnostics {
+    let msg = match payload.downcast::<String>() {
+        Ok(msg) => *msg,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(msg) => msg.to_string(),
+            Err(_) => "unknown cause".to_string(),
+        },
+    };
+    let mut diags = Diagnostics::new();
+    let Some(loc) = position.current_loc() else {
+        diags.add(diag!(
+            Bug::CompilerPanic,
+            (
+                Loc::invalid(),
+                format!("The compiler panicked outside of any item: {}", msg)
+            )
+        ));
+        return diags;
+    };
+    let mut diag = diag!(
+        Bug::CompilerPanic,
+        (
+            loc,
+            format!("The compiler panicked while compiling this item: {}", msg)
+        )
+    );
+    if let Some(repro_dir) = repro_dir {
+        match write_ice_repro(files, loc, &msg, Path::new(repro_dir)) {
+            Ok(()) => diag.add_note(format!(
+                "The source of this item was written to '{}', please attach it to a bug report",
+                repro_dir
+            )),
+            Err(e) => diag.add_note(format!(
+                "The source of this item could not be written to '{}': {}",
+                repro_dir, e
+            )),
+        }
     }
+    diags.add(diag);
+    diags
+}
+
+/// Writes the module of the item at `loc` to `repro_dir`, in a file named after its source file,
+/// rather than the whole package, and describes the panic in its `ICE_REPRO_FILE`. The whole source
+/// file is written if the module cannot be told apart from the rest of it.
+fn write_ice_repro(
+    files: &FilesSourceText,
+    loc: Loc,
+    msg: &str,
+    repro_dir: &Path,
+) -> anyhow::Result<()> {
+    let Some((path, source)) = files.get(&loc.file_hash()) else {
+        anyhow::bail!("unknown source file")
+    };
+    let file_name = Path::new(path.as_str())
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("invalid source file '{}'", path))?;
+    let offset = loc.start() as usize;
+    let (source, offset) = match enclosing_module(source, loc.file_hash(), offset) {
+        Some((start, module)) => (format!("{}\n", module), offset - start),
+        None => (source.to_string(), offset),
+    };
+    std::fs::create_dir_all(repro_dir)?;
+    fs::write(repro_dir.join(file_name), source.as_bytes())?;
+    let before = &source[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before.len() - before.rfind('\n').map_or(0, |i| i + 1) + 1;
+    let description = format!(
+        "The compiler panicked: {}\nWhile compiling the item at {}:{}:{}\n",
+        msg,
+        file_name.to_string_lossy(),
+        line,
+        column
+    );
+    fs::write(repro_dir.join(ICE_REPRO_FILE), description)?;
     Ok(())
 }
 
+/// The start and source of the module declared at the top level of `source` around `offset`, with
+/// its attributes, found by matching the braces of its body. `None` if there is no such module,
+/// e.g. if it is declared in an `address` block or without braces, or if the file does not lex.
+fn enclosing_module(source: &str, file_hash: FileHash, offset: usize) -> Option<(usize, &str)> {
+    let mut lexer = Lexer::new(source, file_hash, SyntaxEdition::E2024);
+    lexer.advance().ok()?;
+    let mut depth = 0usize;
+    let mut attributes_start = None;
+    let mut module_start = None;
+    loop {
+        match lexer.peek() {
+            Tok::EOF => return None,
+            Tok::NumSign if depth == 0 && attributes_start.is_none() => {
+                attributes_start = Some(lexer.start_loc())
+            }
+            Tok::Module if depth == 0 => {
+                module_start = Some(attributes_start.take().unwrap_or(lexer.start_loc()))
+            }
+            Tok::Semicolon if depth == 0 && module_start.is_some() => return None,
+            Tok::LBrace => depth += 1,
+            Tok::RBrace => {
+                depth = depth.checked_sub(1)?;
+                if depth == 0 {
+                    let start = module_start.take()?;
+                    let end = lexer.start_loc() + 1;
+                    if (start..end).contains(&offset) {
+                        return Some((start, &source[start..end]));
+                    }
+                }
+            }
+            _ => (),
+        }
+        lexer.advance().ok()?;
+    }
+}
+
+/// Orders the workspace packages so that each comes after the packages it depends on, keeping the
+/// given order otherwise. Returns the order and the transitive dependencies of each package.
+fn workspace_order<Paths: Into<Symbol>, NamedAddress: Into<Symbol>>(
+    packages: &[WorkspacePackage<Paths, NamedAddress>],
+    dependencies: &BTreeMap<Symbol, Vec<Symbol>>,
+) -> anyhow::Result<(Vec<Symbol>, BTreeMap<Symbol, BTreeSet<Symbol>>)> {
+    fn visit(
+        dependencies: &BTreeMap<Symbol, Vec<Symbol>>,
+        stack: &mut Vec<Symbol>,
+        order: &mut Vec<Symbol>,
+        closures: &mut BTreeMap<Symbol, BTreeSet<Symbol>>,
+        package: Symbol,
+    ) -> anyhow::Result<()> {
+        if closures.contains_key(&package) {
+            return Ok(());
+        }
+        if let Some(start) = stack.iter().position(|p| *p == package) {
+            let cycle = stack[start..]
+                .iter()
+                .chain([&package])
+                .map(|p| p.as_str())
+                .collect::<Vec<_>>()
+                .join(" -> ");
+            anyhow::bail!("Cyclic dependency between workspace packages: {cycle}")
+        }
+        stack.push(package);
+        let mut closure = BTreeSet::new();
+        for dep in &dependencies[&package] {
+            anyhow::ensure!(
+                dependencies.contains_key(dep),
+                "Package '{package}' depends on '{dep}', which is not part of the workspace"
+            );
+            visit(dependencies, stack, order, closures, *dep)?;
+            closure.insert(*dep);
+            closure.extend(closures[dep].iter().copied());
+        }
+        stack.pop();
+        closures.insert(package, closure);
+        order.push(package);
+        Ok(())
+    }
+
+    let mut order = vec![];
+    let mut closures = BTreeMap::new();
+    for package in packages {
+        visit(
+            dependencies,
+            &mut vec![],
+            &mut order,
+            &mut closures,
+            package.name,
+        )?;
+    }
+    Ok((order, closures))
+}
+
+/// Assigns each deferred named address the value given by the resolver, in every map that does not
+/// assign it a value already. Returns the names the resolver gave no value.
+fn resolve_deferred_named_addresses(
+    maps: &mut NamedAddressMaps,
+    deferred_named_addresses: BTreeSet<Symbol>,
+    resolver: Option<NamedAddressResolver>,
+) -> BTreeSet<Symbol> {
+    let mut unresolved = BTreeSet::new();
+    for name in deferred_named_addresses {
+        match resolver.as_ref().and_then(|resolver| resolver(name)) {
+            Some(addr) => {
+                for map in maps.all_mut() {
+                    map.entry(name).or_insert(addr);
+                }
+            }
+            None => {
+                unresolved.insert(name);
+            }
+        }
+    }
+    unresolved
+}
+
+/// A resolver reading the value of a deferred named address `name` from the environment variable
+/// `MOVE_ADDRESS_<name>`. Names whose variable is not set, or does not hold a valid address, have no
+/// value.
+pub fn env_named_address_resolver() -> NamedAddressResolver {
+    Box::new(|name| {
+        let var = format!("{}{}", NAMED_ADDRESS_ENV_VAR_PREFIX, name);
+        let value = std::env::var(var).ok()?;
+        NumericalAddress::parse_str(&value).ok()
+    })
+}
+
 fn generate_interface_files_for_deps(
     deps: &mut Vec<IndexedPackagePath>,
     interface_files_dir_opt: Option<String>,
@@ -747,6 +1490,12 @@ pub fn generate_interface_files(
         for IndexedPackagePath { path, .. } in &mv_files {
             std::fs::read(path.as_str())?.hash(&mut hasher);
             HASH_DELIM.hash(&mut hasher);
+            // the interface also depends on the source information found for the module
+            let (source_map, source) = interface_generator::source_files(Path::new(path.as_str()));
+            for source_file in source_map.into_iter().chain(source) {
+                std::fs::read(source_file)?.hash(&mut hasher);
+                HASH_DELIM.hash(&mut hasher);
+            }
         }
 
         let mut dir = interface_sub_dir;
@@ -849,6 +1598,7 @@ impl PassResult {
             PassResult::Expansion(_) => PASS_EXPANSION,
             PassResult::Naming(_) => PASS_NAMING,
             PassResult::Typing(_) => PASS_TYPING,
+            PassResult::Prover(_) => PASS_PROVER,
             PassResult::HLIR(_) => PASS_HLIR,
             PassResult::CFGIR(_) => PASS_CFGIR,
             PassResult::Compilation(_, _) => PASS_COMPILATION,
@@ -871,6 +1621,13 @@ fn run(
     if cur.equivalent_pass() >= until {
         return Ok(cur);
     }
+    if compilation_env.is_cancelled() {
+        // the `Compiler` entry points report this as `Cancelled`
+        return Err(Diagnostics::new());
+    }
+    // each pass records the items it compiles, so a panic is not reported at an item of the
+    // previous pass
+    compilation_env.clear_current_position();
 
     match cur {
         PassResult::Parser(prog) => {
@@ -878,6 +1635,7 @@ fn run(
             let prog = unit_test::filter_test_members::program(compilation_env, prog);
             let prog = verification::ast_filter::program(compilation_env, prog);
             let eprog = expansion::translate::program(compilation_env, pre_compiled_lib, prog);
+            compilation_env.stream_diags(PASS_EXPANSION);
             compilation_env.check_diags_at_or_above_severity(Severity::Bug)?;
             run(
                 compilation_env,
@@ -888,7 +1646,12 @@ fn run(
             )
         }
         PassResult::Expansion(eprog) => {
+            #[cfg(feature = "prover")]
+            if compilation_env.has_prover_backend() {
+                compilation_env.set_prover_specs(crate::prover::ProgramSpecs::new(&eprog));
+            }
             let nprog = naming::translate::program(compilation_env, pre_compiled_lib, eprog);
+            compilation_env.stream_diags(PASS_NAMING);
             compilation_env.check_diags_at_or_above_severity(Severity::Bug)?;
             run(
                 compilation_env,
@@ -900,6 +1663,7 @@ fn run(
         }
         PassResult::Naming(nprog) => {
             let tprog = typing::translate::program(compilation_env, pre_compiled_lib, nprog);
+            compilation_env.stream_diags(PASS_TYPING);
             compilation_env.check_diags_at_or_above_severity(Severity::BlockingError)?;
             run(
                 compilation_env,
@@ -910,7 +1674,21 @@ fn run(
             )
         }
         PassResult::Typing(tprog) => {
+            #[cfg(feature = "prover")]
+            crate::prover::program(compilation_env, &tprog);
+            compilation_env.stream_diags(PASS_PROVER);
+            compilation_env.check_diags_at_or_above_severity(Severity::BlockingError)?;
+            run(
+                compilation_env,
+                pre_compiled_lib,
+                PassResult::Prover(tprog),
+                until,
+                result_check,
+            )
+        }
+        PassResult::Prover(tprog) => {
             let hprog = hlir::translate::program(compilation_env, pre_compiled_lib, tprog);
+            compilation_env.stream_diags(PASS_HLIR);
             compilation_env.check_diags_at_or_above_severity(Severity::Bug)?;
             run(
                 compilation_env,
@@ -922,6 +1700,7 @@ fn run(
         }
         PassResult::HLIR(hprog) => {
             let cprog = cfgir::translate::program(compilation_env, pre_compiled_lib, hprog);
+            compilation_env.stream_diags(PASS_CFGIR);
             compilation_env.check_diags_at_or_above_severity(Severity::NonblockingError)?;
             run(
                 compilation_env,
@@ -934,6 +1713,7 @@ fn run(
         PassResult::CFGIR(cprog) => {
             let compiled_units =
                 to_bytecode::translate::program(compilation_env, pre_compiled_lib, cprog);
+            compilation_env.stream_diags(PASS_COMPILATION);
             compilation_env.check_diags_at_or_above_severity(Severity::NonblockingError)?;
             let warnings = compilation_env.take_final_warning_diags();
             assert!(until == PASS_COMPILATION);
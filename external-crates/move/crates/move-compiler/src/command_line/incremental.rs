@@ -0,0 +1,168 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Re-checking a single modified file against a completed compilation. The completed compilation
+//! is used as a pre-compiled library, so only the modified file, and the files of the modules
+//! using its modules directly or transitively, are compiled again.
+
+use crate::{
+    command_line::compiler::{construct_pre_compiled_lib, Compiler, FullyCompiledProgram},
+    diagnostics::{Diagnostics, FilesSourceText},
+    expansion::ast::ModuleIdent,
+    parser::ast as P,
+    shared::{Flags, NamedAddressMapIndex, PackageConfig, PackagePaths},
+};
+use anyhow::bail;
+use move_command_line_common::files::FileHash;
+use move_symbol_pool::Symbol;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A completed compilation, against which modified files can be checked
+pub struct CheckedProgram {
+    flags: Flags,
+    package_configs: BTreeMap<Symbol, PackageConfig>,
+    program: FullyCompiledProgram,
+}
+
+impl CheckedProgram {
+    /// Compiles `targets`, returning their diagnostics if the compilation fails
+    pub fn new(
+        targets: Vec<PackagePaths>,
+        flags: Flags,
+    ) -> anyhow::Result<Result<Self, (FilesSourceText, Diagnostics)>> {
+        let package_configs = targets
+            .iter()
+            .filter_map(|package| package.name.clone())
+            .collect();
+        let program = match construct_pre_compiled_lib(targets, None, flags.clone())? {
+            Ok(program) => program,
+            Err(errors) => return Ok(Err(errors)),
+        };
+        Ok(Ok(Self {
+            flags,
+            package_configs,
+            program,
+        }))
+    }
+
+    pub fn program(&self) -> &FullyCompiledProgram {
+        &self.program
+    }
+
+    /// Checks the current contents of `path`, a file of the completed compilation. The file is
+    /// compiled again along with the files of the modules using its modules, directly or through
+    /// other modules, and the diagnostics of these files are returned. The other modules are reused from the completed compilation.
+    /// Modules removed from the file remain visible to the modules that are not compiled again.
+    pub fn check_file(
+        &self,
+        path: impl Into<Symbol>,
+    ) -> anyhow::Result<(FilesSourceText, Diagnostics)> {
+        let path = path.into();
+        let Some(file_hash) = self
+            .program
+            .files
+            .iter()
+            .find(|(_, (name, _))| *name == path)
+            .map(|(file_hash, _)| *file_hash)
+        else {
+            bail!("'{}' is not part of the compilation", path)
+        };
+
+        let modules = &self.program.typing.inner.modules;
+        let changed: BTreeSet<ModuleIdent> = modules
+            .key_cloned_iter()
+            .filter(|(_, mdef)| mdef.loc.file_hash() == file_hash)
+            .map(|(mident, _)| mident)
+            .collect();
+        // a change can reach the modules using the changed modules through the ones in between,
+        // e.g. through an inlined function or a changed type, so all of them are checked again
+        let mut affected = changed;
+        loop {
+            let dependents: Vec<ModuleIdent> = modules
+                .key_cloned_iter()
+                .filter(|(mident, mdef)| {
+                    !affected.contains(mident)
+                        && mdef
+                            .immediate_neighbors
+                            .key_cloned_iter()
+                            .any(|(neighbor, _)| affected.contains(&neighbor))
+                })
+                .map(|(mident, _)| mident)
+                .collect();
+            if dependents.is_empty() {
+                break;
+            }
+            affected.extend(dependents);
+        }
+        let mut checked_files = BTreeSet::from([file_hash]);
+        checked_files.extend(
+            modules
+                .key_cloned_iter()
+                .filter(|(mident, _)| affected.contains(mident))
+                .map(|(_, mdef)| mdef.loc.file_hash()),
+        );
+
+        let targets = self.package_paths(&checked_files);
+        if !targets.iter().any(|package| package.paths.contains(&path)) {
+            bail!("'{}' has no definitions in the compilation", path)
+        }
+        let (files, res) = Compiler::from_package_paths(targets, vec![])?
+            .set_pre_compiled_lib(&self.program)
+            .set_flags(self.flags.clone())
+            .build()?;
+        let diags = match res {
+            Ok((_units, warnings)) => warnings,
+            Err(errors) => errors,
+        };
+        Ok((files, diags))
+    }
+
+    /// The package paths of `checked_files`, with the package and named addresses they were
+    /// compiled with
+    fn package_paths(&self, checked_files: &BTreeSet<FileHash>) -> Vec<PackagePaths> {
+        let parser = &self.program.parser;
+        let mut entries: BTreeMap<
+            (Option<Symbol>, usize),
+            (NamedAddressMapIndex, BTreeSet<Symbol>),
+        > = BTreeMap::new();
+        for P::PackageDefinition {
+            package,
+            named_address_map,
+            def,
+        } in &parser.source_definitions
+        {
+            let file_hash = match def {
+                P::Definition::Module(m) => m.loc.file_hash(),
+                P::Definition::Address(a) => a.loc.file_hash(),
+                P::Definition::Script(s) => s.loc.file_hash(),
+            };
+            if !checked_files.contains(&file_hash) {
+                continue;
+            }
+            let key = (
+                *package,
+                parser.named_address_maps.position(*named_address_map),
+            );
+            entries
+                .entry(key)
+                .or_insert_with(|| (*named_address_map, BTreeSet::new()))
+                .1
+                .insert(self.program.files[&file_hash].0);
+        }
+        entries
+            .into_iter()
+            .map(|((package, _), (named_address_map, paths))| PackagePaths {
+                name: package.map(|package| {
+                    let config = self
+                        .package_configs
+                        .get(&package)
+                        .cloned()
+                        .unwrap_or_default();
+                    (package, config)
+                }),
+                paths: paths.into_iter().collect(),
+                named_address_map: parser.named_address_maps.get(named_address_map).clone(),
+            })
+            .collect()
+    }
+}
@@ -2,7 +2,9 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod attestation;
 pub mod compiler;
+pub mod incremental;
 
 pub const DEPENDENCY: &str = "dependency";
 pub const DEPENDENCY_SHORT: char = 'd';
@@ -17,6 +19,10 @@ pub const DEFAULT_OUTPUT_DIR: &str = "build";
 pub const SHADOW: &str = "shadow";
 pub const SHADOW_SHORT: char = 'S';
 
+pub const DUPLICATE_MODULES: &str = "duplicate-modules";
+
+pub const AUDIT_DUPLICATE_MODULES: &str = "audit-duplicate-modules";
+
 pub const SILENCE_WARNINGS: &str = "silence-warnings";
 pub const SILENCE_WARNINGS_SHORT: char = 'w';
 
@@ -32,9 +38,39 @@ pub const VERIFY_SHORT: char = 'v';
 pub const WARNINGS_ARE_ERRORS: &str = "warnings-are-errors";
 
 pub const BYTECODE_VERSION: &str = "bytecode-version";
+pub const BYTECODE_VERSIONS: &str = "bytecode-versions";
+
+pub const MODULE_NAMING: &str = "module-naming";
+
+pub const TRANSACTION_TEMPLATES: &str = "transaction-templates";
+
+pub const API_REPORT: &str = "api-report";
+pub const CHECK_API: &str = "check-api";
+
+pub const VISIBILITY_REPORT: &str = "visibility-report";
+
+pub const NAMED_ADDRESS_REPORT: &str = "named-address-report";
+
+pub const OPTIMIZATION_LEVEL: &str = "optimization-level";
+
+pub const INLINE_FUNCTIONS: &str = "inline-functions";
+
+pub const ICE_REPRO_DIR: &str = "ice-repro-dir";
+
+pub const DEFERRED_ADDRESSES: &str = "deferred-addresses";
+
+pub const ATTESTATION: &str = "attestation";
+pub const ATTESTATION_SEVERITY: &str = "attestation-severity";
+
+pub const MIGRATE: &str = "migrate";
 
 pub const COLOR_MODE_ENV_VAR: &str = "COLOR_MODE";
 
+/// Prefix of the environment variables giving the values of deferred named addresses
+pub const NAMED_ADDRESS_ENV_VAR_PREFIX: &str = "MOVE_ADDRESS_";
+
 pub const MOVE_COMPILED_INTERFACES_DIR: &str = "mv_interfaces";
+/// Prefix of the directory of the artifacts for each bytecode version, when several are emitted
+pub const BYTECODE_VERSION_DIR: &str = "bytecode_v";
 
 pub const COMPILED_NAMED_ADDRESS_MAPPING: &str = "compiled-module-address-name";
@@ -3,14 +3,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    cfgir::abort_codes::AbortCode,
     diag,
-    diagnostics::Diagnostics,
+    diagnostics::{Diagnostic, Diagnostics},
     expansion::ast::{Attributes, ModuleIdent, ModuleIdent_, SpecId},
     hlir::ast as H,
     parser::ast::{FunctionName, ModuleName},
-    shared::{unique_map::UniqueMap, Name, NumericalAddress},
+    shared::{abort_messages::AbortMessage, unique_map::UniqueMap, Name, NumericalAddress},
+};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format as F,
+    file_format_common::{VERSION_6, VERSION_MAX, VERSION_MIN},
 };
-use move_binary_format::file_format as F;
 use move_bytecode_source_map::source_map::SourceMap;
 use move_core_types::{
     account_address::AccountAddress, identifier::Identifier as MoveCoreIdentifier,
@@ -70,6 +75,10 @@ pub struct AnnotatedCompiledModule {
     pub address_name: Option<Name>,
     pub named_module: NamedCompiledModule,
     pub function_infos: UniqueMap<FunctionName, FunctionInfo>,
+    /// The catalogue of the formatted abort messages of the module
+    pub abort_messages: Vec<AbortMessage>,
+    /// The constants used as abort codes by the module
+    pub abort_codes: Vec<AbortCode>,
 }
 
 #[derive(Debug, Clone)]
@@ -143,6 +152,34 @@ impl AnnotatedCompiledUnit {
         }
     }
 
+    /// Reports the constructs of the unit that cannot be represented in bytecode version
+    /// `version`, at their location in the source
+    pub fn check_bytecode_version(&self, version: u32) -> Diagnostics {
+        let mut diags = Diagnostics::new();
+        if version >= SIZED_INTEGERS_VERSION {
+            return diags;
+        }
+        match self {
+            Self::Module(AnnotatedCompiledModule {
+                loc,
+                named_module:
+                    NamedCompiledModule {
+                        module, source_map, ..
+                    },
+                ..
+            }) => check_module_version(&mut diags, source_map, *loc, module, version),
+            Self::Script(AnnotatedCompiledScript {
+                loc,
+                named_script:
+                    NamedCompiledScript {
+                        script, source_map, ..
+                    },
+                ..
+            }) => check_script_version(&mut diags, source_map, *loc, script, version),
+        }
+        diags
+    }
+
     pub fn into_compiled_unit(self) -> CompiledUnit {
         match self {
             Self::Module(AnnotatedCompiledModule {
@@ -266,6 +303,172 @@ fn verify_script(sm: &SourceMap, loc: Loc, cs: &F::CompiledScript) -> Diagnostic
     }
 }
 
+//**************************************************************************************************
+// Bytecode versions
+//**************************************************************************************************
+
+/// The first bytecode version supporting the u16, u32 and u256 integer types
+const SIZED_INTEGERS_VERSION: u32 = VERSION_6;
+
+fn check_module_version(
+    diags: &mut Diagnostics,
+    sm: &SourceMap,
+    loc: Loc,
+    module: &F::CompiledModule,
+    version: u32,
+) {
+    for (idx, sdef) in module.struct_defs().iter().enumerate() {
+        let F::StructFieldInformation::Declared(fields) = &sdef.field_information else {
+            continue;
+        };
+        let struct_sm = sm
+            .get_struct_source_map(F::StructDefinitionIndex(idx as u16))
+            .ok();
+        for (field_idx, field) in fields.iter().enumerate() {
+            if let Some(ty) = sized_integer(&field.signature.0) {
+                let field_loc = struct_sm
+                    .and_then(|struct_sm| struct_sm.get_field_location(field_idx as u16))
+                    .unwrap_or(loc);
+                let what = format!("A field of type '{}' is", ty);
+                diags.add(unsupported_in_version(field_loc, what, version))
+            }
+        }
+    }
+    for (idx, fdef) in module.function_defs().iter().enumerate() {
+        let fdef_idx = F::FunctionDefinitionIndex(idx as u16);
+        let handle = module.function_handle_at(fdef.function);
+        let locals = fdef.code.as_ref().map(|code| code.locals);
+        let signatures = [Some(handle.parameters), Some(handle.return_), locals];
+        let function_loc = sm
+            .get_function_source_map(fdef_idx)
+            .map(|function_sm| function_sm.definition_location)
+            .unwrap_or(loc);
+        let tokens = signatures
+            .into_iter()
+            .flatten()
+            .flat_map(|idx| &module.signature_at(idx).0);
+        if let Some(ty) = tokens.filter_map(sized_integer).next() {
+            let name = module.identifier_at(handle.name);
+            let what = format!("A local of type '{}' in function '{}' is", ty, name);
+            diags.add(unsupported_in_version(function_loc, what, version))
+        }
+        if let Some(code) = &fdef.code {
+            check_code_version(diags, sm, fdef_idx, function_loc, &code.code, version)
+        }
+    }
+    check_constants_version(diags, sm, loc, module.constant_pool(), version)
+}
+
+fn check_script_version(
+    diags: &mut Diagnostics,
+    sm: &SourceMap,
+    loc: Loc,
+    script: &F::CompiledScript,
+    version: u32,
+) {
+    let signatures = [script.parameters, script.code.locals];
+    let tokens = signatures
+        .into_iter()
+        .flat_map(|idx| &script.signatures[idx.0 as usize].0);
+    if let Some(ty) = tokens.filter_map(sized_integer).next() {
+        let what = format!("A local of type '{}' is", ty);
+        diags.add(unsupported_in_version(loc, what, version))
+    }
+    let fdef_idx = F::FunctionDefinitionIndex(0);
+    check_code_version(diags, sm, fdef_idx, loc, &script.code.code, version);
+    check_constants_version(diags, sm, loc, &script.constant_pool, version)
+}
+
+fn check_code_version(
+    diags: &mut Diagnostics,
+    sm: &SourceMap,
+    fdef_idx: F::FunctionDefinitionIndex,
+    loc: Loc,
+    code: &[F::Bytecode],
+    version: u32,
+) {
+    use F::Bytecode as B;
+    for (offset, instr) in code.iter().enumerate() {
+        let ty = match instr {
+            B::LdU16(_) | B::CastU16 => "u16",
+            B::LdU32(_) | B::CastU32 => "u32",
+            B::LdU256(_) | B::CastU256 => "u256",
+            _ => continue,
+        };
+        let instr_loc = sm
+            .get_code_location(fdef_idx, offset as F::CodeOffset)
+            .unwrap_or(loc);
+        let what = format!("A '{}' value is", ty);
+        diags.add(unsupported_in_version(instr_loc, what, version))
+    }
+}
+
+fn check_constants_version(
+    diags: &mut Diagnostics,
+    sm: &SourceMap,
+    loc: Loc,
+    constants: &[F::Constant],
+    version: u32,
+) {
+    for (name, idx) in &sm.constant_map {
+        let Some(ty) = constants
+            .get(*idx as usize)
+            .and_then(|constant| sized_integer(&constant.type_))
+        else {
+            continue;
+        };
+        let what = format!("The constant '{}' of type '{}' is", name, ty);
+        diags.add(unsupported_in_version(loc, what, version))
+    }
+}
+
+/// The first u16, u32 or u256 type in `token`
+fn sized_integer(token: &F::SignatureToken) -> Option<&'static str> {
+    token.preorder_traversal().find_map(|t| match t {
+        F::SignatureToken::U16 => Some("u16"),
+        F::SignatureToken::U32 => Some("u32"),
+        F::SignatureToken::U256 => Some("u256"),
+        _ => None,
+    })
+}
+
+fn unsupported_in_version(loc: Loc, what: String, version: u32) -> Diagnostic {
+    diag!(
+        BytecodeGeneration::UnsupportedInBytecodeVersion,
+        (
+            loc,
+            format!(
+                "{} not supported in bytecode version {}. The integer types u16, u32 and u256 \
+                 require bytecode version {} or later",
+                what, version, SIZED_INTEGERS_VERSION
+            )
+        )
+    )
+}
+
+/// Reports the constructs of the units that cannot be represented in each of the bytecode
+/// versions, or fails if a version is not supported by the serializer
+pub fn check_bytecode_versions(
+    units: &[AnnotatedCompiledUnit],
+    versions: &[u32],
+) -> anyhow::Result<Diagnostics> {
+    let mut diags = Diagnostics::new();
+    for version in versions {
+        if !(VERSION_MIN..=VERSION_MAX).contains(version) {
+            anyhow::bail!(
+                "Unsupported bytecode version {}. Expected a version between {} and {}",
+                version,
+                VERSION_MIN,
+                VERSION_MAX
+            )
+        }
+        for unit in units {
+            diags.extend(unit.check_bytecode_version(*version))
+        }
+    }
+    Ok(diags)
+}
+
 pub fn verify_units<'a>(units: impl IntoIterator<Item = &'a AnnotatedCompiledUnit>) -> Diagnostics {
     let mut diags = Diagnostics::new();
     for unit in units {
@@ -182,6 +182,7 @@ codes!(
         InvalidSpecBlockMember: { msg: "invalid spec block member", severity: NonblockingError },
         InvalidRestrictedIdentifier:
             { msg: "invalid identifier escape", severity: NonblockingError },
+        InvalidAbortMessage: { msg: "invalid abort message", severity: NonblockingError },
     ],
     // errors for any rules around declaration items
     Declarations: [
@@ -207,6 +208,10 @@ codes!(
         InvalidVisibilityModifier:
             { msg: "invalid visibility modifier", severity: NonblockingError },
         InvalidUseFun: { msg: "invalid 'use fun' declaration", severity: NonblockingError },
+        DuplicateModuleResolved:
+            { msg: "duplicate module definition resolved", severity: Warning },
+        DuplicateModuleAcrossPackages:
+            { msg: "module defined by several packages", severity: Warning },
     ],
     // errors name resolution, mostly expansion/translate and naming/translate
     NameResolution: [
@@ -223,6 +228,10 @@ codes!(
         ReservedName: { msg: "invalid use of reserved name", severity: BlockingError },
         UnboundMacro: { msg: "unbound macro", severity: BlockingError },
         PositionalCallMismatch: { msg: "positional call mismatch", severity: NonblockingError },
+        UndeclaredPackageDependency: {
+            msg: "use of an undeclared package dependency",
+            severity: NonblockingError,
+        },
     ],
     // errors for typing rules. mostly typing/translate
     TypeSafety: [
@@ -257,6 +266,7 @@ codes!(
         InvalidMethodCall: { msg: "invalid method call", severity: BlockingError },
         InvalidImmVariableUsage:
             { msg: "invalid usage of immutable variable", severity: NonblockingError },
+        ShadowedMethod: { msg: "method shadows another method", severity: Warning },
     ],
     // errors for ability rules. mostly typing/translate
     AbilitySafety: [
@@ -283,6 +293,11 @@ codes!(
     ],
     BytecodeGeneration: [
         UnfoldableConstant: { msg: "cannot compute constant value", severity: NonblockingError },
+        UnsupportedInBytecodeVersion: {
+            msg: "feature not supported in bytecode version",
+            severity: BlockingError,
+        },
+        LimitExceeded: { msg: "package limit exceeded", severity: BlockingError },
     ],
     // errors for any unused code or items
     UnusedItem: [
@@ -300,6 +315,7 @@ codes!(
         MutModifier: { msg: "unused 'mut' modifiers", severity: Warning },
         MutReference: { msg: "unused mutable reference '&mut'", severity: Warning },
         MutParam: { msg: "unused mutable reference '&mut' parameter", severity: Warning },
+        Friend: { msg: "unused 'friend' declaration", severity: Warning },
     ],
     Attributes: [
         Duplicate: { msg: "invalid duplicate attribute", severity: NonblockingError },
@@ -317,13 +333,37 @@ codes!(
     Bug: [
         BytecodeGeneration: { msg: "BYTECODE GENERATION FAILED", severity: Bug },
         BytecodeVerification: { msg: "BYTECODE VERIFICATION FAILED", severity: Bug },
+        CompilerPanic: { msg: "COMPILER PANICKED", severity: Bug },
     ],
     Editions: [
         FeatureTooNew: {
             msg: "feature is not supported in specified edition",
             severity: BlockingError,
         },
-    ]
+    ],
+    // changes needed to move to a newer edition, reported only in migration mode
+    Migration: [
+        NeedsLetMut: { msg: "needs 'mut' modifier", severity: NonblockingError },
+        RemoveFriend: { msg: "remove 'friend' declaration", severity: NonblockingError },
+        MakePubPackage: { msg: "replace with 'public(package)'", severity: NonblockingError },
+    ],
+    // issues in spec blocks that would otherwise only be found by the prover
+    Specs: [
+        UnresolvedName: { msg: "unresolved name in spec block", severity: Warning },
+    ],
+    // findings of the prover backend, run at PASS_PROVER
+    Prover: [
+        VerificationError: { msg: "verification failed", severity: NonblockingError },
+        VerificationWarning: { msg: "verification warning", severity: Warning },
+    ],
+    // legacy definitions still accepted, but slated for removal
+    Deprecation: [
+        Script: { msg: "deprecated script", severity: Warning },
+        UntranslatableScript: {
+            msg: "script cannot be translated to a transaction",
+            severity: NonblockingError,
+        },
+    ],
 );
 
 //**************************************************************************************************
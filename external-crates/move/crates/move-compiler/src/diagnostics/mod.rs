@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod codes;
+pub mod testing;
 
 use crate::{
     command_line::COLOR_MODE_ENV_VAR,
@@ -11,8 +12,9 @@ use crate::{
         WellKnownFilterName,
     },
     shared::{
-        ast_debug::AstDebug, FILTER_UNUSED_CONST, FILTER_UNUSED_FUNCTION, FILTER_UNUSED_MUT_PARAM,
-        FILTER_UNUSED_MUT_REF, FILTER_UNUSED_STRUCT_FIELD, FILTER_UNUSED_TYPE_PARAMETER,
+        ast_debug::AstDebug, FILTER_UNUSED_CONST, FILTER_UNUSED_FRIEND, FILTER_UNUSED_FUNCTION,
+        FILTER_UNUSED_MUT_PARAM, FILTER_UNUSED_MUT_REF, FILTER_UNUSED_STRUCT_FIELD,
+        FILTER_UNUSED_TYPE_PARAMETER,
     },
 };
 use codespan_reporting::{
@@ -31,6 +33,7 @@ use std::{
     collections::{BTreeMap, HashMap, HashSet},
     iter::FromIterator,
     ops::Range,
+    sync::Arc,
 };
 
 use self::codes::{UnusedItem, WARNING_FILTER_ATTR};
@@ -66,9 +69,11 @@ struct Diagnostics_ {
 }
 
 #[derive(PartialEq, Eq, Clone, Debug)]
-/// Used to filter out diagnostics, specifically used for warning suppression
+/// Used to filter out diagnostics, specifically used for warning suppression.
+/// The filters are shared between clones, and only copied when a clone is modified, as they are
+/// cloned for every module member during expansion
 pub struct WarningFilters {
-    filters: BTreeMap<ExternalPrefix, UnprefixedWarningFilters>,
+    filters: Arc<BTreeMap<ExternalPrefix, UnprefixedWarningFilters>>,
     for_dependency: bool, // if false, the filters are used for source code
 }
 
@@ -192,9 +197,13 @@ fn render_diagnostic(
     diag: Diagnostic,
 ) -> csr::diagnostic::Diagnostic<FileId> {
     use csr::diagnostic::{Label, LabelStyle};
-    let mk_lbl = |style: LabelStyle, msg: (Loc, String)| -> Label<FileId> {
+    // a diagnostic at `Loc::invalid()` is not reported in any file, so it has no label there
+    let mk_lbl = |style: LabelStyle, msg: (Loc, String)| -> Option<Label<FileId>> {
+        if msg.0 == Loc::invalid() {
+            return None;
+        }
         let (id, range) = convert_loc(file_mapping, msg.0);
-        csr::diagnostic::Label::new(style, id, range).with_message(msg.1)
+        Some(csr::diagnostic::Label::new(style, id, range).with_message(msg.1))
     };
     let Diagnostic {
         info,
@@ -206,11 +215,15 @@ fn render_diagnostic(
     let (code, message) = info.render();
     diag = diag.with_code(code);
     diag = diag.with_message(message.to_string());
-    diag = diag.with_labels(vec![mk_lbl(LabelStyle::Primary, primary_label)]);
+    diag = diag.with_labels(
+        mk_lbl(LabelStyle::Primary, primary_label)
+            .into_iter()
+            .collect(),
+    );
     diag = diag.with_labels(
         secondary_labels
             .into_iter()
-            .map(|msg| mk_lbl(LabelStyle::Secondary, msg))
+            .filter_map(|msg| mk_lbl(LabelStyle::Secondary, msg))
             .collect(),
     );
     diag = diag.with_notes(notes);
@@ -246,6 +259,10 @@ impl Diagnostics {
         inner.diagnostics.len()
     }
 
+    pub fn iter(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.0.iter().flat_map(|inner| inner.diagnostics.iter())
+    }
+
     pub fn add(&mut self, diag: Diagnostic) {
         if self.0.is_none() {
             self.0 = Some(Diagnostics_::default())
@@ -414,6 +431,10 @@ impl Diagnostic {
     pub fn info(&self) -> &DiagnosticInfo {
         &self.info
     }
+
+    pub fn primary_loc(&self) -> Loc {
+        self.primary_label.0
+    }
 }
 
 #[macro_export]
@@ -443,14 +464,14 @@ macro_rules! diag {
 impl WarningFilters {
     pub fn new_for_source() -> Self {
         Self {
-            filters: BTreeMap::new(),
+            filters: Arc::new(BTreeMap::new()),
             for_dependency: false,
         }
     }
 
     pub fn new_for_dependency() -> Self {
         Self {
-            filters: BTreeMap::new(),
+            filters: Arc::new(BTreeMap::new()),
             for_dependency: true,
         }
     }
@@ -467,11 +488,18 @@ impl WarningFilters {
     }
 
     pub fn union(&mut self, other: &Self) {
-        for (prefix, filters) in &other.filters {
-            self.filters
-                .entry(*prefix)
-                .or_insert_with(UnprefixedWarningFilters::new)
-                .union(filters);
+        // share the other filters when there is nothing to merge them with, and skip the union
+        // when both are already the same filters
+        if self.filters.is_empty() {
+            self.filters = other.filters.clone();
+        } else if !other.filters.is_empty() && !Arc::ptr_eq(&self.filters, &other.filters) {
+            let self_filters = Arc::make_mut(&mut self.filters);
+            for (prefix, filters) in other.filters.iter() {
+                self_filters
+                    .entry(*prefix)
+                    .or_insert_with(UnprefixedWarningFilters::new)
+                    .union(filters);
+            }
         }
         // if there is a dependency code filter on the stack, it means we are filtering dependent
         // code and this information must be preserved when stacking up additional filters (which
@@ -482,7 +510,7 @@ impl WarningFilters {
     pub fn add(&mut self, filter: WarningFilter) {
         let (prefix, category, code, name) = match filter {
             WarningFilter::All(prefix) => {
-                self.filters.insert(prefix, UnprefixedWarningFilters::All);
+                Arc::make_mut(&mut self.filters).insert(prefix, UnprefixedWarningFilters::All);
                 return;
             }
             WarningFilter::Category {
@@ -497,7 +525,7 @@ impl WarningFilters {
                 name,
             } => (prefix, category, Some(code), name),
         };
-        self.filters
+        Arc::make_mut(&mut self.filters)
             .entry(prefix)
             .or_insert(UnprefixedWarningFilters::Empty)
             .add(category, code, name)
@@ -505,10 +533,10 @@ impl WarningFilters {
 
     pub fn unused_warnings_filter_for_test() -> Self {
         Self {
-            filters: BTreeMap::from([(
+            filters: Arc::new(BTreeMap::from([(
                 None,
                 UnprefixedWarningFilters::unused_warnings_filter_for_test(),
-            )]),
+            )])),
             for_dependency: false,
         }
     }
@@ -600,6 +628,7 @@ impl UnprefixedWarningFilters {
             (UnusedItem::Constant, FILTER_UNUSED_CONST),
             (UnusedItem::MutReference, FILTER_UNUSED_MUT_REF),
             (UnusedItem::MutParam, FILTER_UNUSED_MUT_PARAM),
+            (UnusedItem::Friend, FILTER_UNUSED_FRIEND),
         ]
         .into_iter()
         .map(|(item, filter)| {
@@ -651,7 +680,7 @@ impl From<Option<Diagnostic>> for Diagnostics {
 
 impl AstDebug for WarningFilters {
     fn ast_debug(&self, w: &mut crate::shared::ast_debug::AstWriter) {
-        for (prefix, filters) in &self.filters {
+        for (prefix, filters) in self.filters.iter() {
             let prefix_str = prefix.unwrap_or(WARNING_FILTER_ATTR);
             match filters {
                 UnprefixedWarningFilters::All => w.write(&format!(
@@ -0,0 +1,264 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A harness for tests that compile Move sources and check the diagnostics reported against an
+//! expected baseline. Each diagnostic is recorded by its code and the span of its primary label,
+//! so the baseline is not affected by rewording messages or notes. Tools extending the compiler,
+//! e.g. with additional lints, can use it to test their visitors without depending on the
+//! compiler's own testsuite.
+
+use crate::{
+    command_line::compiler::{move_check_for_errors, Visitor},
+    diagnostics::{
+        codes::{Severity, WarningFilter},
+        Diagnostic, Diagnostics, FilesSourceText,
+    },
+    expansion::ast as E,
+    shared::{Flags, NumericalAddress, PackageConfig, PackagePaths},
+    Compiler, PASS_PARSER,
+};
+use move_command_line_common::testing::{
+    add_update_baseline_fix, format_diff, read_env_update_baseline,
+};
+use move_ir_types::location::Loc;
+use move_symbol_pool::Symbol;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::{self, Write},
+    fs,
+    path::Path,
+};
+
+/// Extension for expected diagnostics files
+pub const DIAGNOSTICS_EXT: &str = "diags";
+
+/// A diagnostic reported by the compiler, as recorded in the expected diagnostics baseline
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ReportedDiagnostic {
+    /// The name of the file containing the primary label, without its directory
+    pub file: String,
+    pub start: Position,
+    pub end: Position,
+    /// The rendered code of the diagnostic, e.g. `W09001` or `Lint W01002`
+    pub code: String,
+    pub severity: Severity,
+    /// The message of the primary label, not part of the baseline
+    pub label: String,
+}
+
+/// A 1-based line and column, where columns are counted in characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// The configuration of a compilation whose diagnostics are checked
+pub struct DiagnosticsTest {
+    targets: Vec<String>,
+    deps: Vec<PackagePaths>,
+    named_address_map: BTreeMap<String, NumericalAddress>,
+    flags: Flags,
+    config: PackageConfig,
+    visitors: Vec<Visitor>,
+    known_filters: Vec<(Vec<WarningFilter>, E::AttributeName_)>,
+}
+
+impl DiagnosticsTest {
+    /// A test compiling the given source files, with no dependencies
+    pub fn new(targets: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        Self {
+            targets: targets.into_iter().map(Into::into).collect(),
+            deps: vec![],
+            named_address_map: BTreeMap::new(),
+            flags: Flags::empty(),
+            config: PackageConfig::default(),
+            visitors: vec![],
+            known_filters: vec![],
+        }
+    }
+
+    /// Adds a dependency package. Diagnostics in dependencies are filtered by the compiler as
+    /// usual, and so do not appear in the baseline.
+    pub fn add_dependency(mut self, dep: PackagePaths) -> Self {
+        self.deps.push(dep);
+        self
+    }
+
+    /// Sets the named addresses used to compile the targets
+    pub fn set_named_address_map(
+        mut self,
+        named_address_map: BTreeMap<String, NumericalAddress>,
+    ) -> Self {
+        self.named_address_map = named_address_map;
+        self
+    }
+
+    pub fn set_flags(mut self, flags: Flags) -> Self {
+        self.flags = flags;
+        self
+    }
+
+    /// Sets the config of the targets, e.g. their edition or flavor
+    pub fn set_config(mut self, config: PackageConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn add_visitor(mut self, visitor: impl Into<Visitor>) -> Self {
+        self.visitors.push(visitor.into());
+        self
+    }
+
+    /// Registers the warning filters of the diagnostics added by visitors, so that they can be
+    /// suppressed in the sources with `filter_attr_name`
+    pub fn add_custom_known_filters(
+        mut self,
+        filters: Vec<WarningFilter>,
+        filter_attr_name: E::AttributeName_,
+    ) -> Self {
+        self.known_filters.push((filters, filter_attr_name));
+        self
+    }
+
+    /// Compiles the targets, returning the diagnostics reported, sorted by location and without
+    /// duplicates
+    pub fn diagnostics(self) -> anyhow::Result<Vec<ReportedDiagnostic>> {
+        let Self {
+            targets,
+            deps,
+            named_address_map,
+            flags,
+            config,
+            visitors,
+            known_filters,
+        } = self;
+        let targets = vec![PackagePaths {
+            name: None,
+            paths: targets.into_iter().map(Symbol::from).collect(),
+            named_address_map: named_address_map
+                .into_iter()
+                .map(|(name, addr)| (Symbol::from(name), addr))
+                .collect(),
+        }];
+        let mut compiler = Compiler::from_package_paths(targets, deps)?
+            .set_flags(flags)
+            .set_default_config(config)
+            .add_visitors(visitors);
+        for (filters, filter_attr_name) in known_filters {
+            compiler = compiler.add_custom_known_filters(filters, filter_attr_name);
+        }
+        let (files, comments_and_compiler_res) = compiler.run::<PASS_PARSER>()?;
+        let diags = move_check_for_errors(comments_and_compiler_res);
+        Ok(reported_diagnostics(&files, diags))
+    }
+
+    /// Compiles the targets, and checks the diagnostics reported against the baseline in
+    /// `exp_path`, where a missing baseline means no diagnostics are expected. If the
+    /// `UPDATE_BASELINE` environment variable is set, the baseline is overwritten instead.
+    pub fn check(self, exp_path: &Path) -> anyhow::Result<()> {
+        let diags = self.diagnostics()?;
+        let mut rendered = String::new();
+        for diag in &diags {
+            writeln!(rendered, "{diag}").unwrap();
+        }
+
+        if read_env_update_baseline() {
+            if !diags.is_empty() {
+                fs::write(exp_path, &rendered)?;
+            } else if exp_path.is_file() {
+                fs::remove_file(exp_path)?;
+            }
+            return Ok(());
+        }
+
+        let expected = if exp_path.is_file() {
+            fs::read_to_string(exp_path)?
+        } else {
+            String::new()
+        };
+        if rendered != expected {
+            let msg = format!(
+                "Expected diagnostics differ from actual diagnostics:\n{}",
+                format_diff(expected, rendered),
+            );
+            anyhow::bail!(add_update_baseline_fix(msg))
+        }
+        Ok(())
+    }
+}
+
+fn reported_diagnostics(files: &FilesSourceText, diags: Diagnostics) -> Vec<ReportedDiagnostic> {
+    let diags: BTreeSet<_> = diags
+        .into_vec()
+        .into_iter()
+        .map(|diag| ReportedDiagnostic::new(files, diag))
+        .collect();
+    diags.into_iter().collect()
+}
+
+impl ReportedDiagnostic {
+    fn new(files: &FilesSourceText, diag: Diagnostic) -> Self {
+        let Diagnostic {
+            info,
+            primary_label: (loc, label),
+            ..
+        } = diag;
+        let severity = info.severity();
+        let (code, _) = info.render();
+        let (file, start, end) = match files.get(&loc.file_hash()) {
+            Some((fname, source)) => {
+                let file = Path::new(fname.as_str())
+                    .file_name()
+                    .map_or_else(|| fname.to_string(), |f| f.to_string_lossy().into_owned());
+                let (start, end) = span(source, loc);
+                (file, start, end)
+            }
+            None => {
+                let unknown = Position { line: 0, column: 0 };
+                ("<unknown>".to_string(), unknown, unknown)
+            }
+        };
+        Self {
+            file,
+            start,
+            end,
+            code,
+            severity,
+            label,
+        }
+    }
+}
+
+fn span(source: &str, loc: Loc) -> (Position, Position) {
+    let position = |offset: usize| {
+        let offset = offset.min(source.len());
+        let before = &source[..offset];
+        let line_start = before.rfind('\n').map_or(0, |idx| idx + 1);
+        Position {
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+        }
+    };
+    let range = loc.usize_range();
+    (position(range.start), position(range.end))
+}
+
+impl fmt::Display for ReportedDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self {
+            file,
+            start,
+            end,
+            code,
+            ..
+        } = self;
+        write!(f, "{code} {file}:{start}-{end}")
+    }
+}
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Converts the `Migration` diagnostics produced when compiling under `Edition::E2024_MIGRATION`
+//! into source rewrites
+
+use std::collections::BTreeMap;
+
+use crate::{
+    diagnostics::{
+        codes::{self, DiagnosticCode},
+        Diagnostic, Diagnostics, FileName, FilesSourceText,
+    },
+    expansion::ast::Visibility,
+};
+use move_command_line_common::files::FileHash;
+
+//**************************************************************************************************
+// types
+//**************************************************************************************************
+
+/// A single textual edit, replacing the bytes `start..end` with `replacement`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Change {
+    pub start: usize,
+    pub end: usize,
+    pub replacement: String,
+}
+
+pub struct Migration {
+    files: FilesSourceText,
+    changes: BTreeMap<FileHash, Vec<Change>>,
+    /// Diagnostics that cannot be fixed automatically, including any feature gate errors
+    unresolved: Diagnostics,
+}
+
+//**************************************************************************************************
+// impls
+//**************************************************************************************************
+
+impl Migration {
+    pub fn new(files: FilesSourceText, diags: Diagnostics) -> Self {
+        let mut changes: BTreeMap<FileHash, Vec<Change>> = BTreeMap::new();
+        let mut unresolved = Diagnostics::new();
+        for diag in diags.into_vec() {
+            match change_for_diag(&files, &diag) {
+                Some(change) => changes
+                    .entry(diag.primary_loc().file_hash())
+                    .or_default()
+                    .push(change),
+                None => unresolved.add(diag),
+            }
+        }
+        for file_changes in changes.values_mut() {
+            file_changes.sort();
+            file_changes.dedup();
+        }
+        Self {
+            files,
+            changes,
+            unresolved,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    pub fn changes(&self) -> &BTreeMap<FileHash, Vec<Change>> {
+        &self.changes
+    }
+
+    pub fn unresolved(&self) -> &Diagnostics {
+        &self.unresolved
+    }
+
+    pub fn files(&self) -> &FilesSourceText {
+        &self.files
+    }
+
+    /// Returns the migrated contents of every file that has at least one change
+    pub fn render_output(&self) -> BTreeMap<FileName, String> {
+        self.changes
+            .iter()
+            .map(|(fhash, file_changes)| {
+                let (fname, source) = self.files.get(fhash).unwrap();
+                (*fname, apply_changes(source, file_changes))
+            })
+            .collect()
+    }
+}
+
+fn change_for_diag(files: &FilesSourceText, diag: &Diagnostic) -> Option<Change> {
+    let info = diag.info();
+    if info.is_external() || info.category() != codes::Category::Migration as u8 {
+        return None;
+    }
+    let loc = diag.primary_loc();
+    let (_, source) = files.get(&loc.file_hash())?;
+    let start = loc.start() as usize;
+    let end = loc.end() as usize;
+    let code = info.code();
+    let change = if code == code_of(codes::Migration::NeedsLetMut) {
+        Change {
+            start,
+            end: start,
+            replacement: "mut ".to_string(),
+        }
+    } else if code == code_of(codes::Migration::RemoveFriend) {
+        // if the declaration is alone on its line, remove the whole line, including its
+        // indentation and newline
+        let line_start = source[..start].rfind('\n').map_or(0, |newline| newline + 1);
+        let line_end = source[end..]
+            .find('\n')
+            .map_or(source.len(), |newline| end + newline + 1);
+        let (start, end) = if source[line_start..start].trim().is_empty()
+            && source[end..line_end].trim().is_empty()
+        {
+            (line_start, line_end)
+        } else {
+            (start, end)
+        };
+        Change {
+            start,
+            end,
+            replacement: String::new(),
+        }
+    } else if code == code_of(codes::Migration::MakePubPackage) {
+        Change {
+            start,
+            end,
+            replacement: Visibility::PACKAGE.to_string(),
+        }
+    } else {
+        return None;
+    };
+    Some(change)
+}
+
+fn code_of(code: impl DiagnosticCode) -> u8 {
+    code.into_info().code()
+}
+
+/// Applies non-overlapping, sorted changes to the source text
+fn apply_changes(source: &str, changes: &[Change]) -> String {
+    let mut output = String::with_capacity(source.len());
+    let mut cur = 0;
+    for Change {
+        start,
+        end,
+        replacement,
+    } in changes
+    {
+        if *start < cur {
+            // overlapping change, keep the first one
+            continue;
+        }
+        output.push_str(&source[cur..*start]);
+        output.push_str(replacement);
+        cur = *end;
+    }
+    output.push_str(&source[cur..]);
+    output
+}
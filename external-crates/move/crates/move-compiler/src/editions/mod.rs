@@ -9,12 +9,17 @@ use std::{
     str::FromStr,
 };
 
-use crate::{diag, shared::CompilationEnv};
+use crate::{
+    diag,
+    shared::{CompilationEnv, PackageConfig},
+};
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 
+pub mod migration;
+
 //**************************************************************************************************
 // types
 //**************************************************************************************************
@@ -36,6 +41,15 @@ pub enum FeatureGate {
     LetMut,
 }
 
+/// Describes a single feature gate: the editions that support it, and whether it is enabled by a
+/// given package configuration.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct FeatureGateInfo {
+    pub feature: FeatureGate,
+    pub supported_editions: Vec<Edition>,
+    pub enabled: bool,
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug, PartialOrd, Ord, Default)]
 pub enum Flavor {
     #[default]
@@ -94,6 +108,19 @@ pub fn valid_editions_for_feature(feature: FeatureGate) -> Vec<Edition> {
         .collect()
 }
 
+/// Lists every feature gate, the editions that support it, and whether `config` enables it.
+/// Intended for build tools that want to surface edition requirements before compilation.
+pub fn feature_gates(config: &PackageConfig) -> Vec<FeatureGateInfo> {
+    FeatureGate::ALL
+        .iter()
+        .map(|feature| FeatureGateInfo {
+            feature: *feature,
+            supported_editions: valid_editions_for_feature(*feature),
+            enabled: config.edition.supports(*feature),
+        })
+        .collect()
+}
+
 //**************************************************************************************************
 // impls
 //**************************************************************************************************
@@ -120,10 +147,16 @@ impl Edition {
         edition: symbol!("2024"),
         release: Some(symbol!("alpha")),
     };
+    /// Compiles with legacy syntax, but reports the changes needed to move to the 2024 edition
+    /// as `Migration` diagnostics
+    pub const E2024_MIGRATION: Self = Self {
+        edition: symbol!("2024"),
+        release: Some(symbol!("migration")),
+    };
 
     const SEP: &str = ".";
 
-    pub const ALL: &[Self] = &[Self::LEGACY, Self::E2024_ALPHA];
+    pub const ALL: &[Self] = &[Self::LEGACY, Self::E2024_ALPHA, Self::E2024_MIGRATION];
 
     pub fn supports(&self, feature: FeatureGate) -> bool {
         SUPPORTED_FEATURES.get(self).unwrap().contains(&feature)
@@ -133,14 +166,22 @@ impl Edition {
         match *self {
             Self::LEGACY => SyntaxEdition::Legacy,
             Self::E2024_ALPHA => SyntaxEdition::E2024,
+            Self::E2024_MIGRATION => SyntaxEdition::Legacy,
             _ => self.unknown_edition_panic(),
         }
     }
+
+    /// Returns true if this edition should report migration changes
+    pub fn is_migration(&self) -> bool {
+        *self == Self::E2024_MIGRATION
+    }
+
     // Intended only for implementing the lazy static (supported feature map) above
     fn prev(&self) -> Option<Self> {
         match *self {
             Self::LEGACY => None,
             Self::E2024_ALPHA => Some(Self::LEGACY),
+            Self::E2024_MIGRATION => Some(Self::LEGACY),
             _ => self.unknown_edition_panic(),
         }
     }
@@ -155,6 +196,8 @@ impl Edition {
                 features.extend(E2024_ALPHA_FEATURES);
                 features
             }
+            // migration does not enable any features, it only reports what needs to change
+            Self::E2024_MIGRATION => self.prev().unwrap().features(),
             _ => self.unknown_edition_panic(),
         }
     }
@@ -182,6 +225,33 @@ impl Flavor {
 }
 
 impl FeatureGate {
+    pub const ALL: &[Self] = &[
+        Self::NestedUse,
+        Self::PublicPackage,
+        Self::PostFixAbilities,
+        Self::StructTypeVisibility,
+        Self::DotCall,
+        Self::PositionalFields,
+        Self::LetMut,
+    ];
+
+    /// The earliest edition that supports this feature, if any
+    pub fn min_edition(&self) -> Option<Edition> {
+        Edition::ALL.iter().find(|e| e.supports(*self)).copied()
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FeatureGate::NestedUse => "nested_use",
+            FeatureGate::PublicPackage => "public_package",
+            FeatureGate::PostFixAbilities => "postfix_abilities",
+            FeatureGate::StructTypeVisibility => "struct_type_visibility",
+            FeatureGate::DotCall => "dot_call",
+            FeatureGate::PositionalFields => "positional_fields",
+            FeatureGate::LetMut => "let_mut",
+        }
+    }
+
     fn error_prefix(&self) -> &'static str {
         match self {
             FeatureGate::NestedUse => "Nested 'use' forms are",
@@ -272,6 +342,12 @@ impl Display for Edition {
     }
 }
 
+impl Display for FeatureGate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
 impl Display for Flavor {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
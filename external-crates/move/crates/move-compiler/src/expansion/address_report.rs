@@ -0,0 +1,187 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A report of the named addresses of a program: the value of each name, the packages that
+//! declare it, and why it conflicts with other names, if it does. Names conflict when there is no
+//! bidirectional mapping between a name and its value, i.e. the name has several values, or its
+//! value is shared with other names. Addresses with conflicting names are displayed along with
+//! their value, e.g. `(std=0x1)`. The command line tools print the report when
+//! `Flags::named_address_report` is set.
+
+use crate::{
+    command_line::compiler::FullyCompiledProgram,
+    parser::ast as P,
+    shared::{NamedAddressMapIndex, NamedAddressMaps},
+};
+use move_core_types::account_address::AccountAddress;
+use move_symbol_pool::Symbol;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct NamedAddressReport {
+    /// One entry per distinct name and value
+    pub entries: Vec<NamedAddressEntry>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamedAddressEntry {
+    pub name: Symbol,
+    pub value: AccountAddress,
+    /// The packages assigning this value to the name. `None` for sources without a package.
+    pub packages: BTreeSet<Option<Symbol>>,
+    /// The other values assigned to the name by other packages
+    pub other_values: BTreeSet<AccountAddress>,
+    /// The other names with the same value
+    pub other_names: BTreeSet<Symbol>,
+}
+
+impl NamedAddressEntry {
+    /// Whether the name is marked as conflicting
+    pub fn is_conflict(&self) -> bool {
+        !self.other_values.is_empty() || !self.other_names.is_empty()
+    }
+}
+
+impl NamedAddressReport {
+    /// The report for a parsed program, and the pre-compiled library it is compiled against
+    pub fn new(pre_compiled_lib: Option<&FullyCompiledProgram>, prog: &P::Program) -> Self {
+        let programs = std::iter::once(prog).chain(pre_compiled_lib.map(|pre| &pre.parser));
+        Self::from_maps(programs.map(|prog| {
+            let packages = prog
+                .source_definitions
+                .iter()
+                .chain(&prog.lib_definitions)
+                .map(|def| (def.package, def.named_address_map));
+            (&prog.named_address_maps, packages)
+        }))
+    }
+
+    /// The report for sets of named address maps, given the packages using each map
+    pub(crate) fn from_maps<'a>(
+        maps: impl IntoIterator<
+            Item = (
+                &'a NamedAddressMaps,
+                impl IntoIterator<Item = (Option<Symbol>, NamedAddressMapIndex)>,
+            ),
+        >,
+    ) -> Self {
+        let mut packages: BTreeMap<(Symbol, AccountAddress), BTreeSet<Option<Symbol>>> =
+            BTreeMap::new();
+        for (named_address_maps, map_users) in maps {
+            let mut map_packages: BTreeMap<usize, BTreeSet<Option<Symbol>>> = BTreeMap::new();
+            for (package, idx) in map_users {
+                let idx = named_address_maps.position(idx);
+                map_packages.entry(idx).or_default().insert(package);
+            }
+            for (idx, map) in named_address_maps.all().iter().enumerate() {
+                for (n, addr) in map {
+                    let pkgs = packages.entry((*n, addr.into_inner())).or_default();
+                    if let Some(map_pkgs) = map_packages.get(&idx) {
+                        pkgs.extend(map_pkgs.iter().copied())
+                    }
+                }
+            }
+        }
+
+        let mut name_to_addr: BTreeMap<Symbol, BTreeSet<AccountAddress>> = BTreeMap::new();
+        let mut addr_to_name: BTreeMap<AccountAddress, BTreeSet<Symbol>> = BTreeMap::new();
+        for (n, addr) in packages.keys() {
+            name_to_addr.entry(*n).or_default().insert(*addr);
+            addr_to_name.entry(*addr).or_default().insert(*n);
+        }
+        let entries = packages
+            .into_iter()
+            .map(|((name, value), packages)| NamedAddressEntry {
+                name,
+                value,
+                packages,
+                other_values: others(&name_to_addr[&name], &value),
+                other_names: others(&addr_to_name[&value], &name),
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// The names marked as conflicting
+    pub fn conflicts(&self) -> BTreeSet<Symbol> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.is_conflict())
+            .map(|entry| entry.name)
+            .collect()
+    }
+}
+
+fn others<T: Ord + Copy>(set: &BTreeSet<T>, this: &T) -> BTreeSet<T> {
+    set.iter().filter(|v| *v != this).copied().collect()
+}
+
+impl fmt::Display for NamedAddressReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rows: Vec<[String; 4]> = self
+            .entries
+            .iter()
+            .map(|entry| {
+                let packages = entry
+                    .packages
+                    .iter()
+                    .map(|pkg| pkg.map_or_else(|| "<no package>".to_string(), |p| p.to_string()))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let mut reasons = vec![];
+                if !entry.other_values.is_empty() {
+                    let values = entry
+                        .other_values
+                        .iter()
+                        .map(|v| v.to_hex_literal())
+                        .collect::<Vec<_>>();
+                    reasons.push(format!("also assigned {}", values.join(", ")));
+                }
+                if !entry.other_names.is_empty() {
+                    let names = entry
+                        .other_names
+                        .iter()
+                        .map(|n| n.to_string())
+                        .collect::<Vec<_>>();
+                    reasons.push(format!("same value as {}", names.join(", ")));
+                }
+                let conflict = if reasons.is_empty() {
+                    "no".to_string()
+                } else {
+                    format!("yes ({})", reasons.join("; "))
+                };
+                [
+                    entry.name.to_string(),
+                    entry.value.to_hex_literal(),
+                    packages,
+                    conflict,
+                ]
+            })
+            .collect();
+        let header = ["name", "value", "packages", "conflict"].map(String::from);
+        let mut widths = header.clone().map(|h| h.len());
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell.len());
+            }
+        }
+        for row in std::iter::once(&header).chain(&rows) {
+            let [name, value, packages, conflict] = row;
+            writeln!(
+                f,
+                "{:name_w$}  {:value_w$}  {:packages_w$}  {}",
+                name,
+                value,
+                packages,
+                conflict,
+                name_w = widths[0],
+                value_w = widths[1],
+                packages_w = widths[2],
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -10,6 +10,8 @@ use crate::{
 use move_ir_types::location::*;
 
 type ScopeDepth = usize;
+type ModuleAliasEntry = (Option<ScopeDepth>, ModuleIdent);
+type MemberAliasEntry = (Option<ScopeDepth>, (ModuleIdent, Name));
 
 #[derive(Clone, Debug)]
 pub struct AliasSet {
@@ -25,13 +27,22 @@ pub struct AliasMapBuilder {
 
 #[derive(Clone, Debug)]
 pub struct AliasMap {
-    modules: UniqueMap<Name, (Option<ScopeDepth>, ModuleIdent)>,
-    members: UniqueMap<Name, (Option<ScopeDepth>, (ModuleIdent, Name))>,
+    modules: UniqueMap<Name, ModuleAliasEntry>,
+    members: UniqueMap<Name, MemberAliasEntry>,
     // essentially a mapping from ScopeDepth => AliasSet, which are the unused aliases at that depth
     unused: Vec<AliasSet>,
 }
 
-pub struct OldAliasMap(Option<AliasMap>);
+/// The entries of the outer scope that were shadowed by an inner scope. Restoring these undoes the
+/// inner scope without having to copy the whole alias map on every scope entry
+pub struct OldAliasMap(Option<ShadowedAliases>);
+
+struct ShadowedAliases {
+    outer_is_empty: bool,
+    // the previous binding (if any) for each alias bound or removed by the inner scope
+    modules: Vec<(Name, Option<(Name, ModuleAliasEntry)>)>,
+    members: Vec<(Name, Option<(Name, MemberAliasEntry)>)>,
+}
 
 pub struct ParserExplicitUseFun {
     pub loc: Loc,
@@ -218,7 +229,11 @@ impl AliasMap {
             return OldAliasMap(None);
         }
 
-        let outer_scope = OldAliasMap(Some(self.clone()));
+        let mut shadowed = ShadowedAliases {
+            outer_is_empty: self.is_empty(),
+            modules: vec![],
+            members: vec![],
+        };
         let AliasMapBuilder {
             modules: new_modules,
             members: new_members,
@@ -230,20 +245,22 @@ impl AliasMap {
             if !is_implicit {
                 current_scope.modules.add(alias).unwrap();
             }
-            self.modules.remove(&alias);
+            let prev = remove_full(&mut self.modules, &alias);
+            shadowed.modules.push((alias, prev));
             self.modules.add(alias, (Some(next_depth), ident)).unwrap();
         }
         for (alias, (ident_member, is_implicit)) in new_members {
             if !is_implicit {
                 current_scope.members.add(alias).unwrap();
             }
-            self.members.remove(&alias);
+            let prev = remove_full(&mut self.members, &alias);
+            shadowed.members.push((alias, prev));
             self.members
                 .add(alias, (Some(next_depth), ident_member))
                 .unwrap();
         }
         self.unused.push(current_scope);
-        outer_scope
+        OldAliasMap(Some(shadowed))
     }
 
     /// Similar to add_and_shadow but just removes aliases now shadowed by a type parameter
@@ -259,34 +276,60 @@ impl AliasMap {
             return OldAliasMap(None);
         }
 
-        let outer_scope = OldAliasMap(Some(self.clone()));
+        let mut shadowed = ShadowedAliases {
+            outer_is_empty: self.is_empty(),
+            modules: vec![],
+            members: vec![],
+        };
         self.unused.push(AliasSet::new());
         for tp_name in tparams_iter {
-            self.members.remove(tp_name);
+            if let Some(prev) = remove_full(&mut self.members, tp_name) {
+                shadowed.members.push((*tp_name, Some(prev)));
+            }
         }
-        outer_scope
+        OldAliasMap(Some(shadowed))
     }
 
     /// Resets the alias map and gives the set of aliases that were unused
     pub fn set_to_outer_scope(&mut self, outer_scope: OldAliasMap) -> AliasSet {
-        let outer_scope = match outer_scope.0 {
+        let ShadowedAliases {
+            outer_is_empty: _,
+            modules,
+            members,
+        } = match outer_scope.0 {
             None => return AliasSet::new(),
-            Some(outer) => outer,
+            Some(shadowed) => shadowed,
         };
-        let mut inner_scope = std::mem::replace(self, outer_scope);
-        let outer_scope = self;
-        assert!(outer_scope.current_depth() + 1 == inner_scope.current_depth());
-        let unused = inner_scope.unused.pop().unwrap();
-        outer_scope.unused = inner_scope.unused;
-        unused
+        // restore in reverse, so an alias touched more than once ends with its outermost binding
+        for (alias, prev) in modules.into_iter().rev() {
+            self.modules.remove(&alias);
+            if let Some((prev_alias, entry)) = prev {
+                self.modules.add(prev_alias, entry).unwrap();
+            }
+        }
+        for (alias, prev) in members.into_iter().rev() {
+            self.members.remove(&alias);
+            if let Some((prev_alias, entry)) = prev {
+                self.members.add(prev_alias, entry).unwrap();
+            }
+        }
+        self.unused.pop().unwrap()
     }
 }
 
+/// Removes the alias, giving back the binding along with the alias as originally declared (so its
+/// location is preserved when the binding is restored)
+fn remove_full<V>(map: &mut UniqueMap<Name, V>, alias: &Name) -> Option<(Name, V)> {
+    let full_alias = map.get_full_key(alias)?;
+    let value = map.remove(alias).unwrap();
+    Some((full_alias, value))
+}
+
 impl OldAliasMap {
     pub fn is_empty(&self) -> bool {
         match &self.0 {
             None => true,
-            Some(aliases) => aliases.is_empty(),
+            Some(shadowed) => shadowed.outer_is_empty,
         }
     }
 }
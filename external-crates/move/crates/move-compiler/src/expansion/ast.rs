@@ -19,6 +19,7 @@ use std::{
     collections::{BTreeMap, BTreeSet, VecDeque},
     fmt,
     hash::Hash,
+    sync::Arc,
 };
 
 //**************************************************************************************************
@@ -55,7 +56,9 @@ pub enum ImplicitUseFunKind {
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ImplicitUseFunCandidate {
     pub loc: Loc,
-    pub attributes: Attributes,
+    /// Shared between the candidates from the same declaration. Empty for function declarations,
+    /// whose attributes are kept on the function itself
+    pub attributes: Arc<Attributes>,
     pub is_public: Option<Loc>,
     pub function: (ModuleIdent, Name),
     pub kind: ImplicitUseFunKind,
@@ -76,6 +79,7 @@ pub enum AttributeValue_ {
     Value(Value),
     Module(ModuleIdent),
     ModuleAccess(ModuleAccess),
+    List(Vec<Value>),
 }
 pub type AttributeValue = Spanned<AttributeValue_>;
 
@@ -1044,6 +1048,11 @@ impl AstDebug for AttributeValue_ {
             AttributeValue_::Value(v) => v.ast_debug(w),
             AttributeValue_::Module(m) => w.write(&format!("{}", m)),
             AttributeValue_::ModuleAccess(n) => n.ast_debug(w),
+            AttributeValue_::List(vs) => {
+                w.write("[");
+                w.comma(vs, |w, v| v.ast_debug(w));
+                w.write("]");
+            }
         }
     }
 }
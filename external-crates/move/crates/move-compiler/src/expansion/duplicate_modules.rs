@@ -0,0 +1,120 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! An audit of the modules defined by several packages, reported when
+//! `Flags::audit_duplicate_modules` is set. Unlike the duplicate definition error, which stops at
+//! the first pair of definitions, every definition of such a module is reported, along with its
+//! package and the `PackagePaths` entry it comes from. This usually points to two dependencies
+//! embedding different copies of the same package, which cannot be published together.
+
+use crate::{
+    diag,
+    parser::ast::{self as P, LeadingNameAccess, LeadingNameAccess_},
+    shared::{CompilationEnv, NamedAddressMap},
+};
+use move_core_types::account_address::AccountAddress;
+use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
+use std::collections::{BTreeMap, BTreeSet};
+
+struct Definition {
+    package: Option<Symbol>,
+    /// The position of the `PackagePaths` entry, targets first, then dependencies
+    entry: usize,
+    is_dependency: bool,
+    loc: Loc,
+}
+
+/// Reports the modules of `prog` defined by more than one package
+pub fn audit(env: &mut CompilationEnv, prog: &P::Program) {
+    let mut definitions: BTreeMap<(AccountAddress, Symbol), Vec<Definition>> = BTreeMap::new();
+    let package_defs = prog
+        .source_definitions
+        .iter()
+        .map(|def| (def, false))
+        .chain(prog.lib_definitions.iter().map(|def| (def, true)));
+    for (
+        P::PackageDefinition {
+            package,
+            named_address_map,
+            def,
+        },
+        is_dependency,
+    ) in package_defs
+    {
+        let map = prog.named_address_maps.get(*named_address_map);
+        let modules: Vec<_> = match def {
+            P::Definition::Module(m) => m.address.iter().map(|addr| (*addr, m)).collect(),
+            P::Definition::Address(addr_def) => addr_def
+                .modules
+                .iter()
+                .map(|m| (addr_def.addr, m))
+                .collect(),
+            P::Definition::Script(_) => vec![],
+        };
+        for (addr, m) in modules {
+            // addresses without a value are reported by expansion
+            let Some(addr) = address(map, addr) else {
+                continue;
+            };
+            definitions
+                .entry((addr, m.name.0.value))
+                .or_default()
+                .push(Definition {
+                    package: *package,
+                    entry: prog.named_address_maps.position(*named_address_map),
+                    is_dependency,
+                    loc: m.name.0.loc,
+                })
+        }
+    }
+    for ((addr, name), defs) in definitions {
+        let packages: BTreeSet<_> = defs.iter().map(|def| def.package).collect();
+        if packages.len() < 2 {
+            continue;
+        }
+        let (first, others) = defs.split_first().unwrap();
+        let msg = format!(
+            "Module '{}::{}' is defined by {} packages. This definition is from {}",
+            addr.to_hex_literal(),
+            name,
+            packages.len(),
+            describe(first)
+        );
+        let mut diag = diag!(
+            Declarations::DuplicateModuleAcrossPackages,
+            (first.loc, msg)
+        );
+        diag.add_secondary_labels(others.iter().map(|def| {
+            (
+                def.loc,
+                format!("Also defined here, from {}", describe(def)),
+            )
+        }));
+        diag.add_note(
+            "The packages cannot be published together. This usually means that two \
+             dependencies include different copies of the same package",
+        );
+        env.add_diag(diag)
+    }
+}
+
+fn address(map: &NamedAddressMap, sp!(_, addr_): LeadingNameAccess) -> Option<AccountAddress> {
+    match addr_ {
+        LeadingNameAccess_::AnonymousAddress(addr) => Some(addr.into_inner()),
+        LeadingNameAccess_::Name(n) => map.get(&n.value).map(|addr| addr.into_inner()),
+    }
+}
+
+fn describe(def: &Definition) -> String {
+    let package = match def.package {
+        Some(package) => format!("package '{}'", package),
+        None => "sources without a package".to_string(),
+    };
+    let kind = if def.is_dependency {
+        "dependency"
+    } else {
+        "target"
+    };
+    format!("{} ({} package paths entry {})", package, kind, def.entry)
+}
@@ -2,9 +2,13 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod address_report;
 mod aliases;
 pub mod ast;
 mod byte_string;
+pub(crate) mod duplicate_modules;
 mod hex_string;
 mod primitive_definers;
+pub mod rename;
+pub mod symbol_index;
 pub(crate) mod translate;
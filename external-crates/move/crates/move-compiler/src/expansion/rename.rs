@@ -0,0 +1,91 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Computes the source edits needed to rename a function, struct, or constant, based on the names
+//! resolved during expansion and the method calls resolved during typing (see `symbol_index`).
+//! Uses through an alias with a different name (`use a::m::{f as g}`, or `use fun a::m::f as S.g`)
+//! keep the alias, so only the `use` itself is rewritten.
+
+use crate::{
+    diag,
+    diagnostics::{codes::Severity, Diagnostics, FilesSourceText},
+    expansion::{symbol_index::SymbolKind, translate::check_valid_module_member_rename},
+    shared::CompilationEnv,
+};
+use move_ir_types::location::*;
+use move_symbol_pool::Symbol;
+
+/// Replaces the text at `loc` with `new_text`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RenameEdit {
+    pub loc: Loc,
+    pub new_text: Symbol,
+}
+
+/// Returns the edits renaming the module member defined at `def_loc` to `new_name`, ordered by
+/// file and position. The symbol index of `env` must have been populated by expansion and typing.
+pub(crate) fn rename_edits(
+    env: &mut CompilationEnv,
+    files: &FilesSourceText,
+    def_loc: Loc,
+    new_name: Symbol,
+) -> anyhow::Result<Result<Vec<RenameEdit>, Diagnostics>> {
+    let Some(definition) = env.symbol_index().definition(def_loc).cloned() else {
+        anyhow::bail!("No definition found at the given location")
+    };
+    let (Some(old_name), SymbolKind::Function | SymbolKind::Struct | SymbolKind::Constant) =
+        (definition.member, definition.kind)
+    else {
+        anyhow::bail!("Only functions, structs, and constants can be renamed")
+    };
+    if old_name.value == new_name {
+        return Ok(Ok(vec![]));
+    }
+
+    check_valid_module_member_rename(env, definition.kind, sp(def_loc, new_name));
+    let conflict = env
+        .symbol_index()
+        .definitions()
+        .find(|(_, other)| {
+            other.module == definition.module
+                && other.member.is_some_and(|member| member.value == new_name)
+        })
+        .map(|(loc, _)| *loc);
+    if let Some(conflict_loc) = conflict {
+        let msg = format!(
+            "Cannot rename '{}' to '{}'. Module '{}' already has a member named '{}'",
+            old_name, new_name, definition.module, new_name
+        );
+        env.add_diag(diag!(
+            Declarations::DuplicateItem,
+            (def_loc, msg),
+            (conflict_loc, "Previously defined here"),
+        ));
+    }
+    if let Err(diags) = env.check_diags_at_or_above_severity(Severity::NonblockingError) {
+        return Ok(Err(diags));
+    }
+
+    let source_text = |loc: Loc| {
+        files
+            .get(&loc.file_hash())
+            .and_then(|(_, text)| text.get(loc.usize_range()))
+    };
+    let mut locs = vec![def_loc];
+    locs.extend(
+        env.symbol_index()
+            .references_to(def_loc)
+            .map(|reference| reference.use_loc)
+            // skip uses through aliases with a different name
+            .filter(|use_loc| source_text(*use_loc) == Some(old_name.value.as_str())),
+    );
+    locs.sort_by_key(|loc| (loc.file_hash(), loc.start()));
+    locs.dedup();
+    Ok(Ok(locs
+        .into_iter()
+        .map(|loc| RenameEdit {
+            loc,
+            new_text: new_name,
+        })
+        .collect()))
+}
@@ -0,0 +1,133 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Records, for each name resolved during expansion and each method call resolved during typing,
+//! the module or module member it refers to and where that entity is defined. This is the data
+//! IDE tooling needs for go-to-definition, find references, and symbol search. The index is only
+//! populated when `Flags::symbol_index` is set.
+
+use crate::{expansion::ast::ModuleIdent, shared::Name};
+use move_command_line_common::files::FileHash;
+use move_ir_types::location::Loc;
+use std::collections::{BTreeMap, HashMap};
+
+//**************************************************************************************************
+// Types
+//**************************************************************************************************
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SymbolKind {
+    Module,
+    Constant,
+    Function,
+    Struct,
+    Schema,
+}
+
+/// A single resolved name
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolReference {
+    /// The location of the name at its use site
+    pub use_loc: Loc,
+    /// The module referred to, or the module containing the member referred to
+    pub module: ModuleIdent,
+    /// The member referred to, `None` if the name refers to the module itself
+    pub member: Option<Name>,
+    pub kind: SymbolKind,
+    /// The location of the module or member definition
+    pub def_loc: Loc,
+}
+
+/// A module or module member definition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolDefinition {
+    /// The module defined, or the module containing the member defined
+    pub module: ModuleIdent,
+    /// The member defined, `None` for modules
+    pub member: Option<Name>,
+    pub kind: SymbolKind,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SymbolIndex {
+    /// Definitions, by the location of their name
+    definitions: HashMap<Loc, SymbolDefinition>,
+    /// References per file, sorted by the start of their use location
+    references: BTreeMap<FileHash, Vec<SymbolReference>>,
+}
+
+//**************************************************************************************************
+// impls
+//**************************************************************************************************
+
+impl SymbolIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.definitions.is_empty() && self.references.is_empty()
+    }
+
+    pub(crate) fn add_definition(&mut self, def_loc: Loc, definition: SymbolDefinition) {
+        self.definitions.insert(def_loc, definition);
+    }
+
+    pub(crate) fn add(&mut self, reference: SymbolReference) {
+        let refs = self
+            .references
+            .entry(reference.use_loc.file_hash())
+            .or_default();
+        let idx = refs.partition_point(|r| r.use_loc.start() <= reference.use_loc.start());
+        refs.insert(idx, reference);
+    }
+
+    /// The definition whose name is at `def_loc`
+    pub fn definition(&self, def_loc: Loc) -> Option<&SymbolDefinition> {
+        self.definitions.get(&def_loc)
+    }
+
+    pub fn definitions(&self) -> impl Iterator<Item = (&Loc, &SymbolDefinition)> {
+        self.definitions.iter()
+    }
+
+    /// All references recorded in the given file, ordered by position
+    pub fn file_references(&self, file_hash: FileHash) -> &[SymbolReference] {
+        self.references
+            .get(&file_hash)
+            .map(|refs| refs.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn references(&self) -> impl Iterator<Item = &SymbolReference> {
+        self.references.values().flatten()
+    }
+
+    /// The reference whose use site contains the byte `offset` of the given file, preferring the
+    /// innermost one if several overlap
+    pub fn reference_at(&self, file_hash: FileHash, offset: u32) -> Option<&SymbolReference> {
+        self.file_references(file_hash)
+            .iter()
+            .filter(|r| r.use_loc.start() <= offset && offset < r.use_loc.end())
+            .min_by_key(|r| r.use_loc.end() - r.use_loc.start())
+    }
+
+    /// The location of the definition of the name at the byte `offset` of the given file
+    pub fn definition_at(&self, file_hash: FileHash, offset: u32) -> Option<Loc> {
+        self.reference_at(file_hash, offset).map(|r| r.def_loc)
+    }
+
+    /// All uses of the entity defined at `def_loc`
+    pub fn references_to(&self, def_loc: Loc) -> impl Iterator<Item = &SymbolReference> {
+        self.references().filter(move |r| r.def_loc == def_loc)
+    }
+
+    /// All references whose member (or module, for module references) name matches `name`
+    pub fn search(&self, name: &str) -> impl Iterator<Item = &SymbolReference> + '_ {
+        let name = name.to_owned();
+        self.references().filter(move |r| match &r.member {
+            Some(member) => member.value.as_str() == name,
+            None => r.module.value.module.0.value.as_str() == name,
+        })
+    }
+}
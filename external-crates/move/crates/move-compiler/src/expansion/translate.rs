@@ -5,11 +5,13 @@
 use crate::{
     diag,
     diagnostics::{codes::WarningFilter, Diagnostic, WarningFilters},
-    editions::FeatureGate,
+    editions::{Edition, FeatureGate},
     expansion::{
+        address_report::NamedAddressReport,
         aliases::{AliasMap, AliasSet},
         ast::{self as E, Address, Fields, ModuleIdent, ModuleIdent_, SpecId},
         byte_string, hex_string,
+        symbol_index::{SymbolDefinition, SymbolKind, SymbolReference},
     },
     parser::ast::{
         self as P, Ability, ConstantName, Field, FieldBindings, FunctionName, ModuleName,
@@ -19,12 +21,12 @@ use crate::{
     FullyCompiledProgram,
 };
 use move_command_line_common::parser::{parse_u16, parse_u256, parse_u32};
-use move_core_types::account_address::AccountAddress;
 use move_ir_types::location::*;
-use move_symbol_pool::Symbol;
+use move_symbol_pool::{symbol, Symbol};
 use std::{
     collections::{BTreeMap, BTreeSet, VecDeque},
     iter::IntoIterator,
+    sync::Arc,
 };
 
 use super::aliases::{AliasMapBuilder, OldAliasMap, ParserExplicitUseFun, UseFunsBuilder};
@@ -48,6 +50,8 @@ struct Context<'env, 'map> {
     // Cached warning filters for all available prefixes. Used by non-source defs
     // and dependency packages
     all_filter_alls: WarningFilters,
+    // Shared by all implicit use funs from function declarations
+    no_attributes: Arc<E::Attributes>,
     env: &'env mut CompilationEnv,
 }
 
@@ -75,6 +79,7 @@ impl<'env, 'map> Context<'env, 'map> {
             in_spec_context: false,
             exp_specs: BTreeMap::new(),
             all_filter_alls,
+            no_attributes: Arc::new(UniqueMap::new()),
         }
     }
 
@@ -82,6 +87,68 @@ impl<'env, 'map> Context<'env, 'map> {
         self.address.as_ref().unwrap()
     }
 
+    /// Records all known module and module member definitions for IDE tooling
+    fn record_definitions(&mut self) {
+        if !self.env.flags().symbol_index() {
+            return;
+        }
+        for (mident, members) in self.module_members.key_cloned_iter() {
+            let module_def = SymbolDefinition {
+                module: mident,
+                member: None,
+                kind: SymbolKind::Module,
+            };
+            self.env.add_symbol_definition(mident.loc, module_def);
+            for (name, kind) in members {
+                let member_def = SymbolDefinition {
+                    module: mident,
+                    member: Some(*name),
+                    kind: kind.symbol_kind(),
+                };
+                self.env.add_symbol_definition(name.loc, member_def);
+            }
+        }
+    }
+
+    /// Records a use of a module for IDE tooling, if the module is known
+    fn record_module_reference(&mut self, use_loc: Loc, mident: ModuleIdent) {
+        if !self.env.flags().symbol_index() {
+            return;
+        }
+        let Some(def_loc) = self.module_members.get_loc(&mident).copied() else {
+            return;
+        };
+        self.env.add_symbol_reference(SymbolReference {
+            use_loc,
+            module: mident,
+            member: None,
+            kind: SymbolKind::Module,
+            def_loc,
+        })
+    }
+
+    /// Records a use of a module member for IDE tooling, if the member is known
+    fn record_member_reference(&mut self, mident: ModuleIdent, member: Name) {
+        if !self.env.flags().symbol_index() {
+            return;
+        }
+        let Some((def_name, kind)) = self
+            .module_members
+            .get(&mident)
+            .and_then(|members| members.get_key_value(&member))
+        else {
+            return;
+        };
+        let reference = SymbolReference {
+            use_loc: member.loc,
+            module: mident,
+            member: Some(member),
+            kind: kind.symbol_kind(),
+            def_loc: def_name.loc,
+        };
+        self.env.add_symbol_reference(reference)
+    }
+
     /// Resets the alias map and reports errors for aliases that were unused
     /// Marks implicit use funs as unused
     pub fn set_to_outer_scope(
@@ -127,40 +194,6 @@ impl<'env, 'map> Context<'env, 'map> {
     }
 }
 
-/// We mark named addresses as having a conflict if there is not a bidirectional mapping between
-/// the name and its value
-fn compute_address_conflicts(
-    pre_compiled_lib: Option<&FullyCompiledProgram>,
-    prog: &P::Program,
-) -> BTreeSet<Symbol> {
-    let mut name_to_addr: BTreeMap<Symbol, BTreeSet<AccountAddress>> = BTreeMap::new();
-    let mut addr_to_name: BTreeMap<AccountAddress, BTreeSet<Symbol>> = BTreeMap::new();
-    let all_addrs = prog.named_address_maps.all().iter().chain(
-        pre_compiled_lib
-            .iter()
-            .flat_map(|pre| pre.parser.named_address_maps.all()),
-    );
-    for map in all_addrs {
-        for (n, addr) in map {
-            let n = *n;
-            let addr = addr.into_inner();
-            name_to_addr.entry(n).or_default().insert(addr);
-            addr_to_name.entry(addr).or_default().insert(n);
-        }
-    }
-    let name_to_addr_conflicts = name_to_addr
-        .into_iter()
-        .filter(|(_, addrs)| addrs.len() > 1)
-        .map(|(n, _)| n);
-    let addr_to_name_conflicts = addr_to_name
-        .into_iter()
-        .filter(|(_, addrs)| addrs.len() > 1)
-        .flat_map(|(_, ns)| ns.into_iter());
-    name_to_addr_conflicts
-        .chain(addr_to_name_conflicts)
-        .collect()
-}
-
 //**************************************************************************************************
 // Entry
 //**************************************************************************************************
@@ -170,7 +203,12 @@ pub fn program(
     pre_compiled_lib: Option<&FullyCompiledProgram>,
     prog: P::Program,
 ) -> E::Program {
-    let address_conflicts = compute_address_conflicts(pre_compiled_lib, &prog);
+    // We mark named addresses as having a conflict if there is not a bidirectional mapping between
+    // the name and its value
+    let address_conflicts = NamedAddressReport::new(pre_compiled_lib, &prog).conflicts();
+    if compilation_env.flags().audit_duplicate_modules() {
+        super::duplicate_modules::audit(compilation_env, &prog);
+    }
     let module_members = {
         let mut members = UniqueMap::new();
         all_module_members(
@@ -204,6 +242,7 @@ pub fn program(
     };
 
     let mut context = Context::new(compilation_env, module_members, address_conflicts);
+    context.record_definitions();
 
     let mut source_module_map = UniqueMap::new();
     let mut lib_module_map = UniqueMap::new();
@@ -252,10 +291,10 @@ pub fn program(
     context.current_package = None;
 
     for (mident, module) in lib_module_map {
-        if let Err((mident, old_loc)) = source_module_map.add(mident, module) {
-            if !context.env.flags().sources_shadow_deps() {
-                duplicate_module(&mut context, &source_module_map, mident, old_loc)
-            }
+        if source_module_map.contains_key(&mident) {
+            duplicate_source_and_dep_module(&mut context, &mut source_module_map, mident, module)
+        } else {
+            source_module_map.add(mident, module).unwrap()
         }
     }
     let module_map = source_module_map;
@@ -291,6 +330,12 @@ pub fn program(
     };
 
     super::primitive_definers::modules(context.env, pre_compiled_lib, &module_map);
+    let deferred_address_uses = context.env.take_unresolved_deferred_address_uses();
+    if !deferred_address_uses.is_empty() {
+        context.env.add_diag(deferred_addresses_without_value_error(
+            deferred_address_uses,
+        ));
+    }
     E::Program {
         modules: module_map,
         scripts,
@@ -335,6 +380,29 @@ fn address_without_value_error(suggest_declaration: bool, loc: Loc, n: &Name) ->
     diag!(NameResolution::AddressWithoutValue, (loc, msg))
 }
 
+// Reports every use of the deferred addresses that were not resolved at once, rather than one
+// error per use
+fn deferred_addresses_without_value_error(uses: BTreeMap<Symbol, BTreeSet<Loc>>) -> Diagnostic {
+    let names = uses
+        .keys()
+        .map(|n| format!("'{}'", n))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut uses = uses
+        .into_iter()
+        .flat_map(|(n, locs)| locs.into_iter().map(move |loc| sp(loc, n)));
+    let first = uses.next().unwrap();
+    let mut diag = address_without_value_error(false, first.loc, &first);
+    diag.add_secondary_labels(
+        uses.map(|n| (n.loc, format!("address '{}' is not assigned a value", n))),
+    );
+    diag.add_note(format!(
+        "Deferred named addresses not resolved when building: {}",
+        names
+    ));
+    diag
+}
+
 // Access a top level address as declared, not affected by any aliasing/shadowing
 fn address(context: &mut Context, suggest_declaration: bool, ln: P::LeadingNameAccess) -> Address {
     address_(
@@ -367,7 +435,7 @@ fn address_(
                 name_conflict: address_conflicts.contains(&n.value),
             },
             None => {
-                if name_res.is_ok() {
+                if name_res.is_ok() && !compilation_env.record_unresolved_deferred_address(&n) {
                     compilation_env.add_diag(address_without_value_error(
                         suggest_declaration,
                         loc,
@@ -422,14 +490,90 @@ fn duplicate_module(
     mident: ModuleIdent,
     old_loc: Loc,
 ) {
+    let diag = duplicate_module_diag(module_map, mident, old_loc);
+    context.env.add_diag(diag)
+}
+
+fn duplicate_module_diag(
+    module_map: &UniqueMap<ModuleIdent, E::ModuleDefinition>,
+    mident: ModuleIdent,
+    old_loc: Loc,
+) -> Diagnostic {
     let old_mident = module_map.get_key(&mident).unwrap();
     let dup_msg = format!("Duplicate definition for module '{}'", mident);
     let prev_msg = format!("Module previously defined here, with '{}'", old_mident);
-    context.env.add_diag(diag!(
+    diag!(
         Declarations::DuplicateItem,
         (mident.loc, dup_msg),
         (old_loc, prev_msg),
-    ))
+    )
+}
+
+// Resolves a module defined in both the sources and the dependencies, as set by the
+// `DuplicateModuleStrategy`
+fn duplicate_source_and_dep_module(
+    context: &mut Context,
+    module_map: &mut UniqueMap<ModuleIdent, E::ModuleDefinition>,
+    mident: ModuleIdent,
+    dep_module: E::ModuleDefinition,
+) {
+    use DuplicateModuleStrategy as S;
+    let strategy = context.env.flags().duplicate_module_strategy();
+    let source_module = module_map.get(&mident).unwrap();
+    let source_loc = *module_map.get_loc(&mident).unwrap();
+    let prefer_dep = match strategy {
+        S::Error => {
+            duplicate_module(context, module_map, mident, source_loc);
+            return;
+        }
+        S::PreferSource => false,
+        S::PreferDep => true,
+        S::PreferHigherVersion => {
+            let source_version = context
+                .env
+                .package_config(source_module.package_name)
+                .version;
+            let dep_version = context.env.package_config(dep_module.package_name).version;
+            match (source_version, dep_version) {
+                (Some(source_version), Some(dep_version)) => dep_version > source_version,
+                _ => {
+                    let mut diag = duplicate_module_diag(module_map, mident, source_loc);
+                    diag.add_note(format!(
+                        "Duplicate modules are resolved with '{}', but the packages defining \
+                         the module do not all have a version",
+                        strategy.name()
+                    ));
+                    context.env.add_diag(diag);
+                    return;
+                }
+            }
+        }
+    };
+
+    let dep_loc = mident.loc;
+    if context.env.flags().report_duplicate_modules() {
+        let (kept, kept_loc, ignored_loc) = if prefer_dep {
+            ("dependency", dep_loc, source_loc)
+        } else {
+            ("source", source_loc, dep_loc)
+        };
+        let msg = format!(
+            "Module '{}' is defined in both the sources and the dependencies. Using the {} \
+             definition, as set by '{}'",
+            mident,
+            kept,
+            strategy.name(),
+        );
+        context.env.add_diag(diag!(
+            Declarations::DuplicateModuleResolved,
+            (kept_loc, msg),
+            (ignored_loc, "Definition ignored"),
+        ));
+    }
+    if prefer_dep {
+        module_map.remove(&mident);
+        module_map.add(mident, dep_module).unwrap();
+    }
 }
 
 fn module(
@@ -440,6 +584,7 @@ fn module(
     module_def: P::ModuleDefinition,
 ) {
     assert!(context.address.is_none());
+    context.env.set_current_module(module_def.name.0.loc);
     let (mident, mod_) = module_(context, package_name, module_address, module_def);
     if let Err((mident, old_loc)) = module_map.add(mident, mod_) {
         duplicate_module(context, module_map, mident, old_loc)
@@ -651,6 +796,31 @@ fn check_visibility_modifiers(
             }
         }
     }
+
+    if context.env.edition(package_name).is_migration() {
+        for (_, mident, friend) in friends {
+            let msg = format!(
+                "'friend' declarations are deprecated. \
+                 Remove the declaration of '{mident}' and use '{}' visibility instead",
+                E::Visibility::PACKAGE
+            );
+            context
+                .env
+                .add_diag(diag!(Migration::RemoveFriend, (friend.loc, msg)));
+        }
+        for (_, _, function) in functions {
+            if let E::Visibility::Friend(loc) = function.visibility {
+                let msg = format!(
+                    "'{}' is deprecated. Replace with '{}'",
+                    E::Visibility::FRIEND,
+                    E::Visibility::PACKAGE
+                );
+                context
+                    .env
+                    .add_diag(diag!(Migration::MakePubPackage, (loc, msg)));
+            }
+        }
+    }
 }
 
 fn script(
@@ -673,13 +843,26 @@ fn script_(context: &mut Context, package_name: Option<Symbol>, pscript: P::Scri
         function: pfunction,
         specs: pspecs,
     } = pscript;
+    context.env.set_current_module(pfunction.name.0.loc);
 
     let attributes = flatten_attributes(context, AttributePosition::Script, attributes);
     let mut warning_filter = warning_filter(context, &attributes);
     let config = context.env.package_config(package_name);
     warning_filter.union(&config.warning_filter);
+    // the legacy edition keeps accepting scripts silently
+    let deprecated = config.edition != Edition::LEGACY;
 
     context.env.add_warning_filter_scope(warning_filter.clone());
+    if deprecated {
+        let msg = "Scripts are deprecated. Call public or entry functions from a programmable \
+            transaction block instead";
+        let mut diag = diag!(Deprecation::Script, (pfunction.name.0.loc, msg));
+        diag.add_note(
+            "Scripts can be translated to transaction block templates with the \
+            '--transaction-templates' option of 'move-build'",
+        );
+        context.env.add_diag(diag);
+    }
     let (new_scope, use_funs_builder) = uses(context, puses);
     let old_aliases = context.aliases.add_and_shadow_all(new_scope);
     assert!(
@@ -894,6 +1077,11 @@ fn attribute_value(
                 EV::Module(mident)
             }
             PV::ModuleAccess(ma) => EV::ModuleAccess(name_access_chain(context, Access::Type, ma)?),
+            PV::List(vs) => EV::List(
+                vs.into_iter()
+                    .map(|v| value(context, v))
+                    .collect::<Option<_>>()?,
+            ),
         },
     ))
 }
@@ -926,11 +1114,14 @@ fn warning_filter(
     use crate::diagnostics::codes::Category;
     use known_attributes::DiagnosticAttribute;
     let mut warning_filters = WarningFilters::new_for_source();
-    let filter_attribute_names = context.env.filter_attributes().clone();
-    for allow in filter_attribute_names {
-        let Some(attr) = attributes.get_(&allow) else {
-            continue;
-        };
+    // only the filter attributes present are collected, as most members have none
+    let present_filter_attributes: Vec<_> = context
+        .env
+        .filter_attributes()
+        .iter()
+        .filter_map(|allow| Some((*allow, attributes.get_(allow)?)))
+        .collect();
+    for (allow, attr) in present_filter_attributes {
         let inners = match &attr.value {
             E::Attribute_::Parameterized(_, inner) if !inner.is_empty() => inner,
             _ => {
@@ -1087,7 +1278,7 @@ fn module_members(
 }
 
 fn module_self_aliases(acc: &mut AliasMapBuilder, current_module: &ModuleIdent) {
-    let self_name = sp(current_module.loc, ModuleName::SELF_NAME.into());
+    let self_name = sp(current_module.loc, symbol!("Self"));
     acc.add_implicit_module_alias(self_name, *current_module)
         .unwrap()
 }
@@ -1187,12 +1378,14 @@ fn use_(
     let attributes = flatten_attributes(context, AttributePosition::Use, attributes);
     match u {
         P::Use::NestedModuleUses(address, use_decls) => {
+            let attributes = Arc::new(attributes);
             for (module, use_) in use_decls {
                 let mident = sp(module.loc(), P::ModuleIdent_ { address, module });
                 module_use(context, acc, use_funs, mident, &attributes, use_);
             }
         }
         P::Use::ModuleUse(mident, use_) => {
+            let attributes = Arc::new(attributes);
             module_use(context, acc, use_funs, mident, &attributes, use_);
         }
         P::Use::Fun {
@@ -1242,7 +1435,7 @@ fn module_use(
     acc: &mut AliasMapBuilder,
     use_funs: &mut UseFunsBuilder,
     in_mident: P::ModuleIdent,
-    attributes: &E::Attributes,
+    attributes: &Arc<E::Attributes>,
     muse: P::ModuleUse,
 ) {
     let unbound_module = |mident: &ModuleIdent| -> Diagnostic {
@@ -1274,10 +1467,12 @@ fn module_use(
                 context.env.add_diag(unbound_module(&mident));
                 return;
             };
+            context.record_module_reference(mident.value.module.0.loc, mident);
             add_module_alias!(mident, alias_opt.map(|m| m.0))
         }
         P::ModuleUse::Members(sub_uses) => {
             let mident = module_ident(context, in_mident);
+            context.record_module_reference(mident.value.module.0.loc, mident);
             let members = match context.module_members.get(&mident) {
                 Some(members) => members,
                 None => {
@@ -1295,7 +1490,7 @@ fn module_use(
                 .collect::<Vec<_>>();
 
             for (member, alias_opt, member_kind_opt) in sub_uses_kinds {
-                if member.value.as_str() == ModuleName::SELF_NAME {
+                if member.value == symbol!("Self") {
                     add_module_alias!(mident, alias_opt);
                     continue;
                 }
@@ -1318,6 +1513,11 @@ fn module_use(
                     Some(m) => m,
                 };
 
+                context.record_member_reference(mident, member);
+                if let Some(alias) = alias_opt {
+                    // the alias refers to the member as well
+                    context.record_member_reference(mident, sp(alias.loc, member.value));
+                }
                 let alias = alias_opt.unwrap_or(member);
 
                 let alias = match check_valid_module_member_alias(context, member_kind, alias) {
@@ -1337,7 +1537,7 @@ fn module_use(
                     let kind = E::ImplicitUseFunKind::UseAlias { used: true };
                     let implicit = E::ImplicitUseFunCandidate {
                         loc: alias.loc,
-                        attributes: attributes.clone(),
+                        attributes: Arc::clone(attributes),
                         is_public,
                         function: (mident, member),
                         kind,
@@ -1433,6 +1633,7 @@ fn struct_def(
     structs: &mut UniqueMap<StructName, E::StructDefinition>,
     pstruct: P::StructDefinition,
 ) {
+    context.env.set_current_member(pstruct.name.0.loc);
     let (sname, sdef) = struct_def_(context, structs.len(), pstruct);
     if let Err(_old_loc) = structs.add(sname, sdef) {
         assert!(context.env.has_errors())
@@ -1558,6 +1759,7 @@ fn constant(
     constants: &mut UniqueMap<ConstantName, E::Constant>,
     pconstant: P::Constant,
 ) {
+    context.env.set_current_member(pconstant.name.0.loc);
     let (name, constant) = constant_(context, constants.len(), pconstant);
     if let Err(_old_loc) = constants.add(name, constant) {
         assert!(context.env.has_errors())
@@ -1605,6 +1807,7 @@ fn function(
     functions: &mut UniqueMap<FunctionName, E::Function>,
     pfunction: P::Function,
 ) {
+    context.env.set_current_member(pfunction.name.0.loc);
     let (fname, fdef) = function_(context, module_and_use_funs, functions.len(), pfunction);
     if let Err(_old_loc) = functions.add(fname, fdef) {
         assert!(context.env.has_errors())
@@ -1637,7 +1840,7 @@ fn function_(
     if let Some((m, use_funs_builder)) = module_and_use_funs {
         let implicit = E::ImplicitUseFunCandidate {
             loc: name.loc(),
-            attributes: attributes.clone(),
+            attributes: Arc::clone(&context.no_attributes),
             is_public: Some(visibility.loc().unwrap_or_else(|| name.loc())),
             function: (m, name.0),
             // disregard used/unused information tracking
@@ -2070,12 +2273,18 @@ fn name_access_chain(
         (Access::ApplyPositional, PN::One(n))
         | (Access::ApplyNamed, PN::One(n))
         | (Access::Type, PN::One(n)) => match context.aliases.member_alias_get(&n) {
-            Some((mident, mem)) => EN::ModuleAccess(mident, mem),
+            Some((mident, mem)) => {
+                context.record_member_reference(mident, mem);
+                EN::ModuleAccess(mident, mem)
+            }
             None => EN::Name(n),
         },
         (Access::Term, PN::One(n)) if is_valid_struct_constant_or_schema_name(n.value.as_str()) => {
             match context.aliases.member_alias_get(&n) {
-                Some((mident, mem)) => EN::ModuleAccess(mident, mem),
+                Some((mident, mem)) => {
+                    context.record_member_reference(mident, mem);
+                    EN::ModuleAccess(mident, mem)
+                }
                 None => EN::Name(n),
             }
         }
@@ -2095,11 +2304,17 @@ fn name_access_chain(
                 ));
                 return None;
             }
-            Some(mident) => EN::ModuleAccess(mident, n2),
+            Some(mident) => {
+                context.record_module_reference(n1.loc, mident);
+                context.record_member_reference(mident, n2);
+                EN::ModuleAccess(mident, n2)
+            }
         },
         (_, PN::Three(sp!(ident_loc, (ln, n2)), n3)) => {
             let addr = address(context, /* suggest_declaration */ false, ln);
             let mident = sp(ident_loc, ModuleIdent_::new(addr, ModuleName(n2)));
+            context.record_module_reference(n2.loc, mident);
+            context.record_member_reference(mident, n3);
             EN::ModuleAccess(mident, n3)
         }
     };
@@ -2120,14 +2335,19 @@ fn name_access_chain_to_module_ident(
                 ));
                 None
             }
-            Some(mident) => Some(mident),
+            Some(mident) => {
+                context.record_module_reference(name.loc, mident);
+                Some(mident)
+            }
         },
         PN::Two(ln, n) => {
             let pmident_ = P::ModuleIdent_ {
                 address: ln,
                 module: ModuleName(n),
             };
-            Some(module_ident(context, sp(loc, pmident_)))
+            let mident = module_ident(context, sp(loc, pmident_));
+            context.record_module_reference(n.loc, mident);
+            Some(mident)
         }
         PN::Three(sp!(ident_loc, (ln, n)), mem) => {
             // Process the module ident just for errors
@@ -2819,6 +3039,7 @@ fn mutability(context: &mut Context, loc: Loc, pmut: Mutability) -> Mutability {
 fn unbound_names_spec_block(unbound: &mut BTreeSet<Name>, sp!(_, sb_): &E::SpecBlock) {
     sb_.members
         .iter()
+        .rev()
         .for_each(|member| unbound_names_spec_block_member(unbound, member))
 }
 
@@ -2835,12 +3056,16 @@ fn unbound_names_spec_block_member(unbound: &mut BTreeSet<Name>, sp!(_, m_): &E:
                 .iter()
                 .for_each(|e| unbound_names_exp(unbound, e));
         }
+        M::Let { name, def, .. } => {
+            // remove the name bound by the `let`, visible in the members after it
+            unbound.remove(name);
+            unbound_names_exp(unbound, def);
+        }
         // No unbound names
         // And will error in the Move prover
         M::Function { .. }
         | M::Variable { .. }
         | M::Update { .. }
-        | M::Let { .. }
         | M::Include { .. }
         | M::Apply { .. }
         | M::Pragma { .. } => (),
@@ -3072,6 +3297,15 @@ impl ModuleMemberKind {
             ModuleMemberKind::Schema => NameCase::Schema,
         }
     }
+
+    fn symbol_kind(self) -> SymbolKind {
+        match self {
+            ModuleMemberKind::Constant => SymbolKind::Constant,
+            ModuleMemberKind::Function => SymbolKind::Function,
+            ModuleMemberKind::Struct => SymbolKind::Struct,
+            ModuleMemberKind::Schema => SymbolKind::Schema,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -3117,6 +3351,24 @@ fn check_valid_module_member_name(
     }
 }
 
+/// Checks that `name` is a valid new name for an existing module member of the given kind,
+/// reporting any issue to `env`. Used when renaming members outside of compilation.
+pub(crate) fn check_valid_module_member_rename(
+    env: &mut CompilationEnv,
+    kind: SymbolKind,
+    name: Name,
+) -> Option<Name> {
+    let member = match kind {
+        SymbolKind::Constant => ModuleMemberKind::Constant,
+        SymbolKind::Function => ModuleMemberKind::Function,
+        SymbolKind::Struct => ModuleMemberKind::Struct,
+        SymbolKind::Schema => ModuleMemberKind::Schema,
+        SymbolKind::Module => return None,
+    };
+    let mut context = Context::new(env, UniqueMap::new(), BTreeSet::new());
+    check_valid_module_member_name(&mut context, member, name)
+}
+
 fn check_valid_module_member_alias(
     context: &mut Context,
     member: ModuleMemberKind,
@@ -3218,12 +3470,9 @@ fn check_restricted_name_all_cases_(
     case: NameCase,
     n: &Name,
 ) -> Result<(), ()> {
-    let n_str = n.value.as_str();
     let can_be_vector = matches!(case, NameCase::Module | NameCase::ModuleAlias);
-    if n_str == ModuleName::SELF_NAME
-        || (!can_be_vector && n_str == crate::naming::ast::BuiltinTypeName_::VECTOR)
-    {
-        env.add_diag(restricted_name_error(case, n.loc, n_str));
+    if n.value == symbol!("Self") || (!can_be_vector && n.value == symbol!("vector")) {
+        env.add_diag(restricted_name_error(case, n.loc, n.value.as_str()));
         Err(())
     } else {
         Ok(())
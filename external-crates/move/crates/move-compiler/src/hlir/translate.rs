@@ -215,6 +215,7 @@ fn module(
     module_ident: ModuleIdent,
     mdef: T::ModuleDefinition,
 ) -> (ModuleIdent, H::ModuleDefinition) {
+    context.env.set_current_module(module_ident.loc);
     let T::ModuleDefinition {
         loc: _,
         warning_filter,
@@ -280,6 +281,7 @@ fn script(context: &mut Context, tscript: T::Script) -> H::Script {
         function: tfunction,
         spec_dependencies: _,
     } = tscript;
+    context.env.set_current_module(function_name.0.loc);
     context.current_package = package_name;
     context.env.add_warning_filter_scope(warning_filter.clone());
     let constants = tconstants.map(|name, c| constant(context, name, c));
@@ -301,7 +303,8 @@ fn script(context: &mut Context, tscript: T::Script) -> H::Script {
 // Functions
 //**************************************************************************************************
 
-fn function(context: &mut Context, _name: FunctionName, f: T::Function) -> H::Function {
+fn function(context: &mut Context, name: FunctionName, f: T::Function) -> H::Function {
+    context.env.set_current_member(name.0.loc);
     assert!(context.has_empty_locals());
     assert!(context.tmp_counter == 0);
     let T::Function {
@@ -411,7 +414,8 @@ fn visibility(evisibility: E::Visibility) -> H::Visibility {
 // Constants
 //**************************************************************************************************
 
-fn constant(context: &mut Context, _name: ConstantName, cdef: T::Constant) -> H::Constant {
+fn constant(context: &mut Context, name: ConstantName, cdef: T::Constant) -> H::Constant {
+    context.env.set_current_member(name.0.loc);
     let T::Constant {
         warning_filter,
         index,
@@ -451,9 +455,10 @@ fn constant(context: &mut Context, _name: ConstantName, cdef: T::Constant) -> H:
 
 fn struct_def(
     context: &mut Context,
-    _name: StructName,
+    name: StructName,
     sdef: N::StructDefinition,
 ) -> H::StructDefinition {
+    context.env.set_current_member(name.0.loc);
     let N::StructDefinition {
         warning_filter,
         index,
@@ -2,18 +2,31 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::shared::{NumberFormat, NumericalAddress};
+use crate::{
+    hlir::translate::{display_var, DisplayVar},
+    shared::{NumberFormat, NumericalAddress},
+};
 use anyhow::{anyhow, Result};
 use move_binary_format::{
     access::ModuleAccess,
     file_format::{
-        Ability, AbilitySet, CompiledModule, FunctionDefinition, ModuleHandle, SignatureToken,
-        StructDefinition, StructFieldInformation, StructHandleIndex, StructTypeParameter,
+        Ability, AbilitySet, CompiledModule, FunctionDefinition, FunctionDefinitionIndex,
+        ModuleHandle, SignatureToken, StructDefinition, StructDefinitionIndex,
+        StructFieldInformation, StructHandleIndex, StructTypeParameter, TableIndex,
         TypeParameterIndex, Visibility,
     },
 };
+use move_bytecode_source_map::{source_map::SourceMap, utils::source_map_from_file};
+use move_command_line_common::files::{
+    FileHash, MOVE_COMPILED_EXTENSION, MOVE_EXTENSION, SOURCE_MAP_EXTENSION,
+};
 use move_core_types::language_storage::ModuleId;
-use std::{collections::BTreeMap, fs};
+use move_ir_types::location::Loc;
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
 
 macro_rules! push_line {
     ($s:ident, $e:expr) => {{
@@ -30,6 +43,8 @@ macro_rules! push {
 /// Generate the text for the "interface" file of a compiled module. This "interface" is the
 /// publically visible contents of the CompiledModule, represented in source language syntax
 /// Additionally, it returns the module id (address+name) of the module that was deserialized
+/// If the source map of the module is found (see `source_files`), the original parameter names are
+/// used, and if the source file is found as well, the doc comments of the structs and functions
 pub fn write_file_to_string(
     named_address_mapping: &BTreeMap<ModuleId, impl AsRef<str>>,
     compiled_module_file_input_path: &str,
@@ -42,12 +57,33 @@ pub fn write_file_to_string(
             e
         )
     })?;
-    write_module_to_string(named_address_mapping, &module)
+    let (source_map_path, source_path) = source_files(Path::new(compiled_module_file_input_path));
+    // the source information is best effort, the interface is still generated without it
+    let source_map = source_map_path.and_then(|path| source_map_from_file(&path).ok());
+    let source = source_path.and_then(|path| fs::read_to_string(path).ok());
+    write_module_to_string_with_source(
+        named_address_mapping,
+        &module,
+        source_map.as_ref(),
+        source.as_deref(),
+    )
 }
 
 pub fn write_module_to_string(
     named_address_mapping: &BTreeMap<ModuleId, impl AsRef<str>>,
     module: &CompiledModule,
+) -> Result<(ModuleId, String)> {
+    write_module_to_string_with_source(named_address_mapping, module, None, None)
+}
+
+/// Like `write_module_to_string`, with the parameter names of the source map of the module, and the
+/// doc comments found in its source file. The source is ignored if it is not the file the source
+/// map was generated from
+pub fn write_module_to_string_with_source(
+    named_address_mapping: &BTreeMap<ModuleId, impl AsRef<str>>,
+    module: &CompiledModule,
+    source_map: Option<&SourceMap>,
+    source: Option<&str>,
 ) -> Result<(ModuleId, String)> {
     let mut out = String::new();
 
@@ -58,7 +94,11 @@ pub fn write_module_to_string(
     );
     push_line!(out, "");
 
-    let mut context = Context::new(module);
+    let source = source_map.zip(source).and_then(|(source_map, source)| {
+        let file_hash = source_map.definition_location.file_hash();
+        (FileHash::new(source) == file_hash).then_some(source)
+    });
+    let mut context = Context::new(module, source_map, source);
     let mut members = vec![];
 
     for fdecl in module.friend_decls() {
@@ -68,8 +108,12 @@ pub fn write_module_to_string(
         members.push("".to_string());
     }
 
-    for sdef in module.struct_defs() {
-        members.push(write_struct_def(&mut context, sdef));
+    for (idx, sdef) in module.struct_defs().iter().enumerate() {
+        members.push(write_struct_def(
+            &mut context,
+            StructDefinitionIndex(idx as TableIndex),
+            sdef,
+        ));
     }
     if !module.struct_defs().is_empty() {
         members.push("".to_string());
@@ -78,7 +122,8 @@ pub fn write_module_to_string(
     let mut externally_visible_funs = module
         .function_defs()
         .iter()
-        .filter(|fdef| match fdef.visibility {
+        .enumerate()
+        .filter(|(_, fdef)| match fdef.visibility {
             Visibility::Public | Visibility::Friend => true,
             Visibility::Private => false,
         })
@@ -87,8 +132,12 @@ pub fn write_module_to_string(
     if has_externally_visible_funs {
         members.push(format!("    {}", DISCLAIMER));
     }
-    for fdef in externally_visible_funs {
-        members.push(write_function_def(&mut context, fdef));
+    for (idx, fdef) in externally_visible_funs {
+        members.push(write_function_def(
+            &mut context,
+            FunctionDefinitionIndex(idx as TableIndex),
+            fdef,
+        ));
     }
     if has_externally_visible_funs {
         members.push("".to_string());
@@ -121,21 +170,75 @@ pub fn write_module_to_string(
     Ok((id, out))
 }
 
+/// The directories of the bytecode, source maps and sources in the build output of a package
+const PACKAGE_MODULES_DIR: &str = "bytecode_modules";
+const PACKAGE_SOURCE_MAPS_DIR: &str = "source_maps";
+const PACKAGE_SOURCES_DIR: &str = "sources";
+
+/// The source map and source file of the compiled module at `path`, if they exist. They are looked
+/// for next to the module, as emitted by `move-build`, then in the build output layout of packages,
+/// where they mirror the path of the module under `bytecode_modules`
+pub fn source_files(path: &Path) -> (Option<PathBuf>, Option<PathBuf>) {
+    let find = |dir: &str, extension: &str| {
+        let sibling = path.with_extension(extension);
+        if sibling.is_file() {
+            return Some(sibling);
+        }
+        let mut components: Vec<_> = path.iter().collect();
+        let modules_dir = components
+            .iter()
+            .rposition(|component| *component == PACKAGE_MODULES_DIR)?;
+        components[modules_dir] = dir.as_ref();
+        let in_layout = components
+            .into_iter()
+            .collect::<PathBuf>()
+            .with_extension(extension);
+        in_layout.is_file().then_some(in_layout)
+    };
+    if path
+        .extension()
+        .map_or(true, |ext| ext != MOVE_COMPILED_EXTENSION)
+    {
+        return (None, None);
+    }
+    (
+        find(PACKAGE_SOURCE_MAPS_DIR, SOURCE_MAP_EXTENSION),
+        find(PACKAGE_SOURCES_DIR, MOVE_EXTENSION),
+    )
+}
+
 struct Context<'a> {
     module: &'a CompiledModule,
+    source_map: Option<&'a SourceMap>,
+    /// The source of the module, only if it is the one of the source map
+    source: Option<&'a str>,
     uses: BTreeMap<ModuleId, String>,
     counts: BTreeMap<String, usize>,
 }
 
 impl<'a> Context<'a> {
-    fn new(module: &'a CompiledModule) -> Self {
+    fn new(
+        module: &'a CompiledModule,
+        source_map: Option<&'a SourceMap>,
+        source: Option<&'a str>,
+    ) -> Self {
         Self {
             module,
+            source_map,
+            source,
             uses: BTreeMap::new(),
             counts: BTreeMap::new(),
         }
     }
 
+    /// The doc comment lines preceding the definition at `loc` in the source
+    fn doc_comment(&self, loc: Loc) -> Vec<&'a str> {
+        match self.source {
+            Some(source) => doc_comment(source, loc),
+            None => vec![],
+        }
+    }
+
     fn module_alias(&mut self, module_id: ModuleId) -> &String {
         let module_name = module_id.name().to_owned().into_string();
         let counts = &mut self.counts;
@@ -177,10 +280,22 @@ fn write_friend_decl(ctx: &mut Context, fdecl: &ModuleHandle) -> String {
     )
 }
 
-fn write_struct_def(ctx: &mut Context, sdef: &StructDefinition) -> String {
+fn write_struct_def(
+    ctx: &mut Context,
+    idx: StructDefinitionIndex,
+    sdef: &StructDefinition,
+) -> String {
     let mut out = String::new();
 
     let shandle = ctx.module.struct_handle_at(sdef.struct_handle);
+    if let Some(smap) = ctx
+        .source_map
+        .and_then(|sm| sm.get_struct_source_map(idx).ok())
+    {
+        for line in ctx.doc_comment(smap.definition_location) {
+            push_line!(out, format!("    {}", line));
+        }
+    }
 
     push_line!(
         out,
@@ -214,19 +329,37 @@ fn write_struct_def(ctx: &mut Context, sdef: &StructDefinition) -> String {
     out
 }
 
-fn write_function_def(ctx: &mut Context, fdef: &FunctionDefinition) -> String {
+fn write_function_def(
+    ctx: &mut Context,
+    idx: FunctionDefinitionIndex,
+    fdef: &FunctionDefinition,
+) -> String {
+    let mut out = String::new();
     let fhandle = ctx.module.function_handle_at(fdef.function);
     let parameters = &ctx.module.signature_at(fhandle.parameters).0;
     let return_ = &ctx.module.signature_at(fhandle.return_).0;
-    format!(
-        "    native {}{}fun {}{}({}){};",
-        write_visibility(fdef.visibility),
-        if fdef.is_entry { "entry " } else { "" },
-        ctx.module.identifier_at(fhandle.name),
-        write_fun_type_parameters(&fhandle.type_parameters),
-        write_parameters(ctx, parameters),
-        write_return_type(ctx, return_)
-    )
+    let fmap = ctx
+        .source_map
+        .and_then(|sm| sm.get_function_source_map(idx).ok());
+    if let Some(fmap) = fmap {
+        for line in ctx.doc_comment(fmap.definition_location) {
+            push_line!(out, format!("    {}", line));
+        }
+    }
+    let parameter_names = fmap.map(|fmap| parameter_names(&fmap.parameters, parameters.len()));
+    push!(
+        out,
+        format!(
+            "    native {}{}fun {}{}({}){};",
+            write_visibility(fdef.visibility),
+            if fdef.is_entry { "entry " } else { "" },
+            ctx.module.identifier_at(fhandle.name),
+            write_fun_type_parameters(&fhandle.type_parameters),
+            write_parameters(ctx, parameter_names, parameters),
+            write_return_type(ctx, return_)
+        )
+    );
+    out
 }
 
 fn write_visibility(visibility: Visibility) -> String {
@@ -316,15 +449,82 @@ fn write_fun_type_parameters(tps: &[AbilitySet]) -> String {
     format!("<{}>", tp_and_constraints)
 }
 
-fn write_parameters(ctx: &mut Context, params: &[SignatureToken]) -> String {
-    params
+fn write_parameters(
+    ctx: &mut Context,
+    names: Option<Vec<String>>,
+    params: &[SignatureToken],
+) -> String {
+    let names = names.unwrap_or_else(|| (0..params.len()).map(parameter_name).collect());
+    names
         .iter()
-        .enumerate()
-        .map(|(idx, ty)| format!("a{}: {}", idx, write_signature_token(ctx, ty)))
+        .zip(params)
+        .map(|(name, ty)| format!("{}: {}", name, write_signature_token(ctx, ty)))
         .collect::<Vec<_>>()
         .join(", ")
 }
 
+fn parameter_name(idx: usize) -> String {
+    format!("a{}", idx)
+}
+
+/// The source names of the parameters, without the suffix added by the compiler. A parameter is
+/// named by its position instead if its name is missing, generated, or already used
+fn parameter_names(source_names: &[(String, Loc)], num_params: usize) -> Vec<String> {
+    let mut seen = BTreeSet::new();
+    (0..num_params)
+        .map(|idx| {
+            let name = source_names
+                .get(idx)
+                .and_then(|(name, _)| match display_var(name.as_str().into()) {
+                    DisplayVar::Orig(name) => Some(name),
+                    DisplayVar::Tmp => None,
+                })
+                .filter(|name| name != "_" && !name.is_empty());
+            match name {
+                Some(name) if seen.insert(name.clone()) => name,
+                _ => parameter_name(idx),
+            }
+        })
+        .collect()
+}
+
+/// The doc comment lines directly preceding the line of `loc` in `source`. Both `///` and
+/// `/** */` comments are recognized
+fn doc_comment(source: &str, loc: Loc) -> Vec<&str> {
+    let Some(before) = source.get(..loc.start() as usize) else {
+        return vec![];
+    };
+    let mut lines = before
+        .lines()
+        .rev()
+        // the line of the definition itself
+        .skip(usize::from(!before.ends_with('\n')))
+        .map(str::trim);
+    let mut doc = vec![];
+    let mut in_block = false;
+    for line in lines.by_ref() {
+        let ends_block = !in_block && line.ends_with("*/") && !line.starts_with("//");
+        if !(in_block || ends_block || line.starts_with("///")) {
+            break;
+        }
+        doc.push(line);
+        in_block = in_block || ends_block;
+        if in_block && line.starts_with("/*") {
+            if !line.starts_with("/**") {
+                // a regular block comment
+                return vec![];
+            }
+            in_block = false
+        }
+    }
+    if in_block {
+        // the start of the block comment was not found
+        return vec![];
+    }
+    doc.reverse();
+    doc
+}
+
 fn write_return_type(ctx: &mut Context, tys: &[SignatureToken]) -> String {
     match tys.len() {
         0 => "".to_string(),
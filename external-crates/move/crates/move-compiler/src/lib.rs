@@ -21,6 +21,8 @@ pub mod interface_generator;
 pub mod ir_translation;
 pub mod naming;
 pub mod parser;
+#[cfg(feature = "prover")]
+pub mod prover;
 pub mod shared;
 pub mod sui_mode;
 mod to_bytecode;
@@ -32,7 +34,7 @@ pub use command_line::{
     compiler::{
         construct_pre_compiled_lib, generate_interface_files, output_compiled_units, Compiler,
         FullyCompiledProgram, SteppedCompiler, PASS_CFGIR, PASS_COMPILATION, PASS_EXPANSION,
-        PASS_HLIR, PASS_NAMING, PASS_PARSER, PASS_TYPING,
+        PASS_HLIR, PASS_NAMING, PASS_PARSER, PASS_PROVER, PASS_TYPING,
     },
     MOVE_COMPILED_INTERFACES_DIR,
 };
@@ -20,6 +20,7 @@ use once_cell::sync::Lazy;
 use std::{
     collections::{BTreeMap, BTreeSet, VecDeque},
     fmt,
+    sync::Arc,
 };
 
 //**************************************************************************************************
@@ -57,7 +58,7 @@ pub enum UseFunKind {
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct UseFun {
     pub loc: Loc,
-    pub attributes: Attributes,
+    pub attributes: Arc<Attributes>,
     pub is_public: Option<Loc>,
     pub target_function: (ModuleIdent, FunctionName),
     // If None, disregard any use/unused information.
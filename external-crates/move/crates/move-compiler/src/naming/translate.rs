@@ -17,7 +17,10 @@ use crate::{
 };
 use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
-use std::collections::{BTreeMap, BTreeSet};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
 
 use super::{
     ast::{Neighbor, TParamID},
@@ -82,6 +85,12 @@ struct Context<'env> {
     /// to translate a function and to false after translation is over).
     translating_fun: bool,
     current_package: Option<Symbol>,
+    /// Spec variables declared in the specs of the current module or script, which can be referred
+    /// to by name in spec blocks
+    spec_variables: BTreeSet<Symbol>,
+    /// The constants of the current module defined as literal values, which can be arguments of
+    /// formatted abort messages. `None` outside of modules.
+    literal_constants: Option<BTreeMap<Symbol, E::Value_>>,
 }
 
 impl<'env> Context<'env> {
@@ -164,6 +173,8 @@ impl<'env> Context<'env> {
             used_fun_tparams: BTreeSet::new(),
             translating_fun: false,
             current_package: None,
+            spec_variables: BTreeSet::new(),
+            literal_constants: None,
         }
     }
 
@@ -456,6 +467,32 @@ impl<'env> Context<'env> {
             }
         }
     }
+
+    /// Whether a name that is not a local can still be resolved in a spec block, as a spec
+    /// variable, a constant, a builtin spec constant, or the result of the function
+    fn is_spec_name(&self, name: &Symbol) -> bool {
+        const SPEC_BUILTIN_CONSTANTS: &[&str] = &[
+            "MAX_U8", "MAX_U16", "MAX_U32", "MAX_U64", "MAX_U128", "MAX_U256",
+        ];
+        if SPEC_BUILTIN_CONSTANTS.contains(&name.as_str()) {
+            return true;
+        }
+        let is_result = name
+            .as_str()
+            .strip_prefix("result")
+            .map_or(false, |suffix| {
+                suffix.is_empty()
+                    || suffix
+                        .strip_prefix('_')
+                        .map_or(false, |n| n.parse::<usize>().is_ok())
+            });
+        let is_constant = self.unscoped_constants.contains_key(name)
+            || self
+                .current_module
+                .and_then(|m| self.scoped_constants.get(&m))
+                .map_or(false, |constants| constants.contains_key(name));
+        is_result || is_constant || self.spec_variables.contains(name)
+    }
 }
 
 //**************************************************************************************************
@@ -493,6 +530,7 @@ fn module(
     mdef: E::ModuleDefinition,
 ) -> N::ModuleDefinition {
     context.current_module = Some(ident);
+    context.env.set_current_module(ident.loc);
     let E::ModuleDefinition {
         loc,
         warning_filter,
@@ -511,6 +549,8 @@ fn module(
     let unscoped = context.save_unscoped();
     let mut spec_dependencies = BTreeSet::new();
     spec_blocks(&mut spec_dependencies, &specs);
+    context.spec_variables = spec_variables(&specs);
+    context.literal_constants = Some(literal_constants(&econstants));
     let use_funs = use_funs(context, euse_funs);
     let friends = efriends.filter_map(|mident, f| friend(context, mident, f));
     let structs = estructs.map(|name, s| {
@@ -528,6 +568,8 @@ fn module(
     context.restore_unscoped(unscoped);
     context.env.pop_warning_filter_scope();
     context.current_package = None;
+    context.spec_variables = BTreeSet::new();
+    context.literal_constants = None;
     N::ModuleDefinition {
         loc,
         warning_filter,
@@ -565,11 +607,13 @@ fn script(context: &mut Context, escript: E::Script) -> N::Script {
         function: efunction,
         specs,
     } = escript;
+    context.env.set_current_module(function_name.0.loc);
     context.current_package = package_name;
     context.env.add_warning_filter_scope(warning_filter.clone());
     let outer_unscoped = context.save_unscoped();
     let mut spec_dependencies = BTreeSet::new();
     spec_blocks(&mut spec_dependencies, &specs);
+    context.spec_variables = spec_variables(&specs);
     let use_funs = use_funs(context, euse_funs);
     for (loc, s, _) in &econstants {
         context.bind_constant(*s, loc)
@@ -590,6 +634,7 @@ fn script(context: &mut Context, escript: E::Script) -> N::Script {
     context.restore_unscoped(outer_unscoped);
     context.env.pop_warning_filter_scope();
     context.current_package = None;
+    context.spec_variables = BTreeSet::new();
     N::Script {
         warning_filter,
         package_name,
@@ -717,7 +762,7 @@ fn explicit_use_fun(
     let target_function = m_f_opt?;
     let use_fun = N::UseFun {
         loc,
-        attributes,
+        attributes: Arc::new(attributes),
         is_public,
         target_function,
         kind: N::UseFunKind::Explicit,
@@ -805,6 +850,7 @@ fn function(
     name: FunctionName,
     ef: E::Function,
 ) -> N::Function {
+    context.env.set_current_member(name.0.loc);
     let E::Function {
         warning_filter,
         index,
@@ -907,9 +953,10 @@ fn function_body(context: &mut Context, sp!(loc, b_): E::FunctionBody) -> N::Fun
 
 fn struct_def(
     context: &mut Context,
-    _name: StructName,
+    name: StructName,
     sdef: E::StructDefinition,
 ) -> N::StructDefinition {
+    context.env.set_current_member(name.0.loc);
     let E::StructDefinition {
         warning_filter,
         index,
@@ -961,7 +1008,8 @@ fn struct_fields(context: &mut Context, efields: E::StructFields) -> N::StructFi
 // Constants
 //**************************************************************************************************
 
-fn constant(context: &mut Context, _name: ConstantName, econstant: E::Constant) -> N::Constant {
+fn constant(context: &mut Context, name: ConstantName, econstant: E::Constant) -> N::Constant {
+    context.env.set_current_member(name.0.loc);
     let E::Constant {
         warning_filter,
         index,
@@ -1341,6 +1389,12 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
             use E::ModuleAccess_ as EA;
             use N::BuiltinFunction_ as BF;
             assert!(tys_opt.is_none(), "ICE macros do not have type arguments");
+            let rhs = match &ma_ {
+                EA::Name(n) if n.value.as_str() == BF::ASSERT_MACRO => {
+                    assert_message_args(context, rhs)
+                }
+                _ => rhs,
+            };
             let nes = call_args(context, rhs);
             match ma_ {
                 EA::Name(n) if n.value.as_str() == BF::ASSERT_MACRO => {
@@ -1438,6 +1492,7 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
 
         EE::Spec(u, unbound_names) => {
             // Vars currently aren't shadowable by types/functions
+            let mut unresolved = vec![];
             let used_locals = unbound_names
                 .into_iter()
                 .filter_map(|v| {
@@ -1447,10 +1502,16 @@ fn exp_(context: &mut Context, e: E::Exp) -> N::Exp {
                             .unwrap();
                         Some(nv)
                     } else {
+                        if !context.is_spec_name(&v.value) {
+                            unresolved.push(v);
+                        }
                         None
                     }
                 })
                 .collect();
+            if !unresolved.is_empty() {
+                unresolved_spec_names(context, eloc, unresolved);
+            }
             NE::Spec(u, used_locals)
         }
         EE::UnresolvedError => {
@@ -1952,6 +2013,144 @@ fn report_unused_local(context: &mut Context, sp!(loc, unused_): &N::Var) {
 // Specs
 //**************************************************************************************************
 
+fn spec_variables<'a>(specs: impl IntoIterator<Item = &'a E::SpecBlock>) -> BTreeSet<Symbol> {
+    specs
+        .into_iter()
+        .flat_map(|sp!(_, sb_)| &sb_.members)
+        .filter_map(|sp!(_, sbm_)| match sbm_ {
+            E::SpecBlockMember_::Variable { name, .. } => Some(name.value),
+            _ => None,
+        })
+        .collect()
+}
+
+fn literal_constants(
+    constants: &UniqueMap<ConstantName, E::Constant>,
+) -> BTreeMap<Symbol, E::Value_> {
+    constants
+        .key_cloned_iter()
+        .filter_map(|(name, c)| match &c.value.value {
+            E::Exp_::Value(sp!(_, v)) => Some((name.value(), v.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+//**************************************************************************************************
+// Abort messages
+//**************************************************************************************************
+
+// Lowers `assert!(cond, b"message {}", args..)` to `assert!(cond, code)`, where `code` is the abort
+// code of the message formatted at compile time. Other arguments are left as is.
+fn assert_message_args(context: &mut Context, rhs: Spanned<Vec<E::Exp>>) -> Spanned<Vec<E::Exp>> {
+    let sp!(rloc, mut args) = rhs;
+    if !matches!(
+        args.get(1),
+        Some(sp!(_, E::Exp_::Value(sp!(_, E::Value_::Bytearray(_)))))
+    ) {
+        return sp(rloc, args);
+    }
+    let message_args = args.split_off(2);
+    let sp!(mloc, message_exp) = args.pop().unwrap();
+    let E::Exp_::Value(sp!(_, E::Value_::Bytearray(template))) = message_exp else {
+        unreachable!()
+    };
+    let code = match abort_message(context, mloc, template, message_args) {
+        Some(code) => E::Exp_::Value(sp(mloc, E::Value_::U64(code))),
+        None => E::Exp_::UnresolvedError,
+    };
+    args.push(sp(mloc, code));
+    sp(rloc, args)
+}
+
+fn abort_message(
+    context: &mut Context,
+    loc: Loc,
+    template: Vec<u8>,
+    args: Vec<E::Exp>,
+) -> Option<u64> {
+    let (Some(mident), Some(constants)) = (context.current_module, &context.literal_constants)
+    else {
+        context.env.add_diag(diag!(
+            Syntax::InvalidAbortMessage,
+            (
+                loc,
+                "Formatted abort messages are only supported in modules"
+            ),
+        ));
+        return None;
+    };
+    let Ok(template) = String::from_utf8(template) else {
+        context.env.add_diag(diag!(
+            Syntax::InvalidAbortMessage,
+            (
+                loc,
+                "Invalid abort message. The message must be valid UTF-8"
+            ),
+        ));
+        return None;
+    };
+    let mut formatted_args = vec![];
+    let mut valid = true;
+    for sp!(aloc, arg) in &args {
+        let value = match arg {
+            E::Exp_::Value(sp!(_, v)) => Some(v),
+            E::Exp_::Name(sp!(_, E::ModuleAccess_::Name(n)), None) => constants.get(&n.value),
+            E::Exp_::Name(sp!(_, E::ModuleAccess_::ModuleAccess(m, n)), None) if m == &mident => {
+                constants.get(&n.value)
+            }
+            _ => None,
+        };
+        match value {
+            Some(v) => formatted_args.push(abort_messages::format_value(v)),
+            None => {
+                valid = false;
+                context.env.add_diag(diag!(
+                    Syntax::InvalidAbortMessage,
+                    (
+                        *aloc,
+                        "Invalid abort message argument. Arguments must be literal values, or \
+                         constants of the module defined as literal values"
+                    ),
+                ));
+            }
+        }
+    }
+    if !valid {
+        return None;
+    }
+    match abort_messages::format(&template, &formatted_args) {
+        Ok(message) => Some(context.env.abort_message_code(mident, message, loc)),
+        Err(msg) => {
+            context.env.add_diag(diag!(
+                Syntax::InvalidAbortMessage,
+                (loc, format!("Invalid abort message. {}", msg)),
+            ));
+            None
+        }
+    }
+}
+
+fn unresolved_spec_names(context: &mut Context, loc: Loc, names: Vec<Name>) {
+    let listed = names
+        .iter()
+        .map(|n| format!("'{}'", n))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let msg = format!(
+        "Spec block refers to names that are not in scope: {}. \
+         The prover will not be able to check this block",
+        listed
+    );
+    let mut diag = diag!(Specs::UnresolvedName, (loc, msg));
+    diag.add_secondary_labels(
+        names
+            .into_iter()
+            .map(|n| (n.loc, format!("Unbound name '{}'", n))),
+    );
+    context.env.add_diag(diag);
+}
+
 fn spec_blocks<'a>(
     used: &mut BTreeSet<(ModuleIdent, Neighbor)>,
     specs: impl IntoIterator<Item = &'a E::SpecBlock>,
@@ -133,6 +133,7 @@ pub struct UseDecl {
 pub enum AttributeValue_ {
     Value(Value),
     ModuleAccess(NameAccessChain),
+    List(Vec<Value>),
 }
 pub type AttributeValue = Spanned<AttributeValue_>;
 
@@ -1090,6 +1091,11 @@ impl AstDebug for AttributeValue_ {
         match self {
             AttributeValue_::Value(v) => v.ast_debug(w),
             AttributeValue_::ModuleAccess(n) => n.ast_debug(w),
+            AttributeValue_::List(vs) => {
+                w.write("[");
+                w.comma(vs, |w, v| v.ast_debug(w));
+                w.write("]");
+            }
         }
     }
 }
@@ -11,9 +11,10 @@ pub(crate) mod merge_spec_modules;
 pub(crate) mod syntax;
 
 use crate::{
+    command_line::compiler::PASS_PARSER,
     diagnostics::{codes::Severity, Diagnostics, FilesSourceText},
     parser::{self, ast::PackageDefinition, syntax::parse_file_string},
-    shared::{CompilationEnv, IndexedPackagePath, NamedAddressMaps},
+    shared::{CompilationEnv, DuplicateModuleStrategy, IndexedPackagePath, NamedAddressMaps},
 };
 use anyhow::anyhow;
 use comments::*;
@@ -60,9 +61,9 @@ pub(crate) fn parse_program(
         Ok(res)
     }
 
-    let targets = find_move_filenames_with_address_mapping(targets)?;
+    let mut targets = find_move_filenames_with_address_mapping(targets)?;
     let mut deps = find_move_filenames_with_address_mapping(deps)?;
-    ensure_targets_deps_dont_intersect(compilation_env, &targets, &mut deps)?;
+    ensure_targets_deps_dont_intersect(compilation_env, &mut targets, &mut deps)?;
     let mut files: FilesSourceText = HashMap::new();
     let mut source_definitions = Vec::new();
     let mut source_comments = CommentMap::new();
@@ -83,6 +84,7 @@ pub(crate) fn parse_program(
             def,
         }));
         source_comments.insert(file_hash, comments);
+        compilation_env.stream_external_diags(PASS_PARSER, &ds);
         diags.extend(ds);
     }
 
@@ -98,11 +100,13 @@ pub(crate) fn parse_program(
             named_address_map,
             def,
         }));
+        compilation_env.stream_external_diags(PASS_PARSER, &ds);
         diags.extend(ds);
     }
 
     // TODO fix this so it works likes other passes and the handling of errors is done outside of
     // this function
+    compilation_env.stream_diags(PASS_PARSER);
     let env_result = compilation_env.check_diags_at_or_above_severity(Severity::BlockingError);
     if let Err(env_diags) = env_result {
         diags.extend(env_diags)
@@ -123,7 +127,7 @@ pub(crate) fn parse_program(
 
 fn ensure_targets_deps_dont_intersect(
     compilation_env: &CompilationEnv,
-    targets: &[IndexedPackagePath],
+    targets: &mut Vec<IndexedPackagePath>,
     deps: &mut Vec<IndexedPackagePath>,
 ) -> anyhow::Result<()> {
     /// Canonicalize a file path.
@@ -146,9 +150,16 @@ fn ensure_targets_deps_dont_intersect(
     if intersection.is_empty() {
         return Ok(());
     }
-    if compilation_env.flags().sources_shadow_deps() {
-        deps.retain(|p| !intersection.contains(&&canonicalize(&p.path)));
-        return Ok(());
+    match compilation_env.flags().duplicate_module_strategy() {
+        DuplicateModuleStrategy::Error => (),
+        DuplicateModuleStrategy::PreferDep => {
+            targets.retain(|p| !intersection.contains(&&canonicalize(&p.path)));
+            return Ok(());
+        }
+        DuplicateModuleStrategy::PreferSource | DuplicateModuleStrategy::PreferHigherVersion => {
+            deps.retain(|p| !intersection.contains(&&canonicalize(&p.path)));
+            return Ok(());
+        }
     }
     let all_files = intersection
         .into_iter()
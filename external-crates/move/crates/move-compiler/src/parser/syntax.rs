@@ -597,15 +597,28 @@ fn parse_visibility(context: &mut Context) -> Result<Visibility, Box<Diagnostic>
         }
     })
 }
-// Parse an attribute value. Either a value literal or a module access
+// Parse an attribute value. Either a value literal, a list of value literals, or a module access
 //      AttributeValue =
 //          <Value>
+//          | "[" Comma<Value> "]"
 //          | <NameAccessChain>
 fn parse_attribute_value(context: &mut Context) -> Result<AttributeValue, Box<Diagnostic>> {
     if let Some(v) = maybe_parse_value(context)? {
         return Ok(sp(v.loc, AttributeValue_::Value(v)));
     }
 
+    if context.tokens.peek() == Tok::LBracket {
+        let start_loc = context.tokens.start_loc();
+        let values = parse_comma_list(context, Tok::LBracket, Tok::RBracket, parse_value, "value")?;
+        let end_loc = context.tokens.previous_end_loc();
+        return Ok(spanned(
+            context.tokens.file_hash(),
+            start_loc,
+            end_loc,
+            AttributeValue_::List(values),
+        ));
+    }
+
     let ma = parse_name_access_chain(context, || "attribute name value")?;
     Ok(sp(ma.loc, AttributeValue_::ModuleAccess(ma)))
 }
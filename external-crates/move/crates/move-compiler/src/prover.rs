@@ -0,0 +1,130 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Extension point for running a prover backend as a pass of the compiler. A backend registered
+//! with `Compiler::set_prover_backend` is run at `PASS_PROVER`, right after typing, on the typed
+//! program and the spec blocks extracted during expansion. Its findings are reported as compiler
+//! diagnostics, so that it can reuse the compiler's parsing, address resolution, and reporting
+//! instead of running a separate pipeline.
+//!
+//! No backend is provided by this crate. The Move Prover depends on the compiler (through
+//! `move-model`, which builds its `GlobalEnv` by running its own compilation from source), so a
+//! backend that drives it has to be implemented downstream of both, and the prover itself still
+//! runs as a separate pipeline until `move-model` can be built from a typed program.
+
+use crate::{
+    diag,
+    diagnostics::{Diagnostic, Diagnostics},
+    expansion::ast::{self as E, ModuleIdent, SpecId},
+    parser::ast::FunctionName,
+    shared::{unique_map::UniqueMap, CompilationEnv},
+    typing::ast as T,
+};
+use move_ir_types::location::Loc;
+use std::collections::BTreeMap;
+
+pub type ProverBackendObj = Box<dyn ProverBackend + Send>;
+
+pub trait ProverBackend {
+    /// Verifies the typed program against its specs. Only called if the program has no blocking
+    /// errors.
+    fn verify(&mut self, program: &T::Program, specs: &ProgramSpecs) -> Vec<ProverFinding>;
+}
+
+/// The spec blocks of the modules being compiled, as extracted during expansion
+#[derive(Debug, Clone, Default)]
+pub struct ProgramSpecs {
+    pub modules: BTreeMap<ModuleIdent, ModuleSpecs>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ModuleSpecs {
+    /// The module level spec blocks, including the ones targeting its members
+    pub specs: Vec<E::SpecBlock>,
+    /// The spec blocks inside function bodies, referred to by `T::UnannotatedExp_::Spec`
+    pub function_specs: BTreeMap<FunctionName, BTreeMap<SpecId, E::SpecBlock>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProverFindingKind {
+    /// A property that does not hold
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProverFinding {
+    pub kind: ProverFindingKind,
+    pub loc: Loc,
+    pub message: String,
+    /// Additional locations, e.g. an execution trace leading to the failure
+    pub secondary_labels: Vec<(Loc, String)>,
+    pub notes: Vec<String>,
+}
+
+impl ProgramSpecs {
+    pub fn new(prog: &E::Program) -> Self {
+        let modules = prog
+            .modules
+            .key_cloned_iter()
+            .map(|(mident, mdef)| (mident, ModuleSpecs::new(mdef)))
+            .collect();
+        Self { modules }
+    }
+}
+
+impl ModuleSpecs {
+    fn new(mdef: &E::ModuleDefinition) -> Self {
+        Self {
+            specs: mdef.specs.clone(),
+            function_specs: function_specs(&mdef.functions),
+        }
+    }
+}
+
+fn function_specs(
+    functions: &UniqueMap<FunctionName, E::Function>,
+) -> BTreeMap<FunctionName, BTreeMap<SpecId, E::SpecBlock>> {
+    functions
+        .key_cloned_iter()
+        .filter(|(_, f)| !f.specs.is_empty())
+        .map(|(name, f)| (name, f.specs.clone()))
+        .collect()
+}
+
+impl ProverFinding {
+    fn into_diag(self) -> Diagnostic {
+        let Self {
+            kind,
+            loc,
+            message,
+            secondary_labels,
+            notes,
+        } = self;
+        let mut diag = match kind {
+            ProverFindingKind::Error => diag!(Prover::VerificationError, (loc, message)),
+            ProverFindingKind::Warning => diag!(Prover::VerificationWarning, (loc, message)),
+        };
+        diag.add_secondary_labels(secondary_labels);
+        diag.add_notes(notes);
+        diag
+    }
+}
+
+//**************************************************************************************************
+// Entry
+//**************************************************************************************************
+
+/// Runs the registered backend, if any, on the typed program
+pub(crate) fn program(compilation_env: &mut CompilationEnv, prog: &T::Program) {
+    let Some(mut backend) = compilation_env.take_prover_backend() else {
+        return;
+    };
+    let specs = compilation_env.take_prover_specs().unwrap_or_default();
+    let diags: Diagnostics = backend
+        .verify(prog, &specs)
+        .into_iter()
+        .map(ProverFinding::into_diag)
+        .collect();
+    compilation_env.add_diags(diags);
+}
@@ -0,0 +1,128 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Abort messages formatted at compile time. `assert!(cond, b"message {}", args..)` is lowered to
+//! `assert!(cond, code)`, where `code` identifies the formatted message in the catalogue of its
+//! module. The messages are added to the constant pool of the module, and the catalogue is emitted
+//! beside the compiled unit, so that an abort code can be translated back to its message.
+
+use crate::{diagnostics::FilesSourceText, expansion::ast as E};
+use move_ir_types::location::Loc;
+use serde::Serialize;
+
+/// Set in every abort code allocated for a message, to tell them apart from user abort codes
+pub const ABORT_MESSAGE_CODE_BASE: u64 = 1 << 63;
+
+/// Prefix of the constants holding the messages in the constant pool of the module
+pub const ABORT_MESSAGE_CONSTANT_PREFIX: &str = "__abort_message_";
+
+/// Extension of the catalogue file emitted beside the compiled unit
+pub const ABORT_MESSAGES_EXTENSION: &str = "abort_messages.json";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbortMessage {
+    pub code: u64,
+    pub message: String,
+    /// The location of the first `assert!` using the message
+    pub loc: Loc,
+}
+
+#[derive(Serialize)]
+struct CatalogueEntry<'a> {
+    code: u64,
+    message: &'a str,
+    file: &'a str,
+    line: usize,
+}
+
+impl AbortMessage {
+    /// The name of the constant holding the message
+    pub fn constant_name(&self) -> String {
+        format!(
+            "{}{}",
+            ABORT_MESSAGE_CONSTANT_PREFIX,
+            self.code - ABORT_MESSAGE_CODE_BASE
+        )
+    }
+}
+
+/// Formats `template`, replacing each `{}` with the next argument. `{{` and `}}` are escapes for
+/// braces. Fails if the number of arguments does not match the number of placeholders.
+pub fn format(template: &str, args: &[String]) -> Result<String, String> {
+    let mut message = String::new();
+    let mut remaining = args.iter();
+    let mut chars = template.chars().peekable();
+    let mut placeholders = 0;
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('{', Some('{')) | ('}', Some('}')) => {
+                chars.next();
+                message.push(c)
+            }
+            ('{', Some('}')) => {
+                chars.next();
+                placeholders += 1;
+                if let Some(arg) = remaining.next() {
+                    message.push_str(arg)
+                }
+            }
+            ('{', _) | ('}', _) => {
+                return Err(format!(
+                    "Unmatched '{}'. Use '{{}}' for an argument, and '{}{}' for a brace",
+                    c, c, c
+                ))
+            }
+            _ => message.push(c),
+        }
+    }
+    if placeholders != args.len() {
+        return Err(format!(
+            "Expected {} argument(s) for the message, found {}",
+            placeholders,
+            args.len()
+        ));
+    }
+    Ok(message)
+}
+
+/// Formats a literal value as an argument of a message
+pub fn format_value(value: &E::Value_) -> String {
+    use E::Value_ as V;
+    match value {
+        V::Address(addr) => format!("{}", addr),
+        V::InferredNum(n) | V::U256(n) => format!("{}", n),
+        V::U8(n) => format!("{}", n),
+        V::U16(n) => format!("{}", n),
+        V::U32(n) => format!("{}", n),
+        V::U64(n) => format!("{}", n),
+        V::U128(n) => format!("{}", n),
+        V::Bool(b) => format!("{}", b),
+        V::Bytearray(bytes) => match std::str::from_utf8(bytes) {
+            Ok(s) => s.to_string(),
+            Err(_) => format!("0x{}", hex::encode(bytes)),
+        },
+    }
+}
+
+/// Renders the catalogue of a module as JSON
+pub fn catalogue_json(files: &FilesSourceText, messages: &[AbortMessage]) -> String {
+    let entries: Vec<_> = messages
+        .iter()
+        .map(|AbortMessage { code, message, loc }| {
+            let (file, line) = match files.get(&loc.file_hash()) {
+                Some((file, source)) => {
+                    let start = (loc.start() as usize).min(source.len());
+                    (file.as_str(), source[..start].matches('\n').count() + 1)
+                }
+                None => ("<unknown>", 0),
+            };
+            CatalogueEntry {
+                code: *code,
+                message,
+                file,
+                line,
+            }
+        })
+        .collect();
+    serde_json::to_string_pretty(&entries).unwrap()
+}
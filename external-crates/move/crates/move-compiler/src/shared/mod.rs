@@ -6,13 +6,20 @@ use crate::{
     cfgir::visitor::{AbsIntVisitorObj, AbstractInterpreterVisitor},
     command_line as cli,
     diagnostics::{
-        codes::{Category, Declarations, DiagnosticsID, Severity, UnusedItem, WarningFilter},
+        codes::{
+            Category, Declarations, DiagnosticsID, Severity, Specs, TypeSafety, UnusedItem,
+            WarningFilter,
+        },
         Diagnostic, Diagnostics, WarningFilters,
     },
     editions::{
-        check_feature as edition_check_feature, Edition, FeatureGate, Flavor, SyntaxEdition,
+        check_feature as edition_check_feature, feature_gates, Edition, FeatureGate,
+        FeatureGateInfo, Flavor, SyntaxEdition,
+    },
+    expansion::{
+        ast as E,
+        symbol_index::{SymbolDefinition, SymbolIndex, SymbolReference},
     },
-    expansion::ast as E,
     naming::ast as N,
     naming::ast::ModuleDefinition,
     sui_mode,
@@ -23,15 +30,20 @@ use move_ir_types::location::*;
 use move_symbol_pool::Symbol;
 use petgraph::{algo::astar as petgraph_astar, graphmap::DiGraphMap};
 use std::{
-    cell::RefCell,
+    cell::{Cell, RefCell},
     collections::{BTreeMap, BTreeSet},
     fmt,
     hash::Hash,
     rc::Rc,
-    sync::atomic::{AtomicUsize, Ordering as AtomicOrdering},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering as AtomicOrdering},
+        Arc,
+    },
 };
 
+pub mod abort_messages;
 pub mod ast_debug;
+pub mod package_builder;
 pub mod program_info;
 pub mod remembering_unique_map;
 pub mod unique_map;
@@ -144,6 +156,25 @@ pub fn shortest_cycle<'a, T: Ord + Hash>(
 // Compilation Env
 //**************************************************************************************************
 
+/// The item being compiled, as recorded by the passes. Shared with the `Compiler`, so that a panic
+/// can be reported at the item that caused it.
+#[derive(Debug, Clone, Default)]
+pub struct CompilationPosition(Rc<Cell<Position>>);
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Position {
+    module: Option<Loc>,
+    member: Option<Loc>,
+}
+
+impl CompilationPosition {
+    /// The location of the name of the current member, or of the current module if not in a member
+    pub fn current_loc(&self) -> Option<Loc> {
+        let Position { module, member } = self.0.get();
+        member.or(module)
+    }
+}
+
 pub const FILTER_ALL: &str = "all";
 pub const FILTER_UNUSED: &str = "unused";
 pub const FILTER_MISSING_PHANTOM: &str = "missing_phantom";
@@ -160,6 +191,10 @@ pub const FILTER_DEAD_CODE: &str = "dead_code";
 pub const FILTER_UNUSED_LET_MUT: &str = "unused_let_mut";
 pub const FILTER_UNUSED_MUT_REF: &str = "unused_mut_ref";
 pub const FILTER_UNUSED_MUT_PARAM: &str = "unused_mut_parameter";
+pub const FILTER_UNUSED_FRIEND: &str = "unused_friend";
+pub const FILTER_DEAD_SPEC: &str = "dead_spec";
+pub const FILTER_DEPRECATED: &str = "deprecated";
+pub const FILTER_SHADOWED_METHOD: &str = "shadowed_method";
 
 pub type NamedAddressMap = BTreeMap<Symbol, NumericalAddress>;
 
@@ -187,6 +222,15 @@ impl NamedAddressMaps {
     pub fn all(&self) -> &[NamedAddressMap] {
         &self.0
     }
+
+    /// The position in `all` of the map at `idx`
+    pub fn position(&self, idx: NamedAddressMapIndex) -> usize {
+        idx.0
+    }
+
+    pub(crate) fn all_mut(&mut self) -> &mut [NamedAddressMap] {
+        &mut self.0
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -196,6 +240,17 @@ pub struct PackagePaths<Path: Into<Symbol> = Symbol, NamedAddress: Into<Symbol>
     pub named_address_map: BTreeMap<NamedAddress, NumericalAddress>,
 }
 
+/// A target package of a workspace, compiled with the other packages of the workspace
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct WorkspacePackage<Path: Into<Symbol> = Symbol, NamedAddress: Into<Symbol> = Symbol> {
+    pub name: Symbol,
+    pub config: PackageConfig,
+    pub paths: Vec<Path>,
+    pub named_address_map: BTreeMap<NamedAddress, NumericalAddress>,
+    /// The packages of the workspace whose modules this package can use
+    pub dependencies: Vec<Symbol>,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct IndexedPackagePath {
     pub package: Option<Symbol>,
@@ -240,6 +295,32 @@ pub struct CompilationEnv {
     known_filter_attributes: BTreeSet<E::AttributeName_>,
     prim_definers:
         BTreeMap<crate::naming::ast::BuiltinTypeName_, crate::expansion::ast::ModuleIdent>,
+    /// Checked between passes to stop compilation early
+    cancellation: CancellationToken,
+    /// Receives the diagnostics in `diags` as passes finish
+    diagnostics_sink: Option<cli::compiler::DiagnosticsSink>,
+    /// The number of diagnostics in `diags` already given to `diagnostics_sink`
+    streamed_diags: usize,
+    /// The module and member being compiled, to report a panic at the item that caused it
+    position: CompilationPosition,
+    /// Resolved names and their definitions, populated only if `Flags::symbol_index` is set
+    symbol_index: SymbolIndex,
+    /// Run at `PASS_PROVER`, taken when run
+    #[cfg(feature = "prover")]
+    prover_backend: Option<crate::prover::ProverBackendObj>,
+    /// The specs extracted during expansion, only if there is a prover backend
+    #[cfg(feature = "prover")]
+    prover_specs: Option<crate::prover::ProgramSpecs>,
+    /// Deferred named addresses left without a value by the resolver, and the locations using them
+    unresolved_deferred_addresses: BTreeMap<Symbol, BTreeSet<Loc>>,
+    /// The catalogue of formatted abort messages of each module, in order of allocation
+    abort_messages: BTreeMap<E::ModuleIdent, Vec<abort_messages::AbortMessage>>,
+    /// The doc comments of the targets, keyed by the start of the item they document
+    doc_comments: crate::parser::comments::CommentMap,
+    /// The constants used as abort codes by each module, found in CFGIR
+    abort_codes: BTreeMap<E::ModuleIdent, Vec<crate::cfgir::abort_codes::AbortCode>>,
+    /// For each package of a workspace, the workspace packages it depends on, transitively
+    workspace_dependencies: BTreeMap<Symbol, BTreeSet<Symbol>>,
     // TODO(tzakian): Remove the global counter and use this counter instead
     // pub counter: u64,
 }
@@ -268,6 +349,7 @@ impl CompilationEnv {
         visitors.extend([
             sui_mode::id_leak::IDLeakVerifier.visitor(),
             sui_mode::typing::SuiTypeChecks.visitor(),
+            sui_mode::entry_points::EntrySignatureChecks.visitor(),
         ]);
         let filter_attr_name =
             E::AttributeName_::Known(known_attributes::KnownAttribute::Diagnostic(
@@ -357,6 +439,21 @@ impl CompilationEnv {
                 UnusedItem::MutParam,
                 filter_attr_name
             ),
+            known_code_filter!(FILTER_UNUSED_FRIEND, UnusedItem::Friend, filter_attr_name),
+            known_code_filter!(FILTER_DEAD_SPEC, Specs::UnresolvedName, filter_attr_name),
+            (
+                KnownFilterInfo::new(FILTER_DEPRECATED, filter_attr_name),
+                BTreeSet::from([WarningFilter::Category {
+                    prefix: None,
+                    category: Category::Deprecation as u8,
+                    name: Some(FILTER_DEPRECATED),
+                }]),
+            ),
+            known_code_filter!(
+                FILTER_SHADOWED_METHOD,
+                TypeSafety::ShadowedMethod,
+                filter_attr_name
+            ),
         ]);
 
         let known_filter_names: BTreeMap<DiagnosticsID, KnownFilterInfo> = known_filters
@@ -396,6 +493,20 @@ impl CompilationEnv {
             known_filter_names,
             known_filter_attributes: filter_attributes,
             prim_definers: BTreeMap::new(),
+            cancellation: CancellationToken::new(),
+            diagnostics_sink: None,
+            streamed_diags: 0,
+            position: CompilationPosition::default(),
+            symbol_index: SymbolIndex::new(),
+            #[cfg(feature = "prover")]
+            prover_backend: None,
+            #[cfg(feature = "prover")]
+            prover_specs: None,
+            unresolved_deferred_addresses: BTreeMap::new(),
+            abort_messages: BTreeMap::new(),
+            doc_comments: BTreeMap::new(),
+            abort_codes: BTreeMap::new(),
+            workspace_dependencies: BTreeMap::new(),
         }
     }
 
@@ -459,6 +570,7 @@ impl CompilationEnv {
         threshold: Severity,
     ) -> Result<(), Diagnostics> {
         if self.has_diags_at_or_above_severity(threshold) {
+            self.streamed_diags = 0;
             Err(std::mem::take(&mut self.diags))
         } else {
             Ok(())
@@ -467,6 +579,7 @@ impl CompilationEnv {
 
     /// Should only be called after compilation is finished
     pub fn take_final_warning_diags(&mut self) -> Diagnostics {
+        self.streamed_diags = 0;
         let final_diags = std::mem::take(&mut self.diags);
         debug_assert!(final_diags
             .max_severity()
@@ -562,6 +675,186 @@ impl CompilationEnv {
         self.visitors.clone()
     }
 
+    pub fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation = token;
+    }
+
+    pub fn set_diagnostics_sink(&mut self, sink: cli::compiler::DiagnosticsSink) {
+        self.diagnostics_sink = Some(sink);
+    }
+
+    /// Gives the diagnostics added since the last call to the diagnostics sink, if any
+    pub fn stream_diags(&mut self, pass: cli::compiler::Pass) {
+        let Some(sink) = &mut self.diagnostics_sink else {
+            return;
+        };
+        for diag in self.diags.iter().skip(self.streamed_diags) {
+            sink(pass, diag)
+        }
+        self.streamed_diags = self.diags.len();
+    }
+
+    /// Gives diagnostics kept outside of the environment, such as the ones of a parsed file, to the
+    /// diagnostics sink, if any
+    pub fn stream_external_diags(&mut self, pass: cli::compiler::Pass, diags: &Diagnostics) {
+        if let Some(sink) = &mut self.diagnostics_sink {
+            for diag in diags.iter() {
+                sink(pass, diag)
+            }
+        }
+    }
+
+    #[cfg(feature = "prover")]
+    pub fn set_prover_backend(&mut self, backend: crate::prover::ProverBackendObj) {
+        self.prover_backend = Some(backend);
+    }
+
+    #[cfg(feature = "prover")]
+    pub fn has_prover_backend(&self) -> bool {
+        self.prover_backend.is_some()
+    }
+
+    #[cfg(feature = "prover")]
+    pub(crate) fn take_prover_backend(&mut self) -> Option<crate::prover::ProverBackendObj> {
+        self.prover_backend.take()
+    }
+
+    #[cfg(feature = "prover")]
+    pub(crate) fn set_prover_specs(&mut self, specs: crate::prover::ProgramSpecs) {
+        self.prover_specs = Some(specs);
+    }
+
+    #[cfg(feature = "prover")]
+    pub(crate) fn take_prover_specs(&mut self) -> Option<crate::prover::ProgramSpecs> {
+        self.prover_specs.take()
+    }
+
+    pub(crate) fn set_workspace_dependencies(
+        &mut self,
+        dependencies: BTreeMap<Symbol, BTreeSet<Symbol>>,
+    ) {
+        self.workspace_dependencies = dependencies
+    }
+
+    /// The workspace packages `package` depends on, transitively, or `None` if the package is not
+    /// part of a workspace
+    pub fn workspace_dependencies(&self, package: Option<Symbol>) -> Option<&BTreeSet<Symbol>> {
+        self.workspace_dependencies.get(&package?)
+    }
+
+    pub(crate) fn set_unresolved_deferred_addresses(&mut self, names: BTreeSet<Symbol>) {
+        self.unresolved_deferred_addresses =
+            names.into_iter().map(|n| (n, BTreeSet::new())).collect()
+    }
+
+    /// Records a use of a named address without a value. Returns `false` if the name is not an
+    /// unresolved deferred address, in which case the use should be reported on its own.
+    pub(crate) fn record_unresolved_deferred_address(&mut self, n: &Name) -> bool {
+        match self.unresolved_deferred_addresses.get_mut(&n.value) {
+            Some(locs) => {
+                locs.insert(n.loc);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The unresolved deferred addresses used so far, and their uses
+    pub(crate) fn take_unresolved_deferred_address_uses(
+        &mut self,
+    ) -> BTreeMap<Symbol, BTreeSet<Loc>> {
+        self.unresolved_deferred_addresses
+            .iter_mut()
+            .filter(|(_, locs)| !locs.is_empty())
+            .map(|(n, locs)| (*n, std::mem::take(locs)))
+            .collect()
+    }
+
+    /// The abort code of `message` in `module`, allocated if the module does not use the message
+    /// yet
+    pub(crate) fn abort_message_code(
+        &mut self,
+        module: E::ModuleIdent,
+        message: String,
+        loc: Loc,
+    ) -> u64 {
+        let messages = self.abort_messages.entry(module).or_default();
+        if let Some(m) = messages.iter().find(|m| m.message == message) {
+            return m.code;
+        }
+        let code = abort_messages::ABORT_MESSAGE_CODE_BASE | messages.len() as u64;
+        messages.push(abort_messages::AbortMessage { code, message, loc });
+        code
+    }
+
+    pub(crate) fn take_abort_messages(
+        &mut self,
+        module: &E::ModuleIdent,
+    ) -> Vec<abort_messages::AbortMessage> {
+        self.abort_messages.remove(module).unwrap_or_default()
+    }
+
+    pub(crate) fn set_doc_comments(&mut self, doc_comments: crate::parser::comments::CommentMap) {
+        self.doc_comments = doc_comments
+    }
+
+    /// The doc comment of the item starting at `loc`, if it has one
+    pub fn doc_comment(&self, loc: Loc) -> Option<&str> {
+        self.doc_comments
+            .get(&loc.file_hash())?
+            .get(&loc.start())
+            .map(|doc| doc.as_str())
+            .filter(|doc| !doc.is_empty())
+    }
+
+    pub(crate) fn add_abort_codes(
+        &mut self,
+        module: E::ModuleIdent,
+        codes: Vec<crate::cfgir::abort_codes::AbortCode>,
+    ) {
+        if !codes.is_empty() {
+            self.abort_codes.insert(module, codes);
+        }
+    }
+
+    pub(crate) fn take_abort_codes(
+        &mut self,
+        module: &E::ModuleIdent,
+    ) -> Vec<crate::cfgir::abort_codes::AbortCode> {
+        self.abort_codes.remove(module).unwrap_or_default()
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.cancellation.is_cancelled()
+    }
+
+    /// Records that the module named at `loc` is being compiled, outside of any member
+    pub fn set_current_module(&self, loc: Loc) {
+        self.position.0.set(Position {
+            module: Some(loc),
+            member: None,
+        })
+    }
+
+    /// Records that the member named at `loc`, of the current module if any, is being compiled
+    pub fn set_current_member(&self, loc: Loc) {
+        let Position { module, .. } = self.position.0.get();
+        self.position.0.set(Position {
+            module,
+            member: Some(loc),
+        })
+    }
+
+    /// Records that no item is being compiled, e.g. between passes
+    pub fn clear_current_position(&self) {
+        self.position.0.set(Position::default())
+    }
+
+    /// The position of the compilation, which can still be read if the compilation panics
+    pub fn position(&self) -> CompilationPosition {
+        self.position.clone()
+    }
+
     // Logs an error if the feature isn't supported. Returns `false` if the feature is not
     // supported, and `true` otherwise.
     pub fn check_feature(
@@ -577,6 +870,15 @@ impl CompilationEnv {
         self.package_config(package).edition.supports(feature)
     }
 
+    /// Lists all feature gates and whether they are enabled for the given package
+    pub fn feature_gates(&self, package: Option<Symbol>) -> Vec<FeatureGateInfo> {
+        feature_gates(self.package_config(package))
+    }
+
+    pub fn edition(&self, package: Option<Symbol>) -> Edition {
+        self.package_config(package).edition
+    }
+
     pub fn syntax_edition(&self, package: Option<Symbol>) -> SyntaxEdition {
         self.package_config(package).edition.syntax()
     }
@@ -597,8 +899,68 @@ impl CompilationEnv {
     pub fn primitive_definer(&self, t: N::BuiltinTypeName_) -> Option<&E::ModuleIdent> {
         self.prim_definers.get(&t)
     }
+
+    pub fn symbol_index(&self) -> &SymbolIndex {
+        &self.symbol_index
+    }
+
+    pub(crate) fn add_symbol_definition(&mut self, def_loc: Loc, definition: SymbolDefinition) {
+        if self.flags.symbol_index() {
+            self.symbol_index.add_definition(def_loc, definition)
+        }
+    }
+
+    pub(crate) fn add_symbol_reference(&mut self, reference: SymbolReference) {
+        if self.flags.symbol_index() {
+            self.symbol_index.add(reference)
+        }
+    }
 }
 
+//**************************************************************************************************
+// Cancellation
+//**************************************************************************************************
+
+/// A shared flag for cooperatively cancelling a compilation. The compiler checks it between passes,
+/// so a cancelled compilation stops at the next pass boundary rather than immediately.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+/// The error returned by `Compiler` entry points when compilation was stopped through its
+/// `CancellationToken`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cancelled;
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, AtomicOrdering::Relaxed)
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(AtomicOrdering::Relaxed)
+    }
+
+    pub(crate) fn check(&self) -> Result<(), Cancelled> {
+        if self.is_cancelled() {
+            Err(Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Compilation was cancelled")
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
 //**************************************************************************************************
 // Counter
 //**************************************************************************************************
@@ -634,6 +996,9 @@ pub fn format_comma<T: fmt::Display, I: IntoIterator<Item = T>>(items: I) -> Str
 // Flags
 //**************************************************************************************************
 
+/// The highest optimization level, see `Flags::optimization_level`
+pub const MAX_OPTIMIZATION_LEVEL: u8 = 2;
+
 #[derive(Clone, Debug, Eq, PartialEq, Parser)]
 pub struct Flags {
     /// Compile in test mode
@@ -672,6 +1037,22 @@ pub struct Flags {
     )]
     shadow: bool,
 
+    /// How a module defined in both the sources and the dependencies is resolved. If set, the
+    /// module kept is reported. Defaults to 'prefer-source' if sources shadow dependencies, and to
+    /// 'error' otherwise
+    #[clap(
+        long = cli::DUPLICATE_MODULES,
+        value_enum,
+    )]
+    duplicate_modules: Option<DuplicateModuleStrategy>,
+
+    /// If set, every module defined by several packages is reported, with the package and the
+    /// package paths entry of each definition
+    #[clap(
+        long = cli::AUDIT_DUPLICATE_MODULES,
+    )]
+    audit_duplicate_modules: bool,
+
     /// Bytecode version.
     #[clap(
         long = cli::BYTECODE_VERSION,
@@ -682,6 +1063,48 @@ pub struct Flags {
     /// included only in tests, without creating the unit test code regular tests do.
     #[clap(skip)]
     keep_testing_functions: bool,
+
+    /// Internal flag used by IDE tooling to record, for every resolved name, the location of its
+    /// definition (see `CompilationEnv::symbol_index`).
+    #[clap(skip)]
+    symbol_index: bool,
+
+    /// Internal flag used by the unit test runner to instrument every basic block of the compiled
+    /// code with a coverage counter. Only takes effect in test mode.
+    #[clap(skip)]
+    coverage: bool,
+
+    /// If set, a report of every named address, its value, the packages declaring it, and its
+    /// conflicts with other names is printed before compiling
+    #[clap(
+        long = cli::NAMED_ADDRESS_REPORT,
+    )]
+    named_address_report: bool,
+
+    /// The optimization level. 0 runs the default optimizations, 1 also eliminates dead stores,
+    /// propagates copies and simplifies branches, and 2 also inlines small private functions
+    #[clap(
+        long = cli::OPTIMIZATION_LEVEL,
+        value_parser = clap::value_parser!(u8).range(0..=MAX_OPTIMIZATION_LEVEL as i64),
+        default_value_t = 0,
+    )]
+    optimization_level: u8,
+
+    /// If set, small private functions are inlined into their callers in the same module, and
+    /// constants are folded into function bodies, before bytecode generation
+    #[clap(
+        long = cli::INLINE_FUNCTIONS,
+    )]
+    inline_functions: bool,
+
+    /// If set, when the compiler panics, the module of the item being compiled is written to this
+    /// directory, with a description of the panic, to be attached to a bug report. Panics are only
+    /// caught in builds that unwind on panic, not in those built with `panic = 'abort'`
+    #[clap(
+        name = "ICE_REPRO_DIR",
+        long = cli::ICE_REPRO_DIR,
+    )]
+    ice_repro_dir: Option<String>,
 }
 
 impl Flags {
@@ -694,6 +1117,14 @@ impl Flags {
             warnings_are_errors: false,
             silence_warnings: false,
             keep_testing_functions: false,
+            symbol_index: false,
+            coverage: false,
+            named_address_report: false,
+            duplicate_modules: None,
+            audit_duplicate_modules: false,
+            inline_functions: false,
+            optimization_level: 0,
+            ice_repro_dir: None,
         }
     }
 
@@ -706,6 +1137,14 @@ impl Flags {
             warnings_are_errors: false,
             silence_warnings: false,
             keep_testing_functions: false,
+            symbol_index: false,
+            coverage: false,
+            named_address_report: false,
+            duplicate_modules: None,
+            audit_duplicate_modules: false,
+            inline_functions: false,
+            optimization_level: 0,
+            ice_repro_dir: None,
         }
     }
 
@@ -718,6 +1157,14 @@ impl Flags {
             warnings_are_errors: false,
             silence_warnings: false,
             keep_testing_functions: false,
+            symbol_index: false,
+            coverage: false,
+            named_address_report: false,
+            duplicate_modules: None,
+            audit_duplicate_modules: false,
+            inline_functions: false,
+            optimization_level: 0,
+            ice_repro_dir: None,
         }
     }
 
@@ -728,6 +1175,63 @@ impl Flags {
         }
     }
 
+    pub fn set_symbol_index(self, value: bool) -> Self {
+        Self {
+            symbol_index: value,
+            ..self
+        }
+    }
+
+    pub fn set_coverage(self, value: bool) -> Self {
+        Self {
+            coverage: value,
+            ..self
+        }
+    }
+
+    pub fn set_named_address_report(self, value: bool) -> Self {
+        Self {
+            named_address_report: value,
+            ..self
+        }
+    }
+
+    pub fn set_duplicate_module_strategy(self, strategy: DuplicateModuleStrategy) -> Self {
+        Self {
+            duplicate_modules: Some(strategy),
+            ..self
+        }
+    }
+
+    pub fn set_audit_duplicate_modules(self, value: bool) -> Self {
+        Self {
+            audit_duplicate_modules: value,
+            ..self
+        }
+    }
+
+    pub fn set_optimization_level(self, level: u8) -> Self {
+        assert!(level <= MAX_OPTIMIZATION_LEVEL);
+        Self {
+            optimization_level: level,
+            ..self
+        }
+    }
+
+    pub fn set_inline_functions(self, value: bool) -> Self {
+        Self {
+            inline_functions: value,
+            ..self
+        }
+    }
+
+    pub fn set_ice_repro_dir(self, ice_repro_dir: Option<String>) -> Self {
+        Self {
+            ice_repro_dir,
+            ..self
+        }
+    }
+
     pub fn set_sources_shadow_deps(self, sources_shadow_deps: bool) -> Self {
         Self {
             shadow: sources_shadow_deps,
@@ -761,6 +1265,32 @@ impl Flags {
         self.test || self.keep_testing_functions
     }
 
+    pub fn symbol_index(&self) -> bool {
+        self.symbol_index
+    }
+
+    /// Whether basic blocks are instrumented with coverage counters, which requires test mode
+    pub fn coverage(&self) -> bool {
+        self.test && self.coverage
+    }
+
+    pub fn named_address_report(&self) -> bool {
+        self.named_address_report
+    }
+
+    pub fn optimization_level(&self) -> u8 {
+        self.optimization_level
+    }
+
+    /// Whether small private functions are inlined, either explicitly or by the optimization level
+    pub fn inline_functions(&self) -> bool {
+        self.inline_functions || self.optimization_level >= 2
+    }
+
+    pub fn ice_repro_dir(&self) -> Option<&str> {
+        self.ice_repro_dir.as_deref()
+    }
+
     pub fn is_verification(&self) -> bool {
         self.verify
     }
@@ -769,6 +1299,23 @@ impl Flags {
         self.shadow
     }
 
+    pub fn duplicate_module_strategy(&self) -> DuplicateModuleStrategy {
+        match self.duplicate_modules {
+            Some(strategy) => strategy,
+            None if self.shadow => DuplicateModuleStrategy::PreferSource,
+            None => DuplicateModuleStrategy::Error,
+        }
+    }
+
+    /// Whether the strategy was chosen explicitly, in which case each resolution is reported
+    pub fn report_duplicate_modules(&self) -> bool {
+        self.duplicate_modules.is_some()
+    }
+
+    pub fn audit_duplicate_modules(&self) -> bool {
+        self.audit_duplicate_modules
+    }
+
     pub fn bytecode_version(&self) -> Option<u32> {
         self.bytecode_version
     }
@@ -782,16 +1329,60 @@ impl Flags {
     }
 }
 
+/// How a module defined in both the sources and the dependencies is resolved
+#[derive(Clone, Copy, Debug, Eq, PartialEq, clap::ValueEnum)]
+pub enum DuplicateModuleStrategy {
+    /// Report the duplicate definition as an error
+    Error,
+    /// Keep the source module, e.g. to test local changes to a framework
+    PreferSource,
+    /// Keep the dependency module
+    PreferDep,
+    /// Keep the module whose package has the higher `PackageConfig::version`. Ties keep the source
+    /// module, and a package without a version is an error.
+    PreferHigherVersion,
+}
+
+impl DuplicateModuleStrategy {
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::PreferSource => "prefer-source",
+            Self::PreferDep => "prefer-dep",
+            Self::PreferHigherVersion => "prefer-higher-version",
+        }
+    }
+}
+
 //**************************************************************************************************
 // Package Level Config
 //**************************************************************************************************
 
+/// A package version, as major, minor, and patch numbers
+pub type PackageVersion = (u64, u64, u64);
+
 #[derive(PartialEq, Eq, Clone, Debug)]
 pub struct PackageConfig {
     pub is_dependency: bool,
     pub warning_filter: WarningFilters,
     pub flavor: Flavor,
     pub edition: Edition,
+    /// Metadata used to resolve duplicate modules with `DuplicateModuleStrategy::PreferHigherVersion`
+    pub version: Option<PackageVersion>,
+    /// Limits of the compiled modules of the package, checked during compilation
+    pub limits: PackageLimits,
+}
+
+/// Limits enforced when a package is published, checked on its compiled modules so that they are
+/// reported at the offending definitions. A limit is not checked if it is not set.
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
+pub struct PackageLimits {
+    pub max_function_parameters: Option<usize>,
+    pub max_struct_fields: Option<usize>,
+    /// The maximum number of nodes of a type in a signature or field, e.g. `vector<u64>` has 2
+    pub max_type_nodes: Option<usize>,
+    /// The maximum total size in bytes of the serialized modules of the package
+    pub max_package_size: Option<usize>,
 }
 
 impl Default for PackageConfig {
@@ -801,6 +1392,8 @@ impl Default for PackageConfig {
             warning_filter: WarningFilters::new_for_source(),
             flavor: Flavor::default(),
             edition: Edition::default(),
+            version: None,
+            limits: PackageLimits::default(),
         }
     }
 }
@@ -869,6 +1462,8 @@ pub mod known_attributes {
         TestOnly,
         // Is a test that will be run
         Test,
+        // Is a test that will be run with randomly generated arguments
+        RandTest,
         // This test is expected to fail
         ExpectedFailure,
     }
@@ -913,6 +1508,7 @@ pub mod known_attributes {
         pub fn resolve(attribute_str: impl AsRef<str>) -> Option<Self> {
             Some(match attribute_str.as_ref() {
                 TestingAttribute::TEST => Self::Testing(TestingAttribute::Test),
+                TestingAttribute::RAND_TEST => Self::Testing(TestingAttribute::RandTest),
                 TestingAttribute::TEST_ONLY => Self::Testing(TestingAttribute::TestOnly),
                 TestingAttribute::EXPECTED_FAILURE => {
                     Self::Testing(TestingAttribute::ExpectedFailure)
@@ -952,6 +1548,7 @@ pub mod known_attributes {
 
     impl TestingAttribute {
         pub const TEST: &'static str = "test";
+        pub const RAND_TEST: &'static str = "random_test";
         pub const EXPECTED_FAILURE: &'static str = "expected_failure";
         pub const TEST_ONLY: &'static str = "test_only";
         pub const ABORT_CODE_NAME: &'static str = "abort_code";
@@ -965,6 +1562,7 @@ pub mod known_attributes {
         pub const fn name(&self) -> &str {
             match self {
                 Self::Test => Self::TEST,
+                Self::RandTest => Self::RAND_TEST,
                 Self::TestOnly => Self::TEST_ONLY,
                 Self::ExpectedFailure => Self::EXPECTED_FAILURE,
             }
@@ -988,7 +1586,7 @@ pub mod known_attributes {
                 Lazy::new(|| BTreeSet::from([AttributePosition::Function]));
             match self {
                 TestingAttribute::TestOnly => &TEST_ONLY_POSITIONS,
-                TestingAttribute::Test => &TEST_POSITIONS,
+                TestingAttribute::Test | TestingAttribute::RandTest => &TEST_POSITIONS,
                 TestingAttribute::ExpectedFailure => &EXPECTED_FAILURE_POSITIONS,
             }
         }
@@ -0,0 +1,215 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Configuring the packages of a compilation programmatically, without a manifest. The settings
+//! of a package are checked when it is built, reporting the first invalid one.
+
+use crate::{
+    diagnostics::{codes::WarningFilter, WarningFilters},
+    editions::{Edition, Flavor},
+    shared::{NumericalAddress, PackageConfig, PackageLimits, PackagePaths, PackageVersion},
+};
+use move_symbol_pool::Symbol;
+use std::{collections::BTreeMap, fmt};
+
+/// An invalid package configuration
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PackageConfigError {
+    /// Two packages of a compilation have the same name
+    DuplicatePackage(Symbol),
+    /// A path is given more than once for the same package
+    DuplicatePath {
+        package: Option<Symbol>,
+        path: Symbol,
+    },
+    /// A named address is given two different values for the same package
+    ConflictingNamedAddress {
+        package: Option<Symbol>,
+        name: Symbol,
+        previous: NumericalAddress,
+        value: NumericalAddress,
+    },
+    /// Package settings are given for a package without a name, which cannot have a config
+    UnnamedPackageConfig,
+}
+
+/// Builds the `PackagePaths` of a package and its `PackageConfig`
+#[derive(Clone, Debug)]
+pub struct PackageBuilder {
+    name: Option<Symbol>,
+    config: PackageConfig,
+    configured: bool,
+    paths: Vec<Symbol>,
+    named_address_map: BTreeMap<Symbol, NumericalAddress>,
+    error: Option<PackageConfigError>,
+}
+
+impl PackageBuilder {
+    /// A source package named `name`, with the default config
+    pub fn new(name: impl Into<Symbol>) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..Self::unnamed()
+        }
+    }
+
+    /// A package without a name, always compiled with the default config
+    pub fn unnamed() -> Self {
+        Self {
+            name: None,
+            config: PackageConfig::default(),
+            configured: false,
+            paths: vec![],
+            named_address_map: BTreeMap::new(),
+            error: None,
+        }
+    }
+
+    /// Marks the package as a dependency, whose warnings are filtered
+    pub fn dependency(mut self) -> Self {
+        let filters = self.config.warning_filter.clone();
+        self.config.is_dependency = true;
+        self.config.warning_filter = WarningFilters::new_for_dependency();
+        self.config.warning_filter.union(&filters);
+        self.configure()
+    }
+
+    pub fn edition(mut self, edition: Edition) -> Self {
+        self.config.edition = edition;
+        self.configure()
+    }
+
+    pub fn flavor(mut self, flavor: Flavor) -> Self {
+        self.config.flavor = flavor;
+        self.configure()
+    }
+
+    /// Filters the warnings of the package matching `filter`
+    pub fn warning_filter(mut self, filter: WarningFilter) -> Self {
+        self.config.warning_filter.add(filter);
+        self.configure()
+    }
+
+    pub fn version(mut self, version: PackageVersion) -> Self {
+        self.config.version = Some(version);
+        self.configure()
+    }
+
+    pub fn limits(mut self, limits: PackageLimits) -> Self {
+        self.config.limits = limits;
+        self.configure()
+    }
+
+    pub fn path(mut self, path: impl Into<Symbol>) -> Self {
+        let path = path.into();
+        if self.paths.contains(&path) {
+            self.fail(PackageConfigError::DuplicatePath {
+                package: self.name,
+                path,
+            });
+        } else {
+            self.paths.push(path);
+        }
+        self
+    }
+
+    pub fn paths(self, paths: impl IntoIterator<Item = impl Into<Symbol>>) -> Self {
+        paths
+            .into_iter()
+            .fold(self, |builder, path| builder.path(path))
+    }
+
+    /// Assigns `value` to the named address `name`. Assigning the same value again is allowed
+    pub fn named_address(mut self, name: impl Into<Symbol>, value: NumericalAddress) -> Self {
+        let name = name.into();
+        match self.named_address_map.get(&name) {
+            Some(previous) if *previous != value => {
+                let error = PackageConfigError::ConflictingNamedAddress {
+                    package: self.name,
+                    name,
+                    previous: *previous,
+                    value,
+                };
+                self.fail(error);
+            }
+            _ => {
+                self.named_address_map.insert(name, value);
+            }
+        }
+        self
+    }
+
+    pub fn named_addresses(
+        self,
+        named_addresses: impl IntoIterator<Item = (impl Into<Symbol>, NumericalAddress)>,
+    ) -> Self {
+        named_addresses
+            .into_iter()
+            .fold(self, |builder, (name, value)| {
+                builder.named_address(name, value)
+            })
+    }
+
+    /// The package, or the first invalid setting given to the builder
+    pub fn build(self) -> Result<PackagePaths, PackageConfigError> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+        let name = match self.name {
+            Some(name) => Some((name, self.config)),
+            None if self.configured => return Err(PackageConfigError::UnnamedPackageConfig),
+            None => None,
+        };
+        Ok(PackagePaths {
+            name,
+            paths: self.paths,
+            named_address_map: self.named_address_map,
+        })
+    }
+
+    fn configure(mut self) -> Self {
+        self.configured = true;
+        self
+    }
+
+    fn fail(&mut self, error: PackageConfigError) {
+        self.error.get_or_insert(error);
+    }
+}
+
+//**************************************************************************************************
+// traits
+//**************************************************************************************************
+
+impl fmt::Display for PackageConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn in_package(package: &Option<Symbol>) -> String {
+            match package {
+                Some(package) => format!(" in package '{package}'"),
+                None => String::new(),
+            }
+        }
+        match self {
+            Self::DuplicatePackage(name) => write!(f, "Duplicate package entry for '{name}'"),
+            Self::DuplicatePath { package, path } => {
+                write!(f, "Duplicate path '{path}'{}", in_package(package))
+            }
+            Self::ConflictingNamedAddress {
+                package,
+                name,
+                previous,
+                value,
+            } => write!(
+                f,
+                "Conflicting values for named address '{name}'{}: '{previous}' and '{value}'",
+                in_package(package)
+            ),
+            Self::UnnamedPackageConfig => write!(
+                f,
+                "A package without a name cannot have a config. Name the package to configure it"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PackageConfigError {}
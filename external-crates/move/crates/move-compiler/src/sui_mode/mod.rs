@@ -5,6 +5,7 @@ use move_symbol_pool::Symbol;
 
 use crate::diagnostics::codes::{custom, DiagnosticInfo, Severity};
 
+pub mod entry_points;
 pub mod id_leak;
 pub mod typing;
 
@@ -8,11 +8,11 @@ use crate::{
     diag,
     diagnostics::{Diagnostic, WarningFilters},
     editions::Flavor,
-    expansion::ast::{AbilitySet, AttributeName_, Fields, ModuleIdent, Visibility},
+    expansion::ast::{AttributeName_, Fields, ModuleIdent, Visibility},
     naming::ast::{
-        self as N, BuiltinTypeName_, FunctionSignature, StructFields, Type, TypeName_, Type_, Var,
+        self as N, BuiltinTypeName_, FunctionSignature, StructFields, Type, TypeName_, Type_,
     },
-    parser::ast::{Ability_, FunctionName, Mutability, StructName},
+    parser::ast::{Ability_, FunctionName, StructName},
     shared::{
         known_attributes::{KnownAttribute, TestingAttribute},
         program_info::TypingProgramInfo,
@@ -21,7 +21,7 @@ use crate::{
     sui_mode::*,
     typing::{
         ast::{self as T, ModuleCall},
-        core::{ability_not_satisfied_tips, error_format, error_format_, Subst},
+        core::{error_format, error_format_, Subst},
         visitor::{TypingVisitorConstructor, TypingVisitorContext},
     },
 };
@@ -276,7 +276,7 @@ fn function(context: &mut Context, name: FunctionName, fdef: &mut T::Function) {
         matches!(
             attr_,
             AttributeName_::Known(KnownAttribute::Testing(
-                TestingAttribute::Test | TestingAttribute::TestOnly
+                TestingAttribute::Test | TestingAttribute::RandTest | TestingAttribute::TestOnly
             ))
         )
     }) {
@@ -285,9 +285,6 @@ fn function(context: &mut Context, name: FunctionName, fdef: &mut T::Function) {
     if name.0.value == INIT_FUNCTION_NAME {
         init_visibility(context, name, *visibility, *entry);
     }
-    if let Some(entry_loc) = entry {
-        entry_signature(context, *entry_loc, name, signature);
-    }
     if let sp!(_, T::FunctionBody_::Defined(seq)) = body {
         context.visit_seq(seq)
     }
@@ -567,31 +564,10 @@ fn invalid_otw_field_loc(fields: &Fields<Type>) -> Option<InvalidOTW> {
 }
 
 //**************************************************************************************************
-// entry types
+// TxContext
 //**************************************************************************************************
 
-fn entry_signature(
-    context: &mut Context,
-    entry_loc: Loc,
-    name: FunctionName,
-    signature: &FunctionSignature,
-) {
-    let FunctionSignature {
-        type_parameters: _,
-        parameters,
-        return_type,
-    } = signature;
-    let all_non_ctx_parameters = match parameters.last() {
-        Some((_, _, last_param_ty)) if tx_context_kind(last_param_ty) != TxContextKind::None => {
-            &parameters[0..parameters.len() - 1]
-        }
-        _ => parameters,
-    };
-    entry_param(context, entry_loc, name, all_non_ctx_parameters);
-    entry_return(context, entry_loc, name, return_type);
-}
-
-fn tx_context_kind(sp!(_, last_param_ty_): &Type) -> TxContextKind {
+pub(crate) fn tx_context_kind(sp!(_, last_param_ty_): &Type) -> TxContextKind {
     // Already an error, so assume a valid, mutable TxContext
     if matches!(last_param_ty_, Type_::UnresolvedError | Type_::Var(_)) {
         return TxContextKind::Mutable;
@@ -627,263 +603,6 @@ pub enum TxContextKind {
     Immutable,
 }
 
-fn entry_param(
-    context: &mut Context,
-    entry_loc: Loc,
-    name: FunctionName,
-    parameters: &[(Mutability, Var, Type)],
-) {
-    for (_, var, ty) in parameters {
-        entry_param_ty(context, entry_loc, name, var, ty);
-    }
-}
-
-/// A valid entry param type is
-/// - A primitive (including strings, ID, and object)
-/// - A vector of primitives (including nested vectors)
-///
-/// - An object
-/// - A reference to an object
-/// - A vector of objects
-fn entry_param_ty(
-    context: &mut Context,
-    entry_loc: Loc,
-    name: FunctionName,
-    param: &Var,
-    param_ty: &Type,
-) {
-    let is_mut_clock = is_mut_clock(param_ty);
-    // TODO better error message for cases such as `MyObject<InnerTypeWithoutStore>`
-    // which should give a contextual error about `MyObject` having `key`, but the instantiation
-    // `MyObject<InnerTypeWithoutStore>` not having `key` due to `InnerTypeWithoutStore` not having
-    // `store`
-    let is_valid = is_entry_primitive_ty(param_ty)
-        || is_entry_object_ty(param_ty)
-        || is_entry_receiving_ty(param_ty);
-    if is_mut_clock || !is_valid {
-        let pmsg = format!(
-            "Invalid 'entry' parameter type for parameter '{}'",
-            param.value.name
-        );
-        let tmsg = if is_mut_clock {
-            format!(
-                "{a}::{m}::{n} must be passed by immutable reference, e.g. '&{a}::{m}::{n}'",
-                a = SUI_ADDR_NAME,
-                m = CLOCK_MODULE_NAME,
-                n = CLOCK_TYPE_NAME,
-            )
-        } else {
-            "'entry' parameters must be primitives (by-value), vectors of primitives, objects \
-            (by-reference or by-value), vectors of objects, or 'Receiving' arguments (by-reference or by-value)"
-                .to_owned()
-        };
-        let emsg = format!("'{name}' was declared 'entry' here");
-        context.env.add_diag(diag!(
-            ENTRY_FUN_SIGNATURE_DIAG,
-            (param.loc, pmsg),
-            (param_ty.loc, tmsg),
-            (entry_loc, emsg)
-        ));
-    }
-}
-
-fn is_mut_clock(param_ty: &Type) -> bool {
-    match &param_ty.value {
-        Type_::Ref(/* mut */ false, _) => false,
-        Type_::Ref(/* mut */ true, t) => is_mut_clock(t),
-        Type_::Apply(_, sp!(_, n_), _) => n_.is(SUI_ADDR_NAME, CLOCK_MODULE_NAME, CLOCK_TYPE_NAME),
-        Type_::Unit
-        | Type_::Param(_)
-        | Type_::Var(_)
-        | Type_::Anything
-        | Type_::UnresolvedError => false,
-    }
-}
-
-fn is_entry_receiving_ty(param_ty: &Type) -> bool {
-    match &param_ty.value {
-        Type_::Ref(_, t) => is_entry_receiving_ty(t),
-        Type_::Apply(_, sp!(_, n), targs)
-            if n.is(SUI_ADDR_NAME, TRANSFER_MODULE_NAME, RECEIVING_TYPE_NAME) =>
-        {
-            debug_assert!(targs.len() == 1);
-            // Don't care about the type parameter, just that it's a receiving type -- since it has
-            // a `key` requirement on the type parameter it must be an object or type checking will
-            // fail.
-            true
-        }
-        _ => false,
-    }
-}
-
-fn is_entry_primitive_ty(param_ty: &Type) -> bool {
-    use BuiltinTypeName_ as B;
-    use TypeName_ as N;
-
-    match &param_ty.value {
-        // A bit of a hack since no primitive has key
-        Type_::Param(tp) => !tp.abilities.has_ability_(Ability_::Key),
-        // nonsensical, but no error needed
-        Type_::Apply(_, sp!(_, N::Multiple(_)), ts) => ts.iter().all(is_entry_primitive_ty),
-        // Simple recursive cases
-        Type_::Ref(_, t) => is_entry_primitive_ty(t),
-        Type_::Apply(_, sp!(_, N::Builtin(sp!(_, B::Vector))), targs) => {
-            debug_assert!(targs.len() == 1);
-            is_entry_primitive_ty(&targs[0])
-        }
-
-        // custom "primitives"
-        Type_::Apply(_, sp!(_, n), targs)
-            if n.is(STD_ADDR_NAME, ASCII_MODULE_NAME, ASCII_TYPE_NAME)
-                || n.is(STD_ADDR_NAME, UTF_MODULE_NAME, UTF_TYPE_NAME)
-                || n.is(SUI_ADDR_NAME, OBJECT_MODULE_NAME, ID_TYPE_NAME) =>
-        {
-            debug_assert!(targs.is_empty());
-            true
-        }
-        Type_::Apply(_, sp!(_, n), targs)
-            if n.is(STD_ADDR_NAME, OPTION_MODULE_NAME, OPTION_TYPE_NAME) =>
-        {
-            debug_assert!(targs.len() == 1);
-            is_entry_primitive_ty(&targs[0])
-        }
-
-        // primitives
-        Type_::Apply(_, sp!(_, N::Builtin(_)), targs) => {
-            debug_assert!(targs.is_empty());
-            true
-        }
-
-        // Non primitive
-        Type_::Apply(_, sp!(_, N::ModuleType(_, _)), _) => false,
-        Type_::Unit => false,
-
-        // Error case nothing to do
-        Type_::UnresolvedError | Type_::Anything | Type_::Var(_) => true,
-    }
-}
-
-fn is_entry_object_ty(param_ty: &Type) -> bool {
-    use BuiltinTypeName_ as B;
-    use TypeName_ as N;
-    match &param_ty.value {
-        Type_::Ref(_, t) => is_entry_object_ty_inner(t),
-        Type_::Apply(_, sp!(_, N::Builtin(sp!(_, B::Vector))), targs) => {
-            debug_assert!(targs.len() == 1);
-            is_entry_object_ty_inner(&targs[0])
-        }
-        _ => is_entry_object_ty_inner(param_ty),
-    }
-}
-
-fn is_entry_object_ty_inner(param_ty: &Type) -> bool {
-    use TypeName_ as N;
-    match &param_ty.value {
-        Type_::Param(tp) => tp.abilities.has_ability_(Ability_::Key),
-        // nonsensical, but no error needed
-        Type_::Apply(_, sp!(_, N::Multiple(_)), ts) => ts.iter().all(is_entry_object_ty_inner),
-        // Simple recursive cases, shouldn't be hit but no need to error
-        Type_::Ref(_, t) => is_entry_object_ty_inner(t),
-
-        // Objects
-        Type_::Apply(Some(abilities), _, _) => abilities.has_ability_(Ability_::Key),
-
-        // Error case nothing to do
-        Type_::UnresolvedError | Type_::Anything | Type_::Var(_) | Type_::Unit => true,
-        // Unreachable cases
-        Type_::Apply(None, _, _) => unreachable!("ICE abilities should have been expanded"),
-    }
-}
-
-fn entry_return(
-    context: &mut Context,
-    entry_loc: Loc,
-    name: FunctionName,
-    return_type @ sp!(tloc, return_type_): &Type,
-) {
-    match return_type_ {
-        // unit is fine, nothing to do
-        Type_::Unit => (),
-        Type_::Ref(_, _) => {
-            let fmsg = format!("Invalid return type for entry function '{}'", name);
-            let tmsg = "Expected a non-reference type";
-            context.env.add_diag(diag!(
-                ENTRY_FUN_SIGNATURE_DIAG,
-                (entry_loc, fmsg),
-                (*tloc, tmsg)
-            ))
-        }
-        Type_::Param(tp) => {
-            if !tp.abilities.has_ability_(Ability_::Drop) {
-                let declared_loc_opt = Some(tp.user_specified_name.loc);
-                let declared_abilities = tp.abilities.clone();
-                invalid_entry_return_ty(
-                    context,
-                    entry_loc,
-                    name,
-                    return_type,
-                    declared_loc_opt,
-                    &declared_abilities,
-                    std::iter::empty(),
-                )
-            }
-        }
-        Type_::Apply(Some(abilities), sp!(_, tn_), ty_args) => {
-            if !abilities.has_ability_(Ability_::Drop) {
-                let (declared_loc_opt, declared_abilities) = match tn_ {
-                    TypeName_::Multiple(_) => (None, AbilitySet::collection(*tloc)),
-                    TypeName_::ModuleType(m, n) => (
-                        Some(context.info.struct_declared_loc(m, n)),
-                        context.info.struct_declared_abilities(m, n).clone(),
-                    ),
-                    TypeName_::Builtin(b) => (None, b.value.declared_abilities(b.loc)),
-                };
-                invalid_entry_return_ty(
-                    context,
-                    entry_loc,
-                    name,
-                    return_type,
-                    declared_loc_opt,
-                    &declared_abilities,
-                    ty_args.iter().map(|ty_arg| (ty_arg, get_abilities(ty_arg))),
-                )
-            }
-        }
-        // Error case nothing to do
-        Type_::UnresolvedError | Type_::Anything | Type_::Var(_) => (),
-        // Unreachable cases
-        Type_::Apply(None, _, _) => unreachable!("ICE abilities should have been expanded"),
-    }
-}
-
-fn get_abilities(sp!(loc, ty_): &Type) -> AbilitySet {
-    ty_.abilities(*loc)
-        .expect("ICE abilities should have been expanded")
-}
-
-fn invalid_entry_return_ty<'a>(
-    context: &mut Context,
-    entry_loc: Loc,
-    name: FunctionName,
-    ty: &Type,
-    declared_loc_opt: Option<Loc>,
-    declared_abilities: &AbilitySet,
-    ty_args: impl IntoIterator<Item = (&'a Type, AbilitySet)>,
-) {
-    let fmsg = format!("Invalid return type for entry function '{}'", name);
-    let mut diag = diag!(ENTRY_FUN_SIGNATURE_DIAG, (entry_loc, fmsg));
-    ability_not_satisfied_tips(
-        &Subst::empty(),
-        &mut diag,
-        Ability_::Drop,
-        ty,
-        declared_loc_opt,
-        declared_abilities,
-        ty_args,
-    );
-    context.env.add_diag(diag)
-}
-
 //**************************************************************************************************
 // Expr
 //**************************************************************************************************
@@ -9,7 +9,7 @@ use crate::{
     shared::{CompilationEnv, NumericalAddress},
 };
 use move_core_types::account_address::AccountAddress as MoveAddress;
-use move_ir_types::ast as IR;
+use move_ir_types::{ast as IR, location::*};
 use move_symbol_pool::Symbol;
 use std::{
     clone::Clone,
@@ -26,6 +26,14 @@ pub struct Context<'a> {
     seen_structs: BTreeSet<(ModuleIdent, StructName)>,
     seen_functions: BTreeSet<(ModuleIdent, FunctionName)>,
     spec_info: BTreeMap<SpecId, (IR::NopLabel, BTreeMap<Var, H::SingleType>)>,
+    coverage_counters: Option<&'a mut CoverageCounters>,
+}
+
+/// Allocates the coverage counters incremented at the start of every basic block when compiling
+/// with `Flags::coverage`. Counters are numbered sequentially across the compilation.
+pub struct CoverageCounters {
+    increment_function: (ModuleIdent, FunctionName),
+    next_counter: u64,
 }
 
 impl<'a> Context<'a> {
@@ -41,9 +49,14 @@ impl<'a> Context<'a> {
             seen_structs: BTreeSet::new(),
             seen_functions: BTreeSet::new(),
             spec_info: BTreeMap::new(),
+            coverage_counters: None,
         }
     }
 
+    pub fn set_coverage_counters(&mut self, coverage_counters: Option<&'a mut CoverageCounters>) {
+        self.coverage_counters = coverage_counters;
+    }
+
     #[allow(unused)]
     pub fn current_package(&self) -> Option<Symbol> {
         self.current_package
@@ -57,6 +70,19 @@ impl<'a> Context<'a> {
         self.current_module.map(|cur| cur == m).unwrap_or(false)
     }
 
+    /// The code incrementing a fresh coverage counter, if coverage is enabled
+    pub fn coverage_counter_increment(&mut self, loc: Loc) -> Option<IR::BytecodeBlock> {
+        let counters = self.coverage_counters.as_mut()?;
+        let counter = counters.next_counter;
+        counters.next_counter += 1;
+        let (mident, fname) = counters.increment_function;
+        let (m, n) = self.qualified_function_name(&mident, fname);
+        Some(vec![
+            sp(loc, IR::Bytecode_::LdU64(counter)),
+            sp(loc, IR::Bytecode_::Call(m, n, vec![])),
+        ])
+    }
+
     pub fn finish_function(
         &mut self,
     ) -> BTreeMap<SpecId, (IR::NopLabel, BTreeMap<Var, H::SingleType>)> {
@@ -338,3 +364,12 @@ impl<'a> Context<'a> {
         label
     }
 }
+
+impl CoverageCounters {
+    pub fn new(increment_function: (ModuleIdent, FunctionName)) -> Self {
+        Self {
+            increment_function,
+            next_counter: 0,
+        }
+    }
+}
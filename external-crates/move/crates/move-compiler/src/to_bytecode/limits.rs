@@ -0,0 +1,151 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    compiled_unit::{AnnotatedCompiledModule, AnnotatedCompiledUnit, NamedCompiledModule},
+    diag,
+    diagnostics::Diagnostic,
+    shared::{CompilationEnv, PackageLimits},
+};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{
+        FunctionDefinitionIndex, SignatureToken, StructDefinitionIndex, StructFieldInformation,
+        TableIndex,
+    },
+};
+use move_ir_types::location::Loc;
+use move_symbol_pool::Symbol;
+use std::collections::BTreeMap;
+
+/// Checks the compiled modules against the `PackageLimits` of their package, reporting the
+/// definitions exceeding them. Scripts are not published, and are not checked.
+pub fn check(env: &mut CompilationEnv, units: &[AnnotatedCompiledUnit]) {
+    let mut packages: BTreeMap<Option<Symbol>, Vec<&AnnotatedCompiledModule>> = BTreeMap::new();
+    for unit in units {
+        if let AnnotatedCompiledUnit::Module(m) = unit {
+            packages
+                .entry(m.named_module.package_name)
+                .or_default()
+                .push(m)
+        }
+    }
+    for (package_name, modules) in packages {
+        let limits = env.package_config(package_name).limits;
+        if limits == PackageLimits::default() {
+            continue;
+        }
+        for m in &modules {
+            module(env, &limits, m)
+        }
+        if let Some(max) = limits.max_package_size {
+            package_size(env, max, &modules)
+        }
+    }
+}
+
+fn module(env: &mut CompilationEnv, limits: &PackageLimits, m: &AnnotatedCompiledModule) {
+    let NamedCompiledModule {
+        module, source_map, ..
+    } = &m.named_module;
+    for (idx, sdef) in module.struct_defs().iter().enumerate() {
+        let StructFieldInformation::Declared(fields) = &sdef.field_information else {
+            continue;
+        };
+        let struct_map = source_map
+            .get_struct_source_map(StructDefinitionIndex(idx as TableIndex))
+            .ok();
+        let struct_loc = struct_map.map_or(m.loc, |smap| smap.definition_location);
+        if let Some(max) = limits.max_struct_fields {
+            if fields.len() > max {
+                let msg = format!("The struct has {} fields", fields.len());
+                env.add_diag(limit_exceeded(struct_loc, msg, "fields in a struct", max))
+            }
+        }
+        for (field_idx, field) in fields.iter().enumerate() {
+            let field_loc = struct_map
+                .and_then(|smap| smap.get_field_location(field_idx as TableIndex))
+                .unwrap_or(struct_loc);
+            type_nodes(env, limits, field_loc, &field.signature.0)
+        }
+    }
+    for (idx, fdef) in module.function_defs().iter().enumerate() {
+        let function_loc = source_map
+            .get_function_source_map(FunctionDefinitionIndex(idx as TableIndex))
+            .map_or(m.loc, |fmap| fmap.definition_location);
+        let handle = module.function_handle_at(fdef.function);
+        let parameters = &module.signature_at(handle.parameters).0;
+        if let Some(max) = limits.max_function_parameters {
+            if parameters.len() > max {
+                let msg = format!("The function has {} parameters", parameters.len());
+                env.add_diag(limit_exceeded(
+                    function_loc,
+                    msg,
+                    "function parameters",
+                    max,
+                ))
+            }
+        }
+        let locals = fdef.code.as_ref().map(|code| code.locals);
+        let tokens = [Some(handle.parameters), Some(handle.return_), locals]
+            .into_iter()
+            .flatten()
+            .flat_map(|idx| &module.signature_at(idx).0);
+        // reported once per function, for its largest type
+        if let Some(largest) = tokens.max_by_key(|token| type_nodes_count(token)) {
+            type_nodes(env, limits, function_loc, largest)
+        }
+    }
+}
+
+fn type_nodes_count(ty: &SignatureToken) -> usize {
+    ty.preorder_traversal().count()
+}
+
+fn type_nodes(env: &mut CompilationEnv, limits: &PackageLimits, loc: Loc, ty: &SignatureToken) {
+    let Some(max) = limits.max_type_nodes else {
+        return;
+    };
+    let nodes = type_nodes_count(ty);
+    if nodes > max {
+        let msg = format!("A type used here has {} nodes", nodes);
+        env.add_diag(limit_exceeded(loc, msg, "nodes in a type", max))
+    }
+}
+
+fn package_size(env: &mut CompilationEnv, max: usize, modules: &[&AnnotatedCompiledModule]) {
+    let bytecode_version = env.flags().bytecode_version();
+    let mut sizes = vec![];
+    for m in modules {
+        let mut bytes = vec![];
+        // a module not serializable in the version is reported when it is written
+        if m.named_module
+            .module
+            .serialize_for_version(bytecode_version, &mut bytes)
+            .is_err()
+        {
+            return;
+        }
+        sizes.push((bytes.len(), m.loc))
+    }
+    let size: usize = sizes.iter().map(|(size, _)| size).sum();
+    if size <= max {
+        return;
+    }
+    // reported at the largest module, the first candidate for a split
+    let (largest, loc) = sizes.into_iter().max_by_key(|(size, _)| *size).unwrap();
+    let msg = format!(
+        "The modules of the package have {} bytes, {} of which in this module",
+        size, largest
+    );
+    env.add_diag(limit_exceeded(loc, msg, "bytes in a package", max))
+}
+
+fn limit_exceeded(loc: Loc, msg: String, limit: &str, max: usize) -> Diagnostic {
+    let mut diag = diag!(BytecodeGeneration::LimitExceeded, (loc, msg));
+    diag.add_note(format!(
+        "The package is limited to {} {}, and could not be published",
+        max, limit
+    ));
+    diag
+}
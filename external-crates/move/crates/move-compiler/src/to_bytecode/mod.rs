@@ -5,5 +5,6 @@
 mod canonicalize_handles;
 #[macro_use]
 mod context;
+mod limits;
 mod optimize;
 pub mod translate;
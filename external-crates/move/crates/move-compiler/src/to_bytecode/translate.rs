@@ -2,7 +2,7 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use super::{canonicalize_handles, context::*, optimize};
+use super::{canonicalize_handles, context::*, limits, optimize};
 use crate::{
     cfgir::{ast as G, translate::move_value_from_value_},
     compiled_unit::*,
@@ -20,12 +20,18 @@ use crate::{
         Ability, Ability_, BinOp, BinOp_, ConstantName, Field, FunctionName, ModuleName,
         StructName, UnaryOp, UnaryOp_,
     },
-    shared::{unique_map::UniqueMap, *},
+    shared::{abort_messages::AbortMessage, unique_map::UniqueMap, *},
+    unit_test::{
+        coverage::COVERAGE_COUNTER_FUNCTION_NAME,
+        filter_test_members::{
+            STDLIB_ADDRESS_NAME, UNIT_TEST_MODULE_NAME, UNIT_TEST_POISON_FUN_NAME,
+        },
+    },
     FullyCompiledProgram,
 };
 use move_binary_format::file_format as F;
 use move_bytecode_source_map::source_map::SourceMap;
-use move_core_types::account_address::AccountAddress as MoveAddress;
+use move_core_types::{account_address::AccountAddress as MoveAddress, value::MoveValue};
 use move_ir_types::{ast as IR, location::*};
 use move_symbol_pool::Symbol;
 use std::{
@@ -121,6 +127,17 @@ pub fn program(
     let mut units = vec![];
 
     let (orderings, sdecls, fdecls) = extract_decls(compilation_env, pre_compiled_lib, &prog);
+    let mut coverage_counters = if compilation_env.flags().coverage() {
+        fdecls
+            .keys()
+            .find(|(m, f)| {
+                m.value.is(STDLIB_ADDRESS_NAME, UNIT_TEST_MODULE_NAME)
+                    && f.0.value.as_str() == COVERAGE_COUNTER_FUNCTION_NAME
+            })
+            .map(|increment_function| CoverageCounters::new(*increment_function))
+    } else {
+        None
+    };
     let G::Program {
         modules: gmodules,
         scripts: gscripts,
@@ -132,7 +149,15 @@ pub fn program(
         .collect::<Vec<_>>();
     source_modules.sort_by_key(|(_, mdef)| mdef.dependency_order);
     for (m, mdef) in source_modules {
-        if let Some(unit) = module(compilation_env, m, mdef, &orderings, &sdecls, &fdecls) {
+        if let Some(unit) = module(
+            compilation_env,
+            coverage_counters.as_mut(),
+            m,
+            mdef,
+            &orderings,
+            &sdecls,
+            &fdecls,
+        ) {
             units.push(unit)
         }
     }
@@ -160,11 +185,13 @@ pub fn program(
             units.push(unit)
         }
     }
+    limits::check(compilation_env, &units);
     units
 }
 
 fn module(
     compilation_env: &mut CompilationEnv,
+    coverage_counters: Option<&mut CoverageCounters>,
     ident: ModuleIdent,
     mdef: G::ModuleDefinition,
     dependency_orderings: &HashMap<ModuleIdent, usize>,
@@ -177,6 +204,7 @@ fn module(
         (BTreeSet<(ModuleIdent, StructName)>, IR::FunctionSignature),
     >,
 ) -> Option<AnnotatedCompiledUnit> {
+    compilation_env.set_current_module(ident.loc);
     let G::ModuleDefinition {
         warning_filter: _warning_filter,
         package_name,
@@ -189,8 +217,12 @@ fn module(
         functions: gfunctions,
     } = mdef;
     let mut context = Context::new(compilation_env, package_name, Some(&ident));
+    context.set_coverage_counters(coverage_counters);
     let structs = struct_defs(&mut context, &ident, gstructs);
-    let constants = constants(&mut context, Some(&ident), gconstants);
+    let mut constants = constants(&mut context, Some(&ident), gconstants);
+    let abort_messages = context.env.take_abort_messages(&ident);
+    let abort_codes = context.env.take_abort_codes(&ident);
+    constants.extend(abort_messages.iter().map(abort_message_constant));
     let (collected_function_infos, functions) = functions(&mut context, Some(&ident), gfunctions);
 
     let friends = gfriends
@@ -260,6 +292,8 @@ fn module(
         module_name_loc: module_name.loc(),
         named_module: module,
         function_infos,
+        abort_messages,
+        abort_codes,
     }))
 }
 
@@ -281,6 +315,7 @@ fn script(
     >,
 ) -> Option<AnnotatedCompiledUnit> {
     let loc = name.loc();
+    compilation_env.set_current_module(loc);
     let mut context = Context::new(compilation_env, package_name, None);
 
     let constants = constants(&mut context, None, gconstants);
@@ -488,6 +523,7 @@ fn struct_def(
     s: StructName,
     sdef: H::StructDefinition,
 ) -> IR::StructDefinition {
+    context.env.set_current_member(s.0.loc);
     let H::StructDefinition {
         warning_filter: _warning_filter,
         index: _index,
@@ -563,6 +599,7 @@ fn constant(
     n: ConstantName,
     c: G::Constant,
 ) -> IR::Constant {
+    context.env.set_current_member(n.0.loc);
     let name = context.constant_definition_name(m, n);
     let signature = base_type(context, c.signature);
     let value = c.value.unwrap();
@@ -573,6 +610,21 @@ fn constant(
     }
 }
 
+// The constant holding a formatted abort message, so that it can be read from the module
+fn abort_message_constant(message: &AbortMessage) -> IR::Constant {
+    let bytes = message
+        .message
+        .as_bytes()
+        .iter()
+        .copied()
+        .map(MoveValue::U8);
+    IR::Constant {
+        name: IR::ConstantName(message.constant_name().into()),
+        signature: IR::Type::Vector(Box::new(IR::Type::U8)),
+        value: MoveValue::Vector(bytes.collect()),
+    }
+}
+
 //**************************************************************************************************
 // Functions
 //**************************************************************************************************
@@ -610,6 +662,7 @@ fn function(
     f: FunctionName,
     fdef: G::Function,
 ) -> ((IR::FunctionName, IR::Function), CollectedInfo) {
+    context.env.set_current_member(f.0.loc);
     let G::Function {
         warning_filter: _warning_filter,
         index: _index,
@@ -773,6 +826,18 @@ fn function_body(
         .collect();
     optimize::code(f, &loop_heads, &mut locals, &mut bytecode_blocks);
 
+    // Instrument the optimized blocks, so that counters match the blocks of the final bytecode
+    if f.0.value != UNIT_TEST_POISON_FUN_NAME {
+        for (_, code) in &mut bytecode_blocks {
+            let Some(first) = code.first() else {
+                continue;
+            };
+            if let Some(increment) = context.coverage_counter_increment(first.loc) {
+                code.splice(0..0, increment);
+            }
+        }
+    }
+
     (locals, bytecode_blocks)
 }
 
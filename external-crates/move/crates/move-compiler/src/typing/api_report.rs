@@ -0,0 +1,399 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A stable description of the public API of the source modules: their structs, and their
+//! functions callable from other modules or transactions. Two reports can be compared to classify
+//! the changes between them as breaking or compatible, following the rules of the default upgrade
+//! compatibility check, so that incompatible upgrades are found before publishing.
+//! Type parameters are described by position, so renaming them is not a change.
+
+use crate::{
+    expansion::ast::{AbilitySet, Address, Visibility},
+    naming::ast::{StructFields, TParam, TParamID, Type, TypeName_, Type_},
+    typing::ast as T,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiReport {
+    /// Keyed by `<address>::<module>`
+    pub modules: BTreeMap<String, ModuleApi>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleApi {
+    /// All structs, as their layout is part of the API of published values
+    pub structs: BTreeMap<String, StructApi>,
+    /// The public, friend, package, and entry functions
+    pub functions: BTreeMap<String, FunctionApi>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructApi {
+    pub abilities: BTreeSet<String>,
+    pub type_parameters: Vec<StructTypeParameterApi>,
+    /// The name and type of each field, in declaration order. `None` for native structs
+    pub fields: Option<Vec<(String, String)>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StructTypeParameterApi {
+    pub is_phantom: bool,
+    pub constraints: BTreeSet<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionApi {
+    pub visibility: ApiVisibility,
+    pub entry: bool,
+    /// The constraints of each type parameter
+    pub type_parameters: Vec<BTreeSet<String>>,
+    pub parameters: Vec<String>,
+    pub return_type: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiVisibility {
+    Private,
+    /// `public(friend)` and `public(package)`, which are both callable from some other modules
+    Friend,
+    Public,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    Compatible,
+    Breaking,
+}
+
+/// A change between two reports, on the module, struct, or function `item`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ApiChange {
+    pub kind: ChangeKind,
+    pub item: String,
+    pub description: String,
+}
+
+//**************************************************************************************************
+// Report
+//**************************************************************************************************
+
+impl ApiReport {
+    /// The API of the source modules of `prog`
+    pub fn new(prog: &T::Program) -> Self {
+        let modules = prog
+            .inner
+            .modules
+            .key_cloned_iter()
+            .filter(|(_, mdef)| mdef.is_source_module)
+            .map(|(sp!(_, mident), mdef)| {
+                let name = format!("{}::{}", address(&mident.address), mident.module);
+                (name, module(mdef))
+            })
+            .collect();
+        Self { modules }
+    }
+}
+
+fn module(mdef: &T::ModuleDefinition) -> ModuleApi {
+    let structs = mdef
+        .structs
+        .key_cloned_iter()
+        .map(|(name, sdef)| {
+            let tparams: Vec<_> = sdef.type_parameters.iter().map(|tp| &tp.param).collect();
+            let fields = match &sdef.fields {
+                StructFields::Native(_) => None,
+                StructFields::Defined(fields) => {
+                    let mut fields: Vec<_> = fields
+                        .key_cloned_iter()
+                        .map(|(f, (idx, ty))| (*idx, f.to_string(), type_(&tparams, ty)))
+                        .collect();
+                    fields.sort_by_key(|(idx, _, _)| *idx);
+                    Some(fields.into_iter().map(|(_, f, ty)| (f, ty)).collect())
+                }
+            };
+            let struct_api = StructApi {
+                abilities: abilities(&sdef.abilities),
+                type_parameters: sdef
+                    .type_parameters
+                    .iter()
+                    .map(|tp| StructTypeParameterApi {
+                        is_phantom: tp.is_phantom,
+                        constraints: abilities(&tp.param.abilities),
+                    })
+                    .collect(),
+                fields,
+            };
+            (name.to_string(), struct_api)
+        })
+        .collect();
+    let functions = mdef
+        .functions
+        .key_cloned_iter()
+        .filter_map(|(name, fdef)| Some((name.to_string(), function(fdef)?)))
+        .collect();
+    ModuleApi { structs, functions }
+}
+
+fn function(fdef: &T::Function) -> Option<FunctionApi> {
+    let visibility = match fdef.visibility {
+        Visibility::Public(_) => ApiVisibility::Public,
+        Visibility::Friend(_) | Visibility::Package(_) => ApiVisibility::Friend,
+        Visibility::Internal => ApiVisibility::Private,
+    };
+    let entry = fdef.entry.is_some();
+    if visibility == ApiVisibility::Private && !entry {
+        return None;
+    }
+    let signature = &fdef.signature;
+    let tparams: Vec<_> = signature.type_parameters.iter().collect();
+    let return_type = match &signature.return_type.value {
+        Type_::Unit => vec![],
+        Type_::Apply(_, sp!(_, TypeName_::Multiple(_)), tys) => {
+            tys.iter().map(|ty| type_(&tparams, ty)).collect()
+        }
+        _ => vec![type_(&tparams, &signature.return_type)],
+    };
+    Some(FunctionApi {
+        visibility,
+        entry,
+        type_parameters: signature
+            .type_parameters
+            .iter()
+            .map(|tp| abilities(&tp.abilities))
+            .collect(),
+        parameters: signature
+            .parameters
+            .iter()
+            .map(|(_, _, ty)| type_(&tparams, ty))
+            .collect(),
+        return_type,
+    })
+}
+
+fn abilities(abilities: &AbilitySet) -> BTreeSet<String> {
+    abilities.iter().map(|a| a.to_string()).collect()
+}
+
+fn address(addr: &Address) -> String {
+    addr.into_addr_bytes().into_inner().to_hex_literal()
+}
+
+/// The type with numerical addresses, and type parameters named by position
+fn type_(tparams: &[&TParam], sp!(_, ty_): &Type) -> String {
+    match ty_ {
+        Type_::Unit => "()".to_string(),
+        Type_::Ref(mut_, inner) => {
+            let prefix = if *mut_ { "&mut " } else { "&" };
+            format!("{}{}", prefix, type_(tparams, inner))
+        }
+        Type_::Param(tp) => type_parameter(tparams, tp.id),
+        Type_::Apply(_, sp!(_, n), tys) => {
+            let name = match n {
+                TypeName_::Builtin(sp!(_, b)) => b.to_string(),
+                TypeName_::ModuleType(sp!(_, m), s) => {
+                    format!("{}::{}::{}", address(&m.address), m.module, s)
+                }
+                TypeName_::Multiple(_) => String::new(),
+            };
+            if tys.is_empty() {
+                return name;
+            }
+            let args: Vec<_> = tys.iter().map(|ty| type_(tparams, ty)).collect();
+            match n {
+                TypeName_::Multiple(_) => format!("({})", args.join(", ")),
+                _ => format!("{}<{}>", name, args.join(", ")),
+            }
+        }
+        Type_::Var(_) | Type_::Anything | Type_::UnresolvedError => "_".to_string(),
+    }
+}
+
+fn type_parameter(tparams: &[&TParam], id: TParamID) -> String {
+    match tparams.iter().position(|tp| tp.id == id) {
+        Some(idx) => format!("T{}", idx),
+        None => "_".to_string(),
+    }
+}
+
+//**************************************************************************************************
+// Comparison
+//**************************************************************************************************
+
+/// The changes from the `old` report to the `new` one
+pub fn compare(old: &ApiReport, new: &ApiReport) -> Vec<ApiChange> {
+    let mut changes = vec![];
+    let mut add = |kind, item: &str, description: String| {
+        changes.push(ApiChange {
+            kind,
+            item: item.to_string(),
+            description,
+        })
+    };
+    for (mname, old_module) in &old.modules {
+        let Some(new_module) = new.modules.get(mname) else {
+            add(ChangeKind::Breaking, mname, "module removed".to_string());
+            continue;
+        };
+        for (sname, old_struct) in &old_module.structs {
+            let item = format!("{}::{}", mname, sname);
+            match new_module.structs.get(sname) {
+                None => add(ChangeKind::Breaking, &item, "struct removed".to_string()),
+                Some(new_struct) => {
+                    for (kind, description) in compare_structs(old_struct, new_struct) {
+                        add(kind, &item, description)
+                    }
+                }
+            }
+        }
+        for sname in new_module.structs.keys() {
+            if !old_module.structs.contains_key(sname) {
+                let item = format!("{}::{}", mname, sname);
+                add(ChangeKind::Compatible, &item, "struct added".to_string())
+            }
+        }
+        for (fname, old_function) in &old_module.functions {
+            let item = format!("{}::{}", mname, fname);
+            match new_module.functions.get(fname) {
+                None => add(ChangeKind::Breaking, &item, "function removed".to_string()),
+                Some(new_function) => {
+                    for (kind, description) in compare_functions(old_function, new_function) {
+                        add(kind, &item, description)
+                    }
+                }
+            }
+        }
+        for fname in new_module.functions.keys() {
+            if !old_module.functions.contains_key(fname) {
+                let item = format!("{}::{}", mname, fname);
+                add(ChangeKind::Compatible, &item, "function added".to_string())
+            }
+        }
+    }
+    for mname in new.modules.keys() {
+        if !old.modules.contains_key(mname) {
+            add(ChangeKind::Compatible, mname, "module added".to_string())
+        }
+    }
+    changes
+}
+
+fn compare_structs(old: &StructApi, new: &StructApi) -> Vec<(ChangeKind, String)> {
+    let mut changes = vec![];
+    let removed: Vec<_> = old.abilities.difference(&new.abilities).cloned().collect();
+    if !removed.is_empty() {
+        let msg = format!("abilities removed: {}", removed.join(", "));
+        changes.push((ChangeKind::Breaking, msg))
+    }
+    let added: Vec<_> = new.abilities.difference(&old.abilities).cloned().collect();
+    if !added.is_empty() {
+        let msg = format!("abilities added: {}", added.join(", "));
+        changes.push((ChangeKind::Compatible, msg))
+    }
+    if old.type_parameters != new.type_parameters {
+        let msg = "type parameters, their constraints, or their phantom declarations changed";
+        changes.push((ChangeKind::Breaking, msg.to_string()))
+    }
+    if old.fields != new.fields {
+        changes.push((ChangeKind::Breaking, "fields changed".to_string()))
+    }
+    changes
+}
+
+fn compare_functions(old: &FunctionApi, new: &FunctionApi) -> Vec<(ChangeKind, String)> {
+    let mut changes = vec![];
+    if old.visibility != new.visibility {
+        let kind = if new.visibility < old.visibility {
+            ChangeKind::Breaking
+        } else {
+            ChangeKind::Compatible
+        };
+        let msg = format!(
+            "visibility changed from {} to {}",
+            old.visibility, new.visibility
+        );
+        changes.push((kind, msg))
+    }
+    if old.entry != new.entry {
+        let (kind, msg) = if old.entry {
+            (ChangeKind::Breaking, "no longer 'entry'")
+        } else {
+            (ChangeKind::Compatible, "now 'entry'")
+        };
+        changes.push((kind, msg.to_string()))
+    }
+    if old.parameters != new.parameters {
+        let msg = format!(
+            "parameters changed from ({}) to ({})",
+            old.parameters.join(", "),
+            new.parameters.join(", ")
+        );
+        changes.push((ChangeKind::Breaking, msg))
+    }
+    if old.return_type != new.return_type {
+        let msg = format!(
+            "return type changed from ({}) to ({})",
+            old.return_type.join(", "),
+            new.return_type.join(", ")
+        );
+        changes.push((ChangeKind::Breaking, msg))
+    }
+    if old.type_parameters.len() != new.type_parameters.len() {
+        let msg = format!(
+            "number of type parameters changed from {} to {}",
+            old.type_parameters.len(),
+            new.type_parameters.len()
+        );
+        changes.push((ChangeKind::Breaking, msg))
+    } else if old.type_parameters != new.type_parameters {
+        // removing a constraint accepts more type arguments
+        let relaxed = old
+            .type_parameters
+            .iter()
+            .zip(&new.type_parameters)
+            .all(|(old, new)| new.is_subset(old));
+        let (kind, msg) = if relaxed {
+            (ChangeKind::Compatible, "type parameter constraints relaxed")
+        } else {
+            (ChangeKind::Breaking, "type parameter constraints added")
+        };
+        changes.push((kind, msg.to_string()))
+    }
+    changes
+}
+
+impl ApiChange {
+    pub fn is_breaking(&self) -> bool {
+        self.kind == ChangeKind::Breaking
+    }
+}
+
+impl fmt::Display for ApiVisibility {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Private => write!(f, "private"),
+            Self::Friend => write!(f, "friend"),
+            Self::Public => write!(f, "public"),
+        }
+    }
+}
+
+impl fmt::Display for ChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Compatible => write!(f, "compatible"),
+            Self::Breaking => write!(f, "breaking"),
+        }
+    }
+}
+
+impl fmt::Display for ApiChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.kind, self.item, self.description)
+    }
+}
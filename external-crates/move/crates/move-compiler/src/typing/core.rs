@@ -5,7 +5,10 @@
 use crate::{
     debug_display, diag,
     diagnostics::{codes::NameResolution, Diagnostic},
-    expansion::ast::{AbilitySet, AttributeName_, ModuleIdent, ModuleIdent_, Visibility},
+    expansion::{
+        ast::{AbilitySet, AttributeName_, ModuleIdent, ModuleIdent_, Visibility},
+        symbol_index::{SymbolKind, SymbolReference},
+    },
     naming::ast::{
         self as N, BuiltinTypeName_, ResolvedUseFuns, StructDefinition, StructTypeParameter,
         TParam, TParamID, TVar, Type, TypeName, TypeName_, Type_, UseFunKind, Var,
@@ -33,6 +36,8 @@ struct UseFunsScope {
     count: usize,
     unused: BTreeMap<(TypeName, Name), (Loc, UseFunKind, /* depth */ usize)>,
     use_funs: ResolvedUseFuns,
+    /// The methods of outer scopes hidden by a method of the same name, with a different target
+    shadowed: BTreeMap<(TypeName, Name), Vec<N::UseFun>>,
 }
 
 pub enum Constraint {
@@ -90,6 +95,11 @@ pub struct Context<'env> {
     /// collects all used module members (functions and constants) but it's a superset of these in
     /// that it may contain other identifiers that do not in fact represent a function or a constant
     pub used_module_members: BTreeMap<ModuleIdent_, BTreeSet<Symbol>>,
+    /// maps a module to the friends that call at least one of its 'public(friend)' functions
+    pub used_friends: BTreeMap<ModuleIdent_, BTreeSet<ModuleIdent_>>,
+    /// the 'use fun' declarations already reported as shadowing methods of outer scopes, so that
+    /// each is reported once, rather than at every call of the method
+    reported_shadowing_use_funs: BTreeSet<Loc>,
 }
 
 impl UseFunsScope {
@@ -126,6 +136,7 @@ impl UseFunsScope {
             count,
             unused,
             use_funs,
+            shadowed: BTreeMap::new(),
         }
     }
 }
@@ -152,6 +163,8 @@ impl<'env> Context<'env> {
             env,
             new_friends: BTreeSet::new(),
             used_module_members: BTreeMap::new(),
+            used_friends: BTreeMap::new(),
+            reported_shadowing_use_funs: BTreeSet::new(),
         }
     }
 
@@ -172,6 +185,7 @@ impl<'env> Context<'env> {
         }
         let mut unused = cur.unused.clone();
         let mut use_funs = cur.use_funs.clone();
+        let mut shadowed = cur.shadowed.clone();
         for (tn, additional_methods) in new_scope {
             for (method, nuf) in additional_methods {
                 match nuf.kind {
@@ -184,7 +198,11 @@ impl<'env> Context<'env> {
                     _ => (),
                 }
                 let cur_methods = use_funs.entry(tn.clone()).or_default();
-                cur_methods.remove(&method);
+                if let Some(prev) = cur_methods.remove(&method) {
+                    if prev.target_function != nuf.target_function {
+                        shadowed.entry((tn.clone(), method)).or_default().push(prev);
+                    }
+                }
                 cur_methods.add(method, nuf).unwrap();
             }
         }
@@ -192,6 +210,7 @@ impl<'env> Context<'env> {
             count: 1,
             unused,
             use_funs,
+            shadowed,
         })
     }
 
@@ -365,6 +384,15 @@ impl<'env> Context<'env> {
         })
     }
 
+    fn record_current_module_as_used_friend(&mut self, m: &ModuleIdent) {
+        if let Some(current_mident) = self.current_module {
+            self.used_friends
+                .entry(m.value)
+                .or_default()
+                .insert(current_mident.value);
+        }
+    }
+
     fn current_module_is_a_friend_of(&self, m: &ModuleIdent) -> bool {
         match &self.current_module {
             None => false,
@@ -380,6 +408,7 @@ impl<'env> Context<'env> {
         // TODO should we store this in the context?
         let test_only = AttributeName_::Known(KnownAttribute::Testing(TestingAttribute::TestOnly));
         let test = AttributeName_::Known(KnownAttribute::Testing(TestingAttribute::Test));
+        let rand_test = AttributeName_::Known(KnownAttribute::Testing(TestingAttribute::RandTest));
 
         self.current_module.as_ref().is_some_and(|m| {
             let minfo = self.module_info(m);
@@ -389,6 +418,7 @@ impl<'env> Context<'env> {
                     let finfo = minfo.functions.get(f).unwrap();
                     finfo.attributes.contains_key_(&test_only)
                         || finfo.attributes.contains_key_(&test)
+                        || finfo.attributes.contains_key_(&rand_test)
                 })
         })
     }
@@ -862,15 +892,16 @@ pub fn make_method_call_type(
     Vec<(Var, Type)>,
     Type,
 )> {
-    let target_function_opt = context
+    let use_fun_opt = context
         .use_funs
         .last()
         .unwrap()
         .use_funs
         .get(tn)
-        .and_then(|methods| Some(methods.get(&method)?.target_function));
+        .and_then(|methods| methods.get(&method))
+        .cloned();
     // try to find a function in the defining module for errors
-    let Some((target_m, target_f)) = target_function_opt else {
+    let Some(use_fun) = use_fun_opt else {
         let lhs_ty_str = error_format_nested(lhs_ty, &Subst::empty());
         let defining_module = match &tn.value {
             TypeName_::Multiple(_) => panic!("ICE method on tuple"),
@@ -926,14 +957,18 @@ pub fn make_method_call_type(
             };
             let fmsg =
                 format!("No local 'use fun' alias was found for '{lhs_ty_str}.{method}'{decl_msg}");
-            context.env.add_diag(diag!(
+            let mut diag = diag!(
                 TypeSafety::InvalidMethodCall,
                 (loc, msg),
                 (method.loc, fmsg)
-            ));
+            );
+            diag.add_secondary_labels(method_candidates(context, tn, method));
+            context.env.add_diag(diag);
         }
         return None;
     };
+    let (target_m, target_f) = use_fun.target_function;
+    report_shadowed_methods(context, loc, tn, method, &use_fun);
     // mark the method as used
     context
         .use_funs
@@ -944,10 +979,94 @@ pub fn make_method_call_type(
 
     let (defined_loc, ty_args, params, return_ty) =
         make_function_type(context, loc, &target_m, &target_f, ty_args_opt);
+    // Methods are resolved here, so expansion could not index their uses. The method name of an
+    // explicit 'use fun' is an alias, like 'use a::m::{f as g}', so it is not a use of the function
+    if !matches!(use_fun.kind, UseFunKind::Explicit) {
+        context.env.add_symbol_reference(SymbolReference {
+            use_loc: method.loc,
+            module: target_m,
+            member: Some(target_f.0),
+            kind: SymbolKind::Function,
+            def_loc: defined_loc,
+        });
+    }
 
     Some((defined_loc, target_m, target_f, ty_args, params, return_ty))
 }
 
+/// The functions named `method` taking `tn` as their first argument, which could be used as the
+/// method through a 'use fun'
+fn method_candidates(context: &Context, tn: &TypeName, method: Name) -> Vec<(Loc, String)> {
+    context
+        .modules
+        .modules
+        .key_cloned_iter()
+        .filter_map(|(m, minfo)| {
+            let finfo = minfo.functions.get(&FunctionName(method))?;
+            let (_, _, first_ty) = finfo.signature.parameters.first()?;
+            if first_ty.value.unfold_to_type_name()? != tn {
+                return None;
+            }
+            let msg = format!(
+                "A candidate is defined in module '{m}'. \
+                Consider adding 'use fun {m}::{method} as {tn}.{method}'"
+            );
+            Some((finfo.defined_loc, msg))
+        })
+        .collect()
+}
+
+/// Warns if the method resolved through `use_fun` shadows methods of outer scopes, listing each
+/// of them with its defining module. Each 'use fun' is only reported at its first call.
+fn report_shadowed_methods(
+    context: &mut Context,
+    loc: Loc,
+    tn: &TypeName,
+    method: Name,
+    use_fun: &N::UseFun,
+) {
+    let target_function = &use_fun.target_function;
+    let Some(shadowed) = context
+        .use_funs
+        .last()
+        .unwrap()
+        .shadowed
+        .get(&(tn.clone(), method))
+    else {
+        return;
+    };
+    // the method might be shadowing a declaration of the same function in an outer scope
+    let shadowed: Vec<_> = shadowed
+        .iter()
+        .filter(|prev| &prev.target_function != target_function)
+        .map(|prev| {
+            let (prev_m, prev_f) = &prev.target_function;
+            (
+                prev.loc,
+                format!("Shadows '{prev_m}::{prev_f}', declared here"),
+            )
+        })
+        .collect();
+    if shadowed.is_empty() || !context.reported_shadowing_use_funs.insert(use_fun.loc) {
+        return;
+    }
+    let (target_m, target_f) = target_function;
+    let msg = format!(
+        "Method '{tn}.{method}' resolves to '{target_m}::{target_f}', \
+        shadowing the methods of outer scopes"
+    );
+    let mut diag = diag!(
+        TypeSafety::ShadowedMethod,
+        (loc, msg),
+        (
+            use_fun.loc,
+            format!("'{target_m}::{target_f}' is declared as the method here")
+        )
+    );
+    diag.add_secondary_labels(shadowed);
+    context.env.add_diag(diag);
+}
+
 pub fn make_function_type(
     context: &mut Context,
     loc: Loc,
@@ -1049,7 +1168,10 @@ pub fn make_function_type(
                 (vis_loc, internal_msg),
             );
         }
-        Visibility::Friend(_) if in_current_module || context.current_module_is_a_friend_of(m) => {}
+        Visibility::Friend(_) if in_current_module => (),
+        Visibility::Friend(_) if context.current_module_is_a_friend_of(m) => {
+            context.record_current_module_as_used_friend(m);
+        }
         Visibility::Friend(vis_loc) => {
             let internal_msg = format!(
                 "This function can only be called from a 'friend' of module '{}'",
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    diag,
     diagnostics::{codes::*, Diagnostic},
     expansion::ast::{Address, ModuleIdent, Value_},
     naming::ast::{self as N, Neighbor, Neighbor_},
@@ -34,6 +35,7 @@ pub fn program(
         addresses_by_node,
         ..
     } = context;
+    check_workspace_dependencies(compilation_env, modules, &module_neighbors);
     let graph = dependency_graph(&module_neighbors);
     match petgraph_toposort(&graph, None) {
         Err(cycle_node) => {
@@ -73,6 +75,48 @@ pub fn program(
     }
 }
 
+/// Reports the modules using a module of a workspace package that their own package does not
+/// depend on
+fn check_workspace_dependencies(
+    compilation_env: &mut CompilationEnv,
+    modules: &UniqueMap<ModuleIdent, T::ModuleDefinition>,
+    module_neighbors: &BTreeMap<ModuleIdent, BTreeMap<ModuleIdent, BTreeMap<DepType, Loc>>>,
+) {
+    let mut undeclared = vec![];
+    for (mident, neighbors) in module_neighbors {
+        let package = modules.get(mident).unwrap().package_name;
+        let Some(deps) = compilation_env.workspace_dependencies(package) else {
+            continue;
+        };
+        for (neighbor, dep_types) in neighbors {
+            let Some(loc) = dep_types.get(&DepType::Use) else {
+                continue;
+            };
+            let Some(neighbor_package) = modules.get(neighbor).unwrap().package_name else {
+                continue;
+            };
+            if Some(neighbor_package) != package
+                && !deps.contains(&neighbor_package)
+                && compilation_env
+                    .workspace_dependencies(Some(neighbor_package))
+                    .is_some()
+            {
+                undeclared.push((*loc, *neighbor, package.unwrap(), neighbor_package))
+            }
+        }
+    }
+    for (loc, neighbor, package, neighbor_package) in undeclared {
+        let msg = format!(
+            "Invalid use of module '{}' of package '{}'. Package '{}' does not depend on '{}'",
+            neighbor, neighbor_package, package, neighbor_package
+        );
+        compilation_env.add_diag(diag!(
+            NameResolution::UndeclaredPackageDependency,
+            (loc, msg)
+        ));
+    }
+}
+
 #[derive(Clone, Copy, Eq, PartialEq, Ord, PartialOrd)]
 enum DepType {
     Use,
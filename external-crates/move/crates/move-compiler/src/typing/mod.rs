@@ -2,11 +2,14 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod api_report;
 pub mod ast;
 pub mod core;
 mod dependency_ordering;
 mod expand;
 mod infinite_instantiations;
 mod recursive_structs;
+pub mod transaction_templates;
 pub(crate) mod translate;
+pub mod visibility_report;
 pub mod visitor;
@@ -0,0 +1,347 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Translates scripts into programmable transaction block templates, so that workflows built on
+//! scripts can move to transactions calling the same functions. Only scripts made of a sequence
+//! of module calls, whose arguments are parameters, literals, or results of previous calls, can be
+//! translated.
+
+use crate::{
+    diag,
+    diagnostics::{Diagnostic, Diagnostics},
+    expansion::ast::{ModuleIdent, ModuleIdent_, Value, Value_},
+    naming::ast::{BuiltinTypeName_, Type, TypeName_, Type_, Var_},
+    parser::ast::FunctionName,
+    typing::ast as T,
+};
+use move_ir_types::location::*;
+use serde::Serialize;
+use std::{collections::BTreeMap, fmt::Write};
+
+/// The transaction equivalent to a script, in the JSON layout of programmable transactions
+#[derive(Debug, Serialize)]
+pub struct TransactionTemplate {
+    /// The name of the script function
+    pub script: String,
+    /// The type parameters of the script, to be instantiated when building the transaction
+    pub type_parameters: Vec<String>,
+    pub inputs: Vec<TemplateInput>,
+    pub commands: Vec<TemplateCommand>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TemplateInput {
+    /// A parameter of the script, to be provided when building the transaction
+    Parameter {
+        name: String,
+        #[serde(rename = "type")]
+        type_: String,
+    },
+    /// A literal used as an argument in the script
+    Pure {
+        #[serde(rename = "type")]
+        type_: String,
+        value: String,
+    },
+}
+
+#[derive(Debug, Serialize)]
+pub enum TemplateCommand {
+    MoveCall {
+        package: String,
+        module: String,
+        function: String,
+        type_arguments: Vec<String>,
+        arguments: Vec<TemplateArgument>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum TemplateArgument {
+    Input(u16),
+    Result(u16),
+    NestedResult(u16, u16),
+}
+
+struct Context {
+    inputs: Vec<TemplateInput>,
+    commands: Vec<TemplateCommand>,
+    locals: BTreeMap<Var_, TemplateArgument>,
+}
+
+/// Translates every script of the program, reporting the ones without an equivalent transaction
+pub fn program(prog: &T::Program) -> Result<Vec<TransactionTemplate>, Diagnostics> {
+    let mut diags = Diagnostics::new();
+    let mut templates = vec![];
+    for script in prog.inner.scripts.values() {
+        match script_(script) {
+            Ok(template) => templates.push(template),
+            Err(diag) => diags.add(*diag),
+        }
+    }
+    if diags.is_empty() {
+        Ok(templates)
+    } else {
+        Err(diags)
+    }
+}
+
+fn script_(script: &T::Script) -> Result<TransactionTemplate, Box<Diagnostic>> {
+    let T::Function {
+        signature, body, ..
+    } = &script.function;
+    let type_parameters: Vec<_> = signature
+        .type_parameters
+        .iter()
+        .map(|tp| tp.user_specified_name.value.to_string())
+        .collect();
+    let mut context = Context {
+        inputs: vec![],
+        commands: vec![],
+        locals: BTreeMap::new(),
+    };
+    for (_, var, ty) in &signature.parameters {
+        if is_signer(ty) {
+            return Err(untranslatable(
+                var.loc,
+                "Signer parameters have no equivalent in a transaction",
+            ));
+        }
+        let input = context.add_input(TemplateInput::Parameter {
+            name: var.value.name.to_string(),
+            type_: type_(ty),
+        });
+        context.locals.insert(var.value, input);
+    }
+    let T::FunctionBody_::Defined(seq) = &body.value else {
+        return Err(untranslatable(
+            body.loc,
+            "Native scripts have no equivalent in a transaction",
+        ));
+    };
+    for item in seq {
+        sequence_item(&mut context, item)?;
+    }
+    let Context {
+        inputs, commands, ..
+    } = context;
+    Ok(TransactionTemplate {
+        script: script.function_name.to_string(),
+        type_parameters,
+        inputs,
+        commands,
+    })
+}
+
+impl Context {
+    fn add_input(&mut self, input: TemplateInput) -> TemplateArgument {
+        self.inputs.push(input);
+        TemplateArgument::Input((self.inputs.len() - 1) as u16)
+    }
+}
+
+//**************************************************************************************************
+// Commands
+//**************************************************************************************************
+
+fn sequence_item(
+    context: &mut Context,
+    sp!(_, item_): &T::SequenceItem,
+) -> Result<(), Box<Diagnostic>> {
+    use T::SequenceItem_ as S;
+    match item_ {
+        S::Seq(e) if matches!(e.exp.value, T::UnannotatedExp_::Unit { .. }) => Ok(()),
+        S::Seq(e) => {
+            command(context, e)?;
+            Ok(())
+        }
+        // the bound locals are only usable once bound to a call result
+        S::Declare(_) => Ok(()),
+        S::Bind(lvalues, _, e) => {
+            let result = command(context, e)?;
+            if let [lvalue] = &lvalues.value[..] {
+                return bind(context, lvalue, TemplateArgument::Result(result));
+            }
+            for (idx, lvalue) in lvalues.value.iter().enumerate() {
+                bind(
+                    context,
+                    lvalue,
+                    TemplateArgument::NestedResult(result, idx as u16),
+                )?
+            }
+            Ok(())
+        }
+    }
+}
+
+fn bind(
+    context: &mut Context,
+    sp!(loc, lvalue_): &T::LValue,
+    argument: TemplateArgument,
+) -> Result<(), Box<Diagnostic>> {
+    match lvalue_ {
+        T::LValue_::Ignore => Ok(()),
+        T::LValue_::Var { var, .. } => {
+            context.locals.insert(var.value, argument);
+            Ok(())
+        }
+        T::LValue_::Unpack(..) | T::LValue_::BorrowUnpack(..) => Err(untranslatable(
+            *loc,
+            "Unpacking a call result has no equivalent in a transaction",
+        )),
+    }
+}
+
+/// Adds the command for the call `e`, returning its index
+fn command(context: &mut Context, e: &T::Exp) -> Result<u16, Box<Diagnostic>> {
+    let T::UnannotatedExp_::ModuleCall(call) = &e.exp.value else {
+        return Err(untranslatable(
+            e.exp.loc,
+            "Only module calls can be translated to transaction commands",
+        ));
+    };
+    let T::ModuleCall {
+        module,
+        name,
+        type_arguments,
+        arguments,
+        ..
+    } = &**call;
+    let arguments = call_arguments(context, arguments)?;
+    let type_arguments = type_arguments.iter().map(type_).collect();
+    context
+        .commands
+        .push(move_call(module, name, type_arguments, arguments));
+    Ok((context.commands.len() - 1) as u16)
+}
+
+fn move_call(
+    sp!(_, module): &ModuleIdent,
+    name: &FunctionName,
+    type_arguments: Vec<String>,
+    arguments: Vec<TemplateArgument>,
+) -> TemplateCommand {
+    TemplateCommand::MoveCall {
+        package: address(module),
+        module: module.module.to_string(),
+        function: name.to_string(),
+        type_arguments,
+        arguments,
+    }
+}
+
+fn call_arguments(
+    context: &mut Context,
+    e: &T::Exp,
+) -> Result<Vec<TemplateArgument>, Box<Diagnostic>> {
+    match &e.exp.value {
+        T::UnannotatedExp_::Unit { .. } => Ok(vec![]),
+        T::UnannotatedExp_::ExpList(items) => items
+            .iter()
+            .map(|item| match item {
+                T::ExpListItem::Single(e, _) => argument(context, e),
+                T::ExpListItem::Splat(loc, _, _) => Err(untranslatable(
+                    *loc,
+                    "Only parameters, literals, and results of previous calls can be \
+                    transaction arguments",
+                )),
+            })
+            .collect(),
+        _ => Ok(vec![argument(context, e)?]),
+    }
+}
+
+fn argument(context: &mut Context, e: &T::Exp) -> Result<TemplateArgument, Box<Diagnostic>> {
+    use T::UnannotatedExp_ as E;
+    match &e.exp.value {
+        E::Move { var, .. } | E::Copy { var, .. } | E::Use(var) | E::BorrowLocal(_, var) => {
+            context.locals.get(&var.value).copied().ok_or_else(|| {
+                untranslatable(
+                    var.loc,
+                    "Only locals bound to call results can be transaction arguments",
+                )
+            })
+        }
+        E::Value(v) => {
+            let input = TemplateInput::Pure {
+                type_: type_(&e.ty),
+                value: value(v),
+            };
+            Ok(context.add_input(input))
+        }
+        E::Annotate(inner, _) => argument(context, inner),
+        _ => Err(untranslatable(
+            e.exp.loc,
+            "Only parameters, literals, and results of previous calls can be transaction \
+            arguments",
+        )),
+    }
+}
+
+//**************************************************************************************************
+// Values and types
+//**************************************************************************************************
+
+fn address(module: &ModuleIdent_) -> String {
+    module
+        .address
+        .into_addr_bytes()
+        .into_inner()
+        .to_hex_literal()
+}
+
+fn value(sp!(_, v_): &Value) -> String {
+    match v_ {
+        Value_::Address(a) => a.into_addr_bytes().into_inner().to_hex_literal(),
+        Value_::InferredNum(u) | Value_::U256(u) => u.to_string(),
+        Value_::U8(u) => u.to_string(),
+        Value_::U16(u) => u.to_string(),
+        Value_::U32(u) => u.to_string(),
+        Value_::U64(u) => u.to_string(),
+        Value_::U128(u) => u.to_string(),
+        Value_::Bool(b) => b.to_string(),
+        Value_::Bytearray(bytes) => bytes.iter().fold("0x".to_string(), |mut hex, b| {
+            let _ = write!(hex, "{:02x}", b);
+            hex
+        }),
+    }
+}
+
+/// The type as written in transactions, with numerical addresses. Objects passed by reference are
+/// given by their type.
+fn type_(sp!(_, ty_): &Type) -> String {
+    match ty_ {
+        Type_::Ref(_, inner) => type_(inner),
+        Type_::Param(tp) => tp.user_specified_name.value.to_string(),
+        Type_::Apply(_, sp!(_, n), tys) => {
+            let name = match n {
+                TypeName_::Builtin(sp!(_, b)) => b.to_string(),
+                TypeName_::ModuleType(sp!(_, m), s) => {
+                    format!("{}::{}::{}", address(m), m.module, s)
+                }
+                TypeName_::Multiple(_) => "_".to_string(),
+            };
+            if tys.is_empty() {
+                name
+            } else {
+                let args: Vec<_> = tys.iter().map(type_).collect();
+                format!("{}<{}>", name, args.join(", "))
+            }
+        }
+        Type_::Unit => "()".to_string(),
+        Type_::Var(_) | Type_::Anything | Type_::UnresolvedError => "_".to_string(),
+    }
+}
+
+fn is_signer(sp!(_, ty_): &Type) -> bool {
+    match ty_ {
+        Type_::Ref(_, inner) => is_signer(inner),
+        Type_::Apply(_, sp!(_, TypeName_::Builtin(sp!(_, BuiltinTypeName_::Signer))), _) => true,
+        _ => false,
+    }
+}
+
+fn untranslatable(loc: Loc, msg: &'static str) -> Box<Diagnostic> {
+    Box::new(diag!(Deprecation::UntranslatableScript, (loc, msg)))
+}
@@ -68,6 +68,8 @@ pub fn program(
         .map(|(mident, minfo)| (mident, minfo.use_funs))
         .collect();
     let module_info = TypingProgramInfo::new(pre_compiled_lib, &prog, module_use_funs);
+    // the visitors record the items they visit, if any
+    compilation_env.clear_current_position();
     for v in &compilation_env.visitors().typing {
         let mut v = v.borrow_mut();
         v.visit(compilation_env, &module_info, &mut prog);
@@ -98,6 +100,11 @@ fn modules(
         typed_mdef
     });
 
+    // checked before the generated friends below replace the declared ones
+    for (_, mident, mdef) in &typed_modules {
+        unused_friends(context, &typed_modules, mident, mdef);
+    }
+
     for (mident, friends) in all_new_friends {
         let mdef = typed_modules.get_mut(&mident).unwrap();
         // point of interest: if we have any new friends, we know there can't be any
@@ -136,12 +143,14 @@ fn module(
         spec_dependencies,
     } = mdef;
     context.current_module = Some(ident);
+    context.env.set_current_module(ident.loc);
     context.current_package = package_name;
     context.env.add_warning_filter_scope(warning_filter.clone());
     context.add_use_funs_scope(use_funs);
-    structs
-        .iter_mut()
-        .for_each(|(_, _, s)| struct_def(context, s));
+    structs.iter_mut().for_each(|(loc, _, s)| {
+        context.env.set_current_member(loc);
+        struct_def(context, s)
+    });
     process_attributes(context, &attributes);
     let constants = nconstants.map(|name, c| constant(context, name, c));
     let functions = nfunctions.map(|name, f| function(context, name, f, false));
@@ -194,6 +203,7 @@ fn script(context: &mut Context, nscript: N::Script) -> T::Script {
         function: nfunction,
         spec_dependencies,
     } = nscript;
+    context.env.set_current_module(function_name.0.loc);
     context.current_package = package_name;
     context.env.add_warning_filter_scope(warning_filter.clone());
     context.add_use_funs_scope(use_funs);
@@ -229,6 +239,7 @@ fn function(
     is_script: bool,
 ) -> T::Function {
     let loc = name.loc();
+    context.env.set_current_member(loc);
     let N::Function {
         warning_filter,
         index,
@@ -328,7 +339,8 @@ fn function_body(context: &mut Context, sp!(loc, nb_): N::FunctionBody) -> T::Fu
 // Constants
 //**************************************************************************************************
 
-fn constant(context: &mut Context, _name: ConstantName, nconstant: N::Constant) -> T::Constant {
+fn constant(context: &mut Context, name: ConstantName, nconstant: N::Constant) -> T::Constant {
+    context.env.set_current_member(name.0.loc);
     assert!(context.constraints.is_empty());
     context.reset_for_module_item();
 
@@ -2522,6 +2534,10 @@ fn process_attributes(context: &mut Context, all_attributes: &Attributes) {
 /// Should be called at the end of functions/constants
 fn unused_let_muts(context: &mut Context) {
     let locals = context.take_locals();
+    if context.env.edition(context.current_package).is_migration() {
+        migration_let_muts(context, locals);
+        return;
+    }
     let supports_let_mut = context
         .env
         .supports_feature(context.current_package, FeatureGate::LetMut);
@@ -2543,6 +2559,59 @@ fn unused_let_muts(context: &mut Context) {
     }
 }
 
+/// In migration mode, every local used mutably will need a 'mut' modifier in the new edition
+fn migration_let_muts(context: &mut Context, locals: UniqueMap<N::Var, Local>) {
+    for (v, local) in locals {
+        let Some(usage_loc) = local.used_mut else {
+            continue;
+        };
+        let decl_msg = format!("The variable '{}' needs a 'mut' modifier", v.value.name);
+        context.env.add_diag(diag!(
+            Migration::NeedsLetMut,
+            (v.loc, decl_msg),
+            (usage_loc, "It is used mutably here"),
+        ))
+    }
+}
+
+/// Generates warnings for `friend` declarations where the friend module never calls a
+/// 'public(friend)' function of the declaring module. Friends that are not compiled in this pass
+/// are skipped, as their calls are unknown.
+fn unused_friends(
+    context: &mut Context,
+    modules: &UniqueMap<ModuleIdent, T::ModuleDefinition>,
+    mident: &ModuleIdent_,
+    mdef: &T::ModuleDefinition,
+) {
+    if !mdef.is_source_module {
+        return;
+    }
+
+    context
+        .env
+        .add_warning_filter_scope(mdef.warning_filter.clone());
+
+    for (loc, friend, _) in &mdef.friends {
+        if !modules.contains_key_(friend) {
+            continue;
+        }
+        let is_used = context
+            .used_friends
+            .get(mident)
+            .is_some_and(|friends| friends.contains(friend));
+        if !is_used {
+            let msg = format!(
+                "The friend declaration of '{friend}' is never used. '{friend}' does not call \
+                any '{}' function of '{mident}'. Consider removing it.",
+                Visibility::FRIEND,
+            );
+            context.env.add_diag(diag!(UnusedItem::Friend, (loc, msg)))
+        }
+    }
+
+    context.env.pop_warning_filter_scope();
+}
+
 /// Generates warnings for unused (private) functions and unused constants.
 /// Should be called after the whole program has been processed.
 fn unused_module_members(context: &mut Context, mident: &ModuleIdent_, mdef: &T::ModuleDefinition) {
@@ -2576,9 +2645,14 @@ fn unused_module_members(context: &mut Context, mident: &ModuleIdent_, mdef: &T:
 
     for (loc, name, fun) in &mdef.functions {
         if fun.attributes.iter().any(|(_, n, _)| {
-            n == &AttributeName_::Known(KnownAttribute::Testing(TestingAttribute::Test))
+            matches!(
+                n,
+                AttributeName_::Known(KnownAttribute::Testing(
+                    TestingAttribute::Test | TestingAttribute::RandTest
+                ))
+            )
         }) {
-            // functions with #[test] attribute are implicitly used
+            // functions with #[test] or #[random_test] attribute are implicitly used
             continue;
         }
         if is_sui_mode && *name == sui_mode::INIT_FUNCTION_NAME {
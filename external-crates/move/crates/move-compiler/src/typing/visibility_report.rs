@@ -0,0 +1,159 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! A report of the functions of the source modules callable from outside their module: every
+//! `public`, `public(package)`, `public(friend)`, and `entry` function. Each restricted function
+//! lists the modules allowed to call it, from the friend declarations of its module for
+//! `public(friend)`, and from the modules of its package for `public(package)`.
+
+use crate::{
+    expansion::ast::{Address, ModuleIdent, Visibility},
+    typing::ast as T,
+};
+use move_symbol_pool::Symbol;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VisibilityReport {
+    /// Keyed by `<address>::<module>`
+    pub modules: BTreeMap<String, ModuleVisibility>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModuleVisibility {
+    pub package: Option<String>,
+    /// The modules declared as friends of this module
+    pub friends: BTreeSet<String>,
+    /// The public, friend, package, and entry functions
+    pub functions: BTreeMap<String, FunctionVisibility>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FunctionVisibility {
+    /// The visibility as written: `public`, `public(package)`, `public(friend)`, or empty for
+    /// private entry functions
+    pub visibility: String,
+    pub entry: bool,
+    /// The other modules allowed to call a `public(friend)` or `public(package)` function. `None`
+    /// if the function is callable from any module, or from transactions only
+    pub callable_from: Option<BTreeSet<String>>,
+}
+
+//**************************************************************************************************
+// Report
+//**************************************************************************************************
+
+impl VisibilityReport {
+    /// The externally callable functions of the source modules of `prog`
+    pub fn new(prog: &T::Program) -> Self {
+        let modules = &prog.inner.modules;
+        let mut package_modules: BTreeMap<Option<Symbol>, BTreeSet<String>> = BTreeMap::new();
+        for (mident, mdef) in modules.key_cloned_iter() {
+            package_modules
+                .entry(mdef.package_name)
+                .or_default()
+                .insert(module_name(&mident));
+        }
+        let modules = modules
+            .key_cloned_iter()
+            .filter(|(_, mdef)| mdef.is_source_module)
+            .map(|(mident, mdef)| {
+                let name = module_name(&mident);
+                let same_package = package_modules
+                    .get(&mdef.package_name)
+                    .into_iter()
+                    .flatten()
+                    .filter(|m| **m != name)
+                    .cloned()
+                    .collect();
+                let module = module(mdef, same_package);
+                (name, module)
+            })
+            .collect();
+        Self { modules }
+    }
+
+    /// The number of reported functions
+    pub fn function_count(&self) -> usize {
+        self.modules.values().map(|m| m.functions.len()).sum()
+    }
+}
+
+fn module(mdef: &T::ModuleDefinition, same_package: BTreeSet<String>) -> ModuleVisibility {
+    let friends: BTreeSet<_> = mdef
+        .friends
+        .key_cloned_iter()
+        .map(|(mident, _)| module_name(&mident))
+        .collect();
+    let functions = mdef
+        .functions
+        .key_cloned_iter()
+        .filter_map(|(name, fdef)| {
+            let entry = fdef.entry.is_some();
+            let (visibility, callable_from) = match fdef.visibility {
+                Visibility::Public(_) => (Visibility::PUBLIC, None),
+                Visibility::Friend(_) => (Visibility::FRIEND, Some(friends.clone())),
+                Visibility::Package(_) => (Visibility::PACKAGE, Some(same_package.clone())),
+                Visibility::Internal if entry => (Visibility::INTERNAL, None),
+                Visibility::Internal => return None,
+            };
+            let function = FunctionVisibility {
+                visibility: visibility.to_string(),
+                entry,
+                callable_from,
+            };
+            Some((name.to_string(), function))
+        })
+        .collect();
+    ModuleVisibility {
+        package: mdef.package_name.map(|p| p.to_string()),
+        friends,
+        functions,
+    }
+}
+
+fn module_name(sp!(_, mident): &ModuleIdent) -> String {
+    format!("{}::{}", address(&mident.address), mident.module)
+}
+
+fn address(addr: &Address) -> String {
+    addr.into_addr_bytes().into_inner().to_hex_literal()
+}
+
+//**************************************************************************************************
+// Display
+//**************************************************************************************************
+
+impl fmt::Display for VisibilityReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, module) in &self.modules {
+            match &module.package {
+                Some(package) => writeln!(f, "module {} (package {})", name, package)?,
+                None => writeln!(f, "module {}", name)?,
+            }
+            for friend in &module.friends {
+                writeln!(f, "    friend {}", friend)?;
+            }
+            for (fname, function) in &module.functions {
+                let mut modifiers = function.visibility.clone();
+                if function.entry {
+                    if !modifiers.is_empty() {
+                        modifiers.push(' ');
+                    }
+                    modifiers.push_str("entry");
+                }
+                write!(f, "    {} fun {}", modifiers, fname)?;
+                if let Some(callable_from) = &function.callable_from {
+                    let callers: Vec<_> = callable_from.iter().map(|m| m.as_str()).collect();
+                    write!(f, ", callable from [{}]", callers.join(", "))?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -9,7 +9,7 @@ use crate::shared::{program_info::TypingProgramInfo, CompilationEnv};
 use crate::typing::ast as T;
 use move_symbol_pool::Symbol;
 
-pub type TypingVisitorObj = Box<dyn TypingVisitor>;
+pub type TypingVisitorObj = Box<dyn TypingVisitor + Send>;
 
 pub trait TypingVisitor {
     fn visit(
@@ -21,7 +21,7 @@ pub trait TypingVisitor {
 
     fn visitor(self) -> Visitor
     where
-        Self: 'static + Sized,
+        Self: 'static + Sized + Send,
     {
         Visitor::TypingVisitor(Box::new(self))
     }
@@ -200,7 +200,7 @@ pub trait TypingVisitorContext {
     }
 }
 
-impl<V: TypingVisitor + 'static> From<V> for TypingVisitorObj {
+impl<V: TypingVisitor + Send + 'static> From<V> for TypingVisitorObj {
     fn from(value: V) -> Self {
         Box::new(value)
     }
@@ -0,0 +1,133 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Coverage instrumentation for unit tests. When compiling with `Flags::coverage`, every basic
+//! block of the compiled code starts with a call to the native
+//! `std::unit_test::increment_coverage_counter`, passing the block's counter. This module recovers
+//! the instrumented blocks from the compiled bytecode, and maps them back to the source through
+//! the source map, so that the counters collected while running tests can be reported.
+
+use crate::{
+    compiled_unit::NamedCompiledModule, unit_test::filter_test_members::UNIT_TEST_MODULE_NAME,
+};
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{Bytecode, CodeOffset, FunctionDefinitionIndex},
+};
+use move_ir_types::location::Loc;
+use std::collections::BTreeMap;
+
+/// The native function in `std::unit_test` called at the start of every instrumented block
+pub const COVERAGE_COUNTER_FUNCTION_NAME: &str = "increment_coverage_counter";
+
+#[derive(Debug, Clone)]
+pub struct FunctionCoverageMap {
+    pub name: String,
+    /// The location of the function's name
+    pub loc: Loc,
+    /// The instrumented blocks of the function, in code order. The first one is the entry block.
+    pub blocks: Vec<CoverageBlock>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CoverageBlock {
+    pub counter: u64,
+    /// The source locations of the block's instructions, excluding the instrumentation
+    pub locs: Vec<Loc>,
+    /// If the block ends with a conditional branch
+    pub branch: Option<CoverageBranch>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CoverageBranch {
+    pub loc: Loc,
+    /// The counter of the block executed if the condition is true
+    pub if_true: u64,
+    /// The counter of the block executed if the condition is false
+    pub if_false: u64,
+}
+
+/// The instrumented functions of `module`, empty if it was not compiled with coverage
+pub fn module_coverage_map(module: &NamedCompiledModule) -> Vec<FunctionCoverageMap> {
+    let compiled = &module.module;
+    let is_counter_increment = |instr: &Bytecode| match instr {
+        Bytecode::Call(fh_idx) => {
+            let handle = compiled.function_handle_at(*fh_idx);
+            let mhandle = compiled.module_handle_at(handle.module);
+            compiled.identifier_at(handle.name).as_str() == COVERAGE_COUNTER_FUNCTION_NAME
+                && compiled.identifier_at(mhandle.name).as_str() == UNIT_TEST_MODULE_NAME.as_str()
+        }
+        _ => false,
+    };
+
+    let mut functions = vec![];
+    for (idx, fdef) in compiled.function_defs().iter().enumerate() {
+        let Some(code_unit) = &fdef.code else {
+            continue;
+        };
+        let code = &code_unit.code;
+        let fdef_idx = FunctionDefinitionIndex(idx as u16);
+        let Ok(fsource_map) = module.source_map.get_function_source_map(fdef_idx) else {
+            continue;
+        };
+
+        // each block starts with `LdU64(counter); Call(increment_coverage_counter)`
+        let block_starts: BTreeMap<CodeOffset, u64> = code
+            .windows(2)
+            .enumerate()
+            .filter_map(|(offset, instrs)| match &instrs[0] {
+                Bytecode::LdU64(counter) if is_counter_increment(&instrs[1]) => {
+                    Some((offset as CodeOffset, *counter))
+                }
+                _ => None,
+            })
+            .collect();
+        if block_starts.is_empty() {
+            continue;
+        }
+
+        let offsets: Vec<_> = block_starts.keys().copied().collect();
+        let blocks = offsets
+            .iter()
+            .enumerate()
+            .map(|(i, start)| {
+                let end = offsets
+                    .get(i + 1)
+                    .copied()
+                    .unwrap_or(code.len() as CodeOffset);
+                let mut locs: Vec<Loc> = (start + 2..end)
+                    .filter_map(|offset| fsource_map.get_code_location(offset))
+                    .collect();
+                locs.dedup();
+                let last = end - 1;
+                let branch = match &code[last as usize] {
+                    Bytecode::BrTrue(target) => Some((*target, end)),
+                    Bytecode::BrFalse(target) => Some((end, *target)),
+                    _ => None,
+                }
+                .and_then(|(if_true, if_false)| {
+                    Some(CoverageBranch {
+                        loc: fsource_map.get_code_location(last)?,
+                        if_true: *block_starts.get(&if_true)?,
+                        if_false: *block_starts.get(&if_false)?,
+                    })
+                });
+                CoverageBlock {
+                    counter: block_starts[start],
+                    locs,
+                    branch,
+                }
+            })
+            .collect();
+
+        let name = compiled
+            .identifier_at(compiled.function_handle_at(fdef.function).name)
+            .to_string();
+        functions.push(FunctionCoverageMap {
+            name,
+            loc: fsource_map.definition_location,
+            blocks,
+        });
+    }
+    functions
+}
@@ -100,8 +100,9 @@ impl FilterContext for Context<'_> {
 // Filtering of test-annotated module members
 //***************************************************************************
 
-const UNIT_TEST_MODULE_NAME: Symbol = symbol!("unit_test");
-const STDLIB_ADDRESS_NAME: Symbol = symbol!("std");
+pub(crate) const UNIT_TEST_MODULE_NAME: Symbol = symbol!("unit_test");
+pub(crate) const STDLIB_ADDRESS_NAME: Symbol = symbol!("std");
+pub(crate) const UNIT_TEST_POISON_FUN_NAME: Symbol = symbol!("unit_test_poison");
 
 // This filters out all test, and test-only annotated module member from `prog` if the `test` flag
 // in `compilation_env` is not set. If the test flag is set, no filtering is performed, and instead
@@ -199,7 +200,7 @@ fn create_test_poison(mloc: Loc) -> P::ModuleMember {
         visibility: P::Visibility::Internal,
         entry: Some(mloc), // it's a bit of a hack to avoid treating this function as unused
         signature,
-        name: P::FunctionName(sp(mloc, "unit_test_poison".into())),
+        name: P::FunctionName(sp(mloc, UNIT_TEST_POISON_FUN_NAME)),
         body: sp(
             mloc,
             P::FunctionBody_::Defined((
@@ -221,14 +222,17 @@ fn create_test_poison(mloc: Loc) -> P::ModuleMember {
 fn should_remove_node(env: &CompilationEnv, attrs: &[P::Attributes], is_source_def: bool) -> bool {
     use known_attributes::TestingAttribute;
     let flattened_attrs: Vec<_> = attrs.iter().flat_map(test_attributes).collect();
-    let is_test_only = flattened_attrs
-        .iter()
-        .any(|attr| matches!(attr.1, TestingAttribute::Test | TestingAttribute::TestOnly));
+    let is_test_only = flattened_attrs.iter().any(|attr| {
+        matches!(
+            attr.1,
+            TestingAttribute::Test | TestingAttribute::RandTest | TestingAttribute::TestOnly
+        )
+    });
     is_test_only && !env.flags().keep_testing_functions()
         || (!is_source_def
             && flattened_attrs
                 .iter()
-                .any(|attr| attr.1 == TestingAttribute::Test))
+                .any(|attr| matches!(attr.1, TestingAttribute::Test | TestingAttribute::RandTest)))
 }
 
 fn test_attributes(attrs: &P::Attributes) -> Vec<(Loc, known_attributes::TestingAttribute)> {
@@ -8,42 +8,56 @@ use crate::{
     expansion::ast::{
         self as E, Address, Attribute, AttributeValue, ModuleAccess_, ModuleIdent, ModuleIdent_,
     },
-    hlir::translate::display_var,
+    hlir::{ast as H, translate::display_var},
+    naming::ast::BuiltinTypeName_,
     parser::ast::ConstantName,
     shared::{
         known_attributes::{KnownAttribute, TestingAttribute},
         unique_map::UniqueMap,
-        CompilationEnv, Identifier, NumericalAddress,
+        CompilationEnv, Identifier, Name, NumericalAddress,
+    },
+    unit_test::{
+        ExpectedFailure, ExpectedMoveError, ModuleTestPlan, TestArgument, TestCase, TestName,
     },
-    unit_test::{ExpectedFailure, ExpectedMoveError, ModuleTestPlan, TestCase},
 };
 use move_core_types::{
-    account_address::AccountAddress as MoveAddress, language_storage::ModuleId, u256::U256,
-    value::MoveValue, vm_status::StatusCode,
+    account_address::AccountAddress as MoveAddress,
+    language_storage::ModuleId,
+    u256::U256,
+    value::{MoveTypeLayout, MoveValue},
+    vm_status::StatusCode,
 };
 use move_ir_types::location::Loc;
 use move_symbol_pool::Symbol;
 use std::collections::BTreeMap;
 
+/// The value assigned to a test parameter in a #[test] attribute
+enum AssignedArgument {
+    Value(MoveValue),
+    /// A list of values, each of which is run as a separate case of the test
+    Cases(Vec<MoveValue>),
+    /// A value that cannot be used, which has already been reported
+    Invalid,
+}
+
 struct Context<'env> {
     env: &'env mut CompilationEnv,
-    constants: UniqueMap<ModuleIdent, UniqueMap<ConstantName, (Loc, Option<u64>)>>,
+    constants: UniqueMap<ModuleIdent, UniqueMap<ConstantName, (Loc, Option<MoveValue>)>>,
+    /// The module of the tests being built, whose constants can be used without a module
+    current_module: Option<ModuleIdent>,
 }
 
 impl<'env> Context<'env> {
     fn new(compilation_env: &'env mut CompilationEnv, prog: &G::Program) -> Self {
         let constants = prog.modules.ref_map(|_mident, module| {
-            module.constants.ref_map(|_name, constant| {
-                let v_opt = constant.value.as_ref().and_then(|v| match v {
-                    MoveValue::U64(u) => Some(*u),
-                    _ => None,
-                });
-                (constant.loc, v_opt)
-            })
+            module
+                .constants
+                .ref_map(|_name, constant| (constant.loc, constant.value.clone()))
         });
         Self {
             env: compilation_env,
             constants,
+            current_module: None,
         }
     }
 
@@ -51,11 +65,22 @@ impl<'env> Context<'env> {
         (*addr).into_addr_bytes()
     }
 
-    fn constants(&self) -> &UniqueMap<ModuleIdent, UniqueMap<ConstantName, (Loc, Option<u64>)>> {
+    fn constants(
+        &self,
+    ) -> &UniqueMap<ModuleIdent, UniqueMap<ConstantName, (Loc, Option<MoveValue>)>> {
         &self.constants
     }
 }
 
+/// A constant used as an attribute value
+struct AttributeConstant {
+    module: ModuleIdent,
+    name: Name,
+    module_id: ModuleId,
+    loc: Loc,
+    value: Option<MoveValue>,
+}
+
 //***************************************************************************
 // Test Plan Building
 //***************************************************************************
@@ -91,13 +116,11 @@ fn construct_module_test_plan(
     if package_filter.is_some() && module.package_name != package_filter {
         return None;
     }
+    context.current_module = Some(module_ident);
     let tests: BTreeMap<_, _> = module
         .functions
         .iter()
-        .filter_map(|(loc, fn_name, func)| {
-            build_test_info(context, loc, fn_name, func)
-                .map(|test_case| (fn_name.to_string(), test_case))
-        })
+        .flat_map(|(loc, fn_name, func)| build_test_info(context, loc, fn_name, func))
         .collect();
 
     if tests.is_empty() {
@@ -114,7 +137,7 @@ fn build_test_info<'func>(
     fn_loc: Loc,
     fn_name: &str,
     function: &'func G::Function,
-) -> Option<TestCase> {
+) -> Vec<(TestName, TestCase)> {
     let get_attrs = |attr: TestingAttribute| -> Option<&'func E::Attribute> {
         function
             .attributes
@@ -125,10 +148,26 @@ fn build_test_info<'func>(
     const IN_THIS_TEST_MSG: &str = "Error found in this test";
 
     let test_attribute_opt = get_attrs(TestingAttribute::Test);
+    let rand_test_attribute_opt = get_attrs(TestingAttribute::RandTest);
     let abort_attribute_opt = get_attrs(TestingAttribute::ExpectedFailure);
     let test_only_attribute_opt = get_attrs(TestingAttribute::TestOnly);
 
-    let test_attribute = match test_attribute_opt {
+    // A #[test] function cannot also be annotated #[random_test]
+    if let (Some(test_attribute), Some(rand_test_attribute)) =
+        (test_attribute_opt, rand_test_attribute_opt)
+    {
+        let msg = "Function annotated as both #[test(...)] and #[random_test(...)]. You need to \
+                   declare it as either one or the other";
+        context.env.add_diag(diag!(
+            Attributes::InvalidUsage,
+            (rand_test_attribute.loc, msg),
+            (test_attribute.loc, PREVIOUSLY_ANNOTATED_MSG),
+            (fn_loc, IN_THIS_TEST_MSG),
+        ))
+    }
+    let is_random_test = test_attribute_opt.is_none() && rand_test_attribute_opt.is_some();
+
+    let test_attribute = match test_attribute_opt.or(rand_test_attribute_opt) {
         None => {
             // expected failures cannot be annotated on non-#[test] functions
             if let Some(abort_attribute) = abort_attribute_opt {
@@ -141,7 +180,7 @@ fn build_test_info<'func>(
                     (abort_attribute.loc, abort_msg),
                 ))
             }
-            return None;
+            return vec![];
         }
         Some(test_attribute) => test_attribute,
     };
@@ -158,16 +197,48 @@ fn build_test_info<'func>(
         ))
     }
 
-    let test_annotation_params = parse_test_attribute(context, test_attribute, 0);
-    let mut arguments = Vec::new();
-    for (var, _) in &function.signature.parameters {
-        let sp!(vloc, var_) = var.0;
-        let var_ = match display_var(var_) {
-            crate::hlir::translate::DisplayVar::Orig(s) => s.into(),
-            crate::hlir::translate::DisplayVar::Tmp => panic!("ICE temp as parameter"),
-        };
-        match test_annotation_params.get(&var_) {
-            Some(value) => arguments.push(value.clone()),
+    let params: Vec<(Loc, Symbol, &H::SingleType)> = function
+        .signature
+        .parameters
+        .iter()
+        .map(|(var, ty)| {
+            let sp!(vloc, var_) = var.0;
+            let var_ = match display_var(var_) {
+                crate::hlir::translate::DisplayVar::Orig(s) => s.into(),
+                crate::hlir::translate::DisplayVar::Tmp => panic!("ICE temp as parameter"),
+            };
+            (vloc, var_, ty)
+        })
+        .collect();
+    let param_types = params.iter().map(|(_, var_, ty)| (*var_, *ty)).collect();
+    let mut test_annotation_params = parse_test_attribute(context, test_attribute, 0, &param_types);
+    // a test with a list of values assigned to any of its parameters is expanded into one case
+    // per combination of values
+    let mut is_parameterized = false;
+    let mut arguments: Vec<Vec<TestArgument>> = Vec::new();
+    for (vloc, var_, ty) in params {
+        match test_annotation_params.remove(&var_) {
+            Some(AssignedArgument::Value(value)) => {
+                arguments.push(vec![TestArgument::Value(value)])
+            }
+            Some(AssignedArgument::Cases(values)) => {
+                is_parameterized = true;
+                arguments.push(values.into_iter().map(TestArgument::Value).collect())
+            }
+            Some(AssignedArgument::Invalid) => (),
+            // parameters of a #[random_test] that are not assigned are generated
+            None if is_random_test => match random_test_layout(ty) {
+                Some(layout) => arguments.push(vec![TestArgument::Generate(layout)]),
+                None => {
+                    let msg = "Unsupported type for a randomly generated test parameter. Only \
+                               primitive types and vectors of them can be generated";
+                    context.env.add_diag(diag!(
+                        Attributes::InvalidTest,
+                        (vloc, msg),
+                        (fn_loc, IN_THIS_TEST_MSG),
+                    ))
+                }
+            },
             None => {
                 let missing_param_msg = "Missing test parameter assignment in test. Expected a \
                                          parameter to be assigned in this attribute";
@@ -186,11 +257,39 @@ fn build_test_info<'func>(
         Some(abort_attribute) => parse_failure_attribute(context, abort_attribute),
     };
 
-    Some(TestCase {
-        test_name: fn_name.to_string(),
-        arguments,
-        expected_failure,
-    })
+    let mut cases: Vec<Vec<TestArgument>> = vec![vec![]];
+    for values in arguments {
+        cases = cases
+            .into_iter()
+            .flat_map(|prefix| {
+                values.iter().map(move |value| {
+                    let mut case = prefix.clone();
+                    case.push(value.clone());
+                    case
+                })
+            })
+            .collect();
+    }
+
+    let num_cases = cases.len();
+    cases
+        .into_iter()
+        .enumerate()
+        .map(|(idx, arguments)| {
+            let name = if is_parameterized {
+                format!("{fn_name}[{idx}]")
+            } else {
+                debug_assert!(num_cases == 1);
+                fn_name.to_string()
+            };
+            let test_case = TestCase {
+                test_name: fn_name.to_string(),
+                arguments,
+                expected_failure: expected_failure.clone(),
+            };
+            (name, test_case)
+        })
+        .collect()
 }
 
 //***************************************************************************
@@ -201,7 +300,8 @@ fn parse_test_attribute(
     context: &mut Context,
     sp!(aloc, test_attribute): &E::Attribute,
     depth: usize,
-) -> BTreeMap<Symbol, MoveValue> {
+    param_types: &BTreeMap<Symbol, &H::SingleType>,
+) -> BTreeMap<Symbol, AssignedArgument> {
     use E::Attribute_ as EA;
 
     match test_attribute {
@@ -214,7 +314,10 @@ fn parse_test_attribute(
         }
         EA::Name(nm) => {
             assert!(
-                nm.value.as_str() == TestingAttribute::Test.name() && depth == 0,
+                matches!(
+                    nm.value.as_str(),
+                    TestingAttribute::TEST | TestingAttribute::RAND_TEST
+                ) && depth == 0,
                 "ICE: We should only be parsing a raw test attribute"
             );
             BTreeMap::new()
@@ -228,30 +331,74 @@ fn parse_test_attribute(
                 return BTreeMap::new();
             }
             let sp!(assign_loc, attr_value) = &**attr_value;
-            let value = match convert_attribute_value_to_move_value(context, attr_value) {
-                Some(move_value) => move_value,
-                None => {
+            let param_type = param_types
+                .get(&nm.value)
+                .and_then(|ty| builtin_type_name(ty));
+            let argument = match attr_value {
+                E::AttributeValue_::List(values) if values.is_empty() => {
+                    context.env.add_diag(diag!(
+                        Attributes::InvalidValue,
+                        (*assign_loc, "Expected at least one value in this list"),
+                        (*aloc, "Assigned in this attribute"),
+                    ));
+                    Ok(AssignedArgument::Invalid)
+                }
+                E::AttributeValue_::List(values) => values
+                    .iter()
+                    .map(|value| {
+                        convert_attribute_value_to_move_value(context, value, param_type)
+                            .ok_or(value.loc)
+                    })
+                    .collect::<Result<_, _>>()
+                    .map(AssignedArgument::Cases),
+                E::AttributeValue_::Value(value) => {
+                    convert_attribute_value_to_move_value(context, value, param_type)
+                        .map(AssignedArgument::Value)
+                        .ok_or(*assign_loc)
+                }
+                E::AttributeValue_::ModuleAccess(access) => {
+                    match attribute_constant(context, *assign_loc, access) {
+                        Some(constant) => constant
+                            .value
+                            .filter(|value| constant_has_param_type(value, param_type))
+                            .map(AssignedArgument::Value)
+                            .ok_or(*assign_loc),
+                        None => Ok(AssignedArgument::Invalid),
+                    }
+                }
+                _ => Err(*assign_loc),
+            };
+            // the parameter is assigned even when its value is invalid, so that it is not also
+            // reported as missing
+            let argument = match argument {
+                Ok(argument) => argument,
+                Err(vloc) => {
                     context.env.add_diag(diag!(
                         Attributes::InvalidValue,
-                        (*assign_loc, "Unsupported attribute value"),
+                        (vloc, "Unsupported attribute value"),
                         (*aloc, "Assigned in this attribute"),
                     ));
-                    return BTreeMap::new();
+                    AssignedArgument::Invalid
                 }
             };
 
             let mut args = BTreeMap::new();
-            args.insert(nm.value, value);
+            args.insert(nm.value, argument);
             args
         }
         EA::Parameterized(nm, attributes) => {
             assert!(
-                nm.value.as_str() == TestingAttribute::Test.name() && depth == 0,
+                matches!(
+                    nm.value.as_str(),
+                    TestingAttribute::TEST | TestingAttribute::RAND_TEST
+                ) && depth == 0,
                 "ICE: We should only be parsing a raw test attribute"
             );
             attributes
                 .iter()
-                .flat_map(|(_, _, attr)| parse_test_attribute(context, attr, depth + 1))
+                .flat_map(|(_, _, attr)| {
+                    parse_test_attribute(context, attr, depth + 1, param_types)
+                })
                 .collect()
         }
     }
@@ -567,49 +714,72 @@ fn convert_constant_value_u64_constant_or_value(
     value: &AttributeValue,
 ) -> Option<(Loc, Option<ModuleId>, u64)> {
     use E::AttributeValue_ as EAV;
-    let (vloc, module, member) = match value {
-        sp!(
-            vloc,
-            EAV::ModuleAccess(sp!(_, ModuleAccess_::ModuleAccess(m, n)))
-        ) => (*vloc, m, n),
-        _ => {
-            let (vloc, u) = convert_attribute_value_u64(context, loc, value)?;
-            return Some((vloc, None, u));
-        }
+    let sp!(vloc, EAV::ModuleAccess(access)) = value else {
+        let (vloc, u) = convert_attribute_value_u64(context, loc, value)?;
+        return Some((vloc, None, u));
     };
-    let module_id = convert_module_id(context, vloc, module)?;
-    let modules_constants = context.constants().get(module).unwrap();
-    let constant = match modules_constants.get_(&member.value) {
-        None => {
-            context.env.add_diag(diag!(
-                Attributes::InvalidValue,
-                (vloc, INVALID_VALUE),
-                (
-                    module.loc,
-                    format!("Unbound constant '{member}' in module '{module}'")
-                ),
-            ));
-            return None;
-        }
-        Some(c) => c,
-    };
-    match constant {
-        (cloc, None) => {
+    let AttributeConstant {
+        module,
+        name,
+        module_id,
+        loc: cloc,
+        value,
+    } = attribute_constant(context, *vloc, access)?;
+    match value {
+        Some(MoveValue::U64(u)) => Some((*vloc, Some(module_id), u)),
+        _ => {
             let msg = format!(
-                "Constant '{module}::{member}' has a non-u64 value. \
+                "Constant '{module}::{name}' has a non-u64 value. \
                 Only 'u64' values are permitted"
             );
             context.env.add_diag(diag!(
                 Attributes::InvalidValue,
-                (vloc, INVALID_VALUE),
-                (*cloc, msg),
+                (*vloc, INVALID_VALUE),
+                (cloc, msg),
             ));
             None
         }
-        (_, Some(u)) => Some((vloc, Some(module_id), *u)),
     }
 }
 
+// Resolves a constant used as an attribute value, either through a module or, if unqualified, in
+// the module of the tests
+fn attribute_constant(
+    context: &mut Context,
+    vloc: Loc,
+    sp!(_, access): &E::ModuleAccess,
+) -> Option<AttributeConstant> {
+    let (module, name, unbound_loc) = match access {
+        ModuleAccess_::ModuleAccess(m, n) => (*m, *n, m.loc),
+        ModuleAccess_::Name(n) => {
+            let current_module = context
+                .current_module
+                .expect("ICE test plans are built within a module");
+            (current_module, *n, n.loc)
+        }
+    };
+    let module_id = convert_module_id(context, vloc, &module)?;
+    let modules_constants = context.constants().get(&module).unwrap();
+    let Some((loc, value)) = modules_constants.get_(&name.value) else {
+        context.env.add_diag(diag!(
+            Attributes::InvalidValue,
+            (vloc, INVALID_VALUE),
+            (
+                unbound_loc,
+                format!("Unbound constant '{name}' in module '{module}'")
+            ),
+        ));
+        return None;
+    };
+    Some(AttributeConstant {
+        module,
+        name,
+        module_id,
+        loc: *loc,
+        value: value.clone(),
+    })
+}
+
 fn convert_module_id(context: &mut Context, vloc: Loc, module: &ModuleIdent) -> Option<ModuleId> {
     if !context.constants.contains_key(module) {
         context.env.add_diag(diag!(
@@ -672,16 +842,89 @@ fn convert_attribute_value_u64(
     }
 }
 
+// Converts a value assigned to a test parameter of the given builtin type. Addresses are accepted
+// for any parameter, e.g. for signers, while numbers and booleans must match the parameter's type.
 fn convert_attribute_value_to_move_value(
     context: &mut Context,
-    value: &E::AttributeValue_,
+    value: &E::Value,
+    param_type: Option<&BuiltinTypeName_>,
 ) -> Option<MoveValue> {
-    use E::{AttributeValue_ as EAV, Value_ as EV};
-    match value {
-        // Only addresses are allowed
-        EAV::Value(sp!(_, EV::Address(a))) => Some(MoveValue::Address(MoveAddress::new(
-            context.resolve_address(a).into_bytes(),
-        ))),
+    use BuiltinTypeName_ as BT;
+    use E::Value_ as EV;
+    Some(match (&value.value, param_type) {
+        (EV::Address(a), _) => {
+            MoveValue::Address(MoveAddress::new(context.resolve_address(a).into_bytes()))
+        }
+        (EV::Bool(b), Some(BT::Bool)) => MoveValue::Bool(*b),
+        (EV::InferredNum(u), Some(BT::U8)) => MoveValue::U8(u8::try_from(*u).ok()?),
+        (EV::InferredNum(u), Some(BT::U16)) => MoveValue::U16(u16::try_from(*u).ok()?),
+        (EV::InferredNum(u), Some(BT::U32)) => MoveValue::U32(u32::try_from(*u).ok()?),
+        (EV::InferredNum(u), Some(BT::U64)) => MoveValue::U64(u64::try_from(*u).ok()?),
+        (EV::InferredNum(u), Some(BT::U128)) => MoveValue::U128(u128::try_from(*u).ok()?),
+        (EV::InferredNum(u), Some(BT::U256)) => MoveValue::U256(*u),
+        (EV::U8(u), Some(BT::U8)) => MoveValue::U8(*u),
+        (EV::U16(u), Some(BT::U16)) => MoveValue::U16(*u),
+        (EV::U32(u), Some(BT::U32)) => MoveValue::U32(*u),
+        (EV::U64(u), Some(BT::U64)) => MoveValue::U64(*u),
+        (EV::U128(u), Some(BT::U128)) => MoveValue::U128(*u),
+        (EV::U256(u), Some(BT::U256)) => MoveValue::U256(*u),
+        _ => return None,
+    })
+}
+
+// Whether the value of a constant can be assigned to a test parameter of the given builtin type,
+// following the rules for literals
+fn constant_has_param_type(value: &MoveValue, param_type: Option<&BuiltinTypeName_>) -> bool {
+    use BuiltinTypeName_ as BT;
+    matches!(
+        (value, param_type),
+        (MoveValue::Address(_), _)
+            | (MoveValue::Bool(_), Some(BT::Bool))
+            | (MoveValue::U8(_), Some(BT::U8))
+            | (MoveValue::U16(_), Some(BT::U16))
+            | (MoveValue::U32(_), Some(BT::U32))
+            | (MoveValue::U64(_), Some(BT::U64))
+            | (MoveValue::U128(_), Some(BT::U128))
+            | (MoveValue::U256(_), Some(BT::U256))
+    )
+}
+
+// The layout of the values generated for a parameter of a #[random_test], if they can be
+// generated
+fn random_test_layout(sp!(_, ty_): &H::SingleType) -> Option<MoveTypeLayout> {
+    match ty_ {
+        H::SingleType_::Base(bt) => random_test_base_layout(bt),
+        H::SingleType_::Ref(_, _) => None,
+    }
+}
+
+fn random_test_base_layout(sp!(_, bt_): &H::BaseType) -> Option<MoveTypeLayout> {
+    use BuiltinTypeName_ as BT;
+    let H::BaseType_::Apply(_, sp!(_, H::TypeName_::Builtin(sp!(_, b))), ty_args) = bt_ else {
+        return None;
+    };
+    Some(match b {
+        BT::Bool => MoveTypeLayout::Bool,
+        BT::U8 => MoveTypeLayout::U8,
+        BT::U16 => MoveTypeLayout::U16,
+        BT::U32 => MoveTypeLayout::U32,
+        BT::U64 => MoveTypeLayout::U64,
+        BT::U128 => MoveTypeLayout::U128,
+        BT::U256 => MoveTypeLayout::U256,
+        BT::Address => MoveTypeLayout::Address,
+        BT::Signer => MoveTypeLayout::Signer,
+        BT::Vector => MoveTypeLayout::Vector(Box::new(random_test_base_layout(ty_args.first()?)?)),
+    })
+}
+
+fn builtin_type_name(sp!(_, ty_): &H::SingleType) -> Option<&BuiltinTypeName_> {
+    match ty_ {
+        H::SingleType_::Base(
+            sp!(
+                _,
+                H::BaseType_::Apply(_, sp!(_, H::TypeName_::Builtin(sp!(_, b))), _)
+            ),
+        ) => Some(b),
         _ => None,
     }
 }
@@ -0,0 +1,70 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::{
+    cfgir::abort_codes::{AbortCode, ABORT_CODES_FILE},
+    command_line::compiler::ModuleNaming,
+    compiled_unit::AnnotatedCompiledUnit,
+    diagnostics::FilesSourceText,
+};
+use std::fs;
+
+const SOURCE: &str = r#"module 0x42::m {
+    /// The balance is too low
+    /// for the withdrawal
+    const EInsufficientBalance: u64 = 7;
+    #[allow(unused_const)]
+    /// Never used as an abort code
+    const ENotUsed: u64 = 8;
+    const ENoDoc: u64 = 3 + 6;
+    const MAX: u64 = 100;
+
+    public fun withdraw(balance: u64, amount: u64): u64 {
+        assert!(balance >= amount, EInsufficientBalance);
+        if (amount > MAX) abort ENoDoc;
+        balance - amount
+    }
+}
+"#;
+
+fn build(dir: &tempfile::TempDir) -> (FilesSourceText, Vec<AnnotatedCompiledUnit>) {
+    common::build(common::compiler(dir.path(), &[("m.move", SOURCE)]))
+}
+
+#[test]
+fn constants_used_as_abort_codes_are_collected() {
+    let dir = tempfile::tempdir().unwrap();
+    let (_files, units) = build(&dir);
+    assert_eq!(
+        &common::single_module(&units).abort_codes,
+        &vec![
+            AbortCode {
+                name: "EInsufficientBalance".into(),
+                value: 7,
+                doc: Some(" The balance is too low\n for the withdrawal".to_string()),
+            },
+            AbortCode {
+                name: "ENoDoc".into(),
+                value: 9,
+                doc: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn registry_is_emitted_in_the_output_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let (files, units) = build(&dir);
+    let out_dir = common::output(dir.path(), &[], ModuleNaming::Indexed, false, files, units);
+    let registry = fs::read_to_string(out_dir.join(ABORT_CODES_FILE)).unwrap();
+    let registry: serde_json::Value = serde_json::from_str(&registry).unwrap();
+    let entry = &registry["0x42::m::EInsufficientBalance"];
+    assert_eq!(entry["value"], 7);
+    assert_eq!(entry["doc"], " The balance is too low\n for the withdrawal");
+    assert_eq!(registry["0x42::m::ENoDoc"]["value"], 9);
+    assert!(registry["0x42::m::ENoDoc"]["doc"].is_null());
+    assert!(registry.get("0x42::m::ENotUsed").is_none());
+}
@@ -0,0 +1,79 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::{
+    command_line::compiler::ModuleNaming,
+    compiled_unit::{AnnotatedCompiledModule, AnnotatedCompiledUnit},
+    diagnostics::FilesSourceText,
+    shared::abort_messages::{ABORT_MESSAGES_EXTENSION, ABORT_MESSAGE_CODE_BASE},
+};
+use std::fs;
+
+const SOURCE: &str = r#"module 0x42::m {
+    const MAX: u64 = 100;
+
+    public fun f(x: u64) {
+        assert!(x < MAX, b"value must be below {}", MAX);
+        assert!(x != 0, b"value must not be {}", 0);
+        assert!(x != 1, 1);
+    }
+
+    public fun g(x: u64) {
+        assert!(x < MAX, b"value must be below {}", MAX);
+    }
+}
+"#;
+
+fn build(dir: &tempfile::TempDir) -> (FilesSourceText, Vec<AnnotatedCompiledUnit>) {
+    common::build(common::compiler(dir.path(), &[("m.move", SOURCE)]))
+}
+
+#[test]
+fn messages_are_allocated_codes_and_constants() {
+    let dir = tempfile::tempdir().unwrap();
+    let (_files, units) = build(&dir);
+    let AnnotatedCompiledModule {
+        named_module,
+        abort_messages,
+        ..
+    } = common::single_module(&units);
+    let catalogue: Vec<_> = abort_messages
+        .iter()
+        .map(|m| (m.code, m.message.as_str()))
+        .collect();
+    assert_eq!(
+        catalogue,
+        vec![
+            (ABORT_MESSAGE_CODE_BASE, "value must be below 100"),
+            (ABORT_MESSAGE_CODE_BASE | 1, "value must not be 0"),
+        ]
+    );
+    let constants: Vec<_> = named_module
+        .module
+        .constant_pool
+        .iter()
+        .map(|c| c.data.clone())
+        .collect();
+    for message in abort_messages {
+        let mut bytes = vec![message.message.len() as u8];
+        bytes.extend(message.message.as_bytes());
+        assert!(constants.contains(&bytes), "{:?}", constants);
+    }
+}
+
+#[test]
+fn catalogue_is_emitted_beside_the_unit() {
+    let dir = tempfile::tempdir().unwrap();
+    let (files, units) = build(&dir);
+    let out_dir = common::output(dir.path(), &[], ModuleNaming::Indexed, false, files, units);
+    let modules = out_dir.join("modules");
+    assert!(modules.join("0_m.mv").is_file());
+    let catalogue = fs::read_to_string(modules.join(format!("0_m.{ABORT_MESSAGES_EXTENSION}")));
+    let catalogue: serde_json::Value = serde_json::from_str(&catalogue.unwrap()).unwrap();
+    assert_eq!(catalogue[0]["code"], ABORT_MESSAGE_CODE_BASE);
+    assert_eq!(catalogue[0]["message"], "value must be below 100");
+    assert_eq!(catalogue[0]["line"], 5);
+    assert_eq!(catalogue[1]["message"], "value must not be 0");
+}
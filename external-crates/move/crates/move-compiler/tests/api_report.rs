@@ -0,0 +1,104 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::typing::api_report::{compare, ApiReport, ChangeKind};
+
+const V1: &str = r#"module 0x42::m {
+    struct Coin<phantom T> has store { value: u64 }
+    struct Receipt has drop { id: u64 }
+
+    public fun value<T>(c: &Coin<T>): u64 { c.value }
+    public fun split<T: store>(c: &mut Coin<T>, v: u64): Coin<T> {
+        c.value = c.value - v;
+        Coin { value: v }
+    }
+    public fun receipt(id: u64): Receipt { Receipt { id } }
+    entry fun run() {}
+    fun helper(): u64 { 0 }
+}
+"#;
+
+fn report(source: &str) -> ApiReport {
+    let dir = tempfile::tempdir().unwrap();
+    let (_files, res) = common::compiler(dir.path(), &[("m.move", source)])
+        .generate_api_report()
+        .unwrap();
+    res.unwrap()
+}
+
+fn changes(old: &str, new: &str) -> Vec<String> {
+    compare(&report(old), &report(new))
+        .iter()
+        .map(|change| change.to_string())
+        .collect()
+}
+
+#[test]
+fn report_describes_the_public_api() {
+    let report = report(V1);
+    let m = &report.modules["0x42::m"];
+    assert_eq!(
+        m.functions.keys().collect::<Vec<_>>(),
+        vec!["receipt", "run", "split", "value"]
+    );
+    let split = &m.functions["split"];
+    assert_eq!(split.parameters, vec!["&mut 0x42::m::Coin<T0>", "u64"]);
+    assert_eq!(split.return_type, vec!["0x42::m::Coin<T0>"]);
+    assert_eq!(
+        split.type_parameters[0].iter().collect::<Vec<_>>(),
+        vec!["store"]
+    );
+    let coin = &m.structs["Coin"];
+    assert!(coin.type_parameters[0].is_phantom);
+    assert_eq!(
+        coin.fields,
+        Some(vec![("value".to_string(), "u64".to_string())])
+    );
+}
+
+#[test]
+fn private_changes_and_renames_are_not_reported() {
+    let v2 = V1
+        .replace("fun helper(): u64 { 0 }", "fun helper(x: u64): u64 { x }")
+        .replace("value<T>(c: &Coin<T>)", "value<U>(c: &Coin<U>)");
+    assert!(changes(V1, &v2).is_empty());
+}
+
+#[test]
+fn compatible_changes() {
+    let v2 = V1
+        .replace("split<T: store>", "split<T>")
+        .replace("struct Receipt has drop", "struct Receipt has copy, drop")
+        .replace(
+            "entry fun run() {}",
+            "public entry fun run() {}\n    public fun new(): u64 { 0 }",
+        );
+    let changes = compare(&report(V1), &report(&v2));
+    assert!(
+        changes.iter().all(|c| c.kind == ChangeKind::Compatible),
+        "{:?}",
+        changes
+    );
+    assert_eq!(changes.len(), 4, "{:?}", changes);
+}
+
+#[test]
+fn breaking_changes() {
+    let v2 = V1
+        .replace("{ value: u64 }", "{ value: u128 }")
+        .replace("value: v }", "value: (v as u128) }")
+        .replace("c.value - v", "c.value - (v as u128)")
+        .replace("u64 { c.value }", "u64 { (c.value as u64) }")
+        .replace("public fun receipt", "public(friend) fun receipt")
+        .replace("entry fun run() {}", "");
+    assert_eq!(
+        changes(V1, &v2),
+        vec![
+            "breaking: 0x42::m::Coin: fields changed",
+            "breaking: 0x42::m::receipt: visibility changed from public to friend",
+            "breaking: 0x42::m::run: function removed",
+        ]
+    );
+}
@@ -0,0 +1,99 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::{
+    command_line::attestation::{BuildAttestation, DiagnosticsSummary},
+    diagnostics::codes::Severity,
+    Flags,
+};
+use std::{collections::BTreeMap, fs};
+
+const CLEAN: &str = r#"module 0x42::m {
+    public fun f(): u64 { 0 }
+}
+"#;
+
+// has an unused variable warning
+const WARNING: &str = r#"module 0x42::m {
+    public fun f() { let x = 0; }
+}
+"#;
+
+/// Builds `source`, attesting that no diagnostic at or above `threshold` was reported
+fn attest(source: &str, flags: Flags, threshold: Severity) -> anyhow::Result<BuildAttestation> {
+    let dir = tempfile::tempdir().unwrap();
+    let (_files, _units, attestation) = common::compiler(dir.path(), &[("m.move", source)])
+        .set_flags(flags)
+        .build_and_attest(threshold)?;
+    Ok(attestation)
+}
+
+#[test]
+fn clean_build_is_attested() {
+    let attestation = attest(CLEAN, Flags::empty(), Severity::Warning).unwrap();
+    assert_eq!(attestation.severity_threshold, "warning");
+    assert_eq!(attestation.files.len(), 1);
+    assert!(attestation
+        .files
+        .keys()
+        .all(|path| path.ends_with("m.move")));
+    assert_eq!(attestation.compiled_units.len(), 1);
+    assert!(attestation
+        .compiled_units
+        .keys()
+        .all(|name| name.ends_with("::m")));
+    assert_eq!(attestation.diagnostics, DiagnosticsSummary::default());
+    assert!(!attestation.flags.test);
+}
+
+#[test]
+fn attestation_is_deterministic() {
+    let first = attest(CLEAN, Flags::empty(), Severity::Warning).unwrap();
+    let second = attest(CLEAN, Flags::empty(), Severity::Warning).unwrap();
+    // the inputs are in different temporary directories, so only their digests match
+    assert_eq!(
+        first.files.values().collect::<Vec<_>>(),
+        second.files.values().collect::<Vec<_>>()
+    );
+    assert_eq!(first.compiled_units, second.compiled_units);
+
+    let json = first.to_json();
+    let parsed: BuildAttestation = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, first);
+    assert_eq!(parsed.to_json(), json);
+}
+
+#[test]
+fn diagnostics_at_threshold_prevent_attestation() {
+    let err = attest(WARNING, Flags::empty(), Severity::Warning).unwrap_err();
+    assert!(err.to_string().contains("severity 'warning'"));
+}
+
+#[test]
+fn diagnostics_below_threshold_are_summarized() {
+    let attestation = attest(WARNING, Flags::empty(), Severity::NonblockingError).unwrap();
+    assert_eq!(attestation.severity_threshold, "error");
+    assert_eq!(
+        attestation.diagnostics.by_severity,
+        BTreeMap::from([("warning".to_owned(), 1)])
+    );
+    assert_eq!(attestation.diagnostics.by_code.values().sum::<usize>(), 1);
+}
+
+#[test]
+fn silenced_warnings_prevent_attestation() {
+    let flags = Flags::empty().set_silence_warnings(true);
+    let err = attest(CLEAN, flags, Severity::Bug).unwrap_err();
+    assert!(err.to_string().contains("silenced warnings"));
+}
+
+#[test]
+fn attestation_is_written() {
+    let attestation = attest(CLEAN, Flags::empty(), Severity::Warning).unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("attestations").join("build.json");
+    attestation.write(&path).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), attestation.to_json());
+}
@@ -0,0 +1,96 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_binary_format::{
+    file_format_common::{VERSION_5, VERSION_6},
+    CompiledModule,
+};
+use move_compiler::{
+    command_line::{compiler::ModuleNaming, BYTECODE_VERSION_DIR},
+    compiled_unit::{check_bytecode_versions, AnnotatedCompiledUnit},
+    diagnostics::FilesSourceText,
+};
+use std::fs;
+
+const SOURCE: &str = r#"module 0x42::m {
+    public fun add(x: u64, y: u64): u64 { x + y }
+}
+"#;
+
+const SIZED_SOURCE: &str = r#"module 0x42::m {
+    const MAX: u32 = 7;
+
+    struct S has drop { small: u16, big: u64 }
+
+    public fun f(s: S, n: u8): u64 {
+        let S { small, big } = s;
+        (small as u64) + big + (MAX as u64) + ((n as u32) as u64)
+    }
+}
+"#;
+
+fn build(dir: &tempfile::TempDir, source: &str) -> (FilesSourceText, Vec<AnnotatedCompiledUnit>) {
+    common::build(common::compiler(dir.path(), &[("m.move", source)]))
+}
+
+#[test]
+fn one_artifact_set_per_version() {
+    let dir = tempfile::tempdir().unwrap();
+    let (files, units) = build(&dir, SOURCE);
+    let versions = [VERSION_5, VERSION_6];
+    let out_dir = common::output(
+        dir.path(),
+        &versions,
+        ModuleNaming::Indexed,
+        false,
+        files,
+        units,
+    );
+    for version in versions {
+        let version_dir = out_dir.join(format!("{}{}", BYTECODE_VERSION_DIR, version));
+        let bytes = fs::read(version_dir.join("modules").join("0_m.mv")).unwrap();
+        let module = CompiledModule::deserialize_with_defaults(&bytes).unwrap();
+        assert_eq!(module.version, version);
+    }
+}
+
+#[test]
+fn single_version_keeps_the_output_layout() {
+    let dir = tempfile::tempdir().unwrap();
+    let (files, units) = build(&dir, SOURCE);
+    let out_dir = common::output(
+        dir.path(),
+        &[VERSION_5],
+        ModuleNaming::Indexed,
+        false,
+        files,
+        units,
+    );
+    let bytes = fs::read(out_dir.join("modules").join("0_m.mv")).unwrap();
+    let module = CompiledModule::deserialize_with_defaults(&bytes).unwrap();
+    assert_eq!(module.version, VERSION_5);
+}
+
+#[test]
+fn sized_integers_are_reported_before_version_6() {
+    let dir = tempfile::tempdir().unwrap();
+    let (files, units) = build(&dir, SIZED_SOURCE);
+    assert!(check_bytecode_versions(&units, &[VERSION_6])
+        .unwrap()
+        .is_empty());
+    let diags = check_bytecode_versions(&units, &[VERSION_5]).unwrap();
+    // the field, the cast, and the constant, which is reported on the module
+    assert_eq!(
+        common::primary_texts(&files, diags),
+        vec!["(n as u32)", "m", "small"]
+    );
+}
+
+#[test]
+fn unknown_versions_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let (_files, units) = build(&dir, SOURCE);
+    assert!(check_bytecode_versions(&units, &[VERSION_5 - 1]).is_err());
+}
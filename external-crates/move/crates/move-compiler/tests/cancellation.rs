@@ -0,0 +1,96 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::{
+    shared::{CancellationToken, Cancelled},
+    Compiler,
+};
+use std::{sync::mpsc, time::Duration};
+
+// compiles with an unused variable warning, found by the naming pass
+const SOURCE: &str = r#"module 0x42::m {
+    public fun f() { let x = 0; }
+}
+"#;
+
+/// Returns a compiler for `SOURCE`, along with the directory holding it
+fn compiler() -> (tempfile::TempDir, Compiler<'static>) {
+    let dir = tempfile::tempdir().unwrap();
+    let compiler = common::compiler(dir.path(), &[("m.move", SOURCE)]);
+    (dir, compiler)
+}
+
+fn is_cancelled(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<Cancelled>() == Some(&Cancelled)
+}
+
+#[test]
+fn uncancelled_compilation_completes() {
+    let (_dir, compiler) = compiler();
+    let (_files, res) = compiler
+        .set_cancellation_token(CancellationToken::new())
+        .build()
+        .unwrap();
+    let (units, warnings) = res.unwrap();
+    assert_eq!(units.len(), 1);
+    assert_eq!(warnings.len(), 1);
+}
+
+#[test]
+fn cancelled_before_compiling() {
+    let token = CancellationToken::new();
+    token.cancel();
+    let (_dir, compiler) = compiler();
+    let err = compiler.set_cancellation_token(token).check().unwrap_err();
+    assert!(is_cancelled(&err));
+}
+
+#[test]
+fn cancelled_between_passes() {
+    let token = CancellationToken::new();
+    let sink_token = token.clone();
+    let (_dir, compiler) = compiler();
+    // the warning is received after the naming pass, so typing is never started
+    let err = compiler
+        .set_cancellation_token(token)
+        .set_diagnostics_sink(Box::new(move |_, _| sink_token.cancel()))
+        .build()
+        .unwrap_err();
+    assert!(is_cancelled(&err));
+}
+
+#[tokio::test]
+async fn async_compilation_completes() {
+    let (_dir, compiler) = compiler();
+    let (_files, res) = compiler.check_async().await.unwrap();
+    assert!(res.is_ok());
+}
+
+#[tokio::test]
+async fn dropping_async_compilation_cancels_it() {
+    let token = CancellationToken::new();
+    let (start_tx, start_rx) = mpsc::channel::<()>();
+    let (result_tx, result_rx) = mpsc::channel();
+    let (_dir, compiler) = compiler();
+    let compilation = compiler
+        .set_cancellation_token(token.clone())
+        .run_async(move |compiler| {
+            start_rx.recv().unwrap();
+            let res = compiler.check().map(|_| ());
+            result_tx.send(res.map_err(|e| is_cancelled(&e))).unwrap();
+            Ok(())
+        });
+    // polls the compilation once, so that it is started, and then drops it
+    assert!(tokio::time::timeout(Duration::ZERO, compilation)
+        .await
+        .is_err());
+    assert!(token.is_cancelled());
+
+    start_tx.send(()).unwrap();
+    let res = tokio::task::spawn_blocking(move || result_rx.recv().unwrap())
+        .await
+        .unwrap();
+    assert_eq!(res, Err(true));
+}
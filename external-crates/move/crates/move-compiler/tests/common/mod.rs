@@ -0,0 +1,113 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Helpers shared by the compiler tests, which compile sources written to a temporary directory
+
+// each test uses only some of the helpers
+#![allow(dead_code)]
+
+use move_binary_format::{access::ModuleAccess, file_format::Bytecode, CompiledModule};
+use move_compiler::{
+    command_line::compiler::{output_compiled_units, ModuleNaming},
+    compiled_unit::{AnnotatedCompiledModule, AnnotatedCompiledUnit, CompiledUnitEnum},
+    diagnostics::{Diagnostic, Diagnostics, FilesSourceText},
+    shared::NumericalAddress,
+    Compiler,
+};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Writes `sources`, given as file names and their contents, to `dir`, returning their paths
+pub fn write_sources(dir: &Path, sources: &[(&str, &str)]) -> Vec<String> {
+    sources
+        .iter()
+        .map(|(file, source)| {
+            let path = dir.join(file);
+            fs::write(&path, source).unwrap();
+            path.to_string_lossy().into_owned()
+        })
+        .collect()
+}
+
+/// A compiler for `sources`, written to `dir`, without any named address
+pub fn compiler(dir: &Path, sources: &[(&str, &str)]) -> Compiler<'static> {
+    Compiler::from_files(
+        write_sources(dir, sources),
+        vec![],
+        BTreeMap::<String, NumericalAddress>::new(),
+    )
+}
+
+/// Builds with `compiler`, which should not report any diagnostic
+pub fn build(compiler: Compiler) -> (FilesSourceText, Vec<AnnotatedCompiledUnit>) {
+    let (files, res) = compiler.build().unwrap();
+    let (units, warnings) = res.unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    (files, units)
+}
+
+/// Writes the compiled `units` to the `build` directory of `dir`, which is returned
+pub fn output(
+    dir: &Path,
+    bytecode_versions: &[u32],
+    module_naming: ModuleNaming,
+    emit_source_maps: bool,
+    files: FilesSourceText,
+    units: Vec<AnnotatedCompiledUnit>,
+) -> PathBuf {
+    let out_dir = dir.join("build");
+    output_compiled_units(
+        bytecode_versions,
+        module_naming,
+        emit_source_maps,
+        files,
+        units,
+        out_dir.to_str().unwrap(),
+    )
+    .unwrap();
+    out_dir
+}
+
+/// The module of `units`, which should be their only unit
+pub fn single_module(units: &[AnnotatedCompiledUnit]) -> &AnnotatedCompiledModule {
+    let [CompiledUnitEnum::Module(m)] = units else {
+        panic!("expected a single module")
+    };
+    m
+}
+
+/// The code of the function `name` of `module`
+pub fn function_code<'a>(module: &'a CompiledModule, name: &str) -> &'a [Bytecode] {
+    let def = module
+        .function_defs()
+        .iter()
+        .find(|def| {
+            module
+                .identifier_at(module.function_handle_at(def.function).name)
+                .as_str()
+                == name
+        })
+        .unwrap();
+    &def.code.as_ref().unwrap().code
+}
+
+/// The source text at the primary location of each of `diags`, sorted
+pub fn primary_texts(files: &FilesSourceText, diags: Diagnostics) -> Vec<String> {
+    let mut texts: Vec<_> = diags
+        .into_vec()
+        .iter()
+        .map(|diag| primary_text(files, diag))
+        .collect();
+    texts.sort();
+    texts
+}
+
+/// The source text at the primary location of `diag`
+pub fn primary_text(files: &FilesSourceText, diag: &Diagnostic) -> String {
+    let loc = diag.primary_loc();
+    let source = &files[&loc.file_hash()].1;
+    source[loc.usize_range()].to_string()
+}
@@ -0,0 +1,163 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::{
+    command_line::compiler::ICE_REPRO_FILE,
+    diagnostics::{codes::Severity, report_diagnostics_to_buffer, Diagnostics, FilesSourceText},
+    shared::{program_info::TypingProgramInfo, CompilationEnv, Flags},
+    typing::{ast as T, visitor::TypingVisitor},
+};
+use move_ir_types::location::Loc;
+use std::{fs, path::Path};
+
+const SOURCE: &str = r#"module 0x42::m {
+    public fun fine(): u64 { 0 }
+
+    public fun boom(): u64 { 1 }
+}
+"#;
+
+const TWO_MODULES_SOURCE: &str = r#"module 0x42::other {
+    public fun fine(): u64 { 0 }
+}
+
+#[allow(unused_function)]
+module 0x42::m {
+    public fun boom(): u64 { 1 }
+}
+"#;
+
+/// Panics on the function 'boom', after recording it as the member being compiled
+struct PanickingVisitor;
+
+impl TypingVisitor for PanickingVisitor {
+    fn visit(
+        &mut self,
+        env: &mut CompilationEnv,
+        _program_info: &TypingProgramInfo,
+        program: &mut T::Program_,
+    ) {
+        for (mident, mdef) in program.modules.key_cloned_iter() {
+            env.set_current_module(mident.loc);
+            for (name, _) in mdef.functions.key_cloned_iter() {
+                env.set_current_member(name.0.loc);
+                if name.0.value.as_str() == "boom" {
+                    panic!("ICE boom")
+                }
+            }
+        }
+    }
+}
+
+/// Panics before recording any item, so the typing pass has not left its last item as current
+struct PanickingOutsideItemsVisitor;
+
+impl TypingVisitor for PanickingOutsideItemsVisitor {
+    fn visit(
+        &mut self,
+        _env: &mut CompilationEnv,
+        _program_info: &TypingProgramInfo,
+        _program: &mut T::Program_,
+    ) {
+        panic!("ICE outside")
+    }
+}
+
+fn panic_diags(
+    dir: &Path,
+    source: &str,
+    flags: Flags,
+    visitor: impl TypingVisitor + Send + 'static,
+) -> (FilesSourceText, Diagnostics) {
+    let (files, res) = common::compiler(dir, &[("m.move", source)])
+        .set_flags(flags)
+        .add_visitor(visitor.visitor())
+        .build()
+        .unwrap();
+    let Err(diags) = res else {
+        panic!("the panic should be reported as a diagnostic")
+    };
+    (files, diags)
+}
+
+fn compile(dir: &Path, flags: Flags) -> Vec<(Severity, String, String)> {
+    let (files, diags) = panic_diags(dir, SOURCE, flags, PanickingVisitor);
+    diags
+        .into_vec()
+        .iter()
+        .map(|diag| {
+            (
+                diag.info().severity(),
+                diag.info().message().to_string(),
+                common::primary_text(&files, diag),
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn panic_is_reported_at_the_current_member() {
+    let dir = tempfile::tempdir().unwrap();
+    let diags = compile(dir.path(), Flags::empty());
+    assert_eq!(
+        diags,
+        vec![(
+            Severity::Bug,
+            "COMPILER PANICKED".to_string(),
+            "boom".to_string()
+        )]
+    );
+}
+
+#[test]
+fn panic_writes_a_repro() {
+    let dir = tempfile::tempdir().unwrap();
+    let repro_dir = dir.path().join("repro");
+    let flags = Flags::empty().set_ice_repro_dir(Some(repro_dir.to_string_lossy().into_owned()));
+    let diags = compile(dir.path(), flags);
+    assert_eq!(diags.len(), 1);
+    assert_eq!(
+        fs::read_to_string(repro_dir.join("m.move")).unwrap(),
+        SOURCE
+    );
+    let description = fs::read_to_string(repro_dir.join(ICE_REPRO_FILE)).unwrap();
+    assert!(description.contains("ICE boom"), "{}", description);
+    assert!(description.contains("m.move:4:16"), "{}", description);
+}
+
+#[test]
+fn panic_repro_is_the_module_of_the_item() {
+    let dir = tempfile::tempdir().unwrap();
+    let repro_dir = dir.path().join("repro");
+    let flags = Flags::empty().set_ice_repro_dir(Some(repro_dir.to_string_lossy().into_owned()));
+    panic_diags(dir.path(), TWO_MODULES_SOURCE, flags, PanickingVisitor);
+    // the other module is left out, and the location is in the module that was written
+    assert_eq!(
+        fs::read_to_string(repro_dir.join("m.move")).unwrap(),
+        "#[allow(unused_function)]\nmodule 0x42::m {\n    public fun boom(): u64 { 1 }\n}\n"
+    );
+    let description = fs::read_to_string(repro_dir.join(ICE_REPRO_FILE)).unwrap();
+    assert!(description.contains("m.move:3:16"), "{}", description);
+}
+
+#[test]
+fn panic_outside_any_item_has_no_location() {
+    let dir = tempfile::tempdir().unwrap();
+    let repro_dir = dir.path().join("repro");
+    let flags = Flags::empty().set_ice_repro_dir(Some(repro_dir.to_string_lossy().into_owned()));
+    let (files, diags) = panic_diags(dir.path(), SOURCE, flags, PanickingOutsideItemsVisitor);
+    let diags_vec = diags.clone().into_vec();
+    assert_eq!(diags_vec.len(), 1);
+    assert_eq!(diags_vec[0].info().severity(), Severity::Bug);
+    assert_eq!(diags_vec[0].primary_loc(), Loc::invalid());
+    // there is no item to reproduce the panic with
+    assert!(!repro_dir.exists());
+    let rendered = String::from_utf8(report_diagnostics_to_buffer(&files, diags)).unwrap();
+    assert!(
+        rendered.contains("The compiler panicked outside of any item: ICE outside"),
+        "{}",
+        rendered
+    );
+}
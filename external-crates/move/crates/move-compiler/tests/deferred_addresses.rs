@@ -0,0 +1,46 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::{
+    command_line::compiler::move_check_for_errors,
+    diagnostics::{report_diagnostics_to_buffer, Diagnostics, FilesSourceText},
+    shared::NumericalAddress,
+    PASS_PARSER,
+};
+
+const SOURCE: &str = r#"module a::m {
+    public fun f(): address { @b }
+    public fun g(): address { @c }
+    public fun h(): address { @b }
+}
+"#;
+
+fn check(resolved: &'static [(&'static str, &'static str)]) -> (FilesSourceText, Diagnostics) {
+    let dir = tempfile::tempdir().unwrap();
+    let compiler = common::compiler(dir.path(), &[("m.move", SOURCE)])
+        .add_deferred_named_addresses(["a", "b", "c"])
+        .set_named_address_resolver(Box::new(|name| {
+            let (_, value) = resolved.iter().find(|(n, _)| *n == name.as_str())?;
+            Some(NumericalAddress::parse_str(value).unwrap())
+        }));
+    let (files, res) = compiler.run::<PASS_PARSER>().unwrap();
+    (files, move_check_for_errors(res))
+}
+
+#[test]
+fn resolves_deferred_addresses() {
+    let (_files, diags) = check(&[("a", "0x42"), ("b", "0x43"), ("c", "0x44")]);
+    assert!(diags.is_empty(), "{:?}", diags);
+}
+
+#[test]
+fn reports_unresolved_deferred_addresses_together() {
+    let (files, diags) = check(&[("a", "0x42")]);
+    assert_eq!(diags.len(), 1, "{:?}", diags);
+    let rendered = String::from_utf8(report_diagnostics_to_buffer(&files, diags)).unwrap();
+    assert!(rendered.contains("error[E03001]"), "{}", rendered);
+    assert_eq!(rendered.matches("is not assigned a value").count(), 3);
+    assert!(rendered.contains("Deferred named addresses not resolved when building: 'b', 'c'"));
+}
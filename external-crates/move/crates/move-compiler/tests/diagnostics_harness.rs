@@ -0,0 +1,83 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::{
+    diagnostics::testing::{DiagnosticsTest, Position, DIAGNOSTICS_EXT},
+    shared::{NumericalAddress, PackagePaths},
+};
+use std::{collections::BTreeMap, fs};
+
+const SOURCE: &str = r#"module 0x42::m {
+    public fun f(x: u64): u64 {
+        let y = 0;
+        x
+    }
+
+    public fun g<T>(): bool {
+        true
+    }
+}
+"#;
+
+fn write_source(dir: &tempfile::TempDir) -> String {
+    common::write_sources(dir.path(), &[("m.move", SOURCE)]).remove(0)
+}
+
+fn stdlib_dependency() -> PackagePaths {
+    PackagePaths {
+        name: None,
+        paths: move_stdlib::move_stdlib_files()
+            .into_iter()
+            .map(Into::into)
+            .collect(),
+        named_address_map: BTreeMap::from([(
+            "std".into(),
+            NumericalAddress::parse_str("0x1").unwrap(),
+        )]),
+    }
+}
+
+#[test]
+fn reports_codes_and_spans() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_source(&dir);
+    let diags = DiagnosticsTest::new([path])
+        .add_dependency(stdlib_dependency())
+        .diagnostics()
+        .unwrap();
+    let reported: Vec<_> = diags
+        .iter()
+        .map(|diag| (diag.code.as_str(), diag.start, diag.end))
+        .collect();
+    let pos = |line, column| Position { line, column };
+    assert_eq!(
+        reported,
+        vec![
+            ("W09003", pos(3, 13), pos(3, 14)),
+            ("W09010", pos(7, 18), pos(7, 19)),
+        ]
+    );
+    assert!(diags.iter().all(|diag| diag.file == "m.move"));
+}
+
+#[test]
+fn checks_baseline() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = write_source(&dir);
+    let exp_path = dir.path().join("m").with_extension(DIAGNOSTICS_EXT);
+
+    fs::write(
+        &exp_path,
+        "W09003 m.move:3:13-3:14\nW09010 m.move:7:18-7:19\n",
+    )
+    .unwrap();
+    DiagnosticsTest::new([path.clone()])
+        .check(&exp_path)
+        .unwrap();
+
+    fs::write(&exp_path, "W09003 m.move:3:13-3:14\n").unwrap();
+    let err = DiagnosticsTest::new([path]).check(&exp_path).unwrap_err();
+    assert!(err.to_string().contains("W09010 m.move:7:18-7:19"));
+}
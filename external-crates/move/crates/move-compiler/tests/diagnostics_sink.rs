@@ -0,0 +1,73 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::{command_line::compiler::Pass, PASS_PARSER, PASS_TYPING};
+use std::sync::{Arc, Mutex};
+
+/// Compiles the sources, returning the messages received by the sink and the messages returned
+fn compile(sources: &[&str]) -> (Vec<(Pass, String)>, Vec<String>) {
+    let dir = tempfile::tempdir().unwrap();
+    let files: Vec<_> = (0..sources.len()).map(|i| format!("{}.move", i)).collect();
+    let sources: Vec<_> = files
+        .iter()
+        .zip(sources)
+        .map(|(file, source)| (file.as_str(), *source))
+        .collect();
+    let streamed = Arc::new(Mutex::new(vec![]));
+    let sink_streamed = streamed.clone();
+    let (_files, res) = common::compiler(dir.path(), &sources)
+        .set_diagnostics_sink(Box::new(move |pass, diag| {
+            sink_streamed
+                .lock()
+                .unwrap()
+                .push((pass, diag.info().message().to_string()))
+        }))
+        .build()
+        .unwrap();
+    let returned = match res {
+        Ok((_, warnings)) => warnings,
+        Err(errors) => errors,
+    };
+    let returned = returned
+        .into_vec()
+        .into_iter()
+        .map(|diag| diag.info().message().to_string())
+        .collect();
+    let streamed = streamed.lock().unwrap().clone();
+    (streamed, returned)
+}
+
+#[test]
+fn diagnostics_are_streamed_with_their_pass() {
+    let source = r#"module 0x42::m {
+    fun f(): u64 { let x = 0; true }
+}
+"#;
+    let (streamed, returned) = compile(&[source]);
+    assert!(!streamed.is_empty());
+    assert!(streamed.iter().all(|(pass, _)| *pass == PASS_TYPING));
+    let streamed_messages: Vec<_> = streamed.into_iter().map(|(_, msg)| msg).collect();
+    assert_eq!(streamed_messages, returned);
+}
+
+#[test]
+fn parser_diagnostics_are_streamed_per_file() {
+    let (streamed, returned) = compile(&["module 0x42::m {", "module 0x42::n { fun }"]);
+    assert_eq!(streamed.len(), 2);
+    assert!(streamed.iter().all(|(pass, _)| *pass == PASS_PARSER));
+    assert_eq!(returned.len(), 2);
+}
+
+#[test]
+fn filtered_warnings_are_not_streamed() {
+    let source = r#"module 0x42::m {
+    #[allow(unused)]
+    fun f() { let x = 0; }
+}
+"#;
+    let (streamed, returned) = compile(&[source]);
+    assert!(streamed.is_empty(), "{:?}", streamed);
+    assert!(returned.is_empty(), "{:?}", returned);
+}
@@ -0,0 +1,171 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::{
+    command_line::compiler::move_check_for_errors,
+    diagnostics::{report_diagnostics_to_buffer, Diagnostics, FilesSourceText},
+    shared::{
+        DuplicateModuleStrategy, Flags, NumericalAddress, PackageConfig, PackagePaths,
+        PackageVersion,
+    },
+    Compiler, PASS_PARSER,
+};
+use std::collections::BTreeMap;
+
+const SOURCE_MODULE: &str = r#"module 0x42::m {
+    public fun f(): u64 { 0 }
+}
+"#;
+
+const DEP_MODULE: &str = r#"module 0x42::m {
+    public fun f(): u64 { 1 }
+    public fun g(): u64 { 2 }
+}
+"#;
+
+// Calls `g`, which only the dependency defines
+const USER_MODULE: &str = r#"module 0x42::user {
+    public fun h(): u64 { 0x42::m::g() }
+}
+"#;
+
+fn package(
+    dir: &tempfile::TempDir,
+    name: &str,
+    version: Option<PackageVersion>,
+    sources: &[(&str, &str)],
+) -> PackagePaths {
+    let paths = common::write_sources(dir.path(), sources)
+        .into_iter()
+        .map(Into::into)
+        .collect();
+    let config = PackageConfig {
+        version,
+        ..PackageConfig::default()
+    };
+    PackagePaths {
+        name: Some((name.into(), config)),
+        paths,
+        named_address_map: BTreeMap::<_, NumericalAddress>::new(),
+    }
+}
+
+fn check(
+    flags: Flags,
+    source_version: Option<PackageVersion>,
+    dep_version: Option<PackageVersion>,
+) -> (FilesSourceText, Diagnostics) {
+    let dir = tempfile::tempdir().unwrap();
+    let targets = package(
+        &dir,
+        "source",
+        source_version,
+        &[("m.move", SOURCE_MODULE), ("user.move", USER_MODULE)],
+    );
+    let deps = package(&dir, "dep", dep_version, &[("dep_m.move", DEP_MODULE)]);
+    let compiler = Compiler::from_package_paths(vec![targets], vec![deps])
+        .unwrap()
+        .set_flags(flags);
+    let (files, res) = compiler.run::<PASS_PARSER>().unwrap();
+    (files, move_check_for_errors(res))
+}
+
+fn render(files: &FilesSourceText, diags: Diagnostics) -> String {
+    String::from_utf8(report_diagnostics_to_buffer(files, diags)).unwrap()
+}
+
+#[test]
+fn error_by_default() {
+    let (files, diags) = check(Flags::empty(), None, None);
+    let rendered = render(&files, diags);
+    assert!(rendered.contains("Duplicate definition for module '0x42::m'"));
+}
+
+#[test]
+fn shadowing_prefers_source_silently() {
+    let flags = Flags::empty().set_sources_shadow_deps(true);
+    let (files, diags) = check(flags, None, None);
+    let rendered = render(&files, diags);
+    // `g` is not defined by the source module
+    assert!(rendered.contains("Invalid module access"), "{}", rendered);
+    assert!(!rendered.contains("Using the source definition"));
+}
+
+#[test]
+fn prefer_source_is_reported() {
+    let flags = Flags::empty().set_duplicate_module_strategy(DuplicateModuleStrategy::PreferSource);
+    let (files, diags) = check(flags, None, None);
+    let rendered = render(&files, diags);
+    assert!(rendered.contains(
+        "Module '0x42::m' is defined in both the sources and the dependencies. Using the source \
+         definition, as set by 'prefer-source'"
+    ));
+}
+
+#[test]
+fn prefer_dep() {
+    let flags = Flags::empty().set_duplicate_module_strategy(DuplicateModuleStrategy::PreferDep);
+    let (files, diags) = check(flags, None, None);
+    let rendered = render(&files, diags);
+    assert!(!rendered.contains("error"), "{}", rendered);
+    assert!(rendered.contains("Using the dependency definition, as set by 'prefer-dep'"));
+}
+
+#[test]
+fn prefer_higher_version() {
+    let flags =
+        Flags::empty().set_duplicate_module_strategy(DuplicateModuleStrategy::PreferHigherVersion);
+
+    let (files, diags) = check(flags.clone(), Some((1, 0, 0)), Some((1, 1, 0)));
+    let rendered = render(&files, diags);
+    assert!(!rendered.contains("error"), "{}", rendered);
+    assert!(rendered.contains("Using the dependency definition"));
+
+    let (files, diags) = check(flags.clone(), Some((1, 0, 0)), Some((1, 0, 0)));
+    let rendered = render(&files, diags);
+    assert!(rendered.contains("Using the source definition"));
+
+    let (files, diags) = check(flags, Some((1, 0, 0)), None);
+    let rendered = render(&files, diags);
+    assert!(rendered.contains("Duplicate definition for module '0x42::m'"));
+    assert!(rendered.contains("do not all have a version"));
+}
+
+// Two dependencies embedding their own copy of the module, along with the sources
+fn check_diamond(flags: Flags) -> String {
+    let dir = tempfile::tempdir().unwrap();
+    let targets = package(&dir, "source", None, &[("m.move", SOURCE_MODULE)]);
+    let left = package(&dir, "left", None, &[("left_m.move", DEP_MODULE)]);
+    let right = package(&dir, "right", None, &[("right_m.move", DEP_MODULE)]);
+    let compiler = Compiler::from_package_paths(vec![targets], vec![left, right])
+        .unwrap()
+        .set_flags(flags);
+    let (files, res) = compiler.run::<PASS_PARSER>().unwrap();
+    render(&files, move_check_for_errors(res))
+}
+
+#[test]
+fn audit_reports_every_definition() {
+    let rendered = check_diamond(Flags::empty().set_audit_duplicate_modules(true));
+    assert!(
+        rendered.contains(
+            "Module '0x42::m' is defined by 3 packages. This definition is from package \
+             'source' (target package paths entry 0)"
+        ),
+        "{}",
+        rendered
+    );
+    assert!(rendered
+        .contains("Also defined here, from package 'left' (dependency package paths entry 1)"));
+    assert!(rendered
+        .contains("Also defined here, from package 'right' (dependency package paths entry 2)"));
+}
+
+#[test]
+fn audit_is_off_by_default() {
+    let rendered = check_diamond(Flags::empty());
+    assert!(rendered.contains("Duplicate definition for module '0x42::m'"));
+    assert!(!rendered.contains("defined by 3 packages"), "{}", rendered);
+}
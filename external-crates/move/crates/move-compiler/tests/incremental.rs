@@ -0,0 +1,131 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::{
+    command_line::incremental::CheckedProgram,
+    diagnostics::{Diagnostics, FilesSourceText},
+    shared::{Flags, PackagePaths},
+};
+use move_symbol_pool::Symbol;
+use std::{collections::BTreeSet, path::Path};
+
+const LIB: &str = r#"module 0x1::lib {
+    public fun one(): u64 { 1 }
+}
+"#;
+
+const APP: &str = r#"module 0x2::app {
+    public fun two(): u64 { 0x1::lib::one() + 1 }
+}
+"#;
+
+const USER: &str = r#"module 0x4::user {
+    public fun four(): u64 { 0x2::app::two() + 2 }
+}
+"#;
+
+const OTHER: &str = r#"module 0x3::other {
+    public fun three(): u64 { 3 }
+}
+"#;
+
+fn write(dir: &Path, name: &str, source: &str) -> String {
+    let file = format!("{}.move", name);
+    common::write_sources(dir, &[(&file, source)]).remove(0)
+}
+
+fn checked_program(dir: &Path) -> CheckedProgram {
+    let paths = vec![
+        Symbol::from(write(dir, "lib", LIB)),
+        Symbol::from(write(dir, "app", APP)),
+        Symbol::from(write(dir, "user", USER)),
+        Symbol::from(write(dir, "other", OTHER)),
+    ];
+    let targets = vec![PackagePaths {
+        name: None,
+        paths,
+        named_address_map: Default::default(),
+    }];
+    match CheckedProgram::new(targets, Flags::empty()).unwrap() {
+        Ok(program) => program,
+        Err((_, diags)) => panic!("{:?}", diags),
+    }
+}
+
+fn file_names(files: &FilesSourceText) -> BTreeSet<String> {
+    file_names_of(files.values().map(|(name, _)| name.to_string()))
+}
+
+fn file_names_of(paths: impl IntoIterator<Item = String>) -> BTreeSet<String> {
+    paths
+        .into_iter()
+        .map(|path| {
+            Path::new(&path)
+                .file_stem()
+                .unwrap()
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect()
+}
+
+fn messages(diags: Diagnostics) -> Vec<String> {
+    diags
+        .into_vec()
+        .into_iter()
+        .map(|diag| diag.info().message().to_string())
+        .collect()
+}
+
+#[test]
+fn dependents_are_checked_again() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = checked_program(dir.path());
+    let lib = write(
+        dir.path(),
+        "lib",
+        "module 0x1::lib { public fun one(): bool { true } }",
+    );
+    let (files, diags) = program.check_file(lib).unwrap();
+    // including the modules using the dependents
+    assert_eq!(
+        file_names(&files),
+        BTreeSet::from(["lib".to_string(), "app".to_string(), "user".to_string()])
+    );
+    // the errors are in the module using the modified function
+    let diag_files: BTreeSet<_> = diags
+        .clone()
+        .into_vec()
+        .iter()
+        .map(|diag| files[&diag.primary_loc().file_hash()].0.to_string())
+        .collect();
+    assert_eq!(
+        file_names_of(diag_files),
+        BTreeSet::from(["app".to_string()])
+    );
+    assert!(messages(diags).contains(&"incompatible types".to_string()));
+}
+
+#[test]
+fn unaffected_modules_are_reused() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = checked_program(dir.path());
+    let other = write(
+        dir.path(),
+        "other",
+        "module 0x3::other { public fun three(): u64 { 0x1::lib::one() + 2 } }",
+    );
+    let (files, diags) = program.check_file(other).unwrap();
+    assert_eq!(file_names(&files), BTreeSet::from(["other".to_string()]));
+    assert!(diags.is_empty(), "{:?}", diags);
+}
+
+#[test]
+fn unknown_files_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let program = checked_program(dir.path());
+    let unknown = write(dir.path(), "unknown", OTHER);
+    assert!(program.check_file(unknown).is_err());
+}
@@ -0,0 +1,106 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_binary_format::{access::ModuleAccess, file_format::Bytecode, CompiledModule};
+use move_compiler::{
+    compiled_unit::{verify_units, AnnotatedCompiledUnit},
+    shared::Flags,
+    Compiler,
+};
+
+const SOURCE: &str = r#"module 0x42::m {
+    const SCALE: u64 = 10;
+
+    struct Point has copy, drop { x: u64, y: u64 }
+
+    fun scale(x: u64): u64 { x * SCALE }
+
+    fun point(x: u64, y: u64): Point { Point { x: scale(x), y } }
+
+    fun swap(p: Point): (u64, u64) {
+        let Point { x, y } = p;
+        (y, x)
+    }
+
+    fun check(x: u64) { assert!(x > 0, 1) }
+
+    fun big(x: u64): u64 {
+        x + 1 + 2 + 3 + 4 + 5 + 6 + 7 + 8 + 9 + 10 + 11 + 12 + 13 + 14 + 15 + 16
+    }
+
+    fun count(n: u64): u64 {
+        let i = 0;
+        while (i < n) i = i + 1;
+        i
+    }
+
+    public fun f(a: u64, b: u64): u64 {
+        check(a);
+        let p = point(a, b);
+        let (y, x) = swap(p);
+        let i = 0;
+        while (i < 3) {
+            x = scale(x);
+            i = i + 1;
+        };
+        big(x + y) + count(a)
+    }
+}
+"#;
+
+fn build(inline_functions: bool) -> CompiledModule {
+    let dir = tempfile::tempdir().unwrap();
+    let flags = Flags::empty().set_inline_functions(inline_functions);
+    let compiler = common::compiler(dir.path(), &[("m.move", SOURCE)]).set_flags(flags);
+    let (_files, units) = common::build(compiler);
+    assert!(verify_units(&units).is_empty());
+    common::single_module(&units).named_module.module.clone()
+}
+
+/// The names of the functions called by `f`
+fn callees(module: &CompiledModule) -> Vec<String> {
+    common::function_code(module, "f")
+        .iter()
+        .filter_map(|instr| match instr {
+            Bytecode::Call(idx) => {
+                let handle = module.function_handle_at(*idx);
+                Some(module.identifier_at(handle.name).to_string())
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn calls_are_kept_by_default() {
+    let module = build(false);
+    assert_eq!(
+        callees(&module),
+        vec!["check", "point", "swap", "scale", "big", "count"]
+    );
+}
+
+#[test]
+fn small_private_functions_are_inlined() {
+    let module = build(true);
+    // 'check' and 'count' have more than one block, and 'big' exceeds the size budget. The call
+    // of 'scale' in 'point' is nested in a pack, but the one in the loop is inlined
+    assert_eq!(callees(&module), vec!["check", "scale", "big", "count"]);
+}
+
+#[test]
+fn stdlib_is_valid_with_inlining() {
+    let (_files, res) = Compiler::from_files(
+        move_stdlib::move_stdlib_files(),
+        vec![],
+        move_stdlib::move_stdlib_named_addresses(),
+    )
+    .set_flags(Flags::empty().set_inline_functions(true))
+    .build()
+    .unwrap();
+    let (units, _warnings) = res.unwrap();
+    let units: Vec<AnnotatedCompiledUnit> = units;
+    assert!(verify_units(&units).is_empty());
+}
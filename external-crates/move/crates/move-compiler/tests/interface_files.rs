@@ -0,0 +1,108 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::{
+    command_line::compiler::ModuleNaming, interface_generator::write_file_to_string,
+};
+use move_core_types::language_storage::ModuleId;
+use std::{collections::BTreeMap, fs, path::Path};
+
+const SOURCE: &str = r#"module 0x42::m {
+    /// A coin
+    /// with a value
+    struct Coin has store { value: u64 }
+
+    /** Not much */
+    struct Empty has drop {}
+
+    /* not a doc comment */
+    struct Plain has drop {}
+
+    #[allow(unused_variable)]
+    /// Splits `coin`
+    public fun split(coin: &mut Coin, amount: u64, _: bool): Coin {
+        coin.value = coin.value - amount;
+        Coin { value: amount }
+    }
+
+    public fun join(coin: &mut Coin, other: Coin) {
+        let Coin { value } = other;
+        coin.value = coin.value + value
+    }
+}
+"#;
+
+/// Compiles the module, with its source map if `emit_source_maps`, and returns the interface
+/// generated from the output
+fn interface(dir: &Path, emit_source_maps: bool) -> String {
+    let (files, units) = common::build(common::compiler(dir, &[("m.move", SOURCE)]));
+    let out_dir = common::output(
+        dir,
+        &[],
+        ModuleNaming::Indexed,
+        emit_source_maps,
+        files,
+        units,
+    );
+    let module_path = out_dir.join("modules").join("0_m.mv");
+    let (_id, interface) = write_file_to_string(
+        &BTreeMap::<ModuleId, String>::new(),
+        module_path.to_str().unwrap(),
+    )
+    .unwrap();
+    interface
+}
+
+#[test]
+fn interface_without_source_map() {
+    let dir = tempfile::tempdir().unwrap();
+    let interface = interface(dir.path(), false);
+    assert!(interface.contains("fun split(a0: &mut m::Coin, a1: u64, a2: bool): m::Coin;"));
+    assert!(!interface.contains("///"));
+}
+
+#[test]
+fn interface_with_source_map() {
+    let dir = tempfile::tempdir().unwrap();
+    let interface = interface(dir.path(), true);
+    // the source is not next to the module, so only the parameter names are recovered
+    assert!(interface.contains("fun split(coin: &mut m::Coin, amount: u64, a2: bool): m::Coin;"));
+    assert!(interface.contains("fun join(coin: &mut m::Coin, other: m::Coin);"));
+    assert!(!interface.contains("///"));
+}
+
+#[test]
+fn interface_with_source_map_and_source() {
+    let dir = tempfile::tempdir().unwrap();
+    let interface = interface(dir.path(), true);
+    // the source is found next to the module
+    let modules = dir.path().join("build").join("modules");
+    fs::write(modules.join("0_m.move"), SOURCE).unwrap();
+    let (_id, interface_with_docs) = write_file_to_string(
+        &BTreeMap::<ModuleId, String>::new(),
+        modules.join("0_m.mv").to_str().unwrap(),
+    )
+    .unwrap();
+    assert_ne!(interface, interface_with_docs);
+    assert!(interface_with_docs
+        .contains("    /// A coin\n    /// with a value\n    struct Coin has store {"));
+    assert!(interface_with_docs.contains("    /** Not much */\n    struct Empty has drop {"));
+    assert!(!interface_with_docs.contains("not a doc comment"));
+    assert!(interface_with_docs
+        .contains("    /// Splits `coin`\n    native public fun split(coin: &mut m::Coin"));
+
+    // a source that is not the one of the source map is ignored
+    fs::write(
+        modules.join("0_m.move"),
+        SOURCE.replace("A coin", "A token"),
+    )
+    .unwrap();
+    let (_id, interface_other_source) = write_file_to_string(
+        &BTreeMap::<ModuleId, String>::new(),
+        modules.join("0_m.mv").to_str().unwrap(),
+    )
+    .unwrap();
+    assert_eq!(interface, interface_other_source);
+}
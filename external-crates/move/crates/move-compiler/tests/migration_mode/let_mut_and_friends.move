@@ -0,0 +1,26 @@
+module a::m {
+    friend a::n;
+
+    public(friend) fun inc(x: u64): u64 {
+        x = x + 1;
+        x
+    }
+
+    public fun no_change(x: u64): u64 {
+        let y = x;
+        y
+    }
+
+    public fun borrow_mut(): u64 {
+        let v = 0;
+        let r = &mut v;
+        *r = 1;
+        v
+    }
+}
+
+module a::n {
+    public fun call(): u64 {
+        a::m::inc(0)
+    }
+}
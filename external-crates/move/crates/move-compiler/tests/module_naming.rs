@@ -0,0 +1,56 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::command_line::compiler::{ModuleNaming, MODULE_MANIFEST_FILE};
+use std::{fs, path::Path};
+
+// 'a' uses 'b', so 'b' comes first in dependency order
+const SOURCE: &str = r#"module 0x42::a {
+    public fun f(): u64 { 0x42::b::g() }
+}
+
+module 0x42::b {
+    public fun g(): u64 { 1 }
+}
+"#;
+
+fn output(dir: &Path, module_naming: ModuleNaming) -> Vec<String> {
+    let (files, units) = common::build(common::compiler(dir, &[("m.move", SOURCE)]));
+    let out_dir = common::output(dir, &[], module_naming, false, files, units);
+    let mut names: Vec<_> = fs::read_dir(out_dir.join("modules"))
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn indexed_module_names() {
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(
+        output(dir.path(), ModuleNaming::Indexed),
+        ["0_b.mv", "1_a.mv"]
+    );
+    assert!(!dir.path().join("build").join(MODULE_MANIFEST_FILE).exists());
+}
+
+#[test]
+fn qualified_module_names_with_manifest() {
+    let dir = tempfile::tempdir().unwrap();
+    assert_eq!(
+        output(dir.path(), ModuleNaming::Qualified),
+        ["0x42__a.mv", "0x42__b.mv"]
+    );
+    let manifest = fs::read_to_string(dir.path().join("build").join(MODULE_MANIFEST_FILE)).unwrap();
+    let manifest: serde_json::Value = serde_json::from_str(&manifest).unwrap();
+    assert_eq!(
+        manifest,
+        serde_json::json!([
+            { "module": "0x42::b", "file": "0x42__b.mv", "dependencies": [] },
+            { "module": "0x42::a", "file": "0x42__a.mv", "dependencies": ["0x42::b"] },
+        ])
+    );
+}
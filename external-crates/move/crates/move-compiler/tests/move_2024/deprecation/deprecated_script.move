@@ -0,0 +1,16 @@
+module a::m {
+    public fun foo() {}
+}
+
+script {
+    fun main() {
+        a::m::foo()
+    }
+}
+
+#[allow(deprecated)]
+script {
+    fun allowed() {
+        a::m::foo()
+    }
+}
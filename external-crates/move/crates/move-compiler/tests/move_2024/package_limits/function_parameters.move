@@ -0,0 +1,5 @@
+module a::m {
+    public fun few(a: u64, b: u64): u64 { a + b }
+
+    public fun many(a: u64, b: u64, c: u64): u64 { a + b + c }
+}
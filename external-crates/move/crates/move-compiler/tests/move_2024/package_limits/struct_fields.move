@@ -0,0 +1,5 @@
+module a::m {
+    public struct Small has drop { a: u64, b: u64 }
+
+    public struct Wide has drop { a: u64, b: u64, c: u64 }
+}
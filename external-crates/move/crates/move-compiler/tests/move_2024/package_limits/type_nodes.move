@@ -0,0 +1,9 @@
+module a::m {
+    public struct Shallow has drop { v: vector<vector<u64>> }
+
+    public struct Deep has drop { v: vector<vector<vector<u64>>> }
+
+    public fun shallow(v: vector<vector<u64>>): vector<vector<u64>> { v }
+
+    public fun deep(v: vector<vector<vector<u64>>>): vector<vector<vector<u64>>> { v }
+}
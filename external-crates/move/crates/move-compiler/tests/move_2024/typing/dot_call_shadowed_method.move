@@ -0,0 +1,35 @@
+module a::t {
+    public struct X has drop {}
+
+    public fun value(_: &X): u64 { 0 }
+}
+
+module a::other {
+    use a::t::X;
+
+    public fun value(_: &X): u64 { 1 }
+}
+
+module a::m {
+    use a::t::X;
+    use fun a::other::value as X.value;
+
+    fun call(x: &X): u64 {
+        x.value()
+    }
+
+    fun call_again(x: &X): u64 {
+        x.value() + x.value()
+    }
+
+    fun nested(x: &X): u64 {
+        use fun a::t::value as X.value;
+        x.value()
+    }
+
+    #[allow(shadowed_method)]
+    fun allowed(x: &X): u64 {
+        use fun a::t::value as X.value;
+        x.value()
+    }
+}
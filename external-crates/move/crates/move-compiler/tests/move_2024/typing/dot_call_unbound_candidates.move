@@ -0,0 +1,17 @@
+module a::t {
+    public struct X has drop {}
+}
+
+module a::helpers {
+    use a::t::X;
+
+    public fun describe(_: &X): u64 { 0 }
+}
+
+module a::m {
+    use a::t::X;
+
+    fun call(x: &X): u64 {
+        x.describe()
+    }
+}
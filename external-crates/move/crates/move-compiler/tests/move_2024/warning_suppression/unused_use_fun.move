@@ -5,7 +5,7 @@ module a::m {
     public fun foo(_: &X) {}
 }
 
-#[allow(unused_use)]
+#[allow(unused_use, deprecated)]
 script {
     use fun a::m::foo as a::m::X.f;
     fun main() {}
@@ -24,7 +24,7 @@ module a::m2 {
     };
 }
 
-#[allow(unused)]
+#[allow(unused, deprecated)]
 script {
     use fun a::m::foo as a::m::X.f;
     fun main2() {}
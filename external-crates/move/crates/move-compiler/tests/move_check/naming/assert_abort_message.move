@@ -0,0 +1,21 @@
+module 0x42::m {
+    const MAX: u64 = 100;
+    const NAME: vector<u8> = b"coin";
+    const COMPUTED: u64 = 1 + 1;
+
+    public fun ok(x: u64) {
+        assert!(x < MAX, b"value must be below {}", MAX);
+        assert!(x != 0, b"no {} for {}", NAME, @0x42);
+        assert!(x != 1, b"literal {{braces}} and {}", true);
+        assert!(x != 2, b"value must be below {}", MAX);
+    }
+
+    public fun invalid(x: u64) {
+        assert!(x < MAX, b"value must be below {}", x);
+        assert!(x < MAX, b"computed {}", COMPUTED);
+        assert!(x < MAX, b"too few {} {}", MAX);
+        assert!(x < MAX, b"too many", MAX);
+        assert!(x < MAX, b"unmatched {", MAX);
+        assert!(x < MAX, x"ff");
+    }
+}
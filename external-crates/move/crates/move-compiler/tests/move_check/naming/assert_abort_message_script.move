@@ -0,0 +1,5 @@
+script {
+    fun main(x: u64) {
+        assert!(x < 100, b"value must be below {}", 100);
+    }
+}
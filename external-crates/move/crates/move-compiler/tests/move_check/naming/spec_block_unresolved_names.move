@@ -0,0 +1,43 @@
+module 0x42::m {
+    const C: u64 = 0;
+
+    spec module {
+        global counter: u64;
+    }
+
+    fun stale(x: u64): u64 {
+        spec {
+            assert x > 0;
+            assert renamed > 0;
+            assert counter >= C && counter <= MAX_U64;
+        };
+        x
+    }
+
+    fun lets(x: u64): u64 {
+        spec {
+            let y = x + 1;
+            assert y > x;
+            assert z > x && removed == 0;
+        };
+        x
+    }
+
+    fun loop_invariant(v: u64) {
+        let i = 0;
+        while ({
+            spec { invariant i <= old_v; };
+            i < v
+        }) {
+            i = i + 1;
+        }
+    }
+
+    #[allow(dead_spec)]
+    fun allowed(x: u64): u64 {
+        spec {
+            assert gone == x;
+        };
+        x
+    }
+}
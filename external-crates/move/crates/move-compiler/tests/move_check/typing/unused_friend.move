@@ -0,0 +1,23 @@
+module 0x42::m {
+    friend 0x42::used;
+    friend 0x42::unused;
+    friend 0x42::calls_public_only;
+
+    public(friend) fun f() {}
+    public fun g() {}
+}
+
+#[allow(unused_friend)]
+module 0x42::n {
+    friend 0x42::unused;
+}
+
+module 0x42::used {
+    public fun call() { 0x42::m::f() }
+}
+
+module 0x42::unused {}
+
+module 0x42::calls_public_only {
+    public fun call() { 0x42::m::g() }
+}
@@ -0,0 +1,17 @@
+address 0x1 {
+module M {
+    struct S has drop {}
+
+    #[random_test]
+    fun struct_param(s: S) { s; }
+
+    #[random_test]
+    fun reference_param(x: &u64) { x; }
+
+    #[random_test]
+    fun vector_of_struct_param(v: vector<S>) { v; }
+
+    #[test, random_test]
+    fun both_test_and_random_test(x: u64) { x; }
+}
+}
@@ -0,0 +1,22 @@
+module 0x2::m {
+    const ADMIN: address = @0x42;
+}
+
+// check that constants can be assigned to test parameters
+module 0x1::A {
+    #[test_only]
+    use 0x2::m;
+
+    const AMOUNT: u64 = 10 * 10;
+    const SMALL: u8 = 255;
+    const FLAG: bool = true;
+
+    #[test(x = AMOUNT, y = SMALL, b = FLAG)]
+    fun use_local(x: u64, y: u8, b: bool) { x; y; b; }
+
+    #[test(a = m::ADMIN, s = m::ADMIN)]
+    fun use_through_alias(a: address, s: signer) { a; s; }
+
+    #[test(x = 0x1::A::AMOUNT)]
+    fun use_explicit(x: u64) { x; }
+}
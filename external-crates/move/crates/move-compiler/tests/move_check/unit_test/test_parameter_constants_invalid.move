@@ -0,0 +1,21 @@
+// check invalid constant usage in test parameters
+module 0x1::A {
+    const AMOUNT: u64 = 10;
+    const BYTES: vector<u8> = b"abc";
+
+    #[test(x = AMOUNT)]
+    fun wrong_type(x: u8) { x; }
+
+    #[test(s = AMOUNT)]
+    fun number_for_signer(s: signer) { s; }
+
+    #[test(x = BYTES)]
+    fun vector_value(x: vector<u8>) { x; }
+
+    #[test(x = MISSING)]
+    fun unbound_constant(x: u64) { x; }
+
+    #[test]
+    #[expected_failure(abort_code = MISSING)]
+    fun unbound_abort_code() { }
+}
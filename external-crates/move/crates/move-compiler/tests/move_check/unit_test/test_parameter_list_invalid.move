@@ -0,0 +1,15 @@
+address 0x1 {
+module M {
+    #[test(x = [])]
+    fun empty_list(x: u64) { x; }
+
+    #[test(x = [0, 256])]
+    fun out_of_range(x: u8) { x; }
+
+    #[test(x = [1, true])]
+    fun wrong_type(x: u64) { x; }
+
+    #[test(s = [0, 1])]
+    fun number_for_signer(s: signer) { s; }
+}
+}
@@ -12,7 +12,7 @@ use move_compiler::{
     command_line::compiler::move_check_for_errors,
     diagnostics::*,
     editions::{Edition, Flavor},
-    shared::{Flags, NumericalAddress, PackageConfig, PackagePaths},
+    shared::{Flags, NumericalAddress, PackageConfig, PackageLimits, PackagePaths},
     Compiler, PASS_PARSER,
 };
 
@@ -22,9 +22,12 @@ const KEEP_TMP: &str = "KEEP";
 const TEST_EXT: &str = "unit_test";
 const VERIFICATION_EXT: &str = "verification";
 const UNUSED_EXT: &str = "unused";
+const MIGRATION_EXT: &str = "migration";
 
 const SUI_MODE_DIR: &str = "sui_mode";
 const MOVE_2024_DIR: &str = "move_2024";
+const MIGRATION_DIR: &str = "migration_mode";
+const PACKAGE_LIMITS_DIR: &str = "package_limits";
 
 fn default_testing_addresses(flavor: Flavor) -> BTreeMap<String, NumericalAddress> {
     let mut mapping = vec![
@@ -47,6 +50,16 @@ fn default_testing_addresses(flavor: Flavor) -> BTreeMap<String, NumericalAddres
         .collect()
 }
 
+/// Small limits, so that a test can exceed them with a few definitions
+fn testing_package_limits() -> PackageLimits {
+    PackageLimits {
+        max_function_parameters: Some(2),
+        max_struct_fields: Some(2),
+        max_type_nodes: Some(3),
+        max_package_size: None,
+    }
+}
+
 fn move_check_testsuite(path: &Path) -> datatest_stable::Result<()> {
     let flavor = if path.components().any(|c| c.as_os_str() == SUI_MODE_DIR) {
         Flavor::Sui
@@ -55,14 +68,28 @@ fn move_check_testsuite(path: &Path) -> datatest_stable::Result<()> {
     };
     let edition = if path.components().any(|c| c.as_os_str() == MOVE_2024_DIR) {
         Edition::E2024_ALPHA
+    } else if path.components().any(|c| c.as_os_str() == MIGRATION_DIR) {
+        Edition::E2024_MIGRATION
     } else {
         Edition::default()
     };
+    let limits = if path
+        .components()
+        .any(|c| c.as_os_str() == PACKAGE_LIMITS_DIR)
+    {
+        testing_package_limits()
+    } else {
+        PackageLimits::default()
+    };
     let config = PackageConfig {
         flavor,
         edition,
+        limits,
         ..PackageConfig::default()
     };
+    if edition == Edition::E2024_MIGRATION {
+        run_migration_test(path, config.clone())?;
+    }
     testsuite(path, config)
 }
 
@@ -152,6 +179,62 @@ fn testsuite(path: &Path, mut config: PackageConfig) -> datatest_stable::Result<
     Ok(())
 }
 
+// Checks the source produced by applying the migration changes against `path.migration`
+fn run_migration_test(path: &Path, default_config: PackageConfig) -> anyhow::Result<()> {
+    let exp_path = path.with_extension(MIGRATION_EXT);
+    let targets = vec![PackagePaths {
+        name: None,
+        paths: vec![path.to_str().unwrap().to_owned()],
+        named_address_map: default_testing_addresses(default_config.flavor),
+    }];
+    let migration = Compiler::from_package_paths(targets, vec![])
+        .unwrap()
+        .set_default_config(default_config)
+        .generate_migration_patch(None)?;
+    let mut output = migration.render_output();
+    assert!(output.len() <= 1);
+    let migrated = output.pop_first().map(|(_, contents)| contents);
+
+    if read_env_update_baseline() {
+        match &migrated {
+            Some(contents) => fs::write(&exp_path, contents)?,
+            None if exp_path.is_file() => fs::remove_file(&exp_path)?,
+            None => (),
+        }
+        return Ok(());
+    }
+
+    match (migrated, exp_path.is_file()) {
+        (None, false) => Ok(()),
+        (Some(contents), false) => {
+            let msg = format!(
+                "Expected no migration changes. Migrated source:\n{}",
+                contents
+            );
+            anyhow::bail!(add_update_baseline_fix(msg))
+        }
+        (None, true) => {
+            let msg = format!(
+                "Expected migration changes. Expected source:\n{}",
+                fs::read_to_string(&exp_path)?
+            );
+            anyhow::bail!(add_update_baseline_fix(msg))
+        }
+        (Some(contents), true) => {
+            let expected = fs::read_to_string(&exp_path)?;
+            if contents != expected {
+                let msg = format!(
+                    "Expected migrated source differs from actual migrated source:\n{}",
+                    format_diff(expected, contents),
+                );
+                anyhow::bail!(add_update_baseline_fix(msg))
+            } else {
+                Ok(())
+            }
+        }
+    }
+}
+
 // Runs all tests under the test/testsuite directory.
 pub fn run_test(
     path: &Path,
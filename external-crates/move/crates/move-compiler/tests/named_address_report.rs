@@ -0,0 +1,87 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::{
+    diagnostics::report_diagnostics_to_buffer,
+    shared::{NumericalAddress, PackageConfig, PackagePaths},
+    Compiler,
+};
+use move_symbol_pool::Symbol;
+use std::collections::BTreeMap;
+
+// fails to type check, naming the type of `a`, whose name conflicts with `b`
+const A_SOURCE: &str = r#"module a::m {
+    struct S {}
+    fun f(): S { 0 }
+}
+"#;
+
+const B_SOURCE: &str = r#"module std::n {}
+"#;
+
+/// Two packages: `A` with `a = b = 0x42`, and `B` with `a = 0x43`. Both have `std = 0x1`.
+fn compiler(dir: &tempfile::TempDir) -> Compiler<'static> {
+    let package = |name: &str, source: &str, addresses: &[(&str, &str)]| {
+        let file = format!("{}.move", name);
+        PackagePaths {
+            name: Some((Symbol::from(name), PackageConfig::default())),
+            paths: common::write_sources(dir.path(), &[(&file, source)]),
+            named_address_map: addresses
+                .iter()
+                .map(|(n, v)| (n.to_string(), NumericalAddress::parse_str(v).unwrap()))
+                .collect::<BTreeMap<_, _>>(),
+        }
+    };
+    let a = package(
+        "A",
+        A_SOURCE,
+        &[("a", "0x42"), ("b", "0x42"), ("std", "0x1")],
+    );
+    let b = package("B", B_SOURCE, &[("a", "0x43"), ("std", "0x1")]);
+    Compiler::from_package_paths(vec![a, b], vec![]).unwrap()
+}
+
+#[test]
+fn report_explains_conflicts() {
+    let dir = tempfile::tempdir().unwrap();
+    let report = compiler(&dir).named_address_report();
+    let conflicts: Vec<_> = report
+        .conflicts()
+        .into_iter()
+        .map(|n| n.to_string())
+        .collect();
+    assert_eq!(conflicts, vec!["a", "b"]);
+
+    let a = &report.entries[0];
+    assert_eq!(
+        (a.name.as_str(), a.value.to_hex_literal()),
+        ("a", "0x42".to_owned())
+    );
+    assert_eq!(a.packages.len(), 1);
+    assert!(a.is_conflict());
+    let std = report.entries.last().unwrap();
+    assert_eq!(std.name.as_str(), "std");
+    assert_eq!(std.packages.len(), 2);
+    assert!(!std.is_conflict());
+
+    let expected = "\
+name  value  packages  conflict
+a     0x42   A         yes (also assigned 0x43; same value as b)
+a     0x43   B         yes (also assigned 0x42)
+b     0x42   A         yes (same value as a)
+std   0x1    A, B      no
+";
+    assert_eq!(report.to_string(), expected);
+}
+
+#[test]
+fn conflicts_are_shown_in_diagnostics() {
+    let dir = tempfile::tempdir().unwrap();
+    let (files, res) = compiler(&dir).check().unwrap();
+    let rendered =
+        String::from_utf8(report_diagnostics_to_buffer(&files, res.unwrap_err())).unwrap();
+    // conflicting names are displayed along with their value
+    assert!(rendered.contains("(a=0x42)::m::S"), "{}", rendered);
+}
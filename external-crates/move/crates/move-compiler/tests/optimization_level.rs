@@ -0,0 +1,60 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_binary_format::{file_format::Bytecode, CompiledModule};
+use move_compiler::{compiled_unit::verify_units, shared::Flags};
+
+const SOURCE: &str = r#"module 0x42::m {
+    public fun copies(x: u64): u64 {
+        let y = x;
+        let z = y;
+        let w = z + y;
+        let v = w;
+        v + z
+    }
+
+    public fun branches(x: u64): u64 {
+        let r = 0;
+        if (!(x > 10)) r = r + 1 else r = r + 2;
+        r
+    }
+}
+"#;
+
+fn build(level: u8) -> CompiledModule {
+    let dir = tempfile::tempdir().unwrap();
+    let compiler = common::compiler(dir.path(), &[("m.move", SOURCE)])
+        .set_flags(Flags::empty().set_optimization_level(level));
+    let (_files, units) = common::build(compiler);
+    assert!(verify_units(&units).is_empty());
+    common::single_module(&units).named_module.module.clone()
+}
+
+fn code(module: &CompiledModule, name: &str) -> Vec<Bytecode> {
+    common::function_code(module, name).to_vec()
+}
+
+#[test]
+fn copies_are_propagated_and_dead_stores_removed() {
+    let unoptimized = code(&build(0), "copies");
+    let optimized = code(&build(1), "copies");
+    let stores = |code: &[Bytecode]| {
+        code.iter()
+            .filter(|instr| matches!(instr, Bytecode::StLoc(_)))
+            .count()
+    };
+    // only the sum is stored
+    assert_eq!(stores(&unoptimized), 2, "{:?}", unoptimized);
+    assert_eq!(stores(&optimized), 1, "{:?}", optimized);
+    assert!(optimized.len() < unoptimized.len());
+}
+
+#[test]
+fn negated_branches_are_simplified() {
+    let unoptimized = code(&build(0), "branches");
+    let optimized = code(&build(1), "branches");
+    assert!(unoptimized.contains(&Bytecode::Not));
+    assert!(!optimized.contains(&Bytecode::Not), "{:?}", optimized);
+}
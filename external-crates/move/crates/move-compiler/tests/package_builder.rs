@@ -0,0 +1,102 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::{
+    diagnostics::codes::{Category, WarningFilter},
+    editions::Edition,
+    shared::{
+        package_builder::{PackageBuilder, PackageConfigError},
+        NumericalAddress,
+    },
+    Compiler,
+};
+
+const SOURCE: &str = r#"module a::m {
+    public(package) fun f(x: u64): u64 { let unused = 0; x }
+}
+"#;
+
+fn address(value: &str) -> NumericalAddress {
+    NumericalAddress::parse_str(value).unwrap()
+}
+
+#[test]
+fn configured_package_compiles() {
+    let dir = tempfile::tempdir().unwrap();
+    let package = PackageBuilder::new("pkg")
+        .edition(Edition::E2024_ALPHA)
+        .warning_filter(WarningFilter::Category {
+            prefix: None,
+            category: Category::UnusedItem as u8,
+            name: None,
+        })
+        .paths(common::write_sources(dir.path(), &[("m.move", SOURCE)]))
+        .named_address("a", address("0x42"));
+    common::build(Compiler::from_packages(vec![package], vec![]).unwrap());
+}
+
+#[test]
+fn duplicate_paths_are_rejected() {
+    let err = PackageBuilder::new("pkg")
+        .path("m.move")
+        .path("m.move")
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        PackageConfigError::DuplicatePath {
+            package: Some("pkg".into()),
+            path: "m.move".into(),
+        }
+    );
+    assert_eq!(err.to_string(), "Duplicate path 'm.move' in package 'pkg'");
+}
+
+#[test]
+fn conflicting_named_addresses_are_rejected() {
+    let same = PackageBuilder::new("pkg")
+        .named_address("a", address("0x1"))
+        .named_address("a", address("0x01"))
+        .build();
+    assert!(same.is_ok());
+
+    let err = PackageBuilder::new("pkg")
+        .named_addresses([("a", address("0x1")), ("a", address("0x2"))])
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        PackageConfigError::ConflictingNamedAddress {
+            package: Some("pkg".into()),
+            name: "a".into(),
+            previous: address("0x1"),
+            value: address("0x2"),
+        }
+    );
+}
+
+#[test]
+fn unnamed_packages_cannot_be_configured() {
+    let err = PackageBuilder::unnamed()
+        .edition(Edition::E2024_ALPHA)
+        .build()
+        .unwrap_err();
+    assert_eq!(err, PackageConfigError::UnnamedPackageConfig);
+    assert!(PackageBuilder::unnamed().path("m.move").build().is_ok());
+}
+
+#[test]
+fn duplicate_packages_are_rejected() {
+    let err = Compiler::from_packages(
+        vec![PackageBuilder::new("pkg")],
+        vec![PackageBuilder::new("pkg").dependency()],
+    )
+    .err()
+    .unwrap();
+    assert_eq!(
+        err.downcast_ref::<PackageConfigError>(),
+        Some(&PackageConfigError::DuplicatePackage("pkg".into()))
+    );
+}
@@ -0,0 +1,60 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+// The limits on definitions are tested by `tests/move_2024/package_limits`. The package size is
+// tested here, as its diagnostics depend on the size of the serialized modules
+
+mod common;
+
+use move_compiler::{
+    shared::{NumericalAddress, PackageConfig, PackageLimits, PackagePaths},
+    Compiler,
+};
+use std::collections::BTreeMap;
+
+const SOURCE: &str = r#"module 0x42::m {
+    public fun f(a: u64, b: u64): u64 { a + b }
+}
+"#;
+
+/// The text at the primary location of each diagnostic of compiling `SOURCE` with `limits`
+fn check(limits: PackageLimits) -> Vec<String> {
+    let dir = tempfile::tempdir().unwrap();
+    let config = PackageConfig {
+        limits,
+        ..PackageConfig::default()
+    };
+    let targets = PackagePaths {
+        name: Some(("pkg".into(), config)),
+        paths: common::write_sources(dir.path(), &[("m.move", SOURCE)]),
+        named_address_map: BTreeMap::<String, NumericalAddress>::new(),
+    };
+    let compiler = Compiler::from_package_paths(vec![targets], vec![]).unwrap();
+    let (files, res) = compiler.build().unwrap();
+    match res {
+        Ok((_units, warnings)) => {
+            assert!(warnings.is_empty(), "{:?}", warnings);
+            vec![]
+        }
+        Err(diags) => common::primary_texts(&files, diags),
+    }
+}
+
+#[test]
+fn no_limits_by_default() {
+    assert!(check(PackageLimits::default()).is_empty());
+}
+
+#[test]
+fn package_size() {
+    let limits = PackageLimits {
+        max_package_size: Some(10),
+        ..PackageLimits::default()
+    };
+    assert_eq!(check(limits), ["m"]);
+    let limits = PackageLimits {
+        max_package_size: Some(100_000),
+        ..PackageLimits::default()
+    };
+    assert!(check(limits).is_empty());
+}
@@ -0,0 +1,110 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::{
+    command_line::compiler::move_check_for_errors,
+    prover::{ProgramSpecs, ProverBackend, ProverFinding, ProverFindingKind},
+    shared::PackagePaths,
+    typing::ast as T,
+    Compiler, PASS_PARSER, PASS_PROVER,
+};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+const SOURCE: &str = r#"module 0x42::m {
+    public fun f(x: u64): u64 {
+        spec {
+            assert x > 0;
+        };
+        x
+    }
+
+    public fun g(): u64 {
+        0
+    }
+
+    spec g {
+        ensures result == 0;
+    }
+}
+"#;
+
+/// Reports a finding for each function with a spec block in its body
+struct MockBackend {
+    calls: Arc<Mutex<Vec<String>>>,
+}
+
+impl ProverBackend for MockBackend {
+    fn verify(&mut self, program: &T::Program, specs: &ProgramSpecs) -> Vec<ProverFinding> {
+        let mut findings = vec![];
+        for (mident, mdef) in program.inner.modules.key_cloned_iter() {
+            let module_specs = &specs.modules[&mident];
+            self.calls.lock().unwrap().push(format!(
+                "{}: {} module specs",
+                mident,
+                module_specs.specs.len()
+            ));
+            for (name, _) in mdef.functions.key_cloned_iter() {
+                if module_specs.function_specs.contains_key(&name) {
+                    findings.push(ProverFinding {
+                        kind: ProverFindingKind::Error,
+                        loc: name.0.loc,
+                        message: format!("assertion in '{}' does not hold", name),
+                        secondary_labels: vec![],
+                        notes: vec![],
+                    });
+                }
+            }
+        }
+        findings
+    }
+}
+
+#[test]
+fn prover_findings_are_diagnostics() {
+    let dir = tempfile::tempdir().unwrap();
+    let targets = vec![PackagePaths {
+        name: None,
+        paths: common::write_sources(dir.path(), &[("m.move", SOURCE)]),
+        named_address_map: BTreeMap::<String, _>::new(),
+    }];
+    let calls = Arc::new(Mutex::new(vec![]));
+    let backend = MockBackend {
+        calls: calls.clone(),
+    };
+    let (_files, res) = Compiler::from_package_paths(targets, vec![])
+        .unwrap()
+        .set_prover_backend(Box::new(backend))
+        .run::<PASS_PARSER>()
+        .unwrap();
+    let diags = move_check_for_errors(res).into_vec();
+
+    assert_eq!(
+        *calls.lock().unwrap(),
+        vec!["0x42::m: 1 module specs".to_string()]
+    );
+    assert_eq!(diags.len(), 1);
+    let (code, _) = diags[0].info().clone().render();
+    assert_eq!(code, "E16001");
+}
+
+#[test]
+fn prover_pass_without_backend() {
+    let dir = tempfile::tempdir().unwrap();
+    let targets = vec![PackagePaths {
+        name: None,
+        paths: common::write_sources(dir.path(), &[("m.move", SOURCE)]),
+        named_address_map: BTreeMap::<String, _>::new(),
+    }];
+    let (_files, res) = Compiler::from_package_paths(targets, vec![])
+        .unwrap()
+        .run::<PASS_PROVER>()
+        .unwrap();
+    let (_comments, compiler) = res.unwrap();
+    let (_, tprog) = compiler.into_ast();
+    assert_eq!(tprog.inner.modules.len(), 1);
+}
@@ -0,0 +1,95 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_command_line_common::files::FileHash;
+use move_compiler::{
+    diagnostics::Diagnostics, editions::Edition, expansion::rename::RenameEdit,
+    shared::PackageConfig,
+};
+use move_ir_types::location::Loc;
+use move_symbol_pool::Symbol;
+
+const M: &str = r#"module 0x42::m {
+    public struct S has drop { v: u64 }
+    public fun new(): S { S { v: 0 } }
+    public fun value(s: &S): u64 { s.v }
+    public fun double(s: &S): u64 { s.value() + value(s) }
+}
+"#;
+
+// `s.get()` goes through an explicit 'use fun' alias, so it keeps its name
+const N: &str = r#"module 0x42::n {
+    use 0x42::m::{Self, value};
+    use fun m::value as m::S.get;
+    public fun f(s: &m::S): u64 { m::value(s) + value(s) + s.value() + s.get() }
+    public fun g(): u64 { m::new().value() }
+}
+"#;
+
+/// Renames the function `name` of `M` to `new_name`
+fn rename(name: &str, new_name: &str) -> Result<Vec<RenameEdit>, Diagnostics> {
+    let dir = tempfile::tempdir().unwrap();
+    let start = M.find(&format!("fun {}(", name)).unwrap() + "fun ".len();
+    let def_loc = Loc::new(FileHash::new(M), start as u32, (start + name.len()) as u32);
+    let (_files, res) = common::compiler(dir.path(), &[("m.move", M), ("n.move", N)])
+        .set_default_config(PackageConfig {
+            edition: Edition::E2024_ALPHA,
+            ..PackageConfig::default()
+        })
+        .generate_rename_edits(def_loc, Symbol::from(new_name))
+        .unwrap();
+    res
+}
+
+/// The locations of every occurrence of `name` in `source`
+fn occurrences(source: &str, name: &str) -> Vec<Loc> {
+    let file_hash = FileHash::new(source);
+    source
+        .match_indices(name)
+        .map(|(start, _)| Loc::new(file_hash, start as u32, (start + name.len()) as u32))
+        .collect()
+}
+
+fn sorted(mut locs: Vec<Loc>) -> Vec<Loc> {
+    locs.sort_by_key(|loc| (loc.file_hash(), loc.start()));
+    locs
+}
+
+fn locs(edits: &[RenameEdit]) -> Vec<Loc> {
+    sorted(edits.iter().map(|edit| edit.loc).collect())
+}
+
+#[test]
+fn path_uses_are_renamed() {
+    let edits = rename("new", "create").unwrap();
+    assert!(edits.iter().all(|edit| edit.new_text.as_str() == "create"));
+    // the definition and `m::new()`
+    let expected = [occurrences(M, "new"), occurrences(N, "new")].concat();
+    assert_eq!(expected.len(), 2);
+    assert_eq!(locs(&edits), sorted(expected));
+}
+
+#[test]
+fn method_uses_are_renamed() {
+    let edits = rename("value", "get_value").unwrap();
+    assert!(edits
+        .iter()
+        .all(|edit| edit.new_text.as_str() == "get_value"));
+    // the definition, the paths to it, and the method calls `s.value()`, but not `s.get()`
+    let expected = [occurrences(M, "value"), occurrences(N, "value")].concat();
+    assert_eq!(expected.len(), 9);
+    let method_calls = [occurrences(M, ".value()"), occurrences(N, ".value()")].concat();
+    assert_eq!(method_calls.len(), 3);
+    assert_eq!(locs(&edits), sorted(expected));
+}
+
+#[test]
+fn renaming_to_an_existing_member_is_reported() {
+    let diags = rename("value", "double").unwrap_err();
+    // reported at the definition being renamed
+    let def_loc = occurrences(M, "value")[0];
+    assert!(diags.iter().any(|diag| diag.primary_loc() == def_loc
+        && diag.info().message() == "duplicate declaration, item, or annotation"));
+}
@@ -0,0 +1,24 @@
+// return values from entry functions must have drop, including type parameters and the
+// elements of tuples
+
+module a::m {
+    struct Obj has key { id: sui::object::UID }
+
+    public entry fun t0<T>(): T {
+        abort 0
+    }
+
+    public entry fun t1<T: drop>(): T {
+        abort 0
+    }
+
+    public entry fun t2(): (u64, Obj) {
+        abort 0
+    }
+}
+
+module sui::object {
+    struct UID has store {
+        id: address,
+    }
+}
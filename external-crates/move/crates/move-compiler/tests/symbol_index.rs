@@ -0,0 +1,127 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_command_line_common::files::FileHash;
+mod common;
+
+use move_compiler::{
+    diagnostics::FilesSourceText,
+    expansion::symbol_index::{SymbolIndex, SymbolKind},
+    Flags, PASS_EXPANSION,
+};
+
+const M: &str = r#"module 0x42::m {
+    struct S has drop {}
+    const C: u64 = 0;
+    public fun f(): u64 { C }
+}
+"#;
+
+const N: &str = r#"module 0x42::n {
+    use 0x42::m::{Self, S};
+    public fun g(_s: S): u64 { m::f() }
+}
+"#;
+
+/// Runs expansion on `M` and `N`, returning their file hashes and the symbol index
+fn index(flags: Flags) -> (FileHash, FileHash, SymbolIndex) {
+    let dir = tempfile::tempdir().unwrap();
+    let (files, res) = common::compiler(dir.path(), &[("m.move", M), ("n.move", N)])
+        .set_flags(flags)
+        .run::<PASS_EXPANSION>()
+        .unwrap();
+    let (_comments, mut stepped) = res.unwrap();
+    let index = stepped.compilation_env().symbol_index().clone();
+    (file(&files, M), file(&files, N), index)
+}
+
+fn file(files: &FilesSourceText, source: &str) -> FileHash {
+    *files
+        .iter()
+        .find(|(_, (_, text))| text.as_str() == source)
+        .unwrap()
+        .0
+}
+
+/// The offset of `name` in `source`, found as the first occurrence of `context`
+fn offset(source: &str, context: &str, name: &str) -> u32 {
+    (source.find(context).unwrap() + context.find(name).unwrap()) as u32
+}
+
+#[test]
+fn definitions_are_indexed() {
+    let (m_file, _, index) = index(Flags::empty().set_symbol_index(true));
+    let mut members: Vec<_> = index
+        .definitions()
+        .filter(|(loc, _)| loc.file_hash() == m_file)
+        .map(|(loc, def)| {
+            let name = &M[loc.usize_range()];
+            assert_eq!(
+                def.member.map(|member| member.value.to_string()),
+                (def.kind != SymbolKind::Module).then(|| name.to_owned()),
+            );
+            (name.to_owned(), def.kind)
+        })
+        .collect();
+    members.sort();
+    assert_eq!(
+        members,
+        vec![
+            ("C".to_owned(), SymbolKind::Constant),
+            ("S".to_owned(), SymbolKind::Struct),
+            ("f".to_owned(), SymbolKind::Function),
+            ("m".to_owned(), SymbolKind::Module),
+        ]
+    );
+}
+
+#[test]
+fn uses_are_indexed() {
+    let (m_file, n_file, index) = index(Flags::empty().set_symbol_index(true));
+    let def_of = |name: &str| {
+        let (loc, _) = index
+            .definitions()
+            .find(|(loc, _)| loc.file_hash() == m_file && &M[loc.usize_range()] == name)
+            .unwrap();
+        *loc
+    };
+
+    // the struct, used in a `use` and in a signature
+    let struct_use = offset(N, "(_s: S)", "S");
+    let reference = index.reference_at(n_file, struct_use).unwrap();
+    assert_eq!(reference.kind, SymbolKind::Struct);
+    assert_eq!(reference.def_loc, def_of("S"));
+    assert_eq!(index.references_to(def_of("S")).count(), 2);
+    assert_eq!(index.search("S").count(), 2);
+
+    // the module and the function in `m::f()`
+    let call = "m::f()";
+    assert_eq!(
+        index.definition_at(n_file, offset(N, call, "m")),
+        Some(def_of("m"))
+    );
+    assert_eq!(
+        index.definition_at(n_file, offset(N, call, "f")),
+        Some(def_of("f"))
+    );
+
+    // the constant, used in its own module
+    assert_eq!(
+        index.definition_at(m_file, offset(M, "{ C }", "C")),
+        Some(def_of("C"))
+    );
+
+    // references are ordered by position
+    let starts: Vec<_> = index
+        .file_references(n_file)
+        .iter()
+        .map(|r| r.use_loc.start())
+        .collect();
+    assert!(starts.windows(2).all(|w| w[0] <= w[1]));
+}
+
+#[test]
+fn nothing_is_indexed_by_default() {
+    let (_, _, index) = index(Flags::empty());
+    assert!(index.is_empty());
+}
@@ -0,0 +1,114 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+const MODULE: &str = r#"module 0x42::m {
+    public fun make(x: u64): u64 { x }
+    public fun pair(): (u64, vector<u8>) { (1, b"") }
+    public fun take<T>(_a: u64, _b: u64, _c: u64, _d: vector<u8>) {}
+}
+"#;
+
+const UNTRANSLATABLE: &str = "script cannot be translated to a transaction";
+
+fn templates(script: &str) -> Result<serde_json::Value, Vec<(String, String)>> {
+    let dir = tempfile::tempdir().unwrap();
+    let (files, res) = common::compiler(dir.path(), &[("m.move", MODULE), ("s.move", script)])
+        .generate_transaction_templates()
+        .unwrap();
+    match res {
+        Ok(templates) => Ok(serde_json::to_value(templates).unwrap()),
+        Err(diags) => Err(diags
+            .into_vec()
+            .iter()
+            .map(|diag| {
+                (
+                    diag.info().message().to_string(),
+                    common::primary_text(&files, diag),
+                )
+            })
+            .collect()),
+    }
+}
+
+#[test]
+fn calls_are_translated_to_commands() {
+    let script = r#"script {
+    use 0x42::m;
+    fun main<T>(x: u64) {
+        let y = m::make(x);
+        let (a, b) = m::pair();
+        m::take<T>(y, a, 7, b);
+    }
+}
+"#;
+    let expected = serde_json::json!([{
+        "script": "main",
+        "type_parameters": ["T"],
+        "inputs": [
+            { "kind": "parameter", "name": "x", "type": "u64" },
+            { "kind": "pure", "type": "u64", "value": "7" },
+        ],
+        "commands": [
+            { "MoveCall": {
+                "package": "0x42",
+                "module": "m",
+                "function": "make",
+                "type_arguments": [],
+                "arguments": [{ "Input": 0 }],
+            }},
+            { "MoveCall": {
+                "package": "0x42",
+                "module": "m",
+                "function": "pair",
+                "type_arguments": [],
+                "arguments": [],
+            }},
+            { "MoveCall": {
+                "package": "0x42",
+                "module": "m",
+                "function": "take",
+                "type_arguments": ["T"],
+                "arguments": [
+                    { "Result": 0 },
+                    { "NestedResult": [1, 0] },
+                    { "Input": 1 },
+                    { "NestedResult": [1, 1] },
+                ],
+            }},
+        ],
+    }]);
+    assert_eq!(templates(script).unwrap(), expected);
+}
+
+#[test]
+fn computations_are_reported() {
+    let script = r#"script {
+    use 0x42::m;
+    fun main(x: u64) {
+        m::make(x + 1);
+    }
+}
+"#;
+    assert_eq!(
+        templates(script).unwrap_err(),
+        vec![(UNTRANSLATABLE.to_string(), "x + 1".to_string())]
+    );
+}
+
+#[test]
+fn signers_are_reported() {
+    let script = r#"script {
+    use 0x42::m;
+    fun main(s: signer) {
+        let _ = s;
+        m::make(0);
+    }
+}
+"#;
+    assert_eq!(
+        templates(script).unwrap_err(),
+        vec![(UNTRANSLATABLE.to_string(), "s".to_string())]
+    );
+}
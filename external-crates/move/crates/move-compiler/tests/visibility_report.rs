@@ -0,0 +1,82 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::typing::visibility_report::VisibilityReport;
+
+const SOURCE: &str = r#"module 0x42::vault {
+    friend 0x42::admin;
+
+    public fun balance(): u64 { 0 }
+    public(friend) fun withdraw(): u64 { 0 }
+    public entry fun deposit() {}
+    entry fun sweep() {}
+    fun helper(): u64 { 0 }
+}
+
+module 0x42::admin {
+    public fun drain(): u64 { 0x42::vault::withdraw() }
+}
+"#;
+
+fn report(source: &str) -> VisibilityReport {
+    let dir = tempfile::tempdir().unwrap();
+    let (_files, res) = common::compiler(dir.path(), &[("m.move", source)])
+        .generate_visibility_report()
+        .unwrap();
+    res.unwrap()
+}
+
+#[test]
+fn externally_callable_functions_are_listed() {
+    let report = report(SOURCE);
+    let vault = &report.modules["0x42::vault"];
+    assert_eq!(
+        vault.friends.iter().collect::<Vec<_>>(),
+        vec!["0x42::admin"]
+    );
+    assert_eq!(
+        vault.functions.keys().collect::<Vec<_>>(),
+        vec!["balance", "deposit", "sweep", "withdraw"]
+    );
+    assert_eq!(vault.functions["balance"].visibility, "public");
+    assert!(vault.functions["deposit"].entry);
+    assert_eq!(vault.functions["sweep"].visibility, "");
+    assert!(vault.functions["sweep"].entry);
+    assert_eq!(report.function_count(), 5);
+}
+
+#[test]
+fn friend_functions_list_their_callers() {
+    let report = report(SOURCE);
+    let vault = &report.modules["0x42::vault"];
+    let withdraw = &vault.functions["withdraw"];
+    assert_eq!(withdraw.visibility, "public(friend)");
+    assert_eq!(
+        withdraw
+            .callable_from
+            .as_ref()
+            .unwrap()
+            .iter()
+            .collect::<Vec<_>>(),
+        vec!["0x42::admin"]
+    );
+    assert!(vault.functions["balance"].callable_from.is_none());
+}
+
+#[test]
+fn report_is_printed_by_module() {
+    let printed = report(SOURCE).to_string();
+    assert_eq!(
+        printed,
+        "module 0x42::admin\n    \
+        public fun drain\n\
+        module 0x42::vault\n    \
+        friend 0x42::admin\n    \
+        public fun balance\n    \
+        public entry fun deposit\n    \
+        entry fun sweep\n    \
+        public(friend) fun withdraw, callable from [0x42::admin]\n"
+    );
+}
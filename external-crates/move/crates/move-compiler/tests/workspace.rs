@@ -0,0 +1,111 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use move_compiler::{
+    compiled_unit::AnnotatedCompiledUnit,
+    diagnostics::Diagnostics,
+    shared::{PackageConfig, PackagePaths, WorkspacePackage},
+    Compiler,
+};
+use move_symbol_pool::Symbol;
+use std::{collections::BTreeMap, path::Path};
+
+const LIB: &str = r#"module 0x1::lib {
+    public fun one(): u64 { 1 }
+}
+"#;
+
+const APP: &str = r#"module 0x2::app {
+    public fun two(): u64 { 0x1::lib::one() + 1 }
+}
+"#;
+
+fn package(dir: &Path, name: &str, source: &str, dependencies: &[&str]) -> WorkspacePackage {
+    let file = format!("{}.move", name);
+    WorkspacePackage {
+        name: name.into(),
+        config: PackageConfig::default(),
+        paths: common::write_sources(dir, &[(&file, source)])
+            .into_iter()
+            .map(Symbol::from)
+            .collect(),
+        named_address_map: BTreeMap::new(),
+        dependencies: dependencies.iter().map(|dep| Symbol::from(*dep)).collect(),
+    }
+}
+
+fn build(
+    packages: Vec<WorkspacePackage>,
+) -> Result<(Vec<AnnotatedCompiledUnit>, Diagnostics), Diagnostics> {
+    Compiler::from_workspace(packages, Vec::<PackagePaths>::new())
+        .unwrap()
+        .build()
+        .unwrap()
+        .1
+}
+
+#[test]
+fn packages_are_compiled_together() {
+    let dir = tempfile::tempdir().unwrap();
+    // listed before the package it depends on
+    let packages = vec![
+        package(dir.path(), "app", APP, &["lib"]),
+        package(dir.path(), "lib", LIB, &[]),
+    ];
+    let (units, warnings) = build(packages).unwrap();
+    assert!(warnings.is_empty(), "{:?}", warnings);
+    let packages: Vec<_> = units
+        .iter()
+        .map(|unit| match unit {
+            AnnotatedCompiledUnit::Module(m) => m.named_module.package_name.unwrap(),
+            AnnotatedCompiledUnit::Script(_) => panic!("unexpected script"),
+        })
+        .collect();
+    assert_eq!(packages, vec![Symbol::from("lib"), Symbol::from("app")]);
+}
+
+#[test]
+fn undeclared_dependencies_are_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let packages = vec![
+        package(dir.path(), "lib", LIB, &[]),
+        package(dir.path(), "app", APP, &[]),
+    ];
+    let diags = build(packages).unwrap_err().into_vec();
+    assert_eq!(diags.len(), 1);
+    assert_eq!(
+        diags[0].info().message(),
+        "use of an undeclared package dependency"
+    );
+}
+
+#[test]
+fn transitive_dependencies_can_be_used() {
+    let dir = tempfile::tempdir().unwrap();
+    let packages = vec![
+        package(dir.path(), "lib", LIB, &[]),
+        package(dir.path(), "mid", "module 0x3::mid {}", &["lib"]),
+        package(dir.path(), "app", APP, &["mid"]),
+    ];
+    assert!(build(packages).is_ok());
+}
+
+#[test]
+fn cycles_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let packages = vec![
+        package(dir.path(), "lib", LIB, &["app"]),
+        package(dir.path(), "app", APP, &["lib"]),
+    ];
+    let err = Compiler::from_workspace(packages, vec![]).err().unwrap();
+    assert!(err.to_string().contains("lib -> app -> lib"), "{}", err);
+}
+
+#[test]
+fn unknown_dependencies_are_rejected() {
+    let dir = tempfile::tempdir().unwrap();
+    let packages = vec![package(dir.path(), "app", APP, &["lib"])];
+    assert!(Compiler::from_workspace(packages, vec![]).is_err());
+}
@@ -287,6 +287,19 @@ impl<'env, 'translator> ModuleBuilder<'env, 'translator> {
                             };
                         AttributeValue::Value(value_node_id, val)
                     }
+                    EA::AttributeValue_::List(vals) => {
+                        let vals = vals
+                            .iter()
+                            .map(|val| {
+                                ExpTranslator::new(self)
+                                    .translate_value(val)
+                                    .map(|(val, _)| val)
+                                    // Error reported
+                                    .unwrap_or(Value::Bool(false))
+                            })
+                            .collect();
+                        AttributeValue::Value(value_node_id, Value::Vector(vals))
+                    }
                     EA::AttributeValue_::Module(mident) => {
                         let addr_bytes = self.parent.resolve_address(
                             &self.parent.to_loc(&mident.loc),
@@ -461,6 +461,13 @@ impl Package {
                 .or(config.default_edition)
                 .unwrap_or_default(),
             warning_filter: WarningFilters::new_for_source(),
+            version: Some(self.source_package.package.version),
+            limits: self
+                .source_package
+                .build
+                .as_ref()
+                .map(|build| build.limits)
+                .unwrap_or_default(),
         }
     }
 }
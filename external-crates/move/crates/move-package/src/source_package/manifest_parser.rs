@@ -4,7 +4,10 @@
 
 use crate::{package_hooks, source_package::parsed_manifest as PM};
 use anyhow::{anyhow, bail, format_err, Context, Result};
-use move_compiler::editions::{Edition, Flavor};
+use move_compiler::{
+    editions::{Edition, Flavor},
+    shared::PackageLimits,
+};
 use move_core_types::account_address::{AccountAddress, AccountAddressParseError};
 use move_symbol_pool::symbol::Symbol;
 use std::{
@@ -36,6 +39,11 @@ const KNOWN_NAMES: &[&str] = &[
 
 const REQUIRED_FIELDS: &[&str] = &[PACKAGE_NAME];
 
+const MAX_FUNCTION_PARAMETERS_NAME: &str = "max_function_parameters";
+const MAX_STRUCT_FIELDS_NAME: &str = "max_struct_fields";
+const MAX_TYPE_NODES_NAME: &str = "max_type_nodes";
+const MAX_PACKAGE_SIZE_NAME: &str = "max_package_size";
+
 pub fn parse_move_manifest_from_file(path: &Path) -> Result<PM::SourceManifest> {
     let file_contents = if path.is_file() {
         std::fs::read_to_string(path)
@@ -220,12 +228,28 @@ pub fn parse_dependencies(tval: TV) -> Result<PM::Dependencies> {
 pub fn parse_build_info(tval: TV) -> Result<PM::BuildInfo> {
     match tval {
         TV::Table(mut table) => {
-            warn_if_unknown_field_names(&table, &["language_version", "arch"]);
+            warn_if_unknown_field_names(
+                &table,
+                &[
+                    "language_version",
+                    "arch",
+                    MAX_FUNCTION_PARAMETERS_NAME,
+                    MAX_STRUCT_FIELDS_NAME,
+                    MAX_TYPE_NODES_NAME,
+                    MAX_PACKAGE_SIZE_NAME,
+                ],
+            );
             Ok(PM::BuildInfo {
                 language_version: table
                     .remove("language_version")
                     .map(parse_version)
                     .transpose()?,
+                limits: PackageLimits {
+                    max_function_parameters: parse_limit(&mut table, MAX_FUNCTION_PARAMETERS_NAME)?,
+                    max_struct_fields: parse_limit(&mut table, MAX_STRUCT_FIELDS_NAME)?,
+                    max_type_nodes: parse_limit(&mut table, MAX_TYPE_NODES_NAME)?,
+                    max_package_size: parse_limit(&mut table, MAX_PACKAGE_SIZE_NAME)?,
+                },
             })
         }
         x => bail!(
@@ -236,6 +260,19 @@ pub fn parse_build_info(tval: TV) -> Result<PM::BuildInfo> {
     }
 }
 
+/// Parses the package limit `name` from the `[build]` section, which is not checked if it is not
+/// set.
+fn parse_limit(table: &mut toml::map::Map<String, TV>, name: &str) -> Result<Option<usize>> {
+    table
+        .remove(name)
+        .map(|v| {
+            v.as_integer()
+                .and_then(|i| usize::try_from(i).ok())
+                .ok_or_else(|| format_err!("'{name}' must be a non-negative integer"))
+        })
+        .transpose()
+}
+
 pub fn parse_addresses(tval: TV) -> Result<PM::AddressDeclarations> {
     match tval {
         TV::Table(table) => {
@@ -4,7 +4,10 @@
 
 use anyhow::{bail, Result};
 
-use move_compiler::editions::{Edition, Flavor};
+use move_compiler::{
+    editions::{Edition, Flavor},
+    shared::PackageLimits,
+};
 use move_core_types::account_address::AccountAddress;
 use move_symbol_pool::symbol::Symbol;
 use std::{
@@ -95,6 +98,8 @@ pub struct CustomDepInfo {
 #[derive(Default, Debug, Clone, Eq, PartialEq)]
 pub struct BuildInfo {
     pub language_version: Option<Version>,
+    /// Limits checked on the compiled modules of the package
+    pub limits: PackageLimits,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Ord, PartialOrd)]
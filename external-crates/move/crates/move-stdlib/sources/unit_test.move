@@ -9,4 +9,10 @@ module std::unit_test {
     /// This will cause a linking failure if an attempt is made to publish a
     /// test module in a VM that isn't in unit test mode.
     native public fun create_signers_for_testing(num_signers: u64): vector<signer>;
+
+    /// Increment the coverage counter `counter`. Calls to this function are inserted at the
+    /// start of every basic block of code compiled with coverage instrumentation, and should not
+    /// be written by hand.
+    #[test_only]
+    native public fun increment_coverage_counter(counter: u64);
 }
@@ -96,6 +96,9 @@ impl GasParameters {
                     base_cost: 0.into(),
                     unit_cost: 0.into(),
                 },
+                increment_coverage_counter: unit_test::IncrementCoverageCounterGasParameters {
+                    base_cost: 0.into(),
+                },
             },
         }
     }
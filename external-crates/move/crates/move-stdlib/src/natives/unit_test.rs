@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::natives::helpers::make_module_natives;
+use better_any::{Tid, TidAble};
 use move_binary_format::errors::PartialVMResult;
 use move_core_types::{
     account_address::AccountAddress,
@@ -16,7 +17,10 @@ use move_vm_types::{
     loaded_data::runtime_types::Type, natives::function::NativeResult, pop_arg, values::Value,
 };
 use smallvec::smallvec;
-use std::{collections::VecDeque, sync::Arc};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::{Arc, Mutex},
+};
 
 /***************************************************************************************************
  * native fun create_signers_for_testing
@@ -67,19 +71,88 @@ pub fn make_native_create_signers_for_testing(
     )
 }
 
+/***************************************************************************************************
+ * native fun increment_coverage_counter
+ *
+ *   gas cost: base_cost
+ *
+ **************************************************************************************************/
+
+/// Native context extension counting how many times each coverage counter was incremented. It is
+/// shared between the tests of a run, so that it holds the counts of the whole run.
+#[derive(Tid, Clone, Default)]
+pub struct CoverageCounters(Arc<Mutex<BTreeMap<u64, u64>>>);
+
+impl CoverageCounters {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn increment(&self, counter: u64) {
+        *self.0.lock().unwrap().entry(counter).or_default() += 1;
+    }
+
+    /// The number of times each counter was incremented, omitting counters never incremented
+    pub fn counts(&self) -> BTreeMap<u64, u64> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct IncrementCoverageCounterGasParameters {
+    pub base_cost: InternalGas,
+}
+
+fn native_increment_coverage_counter(
+    gas_params: &IncrementCoverageCounterGasParameters,
+    context: &mut NativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> PartialVMResult<NativeResult> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 1);
+
+    let counter = pop_arg!(args, u64);
+    native_charge_gas_early_exit!(context, gas_params.base_cost);
+    // Instrumented code can run without coverage being collected, e.g. when tests compiled for
+    // coverage are run without an lcov report
+    if let Some(counters) = context.extensions().try_get::<CoverageCounters>() {
+        counters.increment(counter);
+    }
+
+    Ok(NativeResult::ok(context.gas_used(), smallvec![]))
+}
+
+pub fn make_native_increment_coverage_counter(
+    gas_params: IncrementCoverageCounterGasParameters,
+) -> NativeFunction {
+    Arc::new(
+        move |context, ty_args, args| -> PartialVMResult<NativeResult> {
+            native_increment_coverage_counter(&gas_params, context, ty_args, args)
+        },
+    )
+}
+
 /***************************************************************************************************
  * module
  **************************************************************************************************/
 #[derive(Debug, Clone)]
 pub struct GasParameters {
     pub create_signers_for_testing: CreateSignersForTestingGasParameters,
+    pub increment_coverage_counter: IncrementCoverageCounterGasParameters,
 }
 
 pub fn make_all(gas_params: GasParameters) -> impl Iterator<Item = (String, NativeFunction)> {
-    let natives = [(
-        "create_signers_for_testing",
-        make_native_create_signers_for_testing(gas_params.create_signers_for_testing),
-    )];
+    let natives = [
+        (
+            "create_signers_for_testing",
+            make_native_create_signers_for_testing(gas_params.create_signers_for_testing),
+        ),
+        (
+            "increment_coverage_counter",
+            make_native_increment_coverage_counter(gas_params.increment_coverage_counter),
+        ),
+    ];
 
     make_module_natives(natives)
 }
@@ -47,9 +47,11 @@ static_symbols!(
     "_",
     "init",
     "unit_test",
+    "unit_test_poison",
     "legacy",
     "2024",
     "alpha",
+    "migration",
     "sui",
     "object",
     "new",
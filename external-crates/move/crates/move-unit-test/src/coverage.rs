@@ -0,0 +1,151 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Renders the coverage counters collected while running tests compiled with coverage
+//! instrumentation as an lcov tracefile, with line, function, and branch records.
+
+use move_command_line_common::files::FileHash;
+use move_compiler::unit_test::{
+    coverage::{module_coverage_map, FunctionCoverageMap},
+    TestPlan,
+};
+use move_ir_types::location::Loc;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write,
+};
+
+/// The instrumented functions of a test plan, grouped by source file
+pub struct LcovReport {
+    files: BTreeMap<String, SourceFileCoverage>,
+}
+
+struct SourceFileCoverage {
+    /// Byte offset of the start of each line
+    line_starts: Vec<usize>,
+    /// Instrumented functions, by fully qualified name
+    functions: Vec<(String, FunctionCoverageMap)>,
+}
+
+impl LcovReport {
+    pub fn new(test_plan: &TestPlan) -> Self {
+        let mut files: BTreeMap<String, SourceFileCoverage> = BTreeMap::new();
+        let paths: HashMap<FileHash, String> = test_plan
+            .files
+            .iter()
+            .map(|(hash, (path, _))| (*hash, path.to_string()))
+            .collect();
+        for (module_id, module) in &test_plan.module_info {
+            for function in module_coverage_map(module) {
+                let file_hash = function.loc.file_hash();
+                let (Some(path), Some((_, source))) =
+                    (paths.get(&file_hash), test_plan.files.get(&file_hash))
+                else {
+                    continue;
+                };
+                let file = files
+                    .entry(path.clone())
+                    .or_insert_with(|| SourceFileCoverage {
+                        line_starts: line_starts(source),
+                        functions: vec![],
+                    });
+                let name = format!("{}::{}", module_id.name(), function.name);
+                file.functions.push((name, function));
+            }
+        }
+        Self { files }
+    }
+
+    /// Whether the test plan contains no instrumented code, e.g. because the standard library
+    /// used does not support coverage instrumentation
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Renders the report, given the number of times each counter was incremented
+    pub fn render(&self, counts: &BTreeMap<u64, u64>) -> String {
+        let count = |counter: u64| counts.get(&counter).copied().unwrap_or(0);
+        let mut out = String::new();
+        for (path, file) in &self.files {
+            let line = |loc: Loc| file.line(loc);
+            writeln!(out, "TN:").unwrap();
+            writeln!(out, "SF:{}", path).unwrap();
+
+            let mut fn_hit = 0;
+            for (name, function) in &file.functions {
+                writeln!(out, "FN:{},{}", line(function.loc), name).unwrap();
+            }
+            for (name, function) in &file.functions {
+                let entry_count = function.blocks.first().map_or(0, |b| count(b.counter));
+                if entry_count > 0 {
+                    fn_hit += 1;
+                }
+                writeln!(out, "FNDA:{},{}", entry_count, name).unwrap();
+            }
+            writeln!(out, "FNF:{}", file.functions.len()).unwrap();
+            writeln!(out, "FNH:{}", fn_hit).unwrap();
+
+            let (mut br_found, mut br_hit) = (0, 0);
+            let mut lines: BTreeMap<usize, u64> = BTreeMap::new();
+            for (_, function) in &file.functions {
+                for block in &function.blocks {
+                    let block_count = count(block.counter);
+                    for loc in &block.locs {
+                        let line_count = lines.entry(line(*loc)).or_default();
+                        *line_count = (*line_count).max(block_count);
+                    }
+                    let Some(branch) = &block.branch else {
+                        continue;
+                    };
+                    for (idx, target) in [branch.if_true, branch.if_false].into_iter().enumerate() {
+                        br_found += 1;
+                        // a target block can be reached from several blocks, so the number of
+                        // times the branch was taken is at most the smallest of the two counts
+                        let taken = if block_count == 0 {
+                            "-".to_string()
+                        } else {
+                            let taken = block_count.min(count(target));
+                            if taken > 0 {
+                                br_hit += 1;
+                            }
+                            taken.to_string()
+                        };
+                        writeln!(
+                            out,
+                            "BRDA:{},{},{},{}",
+                            line(branch.loc),
+                            block.counter,
+                            idx,
+                            taken
+                        )
+                        .unwrap();
+                    }
+                }
+            }
+            writeln!(out, "BRF:{}", br_found).unwrap();
+            writeln!(out, "BRH:{}", br_hit).unwrap();
+
+            for (line, line_count) in &lines {
+                writeln!(out, "DA:{},{}", line, line_count).unwrap();
+            }
+            writeln!(out, "LF:{}", lines.len()).unwrap();
+            writeln!(out, "LH:{}", lines.values().filter(|c| **c > 0).count()).unwrap();
+            writeln!(out, "end_of_record").unwrap();
+        }
+        out
+    }
+}
+
+impl SourceFileCoverage {
+    /// The 1-based line of the start of `loc`
+    fn line(&self, loc: Loc) -> usize {
+        self.line_starts
+            .partition_point(|start| *start <= loc.start() as usize)
+    }
+}
+
+fn line_starts(source: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(source.match_indices('\n').map(|(idx, _)| idx + 1))
+        .collect()
+}
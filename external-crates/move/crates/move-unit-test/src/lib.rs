@@ -3,11 +3,15 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod cargo_runner;
+pub mod coverage;
 pub mod extensions;
 pub mod test_reporter;
 pub mod test_runner;
 
-use crate::test_runner::TestRunner;
+use crate::{
+    coverage::LcovReport,
+    test_runner::{TestRunner, DEFAULT_RAND_NUM_ITERS},
+};
 use clap::*;
 use move_command_line_common::files::verify_and_create_named_address_mapping;
 use move_compiler::{
@@ -100,6 +104,24 @@ pub struct UnitTestingConfig {
     /// Verbose mode
     #[clap(short = 'v', long = "verbose")]
     pub verbose: bool,
+
+    /// Instrument the code under test with coverage counters, and write an lcov report of the
+    /// lines and branches executed by the tests to this path
+    #[clap(name = "lcov", long = "lcov")]
+    pub lcov_report: Option<String>,
+
+    /// Seed used to generate the arguments of #[random_test]s, which are then run only once
+    #[clap(name = "seed", long = "seed")]
+    pub seed: Option<u64>,
+
+    /// Number of times each #[random_test] is run, with arguments generated from a random seed
+    #[clap(
+        name = "rand-num-iters",
+        long = "rand-num-iters",
+        default_value_t = DEFAULT_RAND_NUM_ITERS,
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub rand_num_iters: u64,
 }
 
 fn format_module_id(module_id: &ModuleId) -> String {
@@ -125,6 +147,9 @@ impl UnitTestingConfig {
             verbose: false,
             list: false,
             named_address_values: vec![],
+            lcov_report: None,
+            seed: None,
+            rand_num_iters: DEFAULT_RAND_NUM_ITERS,
         }
     }
 
@@ -141,10 +166,11 @@ impl UnitTestingConfig {
         &self,
         source_files: Vec<String>,
         deps: Vec<String>,
+        coverage: bool,
     ) -> Option<TestPlan> {
         let addresses =
             verify_and_create_named_address_mapping(self.named_address_values.clone()).ok()?;
-        let flags = Flags::testing();
+        let flags = Flags::testing().set_coverage(coverage);
         let (files, comments_and_compiler_res) =
             Compiler::from_files(source_files, deps, addresses)
                 .set_flags(flags)
@@ -170,9 +196,10 @@ impl UnitTestingConfig {
 
         let TestPlan {
             files, module_info, ..
-        } = self.compile_to_test_plan(deps.clone(), vec![])?;
+        } = self.compile_to_test_plan(deps.clone(), vec![], false)?;
 
-        let mut test_plan = self.compile_to_test_plan(self.source_files.clone(), deps)?;
+        let mut test_plan =
+            self.compile_to_test_plan(self.source_files.clone(), deps, self.lcov_report.is_some())?;
         test_plan.module_info.extend(module_info);
         test_plan.files.extend(files);
         Some(test_plan)
@@ -204,6 +231,10 @@ impl UnitTestingConfig {
         }
 
         writeln!(shared_writer.lock().unwrap(), "Running Move unit tests")?;
+        let lcov_report = self
+            .lcov_report
+            .as_ref()
+            .map(|path| (path, LcovReport::new(&test_plan)));
         let mut test_runner = TestRunner::new(
             self.gas_limit.unwrap_or(DEFAULT_EXECUTION_BOUND),
             self.num_threads,
@@ -220,8 +251,20 @@ impl UnitTestingConfig {
         if let Some(filter_str) = &self.filter {
             test_runner.filter(filter_str)
         }
+        test_runner.configure_random_tests(self.seed, self.rand_num_iters);
+        let coverage_counters = lcov_report.as_ref().map(|_| test_runner.enable_coverage());
 
         let test_results = test_runner.run(&shared_writer).unwrap();
+        if let (Some((path, report)), Some(counters)) = (lcov_report, coverage_counters) {
+            if report.is_empty() {
+                writeln!(
+                    shared_writer.lock().unwrap(),
+                    "No coverage data collected: the Move stdlib used does not support coverage \
+                    instrumentation"
+                )?;
+            }
+            std::fs::write(path, report.render(&counters.counts()))?;
+        }
         if let Some(report_type) = &self.report_statistics {
             test_results.report_statistics(&shared_writer, report_type)?;
         }
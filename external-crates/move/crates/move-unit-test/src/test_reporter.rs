@@ -14,7 +14,7 @@ use move_compiler::{
     diagnostics::{self, Diagnostic, Diagnostics},
     unit_test::{ModuleTestPlan, TestName, TestPlan},
 };
-use move_core_types::{language_storage::ModuleId, vm_status::StatusType};
+use move_core_types::{language_storage::ModuleId, value::MoveValue, vm_status::StatusType};
 use move_ir_types::location::Loc;
 use move_symbol_pool::Symbol;
 use std::{
@@ -52,6 +52,15 @@ pub struct TestFailure {
     pub test_run_info: TestRunInfo,
     pub vm_error: Option<VMError>,
     pub failure_reason: FailureReason,
+    pub random_inputs: Option<RandomTestInputs>,
+}
+
+/// The inputs of the failed run of a #[random_test]
+#[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq)]
+pub struct RandomTestInputs {
+    /// The seed the arguments were generated from
+    pub seed: u64,
+    pub arguments: Vec<String>,
 }
 
 #[derive(Debug, Clone, Ord, PartialOrd, PartialEq, Eq)]
@@ -138,10 +147,33 @@ impl TestFailure {
             test_run_info,
             vm_error,
             failure_reason,
+            random_inputs: None,
         }
     }
 
+    /// Records the randomly generated inputs the test failed with
+    pub fn with_random_inputs(mut self, seed: u64, arguments: &[MoveValue]) -> Self {
+        self.random_inputs = Some(RandomTestInputs {
+            seed,
+            arguments: arguments.iter().map(|arg| arg.to_string()).collect(),
+        });
+        self
+    }
+
     pub fn render_error(&self, test_plan: &TestPlan) -> String {
+        let message = self.render_failure_reason(test_plan);
+        match &self.random_inputs {
+            None => message,
+            Some(RandomTestInputs { seed, arguments }) => format!(
+                "{message}\n\
+                This test uses randomly generated inputs, and failed with arguments ({}). \
+                Rerun it with `--seed {seed}` to reproduce this failure.",
+                arguments.join(", ")
+            ),
+        }
+    }
+
+    fn render_failure_reason(&self, test_plan: &TestPlan) -> String {
         match &self.failure_reason {
             FailureReason::NoError(message) => message.to_string(),
             FailureReason::Timeout(message) => message.to_string(),
@@ -20,11 +20,15 @@ use move_bytecode_utils::Modules;
 use move_compiler::{
     diagnostics::WarningFilters,
     shared::{Flags, NumericalAddress, PackagePaths},
-    unit_test::{ExpectedFailure, ModuleTestPlan, TestCase, TestPlan},
+    unit_test::{ExpectedFailure, ModuleTestPlan, TestArgument, TestCase, TestPlan},
 };
 use move_core_types::{
-    account_address::AccountAddress, effects::ChangeSet, identifier::IdentStr,
-    value::serialize_values, vm_status::StatusCode,
+    account_address::AccountAddress,
+    effects::ChangeSet,
+    identifier::IdentStr,
+    u256::U256,
+    value::{serialize_values, MoveTypeLayout, MoveValue},
+    vm_status::StatusCode,
 };
 use move_model::{
     model::GlobalEnv, options::ModelBuilderOptions,
@@ -44,9 +48,17 @@ use move_vm_test_utils::{
 };
 #[cfg(debug_assertions)]
 use move_vm_types::gas::GasMeter;
+use rand::{rngs::StdRng, Rng, SeedableRng};
 use rayon::prelude::*;
-use std::{collections::BTreeMap, io::Write, marker::Send, sync::Mutex, time::Instant};
+use std::{
+    collections::BTreeMap,
+    io::Write,
+    marker::Send,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
+use move_stdlib::natives::unit_test::CoverageCounters;
 use move_vm_runtime::native_extensions::NativeContextExtensions;
 
 /// Test state common to all tests
@@ -60,6 +72,21 @@ pub struct SharedTestingConfig {
     named_address_values: BTreeMap<String, NumericalAddress>,
     check_stackless_vm: bool,
     verbose: bool,
+    coverage_counters: Option<CoverageCounters>,
+    seed: Option<u64>,
+    rand_num_iters: u64,
+}
+
+/// Number of times a #[random_test] is run with different inputs, unless configured otherwise
+pub const DEFAULT_RAND_NUM_ITERS: u64 = 10;
+
+/// Maximum length of the vectors generated as arguments of a #[random_test]
+const MAX_GENERATED_VECTOR_LENGTH: usize = 32;
+
+enum TestOutcome {
+    Pass(TestRunInfo),
+    Fail(TestFailure),
+    Timeout(TestFailure),
 }
 
 pub struct TestRunner {
@@ -130,6 +157,9 @@ impl TestRunner {
                 check_stackless_vm,
                 verbose,
                 named_address_values,
+                coverage_counters: None,
+                seed: None,
+                rand_num_iters: DEFAULT_RAND_NUM_ITERS,
             },
             num_threads,
             tests,
@@ -153,6 +183,22 @@ impl TestRunner {
             })
     }
 
+    /// Counts the executions of the basic blocks instrumented with coverage counters, returning
+    /// the counters shared by all tests
+    pub fn enable_coverage(&mut self) -> CoverageCounters {
+        self.testing_config
+            .coverage_counters
+            .get_or_insert_with(CoverageCounters::new)
+            .clone()
+    }
+
+    /// Runs each #[random_test] once with inputs generated from `seed` if one is given, or
+    /// `rand_num_iters` times with inputs generated from random seeds otherwise
+    pub fn configure_random_tests(&mut self, seed: Option<u64>, rand_num_iters: u64) {
+        self.testing_config.seed = seed;
+        self.testing_config.rand_num_iters = rand_num_iters;
+    }
+
     pub fn filter(&mut self, test_name_slice: &str) {
         for (module_id, module_test) in self.tests.module_tests.iter_mut() {
             if module_id.name().as_str().contains(test_name_slice) {
@@ -219,6 +265,7 @@ impl SharedTestingConfig {
         test_plan: &ModuleTestPlan,
         function_name: &str,
         test_info: &TestCase,
+        arguments: &[MoveValue],
     ) -> (
         VMResult<ChangeSet>,
         VMResult<NativeContextExtensions>,
@@ -226,7 +273,10 @@ impl SharedTestingConfig {
         TestRunInfo,
     ) {
         let move_vm = MoveVM::new(self.native_function_table.clone()).unwrap();
-        let extensions = extensions::new_extensions();
+        let mut extensions = extensions::new_extensions();
+        if let Some(coverage_counters) = &self.coverage_counters {
+            extensions.add(coverage_counters.clone());
+        }
         let mut session =
             move_vm.new_session_with_extensions(&self.starting_storage_state, extensions);
         let mut gas_meter = GasStatus::new(&self.cost_table, Gas::new(self.execution_bound));
@@ -241,9 +291,9 @@ impl SharedTestingConfig {
         let now = Instant::now();
         let serialized_return_values_result = session.execute_function_bypass_visibility(
             &test_plan.module_id,
-            IdentStr::new(function_name).unwrap(),
+            IdentStr::new(&test_info.test_name).unwrap(),
             vec![], // no ty args, at least for now
-            serialize_values(test_info.arguments.iter()),
+            serialize_values(arguments),
             &mut gas_meter,
         );
         let mut return_result = serialized_return_values_result.map(|res| {
@@ -279,6 +329,7 @@ impl SharedTestingConfig {
         test_plan: &ModuleTestPlan,
         function_name: &str,
         test_info: &TestCase,
+        arguments: &[MoveValue],
     ) -> (VMResult<Vec<Vec<u8>>>, TestRunInfo, Option<String>) {
         let now = Instant::now();
 
@@ -295,9 +346,9 @@ impl SharedTestingConfig {
         let global_state = GlobalState::default();
         let (return_result, _, _) = interpreter.interpret(
             &test_plan.module_id,
-            IdentStr::new(function_name).unwrap(),
+            IdentStr::new(&test_info.test_name).unwrap(),
             &[], // no ty args, at least for now
-            &test_info.arguments,
+            arguments,
             &global_state,
         );
         let prop_check_result = interpreter.report_property_checking_results();
@@ -355,136 +406,195 @@ impl SharedTestingConfig {
         let mut stats = TestStatistics::new();
 
         for (function_name, test_info) in &test_plan.tests {
-            let (_cs_result, _ext_result, exec_result, test_run_info) =
-                self.execute_via_move_vm(test_plan, function_name, test_info);
-
-            if self.check_stackless_vm {
-                let (stackless_vm_result, _, prop_check_result) = self.execute_via_stackless_vm(
-                    stackless_model.as_ref().unwrap(),
+            let outcome = if test_info.is_random_test() {
+                self.run_random_test(
+                    stackless_model.as_ref(),
                     test_plan,
                     function_name,
                     test_info,
-                );
-                let move_vm_result = adapt_move_vm_result(exec_result.clone());
-                if stackless_vm_result != move_vm_result {
-                    output.fail(function_name);
-                    stats.test_failure(
-                        TestFailure::new(
-                            FailureReason::mismatch(move_vm_result, stackless_vm_result),
-                            test_run_info,
-                            None,
-                        ),
-                        test_plan,
-                    );
-                    continue;
+                )
+            } else {
+                let arguments: Vec<_> = test_info
+                    .arguments
+                    .iter()
+                    .map(|arg| match arg {
+                        TestArgument::Value(value) => value.clone(),
+                        TestArgument::Generate(_) => unreachable!("not a random test"),
+                    })
+                    .collect();
+                self.run_test(
+                    stackless_model.as_ref(),
+                    test_plan,
+                    function_name,
+                    test_info,
+                    &arguments,
+                )
+            };
+
+            match outcome {
+                TestOutcome::Pass(test_run_info) => {
+                    output.pass(function_name);
+                    stats.test_success(test_run_info, test_plan);
                 }
-                if let Some(prop_failure) = prop_check_result {
+                TestOutcome::Fail(test_failure) => {
                     output.fail(function_name);
-                    stats.test_failure(
-                        TestFailure::new(
-                            FailureReason::property(prop_failure),
-                            test_run_info,
-                            None,
-                        ),
-                        test_plan,
-                    );
-                    continue;
+                    stats.test_failure(test_failure, test_plan);
+                }
+                TestOutcome::Timeout(test_failure) => {
+                    output.timeout(function_name);
+                    stats.test_failure(test_failure, test_plan);
                 }
             }
+        }
 
-            match exec_result {
-                Err(err) => {
-                    let actual_err =
-                        MoveError(err.major_status(), err.sub_status(), err.location().clone());
-                    assert!(err.major_status() != StatusCode::EXECUTED);
-                    match test_info.expected_failure.as_ref() {
-                        Some(ExpectedFailure::Expected) => {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        }
-                        Some(ExpectedFailure::ExpectedWithError(expected_err))
-                            if expected_err == &actual_err =>
-                        {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        }
-                        Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(code))
-                            if actual_err.0 == StatusCode::ABORTED
-                                && actual_err.1.is_some()
-                                && actual_err.1.unwrap() == *code =>
-                        {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        }
-                        // incorrect cases
-                        Some(ExpectedFailure::ExpectedWithError(expected_err)) => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::wrong_error(expected_err.clone(), actual_err),
-                                    test_run_info,
-                                    Some(err),
-                                ),
-                                test_plan,
-                            )
-                        }
-                        Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(expected_code)) => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::wrong_abort_deprecated(
-                                        *expected_code,
-                                        actual_err,
-                                    ),
-                                    test_run_info,
-                                    Some(err),
-                                ),
-                                test_plan,
-                            )
-                        }
-                        None if err.major_status() == StatusCode::OUT_OF_GAS => {
-                            // Ran out of ticks, report a test timeout and log a test failure
-                            output.timeout(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::timeout(),
-                                    test_run_info,
-                                    Some(err),
-                                ),
-                                test_plan,
-                            )
-                        }
-                        None => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::unexpected_error(actual_err),
-                                    test_run_info,
-                                    Some(err),
-                                ),
-                                test_plan,
-                            )
-                        }
-                    }
+        stats
+    }
+
+    /// Runs a test whose arguments are randomly generated, once with the configured seed, or
+    /// `rand_num_iters` times with random seeds, stopping at the first failure
+    fn run_random_test(
+        &self,
+        stackless_model: Option<&GlobalEnv>,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+    ) -> TestOutcome {
+        let seeds: Vec<u64> = match self.seed {
+            Some(seed) => vec![seed],
+            None => (0..self.rand_num_iters).map(|_| rand::random()).collect(),
+        };
+        let mut total_run_info = TestRunInfo::new(function_name.to_string(), Duration::ZERO, 0);
+        for seed in seeds {
+            let mut rng = StdRng::seed_from_u64(seed);
+            let arguments: Vec<_> = test_info
+                .arguments
+                .iter()
+                .map(|arg| match arg {
+                    TestArgument::Value(value) => value.clone(),
+                    TestArgument::Generate(layout) => generate_value(&mut rng, layout),
+                })
+                .collect();
+            match self.run_test(
+                stackless_model,
+                test_plan,
+                function_name,
+                test_info,
+                &arguments,
+            ) {
+                TestOutcome::Pass(test_run_info) => {
+                    total_run_info.elapsed_time += test_run_info.elapsed_time;
+                    total_run_info.instructions_executed += test_run_info.instructions_executed;
                 }
-                Ok(_) => {
-                    // Expected the test to fail, but it executed
-                    if test_info.expected_failure.is_some() {
-                        output.fail(function_name);
-                        stats.test_failure(
-                            TestFailure::new(FailureReason::no_error(), test_run_info, None),
-                            test_plan,
-                        )
-                    } else {
-                        // Expected the test to execute fully and it did
-                        output.pass(function_name);
-                        stats.test_success(test_run_info, test_plan);
-                    }
+                TestOutcome::Fail(test_failure) => {
+                    return TestOutcome::Fail(test_failure.with_random_inputs(seed, &arguments))
+                }
+                TestOutcome::Timeout(test_failure) => {
+                    return TestOutcome::Timeout(test_failure.with_random_inputs(seed, &arguments))
                 }
             }
         }
+        TestOutcome::Pass(total_run_info)
+    }
 
-        stats
+    fn run_test(
+        &self,
+        stackless_model: Option<&GlobalEnv>,
+        test_plan: &ModuleTestPlan,
+        function_name: &str,
+        test_info: &TestCase,
+        arguments: &[MoveValue],
+    ) -> TestOutcome {
+        let (_cs_result, _ext_result, exec_result, test_run_info) =
+            self.execute_via_move_vm(test_plan, function_name, test_info, arguments);
+
+        if let Some(stackless_model) = stackless_model {
+            let (stackless_vm_result, _, prop_check_result) = self.execute_via_stackless_vm(
+                stackless_model,
+                test_plan,
+                function_name,
+                test_info,
+                arguments,
+            );
+            let move_vm_result = adapt_move_vm_result(exec_result.clone());
+            if stackless_vm_result != move_vm_result {
+                return TestOutcome::Fail(TestFailure::new(
+                    FailureReason::mismatch(move_vm_result, stackless_vm_result),
+                    test_run_info,
+                    None,
+                ));
+            }
+            if let Some(prop_failure) = prop_check_result {
+                return TestOutcome::Fail(TestFailure::new(
+                    FailureReason::property(prop_failure),
+                    test_run_info,
+                    None,
+                ));
+            }
+        }
+
+        match exec_result {
+            Err(err) => {
+                let actual_err =
+                    MoveError(err.major_status(), err.sub_status(), err.location().clone());
+                assert!(err.major_status() != StatusCode::EXECUTED);
+                match test_info.expected_failure.as_ref() {
+                    Some(ExpectedFailure::Expected) => TestOutcome::Pass(test_run_info),
+                    Some(ExpectedFailure::ExpectedWithError(expected_err))
+                        if expected_err == &actual_err =>
+                    {
+                        TestOutcome::Pass(test_run_info)
+                    }
+                    Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(code))
+                        if actual_err.0 == StatusCode::ABORTED
+                            && actual_err.1.is_some()
+                            && actual_err.1.unwrap() == *code =>
+                    {
+                        TestOutcome::Pass(test_run_info)
+                    }
+                    // incorrect cases
+                    Some(ExpectedFailure::ExpectedWithError(expected_err)) => {
+                        TestOutcome::Fail(TestFailure::new(
+                            FailureReason::wrong_error(expected_err.clone(), actual_err),
+                            test_run_info,
+                            Some(err),
+                        ))
+                    }
+                    Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(expected_code)) => {
+                        TestOutcome::Fail(TestFailure::new(
+                            FailureReason::wrong_abort_deprecated(*expected_code, actual_err),
+                            test_run_info,
+                            Some(err),
+                        ))
+                    }
+                    None if err.major_status() == StatusCode::OUT_OF_GAS => {
+                        // Ran out of ticks, report a test timeout and log a test failure
+                        TestOutcome::Timeout(TestFailure::new(
+                            FailureReason::timeout(),
+                            test_run_info,
+                            Some(err),
+                        ))
+                    }
+                    None => TestOutcome::Fail(TestFailure::new(
+                        FailureReason::unexpected_error(actual_err),
+                        test_run_info,
+                        Some(err),
+                    )),
+                }
+            }
+            Ok(_) => {
+                // Expected the test to fail, but it executed
+                if test_info.expected_failure.is_some() {
+                    TestOutcome::Fail(TestFailure::new(
+                        FailureReason::no_error(),
+                        test_run_info,
+                        None,
+                    ))
+                } else {
+                    // Expected the test to execute fully and it did
+                    TestOutcome::Pass(test_run_info)
+                }
+            }
+        }
     }
 
     // TODO: comparison of results via different backends
@@ -499,3 +609,25 @@ impl SharedTestingConfig {
         self.exec_module_tests_move_vm_and_stackless_vm(test_plan, &output)
     }
 }
+
+/// Generates a random value of the given layout, for an argument of a #[random_test]
+fn generate_value(rng: &mut StdRng, layout: &MoveTypeLayout) -> MoveValue {
+    match layout {
+        MoveTypeLayout::Bool => MoveValue::Bool(rng.gen()),
+        MoveTypeLayout::U8 => MoveValue::U8(rng.gen()),
+        MoveTypeLayout::U16 => MoveValue::U16(rng.gen()),
+        MoveTypeLayout::U32 => MoveValue::U32(rng.gen()),
+        MoveTypeLayout::U64 => MoveValue::U64(rng.gen()),
+        MoveTypeLayout::U128 => MoveValue::U128(rng.gen()),
+        MoveTypeLayout::U256 => MoveValue::U256(U256::from_le_bytes(&rng.gen())),
+        MoveTypeLayout::Address => MoveValue::Address(AccountAddress::new(rng.gen())),
+        MoveTypeLayout::Signer => MoveValue::Signer(AccountAddress::new(rng.gen())),
+        MoveTypeLayout::Vector(elem) => {
+            let len = rng.gen_range(0..=MAX_GENERATED_VECTOR_LENGTH);
+            MoveValue::Vector((0..len).map(|_| generate_value(rng, elem)).collect())
+        }
+        MoveTypeLayout::Struct(_) => {
+            unreachable!("only primitives and vectors are generated for random tests")
+        }
+    }
+}
@@ -0,0 +1,69 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use move_unit_test::{self, UnitTestingConfig};
+use std::path::PathBuf;
+
+fn coverage_config(lcov_report: Option<String>) -> UnitTestingConfig {
+    let path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/coverage_sources/branches.move");
+    UnitTestingConfig {
+        num_threads: 1,
+        source_files: vec![path.to_string_lossy().to_string()],
+        dep_files: move_stdlib::move_stdlib_files(),
+        lcov_report,
+        ..UnitTestingConfig::default_with_bound(None)
+            .with_named_addresses(move_stdlib::move_stdlib_named_addresses())
+    }
+}
+
+#[test]
+fn test_lcov_report() {
+    let dir = tempfile::tempdir().unwrap();
+    let lcov_path = dir.path().join("lcov.info");
+    let config = coverage_config(Some(lcov_path.to_string_lossy().to_string()));
+    let test_plan = config.build_test_plan().unwrap();
+    let (_, all_passed) = config
+        .run_and_report_unit_tests(test_plan, None, None, Vec::new())
+        .unwrap();
+    assert!(all_passed);
+
+    let lcov = std::fs::read_to_string(&lcov_path).unwrap();
+    let records: Vec<_> = lcov.lines().collect();
+    assert!(records[1].ends_with("branches.move"), "{lcov}");
+    // `classify` and the test itself ran, `never_called` did not
+    assert!(records.contains(&"FNDA:1,branches::classify"), "{lcov}");
+    assert!(
+        records.contains(&"FNDA:1,branches::classify_large"),
+        "{lcov}"
+    );
+    assert!(records.contains(&"FNDA:0,branches::never_called"), "{lcov}");
+    assert!(records.contains(&"FNH:2"), "{lcov}");
+    // Only the `true` branch of the condition on line 3 was taken
+    let branches: Vec<_> = records
+        .iter()
+        .filter(|r| r.starts_with("BRDA:3,"))
+        .collect();
+    assert_eq!(branches.len(), 2, "{lcov}");
+    assert!(branches[0].ends_with(",0,1"), "{lcov}");
+    assert!(branches[1].ends_with(",1,0"), "{lcov}");
+    // The `then` line was executed, the `else` line and the body of `never_called` were not
+    assert!(records.contains(&"DA:4,1"), "{lcov}");
+    assert!(records.contains(&"DA:6,0"), "{lcov}");
+    assert!(records.contains(&"DA:11,0"), "{lcov}");
+}
+
+// Code instrumented with coverage counters must run even when no counters are collected
+#[test]
+fn test_instrumented_code_without_coverage() {
+    let config = coverage_config(Some("unused.lcov".to_string()));
+    let test_plan = config.build_test_plan().unwrap();
+    let config = UnitTestingConfig {
+        lcov_report: None,
+        ..config
+    };
+    let (_, all_passed) = config
+        .run_and_report_unit_tests(test_plan, None, None, Vec::new())
+        .unwrap();
+    assert!(all_passed);
+}
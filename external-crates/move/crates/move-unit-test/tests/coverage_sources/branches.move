@@ -0,0 +1,18 @@
+module 0x1::branches {
+    public fun classify(x: u64): u64 {
+        if (x > 10) {
+            1
+        } else {
+            2
+        }
+    }
+
+    public fun never_called(): u64 {
+        42
+    }
+
+    #[test]
+    fun classify_large() {
+        assert!(classify(11) == 1, 0);
+    }
+}
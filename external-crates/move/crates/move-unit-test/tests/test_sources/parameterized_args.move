@@ -0,0 +1,25 @@
+address 0x1 {
+module M {
+    #[test(x = [0, 1, 18446744073709551615])]
+    fun boundary_values_pass(x: u64) {
+        assert!(x == 0 || x == 1 || x == 18446744073709551615, 0);
+    }
+
+    #[test(x = [0, 1, 2])]
+    fun one_case_fails(x: u8) {
+        assert!(x != 1, 0);
+    }
+
+    #[test(a = @0x1, x = [true, false], y = [1u128, 2u128])]
+    fun combinations_pass(a: signer, x: bool, y: u128) {
+        assert!(std::signer::address_of(&a) == @0x1, 0);
+        assert!(x || !x, 1);
+        assert!(y == 1 || y == 2, 2);
+    }
+
+    #[test(x = [3, 4]), expected_failure(abort_code = 0)]
+    fun all_cases_abort(x: u16) {
+        abort (x as u64) - (x as u64)
+    }
+}
+}
@@ -0,0 +1,28 @@
+address 0x1 {
+module M {
+    use std::vector;
+
+    #[random_test]
+    fun widening_add_commutes(x: u32, y: u32) {
+        assert!((x as u64) + (y as u64) == (y as u64) + (x as u64), 0);
+    }
+
+    #[random_test]
+    fun reverse_twice_is_identity(v: vector<u8>) {
+        let w = copy v;
+        vector::reverse(&mut w);
+        vector::reverse(&mut w);
+        assert!(w == v, 0);
+    }
+
+    #[random_test(a = @0x1)]
+    fun assigned_and_generated(a: signer, _b: bool, _c: address, _d: u256) {
+        assert!(std::signer::address_of(&a) == @0x1, 0);
+    }
+
+    #[random_test, expected_failure(abort_code = 7)]
+    fun always_aborts(x: u64) {
+        if (x >= 0) abort 7
+    }
+}
+}
@@ -4,11 +4,17 @@
 
 use anyhow::{bail, Result};
 use move_binary_format::file_format::{CompiledModule, CompiledScript};
-use move_compiler::{compiled_unit::AnnotatedCompiledUnit, Compiler as MoveCompiler};
+use move_compiler::{
+    compiled_unit::AnnotatedCompiledUnit, shared::Flags, Compiler as MoveCompiler,
+};
 use std::{fs::File, io::Write, path::Path};
 use tempfile::tempdir;
 
 pub fn compile_units(s: &str) -> Result<Vec<AnnotatedCompiledUnit>> {
+    compile_units_with_flags(s, Flags::empty())
+}
+
+pub fn compile_units_with_flags(s: &str, flags: Flags) -> Result<Vec<AnnotatedCompiledUnit>> {
     let dir = tempdir()?;
 
     let file_path = dir.path().join("modules.move");
@@ -22,6 +28,7 @@ pub fn compile_units(s: &str) -> Result<Vec<AnnotatedCompiledUnit>> {
         vec![],
         move_stdlib::move_stdlib_named_addresses(),
     )
+    .set_flags(flags)
     .build_and_report()?;
 
     dir.close()?;
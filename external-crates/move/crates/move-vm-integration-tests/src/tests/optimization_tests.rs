@@ -0,0 +1,147 @@
+// Copyright (c) The Move Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::compiler::{as_module, compile_units_with_flags};
+use move_compiler::shared::{Flags, MAX_OPTIMIZATION_LEVEL};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::Identifier,
+    language_storage::ModuleId,
+    value::MoveValue,
+    vm_status::{StatusCode, VMStatus},
+};
+use move_vm_runtime::move_vm::MoveVM;
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas::UnmeteredGasMeter;
+
+const TEST_ADDR: AccountAddress = AccountAddress::new([42; AccountAddress::LENGTH]);
+
+const CODE: &str = r#"
+    module {{ADDR}}::M {
+        struct S has copy, drop { a: u64, b: bool }
+
+        const E: u64 = 7;
+
+        fun twice(x: u64): u64 { x * 2 }
+
+        public fun copies(x: u64): u64 {
+            let y = x;
+            let z = y;
+            let w = z + y;
+            let _unused = w;
+            w
+        }
+
+        public fun dead_store_aborts(x: u64): u64 {
+            let _dead = x * 1000000000000;
+            x
+        }
+
+        public fun branches(x: u64): u64 {
+            let r = 0;
+            if (!(x > 10)) r = r + 1 else r = r + 2;
+            if (x % 2 == 0) {
+                if (x > 4) r = r + 10
+            };
+            r
+        }
+
+        public fun loops(n: u64): u64 {
+            let i = 0;
+            let s = 0;
+            while (i < n) {
+                let t = i;
+                s = s + t;
+                if (s > 20) break;
+                i = i + 1;
+            };
+            s
+        }
+
+        public fun structs(x: u64): u64 {
+            let s = S { a: x, b: x > 3 };
+            let c = s;
+            s.a = s.a + 1;
+            if (c.b) c.a else s.a
+        }
+
+        public fun borrows(x: u64): u64 {
+            let y = x;
+            let z = y;
+            let r = &mut y;
+            *r = *r + 1;
+            y + z
+        }
+
+        public fun calls(x: u64): u64 {
+            assert!(x != 3, E);
+            let y = twice(x);
+            twice(y) + twice(1)
+        }
+    }
+"#;
+
+const FUNCTIONS: &[&str] = &[
+    "copies",
+    "dead_store_aborts",
+    "branches",
+    "loops",
+    "structs",
+    "borrows",
+    "calls",
+];
+
+const ARGS: &[u64] = &[0, 1, 3, 4, 5, 11, 12, 100_000_000, u64::MAX];
+
+/// The return values, or the status of the failure, of every function for every argument
+fn run_all(level: u8) -> Vec<Result<Vec<Vec<u8>>, (StatusCode, Option<u64>)>> {
+    let code = CODE.replace("{{ADDR}}", &format!("0x{}", TEST_ADDR));
+    let flags = Flags::empty().set_optimization_level(level);
+    let mut units = compile_units_with_flags(&code, flags).unwrap();
+    let m = as_module(units.pop().unwrap());
+    let mut blob = vec![];
+    m.serialize(&mut blob).unwrap();
+
+    let mut storage = InMemoryStorage::new();
+    let module_id = ModuleId::new(TEST_ADDR, Identifier::new("M").unwrap());
+    storage.publish_or_overwrite_module(module_id.clone(), blob);
+    let vm = MoveVM::new(vec![]).unwrap();
+    let mut sess = vm.new_session(&storage);
+
+    let mut results = vec![];
+    for fun in FUNCTIONS {
+        for arg in ARGS {
+            let args = vec![MoveValue::U64(*arg).simple_serialize().unwrap()];
+            let result = sess
+                .execute_function_bypass_visibility(
+                    &module_id,
+                    &Identifier::new(*fun).unwrap(),
+                    vec![],
+                    args,
+                    &mut UnmeteredGasMeter,
+                )
+                .map(|values| {
+                    values
+                        .return_values
+                        .into_iter()
+                        .map(|(bytes, _layout)| bytes)
+                        .collect()
+                })
+                .map_err(|e| match e.into_vm_status() {
+                    VMStatus::MoveAbort(_, code) => (StatusCode::ABORTED, Some(code)),
+                    status => (status.status_code(), None),
+                });
+            results.push(result);
+        }
+    }
+    results
+}
+
+#[test]
+fn optimized_builds_preserve_semantics() {
+    let unoptimized = run_all(0);
+    assert!(unoptimized.iter().any(|r| r.is_err()));
+    for level in 1..=MAX_OPTIMIZATION_LEVEL {
+        assert_eq!(run_all(level), unoptimized, "level {}", level);
+    }
+}
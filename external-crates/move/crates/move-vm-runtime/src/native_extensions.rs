@@ -35,6 +35,13 @@ impl<'a> NativeContextExtensions<'a> {
             .unwrap()
     }
 
+    /// Like `get`, but returns `None` if no extension of type `T` was added.
+    pub fn try_get<T: TidAble<'a>>(&self) -> Option<&T> {
+        self.map
+            .get(&T::id())
+            .map(|ext| ext.as_ref().downcast_ref::<T>().unwrap())
+    }
+
     pub fn get_mut<T: TidAble<'a>>(&mut self) -> &mut T {
         self.map
             .get_mut(&T::id())
@@ -78,6 +85,7 @@ mod tests {
         assert_eq!(*exts.get_mut::<Ext>().a, 24);
         *exts.get_mut::<Ext>().a += 1;
         let e1 = exts.remove::<Ext>();
-        assert_eq!(*e1.a, 25)
+        assert_eq!(*e1.a, 25);
+        assert!(exts.try_get::<Ext>().is_none());
     }
 }